@@ -1,8 +1,15 @@
 import { RouterProvider } from 'react-router-dom'
 import { Toaster } from './components/ui/sonner'
+import { usePanelVersionCheck } from './hooks/usePanelVersionCheck'
+import { useShortUrlBase } from './hooks/useShortUrlBase'
 import { router } from './router'
 
 function App() {
+  // 面板与后端版本握手（登录页同样生效）
+  usePanelVersionCheck()
+  // 完整短链使用后端解析的 base URL
+  useShortUrlBase()
+
   return (
     <>
       <RouterProvider router={router} />
@@ -250,6 +250,11 @@ export function LinkImportDialog({
                     })}
                   </p>
                 )}
+                {result.detected && (
+                  <p className="text-xs text-muted-foreground">
+                    {result.detected}
+                  </p>
+                )}
               </div>
               {result.failed_items.length > 0 && (
                 <div className="mt-2 max-h-32 overflow-auto">
@@ -277,6 +282,32 @@ export function LinkImportDialog({
                   )}
                 </div>
               )}
+              {result.warnings.length > 0 && (
+                <div className="mt-2 max-h-32 overflow-auto">
+                  <p className="text-xs text-muted-foreground mb-1">
+                    {t('links.import.warningDetails')}
+                  </p>
+                  {result.warnings.slice(0, 5).map((item, index) => (
+                    <p
+                      key={item.row ?? `unknown-${index}`}
+                      className="text-xs text-amber-600 dark:text-amber-400"
+                    >
+                      {t('links.import.warningItem', {
+                        row: item.row ?? '?',
+                        code: item.code,
+                        warning: item.warning,
+                      })}
+                    </p>
+                  ))}
+                  {result.warnings.length > 5 && (
+                    <p className="text-xs text-muted-foreground">
+                      {t('links.import.andMore', {
+                        count: result.warnings.length - 5,
+                      })}
+                    </p>
+                  )}
+                </div>
+              )}
             </div>
           )}
 
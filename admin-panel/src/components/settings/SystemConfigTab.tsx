@@ -40,6 +40,9 @@ const CATEGORY_ORDER = [
   'tracking',
   'analytics',
   'cache',
+  'security',
+  'storage',
+  'database',
   'other',
 ]
 
@@ -0,0 +1,61 @@
+import { useEffect } from 'react'
+import { useTranslation } from 'react-i18next'
+import { toast } from 'sonner'
+import { metaService } from '@/services/metaService'
+import { logger } from '@/utils/logger'
+import {
+  isPanelCompatible,
+  PANEL_VERSION,
+  PANEL_VERSION_INCOMPATIBLE_EVENT,
+} from '@/utils/panelVersion'
+
+const TOAST_ID = 'panel-version-incompatible'
+
+// 面板与后端 API 的版本握手：不兼容时提示强制刷新
+export function usePanelVersionCheck() {
+  const { t } = useTranslation()
+
+  useEffect(() => {
+    const promptReload = () => {
+      toast.warning(t('panelVersion.incompatible'), {
+        id: TOAST_ID,
+        action: {
+          label: t('panelVersion.reload'),
+          onClick: () => window.location.reload(),
+        },
+        duration: Infinity,
+      })
+    }
+
+    const controller = new AbortController()
+    metaService
+      .fetchVersion(controller.signal)
+      .then((info) => {
+        if (
+          info &&
+          !isPanelCompatible(
+            PANEL_VERSION,
+            info.server_version,
+            info.min_panel_version,
+          )
+        ) {
+          logger.warn(
+            `Panel ${PANEL_VERSION} is incompatible with server ${info.server_version} (min ${info.min_panel_version})`,
+          )
+          promptReload()
+        }
+      })
+      .catch((error) => {
+        // 握手失败不阻塞面板，后续请求仍会由后端 409 兜底
+        if (error?.name !== 'AbortError') {
+          logger.warn('Version handshake failed:', error)
+        }
+      })
+
+    window.addEventListener(PANEL_VERSION_INCOMPATIBLE_EVENT, promptReload)
+    return () => {
+      controller.abort()
+      window.removeEventListener(PANEL_VERSION_INCOMPATIBLE_EVENT, promptReload)
+    }
+  }, [t])
+}
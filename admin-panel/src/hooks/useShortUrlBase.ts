@@ -0,0 +1,24 @@
+import { useEffect } from 'react'
+import { metaService } from '@/services/metaService'
+import { logger } from '@/utils/logger'
+import { setShortUrlBase } from '@/utils/urlBuilder'
+
+// 获取后端解析的短链 base URL；失败时 buildShortUrl 回退到当前页面 origin
+export function useShortUrlBase() {
+  useEffect(() => {
+    const controller = new AbortController()
+    metaService
+      .fetchBaseUrl(controller.signal)
+      .then((info) => {
+        if (info?.base_url) {
+          setShortUrlBase(info.base_url)
+        }
+      })
+      .catch((error) => {
+        if (error?.name !== 'AbortError') {
+          logger.warn('Failed to fetch short URL base:', error)
+        }
+      })
+    return () => controller.abort()
+  }, [])
+}
@@ -25,6 +25,7 @@ import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
 import { Skeleton } from '@/components/ui/skeleton'
 import { useLinkDetailData } from '@/hooks/useLinkDetailData'
 import type { GeoStats, GroupBy } from '@/services/types'
+import { buildShortUrl } from '@/utils/urlBuilder'
 
 export default function LinkDetailPage() {
   const { t } = useTranslation()
@@ -44,7 +45,7 @@ export default function LinkDetailPage() {
 
   const handleCopy = async () => {
     if (!linkInfo) return
-    const url = `${window.location.origin}/${code}`
+    const url = buildShortUrl(code)
     await navigator.clipboard.writeText(url)
     setCopied(true)
     setTimeout(() => setCopied(false), 2000)
@@ -729,6 +729,13 @@ export interface components {
             expires_in: number;
             message: string;
         };
+        /** @description 短链 base URL（`GET /admin/meta/base-url`） */
+        BaseUrlInfo: {
+            /** @description 生成完整短链时使用的 base URL，不带末尾 `/` */
+            base_url: string;
+            /** @description 来源：`config`（显式配置）/ `forwarded`（可信代理转发头）/ `host`（Host 头） */
+            source: string;
+        };
         BatchCreateRequest: {
             links: components["schemas"]["PostNewLink"][];
         };
@@ -931,13 +938,41 @@ export interface components {
          * @enum {string}
          */
         ImportMode: "skip" | "overwrite" | "error";
+        /** @description 导入 dry-run 预览行（字段转换之后、校验之前的取值，不回显密码） */
+        ImportPreviewItem: {
+            analytics_level: string | null;
+            click_count: number;
+            code: string;
+            created_at: string;
+            expires_at: string | null;
+            extras: string | null;
+            has_password: boolean;
+            /** @description CSV 原文件行号 */
+            row: number;
+            target: string;
+        };
         /** @description 导入响应 */
         ImportResponse: {
+            /** @description CSV 方言检测结果，如 `detected: ; delimiter, latin-1 encoding` */
+            detected: string;
+            /** @description 是否为 dry-run（只解析、转换与校验，未写入） */
+            dry_run?: boolean;
             failed_count: number;
             failed_items: components["schemas"]["ImportFailedItem"][];
+            /** @description dry-run 时转换后的前 10 行 */
+            preview?: components["schemas"]["ImportPreviewItem"][];
             skipped_count: number;
             success_count: number;
             total_rows: number;
+            /** @description 已导入但需要提示的行 */
+            warnings: components["schemas"]["ImportWarningItem"][];
+        };
+        /** @description 导入警告项：该行已照常导入，但需要提示（如过期时间已经过去） */
+        ImportWarningItem: {
+            code: string;
+            /** @description CSV 行号（1-based） */
+            row: number | null;
+            warning: string;
         };
         /** @description 单链接分析数据 */
         LinkAnalytics: {
@@ -1035,6 +1070,18 @@ export interface components {
          * @enum {string}
          */
         ValueType: "string" | "int" | "float" | "bool" | "json" | "enum" | "stringarray" | "enumarray";
+        /** @description 版本握手信息（`GET /admin/meta/version`） */
+        VersionInfo: {
+            /**
+             * Format: int32
+             * @description Admin API 版本（破坏性变更时递增）
+             */
+            api_version: number;
+            /** @description 最低兼容的面板版本 */
+            min_panel_version: string;
+            /** @description 后端版本 */
+            server_version: string;
+        };
     };
     responses: never;
     parameters: never;
@@ -2001,11 +2048,13 @@ export enum ErrorCode {
     FileTooLarge = 1011,
     InvalidDateFormat = 1012,
     ServiceUnavailable = 1030,
+    PanelVersionIncompatible = 1040,
     AuthFailed = 2000,
     TokenExpired = 2001,
     TokenInvalid = 2002,
     CsrfInvalid = 2003,
     RateLimitExceeded = 2004,
+    Forbidden = 2005,
     LinkNotFound = 3000,
     LinkAlreadyExists = 3001,
     LinkInvalidUrl = 3002,
@@ -2015,6 +2064,7 @@ export enum ErrorCode {
     LinkEmptyCode = 3006,
     LinkInvalidCode = 3007,
     LinkReservedCode = 3008,
+    LinkCodeCoolingDown = 3009,
     ImportFailed = 4000,
     ExportFailed = 4001,
     InvalidMultipartData = 4002,
@@ -2027,5 +2077,6 @@ export enum ErrorCode {
     ConfigReloadFailed = 5002,
     AnalyticsQueryFailed = 6000,
     AnalyticsLinkNotFound = 6001,
-    AnalyticsInvalidDateRange = 6002
+    AnalyticsInvalidDateRange = 6002,
+    QuotaExceeded = 7000
 }
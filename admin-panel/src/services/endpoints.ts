@@ -41,6 +41,10 @@ export const ENDPOINTS = {
     EXPORT: `${V1}/analytics/export`,
     DEVICES: `${V1}/analytics/devices`,
   },
+  META: {
+    VERSION: '/meta/version',
+    BASE_URL: '/meta/base-url',
+  },
 } as const
 
 /**
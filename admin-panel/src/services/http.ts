@@ -7,6 +7,11 @@ import { LRUCache } from 'lru-cache'
 import { appConfig } from '@/config/app'
 import { forceLogout, refreshTokenFromHttp } from '@/stores/authStore'
 import { httpLogger } from '@/utils/logger'
+import {
+  PANEL_VERSION,
+  PANEL_VERSION_HEADER,
+  PANEL_VERSION_INCOMPATIBLE_EVENT,
+} from '@/utils/panelVersion'
 import { ENDPOINT_PATTERNS, ENDPOINTS } from './endpoints'
 import { ErrorCode } from './types'
 
@@ -349,8 +354,11 @@ export class HttpClient {
   }
 
   private setupInterceptors(): void {
-    // Request interceptor - add CSRF token header for mutating requests
+    // Request interceptor - add panel version and CSRF token headers
     this.client.interceptors.request.use((config) => {
+      // 后端据此拒绝不兼容的面板（409 PanelVersionIncompatible）
+      config.headers[PANEL_VERSION_HEADER] = PANEL_VERSION
+
       // Add CSRF token for non-GET requests (POST, PUT, DELETE, PATCH)
       if (
         config.method &&
@@ -461,18 +469,25 @@ export class HttpClient {
       const status = axiosError.response?.status
       const context = this.context
 
-      // 从新格式 {code, message, data?} 提取后端错误信息
+      // 优先从错误信封 {error: {code, message, request_id}} 提取，
+      // 再回退到旧版顶层 {code, message}
       let backendMessage: string | undefined
       let backendErrorCode: ErrorCode | undefined
       if (axiosError.response?.data) {
         const data = axiosError.response.data as {
           code?: number
           message?: string
-          error?: string // 兼容旧格式
+          error?: string | { code?: number; message?: string } // string 为旧格式
           data?: { error?: string } // 兼容旧格式
         }
-        backendErrorCode = data.code as ErrorCode | undefined
-        backendMessage = data.message || data.error || data.data?.error
+        const envelope = typeof data.error === 'object' ? data.error : undefined
+        const legacyError =
+          typeof data.error === 'string' ? data.error : undefined
+        backendErrorCode = (envelope?.code ?? data.code) as
+          | ErrorCode
+          | undefined
+        backendMessage =
+          envelope?.message || data.message || legacyError || data.data?.error
       }
 
       // 如果后端返回了 ErrorCode，直接使用
@@ -480,6 +495,9 @@ export class HttpClient {
         backendErrorCode !== undefined &&
         backendErrorCode !== ErrorCode.Success
       ) {
+        if (backendErrorCode === ErrorCode.PanelVersionIncompatible) {
+          window.dispatchEvent(new Event(PANEL_VERSION_INCOMPATIBLE_EVENT))
+        }
         throw new ApiError(
           backendMessage || `Error ${status}`,
           status,
@@ -0,0 +1,29 @@
+import { ENDPOINTS } from './endpoints'
+import { adminClient } from './http'
+import type { BaseUrlInfo, VersionInfo } from './types'
+
+export class MetaService {
+  /**
+   * 获取 API 版本与最低兼容面板版本
+   */
+  async fetchVersion(signal?: AbortSignal): Promise<VersionInfo | null> {
+    const response = await adminClient.get<{
+      code?: number
+      data?: VersionInfo
+    }>(ENDPOINTS.META.VERSION, { signal, skipCache: true })
+    return response.data ?? null
+  }
+
+  /**
+   * 获取完整短链使用的 base URL
+   */
+  async fetchBaseUrl(signal?: AbortSignal): Promise<BaseUrlInfo | null> {
+    const response = await adminClient.get<{
+      code?: number
+      data?: BaseUrlInfo
+    }>(ENDPOINTS.META.BASE_URL, { signal, skipCache: true })
+    return response.data ?? null
+  }
+}
+
+export const metaService = new MetaService()
@@ -1,4 +1,5 @@
 import { qrcodeLogger } from '@/utils/logger'
+import { getShortUrlBase } from '@/utils/urlBuilder'
 import { ApiError, config } from './http'
 import type { QRCodeOptions } from './types'
 
@@ -48,6 +49,7 @@ export class QRCodeService {
   ): Promise<string> {
     const domain =
       baseUrl ||
+      getShortUrlBase() ||
       config.baseUrl ||
       (typeof window !== 'undefined' ? window.location.origin : '')
 
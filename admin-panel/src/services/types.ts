@@ -7,6 +7,7 @@ export { ErrorCode } from './api.generated'
 export type ActionType = components['schemas']['ActionType']
 export type AnalyticsQuery = components['schemas']['AnalyticsQuery']
 export type AuthSuccessResponse = components['schemas']['AuthSuccessResponse']
+export type BaseUrlInfo = components['schemas']['BaseUrlInfo']
 export type BatchCreateRequest = components['schemas']['BatchCreateRequest']
 export type BatchDeleteRequest = components['schemas']['BatchDeleteRequest']
 export type BatchFailedItem = components['schemas']['BatchFailedItem']
@@ -41,6 +42,8 @@ export type HttpMethod = components['schemas']['HttpMethod']
 export type ImportFailedItem = components['schemas']['ImportFailedItem']
 export type ImportMode = components['schemas']['ImportMode']
 export type ImportResponse = components['schemas']['ImportResponse']
+export type ImportWarningItem = components['schemas']['ImportWarningItem']
+export type ImportPreviewItem = components['schemas']['ImportPreviewItem']
 export type LinkAnalytics = components['schemas']['LinkAnalytics']
 export type LinkResponse = components['schemas']['LinkResponse']
 export type LoginCredentials = components['schemas']['LoginCredentials']
@@ -54,6 +57,7 @@ export type StatsResponse = components['schemas']['StatsResponse']
 export type TopLink = components['schemas']['TopLink']
 export type TrendData = components['schemas']['TrendData']
 export type ValueType = components['schemas']['ValueType']
+export type VersionInfo = components['schemas']['VersionInfo']
 
 // ============ 前端专用类型（保留） ============
 
@@ -7,3 +7,6 @@ interface ImportMetaEnv {
 interface ImportMeta {
   readonly env: ImportMetaEnv
 }
+
+/** 面板版本（构建时由 vite `define` 写入） */
+declare const __PANEL_VERSION__: string
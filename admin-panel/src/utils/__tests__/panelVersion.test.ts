@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest'
+import { isPanelCompatible, parseVersion } from '../panelVersion'
+
+describe('panelVersion', () => {
+  describe('parseVersion', () => {
+    it('should parse major.minor.patch', () => {
+      expect(parseVersion('0.6.0')).toEqual([0, 6, 0])
+      expect(parseVersion('v1.2')).toEqual([1, 2, 0])
+    })
+
+    it('should ignore pre-release and build suffixes', () => {
+      expect(parseVersion('0.7.0-beta.1')).toEqual([0, 7, 0])
+      expect(parseVersion('0.7.0+abc')).toEqual([0, 7, 0])
+    })
+
+    it('should reject invalid versions', () => {
+      expect(parseVersion('latest')).toBeNull()
+      expect(parseVersion('1.2.3.4')).toBeNull()
+      expect(parseVersion('1')).toBeNull()
+    })
+  })
+
+  describe('isPanelCompatible', () => {
+    it('should accept panels between min version and server minor', () => {
+      expect(isPanelCompatible('0.6.0', '0.6.3', '0.6.0')).toBe(true)
+      expect(isPanelCompatible('0.6.5', '0.6.3', '0.6.0')).toBe(true)
+    })
+
+    it('should reject panels older than min version', () => {
+      expect(isPanelCompatible('0.5.9', '0.6.0', '0.6.0')).toBe(false)
+    })
+
+    it('should reject panels newer than the server minor', () => {
+      expect(isPanelCompatible('0.7.0', '0.6.3', '0.6.0')).toBe(false)
+    })
+
+    it('should reject unparseable versions', () => {
+      expect(isPanelCompatible('dev', '0.6.0', '0.6.0')).toBe(false)
+    })
+  })
+})
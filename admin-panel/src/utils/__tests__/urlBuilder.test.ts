@@ -6,6 +6,7 @@ import {
   isValidUrl,
   normalizeUrl,
   parseUrlParams,
+  setShortUrlBase,
 } from '../urlBuilder'
 
 describe('urlBuilder', () => {
@@ -270,5 +271,15 @@ describe('urlBuilder', () => {
     it('should handle empty code', () => {
       expect(buildShortUrl('')).toBe('https://short.link/')
     })
+
+    it('should prefer the server-resolved base URL', () => {
+      setShortUrlBase('https://s.example.com/')
+      try {
+        expect(buildShortUrl('abc123')).toBe('https://s.example.com/abc123')
+      } finally {
+        setShortUrlBase(null)
+      }
+      expect(buildShortUrl('abc123')).toBe('https://short.link/abc123')
+    })
   })
 })
@@ -248,6 +248,7 @@ describe('validators', () => {
       expect(reserved).toContain('dashboard')
       expect(reserved).toContain('links')
       expect(reserved).toContain('settings')
+      expect(reserved).toContain('badge')
     })
 
     it('should include default admin and health', () => {
@@ -55,6 +55,7 @@ export const CONFIG_CATEGORY_INFO: Record<
   tracking: { label: 'Click Tracking', i18nKey: 'config.category.tracking' },
   analytics: { label: 'Analytics', i18nKey: 'config.category.analytics' },
   cache: { label: 'Cache Settings', i18nKey: 'config.category.cache' },
+  security: { label: 'Security', i18nKey: 'config.category.security' },
   other: { label: 'Other', i18nKey: 'config.category.other' },
 }
 
@@ -26,12 +26,14 @@ const ERROR_CODE_TO_I18N_KEY: Partial<Record<ErrorCode, string>> = {
   [ErrorCode.FileTooLarge]: 'errors.fileTooLarge',
   [ErrorCode.InvalidDateFormat]: 'errors.invalidDateFormat',
   [ErrorCode.ServiceUnavailable]: 'errors.serviceUnavailable',
+  [ErrorCode.PanelVersionIncompatible]: 'errors.panelVersionIncompatible',
 
   // 认证错误
   [ErrorCode.AuthFailed]: 'errors.authFailed',
   [ErrorCode.TokenExpired]: 'errors.tokenExpired',
   [ErrorCode.TokenInvalid]: 'errors.tokenInvalid',
   [ErrorCode.CsrfInvalid]: 'errors.csrfInvalid',
+  [ErrorCode.Forbidden]: 'errors.forbidden',
 
   // 链接错误
   [ErrorCode.LinkNotFound]: 'errors.linkNotFound',
@@ -43,6 +45,7 @@ const ERROR_CODE_TO_I18N_KEY: Partial<Record<ErrorCode, string>> = {
   [ErrorCode.LinkEmptyCode]: 'errors.linkEmptyCode',
   [ErrorCode.LinkInvalidCode]: 'errors.linkInvalidCode',
   [ErrorCode.LinkReservedCode]: 'errors.linkReservedCode',
+  [ErrorCode.LinkCodeCoolingDown]: 'errors.linkCodeCoolingDown',
 
   // 导入导出错误
   [ErrorCode.ImportFailed]: 'errors.importFailed',
@@ -62,6 +65,9 @@ const ERROR_CODE_TO_I18N_KEY: Partial<Record<ErrorCode, string>> = {
   [ErrorCode.AnalyticsQueryFailed]: 'errors.analyticsQueryFailed',
   [ErrorCode.AnalyticsLinkNotFound]: 'errors.analyticsLinkNotFound',
   [ErrorCode.AnalyticsInvalidDateRange]: 'errors.analyticsInvalidDateRange',
+
+  // 配额错误
+  [ErrorCode.QuotaExceeded]: 'errors.quotaExceeded',
 }
 
 /**
@@ -11,8 +11,10 @@ export {
   buildShortUrl,
   buildUrl,
   buildUrlParams,
+  getShortUrlBase,
   normalizeUrl,
   parseUrlParams,
+  setShortUrlBase,
 } from './urlBuilder'
 
 // 从 validators 导出所有（包括 isValidUrl）
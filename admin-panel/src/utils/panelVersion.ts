@@ -0,0 +1,57 @@
+/**
+ * 面板与后端 API 的版本握手
+ *
+ * 面板版本在构建时由 vite `define` 写入（取自 package.json），
+ * 启动时与 `GET /admin/meta/version` 返回的兼容信息比对。
+ */
+
+/** 当前面板版本（构建时写入） */
+export const PANEL_VERSION: string = __PANEL_VERSION__
+
+/** 携带面板版本的请求头，后端据此拒绝不兼容的面板（409） */
+export const PANEL_VERSION_HEADER = 'X-Panel-Version'
+
+/** 收到 409 PanelVersionIncompatible 时派发的全局事件 */
+export const PANEL_VERSION_INCOMPATIBLE_EVENT = 'panel-version-incompatible'
+
+type Version = [number, number, number]
+
+/**
+ * 解析 `major.minor[.patch]`，忽略 `-pre` / `+build` 后缀
+ */
+export function parseVersion(version: string): Version | null {
+  const core = version.trim().replace(/^v/, '').split(/[-+]/)[0]
+  const parts = core.split('.')
+  if (parts.length < 2 || parts.length > 3) return null
+  const nums = parts.map((p) => (/^\d+$/.test(p) ? Number(p) : Number.NaN))
+  if (nums.some(Number.isNaN)) return null
+  return [nums[0], nums[1], nums[2] ?? 0]
+}
+
+function compare(a: number[], b: number[]): number {
+  for (let i = 0; i < Math.min(a.length, b.length); i++) {
+    if (a[i] !== b[i]) return a[i] - b[i]
+  }
+  return 0
+}
+
+/**
+ * 面板版本是否与后端兼容
+ *
+ * 与后端 `api::version::is_panel_compatible` 规则一致：不低于最低兼容版本，
+ * 且 `major.minor` 不高于后端。
+ */
+export function isPanelCompatible(
+  panelVersion: string,
+  serverVersion: string,
+  minPanelVersion: string,
+): boolean {
+  const panel = parseVersion(panelVersion)
+  const server = parseVersion(serverVersion)
+  const min = parseVersion(minPanelVersion)
+  if (!panel || !server || !min) return false
+  return (
+    compare(panel, min) >= 0 &&
+    compare(panel.slice(0, 2), server.slice(0, 2)) <= 0
+  )
+}
@@ -162,13 +162,34 @@ export function normalizeUrl(
   return `${defaultProtocol}://${url}`
 }
 
+// 后端解析的短链 base URL（`GET /admin/meta/base-url`），未获取前为 null
+let shortUrlBase: string | null = null
+
+/**
+ * 设置后端返回的短链 base URL
+ * @param baseUrl - 如 `https://s.example.com`，传 null 清除
+ */
+export function setShortUrlBase(baseUrl: string | null): void {
+  shortUrlBase = baseUrl ? baseUrl.replace(/\/+$/, '') : null
+}
+
+/**
+ * 获取后端返回的短链 base URL，未获取时返回 null
+ */
+export function getShortUrlBase(): string | null {
+  return shortUrlBase
+}
+
 /**
  * 构建短链接完整 URL
+ * 优先使用后端解析的 base URL（显式配置 public_base_url 或按代理头推断），
+ * 未获取时回退到当前页面的 origin
  * @param code - 短链接代码
  * @returns 完整的短链接 URL
  */
 export function buildShortUrl(code: string): string {
-  // 获取当前域名作为基础 URL
-  const baseUrl = typeof window !== 'undefined' ? window.location.origin : ''
+  const baseUrl =
+    shortUrlBase ??
+    (typeof window !== 'undefined' ? window.location.origin : '')
   return `${baseUrl}/${code}`
 }
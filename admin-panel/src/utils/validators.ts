@@ -27,7 +27,7 @@ const DANGEROUS_PROTOCOLS = [
 ]
 
 /**
- * 静态保留字列表 - 前端路由路径（固定）
+ * 静态保留字列表 - 前端路由路径与后端固定路由（`/badge`）
  */
 const STATIC_RESERVED_CODES = [
   'login',
@@ -35,6 +35,7 @@ const STATIC_RESERVED_CODES = [
   'links',
   'analytics',
   'settings',
+  'badge',
 ] as const
 
 /**
@@ -1,12 +1,21 @@
+import { readFileSync } from 'node:fs'
 import path from 'node:path'
 import tailwindcss from '@tailwindcss/vite'
 import react from '@vitejs/plugin-react'
 import { defineConfig } from 'vite'
 import { VitePWA } from 'vite-plugin-pwa'
 
+// 面板版本写入构建产物，用于与后端 API 版本握手
+const { version: panelVersion } = JSON.parse(
+  readFileSync(path.resolve(__dirname, 'package.json'), 'utf-8'),
+) as { version: string }
+
 // https://vite.dev/config/
 export default defineConfig({
   base: './',
+  define: {
+    __PANEL_VERSION__: JSON.stringify(panelVersion),
+  },
   plugins: [
     react(),
     tailwindcss(),
@@ -3,8 +3,8 @@
 use chrono::{Duration, Utc};
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use migration::entities::short_link;
-use shortlinker::storage::ShortLink;
 use shortlinker::storage::backend::{model_to_shortlink, shortlink_to_active_model};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
 
 fn create_test_model() -> short_link::Model {
     short_link::Model {
@@ -14,6 +14,10 @@ fn create_test_model() -> short_link::Model {
         expires_at: Some(Utc::now() + Duration::days(7)),
         password: Some("$argon2id$v=19$m=19456,t=2,p=1$hash".to_string()),
         click_count: 12345,
+        created_via: "api".to_string(),
+        analytics_level: "inherit".to_string(),
+        extras: None,
+        owner_token: None,
     }
 }
 
@@ -25,6 +29,9 @@ fn create_test_shortlink() -> ShortLink {
         expires_at: Some(Utc::now() + Duration::hours(24)),
         password: Some("$argon2id$v=19$m=19456,t=2,p=1$hash".to_string()),
         click: 9999,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
     }
 }
 
@@ -50,6 +57,10 @@ fn bench_model_to_shortlink(c: &mut Criterion) {
             expires_at: None,
             password: None,
             click_count: 0,
+            created_via: "api".to_string(),
+            analytics_level: "inherit".to_string(),
+            extras: None,
+            owner_token: None,
         };
         b.iter(|| {
             let _ = model_to_shortlink(model.clone());
@@ -99,6 +110,10 @@ fn bench_batch_conversion(c: &mut Criterion) {
                     expires_at: Some(Utc::now() + Duration::days(7)),
                     password: None,
                     click_count: i as i64,
+                    created_via: "api".to_string(),
+                    analytics_level: "inherit".to_string(),
+                    extras: None,
+                    owner_token: None,
                 })
                 .collect();
 
@@ -116,6 +131,9 @@ fn bench_batch_conversion(c: &mut Criterion) {
                     expires_at: Some(Utc::now() + Duration::days(7)),
                     password: None,
                     click: i,
+                    created_via: CreatedVia::Api,
+                    analytics_level: AnalyticsLevel::Inherit,
+                    extras: None,
                 })
                 .collect();
 
@@ -0,0 +1,70 @@
+//! 请求拦截规则评估性能基准测试
+//!
+//! 目标：10 条规则下，正常请求（不命中任何规则）的评估开销 < 50µs。
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use shortlinker::services::firewall::{FirewallRules, RequestFeatures};
+use std::hint::black_box;
+
+/// 10 条覆盖全部条件类型的规则
+const TEN_RULES: &str = r#"[
+    {"name":"ua-sqlmap","user_agent_contains":"sqlmap","action":"block"},
+    {"name":"ua-nikto","user_agent_contains":"nikto","action":"block"},
+    {"name":"ua-scanner","user_agent_regex":"(?i)(masscan|zgrab|nuclei)/\\d+","action":"tarpit","tarpit_ms":3000},
+    {"name":"ua-empty","user_agent_regex":"^$","action":"log_only"},
+    {"name":"ref-spam","referer_regex":"(?i)https?://([a-z0-9-]+\\.)*spam-(seo|links)\\.example","action":"block"},
+    {"name":"ref-casino","referer_regex":"casino|betting","action":"log_only"},
+    {"name":"net-abuse","ip_cidrs":["192.0.2.0/24","198.51.100.0/24","2001:db8::/32"],"action":"block"},
+    {"name":"path-wp","path_prefix":"/wp-","action":"block"},
+    {"name":"path-env","path_prefix":"/.env","action":"block"},
+    {"name":"combo","path_prefix":"/promo","user_agent_contains":"curl","ip_cidrs":["203.0.113.0/24"],"action":"tarpit"}
+]"#;
+
+fn bench_evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("firewall/evaluate");
+    let rules = FirewallRules::parse(TEN_RULES).expect("valid rules");
+    assert_eq!(rules.len(), 10);
+
+    let normal = RequestFeatures {
+        path: "/abc123",
+        user_agent: Some(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+             (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36",
+        ),
+        referer: Some("https://news.example.com/article/42"),
+        client_ip: Some("203.0.114.9".parse().unwrap()),
+    };
+    group.bench_function("10_rules_no_match", |b| {
+        b.iter(|| {
+            assert!(rules.evaluate(black_box(&normal), |_, _| {}).is_none());
+        });
+    });
+
+    let blocked = RequestFeatures {
+        user_agent: Some("sqlmap/1.8"),
+        ..normal
+    };
+    group.bench_function("10_rules_first_match", |b| {
+        b.iter(|| {
+            assert!(rules.evaluate(black_box(&blocked), |_, _| {}).is_some());
+        });
+    });
+
+    let empty = FirewallRules::default();
+    group.bench_function("no_rules", |b| {
+        b.iter(|| {
+            assert!(empty.evaluate(black_box(&normal), |_, _| {}).is_none());
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_parse(c: &mut Criterion) {
+    c.bench_function("firewall/parse_10_rules", |b| {
+        b.iter(|| FirewallRules::parse(black_box(TEN_RULES)).unwrap());
+    });
+}
+
+criterion_group!(benches, bench_evaluate, bench_parse);
+criterion_main!(benches);
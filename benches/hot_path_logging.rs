@@ -0,0 +1,135 @@
+//! 热路径日志开销基准测试
+//!
+//! 安装只放行 info 及以上级别的 subscriber，模拟生产默认日志级别，对比：
+//! - 点击计数（每次 redirect 都会执行）的吞吐
+//! - 被关闭的 debug 日志：宏外预先格式化 vs 字段语法 vs `enabled!` + 采样
+//!
+//! 约定见 `shortlinker::utils::log_sample`。端到端的 redirect 吞吐用
+//! `shortlinker bench` 或 `benches/benchmark_local.sh` 对比。
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use shortlinker::analytics::ClickSink;
+use shortlinker::utils::log_sample::LogSampler;
+use std::hint::black_box;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Metadata, Subscriber, debug, span};
+
+/// 只放行 info 及以上级别、丢弃所有输出的 subscriber
+struct InfoLevel;
+
+impl Subscriber for InfoLevel {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= Level::INFO
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::INFO)
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// 空 sink，只用于测试 increment 性能
+struct NoopSink;
+
+#[async_trait::async_trait]
+impl ClickSink for NoopSink {
+    async fn flush_clicks(&self, _updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn install_info_subscriber() {
+    let _ = tracing::subscriber::set_global_default(InfoLevel);
+}
+
+/// 点击计数在 info 级别下的吞吐
+fn bench_click_increment(c: &mut Criterion) {
+    install_info_subscriber();
+    let manager = shortlinker::analytics::manager::ClickManager::new(
+        Arc::new(NoopSink) as Arc<dyn ClickSink>,
+        Duration::from_secs(3600), // 长间隔，避免自动刷盘
+        usize::MAX,                // 高阈值，避免阈值刷盘
+        shortlinker::metrics::NoopMetrics::arc(),
+    );
+
+    c.bench_function("hot_path_logging/click_increment", |b| {
+        b.iter(|| manager.increment(black_box("abc123")));
+    });
+}
+
+/// 关闭级别的日志调用：参数求值位置决定开销
+fn bench_disabled_debug(c: &mut Criterion) {
+    install_info_subscriber();
+    let mut group = c.benchmark_group("hot_path_logging/disabled_debug");
+    let code = "abc123";
+
+    group.bench_function("preformatted", |b| {
+        b.iter(|| {
+            // 反例：消息在宏外拼好，级别关闭时仍然分配
+            let message = format!("Cache not found for path: {}", black_box(code));
+            debug!("{}", message);
+        });
+    });
+    group.bench_function("field_syntax", |b| {
+        b.iter(|| {
+            debug!(code = black_box(code), "short link not found");
+        });
+    });
+
+    static SAMPLER: LogSampler = LogSampler::new(1000);
+    group.bench_function("enabled_then_sampled", |b| {
+        b.iter(|| {
+            if tracing::enabled!(Level::DEBUG)
+                && let Some(suppressed) = SAMPLER.sample()
+            {
+                debug!(code = black_box(code), suppressed, "short link not found");
+            }
+        });
+    });
+    group.finish();
+}
+
+/// 开启级别（warn）下采样与逐条输出的对比
+fn bench_enabled_warn(c: &mut Criterion) {
+    install_info_subscriber();
+    let mut group = c.benchmark_group("hot_path_logging/enabled_warn");
+
+    group.bench_function("every_event", |b| {
+        b.iter(|| {
+            tracing::warn!(code = black_box("abc123"), "event channel full");
+        });
+    });
+
+    static SAMPLER: LogSampler = LogSampler::new(1000);
+    group.bench_function("sampled", |b| {
+        b.iter(|| {
+            if let Some(suppressed) = SAMPLER.sample() {
+                tracing::warn!(code = black_box("abc123"), suppressed, "event channel full");
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_click_increment,
+    bench_disabled_debug,
+    bench_enabled_warn
+);
+criterion_main!(benches);
@@ -3,7 +3,7 @@
 use bytes::BytesMut;
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use shortlinker::config::init_config;
-use shortlinker::storage::ShortLink;
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, LinkRecord};
 use shortlinker::system::ipc::protocol::{decode, encode};
 use shortlinker::system::ipc::types::{IpcCommand, IpcResponse};
 use shortlinker::system::reload::ReloadTarget;
@@ -31,6 +31,10 @@ fn create_test_commands() -> Vec<IpcCommand> {
             force: true,
             expires_at: Some("2025-12-31T23:59:59Z".to_string()),
             password: Some("secret".to_string()),
+            created_via: None,
+            analytics_level: None,
+            extras: None,
+            override_cooldown: false,
         },
         IpcCommand::ListLinks {
             page: 1,
@@ -60,13 +64,16 @@ fn create_test_responses() -> Vec<IpcResponse> {
         },
         IpcResponse::LinkList {
             links: (0..10u64)
-                .map(|i| ShortLink {
+                .map(|i| LinkRecord {
                     code: format!("code_{}", i),
                     target: format!("https://example.com/{}", i),
                     created_at: chrono::Utc::now(),
                     expires_at: None,
                     password: None,
-                    click: (i * 100) as usize,
+                    click_count: (i * 100) as usize,
+                    created_via: CreatedVia::Api,
+                    analytics_level: AnalyticsLevel::Inherit,
+                    extras: None,
                 })
                 .collect(),
             total: 1000,
@@ -190,13 +197,16 @@ fn bench_batch_roundtrip(c: &mut Criterion) {
     for num_links in [10u64, 100, 200] {
         let response = IpcResponse::LinkList {
             links: (0..num_links)
-                .map(|i| ShortLink {
+                .map(|i| LinkRecord {
                     code: format!("code_{}", i),
                     target: format!("https://example.com/path/{}", i),
                     created_at: chrono::Utc::now(),
                     expires_at: None,
                     password: None,
-                    click: (i * 10) as usize,
+                    click_count: (i * 10) as usize,
+                    created_via: CreatedVia::Api,
+                    analytics_level: AnalyticsLevel::Inherit,
+                    extras: None,
                 })
                 .collect(),
             total: num_links as usize,
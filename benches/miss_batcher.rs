@@ -0,0 +1,150 @@
+//! 缓存 miss 回源微批量基准测试
+//!
+//! 模拟高并发下大量不同短码同时 miss：对比逐个单查（`cache.miss_batch` 关闭）
+//! 与微批量合并的整轮耗时。正式计时前先各跑一轮，打印 DB 查询次数与单请求 p99。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use shortlinker::config::{MissBatchConfig, init_config};
+use shortlinker::metrics::{MetricsRecorder, NoopMetrics};
+use shortlinker::services::MissBatcher;
+use shortlinker::storage::backend::SeaOrmStorage;
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+use tempfile::TempDir;
+
+const LINK_COUNT: usize = 10_000;
+
+/// 按 mode 统计回源查询次数
+#[derive(Default)]
+struct QueryCounter {
+    loads: Mutex<HashMap<String, usize>>,
+}
+
+impl QueryCounter {
+    fn total(&self) -> usize {
+        self.loads.lock().unwrap().values().sum()
+    }
+}
+
+impl MetricsRecorder for QueryCounter {
+    fn inc_cache_miss_load(&self, mode: &str) {
+        *self
+            .loads
+            .lock()
+            .unwrap()
+            .entry(mode.to_string())
+            .or_default() += 1;
+    }
+}
+
+async fn setup_storage(dir: &TempDir) -> Arc<SeaOrmStorage> {
+    init_config();
+    let db_url = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("bench.db").display()
+    );
+    let storage = Arc::new(
+        SeaOrmStorage::new(&db_url, "sqlite", NoopMetrics::arc())
+            .await
+            .unwrap(),
+    );
+    let links = (0..LINK_COUNT)
+        .map(|i| ShortLink {
+            code: format!("bench_{}", i),
+            target: format!("https://example.com/{}", i),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        })
+        .collect();
+    storage.batch_set(links).await.unwrap();
+    storage
+}
+
+fn batcher(
+    storage: &Arc<SeaOrmStorage>,
+    enabled: bool,
+    metrics: Arc<dyn MetricsRecorder>,
+) -> Arc<MissBatcher> {
+    let config = MissBatchConfig {
+        enabled,
+        ..Default::default()
+    };
+    Arc::new(MissBatcher::new(storage.clone(), &config, metrics))
+}
+
+/// 并发发起 `concurrency` 个不同短码的回源，返回各请求耗时
+async fn run_round(batcher: &Arc<MissBatcher>, round: usize, concurrency: usize) -> Vec<Duration> {
+    let handles: Vec<_> = (0..concurrency)
+        .map(|i| {
+            let batcher = batcher.clone();
+            let code = format!("bench_{}", (round * concurrency + i) % LINK_COUNT);
+            tokio::spawn(async move {
+                let started = Instant::now();
+                batcher.load(&code).await.unwrap();
+                started.elapsed()
+            })
+        })
+        .collect();
+    let mut latencies = Vec::with_capacity(concurrency);
+    for handle in handles {
+        latencies.push(handle.await.unwrap());
+    }
+    latencies
+}
+
+fn p99(mut latencies: Vec<Duration>) -> Duration {
+    latencies.sort();
+    latencies[(latencies.len() * 99 / 100).min(latencies.len() - 1)]
+}
+
+fn bench_concurrent_misses(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let dir = TempDir::new().unwrap();
+    let storage = rt.block_on(setup_storage(&dir));
+
+    let mut group = c.benchmark_group("miss_batch/concurrent_misses");
+    group.sample_size(20);
+
+    for concurrency in [64, 256] {
+        for (name, enabled) in [("direct", false), ("batched", true)] {
+            let counter = Arc::new(QueryCounter::default());
+            let probe = batcher(&storage, enabled, counter.clone());
+            let latencies = rt.block_on(run_round(&probe, 0, concurrency));
+            eprintln!(
+                "{} x{}: {} DB queries, p99 {:?}",
+                name,
+                concurrency,
+                counter.total(),
+                p99(latencies)
+            );
+
+            let batcher = batcher(&storage, enabled, NoopMetrics::arc());
+            let mut round = 0;
+            group.throughput(Throughput::Elements(concurrency as u64));
+            group.bench_with_input(
+                BenchmarkId::new(name, concurrency),
+                &concurrency,
+                |b, &concurrency| {
+                    b.to_async(&rt).iter(|| {
+                        round += 1;
+                        let batcher = batcher.clone();
+                        async move { run_round(&batcher, round, concurrency).await }
+                    });
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_concurrent_misses);
+criterion_main!(benches);
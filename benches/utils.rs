@@ -1,6 +1,7 @@
 //! 工具函数性能基准测试
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use shortlinker::utils::redirect_body::fallback_body;
 use shortlinker::utils::{generate_random_code, generate_secure_token, is_valid_short_code};
 
 // ============== is_valid_short_code 基准测试 ==============
@@ -86,10 +87,30 @@ fn bench_generate_secure_token(c: &mut Criterion) {
     group.finish();
 }
 
+// ============== fallback_body 基准测试 ==============
+
+fn bench_fallback_body(c: &mut Criterion) {
+    let mut group = c.benchmark_group("utils/fallback_body");
+
+    let plain = "https://example.com/docs/getting-started";
+    group.bench_function("plain_url", |b| {
+        b.iter(|| fallback_body(std::hint::black_box(plain)));
+    });
+
+    // 查询参数中的 `&` 需要转义
+    let query = "https://example.com/search?q=rust&lang=en&page=2&utm_source=newsletter";
+    group.bench_function("url_with_query", |b| {
+        b.iter(|| fallback_body(std::hint::black_box(query)));
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_is_valid_short_code,
     bench_generate_random_code,
     bench_generate_secure_token,
+    bench_fallback_body,
 );
 criterion_main!(benches);
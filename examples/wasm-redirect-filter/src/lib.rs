@@ -0,0 +1,145 @@
+//! shortlinker redirect 过滤插件示例
+//!
+//! 构建：`cargo build --release --target wasm32-unknown-unknown`，产物为
+//! `target/wasm32-unknown-unknown/release/shortlinker_redirect_filter_example.wasm`，
+//! 在 `config.toml` 中配置 `plugins.redirect_filter` 指向该文件即可。
+//!
+//! 规则：
+//!
+//! - User-Agent 含 `BadBot` → 403
+//! - `internal-` 开头的短码只允许 `10.0.0.0/8` 访问，其余 → 404
+//! - `Accept-Language` 以 `zh` 开头 → 目标追加 `lang=zh`
+//! - 其余放行
+//!
+//! 接口约定见 shortlinker 文档「WASM 过滤插件」。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct Input {
+    pub code: String,
+    pub target: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub client_ip: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Decision {
+    Allow,
+    Deny { status: u16 },
+    Override { target: String },
+}
+
+pub fn decide(input: &Input) -> Decision {
+    let user_agent = input
+        .headers
+        .get("user-agent")
+        .map(|ua| ua.to_ascii_lowercase())
+        .unwrap_or_default();
+    if user_agent.contains("badbot") {
+        return Decision::Deny { status: 403 };
+    }
+
+    let internal_client = input
+        .client_ip
+        .as_deref()
+        .is_some_and(|ip| ip.starts_with("10."));
+    if input.code.starts_with("internal-") && !internal_client {
+        return Decision::Deny { status: 404 };
+    }
+
+    if input
+        .headers
+        .get("accept-language")
+        .is_some_and(|lang| lang.starts_with("zh"))
+    {
+        let separator = if input.target.contains('?') { '&' } else { '?' };
+        return Decision::Override {
+            target: format!("{}{}lang=zh", input.target, separator),
+        };
+    }
+
+    Decision::Allow
+}
+
+/// 分配 `len` 字节供宿主写入输入
+#[unsafe(no_mangle)]
+pub extern "C" fn alloc(len: i32) -> i32 {
+    let buf = vec![0u8; len.max(0) as usize].into_boxed_slice();
+    Box::into_raw(buf) as *mut u8 as i32
+}
+
+/// 释放 [`alloc`] 或 [`filter`] 返回的缓冲区
+///
+/// # Safety
+///
+/// `ptr` / `len` 必须来自本模块分配的同一块缓冲区，且只释放一次。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dealloc(ptr: i32, len: i32) {
+    let slice = std::ptr::slice_from_raw_parts_mut(ptr as *mut u8, len.max(0) as usize);
+    drop(unsafe { Box::from_raw(slice) });
+}
+
+/// 读取输入 JSON，返回 `(out_ptr << 32) | out_len`
+///
+/// # Safety
+///
+/// `ptr` / `len` 必须指向 [`alloc`] 分配并已写入的缓冲区。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn filter(ptr: i32, len: i32) -> i64 {
+    let input = unsafe { std::slice::from_raw_parts(ptr as *const u8, len.max(0) as usize) };
+    // 无法解析的输入放行，交给宿主的其他防线
+    let decision = serde_json::from_slice::<Input>(input)
+        .map(|input| decide(&input))
+        .unwrap_or(Decision::Allow);
+    let output = serde_json::to_vec(&decision)
+        .unwrap_or_default()
+        .into_boxed_slice();
+    let out_len = output.len() as u32;
+    let out_ptr = Box::into_raw(output) as *mut u8 as u32;
+    (((out_ptr as u64) << 32) | out_len as u64) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(code: &str, headers: &[(&str, &str)], client_ip: Option<&str>) -> Input {
+        Input {
+            code: code.to_string(),
+            target: "https://example.com/page".to_string(),
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            client_ip: client_ip.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(
+            decide(&input("a", &[("user-agent", "Mozilla BadBot/1.0")], None)),
+            Decision::Deny { status: 403 }
+        );
+        assert_eq!(
+            decide(&input("internal-wiki", &[], Some("203.0.113.1"))),
+            Decision::Deny { status: 404 }
+        );
+        assert_eq!(
+            decide(&input("internal-wiki", &[], Some("10.1.2.3"))),
+            Decision::Allow
+        );
+        assert_eq!(
+            decide(&input("a", &[("accept-language", "zh-CN,zh;q=0.9")], None)),
+            Decision::Override {
+                target: "https://example.com/page?lang=zh".to_string()
+            }
+        );
+        assert_eq!(decide(&input("a", &[], None)), Decision::Allow);
+    }
+}
@@ -0,0 +1,27 @@
+//! Analytics amendment audit entity (one row per applied `analytics amend`)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "analytics_amendments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub short_code: String,
+    pub range_start: DateTimeUtc,
+    pub range_end: DateTimeUtc,
+    /// JSON array of removed IP CIDRs
+    #[sea_orm(column_type = "Text")]
+    pub ip_cidrs: String,
+    pub removed_rows: i64,
+    /// Link total click count before / after the amendment
+    pub clicks_before: i64,
+    pub clicks_after: i64,
+    pub operator: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+//! API token entity (per-team credentials with quotas)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "api_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub max_links: Option<i64>,
+    pub max_daily_creates: Option<i64>,
+    /// 统计级别上限（none / count_only / aggregate / full），NULL 为不限
+    pub max_analytics_level: Option<String>,
+    /// 最近一次发送用量提醒的日期（YYYY-MM-DD，UTC）
+    pub alert_sent_on: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -18,6 +18,9 @@ pub struct Model {
     pub source: Option<String>,
     /// UserAgent hash (references user_agents.hash)
     pub user_agent_hash: Option<String>,
+    /// Whitelisted query params as a JSON object (`analytics.captured_query_params`)
+    #[sea_orm(column_type = "Text", nullable)]
+    pub query_params: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -16,6 +16,9 @@ pub struct Model {
     pub country_counts: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub source_counts: Option<String>,
+    /// 自定义 query 参数计数 `{"参数名": {"值": 次数}}`
+    #[sea_orm(column_type = "Text", nullable)]
+    pub param_counts: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
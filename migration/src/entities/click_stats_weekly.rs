@@ -0,0 +1,24 @@
+//! 周级点击统计汇总实体（由天汇总物化）
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "click_stats_weekly")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub short_code: String,
+    pub week_start: Date,
+    pub click_count: i64,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub top_referrers: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub top_countries: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub top_sources: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
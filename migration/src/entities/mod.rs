@@ -1,17 +1,35 @@
+pub mod analytics_amendment;
+pub mod api_token;
+pub mod api_token_daily_usage;
 pub mod click_log;
 pub mod click_stats_daily;
 pub mod click_stats_global_daily;
 pub mod click_stats_global_hourly;
 pub mod click_stats_hourly;
+pub mod click_stats_monthly;
+pub mod click_stats_weekly;
 pub mod config_history;
+pub mod pending_side_effect;
+pub mod redirect_timing;
+pub mod retired_code;
 pub mod short_link;
+pub mod short_link_archive;
 pub mod user_agent;
 
+pub use analytics_amendment::Entity as AnalyticsAmendmentEntity;
+pub use api_token::Entity as ApiTokenEntity;
+pub use api_token_daily_usage::Entity as ApiTokenDailyUsageEntity;
 pub use click_log::Entity as ClickLogEntity;
 pub use click_stats_daily::Entity as ClickStatsDailyEntity;
 pub use click_stats_global_daily::Entity as ClickStatsGlobalDailyEntity;
 pub use click_stats_global_hourly::Entity as ClickStatsGlobalHourlyEntity;
 pub use click_stats_hourly::Entity as ClickStatsHourlyEntity;
+pub use click_stats_monthly::Entity as ClickStatsMonthlyEntity;
+pub use click_stats_weekly::Entity as ClickStatsWeeklyEntity;
 pub use config_history::Entity as ConfigHistoryEntity;
+pub use pending_side_effect::Entity as PendingSideEffectEntity;
+pub use redirect_timing::Entity as RedirectTimingEntity;
+pub use retired_code::Entity as RetiredCodeEntity;
 pub use short_link::Entity as ShortLinkEntity;
+pub use short_link_archive::Entity as ShortLinkArchiveEntity;
 pub use user_agent::Entity as UserAgentEntity;
@@ -0,0 +1,22 @@
+//! Pending side effect entity (transactional outbox)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "pending_side_effects")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub effect_type: String,
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    pub created_at: DateTimeUtc,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
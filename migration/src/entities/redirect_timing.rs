@@ -0,0 +1,24 @@
+//! Redirect timing sample entity (per-phase latencies in microseconds)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "redirect_timings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub short_code: String,
+    pub recorded_at: DateTimeUtc,
+    pub status: i16,
+    pub total_us: i32,
+    pub cache_us: i32,
+    pub bloom_us: Option<i32>,
+    pub db_us: Option<i32>,
+    pub geo_us: Option<i32>,
+    pub enqueue_us: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,16 @@
+//! Retired short code entity (code reuse cooldown after deletion)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "retired_codes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub short_code: String,
+    pub retired_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
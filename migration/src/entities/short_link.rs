@@ -11,6 +11,13 @@ pub struct Model {
     pub expires_at: Option<DateTimeUtc>,
     pub password: Option<String>,
     pub click_count: i64,
+    pub created_via: String,
+    pub analytics_level: String,
+    /// 自定义扩展字段（JSON 对象文本）
+    #[sea_orm(column_type = "Text", nullable)]
+    pub extras: Option<String>,
+    /// 计入配额的 API token id；主管理员凭据创建的链接为 NULL
+    pub owner_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
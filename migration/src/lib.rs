@@ -15,6 +15,19 @@ mod m20260209_000001_source_geo_indexes;
 mod m20260209_000002_analytics_indexes_v2;
 mod m20260209_000003_global_daily_rollup;
 mod m20260721_000001_forge_system_config;
+mod m20261016_000001_short_link_created_via;
+mod m20261016_000002_short_link_archive;
+mod m20261016_000003_pending_side_effects;
+mod m20261016_000004_api_tokens;
+mod m20261016_000005_redirect_timings;
+mod m20261016_000006_analytics_amendments;
+mod m20261016_000007_period_rollups;
+mod m20261016_000008_short_link_analytics_level;
+mod m20261016_000009_retired_codes;
+mod m20261016_000010_captured_query_params;
+mod m20261016_000011_short_link_extras;
+mod m20261016_000012_api_token_analytics_cap;
+pub mod rollback;
 
 pub struct Migrator;
 
@@ -36,6 +49,18 @@ impl MigratorTrait for Migrator {
             Box::new(m20260209_000002_analytics_indexes_v2::Migration),
             Box::new(m20260209_000003_global_daily_rollup::Migration),
             Box::new(m20260721_000001_forge_system_config::Migration),
+            Box::new(m20261016_000001_short_link_created_via::Migration),
+            Box::new(m20261016_000002_short_link_archive::Migration),
+            Box::new(m20261016_000003_pending_side_effects::Migration),
+            Box::new(m20261016_000004_api_tokens::Migration),
+            Box::new(m20261016_000005_redirect_timings::Migration),
+            Box::new(m20261016_000006_analytics_amendments::Migration),
+            Box::new(m20261016_000007_period_rollups::Migration),
+            Box::new(m20261016_000008_short_link_analytics_level::Migration),
+            Box::new(m20261016_000009_retired_codes::Migration),
+            Box::new(m20261016_000010_captured_query_params::Migration),
+            Box::new(m20261016_000011_short_link_extras::Migration),
+            Box::new(m20261016_000012_api_token_analytics_cap::Migration),
         ]
     }
 }
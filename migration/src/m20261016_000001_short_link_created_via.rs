@@ -0,0 +1,71 @@
+//! 短链接创建渠道字段迁移
+//!
+//! short_links 添加 created_via 列，记录链接由哪个入口创建
+//! （api / cli / tui / import / ipc / bootstrap），存量数据回填为 unknown。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. 添加 created_via 列，默认值即完成存量回填
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .add_column(
+                        ColumnDef::new(ShortLinks::CreatedVia)
+                            .string_len(16)
+                            .not_null()
+                            .default("unknown"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. 按渠道统计与过滤使用的索引
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_short_links_created_via")
+                    .table(ShortLinks::Table)
+                    .col(ShortLinks::CreatedVia)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_short_links_created_via")
+                    .table(ShortLinks::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .drop_column(ShortLinks::CreatedVia)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShortLinks {
+    #[sea_orm(iden = "short_links")]
+    Table,
+    CreatedVia,
+}
@@ -0,0 +1,105 @@
+//! 短链接归档表迁移
+//!
+//! 新增 short_link_archive 表，结构与 short_links 相同并附加 archived_at。
+//! 归档的链接从主表移入此表，点击日志与汇总表按 short_code 关联，不受影响。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShortLinkArchive::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::ShortCode)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::TargetUrl)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ShortLinkArchive::Password).string().null())
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::ClickCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::CreatedVia)
+                            .string_len(16)
+                            .not_null()
+                            .default("unknown"),
+                    )
+                    .col(
+                        ColumnDef::new(ShortLinkArchive::ArchivedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 归档列表按归档时间倒序分页
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_short_link_archive_archived_at")
+                    .table(ShortLinkArchive::Table)
+                    .col(ShortLinkArchive::ArchivedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_short_link_archive_archived_at")
+                    .table(ShortLinkArchive::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ShortLinkArchive::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShortLinkArchive {
+    #[sea_orm(iden = "short_link_archive")]
+    Table,
+    ShortCode,
+    TargetUrl,
+    CreatedAt,
+    ExpiresAt,
+    Password,
+    ClickCount,
+    CreatedVia,
+    ArchivedAt,
+}
@@ -0,0 +1,93 @@
+//! 待执行副作用（outbox）表迁移
+//!
+//! 新增 pending_side_effects 表：链接写事务内同时写入待执行的收尾副作用
+//! （缓存失效等），提交后立即执行并删除；进程在两者之间崩溃时，
+//! 残留条目由后台任务重放。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PendingSideEffects::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PendingSideEffects::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingSideEffects::EffectType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingSideEffects::Payload)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingSideEffects::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingSideEffects::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(PendingSideEffects::LastError).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 重放按创建时间扫描
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_pending_side_effects_created_at")
+                    .table(PendingSideEffects::Table)
+                    .col(PendingSideEffects::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_pending_side_effects_created_at")
+                    .table(PendingSideEffects::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PendingSideEffects::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PendingSideEffects {
+    Table,
+    Id,
+    EffectType,
+    Payload,
+    CreatedAt,
+    Attempts,
+    LastError,
+}
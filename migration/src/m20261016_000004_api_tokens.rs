@@ -0,0 +1,176 @@
+//! API Token 与配额表迁移
+//!
+//! - api_tokens：团队 API Token（仅存哈希）及其配额
+//! - api_token_daily_usage：按 token + 日期（UTC）累计的创建数，删除链接不回退
+//! - short_links 添加 owner_token 列，记录链接计入哪个 token 的配额
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. api_tokens
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiTokens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiTokens::Id)
+                            .string_len(32)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiTokens::Name).string_len(64).not_null())
+                    .col(
+                        ColumnDef::new(ApiTokens::TokenHash)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApiTokens::MaxLinks).big_integer().null())
+                    .col(
+                        ColumnDef::new(ApiTokens::MaxDailyCreates)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ApiTokens::AlertSentOn).string_len(10).null())
+                    .col(
+                        ColumnDef::new(ApiTokens::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. api_token_daily_usage
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiTokenDailyUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiTokenDailyUsage::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenDailyUsage::TokenId)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenDailyUsage::Day)
+                            .string_len(10)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenDailyUsage::CreatedCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 唯一索引：token_id + day（计数 upsert 的冲突键）
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_api_token_daily_usage_token_day")
+                    .table(ApiTokenDailyUsage::Table)
+                    .col(ApiTokenDailyUsage::TokenId)
+                    .col(ApiTokenDailyUsage::Day)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. short_links.owner_token，NULL 表示由主管理员凭据创建
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .add_column(ColumnDef::new(ShortLinks::OwnerToken).string_len(32).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 4. 实时统计链接数使用的索引
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_short_links_owner_token")
+                    .table(ShortLinks::Table)
+                    .col(ShortLinks::OwnerToken)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_short_links_owner_token")
+                    .table(ShortLinks::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .drop_column(ShortLinks::OwnerToken)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ApiTokenDailyUsage::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ApiTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiTokens {
+    Table,
+    Id,
+    Name,
+    TokenHash,
+    MaxLinks,
+    MaxDailyCreates,
+    AlertSentOn,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ApiTokenDailyUsage {
+    #[sea_orm(iden = "api_token_daily_usage")]
+    Table,
+    Id,
+    TokenId,
+    Day,
+    CreatedCount,
+}
+
+#[derive(DeriveIden)]
+enum ShortLinks {
+    #[sea_orm(iden = "short_links")]
+    Table,
+    OwnerToken,
+}
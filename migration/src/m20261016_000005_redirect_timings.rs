@@ -0,0 +1,104 @@
+//! Redirect 耗时采样表迁移
+//!
+//! 新增 redirect_timings 表：按 `analytics.timing_sample_rate` 采样的 redirect
+//! 各阶段耗时（微秒），用于容量规划。数据只保留短期
+//! （`analytics.timing_retention_days`），由 DataRetentionTask 清理。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RedirectTimings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RedirectTimings::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RedirectTimings::ShortCode)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RedirectTimings::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RedirectTimings::Status)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RedirectTimings::TotalUs)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RedirectTimings::CacheUs)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RedirectTimings::BloomUs).integer().null())
+                    .col(ColumnDef::new(RedirectTimings::DbUs).integer().null())
+                    .col(ColumnDef::new(RedirectTimings::GeoUs).integer().null())
+                    .col(ColumnDef::new(RedirectTimings::EnqueueUs).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 查询与清理都按时间范围扫描
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_redirect_timings_recorded_at")
+                    .table(RedirectTimings::Table)
+                    .col(RedirectTimings::RecordedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_redirect_timings_recorded_at")
+                    .table(RedirectTimings::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RedirectTimings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RedirectTimings {
+    Table,
+    Id,
+    ShortCode,
+    RecordedAt,
+    Status,
+    TotalUs,
+    CacheUs,
+    BloomUs,
+    DbUs,
+    GeoUs,
+    EnqueueUs,
+}
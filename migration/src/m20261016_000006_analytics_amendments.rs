@@ -0,0 +1,118 @@
+//! 点击统计订正审计表迁移
+//!
+//! 新增 analytics_amendments 表：`shortlinker analytics amend` 每次实际执行都写入
+//! 一条记录（订正范围、删除条件、前后点击数），dry-run 不记录。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AnalyticsAmendments::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::ShortCode)
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::RangeStart)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::RangeEnd)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::IpCidrs)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::RemovedRows)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::ClicksBefore)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::ClicksAfter)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::Operator)
+                            .string_len(128)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AnalyticsAmendments::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_analytics_amendments_short_code")
+                    .table(AnalyticsAmendments::Table)
+                    .col(AnalyticsAmendments::ShortCode)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_analytics_amendments_short_code")
+                    .table(AnalyticsAmendments::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AnalyticsAmendments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AnalyticsAmendments {
+    Table,
+    Id,
+    ShortCode,
+    RangeStart,
+    RangeEnd,
+    IpCidrs,
+    RemovedRows,
+    ClicksBefore,
+    ClicksAfter,
+    Operator,
+    CreatedAt,
+}
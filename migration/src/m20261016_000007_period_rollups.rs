@@ -0,0 +1,213 @@
+//! 周 / 月点击统计汇总表迁移
+//!
+//! 新增 `click_stats_weekly` / `click_stats_monthly` 表，由天汇总求和物化，
+//! 支撑长时间范围的 Week / Month 粒度趋势查询。周汇总的 `week_start` 按
+//! `analytics.week_starts_on` 对齐。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // ========== click_stats_weekly ==========
+        manager
+            .create_table(
+                Table::create()
+                    .table(ClickStatsWeekly::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ClickStatsWeekly::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsWeekly::ShortCode)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsWeekly::WeekStart)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsWeekly::ClickCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ClickStatsWeekly::TopReferrers).text().null())
+                    .col(ColumnDef::new(ClickStatsWeekly::TopCountries).text().null())
+                    .col(ColumnDef::new(ClickStatsWeekly::TopSources).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_stats_weekly_code_start")
+                    .table(ClickStatsWeekly::Table)
+                    .col(ClickStatsWeekly::ShortCode)
+                    .col(ClickStatsWeekly::WeekStart)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_stats_weekly_start")
+                    .table(ClickStatsWeekly::Table)
+                    .col(ClickStatsWeekly::WeekStart)
+                    .to_owned(),
+            )
+            .await?;
+
+        // ========== click_stats_monthly ==========
+        manager
+            .create_table(
+                Table::create()
+                    .table(ClickStatsMonthly::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::ShortCode)
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::MonthStart)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::ClickCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::TopReferrers)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ClickStatsMonthly::TopCountries)
+                            .text()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ClickStatsMonthly::TopSources).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_stats_monthly_code_start")
+                    .table(ClickStatsMonthly::Table)
+                    .col(ClickStatsMonthly::ShortCode)
+                    .col(ClickStatsMonthly::MonthStart)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_stats_monthly_start")
+                    .table(ClickStatsMonthly::Table)
+                    .col(ClickStatsMonthly::MonthStart)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_stats_monthly_start")
+                    .table(ClickStatsMonthly::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_stats_monthly_code_start")
+                    .table(ClickStatsMonthly::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_stats_weekly_start")
+                    .table(ClickStatsWeekly::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_stats_weekly_code_start")
+                    .table(ClickStatsWeekly::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ClickStatsMonthly::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ClickStatsWeekly::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ClickStatsWeekly {
+    #[sea_orm(iden = "click_stats_weekly")]
+    Table,
+    Id,
+    ShortCode,
+    WeekStart,
+    ClickCount,
+    TopReferrers,
+    TopCountries,
+    TopSources,
+}
+
+#[derive(DeriveIden)]
+enum ClickStatsMonthly {
+    #[sea_orm(iden = "click_stats_monthly")]
+    Table,
+    Id,
+    ShortCode,
+    MonthStart,
+    ClickCount,
+    TopReferrers,
+    TopCountries,
+    TopSources,
+}
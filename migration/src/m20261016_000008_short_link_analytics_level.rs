@@ -0,0 +1,82 @@
+//! 短链接统计级别字段迁移
+//!
+//! short_links 与 short_link_archive 添加 analytics_level 列
+//! （inherit / none / count_only / aggregate / full），存量数据回填为 inherit，
+//! 即继续跟随全局统计配置。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 默认值即完成存量回填；归档表同步添加，保证归档 / 恢复时级别不丢失
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .add_column(
+                        ColumnDef::new(ShortLinks::AnalyticsLevel)
+                            .string_len(16)
+                            .not_null()
+                            .default("inherit"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinkArchive::Table)
+                    .add_column(
+                        ColumnDef::new(ShortLinkArchive::AnalyticsLevel)
+                            .string_len(16)
+                            .not_null()
+                            .default("inherit"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinkArchive::Table)
+                    .drop_column(ShortLinkArchive::AnalyticsLevel)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .drop_column(ShortLinks::AnalyticsLevel)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShortLinks {
+    #[sea_orm(iden = "short_links")]
+    Table,
+    AnalyticsLevel,
+}
+
+#[derive(DeriveIden)]
+enum ShortLinkArchive {
+    #[sea_orm(iden = "short_link_archive")]
+    Table,
+    AnalyticsLevel,
+}
@@ -0,0 +1,70 @@
+//! 删除冷却期记录表迁移
+//!
+//! 新增 retired_codes 表：开启 `links.code_reuse_cooldown_days` 后，删除链接时记录
+//! 短码与删除时间，冷却期内禁止重新创建同一短码。过期记录由 DataRetentionTask 清理。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetiredCodes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RetiredCodes::ShortCode)
+                            .string_len(128)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RetiredCodes::RetiredAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 清理按删除时间扫描
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_retired_codes_retired_at")
+                    .table(RetiredCodes::Table)
+                    .col(RetiredCodes::RetiredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_retired_codes_retired_at")
+                    .table(RetiredCodes::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RetiredCodes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RetiredCodes {
+    Table,
+    ShortCode,
+    RetiredAt,
+}
@@ -0,0 +1,71 @@
+//! 自定义 query 参数采集字段迁移
+//!
+//! 按 `analytics.captured_query_params` 白名单采集的参数：
+//! - click_logs: 添加 query_params 列（JSON 对象 `{"参数名": "值"}`）
+//! - click_stats_hourly: 添加 param_counts 列（JSON 对象 `{"参数名": {"值": 次数}}`）
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ClickLogs::Table)
+                    .add_column(ColumnDef::new(ClickLogs::QueryParams).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ClickStatsHourly::Table)
+                    .add_column(ColumnDef::new(ClickStatsHourly::ParamCounts).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ClickStatsHourly::Table)
+                    .drop_column(ClickStatsHourly::ParamCounts)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ClickLogs::Table)
+                    .drop_column(ClickLogs::QueryParams)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ClickLogs {
+    #[sea_orm(iden = "click_logs")]
+    Table,
+    QueryParams,
+}
+
+#[derive(DeriveIden)]
+enum ClickStatsHourly {
+    #[sea_orm(iden = "click_stats_hourly")]
+    Table,
+    ParamCounts,
+}
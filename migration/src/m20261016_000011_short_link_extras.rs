@@ -0,0 +1,72 @@
+//! 短链接自定义扩展字段迁移
+//!
+//! short_links 与 short_link_archive 添加 extras 列：业务方自定义的元数据，
+//! 以 JSON 对象文本存储（如 `{"ticket":"JIRA-123"}`），NULL 表示无扩展字段。
+//! 各数据库按文本保存，不建索引，按 key 过滤时逐行解析 JSON。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 归档表同步添加，保证归档 / 恢复时扩展字段不丢失
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .add_column(ColumnDef::new(ShortLinks::Extras).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinkArchive::Table)
+                    .add_column(ColumnDef::new(ShortLinkArchive::Extras).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinkArchive::Table)
+                    .drop_column(ShortLinkArchive::Extras)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ShortLinks::Table)
+                    .drop_column(ShortLinks::Extras)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShortLinks {
+    #[sea_orm(iden = "short_links")]
+    Table,
+    Extras,
+}
+
+#[derive(DeriveIden)]
+enum ShortLinkArchive {
+    #[sea_orm(iden = "short_link_archive")]
+    Table,
+    Extras,
+}
@@ -0,0 +1,44 @@
+//! API Token 统计级别上限迁移
+//!
+//! api_tokens 添加 max_analytics_level 列（none / count_only / aggregate / full），
+//! 团队 token 创建或更新链接时统计级别不超过该上限；NULL 表示不限。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiTokens::Table)
+                    .add_column(
+                        ColumnDef::new(ApiTokens::MaxAnalyticsLevel)
+                            .string_len(16)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiTokens::Table)
+                    .drop_column(ApiTokens::MaxAnalyticsLevel)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiTokens {
+    Table,
+    MaxAnalyticsLevel,
+}
@@ -0,0 +1,169 @@
+//! 迁移回滚说明
+//!
+//! 每个迁移的 `down` 会删除哪些表 / 列，以及是否能安全回滚。
+//! `shortlinker migrate down` 据此在执行前列出数据影响，并拒绝自动回滚不可逆的迁移。
+//!
+//! 新增迁移时须在 [`rollback_impact`] 中登记；未登记的迁移一律按不可逆处理。
+
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, QueryOrder};
+use sea_orm_migration::{MigratorTrait, seaql_migrations};
+
+use crate::Migrator;
+
+/// `down` 删除的对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dropped {
+    Table(&'static str),
+    /// `(表, 列)`
+    Column(&'static str, &'static str),
+}
+
+/// 单个迁移的回滚影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollbackImpact {
+    /// `false` 表示 `down` 无法还原 `up` 前的数据或结构，不允许自动回滚
+    pub reversible: bool,
+    /// `down` 删除的表 / 列（其中的数据会丢失）
+    pub drops: &'static [Dropped],
+    /// 补充说明（仅索引、元数据丢失、不可逆原因等）
+    pub note: &'static str,
+}
+
+impl RollbackImpact {
+    /// 未登记迁移的回滚影响
+    pub const UNREGISTERED: Self =
+        Self::irreversible("no rollback notes registered for this migration");
+
+    const fn reversible(drops: &'static [Dropped]) -> Self {
+        Self {
+            reversible: true,
+            drops,
+            note: "",
+        }
+    }
+
+    const fn indexes_only() -> Self {
+        Self {
+            reversible: true,
+            drops: &[],
+            note: "indexes only",
+        }
+    }
+
+    const fn irreversible(note: &'static str) -> Self {
+        Self {
+            reversible: false,
+            drops: &[],
+            note,
+        }
+    }
+}
+
+/// 按迁移名查询回滚影响（未登记返回 `None`）
+pub fn rollback_impact(name: &str) -> Option<RollbackImpact> {
+    use Dropped::{Column, Table};
+
+    let impact = match name {
+        "m020251023_000001_initial_table" => {
+            RollbackImpact::irreversible("drops short_links; restore a backup instead")
+        }
+        "m020260111_000001_system_config" => RollbackImpact::irreversible(
+            "drops system_config and config_history; restore a backup instead",
+        ),
+        "m020260112_000001_search_index" => RollbackImpact::indexes_only(),
+        "m20260202_000001_click_logs" => RollbackImpact::reversible(&[Table("click_logs")]),
+        "m20260206_000001_click_rollups" => RollbackImpact::reversible(&[
+            Table("click_stats_global_hourly"),
+            Table("click_stats_daily"),
+            Table("click_stats_hourly"),
+        ]),
+        "m20260206_000002_analytics_indexes" => RollbackImpact::indexes_only(),
+        "m20260207_000001_user_agents_table" => RollbackImpact::reversible(&[
+            Column("click_logs", "user_agent_hash"),
+            Table("user_agents"),
+        ]),
+        "m20260207_000002_user_agents_parsed" => RollbackImpact::reversible(&[
+            Column("user_agents", "browser_name"),
+            Column("user_agents", "browser_version"),
+            Column("user_agents", "os_name"),
+            Column("user_agents", "os_version"),
+            Column("user_agents", "device_category"),
+            Column("user_agents", "device_vendor"),
+            Column("user_agents", "is_bot"),
+        ]),
+        "m20260208_000001_utm_source" => RollbackImpact::reversible(&[
+            Column("click_stats_daily", "unique_sources"),
+            Column("click_stats_daily", "top_sources"),
+            Column("click_stats_hourly", "source_counts"),
+            Column("click_logs", "source"),
+        ]),
+        "m20260208_000002_drop_user_agent" => RollbackImpact::irreversible(
+            "up dropped click_logs.user_agent; down can only re-add it empty",
+        ),
+        "m20260209_000001_source_geo_indexes" => RollbackImpact::indexes_only(),
+        "m20260209_000002_analytics_indexes_v2" => RollbackImpact::indexes_only(),
+        "m20260209_000003_global_daily_rollup" => {
+            RollbackImpact::reversible(&[Table("click_stats_global_daily")])
+        }
+        "m20260721_000001_forge_system_config" => RollbackImpact {
+            reversible: true,
+            drops: &[],
+            note: "converts system_config back to the legacy layout; Forge-only metadata is discarded",
+        },
+        "m20261016_000001_short_link_created_via" => {
+            RollbackImpact::reversible(&[Column("short_links", "created_via")])
+        }
+        "m20261016_000002_short_link_archive" => {
+            RollbackImpact::reversible(&[Table("short_link_archive")])
+        }
+        "m20261016_000003_pending_side_effects" => {
+            RollbackImpact::reversible(&[Table("pending_side_effects")])
+        }
+        "m20261016_000004_api_tokens" => RollbackImpact::reversible(&[
+            Column("short_links", "owner_token"),
+            Table("api_token_daily_usage"),
+            Table("api_tokens"),
+        ]),
+        "m20261016_000005_redirect_timings" => {
+            RollbackImpact::reversible(&[Table("redirect_timings")])
+        }
+        "m20261016_000006_analytics_amendments" => {
+            RollbackImpact::reversible(&[Table("analytics_amendments")])
+        }
+        "m20261016_000007_period_rollups" => {
+            RollbackImpact::reversible(&[Table("click_stats_monthly"), Table("click_stats_weekly")])
+        }
+        "m20261016_000008_short_link_analytics_level" => RollbackImpact::reversible(&[
+            Column("short_link_archive", "analytics_level"),
+            Column("short_links", "analytics_level"),
+        ]),
+        "m20261016_000009_retired_codes" => RollbackImpact::reversible(&[Table("retired_codes")]),
+        "m20261016_000010_captured_query_params" => RollbackImpact::reversible(&[
+            Column("click_stats_hourly", "param_counts"),
+            Column("click_logs", "query_params"),
+        ]),
+        "m20261016_000011_short_link_extras" => RollbackImpact::reversible(&[
+            Column("short_link_archive", "extras"),
+            Column("short_links", "extras"),
+        ]),
+        "m20261016_000012_api_token_analytics_cap" => {
+            RollbackImpact::reversible(&[Column("api_tokens", "max_analytics_level")])
+        }
+        _ => return None,
+    };
+    Some(impact)
+}
+
+/// 读取 `seaql_migrations` 中的全部记录（按版本排序）
+///
+/// 与 `Migrator::get_applied_migrations` 不同，这里不要求记录都能对应到当前二进制的迁移，
+/// 因此能发现被更新版本迁移过的数据库。
+pub async fn applied_migrations(
+    db: &DatabaseConnection,
+) -> Result<Vec<seaql_migrations::Model>, DbErr> {
+    Migrator::install(db).await?;
+    seaql_migrations::Entity::find()
+        .order_by_asc(seaql_migrations::Column::Version)
+        .all(db)
+        .await
+}
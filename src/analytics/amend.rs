@@ -0,0 +1,610 @@
+//! 点击统计订正（`shortlinker analytics amend`）
+//!
+//! 按短码 + 时间范围 + IP 网段删除被污染的点击明细，并同步修正派生数据：
+//!
+//! 1. 扫描 `click_logs` 中该短码在范围内的明细，按 CIDR 在内存中匹配（IP 以字符串
+//!    存储，无法在 SQL 中按网段过滤）
+//! 2. 单个事务内：删除匹配的明细；用 [`aggregate_click_details`]（与点击写入时的
+//!    小时汇总口径一致）聚合被删除的行，从对应的 `click_stats_hourly` /
+//!    `click_stats_global_hourly` 行中扣减；扣减链接总点击数；小时汇总已过保留期的
+//!    日期直接扣减 `click_stats_daily` / `click_stats_global_daily`；写入
+//!    `analytics_amendments` 审计记录
+//! 3. 事务提交后，对范围内仍保留完整小时汇总的已结束日期调用
+//!    [`RollupManager::rollup_hourly_to_daily`] 重建天汇总，再用
+//!    [`RollupManager::refresh_periods`] 重算所在的周 / 月汇总
+//!
+//! 小时汇总采用扣减而非按剩余明细重建：采样（`analytics.sample_rate`）、DNT 或关闭
+//! 详细日志时，小时汇总中包含没有明细行的点击，重建会把它们一并抹掉。第 3 步是
+//! 幂等的，失败后以相同参数重跑即可补齐。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use tracing::{info, warn};
+
+use super::query_params::{ParamCounts, param_counts_to_json, parse_param_counts, parse_params};
+use super::{
+    ClickAggregation, ClickDetail, RollupManager, WeekStart, aggregate_click_details,
+    parse_json_counts, to_json_string, truncate_to_hour,
+};
+use crate::config::{keys, try_get_runtime_config};
+use crate::storage::SeaOrmStorage;
+use crate::utils::cidr::Cidr;
+use migration::entities::{
+    analytics_amendment, click_log, click_stats_daily, click_stats_global_daily,
+    click_stats_global_hourly, click_stats_hourly, short_link, short_link_archive,
+};
+
+/// 扫描明细的分页大小
+const SCAN_PAGE_SIZE: u64 = 5_000;
+
+/// 按 id 删除明细的分批大小（避免超出 SQL 变量限制）
+const DELETE_CHUNK_SIZE: usize = 500;
+
+/// 订正请求
+#[derive(Debug, Clone)]
+pub struct AmendRequest {
+    pub code: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// 要删除的客户端 IP 网段
+    pub ip_cidrs: Vec<String>,
+    /// 审计记录中的操作者
+    pub operator: Option<String>,
+}
+
+/// 订正前后的数值
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BeforeAfter {
+    pub before: i64,
+    pub after: i64,
+}
+
+/// 订正结果
+#[derive(Debug, Clone, Default)]
+pub struct AmendReport {
+    pub dry_run: bool,
+    /// 匹配（dry-run）或已删除的明细行数
+    pub removed_rows: u64,
+    /// 涉及的小时桶数
+    pub affected_hours: usize,
+    /// 链接总点击数
+    pub link_clicks: BeforeAfter,
+    /// 范围内该短码的明细行数
+    pub detail_rows: BeforeAfter,
+    /// 范围内该短码的小时汇总点击数
+    pub hourly_clicks: BeforeAfter,
+    /// 范围内该短码的天汇总点击数（dry-run 时 `after` 为预估值）
+    pub daily_clicks: BeforeAfter,
+    /// 从小时汇总重建的日期
+    pub rebuilt_days: Vec<NaiveDate>,
+    /// 小时汇总已过保留期、直接扣减的日期
+    pub adjusted_days: Vec<NaiveDate>,
+    /// 天汇总或周 / 月汇总重建失败的日期（以相同参数重跑可补齐）
+    pub failed_days: Vec<NaiveDate>,
+}
+
+/// 点击统计订正器
+pub struct ClickAmender {
+    storage: Arc<SeaOrmStorage>,
+    rollup: RollupManager,
+}
+
+impl ClickAmender {
+    pub fn new(storage: Arc<SeaOrmStorage>) -> Self {
+        Self {
+            rollup: RollupManager::new(storage.clone()),
+            storage,
+        }
+    }
+
+    /// 执行订正；`dry_run` 时只统计影响范围，不做任何写入
+    pub async fn run(&self, request: &AmendRequest, dry_run: bool) -> anyhow::Result<AmendReport> {
+        if request.start >= request.end {
+            anyhow::bail!("--from must be earlier than --to");
+        }
+        let cidrs = parse_cidrs(&request.ip_cidrs)?;
+        let db = self.storage.get_db();
+
+        let removed = self.find_matching_logs(request, &cidrs).await?;
+        let details: Vec<ClickDetail> = removed.iter().map(to_click_detail).collect();
+        let hourly_removed = aggregate_click_details(&details);
+        let daily_removed = aggregate_by_day(&hourly_removed);
+
+        let detail_rows = click_log::Entity::find()
+            .filter(click_log::Column::ShortCode.eq(request.code.as_str()))
+            .filter(click_log::Column::ClickedAt.gte(request.start))
+            .filter(click_log::Column::ClickedAt.lte(request.end))
+            .count(db)
+            .await? as i64;
+        let hourly_before = self.sum_hourly(request).await?;
+        let daily_before = self.sum_daily(request).await?;
+        let link_before = link_click_count(db, &request.code).await?;
+        let removed_rows = removed.len() as i64;
+
+        let (rebuild_days, adjust_days) = plan_days(request, &daily_removed);
+
+        let mut report = AmendReport {
+            dry_run,
+            removed_rows: removed.len() as u64,
+            affected_hours: hourly_removed.len(),
+            link_clicks: BeforeAfter {
+                before: link_before.unwrap_or(0),
+                after: (link_before.unwrap_or(0) - removed_rows).max(0),
+            },
+            detail_rows: BeforeAfter {
+                before: detail_rows,
+                after: detail_rows - removed_rows,
+            },
+            rebuilt_days: rebuild_days.clone(),
+            adjusted_days: adjust_days.clone(),
+            ..Default::default()
+        };
+
+        if dry_run {
+            report.hourly_clicks = BeforeAfter {
+                before: hourly_before,
+                after: (hourly_before - removed_rows).max(0),
+            };
+            let daily_delta: usize = daily_removed
+                .iter()
+                .filter(|(day, _)| rebuild_days.contains(day) || adjust_days.contains(day))
+                .map(|(_, agg)| agg.count)
+                .sum();
+            report.daily_clicks = BeforeAfter {
+                before: daily_before,
+                after: (daily_before - daily_delta as i64).max(0),
+            };
+            return Ok(report);
+        }
+
+        // ---- 阶段一：单个事务内删除明细、扣减汇总与计数、写审计 ----
+        let txn = db.begin().await?;
+        let ids: Vec<i64> = removed.iter().map(|row| row.id).collect();
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            click_log::Entity::delete_many()
+                .filter(click_log::Column::Id.is_in(chunk.to_vec()))
+                .exec(&txn)
+                .await?;
+        }
+        subtract_hourly(&txn, &request.code, &hourly_removed).await?;
+        for day in &adjust_days {
+            if let Some(agg) = daily_removed.get(day) {
+                subtract_daily(&txn, &request.code, *day, agg).await?;
+            }
+        }
+        let link_after = subtract_link_clicks(&txn, &request.code, removed_rows).await?;
+        report.link_clicks.after = link_after.unwrap_or(0);
+
+        analytics_amendment::ActiveModel {
+            short_code: Set(request.code.clone()),
+            range_start: Set(request.start),
+            range_end: Set(request.end),
+            ip_cidrs: Set(serde_json::to_string(&request.ip_cidrs)?),
+            removed_rows: Set(removed_rows),
+            clicks_before: Set(report.link_clicks.before),
+            clicks_after: Set(report.link_clicks.after),
+            operator: Set(request.operator.clone()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        info!(
+            "Analytics amend for '{}': removed {} click logs ({} - {}), link clicks {} -> {}",
+            request.code,
+            removed_rows,
+            request.start,
+            request.end,
+            report.link_clicks.before,
+            report.link_clicks.after
+        );
+
+        // ---- 阶段二：从小时汇总重建天汇总（幂等） ----
+        for day in &rebuild_days {
+            if let Err(e) = self.rollup.rollup_hourly_to_daily(*day).await {
+                warn!(
+                    "Analytics amend: daily rollup rebuild for {} failed: {}",
+                    day, e
+                );
+                report.failed_days.push(*day);
+            }
+        }
+        // 周 / 月汇总由天汇总物化，随之重算
+        let week_start = WeekStart::current();
+        for day in rebuild_days.iter().chain(&adjust_days) {
+            if report.failed_days.contains(day) {
+                continue;
+            }
+            if let Err(e) = self.rollup.refresh_periods(*day, week_start).await {
+                warn!(
+                    "Analytics amend: weekly/monthly rollup rebuild for {} failed: {}",
+                    day, e
+                );
+                report.failed_days.push(*day);
+            }
+        }
+
+        report.hourly_clicks = BeforeAfter {
+            before: hourly_before,
+            after: self.sum_hourly(request).await?,
+        };
+        report.daily_clicks = BeforeAfter {
+            before: daily_before,
+            after: self.sum_daily(request).await?,
+        };
+        Ok(report)
+    }
+
+    /// 分页扫描该短码在范围内的明细，返回 IP 落在任一网段内的行
+    async fn find_matching_logs(
+        &self,
+        request: &AmendRequest,
+        cidrs: &[Cidr],
+    ) -> anyhow::Result<Vec<click_log::Model>> {
+        let db = self.storage.get_db();
+        let mut matched = Vec::new();
+        let mut last_id = 0i64;
+        loop {
+            let page = click_log::Entity::find()
+                .filter(click_log::Column::ShortCode.eq(request.code.as_str()))
+                .filter(click_log::Column::ClickedAt.gte(request.start))
+                .filter(click_log::Column::ClickedAt.lte(request.end))
+                .filter(click_log::Column::Id.gt(last_id))
+                .order_by_asc(click_log::Column::Id)
+                .limit(SCAN_PAGE_SIZE)
+                .all(db)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            last_id = last.id;
+            let exhausted = (page.len() as u64) < SCAN_PAGE_SIZE;
+            matched.extend(
+                page.into_iter()
+                    .filter(|row| ip_matches(row.ip_address.as_deref(), cidrs)),
+            );
+            if exhausted {
+                break;
+            }
+        }
+        Ok(matched)
+    }
+
+    /// 范围内该短码的小时汇总点击数
+    async fn sum_hourly(&self, request: &AmendRequest) -> anyhow::Result<i64> {
+        let rows = click_stats_hourly::Entity::find()
+            .filter(click_stats_hourly::Column::ShortCode.eq(request.code.as_str()))
+            .filter(click_stats_hourly::Column::HourBucket.gte(truncate_to_hour(request.start)))
+            .filter(click_stats_hourly::Column::HourBucket.lte(request.end))
+            .all(self.storage.get_db())
+            .await?;
+        Ok(rows.iter().map(|row| row.click_count).sum())
+    }
+
+    /// 范围内该短码的天汇总点击数
+    async fn sum_daily(&self, request: &AmendRequest) -> anyhow::Result<i64> {
+        let rows = click_stats_daily::Entity::find()
+            .filter(click_stats_daily::Column::ShortCode.eq(request.code.as_str()))
+            .filter(click_stats_daily::Column::DayBucket.gte(request.start.date_naive()))
+            .filter(click_stats_daily::Column::DayBucket.lte(request.end.date_naive()))
+            .all(self.storage.get_db())
+            .await?;
+        Ok(rows.iter().map(|row| row.click_count).sum())
+    }
+}
+
+fn parse_cidrs(raw: &[String]) -> anyhow::Result<Vec<Cidr>> {
+    if raw.is_empty() {
+        anyhow::bail!("At least one --remove-ip-cidr is required");
+    }
+    raw.iter()
+        .map(|cidr| Cidr::parse(cidr).ok_or_else(|| anyhow::anyhow!("Invalid CIDR '{}'", cidr)))
+        .collect()
+}
+
+fn ip_matches(ip: Option<&str>, cidrs: &[Cidr]) -> bool {
+    ip.and_then(|ip| ip.trim().parse().ok())
+        .is_some_and(|ip| cidrs.iter().any(|cidr| cidr.contains(ip)))
+}
+
+fn to_click_detail(row: &click_log::Model) -> ClickDetail {
+    ClickDetail {
+        code: row.short_code.clone(),
+        timestamp: row.clicked_at,
+        referrer: row.referrer.clone(),
+        user_agent_hash: row.user_agent_hash.clone(),
+        ip_address: row.ip_address.clone(),
+        country: row.country.clone(),
+        city: row.city.clone(),
+        source: row.source.clone(),
+        query_params: parse_params(&row.query_params),
+    }
+}
+
+fn aggregate_by_day(
+    hourly: &HashMap<(String, DateTime<Utc>), ClickAggregation>,
+) -> BTreeMap<NaiveDate, ClickAggregation> {
+    let mut daily: BTreeMap<NaiveDate, ClickAggregation> = BTreeMap::new();
+    for ((_, hour), agg) in hourly {
+        daily.entry(hour.date_naive()).or_default().merge(agg);
+    }
+    daily
+}
+
+/// 划分需要修正的日期：`(从小时汇总重建, 直接扣减天汇总)`
+///
+/// 今天的天汇总尚未生成，只修正小时汇总；已结束且小时汇总仍完整保留的日期
+/// （范围内全部，便于失败后重跑补齐）重建；更早的日期只扣减有删除行的那几天。
+fn plan_days(
+    request: &AmendRequest,
+    daily_removed: &BTreeMap<NaiveDate, ClickAggregation>,
+) -> (Vec<NaiveDate>, Vec<NaiveDate>) {
+    let now = Utc::now();
+    let today = now.date_naive();
+    let hourly_retention_days = try_get_runtime_config()
+        .map(|rt| rt.get_u64_or(keys::ANALYTICS_HOURLY_RETENTION_DAYS, 7))
+        .unwrap_or(7);
+    let hourly_cutoff = now - Duration::days(hourly_retention_days as i64);
+    let hourly_complete = |day: NaiveDate| {
+        day.and_hms_opt(0, 0, 0)
+            .is_some_and(|start| start.and_utc() >= hourly_cutoff)
+    };
+
+    let mut rebuild = BTreeSet::new();
+    let mut day = request.start.date_naive().max(hourly_cutoff.date_naive());
+    while day <= request.end.date_naive() && day < today {
+        if hourly_complete(day) {
+            rebuild.insert(day);
+        }
+        day += Duration::days(1);
+    }
+    let adjust = daily_removed
+        .keys()
+        .filter(|day| **day < today && !hourly_complete(**day))
+        .copied()
+        .collect();
+    (rebuild.into_iter().collect(), adjust)
+}
+
+/// 链接总点击数（含已归档链接），短码不存在时返回 `None`
+async fn link_click_count(
+    db: &sea_orm::DatabaseConnection,
+    code: &str,
+) -> anyhow::Result<Option<i64>> {
+    if let Some(link) = short_link::Entity::find_by_id(code).one(db).await? {
+        return Ok(Some(link.click_count));
+    }
+    Ok(short_link_archive::Entity::find_by_id(code)
+        .one(db)
+        .await?
+        .map(|link| link.click_count))
+}
+
+async fn subtract_link_clicks(
+    txn: &DatabaseTransaction,
+    code: &str,
+    removed: i64,
+) -> anyhow::Result<Option<i64>> {
+    if let Some(link) = short_link::Entity::find_by_id(code).one(txn).await? {
+        let after = (link.click_count - removed).max(0);
+        let mut active = link.into_active_model();
+        active.click_count = Set(after);
+        active.update(txn).await?;
+        return Ok(Some(after));
+    }
+    if let Some(link) = short_link_archive::Entity::find_by_id(code)
+        .one(txn)
+        .await?
+    {
+        let after = (link.click_count - removed).max(0);
+        let mut active = link.into_active_model();
+        active.click_count = Set(after);
+        active.update(txn).await?;
+        return Ok(Some(after));
+    }
+    Ok(None)
+}
+
+/// 从小时汇总（及全局小时汇总）中扣减被删除的点击
+///
+/// 行不存在（已过保留期）时跳过；扣减到 0 的行保留，重建天汇总时据此写入 0。
+async fn subtract_hourly(
+    txn: &DatabaseTransaction,
+    code: &str,
+    removed: &HashMap<(String, DateTime<Utc>), ClickAggregation>,
+) -> anyhow::Result<()> {
+    let buckets: Vec<DateTime<Utc>> = removed.keys().map(|(_, hour)| *hour).collect();
+    for chunk in buckets.chunks(DELETE_CHUNK_SIZE) {
+        let rows = click_stats_hourly::Entity::find()
+            .filter(click_stats_hourly::Column::ShortCode.eq(code))
+            .filter(click_stats_hourly::Column::HourBucket.is_in(chunk.to_vec()))
+            .all(txn)
+            .await?;
+        for row in rows {
+            let Some(agg) = removed.get(&(code.to_string(), row.hour_bucket)) else {
+                continue;
+            };
+            let mut active = row.clone().into_active_model();
+            active.click_count = Set(subtract_count(row.click_count, agg.count));
+            active.referrer_counts =
+                Set(subtract_json_counts(&row.referrer_counts, &agg.referrers));
+            active.country_counts = Set(subtract_json_counts(&row.country_counts, &agg.countries));
+            active.source_counts = Set(subtract_json_counts(&row.source_counts, &agg.sources));
+            active.param_counts = Set(subtract_param_counts(&row.param_counts, &agg.params));
+            active.update(txn).await?;
+        }
+
+        let global_rows = click_stats_global_hourly::Entity::find()
+            .filter(click_stats_global_hourly::Column::HourBucket.is_in(chunk.to_vec()))
+            .all(txn)
+            .await?;
+        for row in global_rows {
+            let Some(agg) = removed.get(&(code.to_string(), row.hour_bucket)) else {
+                continue;
+            };
+            let total = subtract_count(row.total_clicks, agg.count);
+            let mut active = row.into_active_model();
+            active.total_clicks = Set(total);
+            active.update(txn).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 小时汇总已过保留期的日期：直接扣减天汇总
+///
+/// top-N 列表中能对上的条目同步扣减；`unique_*` 无法从 top-N 推算，保持不变。
+async fn subtract_daily(
+    txn: &DatabaseTransaction,
+    code: &str,
+    day: NaiveDate,
+    removed: &ClickAggregation,
+) -> anyhow::Result<()> {
+    if let Some(row) = click_stats_daily::Entity::find()
+        .filter(click_stats_daily::Column::ShortCode.eq(code))
+        .filter(click_stats_daily::Column::DayBucket.eq(day))
+        .one(txn)
+        .await?
+    {
+        let mut active = row.clone().into_active_model();
+        active.click_count = Set(subtract_count(row.click_count, removed.count));
+        active.top_referrers = Set(subtract_top_n(&row.top_referrers, &removed.referrers));
+        active.top_countries = Set(subtract_top_n(&row.top_countries, &removed.countries));
+        active.top_sources = Set(subtract_top_n(&row.top_sources, &removed.sources));
+        active.update(txn).await?;
+    }
+
+    if let Some(row) = click_stats_global_daily::Entity::find()
+        .filter(click_stats_global_daily::Column::DayBucket.eq(day))
+        .one(txn)
+        .await?
+    {
+        let mut active = row.clone().into_active_model();
+        active.total_clicks = Set(subtract_count(row.total_clicks, removed.count));
+        active.top_referrers = Set(subtract_top_n(&row.top_referrers, &removed.referrers));
+        active.top_countries = Set(subtract_top_n(&row.top_countries, &removed.countries));
+        active.top_sources = Set(subtract_top_n(&row.top_sources, &removed.sources));
+        active.update(txn).await?;
+    }
+    Ok(())
+}
+
+fn subtract_count(current: i64, removed: usize) -> i64 {
+    current
+        .saturating_sub(i64::try_from(removed).unwrap_or(i64::MAX))
+        .max(0)
+}
+
+/// 从 `{"key": count}` JSON 中扣减；原值为 NULL（仅计数的汇总行）时保持 NULL
+fn subtract_json_counts(
+    current: &Option<String>,
+    removed: &HashMap<String, usize>,
+) -> Option<String> {
+    current.as_ref()?;
+    let mut counts = parse_json_counts(current);
+    for (key, count) in removed {
+        if let Some(value) = counts.get_mut(key) {
+            *value = value.saturating_sub(*count);
+        }
+    }
+    counts.retain(|_, count| *count > 0);
+    Some(to_json_string(&counts))
+}
+
+/// 从 `{"param": {"value": count}}` JSON 中扣减；已并入 `(other)` 的值无法定位，保持不变
+fn subtract_param_counts(current: &Option<String>, removed: &ParamCounts) -> Option<String> {
+    current.as_ref()?;
+    let mut counts = parse_param_counts(current);
+    for (name, values) in removed {
+        let Some(existing) = counts.get_mut(name) else {
+            continue;
+        };
+        for (value, count) in values {
+            if let Some(existing) = existing.get_mut(value) {
+                *existing = existing.saturating_sub(*count);
+            }
+        }
+        existing.retain(|_, count| *count > 0);
+    }
+    counts.retain(|_, values| !values.is_empty());
+    param_counts_to_json(&counts)
+}
+
+/// 从 `[["key", count], ...]` top-N JSON 中扣减并重新排序
+fn subtract_top_n(current: &Option<String>, removed: &HashMap<String, usize>) -> Option<String> {
+    let raw = current.as_ref()?;
+    let Ok(mut items) = serde_json::from_str::<Vec<(String, usize)>>(raw) else {
+        return current.clone();
+    };
+    for (key, count) in &mut items {
+        if let Some(removed) = removed.get(key) {
+            *count = count.saturating_sub(*removed);
+        }
+    }
+    items.retain(|(_, count)| *count > 0);
+    items.sort_by_key(|item| std::cmp::Reverse(item.1));
+    serde_json::to_string(&items).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_matches() {
+        let cidrs = parse_cidrs(&["1.2.3.0/24".to_string(), "2001:db8::/32".to_string()]).unwrap();
+        assert!(ip_matches(Some("1.2.3.4"), &cidrs));
+        assert!(ip_matches(Some("2001:db8::1"), &cidrs));
+        assert!(!ip_matches(Some("1.2.4.1"), &cidrs));
+        assert!(!ip_matches(Some("not-an-ip"), &cidrs));
+        assert!(!ip_matches(None, &cidrs));
+        assert!(parse_cidrs(&["1.2.3.0/40".to_string()]).is_err());
+        assert!(parse_cidrs(&[]).is_err());
+    }
+
+    #[test]
+    fn test_subtract_json_counts() {
+        let current = Some(r#"{"direct":5,"google.com":2}"#.to_string());
+        let removed = HashMap::from([("google.com".to_string(), 2), ("bing.com".to_string(), 1)]);
+        let after = parse_json_counts(&subtract_json_counts(&current, &removed));
+        assert_eq!(after, HashMap::from([("direct".to_string(), 5)]));
+        assert_eq!(subtract_json_counts(&None, &removed), None);
+    }
+
+    #[test]
+    fn test_subtract_param_counts() {
+        let current = Some(r#"{"ch":{"wechat":3,"mail":1},"utm_source":{"x":1}}"#.to_string());
+        let removed = ParamCounts::from([
+            (
+                "ch".to_string(),
+                HashMap::from([("mail".to_string(), 1), ("sms".to_string(), 1)]),
+            ),
+            (
+                "utm_source".to_string(),
+                HashMap::from([("x".to_string(), 1)]),
+            ),
+        ]);
+        let after = parse_param_counts(&subtract_param_counts(&current, &removed));
+        assert_eq!(after.len(), 1);
+        assert_eq!(after["ch"], HashMap::from([("wechat".to_string(), 3)]));
+        assert_eq!(subtract_param_counts(&None, &removed), None);
+    }
+
+    #[test]
+    fn test_subtract_top_n_resorts() {
+        let current = Some(r#"[["CN",10],["US",8],["JP",1]]"#.to_string());
+        let removed = HashMap::from([("CN".to_string(), 5), ("JP".to_string(), 1)]);
+        assert_eq!(
+            subtract_top_n(&current, &removed).as_deref(),
+            Some(r#"[["US",8],["CN",5]]"#)
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! 点击异常检测
+//!
+//! 每小时对监控集合（近 7 天点击量 top N + `alerts.watch_codes`）中的链接，
+//! 比较刚结束的整点小时与近 7 天同一小时的基线（均值 ± k 倍标准差），
+//! 识别突增（刷量）与骤降（目标站失效导致点击归零等）。
+//!
+//! 判定逻辑 [`detect`] 与冷却 [`AlertCooldown`] 为纯逻辑；数据读取自
+//! `click_stats_hourly`，告警写日志、指标，并可选投递到 `alerts.webhook_url`。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::config::{get_runtime_config, keys};
+use crate::metrics::MetricsRecorder;
+use crate::storage::backend::SeaOrmStorage;
+
+use super::truncate_to_hour;
+
+/// 基线回看天数（同一小时）
+pub const BASELINE_DAYS: i64 = 7;
+/// 基线样本少于该值时不做判定（新链接、汇总数据被清理）
+pub const MIN_BASELINE_SAMPLES: usize = 3;
+/// 每小时检测相对整点的延迟，留出点击缓冲刷写到小时汇总的时间
+pub const CHECK_OFFSET_SECS: i64 = 5 * 60;
+/// Webhook 请求超时
+const WEBHOOK_TIMEOUT_SECS: u64 = 5;
+
+/// 异常类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// 点击突增（疑似刷量）
+    Spike,
+    /// 点击骤降（含归零，疑似目标失效）
+    Drop,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::Spike => "spike",
+            AnomalyKind::Drop => "drop",
+        }
+    }
+}
+
+/// 检测参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectionParams {
+    /// 偏离基线均值超过 `sigma_k` 倍标准差时告警
+    pub sigma_k: f64,
+    /// 突增要求当前小时点击数、骤降要求基线均值不低于该值，过滤低流量噪声
+    pub min_clicks: u64,
+}
+
+impl Default for DetectionParams {
+    fn default() -> Self {
+        Self {
+            sigma_k: 3.0,
+            min_clicks: 10,
+        }
+    }
+}
+
+impl DetectionParams {
+    /// 从运行时配置读取
+    pub fn from_runtime_config() -> Self {
+        let rc = get_runtime_config();
+        let defaults = Self::default();
+        Self {
+            sigma_k: rc.get_f64_or(keys::ALERTS_SIGMA_K, defaults.sigma_k),
+            min_clicks: rc.get_u64_or(keys::ALERTS_MIN_CLICKS, defaults.min_clicks),
+        }
+    }
+}
+
+/// 判定结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub current: u64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+/// 将当前小时点击数与基线样本比较
+///
+/// 标准差按总体标准差计算，并以 1 为下限：基线完全平稳（例如每天同一小时
+/// 都是 0 次或恒定次数）时，避免一两次点击的波动就触发告警。
+pub fn detect(current: u64, baseline: &[u64], params: &DetectionParams) -> Option<Anomaly> {
+    if baseline.len() < MIN_BASELINE_SAMPLES {
+        return None;
+    }
+
+    let n = baseline.len() as f64;
+    let mean = baseline.iter().map(|&c| c as f64).sum::<f64>() / n;
+    let variance = baseline
+        .iter()
+        .map(|&c| {
+            let diff = c as f64 - mean;
+            diff * diff
+        })
+        .sum::<f64>()
+        / n;
+    let stddev = variance.sqrt();
+    let band = params.sigma_k * stddev.max(1.0);
+    let value = current as f64;
+
+    let kind = if current >= params.min_clicks && value > mean + band {
+        AnomalyKind::Spike
+    } else if mean >= params.min_clicks as f64 && value < mean - band {
+        AnomalyKind::Drop
+    } else {
+        return None;
+    };
+
+    Some(Anomaly {
+        kind,
+        current,
+        mean,
+        stddev,
+    })
+}
+
+/// 按链接的告警冷却
+///
+/// 同一链接在冷却期内只告警一次（不区分突增 / 骤降），避免持续异常时每小时刷屏。
+#[derive(Debug, Default)]
+pub struct AlertCooldown {
+    last_fired: HashMap<String, DateTime<Utc>>,
+}
+
+impl AlertCooldown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 冷却期外返回 true 并记录本次告警时间
+    pub fn try_fire(&mut self, code: &str, now: DateTime<Utc>, cooldown: Duration) -> bool {
+        if let Some(last) = self.last_fired.get(code)
+            && now - *last < cooldown
+        {
+            return false;
+        }
+        self.last_fired.insert(code.to_string(), now);
+        true
+    }
+
+    /// 移除已过冷却期的记录
+    pub fn prune(&mut self, now: DateTime<Utc>, cooldown: Duration) {
+        self.last_fired.retain(|_, last| now - *last < cooldown);
+    }
+
+    pub fn len(&self) -> usize {
+        self.last_fired.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last_fired.is_empty()
+    }
+}
+
+/// 距下一次检测（下一个整点 + [`CHECK_OFFSET_SECS`]）的等待时间
+pub fn until_next_check(now: DateTime<Utc>) -> StdDuration {
+    let mut next = truncate_to_hour(now) + Duration::seconds(CHECK_OFFSET_SECS);
+    if next <= now {
+        next += Duration::hours(1);
+    }
+    (next - now).to_std().unwrap_or_default()
+}
+
+/// 告警事件（Webhook 负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyAlert {
+    pub event: &'static str,
+    pub code: String,
+    pub kind: AnomalyKind,
+    /// 被评估的整点小时（UTC）
+    pub hour: DateTime<Utc>,
+    pub clicks: u64,
+    pub baseline_mean: f64,
+    pub baseline_stddev: f64,
+    pub sigma_k: f64,
+}
+
+/// 点击异常检测任务
+pub struct AnomalyDetectionTask {
+    storage: Arc<SeaOrmStorage>,
+    metrics: Arc<dyn MetricsRecorder>,
+    cooldown: AlertCooldown,
+}
+
+impl AnomalyDetectionTask {
+    pub fn new(storage: Arc<SeaOrmStorage>, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        Self {
+            storage,
+            metrics,
+            cooldown: AlertCooldown::new(),
+        }
+    }
+
+    /// 是否启用（运行时配置，修改后下一轮生效）
+    pub fn enabled() -> bool {
+        get_runtime_config().get_bool_or(keys::ALERTS_ENABLED, false)
+    }
+
+    /// 评估 `now` 之前刚结束的整点小时，返回本轮发出的告警
+    pub async fn run_once(&mut self, now: DateTime<Utc>) -> anyhow::Result<Vec<AnomalyAlert>> {
+        let rc = get_runtime_config();
+        let params = DetectionParams::from_runtime_config();
+        let top_n = rc.get_usize_or(keys::ALERTS_TOP_N, 20);
+        let watch_codes: Vec<String> = rc.get_json_or(keys::ALERTS_WATCH_CODES, Vec::new());
+        let cooldown = Duration::minutes(rc.get_int_or(keys::ALERTS_COOLDOWN_MINUTES, 360));
+        let hourly_retention_days = rc.get_int_or(keys::ANALYTICS_HOURLY_RETENTION_DAYS, 7);
+
+        let hour = truncate_to_hour(now) - Duration::hours(1);
+        // 已超出小时汇总保留期的桶可能已被清理，不能当作 0 次点击
+        let retention_cutoff = now - Duration::days(hourly_retention_days);
+        let baseline_buckets: Vec<DateTime<Utc>> = (1..=BASELINE_DAYS)
+            .map(|days| hour - Duration::days(days))
+            .filter(|bucket| *bucket >= retention_cutoff)
+            .collect();
+        if baseline_buckets.len() < MIN_BASELINE_SAMPLES {
+            debug!(
+                "Anomaly detection skipped: only {} baseline hours within hourly retention",
+                baseline_buckets.len()
+            );
+            return Ok(Vec::new());
+        }
+
+        let top = self
+            .storage
+            .get_top_links_from_hourly(hour - Duration::days(BASELINE_DAYS), hour, top_n)
+            .await?;
+        let mut seen = HashSet::new();
+        let codes: Vec<String> = top
+            .into_iter()
+            .map(|row| row.short_code)
+            .chain(watch_codes)
+            .filter(|code| seen.insert(code.clone()))
+            .collect();
+        if codes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut buckets = baseline_buckets.clone();
+        buckets.push(hour);
+        let counts: HashMap<(String, DateTime<Utc>), u64> = self
+            .storage
+            .get_hourly_counts(&codes, &buckets)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    (row.short_code, row.hour_bucket),
+                    row.click_count.max(0) as u64,
+                )
+            })
+            .collect();
+        let count_at = |code: &str, bucket: DateTime<Utc>| {
+            counts
+                .get(&(code.to_string(), bucket))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        self.cooldown.prune(now, cooldown);
+        let mut alerts = Vec::new();
+        for code in &codes {
+            let baseline: Vec<u64> = baseline_buckets
+                .iter()
+                .map(|&bucket| count_at(code, bucket))
+                .collect();
+            let Some(anomaly) = detect(count_at(code, hour), &baseline, &params) else {
+                continue;
+            };
+            if !self.cooldown.try_fire(code, now, cooldown) {
+                debug!(
+                    "Click anomaly for '{}' suppressed by cooldown ({})",
+                    code,
+                    anomaly.kind.as_str()
+                );
+                continue;
+            }
+            alerts.push(AnomalyAlert {
+                event: "click_anomaly",
+                code: code.clone(),
+                kind: anomaly.kind,
+                hour,
+                clicks: anomaly.current,
+                baseline_mean: anomaly.mean,
+                baseline_stddev: anomaly.stddev,
+                sigma_k: params.sigma_k,
+            });
+        }
+
+        info!(
+            "Anomaly detection for {} evaluated {} links, {} alerts",
+            hour.format("%Y-%m-%d %H:00"),
+            codes.len(),
+            alerts.len()
+        );
+        for alert in &alerts {
+            self.emit(alert).await;
+        }
+        Ok(alerts)
+    }
+
+    async fn emit(&self, alert: &AnomalyAlert) {
+        warn!(
+            "Click anomaly ({}) on '{}' at {}: {} clicks vs baseline {:.1} ± {:.1}",
+            alert.kind.as_str(),
+            alert.code,
+            alert.hour.format("%Y-%m-%d %H:00"),
+            alert.clicks,
+            alert.baseline_mean,
+            alert.baseline_stddev
+        );
+        self.metrics.inc_click_anomaly_alert(alert.kind.as_str());
+
+        let url = get_runtime_config().get_or(keys::ALERTS_WEBHOOK_URL, "");
+        if url.is_empty() {
+            return;
+        }
+        let payload = match serde_json::to_value(alert) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize anomaly alert: {}", e);
+                return;
+            }
+        };
+        let result = tokio::task::spawn_blocking(move || post_webhook(&url, payload)).await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Anomaly alert webhook failed for '{}': {}", alert.code, e),
+            Err(e) => warn!("Anomaly alert webhook task failed: {}", e),
+        }
+    }
+}
+
+/// 同步投递 Webhook（在 spawn_blocking 中调用），配额提醒共用
+pub(crate) fn post_webhook(url: &str, payload: serde_json::Value) -> Result<(), ureq::Error> {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    let agent = AGENT.get_or_init(|| {
+        ureq::Agent::config_builder()
+            .timeout_global(Some(StdDuration::from_secs(WEBHOOK_TIMEOUT_SECS)))
+            .build()
+            .into()
+    });
+    agent.post(url).send_json(payload).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: DetectionParams = DetectionParams {
+        sigma_k: 3.0,
+        min_clicks: 10,
+    };
+
+    #[test]
+    fn test_normal_hour_no_alert() {
+        let baseline = [100, 110, 95, 105, 98, 102, 104];
+        assert_eq!(detect(108, &baseline, &PARAMS), None);
+        assert_eq!(detect(92, &baseline, &PARAMS), None);
+    }
+
+    #[test]
+    fn test_spike_detected() {
+        let baseline = [100, 110, 95, 105, 98, 102, 104];
+        let anomaly = detect(400, &baseline, &PARAMS).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::Spike);
+        assert_eq!(anomaly.current, 400);
+        assert!((anomaly.mean - 102.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drop_to_zero_detected() {
+        let baseline = [100, 110, 95, 105, 98, 102, 104];
+        let anomaly = detect(0, &baseline, &PARAMS).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::Drop);
+    }
+
+    #[test]
+    fn test_volatile_baseline_widens_band() {
+        // 波动大的链接：同样从 100 降到 20 不算异常
+        let baseline = [10, 200, 40, 180, 60, 150, 60];
+        assert_eq!(detect(20, &baseline, &PARAMS), None);
+    }
+
+    #[test]
+    fn test_low_traffic_filtered_by_min_clicks() {
+        // 平稳 0 次点击的链接突然来了 5 次：低于 min_clicks，不告警
+        assert_eq!(detect(5, &[0, 0, 0, 0, 0, 0, 0], &PARAMS), None);
+        // 基线均值只有 3 次的链接归零：低于 min_clicks，不告警
+        assert_eq!(detect(0, &[3, 2, 4, 3, 3, 2, 4], &PARAMS), None);
+        // 平稳 0 次的链接突然 50 次：告警
+        assert_eq!(
+            detect(50, &[0, 0, 0, 0, 0, 0, 0], &PARAMS).map(|a| a.kind),
+            Some(AnomalyKind::Spike)
+        );
+    }
+
+    #[test]
+    fn test_flat_baseline_uses_stddev_floor() {
+        // 基线恒为 20（标准差 0），下限 1 → 带宽 ±3
+        let baseline = [20; 7];
+        assert_eq!(detect(23, &baseline, &PARAMS), None);
+        assert_eq!(
+            detect(24, &baseline, &PARAMS).map(|a| a.kind),
+            Some(AnomalyKind::Spike)
+        );
+        assert_eq!(detect(17, &baseline, &PARAMS), None);
+        assert_eq!(
+            detect(16, &baseline, &PARAMS).map(|a| a.kind),
+            Some(AnomalyKind::Drop)
+        );
+    }
+
+    #[test]
+    fn test_sigma_k_controls_sensitivity() {
+        let baseline = [100, 110, 95, 105, 98, 102, 104];
+        let strict = DetectionParams {
+            sigma_k: 10.0,
+            ..PARAMS
+        };
+        assert!(detect(130, &baseline, &PARAMS).is_some());
+        assert!(detect(130, &baseline, &strict).is_none());
+    }
+
+    #[test]
+    fn test_insufficient_baseline_skipped() {
+        assert_eq!(detect(1000, &[100, 100], &PARAMS), None);
+        assert!(detect(1000, &[100, 100, 100], &PARAMS).is_some());
+    }
+
+    #[test]
+    fn test_until_next_check_aligns_to_hour_offset() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        assert_eq!(
+            until_next_check(at("2025-01-01T10:00:00Z")),
+            StdDuration::from_secs(5 * 60)
+        );
+        assert_eq!(
+            until_next_check(at("2025-01-01T10:05:00Z")),
+            StdDuration::from_secs(60 * 60)
+        );
+        assert_eq!(
+            until_next_check(at("2025-01-01T10:30:00Z")),
+            StdDuration::from_secs(35 * 60)
+        );
+    }
+
+    #[test]
+    fn test_cooldown_suppresses_repeat_alerts() {
+        let mut cooldown = AlertCooldown::new();
+        let window = Duration::hours(6);
+        let t0 = DateTime::parse_from_rfc3339("2025-01-01T00:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(cooldown.try_fire("abc", t0, window));
+        assert!(!cooldown.try_fire("abc", t0 + Duration::hours(1), window));
+        assert!(cooldown.try_fire("other", t0 + Duration::hours(1), window));
+        assert!(cooldown.try_fire("abc", t0 + Duration::hours(6), window));
+    }
+
+    #[test]
+    fn test_cooldown_prune() {
+        let mut cooldown = AlertCooldown::new();
+        let window = Duration::hours(1);
+        let t0 = Utc::now();
+        cooldown.try_fire("a", t0, window);
+        cooldown.try_fire("b", t0 + Duration::minutes(30), window);
+        cooldown.prune(t0 + Duration::minutes(70), window);
+        assert_eq!(cooldown.len(), 1);
+        assert!(!cooldown.try_fire("b", t0 + Duration::minutes(70), window));
+    }
+
+    #[test]
+    fn test_zero_cooldown_always_fires() {
+        let mut cooldown = AlertCooldown::new();
+        let t0 = Utc::now();
+        assert!(cooldown.try_fire("a", t0, Duration::zero()));
+        assert!(cooldown.try_fire("a", t0, Duration::zero()));
+    }
+}
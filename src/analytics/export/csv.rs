@@ -0,0 +1,141 @@
+//! CSV 导出：计数列保留为 JSON 字符串
+
+use std::io::Write;
+
+use chrono::SecondsFormat;
+
+use crate::errors::ShortlinkerError;
+
+use super::{BatchWriter, ExportBatch, ExportTable};
+
+const CLICK_LOG_HEADER: &[&str] = &[
+    "id",
+    "short_code",
+    "clicked_at",
+    "referrer",
+    "ip_address",
+    "country",
+    "city",
+    "source",
+    "user_agent_hash",
+    "query_params",
+];
+
+const DAILY_HEADER: &[&str] = &[
+    "id",
+    "short_code",
+    "day_bucket",
+    "click_count",
+    "unique_referrers",
+    "unique_countries",
+    "unique_sources",
+    "top_referrers",
+    "top_countries",
+    "top_sources",
+];
+
+const HOURLY_HEADER: &[&str] = &[
+    "id",
+    "short_code",
+    "hour_bucket",
+    "click_count",
+    "referrer_counts",
+    "country_counts",
+    "source_counts",
+    "param_counts",
+];
+
+pub(super) struct CsvBatchWriter<W: Write> {
+    writer: ::csv::Writer<W>,
+}
+
+impl<W: Write> CsvBatchWriter<W> {
+    pub(super) fn new(output: W, table: ExportTable) -> Result<Self, ShortlinkerError> {
+        let mut writer = ::csv::Writer::from_writer(output);
+        let header = match table {
+            ExportTable::ClickLog => CLICK_LOG_HEADER,
+            ExportTable::Daily => DAILY_HEADER,
+            ExportTable::Hourly => HOURLY_HEADER,
+        };
+        writer.write_record(header).map_err(csv_error)?;
+        Ok(Self { writer })
+    }
+}
+
+impl<W: Write + Send> BatchWriter for CsvBatchWriter<W> {
+    fn write_batch(&mut self, batch: &ExportBatch) -> Result<(), ShortlinkerError> {
+        match batch {
+            ExportBatch::ClickLog(rows) => {
+                for row in rows {
+                    self.writer
+                        .write_record([
+                            row.id.to_string().as_str(),
+                            &row.short_code,
+                            &row.clicked_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                            opt(&row.referrer),
+                            opt(&row.ip_address),
+                            opt(&row.country),
+                            opt(&row.city),
+                            opt(&row.source),
+                            opt(&row.user_agent_hash),
+                            opt(&row.query_params),
+                        ])
+                        .map_err(csv_error)?;
+                }
+            }
+            ExportBatch::Daily(rows) => {
+                for row in rows {
+                    self.writer
+                        .write_record([
+                            row.id.to_string().as_str(),
+                            &row.short_code,
+                            &row.day_bucket.format("%Y-%m-%d").to_string(),
+                            &row.click_count.to_string(),
+                            &opt_num(row.unique_referrers),
+                            &opt_num(row.unique_countries),
+                            &opt_num(row.unique_sources),
+                            opt(&row.top_referrers),
+                            opt(&row.top_countries),
+                            opt(&row.top_sources),
+                        ])
+                        .map_err(csv_error)?;
+                }
+            }
+            ExportBatch::Hourly(rows) => {
+                for row in rows {
+                    self.writer
+                        .write_record([
+                            row.id.to_string().as_str(),
+                            &row.short_code,
+                            &row.hour_bucket.to_rfc3339_opts(SecondsFormat::Secs, true),
+                            &row.click_count.to_string(),
+                            opt(&row.referrer_counts),
+                            opt(&row.country_counts),
+                            opt(&row.source_counts),
+                            opt(&row.param_counts),
+                        ])
+                        .map_err(csv_error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), ShortlinkerError> {
+        self.writer
+            .flush()
+            .map_err(|e| ShortlinkerError::export_failed(format!("Failed to flush CSV: {}", e)))
+    }
+}
+
+fn opt(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("")
+}
+
+fn opt_num(value: Option<i32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn csv_error(e: ::csv::Error) -> ShortlinkerError {
+    ShortlinkerError::export_failed(format!("Failed to write CSV: {}", e))
+}
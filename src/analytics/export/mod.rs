@@ -0,0 +1,246 @@
+//! 点击统计数据导出
+//!
+//! 支持 `click_logs` 明细与小时 / 天汇总表，输出 CSV 或 Parquet（需启用 `parquet`
+//! feature）。数据按 ID 游标分批读取，每批直接交给 writer，内存占用与导出范围无关；
+//! Parquet 每累计 [`PARQUET_ROW_GROUP_SIZE`] 行写出一个 row group。
+//!
+//! 汇总表的计数列在库中是 JSON（小时表为 `{"key": count}`，天表为
+//! `[["key", count], ...]`），导出时可原样保留为 JSON 字符串，或在 Parquet 中
+//! 展开为 `list<struct<key, count>>`。
+
+mod csv;
+#[cfg(feature = "parquet")]
+mod parquet;
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use tracing::warn;
+
+use migration::entities::{click_log, click_stats_daily, click_stats_hourly};
+
+use crate::errors::ShortlinkerError;
+use crate::storage::SeaOrmStorage;
+
+/// 每次从数据库读取的行数
+pub const EXPORT_BATCH_SIZE: u64 = 10_000;
+
+/// Parquet row group 的行数上限
+pub const PARQUET_ROW_GROUP_SIZE: usize = 100_000;
+
+/// 导出的表
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    /// `click_logs` 点击明细
+    ClickLog,
+    /// `click_stats_daily` 天汇总
+    Daily,
+    /// `click_stats_hourly` 小时汇总
+    Hourly,
+}
+
+impl ExportTable {
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            Self::ClickLog => "click_logs",
+            Self::Daily => "click_stats_daily",
+            Self::Hourly => "click_stats_hourly",
+        }
+    }
+}
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+/// 汇总表 JSON 计数列的输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountsEncoding {
+    /// 保留库中的 JSON 字符串
+    #[default]
+    Json,
+    /// 展开为 `list<struct<key: string, count: int64>>`（仅 Parquet）
+    Nested,
+}
+
+/// 导出参数
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub table: ExportTable,
+    /// 时间范围（闭区间）；天汇总表按日期比较
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub format: ExportFormat,
+    pub counts: CountsEncoding,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub rows: u64,
+    pub batches: u64,
+}
+
+/// 一批待写出的行
+pub enum ExportBatch {
+    ClickLog(Vec<click_log::Model>),
+    Daily(Vec<click_stats_daily::Model>),
+    Hourly(Vec<click_stats_hourly::Model>),
+}
+
+impl ExportBatch {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::ClickLog(rows) => rows.len(),
+            Self::Daily(rows) => rows.len(),
+            Self::Hourly(rows) => rows.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 按批写出的格式实现
+trait BatchWriter: Send {
+    fn write_batch(&mut self, batch: &ExportBatch) -> Result<(), ShortlinkerError>;
+    fn finish(self: Box<Self>) -> Result<(), ShortlinkerError>;
+}
+
+/// 导出一张表到 `output`
+pub async fn export_analytics<W>(
+    storage: &SeaOrmStorage,
+    options: &ExportOptions,
+    output: W,
+) -> Result<ExportSummary, ShortlinkerError>
+where
+    W: Write + Send + 'static,
+{
+    if options.start > options.end {
+        return Err(ShortlinkerError::analytics_invalid_date_range(
+            "Start date must not be later than end date",
+        ));
+    }
+    let mut writer = create_writer(options, output)?;
+
+    let mut batches = match options.table {
+        ExportTable::ClickLog => storage
+            .stream_click_logs_cursor(options.start, options.end, EXPORT_BATCH_SIZE)
+            .map(|r| r.map(ExportBatch::ClickLog))
+            .boxed(),
+        ExportTable::Daily => storage
+            .stream_daily_stats_cursor(
+                options.start.date_naive(),
+                options.end.date_naive(),
+                EXPORT_BATCH_SIZE,
+            )
+            .map(|r| r.map(ExportBatch::Daily))
+            .boxed(),
+        ExportTable::Hourly => storage
+            .stream_hourly_stats_cursor(options.start, options.end, EXPORT_BATCH_SIZE)
+            .map(|r| r.map(ExportBatch::Hourly))
+            .boxed(),
+    };
+
+    let mut summary = ExportSummary::default();
+    while let Some(batch) = batches.next().await {
+        let batch = batch.map_err(|e| {
+            ShortlinkerError::analytics_query_failed(format!(
+                "Failed to read {}: {}",
+                options.table.table_name(),
+                e
+            ))
+        })?;
+        writer.write_batch(&batch)?;
+        summary.rows += batch.len() as u64;
+        summary.batches += 1;
+    }
+    writer.finish()?;
+    Ok(summary)
+}
+
+fn create_writer<W>(
+    options: &ExportOptions,
+    output: W,
+) -> Result<Box<dyn BatchWriter>, ShortlinkerError>
+where
+    W: Write + Send + 'static,
+{
+    match options.format {
+        ExportFormat::Csv => {
+            if options.counts == CountsEncoding::Nested {
+                return Err(ShortlinkerError::validation(
+                    "Nested counts are only supported for Parquet output",
+                ));
+            }
+            Ok(Box::new(csv::CsvBatchWriter::new(output, options.table)?))
+        }
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet => Ok(Box::new(parquet::ParquetBatchWriter::new(
+            output,
+            options.table,
+            options.counts,
+        )?)),
+        #[cfg(not(feature = "parquet"))]
+        ExportFormat::Parquet => Err(ShortlinkerError::export_failed(
+            "Parquet export is not available in this build (enable the `parquet` feature)",
+        )),
+    }
+}
+
+/// 解析汇总表的 JSON 计数列
+///
+/// 兼容小时表的对象格式（按计数降序、键升序排列）与天表的 `[key, count]` 数组格式
+/// （保持原顺序）。列为 NULL 时返回 `None`，无法解析时记录警告并返回空列表。
+pub fn parse_count_entries(json: Option<&str>) -> Option<Vec<(String, i64)>> {
+    let json = json?;
+    if json.is_empty() {
+        return Some(Vec::new());
+    }
+    if let Ok(map) = serde_json::from_str::<HashMap<String, i64>>(json) {
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        return Some(entries);
+    }
+    match serde_json::from_str::<Vec<(String, i64)>>(json) {
+        Ok(entries) => Some(entries),
+        Err(e) => {
+            warn!(
+                "Failed to parse JSON counts for export: {} (data: {})",
+                e,
+                &json[..json.len().min(200)]
+            );
+            Some(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(pairs: &[(&str, i64)]) -> Vec<(String, i64)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_parse_count_entries() {
+        assert_eq!(parse_count_entries(None), None);
+        assert_eq!(parse_count_entries(Some("")), Some(Vec::new()));
+        assert_eq!(
+            parse_count_entries(Some(r#"{"b.com": 2, "a.com": 2, "c.com": 5}"#)),
+            Some(entries(&[("c.com", 5), ("a.com", 2), ("b.com", 2)]))
+        );
+        assert_eq!(
+            parse_count_entries(Some(r#"[["US", 7], ["DE", 3]]"#)),
+            Some(entries(&[("US", 7), ("DE", 3)]))
+        );
+        assert_eq!(parse_count_entries(Some("not json")), Some(Vec::new()));
+    }
+}
@@ -20,6 +20,7 @@ use tracing::debug;
 use aster_forge_db::retry::RetryConfig;
 use migration::entities::{click_stats_global_hourly, click_stats_hourly};
 
+use super::query_params::{merge_param_counts, param_counts_to_json, parse_param_counts};
 use super::{ClickAggregation, to_json_string, truncate_to_hour};
 
 /// 小时汇总写入器
@@ -86,6 +87,7 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
                 referrer_counts: Set(None),
                 country_counts: Set(None),
                 source_counts: Set(None),
+                param_counts: Set(None),
                 ..Default::default()
             })
             .collect();
@@ -265,6 +267,12 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
             *merged.sources.entry(k).or_insert(0) += v;
         }
 
+        // 合并自定义参数（写入时再做 top-K 截断）
+        merge_param_counts(
+            &mut merged.params,
+            &parse_param_counts(&record.param_counts),
+        );
+
         merged
     }
 
@@ -286,6 +294,7 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
                 referrer_counts: Set(Some(to_json_string(&agg.referrers))),
                 country_counts: Set(Some(to_json_string(&agg.countries))),
                 source_counts: Set(Some(to_json_string(&agg.sources))),
+                param_counts: Set(param_counts_to_json(&agg.params)),
                 ..Default::default()
             })
             .collect();
@@ -317,6 +326,7 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
         let mut referrer_case = CaseStatement::new();
         let mut country_case = CaseStatement::new();
         let mut source_case = CaseStatement::new();
+        let mut param_case = CaseStatement::new();
 
         for (id, agg) in records {
             let id_expr = Expr::col(click_stats_hourly::Column::Id).eq(Expr::val(*id));
@@ -334,9 +344,13 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
                 SimpleExpr::Value(to_json_string(&agg.countries).into()),
             );
             source_case = source_case.case(
-                id_expr,
+                id_expr.clone(),
                 SimpleExpr::Value(to_json_string(&agg.sources).into()),
             );
+            param_case = param_case.case(
+                id_expr,
+                SimpleExpr::Value(param_counts_to_json(&agg.params).into()),
+            );
         }
 
         // 不匹配的保持原值
@@ -346,6 +360,7 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
             referrer_case.finally(Expr::col(click_stats_hourly::Column::ReferrerCounts));
         country_case = country_case.finally(Expr::col(click_stats_hourly::Column::CountryCounts));
         source_case = source_case.finally(Expr::col(click_stats_hourly::Column::SourceCounts));
+        param_case = param_case.finally(Expr::col(click_stats_hourly::Column::ParamCounts));
 
         let stmt = Query::update()
             .table(click_stats_hourly::Entity)
@@ -353,6 +368,7 @@ impl<'a, C: ConnectionTrait> HourlyRollupWriter<'a, C> {
             .value(click_stats_hourly::Column::ReferrerCounts, referrer_case)
             .value(click_stats_hourly::Column::CountryCounts, country_case)
             .value(click_stats_hourly::Column::SourceCounts, source_case)
+            .value(click_stats_hourly::Column::ParamCounts, param_case)
             .and_where(Expr::col(click_stats_hourly::Column::Id).is_in(ids))
             .to_owned();
 
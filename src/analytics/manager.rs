@@ -2,10 +2,12 @@
 //!
 //! 负责收集和刷新点击统计数据，支持：
 //! - 高并发点击计数（使用 DashMap）
+//! - 按链接统计级别路由（`analytics_level`）
 //! - 定时刷盘到存储后端
 //! - 阈值触发刷盘
 //! - 详细点击日志记录（可选）
 //! - Channel 异步处理（避免热路径 spawn）
+//! - Redirect 耗时样本缓冲（可选，随定时刷盘批量写入）
 
 use crossbeam_channel::{Receiver, Sender, TrySendError};
 use dashmap::DashMap;
@@ -17,9 +19,17 @@ use tokio::sync::Mutex;
 use tokio::time::{Duration, sleep};
 use tracing::{debug, error, trace, warn};
 
-use crate::analytics::{ClickDetail, ClickSink, DetailedClickSink, RawClickEvent};
+use crate::analytics::{
+    ClickDetail, ClickSink, DetailedClickSink, RawClickEvent, RedirectTiming, RedirectTimingSink,
+};
 
 use crate::metrics::MetricsRecorder;
+use crate::runtime::supervisor::Heartbeat;
+use crate::storage::AnalyticsLevel;
+use crate::utils::log_sample::LogSampler;
+
+/// channel 丢弃事件的日志采样（过载或处理器退出后每个请求都会丢弃）
+static CHANNEL_DROP_LOG: LogSampler = LogSampler::new(1000);
 
 /// 点击缓冲区状态，封装所有可变状态
 struct ClickBuffer {
@@ -53,8 +63,6 @@ impl ClickBuffer {
             .and_modify(|v| *v += 1)
             .or_insert(1);
 
-        trace!("ClickBuffer: Incremented key: {}", key);
-
         // 使用 AcqRel 确保与其他线程的操作正确同步
         self.total_clicks.fetch_add(1, Ordering::AcqRel) + 1
     }
@@ -160,6 +168,38 @@ impl DetailedBuffer {
     }
 }
 
+/// Redirect 耗时样本缓冲区上限，超出时丢弃新样本
+const MAX_PENDING_TIMINGS: usize = 10_000;
+
+/// Redirect 耗时样本缓冲区
+///
+/// 样本只用于容量规划，刷盘失败时直接丢弃，不回填。
+struct TimingBuffer {
+    data: std::sync::Mutex<Vec<RedirectTiming>>,
+}
+
+impl TimingBuffer {
+    fn new() -> Self {
+        Self {
+            data: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 加入样本，缓冲区已满时返回 false
+    fn push(&self, timing: RedirectTiming) -> bool {
+        let mut data = self.data.lock().expect("timing buffer lock poisoned");
+        if data.len() >= MAX_PENDING_TIMINGS {
+            return false;
+        }
+        data.push(timing);
+        true
+    }
+
+    fn drain(&self) -> Vec<RedirectTiming> {
+        std::mem::take(&mut *self.data.lock().expect("timing buffer lock poisoned"))
+    }
+}
+
 /// 点击管理器
 ///
 /// 负责收集点击统计并定期刷盘到存储后端。
@@ -168,6 +208,8 @@ impl DetailedBuffer {
 pub struct ClickManager {
     /// 点击缓冲区（共享所有权）
     buffer: Arc<ClickBuffer>,
+    /// 仅计数缓冲区（`count_only` 级别的链接，刷盘时不写小时汇总）
+    count_only_buffer: Arc<ClickBuffer>,
     /// 存储后端
     sink: Arc<dyn ClickSink>,
     /// 刷盘间隔
@@ -180,6 +222,10 @@ pub struct ClickManager {
     detailed_sink: Option<Arc<dyn DetailedClickSink>>,
     /// 原始事件 channel sender（用于异步处理详细日志，使用 crossbeam 高性能 channel）
     raw_event_tx: Option<Sender<RawClickEvent>>,
+    /// Redirect 耗时样本缓冲区（可选）
+    timing_buffer: Option<Arc<TimingBuffer>>,
+    /// Redirect 耗时样本 Sink（可选）
+    timing_sink: Option<Arc<dyn RedirectTimingSink>>,
     /// Metrics recorder for dependency injection
     metrics: Arc<dyn MetricsRecorder>,
     /// Shutdown signal sender
@@ -198,12 +244,15 @@ impl ClickManager {
         let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
         Self {
             buffer: Arc::new(ClickBuffer::new()),
+            count_only_buffer: Arc::new(ClickBuffer::new()),
             sink,
             flush_interval,
             max_clicks_before_flush,
             detailed_buffer: None,
             detailed_sink: None,
             raw_event_tx: None,
+            timing_buffer: None,
+            timing_sink: None,
             metrics,
             shutdown_tx: Arc::new(shutdown_tx),
             shutdown_rx,
@@ -225,12 +274,15 @@ impl ClickManager {
 
         let manager = Self {
             buffer: Arc::new(ClickBuffer::new()),
+            count_only_buffer: Arc::new(ClickBuffer::new()),
             sink,
             flush_interval,
             max_clicks_before_flush,
             detailed_buffer: Some(Arc::new(DetailedBuffer::new())),
             detailed_sink: Some(detailed_sink),
             raw_event_tx: Some(tx),
+            timing_buffer: None,
+            timing_sink: None,
             metrics,
             shutdown_tx: Arc::new(shutdown_tx),
             shutdown_rx,
@@ -239,6 +291,27 @@ impl ClickManager {
         (manager, rx)
     }
 
+    /// 启用 redirect 耗时样本记录（`analytics.timing_sample_rate`）
+    pub fn with_timing_sink(mut self, sink: Arc<dyn RedirectTimingSink>) -> Self {
+        self.timing_buffer = Some(Arc::new(TimingBuffer::new()));
+        self.timing_sink = Some(sink);
+        self
+    }
+
+    /// 检查是否启用了耗时样本记录
+    pub fn is_timing_enabled(&self) -> bool {
+        self.timing_buffer.is_some() && self.timing_sink.is_some()
+    }
+
+    /// 缓冲一条 redirect 耗时样本（热路径调用，不做 IO）
+    pub fn record_timing(&self, timing: RedirectTiming) {
+        if let Some(ref buffer) = self.timing_buffer
+            && !buffer.push(timing)
+        {
+            trace!("ClickManager: timing buffer full, dropping sample");
+        }
+    }
+
     /// 检查是否启用了详细日志
     pub fn is_detailed_logging_enabled(&self) -> bool {
         self.detailed_buffer.is_some() && self.detailed_sink.is_some()
@@ -254,10 +327,6 @@ impl ClickManager {
         // 2. 如果启用详细日志，写入 detailed_buffer
         if let Some(ref buffer) = self.detailed_buffer {
             let current_size = buffer.push(detail);
-            trace!(
-                "ClickManager: Detailed log recorded, buffer size: {}",
-                current_size
-            );
 
             // 阈值触发刷盘
             if current_size >= self.max_clicks_before_flush
@@ -282,9 +351,15 @@ impl ClickManager {
 
     /// 发送原始点击事件到 channel（热路径调用，非阻塞）
     ///
-    /// 返回 true 表示发送成功，false 表示 channel 已满或未启用
+    /// 按事件携带的统计级别路由：不记录明细的级别只计数，不进入 channel。
+    /// 返回 true 表示发送成功，false 表示未发送（级别不需要明细、channel 已满或未启用）
     #[inline]
     pub fn send_raw_event(&self, event: RawClickEvent) -> bool {
+        if !event.analytics_level.allows_details() {
+            self.record_count(&event.code, event.analytics_level);
+            return false;
+        }
+
         // 始终增加 click_count
         self.increment(&event.code);
 
@@ -293,12 +368,19 @@ impl ClickManager {
             match tx.try_send(event) {
                 Ok(()) => true,
                 Err(TrySendError::Full(_)) => {
-                    warn!("ClickManager: Event channel full, dropping event");
+                    if let Some(suppressed) = CHANNEL_DROP_LOG.sample() {
+                        warn!(
+                            suppressed,
+                            "ClickManager: Event channel full, dropping event"
+                        );
+                    }
                     self.metrics.inc_clicks_channel_dropped("full");
                     false
                 }
                 Err(TrySendError::Disconnected(_)) => {
-                    warn!("ClickManager: Event channel disconnected");
+                    if let Some(suppressed) = CHANNEL_DROP_LOG.sample() {
+                        warn!(suppressed, "ClickManager: Event channel disconnected");
+                    }
                     self.metrics.inc_clicks_channel_dropped("disconnected");
                     false
                 }
@@ -313,27 +395,47 @@ impl ClickManager {
         self.raw_event_tx.clone()
     }
 
+    /// 按链接统计级别记录一次不带明细的点击
+    ///
+    /// `None` 不记录；`CountOnly` 只累加 `click_count`；其余级别同 [`Self::increment`]。
+    pub fn record_count(&self, key: &str, level: AnalyticsLevel) {
+        match level {
+            AnalyticsLevel::None => {}
+            AnalyticsLevel::CountOnly => self.increment_buffer(&self.count_only_buffer, key, true),
+            _ => self.increment(key),
+        }
+    }
+
     /// 增加点击计数（线程安全，无锁）
     pub fn increment(&self, key: &str) {
-        let current_size = self.buffer.increment(key);
-        trace!("ClickManager: Current buffer size: {}", current_size);
+        self.increment_buffer(&self.buffer, key, false);
+    }
+
+    fn increment_buffer(&self, buffer: &Arc<ClickBuffer>, key: &str, count_only: bool) {
+        let current_size = buffer.increment(key);
 
         // 检查是否达到阈值，尝试触发刷盘
         if current_size >= self.max_clicks_before_flush {
             // 使用 compare_exchange 防止任务风暴：
             // 只有成功将 flush_pending 从 false 设为 true 的线程才 spawn
-            if self
-                .buffer
+            if buffer
                 .flush_pending
                 .compare_exchange(false, true, Ordering::SeqCst, Ordering::Relaxed)
                 .is_ok()
             {
-                let buffer = Arc::clone(&self.buffer);
+                let buffer = Arc::clone(buffer);
                 let sink = Arc::clone(&self.sink);
                 let metrics = Arc::clone(&self.metrics);
                 tokio::spawn(async move {
                     let success = if let Ok(_guard) = buffer.flush_lock.try_lock() {
-                        Self::flush_buffer_with_trigger(&buffer, &sink, "threshold", &metrics).await
+                        Self::flush_buffer_with_trigger(
+                            &buffer,
+                            &sink,
+                            "threshold",
+                            count_only,
+                            &metrics,
+                        )
+                        .await
                     } else {
                         trace!("ClickManager: flush already in progress, skipping");
                         true // 跳过也算成功，不需要退避
@@ -350,20 +452,30 @@ impl ClickManager {
         }
     }
 
-    /// 启动后台刷盘任务（作为异步方法运行）
-    pub async fn start_background_task(&self) {
+    /// 定时刷盘间隔
+    pub fn flush_interval(&self) -> Duration {
+        self.flush_interval
+    }
+
+    /// 启动后台刷盘任务（作为异步方法运行），每轮刷盘后上报心跳
+    pub async fn start_background_task(&self, heartbeat: &Heartbeat) {
         let mut shutdown_rx = self.shutdown_rx.clone();
         loop {
+            heartbeat.beat();
             tokio::select! {
                 _ = sleep(self.flush_interval) => {
                     debug!("ClickManager: Triggering scheduled flush");
                     // 定期触发刷盘
                     if let Ok(_guard) = self.buffer.flush_lock.try_lock() {
                         trace!("ClickManager: Starting scheduled flush");
-                        Self::flush_buffer(&self.buffer, &self.sink, &self.metrics).await;
+                        Self::flush_buffer(&self.buffer, &self.sink, false, &self.metrics).await;
                     } else {
                         trace!("ClickManager: flush already in progress, skipping scheduled flush");
                     }
+                    if let Ok(_guard) = self.count_only_buffer.flush_lock.try_lock() {
+                        Self::flush_buffer(&self.count_only_buffer, &self.sink, true, &self.metrics)
+                            .await;
+                    }
 
                     // 刷新详细日志
                     if let (Some(detailed_buffer), Some(detailed_sink)) =
@@ -372,6 +484,8 @@ impl ClickManager {
                     {
                         Self::flush_detailed_buffer(detailed_buffer, detailed_sink).await;
                     }
+
+                    self.flush_timings().await;
                 }
                 _ = shutdown_rx.changed() => {
                     debug!("ClickManager: Shutdown signal received, exiting background task");
@@ -384,8 +498,12 @@ impl ClickManager {
     /// 启动原始事件处理器（消费 crossbeam channel 并生成 ClickDetail）
     ///
     /// 需要传入事件处理函数，用于将 RawClickEvent 转换为 ClickDetail
-    pub async fn start_event_processor<F>(&self, rx: Receiver<RawClickEvent>, process_fn: F)
-    where
+    pub async fn start_event_processor<F>(
+        &self,
+        rx: Receiver<RawClickEvent>,
+        process_fn: F,
+        heartbeat: &Heartbeat,
+    ) where
         F: Fn(RawClickEvent) -> ClickDetail + Send + 'static,
     {
         debug!("ClickManager: Starting event processor");
@@ -394,6 +512,7 @@ impl ClickManager {
         // crossbeam channel 的 recv 是阻塞的，需要在 blocking task 中运行
         // 或者用 try_recv + yield
         loop {
+            heartbeat.beat();
             // 先检查 shutdown 信号（非阻塞）
             if shutdown_rx.has_changed().unwrap_or(true) {
                 debug!("ClickManager: Shutdown signal received, draining remaining events");
@@ -463,7 +582,17 @@ impl ClickManager {
     pub async fn flush(&self) {
         debug!("ClickManager: Manual flush triggered");
         let _guard = self.buffer.flush_lock.lock().await;
-        Self::flush_buffer_with_trigger(&self.buffer, &self.sink, "manual", &self.metrics).await;
+        Self::flush_buffer_with_trigger(&self.buffer, &self.sink, "manual", false, &self.metrics)
+            .await;
+        let _count_only_guard = self.count_only_buffer.flush_lock.lock().await;
+        Self::flush_buffer_with_trigger(
+            &self.count_only_buffer,
+            &self.sink,
+            "manual",
+            true,
+            &self.metrics,
+        )
+        .await;
 
         // 刷新详细日志
         if let (Some(detailed_buffer), Some(detailed_sink)) =
@@ -472,6 +601,28 @@ impl ClickManager {
             let _guard = detailed_buffer.flush_lock.lock().await;
             Self::flush_detailed_buffer(detailed_buffer, detailed_sink).await;
         }
+
+        self.flush_timings().await;
+    }
+
+    /// 写入缓冲的 redirect 耗时样本（失败时丢弃）
+    async fn flush_timings(&self) {
+        let (Some(buffer), Some(sink)) = (&self.timing_buffer, &self.timing_sink) else {
+            return;
+        };
+        let timings = buffer.drain();
+        if timings.is_empty() {
+            return;
+        }
+
+        let count = timings.len();
+        match sink.log_timings_batch(timings).await {
+            Ok(()) => debug!("ClickManager: Flushed {} redirect timing samples", count),
+            Err(e) => warn!(
+                "ClickManager: log_timings_batch failed: {}, {} samples dropped",
+                e, count
+            ),
+        }
     }
 
     /// 发送 shutdown 信号，停止后台任务和事件处理器
@@ -484,24 +635,29 @@ impl ClickManager {
     async fn flush_buffer(
         buffer: &ClickBuffer,
         sink: &Arc<dyn ClickSink>,
+        count_only: bool,
         metrics: &Arc<dyn MetricsRecorder>,
     ) -> bool {
-        Self::flush_buffer_with_trigger(buffer, sink, "interval", metrics).await
+        Self::flush_buffer_with_trigger(buffer, sink, "interval", count_only, metrics).await
     }
 
     /// 执行实际的刷盘操作（带触发类型标记）
     ///
+    /// `count_only` 为 true 时只累加 `click_count`，不写小时汇总。
     /// 返回 true 表示成功，false 表示失败
     async fn flush_buffer_with_trigger(
         buffer: &ClickBuffer,
         sink: &Arc<dyn ClickSink>,
         trigger: &str,
+        count_only: bool,
         metrics: &Arc<dyn MetricsRecorder>,
     ) -> bool {
         let updates = buffer.drain();
 
-        // Update buffer size gauge after drain
-        metrics.set_clicks_buffer_entries(buffer.data.len() as f64);
+        // Update buffer size gauge after drain（仅计数缓冲区不计入）
+        if !count_only {
+            metrics.set_clicks_buffer_entries(buffer.data.len() as f64);
+        }
 
         if updates.is_empty() {
             trace!("ClickManager: No clicks to flush");
@@ -509,7 +665,12 @@ impl ClickManager {
         }
 
         let count = updates.len();
-        match sink.flush_clicks(updates.clone()).await {
+        let result = if count_only {
+            sink.flush_counts_only(updates.clone()).await
+        } else {
+            sink.flush_clicks(updates.clone()).await
+        };
+        match result {
             Ok(_) => {
                 debug!("ClickManager: Successfully flushed {} entries", count);
                 metrics.inc_clicks_flush(trigger, "success");
@@ -533,7 +694,9 @@ impl ClickManager {
                 );
                 metrics.inc_clicks_flush(trigger, "failed");
                 // Update gauge again after restore
-                metrics.set_clicks_buffer_entries(buffer.data.len() as f64);
+                if !count_only {
+                    metrics.set_clicks_buffer_entries(buffer.data.len() as f64);
+                }
                 false
             }
         }
@@ -596,7 +759,7 @@ impl ClickManager {
 
     /// 获取当前缓冲区总点击数（用于监控）
     pub fn buffer_size(&self) -> usize {
-        self.buffer.total()
+        self.buffer.total() + self.count_only_buffer.total()
     }
 }
 
@@ -615,6 +778,7 @@ mod tests {
 
     struct MockSink {
         flushed: std::sync::Mutex<Vec<(String, usize)>>,
+        counts_only: std::sync::Mutex<Vec<(String, usize)>>,
     }
 
     struct UnknownCommitSink;
@@ -632,6 +796,7 @@ mod tests {
         fn new() -> Self {
             Self {
                 flushed: std::sync::Mutex::new(Vec::new()),
+                counts_only: std::sync::Mutex::new(Vec::new()),
             }
         }
 
@@ -650,6 +815,11 @@ mod tests {
             self.flushed.lock().unwrap().extend(updates);
             Ok(())
         }
+
+        async fn flush_counts_only(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+            self.counts_only.lock().unwrap().extend(updates);
+            Ok(())
+        }
     }
 
     fn create_test_manager(sink: Arc<dyn ClickSink>, max_clicks: usize) -> ClickManager {
@@ -680,6 +850,43 @@ mod tests {
         assert_eq!(flushed.len(), 2); // 2 个唯一 key
     }
 
+    #[tokio::test]
+    async fn test_record_count_routes_by_level() {
+        let sink = Arc::new(MockSink::new());
+        let manager = create_test_manager(Arc::clone(&sink) as Arc<dyn ClickSink>, 100);
+
+        manager.record_count("silent", AnalyticsLevel::None);
+        manager.record_count("counter", AnalyticsLevel::CountOnly);
+        manager.record_count("counter", AnalyticsLevel::CountOnly);
+        manager.record_count("rolled", AnalyticsLevel::Aggregate);
+        manager.record_count("inherited", AnalyticsLevel::Inherit);
+        assert_eq!(manager.buffer_size(), 4);
+
+        // 不需要明细的级别不进入 channel
+        let event = RawClickEvent {
+            code: "rolled".to_string(),
+            query: None,
+            referrer: None,
+            user_agent: None,
+            ip: None,
+            analytics_level: AnalyticsLevel::Aggregate,
+        };
+        assert!(!manager.send_raw_event(event));
+
+        manager.flush().await;
+
+        assert_eq!(
+            *sink.counts_only.lock().unwrap(),
+            vec![("counter".to_string(), 2)]
+        );
+        let mut flushed = sink.get_flushed();
+        flushed.sort();
+        assert_eq!(
+            flushed,
+            vec![("inherited".to_string(), 1), ("rolled".to_string(), 2)]
+        );
+    }
+
     #[tokio::test]
     async fn test_unknown_commit_outcome_is_not_restored() {
         let manager = create_test_manager(Arc::new(UnknownCommitSink), 100);
@@ -784,4 +991,39 @@ mod tests {
             NUM_THREADS * INCREMENTS_PER_THREAD
         );
     }
+
+    struct MockTimingSink {
+        written: std::sync::Mutex<Vec<RedirectTiming>>,
+    }
+
+    #[async_trait]
+    impl RedirectTimingSink for MockTimingSink {
+        async fn log_timings_batch(&self, timings: Vec<RedirectTiming>) -> anyhow::Result<()> {
+            self.written.lock().unwrap().extend(timings);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timing_samples_flushed_with_clicks() {
+        let sink = Arc::new(MockSink::new());
+        let timing_sink = Arc::new(MockTimingSink {
+            written: std::sync::Mutex::new(Vec::new()),
+        });
+        let manager = create_test_manager(sink, 100)
+            .with_timing_sink(Arc::clone(&timing_sink) as Arc<dyn RedirectTimingSink>);
+        assert!(manager.is_timing_enabled());
+
+        let timing = crate::analytics::RedirectTimer::new(true)
+            .finish("abc", 307)
+            .unwrap();
+        manager.record_timing(timing.clone());
+        manager.flush().await;
+
+        assert_eq!(*timing_sink.written.lock().unwrap(), vec![timing]);
+
+        // 已写入的样本不会重复写入
+        manager.flush().await;
+        assert_eq!(timing_sink.written.lock().unwrap().len(), 1);
+    }
 }
@@ -1,21 +1,34 @@
+pub mod amend;
+pub mod anomaly;
+pub mod export;
 pub mod global;
 pub mod hourly_writer;
 pub mod manager;
+pub mod period;
+pub mod privacy;
+pub mod query_params;
 pub mod retention;
 pub mod rollup;
 pub mod sink;
+pub mod timing;
 
+pub use anomaly::AnomalyDetectionTask;
 pub use hourly_writer::HourlyRollupWriter;
 pub use manager::ClickManager;
+pub use period::{PeriodGranularity, WeekStart};
+pub use privacy::{DntMode, PrivacyClickStats, privacy_click_stats};
 pub use retention::DataRetentionTask;
-pub use rollup::{ClickAggregation, RollupManager, aggregate_click_details};
-pub use sink::{ClickSink, DetailedClickSink};
+pub use rollup::{ClickAggregation, PeriodRebuildReport, RollupManager, aggregate_click_details};
+pub use sink::{ClickSink, DetailedClickSink, RedirectTimingSink};
+pub use timing::{RedirectTimer, RedirectTiming, TimingPhase};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use chrono::{DateTime, Timelike, Utc};
 use tracing::warn;
 
+use crate::storage::AnalyticsLevel;
+
 // ============ 公共工具函数 ============
 
 /// 将时间戳截断到整点
@@ -59,6 +72,8 @@ pub struct RawClickEvent {
     pub user_agent: Option<String>,
     /// 客户端 IP
     pub ip: Option<String>,
+    /// 链接的统计级别（取自缓存中的链接对象）
+    pub analytics_level: AnalyticsLevel,
 }
 
 /// 详细点击信息
@@ -80,6 +95,8 @@ pub struct ClickDetail {
     pub city: Option<String>,
     /// 流量来源 (utm_source 参数值, ref:{domain}, 或 direct)
     pub source: Option<String>,
+    /// 白名单内的 query 参数（`analytics.captured_query_params`）
+    pub query_params: BTreeMap<String, String>,
 }
 
 impl ClickDetail {
@@ -94,6 +111,7 @@ impl ClickDetail {
             country: None,
             city: None,
             source: None,
+            query_params: BTreeMap::new(),
         }
     }
 
@@ -0,0 +1,204 @@
+//! 周 / 月统计周期
+//!
+//! 所有汇总桶都以 UTC 日期划分：点击先按 UTC 时间落入小时桶，再滚动到天汇总，
+//! 周 / 月汇总由天汇总求和得到。周的起始日由 `analytics.week_starts_on` 决定
+//! （`monday` / `sunday`），月总是从 1 号开始。
+//!
+//! 周汇总行以周起始日期作为 `week_start`。修改周起始日后，旧行的 `week_start`
+//! 与新配置的星期不符，查询时会被忽略，需要执行
+//! `shortlinker analytics rebuild-rollups --granularity week` 从天汇总重算。
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use tracing::warn;
+
+use crate::config::{keys, try_get_runtime_config};
+
+/// 周起始日
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn from_config(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "monday" => Self::Monday,
+            "sunday" => Self::Sunday,
+            other => {
+                warn!(
+                    "Unknown analytics.week_starts_on '{}', falling back to monday",
+                    other
+                );
+                Self::Monday
+            }
+        }
+    }
+
+    /// 当前配置的周起始日（运行时配置未初始化时为周一）
+    pub fn current() -> Self {
+        try_get_runtime_config()
+            .map(|rt| Self::from_config(&rt.get_or(keys::ANALYTICS_WEEK_STARTS_ON, "monday")))
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Monday => "monday",
+            Self::Sunday => "sunday",
+        }
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+/// 物化的汇总周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeriodGranularity {
+    Week,
+    Month,
+}
+
+impl PeriodGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// `date` 所在周期的起始日期
+    pub fn period_start(&self, date: NaiveDate, week_start: WeekStart) -> NaiveDate {
+        match self {
+            Self::Week => week_start_of(date, week_start),
+            Self::Month => month_start_of(date),
+        }
+    }
+
+    /// 下一个周期的起始日期（`start` 须为周期起始日）
+    pub fn next_period(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => start + Duration::days(7),
+            Self::Month => next_month_start(start),
+        }
+    }
+}
+
+/// `date` 所在周的起始日期
+pub fn week_start_of(date: NaiveDate, week_start: WeekStart) -> NaiveDate {
+    let offset = match week_start {
+        WeekStart::Monday => date.weekday().num_days_from_monday(),
+        WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+    };
+    date - Duration::days(offset as i64)
+}
+
+/// `date` 所在月的 1 号
+pub fn month_start_of(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn next_month_start(date: NaiveDate) -> NaiveDate {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// 带时区偏移的时间戳按 UTC 日期落桶
+    fn utc_day(s: &str) -> NaiveDate {
+        DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+            .date_naive()
+    }
+
+    #[test]
+    fn test_week_start_of() {
+        // 2026-10-11 是周日，2026-10-12 是周一
+        assert_eq!(
+            week_start_of(date("2026-10-11"), WeekStart::Monday),
+            date("2026-10-05")
+        );
+        assert_eq!(
+            week_start_of(date("2026-10-11"), WeekStart::Sunday),
+            date("2026-10-11")
+        );
+        assert_eq!(
+            week_start_of(date("2026-10-12"), WeekStart::Monday),
+            date("2026-10-12")
+        );
+        assert_eq!(
+            week_start_of(date("2026-10-17"), WeekStart::Sunday),
+            date("2026-10-11")
+        );
+    }
+
+    #[test]
+    fn test_offset_timestamps_use_utc_day() {
+        // 本地周日晚上（UTC-8）在 UTC 已是周一
+        let day = utc_day("2026-10-11T23:30:00-08:00");
+        assert_eq!(day, date("2026-10-12"));
+        assert_eq!(week_start_of(day, WeekStart::Monday), date("2026-10-12"));
+        assert_eq!(week_start_of(day, WeekStart::Sunday), date("2026-10-11"));
+
+        // 本地周一凌晨（UTC+9）在 UTC 仍是周日
+        let day = utc_day("2026-10-12T01:00:00+09:00");
+        assert_eq!(day, date("2026-10-11"));
+        assert_eq!(week_start_of(day, WeekStart::Monday), date("2026-10-05"));
+        assert_eq!(week_start_of(day, WeekStart::Sunday), date("2026-10-11"));
+
+        // 本地 11 月 1 日凌晨（UTC+8）在 UTC 仍属 10 月
+        let day = utc_day("2026-11-01T02:00:00+08:00");
+        assert_eq!(
+            PeriodGranularity::Month.period_start(day, WeekStart::Monday),
+            date("2026-10-01")
+        );
+    }
+
+    #[test]
+    fn test_week_across_year_boundary() {
+        // 2027-01-01 是周五：所在周从上一年开始
+        assert_eq!(
+            week_start_of(date("2027-01-01"), WeekStart::Monday),
+            date("2026-12-28")
+        );
+        assert_eq!(
+            week_start_of(date("2027-01-01"), WeekStart::Sunday),
+            date("2026-12-27")
+        );
+        assert_eq!(
+            PeriodGranularity::Month.next_period(date("2026-12-01")),
+            date("2027-01-01")
+        );
+        assert_eq!(
+            PeriodGranularity::Week.next_period(date("2026-12-28")),
+            date("2027-01-04")
+        );
+    }
+
+    #[test]
+    fn test_week_start_from_config() {
+        assert_eq!(WeekStart::from_config("Sunday"), WeekStart::Sunday);
+        assert_eq!(WeekStart::from_config("monday"), WeekStart::Monday);
+        assert_eq!(WeekStart::from_config("friday"), WeekStart::Monday);
+        assert_eq!(WeekStart::Sunday.weekday(), Weekday::Sun);
+    }
+}
@@ -0,0 +1,152 @@
+//! 点击统计的隐私信号处理（DNT / Global Privacy Control）
+//!
+//! `analytics.respect_dnt` 开启后，带 `DNT: 1` 或 `Sec-GPC: 1` 的点击按
+//! `analytics.dnt_mode` 处理：
+//!
+//! - `details`：不产生点击明细（不读取 IP/UA/Referer），聚合点击数仍 +1
+//! - `strict`：完全不统计，点击数也不增加
+//!
+//! 两种模式下重定向响应都带 `Tk: N`。因隐私信号未记录明细的点击另有一组
+//! 进程内计数（与 `click_count` 相互独立），供统计页计算占比。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use actix_web::http::header::HeaderMap;
+use serde::Serialize;
+
+use crate::config::{get_runtime_config, keys};
+
+/// 遵守隐私信号时的响应头（W3C Tracking Status: N = not tracking）
+pub const TRACKING_STATUS_HEADER: (&str, &str) = ("Tk", "N");
+
+/// 隐私信号的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DntMode {
+    /// 不记录明细，聚合计数照常
+    Details,
+    /// 完全不统计
+    Strict,
+}
+
+impl DntMode {
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "strict" => Self::Strict,
+            _ => Self::Details,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Details => "details",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+/// 请求是否携带 `DNT: 1` 或 `Sec-GPC: 1`
+pub fn has_privacy_signal(headers: &HeaderMap) -> bool {
+    ["dnt", "sec-gpc"].iter().any(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim() == "1")
+    })
+}
+
+/// 本次点击需要遵守的隐私模式；未开启或请求未携带信号时为 `None`
+pub fn requested_mode(headers: &HeaderMap) -> Option<DntMode> {
+    let rt = get_runtime_config();
+    if !rt.get_bool_or(keys::ANALYTICS_RESPECT_DNT, false) || !has_privacy_signal(headers) {
+        return None;
+    }
+    Some(DntMode::from_config(
+        &rt.get_or(keys::ANALYTICS_DNT_MODE, "details"),
+    ))
+}
+
+static CLICKS_SEEN: AtomicU64 = AtomicU64::new(0);
+static CLICKS_OPTED_OUT: AtomicU64 = AtomicU64::new(0);
+
+/// 登记一次进入统计分支的点击
+#[inline]
+pub fn record_click(mode: Option<DntMode>) {
+    CLICKS_SEEN.fetch_add(1, Ordering::Relaxed);
+    if mode.is_some() {
+        CLICKS_OPTED_OUT.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 进程启动以来的隐私信号计数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PrivacyClickStats {
+    /// 进入统计分支的点击总数（不含压测请求）
+    pub clicks: u64,
+    /// 其中因隐私信号未记录明细的点击数
+    pub opted_out: u64,
+}
+
+impl PrivacyClickStats {
+    /// 未记录明细的点击占比（0.0-1.0）
+    pub fn opted_out_ratio(&self) -> f64 {
+        if self.clicks == 0 {
+            0.0
+        } else {
+            self.opted_out as f64 / self.clicks as f64
+        }
+    }
+}
+
+pub fn privacy_click_stats() -> PrivacyClickStats {
+    PrivacyClickStats {
+        clicks: CLICKS_SEEN.load(Ordering::Relaxed),
+        opted_out: CLICKS_OPTED_OUT.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn test_has_privacy_signal() {
+        assert!(has_privacy_signal(&headers(&[("dnt", "1")])));
+        assert!(has_privacy_signal(&headers(&[("sec-gpc", "1")])));
+        assert!(has_privacy_signal(&headers(&[
+            ("dnt", "0"),
+            ("sec-gpc", "1")
+        ])));
+        assert!(!has_privacy_signal(&headers(&[("dnt", "0")])));
+        assert!(!has_privacy_signal(&headers(&[("dnt", "yes")])));
+        assert!(!has_privacy_signal(&headers(&[])));
+    }
+
+    #[test]
+    fn test_dnt_mode_from_config() {
+        assert_eq!(DntMode::from_config("strict"), DntMode::Strict);
+        assert_eq!(DntMode::from_config("details"), DntMode::Details);
+        assert_eq!(DntMode::from_config("unknown"), DntMode::Details);
+    }
+
+    #[test]
+    fn test_opted_out_ratio() {
+        assert_eq!(PrivacyClickStats::default().opted_out_ratio(), 0.0);
+        let stats = PrivacyClickStats {
+            clicks: 8,
+            opted_out: 2,
+        };
+        assert_eq!(stats.opted_out_ratio(), 0.25);
+    }
+}
@@ -0,0 +1,349 @@
+//! 点击事件中的自定义 query 参数采集
+//!
+//! 只有 `analytics.captured_query_params` 白名单内的参数会进入明细（`click_logs.query_params`）
+//! 与小时汇总（`click_stats_hourly.param_counts`），其余 query 内容不落地。
+//! 白名单在事件消费者端逐条读取，修改后无需重启。
+//!
+//! 提取规则：
+//! - 白名单条目为精确参数名，末尾的 `*` 表示前缀匹配（默认 `utm_*`）；区分大小写
+//! - 参数名与值均做 URL 解码（`+` 视为空格），非法 UTF-8 按替换字符处理
+//! - 同名参数取第一个非空值
+//! - 值超过 [`MAX_VALUE_CHARS`] 个字符时按字符边界截断；单次点击最多采集 [`MAX_CAPTURED_PARAMS`] 个参数
+//!
+//! 汇总时每个参数只保留计数最高的 [`MAX_VALUES_PER_PARAM`] 个值，其余合并到 [`OTHER_VALUE`]，
+//! 避免高基数参数（如带随机 ID 的参数）撑大汇总行。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use tracing::warn;
+
+/// 单个参数值的最大字符数
+pub const MAX_VALUE_CHARS: usize = 128;
+
+/// 参与匹配的参数名最大字节数，更长的参数名直接忽略
+pub const MAX_NAME_LEN: usize = 64;
+
+/// 单次点击最多采集的参数个数
+pub const MAX_CAPTURED_PARAMS: usize = 16;
+
+/// 白名单最多条目数
+pub const MAX_PATTERNS: usize = 32;
+
+/// 小时汇总中每个参数保留的值个数（top-K）
+pub const MAX_VALUES_PER_PARAM: usize = 50;
+
+/// top-K 之外的值合并到的桶
+pub const OTHER_VALUE: &str = "(other)";
+
+/// 参数计数：参数名 -> (参数值 -> 次数)
+pub type ParamCounts = HashMap<String, HashMap<String, usize>>;
+
+/// 校验并整理白名单（去空白、去重，保持原有顺序）
+pub fn normalize_patterns(patterns: Vec<String>) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || !seen.insert(pattern.to_string()) {
+            continue;
+        }
+        let name = pattern.strip_suffix('*').unwrap_or(pattern);
+        if name.is_empty() {
+            return Err(
+                "a bare '*' would capture every query parameter; list parameter names or prefixes"
+                    .to_string(),
+            );
+        }
+        if name.len() > MAX_NAME_LEN
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']'))
+        {
+            return Err(format!(
+                "invalid query parameter pattern '{}': use letters, digits, '_', '-', '.', '[', ']' and an optional trailing '*'",
+                pattern
+            ));
+        }
+        normalized.push(pattern.to_string());
+    }
+    if normalized.len() > MAX_PATTERNS {
+        return Err(format!(
+            "at most {} query parameter patterns are allowed",
+            MAX_PATTERNS
+        ));
+    }
+    Ok(normalized)
+}
+
+/// 参数名是否命中白名单
+pub fn is_captured(name: &str, patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => pattern == name,
+        })
+}
+
+/// 从原始 query string 提取白名单内的参数
+pub fn extract_captured_params(query: &str, patterns: &[String]) -> BTreeMap<String, String> {
+    let mut params = BTreeMap::new();
+    if patterns.is_empty() {
+        return params;
+    }
+
+    for pair in query.split('&') {
+        if params.len() >= MAX_CAPTURED_PARAMS {
+            break;
+        }
+        let (raw_name, raw_value) = pair.split_once('=').unwrap_or((pair, ""));
+        if raw_name.is_empty() || raw_value.is_empty() {
+            continue;
+        }
+        let name = decode_component(raw_name);
+        if name.len() > MAX_NAME_LEN || params.contains_key(&name) || !is_captured(&name, patterns)
+        {
+            continue;
+        }
+        let value = sanitize_value(&decode_component(raw_value));
+        if !value.is_empty() {
+            params.insert(name, value);
+        }
+    }
+    params
+}
+
+/// URL 解码（`application/x-www-form-urlencoded` 语义，`+` 为空格）
+fn decode_component(raw: &str) -> String {
+    let spaced = raw.replace('+', " ");
+    String::from_utf8_lossy(&urlencoding::decode_binary(spaced.as_bytes())).into_owned()
+}
+
+/// 去掉控制字符与首尾空白，并按字符边界截断
+fn sanitize_value(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_VALUE_CHARS)
+        .collect()
+}
+
+/// 序列化单次点击的参数（`click_logs.query_params`），为空时返回 `None`
+pub fn params_to_json(params: &BTreeMap<String, String>) -> Option<String> {
+    if params.is_empty() {
+        return None;
+    }
+    serde_json::to_string(params).ok()
+}
+
+/// 解析 `click_logs.query_params` 列
+pub fn parse_params(json_str: &Option<String>) -> BTreeMap<String, String> {
+    json_str
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+/// 将单次点击的参数计入汇总
+pub fn count_params(counts: &mut ParamCounts, params: &BTreeMap<String, String>) {
+    for (name, value) in params {
+        *counts
+            .entry(name.clone())
+            .or_default()
+            .entry(value.clone())
+            .or_insert(0) += 1;
+    }
+}
+
+/// 合并两份参数计数
+pub fn merge_param_counts(into: &mut ParamCounts, other: &ParamCounts) {
+    for (name, values) in other {
+        let target = into.entry(name.clone()).or_default();
+        for (value, count) in values {
+            *target.entry(value.clone()).or_insert(0) += count;
+        }
+    }
+}
+
+/// 解析 `param_counts` 列
+pub fn parse_param_counts(json_str: &Option<String>) -> ParamCounts {
+    match json_str {
+        Some(s) if !s.is_empty() => serde_json::from_str(s).unwrap_or_else(|e| {
+            warn!(
+                "Failed to parse param counts: {} (data: {})",
+                e,
+                s.chars().take(200).collect::<String>()
+            );
+            HashMap::new()
+        }),
+        _ => HashMap::new(),
+    }
+}
+
+/// 序列化参数计数（每个参数截断到 top-K），为空时返回 `None`
+pub fn param_counts_to_json(counts: &ParamCounts) -> Option<String> {
+    if counts.is_empty() {
+        return None;
+    }
+    let truncated: HashMap<&String, HashMap<String, usize>> = counts
+        .iter()
+        .map(|(name, values)| (name, truncate_values(values)))
+        .collect();
+    serde_json::to_string(&truncated).ok()
+}
+
+/// 只保留计数最高的 [`MAX_VALUES_PER_PARAM`] 个值，其余合并到 [`OTHER_VALUE`]
+fn truncate_values(values: &HashMap<String, usize>) -> HashMap<String, usize> {
+    if values.len() <= MAX_VALUES_PER_PARAM {
+        return values.clone();
+    }
+
+    let mut other = values.get(OTHER_VALUE).copied().unwrap_or(0);
+    let mut ranked: Vec<(&String, usize)> = values
+        .iter()
+        .filter(|(value, _)| value.as_str() != OTHER_VALUE)
+        .map(|(value, count)| (value, *count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut kept = HashMap::with_capacity(MAX_VALUES_PER_PARAM + 1);
+    for (index, (value, count)) in ranked.into_iter().enumerate() {
+        if index < MAX_VALUES_PER_PARAM {
+            kept.insert(value.clone(), count);
+        } else {
+            other += count;
+        }
+    }
+    kept.insert(OTHER_VALUE.to_string(), other);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_whitelist_only() {
+        let params = extract_captured_params(
+            "utm_source=news&ch=wechat&token=secret&utm_medium=email",
+            &patterns(&["utm_*", "ch"]),
+        );
+        assert_eq!(params.len(), 3);
+        assert_eq!(params["utm_source"], "news");
+        assert_eq!(params["utm_medium"], "email");
+        assert_eq!(params["ch"], "wechat");
+        assert!(!params.contains_key("token"));
+
+        assert!(extract_captured_params("ch=a", &[]).is_empty());
+        // 精确匹配与大小写敏感
+        assert!(extract_captured_params("channel=a&CH=b", &patterns(&["ch"])).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_params() {
+        let params = extract_captured_params("ch=first&ch=second", &patterns(&["ch"]));
+        assert_eq!(params["ch"], "first");
+
+        // 空值不占位，取第一个非空值
+        let params = extract_captured_params("ch=&ch=+&ch=second&ch", &patterns(&["ch"]));
+        assert_eq!(params["ch"], "second");
+    }
+
+    #[test]
+    fn test_url_decoding() {
+        let params = extract_captured_params(
+            "ch=%E5%BE%AE%E4%BF%A1&utm_campaign=spring+sale%21&%63h2=x",
+            &patterns(&["ch", "ch2", "utm_*"]),
+        );
+        assert_eq!(params["ch"], "微信");
+        assert_eq!(params["utm_campaign"], "spring sale!");
+        assert_eq!(params["ch2"], "x");
+
+        // 非法 UTF-8 与不完整的转义不报错
+        let params =
+            extract_captured_params("ch=%FF%FEabc&utm_x=100%", &patterns(&["ch", "utm_*"]));
+        assert!(params["ch"].ends_with("abc"));
+        assert_eq!(params["utm_x"], "100%");
+
+        // 控制字符被剔除
+        let params = extract_captured_params("ch=a%0Ab%00c", &patterns(&["ch"]));
+        assert_eq!(params["ch"], "abc");
+    }
+
+    #[test]
+    fn test_long_values_truncated() {
+        let long = "测".repeat(MAX_VALUE_CHARS + 10);
+        let query = format!("ch={}", urlencoding::encode(&long));
+        let params = extract_captured_params(&query, &patterns(&["ch"]));
+        assert_eq!(params["ch"].chars().count(), MAX_VALUE_CHARS);
+        assert!(long.starts_with(&params["ch"]));
+
+        let long_name = format!("utm_{}", "x".repeat(MAX_NAME_LEN));
+        let query = format!("{}=1", long_name);
+        assert!(extract_captured_params(&query, &patterns(&["utm_*"])).is_empty());
+    }
+
+    #[test]
+    fn test_param_count_limit() {
+        let query: Vec<String> = (0..MAX_CAPTURED_PARAMS + 5)
+            .map(|i| format!("utm_{}=v", i))
+            .collect();
+        let params = extract_captured_params(&query.join("&"), &patterns(&["utm_*"]));
+        assert_eq!(params.len(), MAX_CAPTURED_PARAMS);
+    }
+
+    #[test]
+    fn test_normalize_patterns() {
+        assert_eq!(
+            normalize_patterns(patterns(&[" ch ", "utm_*", "ch", ""])).unwrap(),
+            ["ch", "utm_*"]
+        );
+        assert!(normalize_patterns(patterns(&["*"])).is_err());
+        assert!(normalize_patterns(patterns(&["c*h"])).is_err());
+        assert!(normalize_patterns(patterns(&["ch=1"])).is_err());
+        assert!(normalize_patterns(Vec::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_top_k_truncation() {
+        let mut counts = ParamCounts::new();
+        let values = counts.entry("ch".to_string()).or_default();
+        for i in 0..MAX_VALUES_PER_PARAM + 10 {
+            values.insert(format!("v{}", i), i + 1);
+        }
+        values.insert(OTHER_VALUE.to_string(), 5);
+
+        let json = param_counts_to_json(&counts).unwrap();
+        let parsed = parse_param_counts(&Some(json));
+        let ch = &parsed["ch"];
+        assert_eq!(ch.len(), MAX_VALUES_PER_PARAM + 1);
+        // 最小的 10 个值（1..=10）并入 (other)，总数不变
+        assert_eq!(ch[OTHER_VALUE], 5 + (1..=10).sum::<usize>());
+        assert!(!ch.contains_key("v0"));
+        assert_eq!(
+            ch.values().sum::<usize>(),
+            counts["ch"].values().sum::<usize>()
+        );
+
+        assert!(param_counts_to_json(&ParamCounts::new()).is_none());
+    }
+
+    #[test]
+    fn test_count_and_merge() {
+        let mut counts = ParamCounts::new();
+        let params = extract_captured_params("ch=a&utm_source=x", &patterns(&["ch", "utm_*"]));
+        count_params(&mut counts, &params);
+        count_params(&mut counts, &params);
+
+        let mut merged = ParamCounts::new();
+        merge_param_counts(&mut merged, &counts);
+        merge_param_counts(&mut merged, &counts);
+        assert_eq!(merged["ch"]["a"], 4);
+        assert_eq!(merged["utm_source"]["x"], 4);
+    }
+}
@@ -1,6 +1,7 @@
 //! 数据清理任务
 //!
-//! 负责清理过期的点击日志和汇总数据，防止数据库无限增长。
+//! 负责清理过期的点击日志、汇总数据、redirect 耗时样本和已过冷却期的短码记录，
+//! 防止数据库无限增长。
 
 use std::sync::Arc;
 use std::time::Duration as StdDuration;
@@ -10,11 +11,13 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
 use tracing::{debug, error, info, warn};
 
 use crate::analytics::global::set_detailed_logging_stopped;
+use crate::config::keys;
 use crate::config::runtime_config::get_runtime_config;
 use crate::storage::backend::SeaOrmStorage;
-use migration::entities::click_log;
+use migration::entities::{click_log, redirect_timing};
 
 use super::RollupManager;
+use super::period::{PeriodGranularity, WeekStart};
 
 /// 清理报告
 #[derive(Debug, Default)]
@@ -27,6 +30,10 @@ pub struct CleanupReport {
     pub hourly_stats_deleted: u64,
     /// 删除的天汇总数量
     pub daily_stats_deleted: u64,
+    /// 删除的 redirect 耗时样本数量
+    pub timings_deleted: u64,
+    /// 删除的已过冷却期短码记录数量
+    pub retired_codes_deleted: u64,
 }
 
 /// 数据清理任务
@@ -39,6 +46,8 @@ pub struct DataRetentionTask {
     hourly_retention_days: u64,
     /// 天汇总保留天数
     daily_retention_days: u64,
+    /// redirect 耗时样本保留天数
+    timing_retention_days: u64,
     /// 每次删除批量大小
     batch_size: u64,
     /// 最大日志行数（0 = 不限制）
@@ -58,6 +67,9 @@ impl DataRetentionTask {
 
         let daily_retention_days = runtime_config.get_u64_or("analytics.daily_retention_days", 365);
 
+        let timing_retention_days =
+            runtime_config.get_u64_or(keys::ANALYTICS_TIMING_RETENTION_DAYS, 7);
+
         let max_log_rows = runtime_config.get_u64_or("analytics.max_log_rows", 0);
 
         let max_rows_action = runtime_config.get_or("analytics.max_rows_action", "cleanup");
@@ -68,6 +80,7 @@ impl DataRetentionTask {
             raw_log_retention_days,
             hourly_retention_days,
             daily_retention_days,
+            timing_retention_days,
             batch_size: 10000,
             max_log_rows,
             max_rows_action,
@@ -84,7 +97,7 @@ impl DataRetentionTask {
         self.max_log_rows
     }
 
-    /// 执行 hourly → daily rollup
+    /// 执行 hourly → daily → weekly / monthly rollup
     ///
     /// 对昨天和前天执行 rollup，防止时区边界遗漏
     async fn run_daily_rollup(&self) {
@@ -104,6 +117,31 @@ impl DataRetentionTask {
                 }
             }
         }
+
+        // 天汇总更新后重算所在的周 / 月
+        let week_start = WeekStart::current();
+        let mut refreshed = Vec::new();
+        for date in [day_before, yesterday] {
+            for granularity in [PeriodGranularity::Week, PeriodGranularity::Month] {
+                let period = (granularity, granularity.period_start(date, week_start));
+                if refreshed.contains(&period) {
+                    continue;
+                }
+                refreshed.push(period);
+                if let Err(e) = self
+                    .rollup_manager
+                    .rollup_daily_to_period(period.0, period.1)
+                    .await
+                {
+                    error!(
+                        "{} rollup failed for {}: {}",
+                        granularity.as_str(),
+                        period.1,
+                        e
+                    );
+                }
+            }
+        }
     }
 
     /// 运行完整的清理流程
@@ -165,12 +203,34 @@ impl DataRetentionTask {
             }
         }
 
+        // 4. 清理 redirect 耗时样本
+        match self.cleanup_redirect_timings().await {
+            Ok(deleted) => {
+                report.timings_deleted = deleted;
+            }
+            Err(e) => {
+                error!("Failed to clean up redirect timings: {}", e);
+            }
+        }
+
+        // 5. 清理已过冷却期的短码记录
+        match self.cleanup_retired_codes().await {
+            Ok(deleted) => {
+                report.retired_codes_deleted = deleted;
+            }
+            Err(e) => {
+                error!("Failed to clean up retired codes: {}", e);
+            }
+        }
+
         info!(
-            "Data cleanup completed: raw logs {} (time-based), {} (row-limit), hourly rollups {}, daily rollups {}",
+            "Data cleanup completed: raw logs {} (time-based), {} (row-limit), hourly rollups {}, daily rollups {}, redirect timings {}, retired codes {}",
             report.raw_logs_deleted,
             report.rows_limit_deleted,
             report.hourly_stats_deleted,
-            report.daily_stats_deleted
+            report.daily_stats_deleted,
+            report.timings_deleted,
+            report.retired_codes_deleted
         );
 
         Ok(report)
@@ -331,4 +391,60 @@ impl DataRetentionTask {
 
         Ok(total_deleted)
     }
+
+    /// 清理过期的 redirect 耗时样本（分批删除避免长事务）
+    async fn cleanup_redirect_timings(&self) -> anyhow::Result<u64> {
+        let db = self.storage.get_db();
+        let cutoff = Utc::now() - Duration::days(self.timing_retention_days as i64);
+        let mut total_deleted = 0u64;
+
+        loop {
+            let ids_to_delete: Vec<i64> = redirect_timing::Entity::find()
+                .select_only()
+                .column(redirect_timing::Column::Id)
+                .filter(redirect_timing::Column::RecordedAt.lt(cutoff))
+                .order_by_asc(redirect_timing::Column::Id)
+                .limit(self.batch_size)
+                .into_tuple()
+                .all(db)
+                .await?;
+
+            if ids_to_delete.is_empty() {
+                break;
+            }
+
+            let deleted = redirect_timing::Entity::delete_many()
+                .filter(redirect_timing::Column::Id.is_in(ids_to_delete))
+                .exec(db)
+                .await?
+                .rows_affected;
+            total_deleted += deleted;
+
+            if deleted < self.batch_size {
+                break;
+            }
+        }
+
+        debug!("Redirect timing cleanup deleted {} rows", total_deleted);
+        Ok(total_deleted)
+    }
+
+    /// 清理已过冷却期的短码记录
+    ///
+    /// 每次运行时读取 `links.code_reuse_cooldown_days`，冷却期关闭（0）时清空全部记录。
+    async fn cleanup_retired_codes(&self) -> anyhow::Result<u64> {
+        let cooldown_days =
+            get_runtime_config().get_u64_or(keys::LINKS_CODE_REUSE_COOLDOWN_DAYS, 0);
+        let Some(cutoff) = i64::try_from(cooldown_days)
+            .ok()
+            .and_then(Duration::try_days)
+            .and_then(|cooldown| Utc::now().checked_sub_signed(cooldown))
+        else {
+            return Ok(0);
+        };
+
+        let deleted = self.storage.purge_retired_codes(cutoff).await?;
+        debug!("Retired code cleanup deleted {} rows", deleted);
+        Ok(deleted)
+    }
 }
@@ -1,21 +1,25 @@
 //! 点击统计汇总管理器
 //!
 //! 负责将原始点击数据聚合到汇总表（hourly/daily），
-//! 以及后台任务将小时汇总滚动到天汇总。
+//! 以及后台任务将小时汇总滚动到天汇总、天汇总物化为周 / 月汇总。
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use sea_orm::sea_query::OnConflict;
-use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
-use tracing::{debug, info};
+use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use tracing::{debug, info, warn};
 
 use super::HourlyRollupWriter;
+use super::period::{PeriodGranularity, WeekStart};
+use super::query_params::{ParamCounts, count_params, merge_param_counts};
+use crate::config::{keys, try_get_runtime_config};
 use crate::storage::backend::SeaOrmStorage;
 use aster_forge_db::retry::RetryConfig;
 use migration::entities::{
     click_stats_daily, click_stats_global_daily, click_stats_global_hourly, click_stats_hourly,
+    click_stats_monthly, click_stats_weekly,
 };
 
 /// 点击聚合数据
@@ -29,6 +33,8 @@ pub struct ClickAggregation {
     pub countries: HashMap<String, usize>,
     /// 流量来源统计 (source -> count)
     pub sources: HashMap<String, usize>,
+    /// 自定义 query 参数统计 (param -> value -> count)
+    pub params: ParamCounts,
 }
 
 impl ClickAggregation {
@@ -38,6 +44,7 @@ impl ClickAggregation {
             referrers: HashMap::new(),
             countries: HashMap::new(),
             sources: HashMap::new(),
+            params: ParamCounts::new(),
         }
     }
 
@@ -52,9 +59,23 @@ impl ClickAggregation {
         for (k, v) in &other.sources {
             *self.sources.entry(k.clone()).or_insert(0) += v;
         }
+        merge_param_counts(&mut self.params, &other.params);
     }
 }
 
+/// 周 / 月汇总重算结果
+#[derive(Debug, Clone, Default)]
+pub struct PeriodRebuildReport {
+    /// 已重算的周期起始日期
+    pub periods: Vec<NaiveDate>,
+    /// 写入的汇总行数
+    pub rows: u64,
+    /// 重算前删除的旧行数（含按旧周起始日对齐的行）
+    pub deleted: u64,
+    /// 天汇总已过保留期、未能重算的最早周期起始日期
+    pub skipped_from: Option<NaiveDate>,
+}
+
 /// 汇总管理器
 pub struct RollupManager {
     storage: Arc<SeaOrmStorage>,
@@ -311,6 +332,206 @@ impl RollupManager {
         Ok(processed)
     }
 
+    /// 从天汇总重算一个周 / 月周期
+    ///
+    /// 先删除该周期的旧行再整体写入，周期内已没有点击的链接不会残留，重复执行
+    /// 结果不变。周期起始日早于天汇总保留期时天汇总已不完整，跳过并返回 0。
+    pub async fn rollup_daily_to_period(
+        &self,
+        granularity: PeriodGranularity,
+        period_start: NaiveDate,
+    ) -> anyhow::Result<u64> {
+        if period_start < Self::daily_cutoff() {
+            warn!(
+                "Skipping {} rollup for {}: daily rollups before {} have expired",
+                granularity.as_str(),
+                period_start,
+                Self::daily_cutoff()
+            );
+            return Ok(0);
+        }
+
+        let db = self.storage.get_db();
+        let period_end = granularity.next_period(period_start);
+        let daily_records = click_stats_daily::Entity::find()
+            .filter(click_stats_daily::Column::DayBucket.gte(period_start))
+            .filter(click_stats_daily::Column::DayBucket.lt(period_end))
+            .all(db)
+            .await?;
+
+        let mut aggregated: HashMap<String, ClickAggregation> = HashMap::new();
+        for record in &daily_records {
+            let agg = aggregated.entry(record.short_code.clone()).or_default();
+            agg.count = agg
+                .count
+                .saturating_add(record.click_count.max(0).try_into().unwrap_or(usize::MAX));
+            merge_top_n(&mut agg.referrers, &record.top_referrers);
+            merge_top_n(&mut agg.countries, &record.top_countries);
+            merge_top_n(&mut agg.sources, &record.top_sources);
+        }
+
+        let processed = aggregated.len() as u64;
+        match granularity {
+            PeriodGranularity::Week => {
+                let mut models = Vec::with_capacity(aggregated.len());
+                for (code, agg) in &aggregated {
+                    models.push(click_stats_weekly::ActiveModel {
+                        short_code: Set(code.clone()),
+                        week_start: Set(period_start),
+                        click_count: Set(i64::try_from(agg.count).unwrap_or(i64::MAX)),
+                        top_referrers: Set(Some(Self::top_n_json(&agg.referrers)?)),
+                        top_countries: Set(Some(Self::top_n_json(&agg.countries)?)),
+                        top_sources: Set(Some(Self::top_n_json(&agg.sources)?)),
+                        ..Default::default()
+                    });
+                }
+                aster_forge_db::retry::with_sea_orm_retry(
+                    "rollup_replace_weekly",
+                    self.retry_config,
+                    || async {
+                        let txn = db.begin().await?;
+                        click_stats_weekly::Entity::delete_many()
+                            .filter(click_stats_weekly::Column::WeekStart.eq(period_start))
+                            .exec(&txn)
+                            .await?;
+                        if !models.is_empty() {
+                            click_stats_weekly::Entity::insert_many(models.clone())
+                                .exec(&txn)
+                                .await?;
+                        }
+                        txn.commit().await
+                    },
+                )
+                .await?;
+            }
+            PeriodGranularity::Month => {
+                let mut models = Vec::with_capacity(aggregated.len());
+                for (code, agg) in &aggregated {
+                    models.push(click_stats_monthly::ActiveModel {
+                        short_code: Set(code.clone()),
+                        month_start: Set(period_start),
+                        click_count: Set(i64::try_from(agg.count).unwrap_or(i64::MAX)),
+                        top_referrers: Set(Some(Self::top_n_json(&agg.referrers)?)),
+                        top_countries: Set(Some(Self::top_n_json(&agg.countries)?)),
+                        top_sources: Set(Some(Self::top_n_json(&agg.sources)?)),
+                        ..Default::default()
+                    });
+                }
+                aster_forge_db::retry::with_sea_orm_retry(
+                    "rollup_replace_monthly",
+                    self.retry_config,
+                    || async {
+                        let txn = db.begin().await?;
+                        click_stats_monthly::Entity::delete_many()
+                            .filter(click_stats_monthly::Column::MonthStart.eq(period_start))
+                            .exec(&txn)
+                            .await?;
+                        if !models.is_empty() {
+                            click_stats_monthly::Entity::insert_many(models.clone())
+                                .exec(&txn)
+                                .await?;
+                        }
+                        txn.commit().await
+                    },
+                )
+                .await?;
+            }
+        }
+
+        debug!(
+            "Daily-to-{} rollup completed: {} links (period: {})",
+            granularity.as_str(),
+            processed,
+            period_start
+        );
+        Ok(processed)
+    }
+
+    /// 重算 `date` 所在的周与月
+    ///
+    /// 天汇总更新后调用；周边界按 `week_start` 对齐。
+    pub async fn refresh_periods(
+        &self,
+        date: NaiveDate,
+        week_start: WeekStart,
+    ) -> anyhow::Result<()> {
+        for granularity in [PeriodGranularity::Week, PeriodGranularity::Month] {
+            self.rollup_daily_to_period(granularity, granularity.period_start(date, week_start))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 从天汇总重算 `[from, to]` 覆盖的全部周 / 月周期
+    ///
+    /// 用于修改 `analytics.week_starts_on` 后重建历史周汇总：范围内按任意星期对齐的
+    /// 旧行都会先被删除。`from` 为空时从天汇总保留期的起点开始；起始日早于保留期
+    /// 的周期无法重算，保持原样，并在 `skipped_from` 中报告。
+    pub async fn rebuild_periods(
+        &self,
+        granularity: PeriodGranularity,
+        from: Option<NaiveDate>,
+        to: NaiveDate,
+        week_start: WeekStart,
+    ) -> anyhow::Result<PeriodRebuildReport> {
+        let mut report = PeriodRebuildReport::default();
+        let today = Utc::now().date_naive();
+        let cutoff = Self::daily_cutoff();
+        let mut first = granularity.period_start(from.unwrap_or(cutoff).max(cutoff), week_start);
+        if first < cutoff {
+            first = granularity.next_period(first);
+        }
+        if let Some(from) = from {
+            let requested_first = granularity.period_start(from, week_start);
+            if requested_first < first {
+                report.skipped_from = Some(requested_first);
+            }
+        }
+        let last = granularity.period_start(to.min(today), week_start);
+        if first > last {
+            return Ok(report);
+        }
+
+        let db = self.storage.get_db();
+        let delete_end = granularity.next_period(last);
+        report.deleted = match granularity {
+            PeriodGranularity::Week => {
+                click_stats_weekly::Entity::delete_many()
+                    .filter(click_stats_weekly::Column::WeekStart.gte(first))
+                    .filter(click_stats_weekly::Column::WeekStart.lt(delete_end))
+                    .exec(db)
+                    .await?
+                    .rows_affected
+            }
+            PeriodGranularity::Month => {
+                click_stats_monthly::Entity::delete_many()
+                    .filter(click_stats_monthly::Column::MonthStart.gte(first))
+                    .filter(click_stats_monthly::Column::MonthStart.lt(delete_end))
+                    .exec(db)
+                    .await?
+                    .rows_affected
+            }
+        };
+
+        let mut period = first;
+        while period <= last {
+            report.rows += self.rollup_daily_to_period(granularity, period).await?;
+            report.periods.push(period);
+            period = granularity.next_period(period);
+        }
+
+        info!(
+            "Rebuilt {} {} rollups ({} - {}, week starts on {}): {} rows",
+            report.periods.len(),
+            granularity.as_str(),
+            first,
+            last,
+            week_start.as_str(),
+            report.rows
+        );
+        Ok(report)
+    }
+
     /// 清理过期的汇总数据
     pub async fn cleanup_expired(
         &self,
@@ -358,6 +579,18 @@ impl RollupManager {
 
     // ============ 辅助方法 ============
 
+    /// 天汇总保留期的起点（更早的天汇总已被清理）
+    fn daily_cutoff() -> NaiveDate {
+        let daily_retention_days = try_get_runtime_config()
+            .map(|rt| rt.get_u64_or(keys::ANALYTICS_DAILY_RETENTION_DAYS, 365))
+            .unwrap_or(365);
+        Self::truncate_to_day(Utc::now() - Duration::days(daily_retention_days as i64))
+    }
+
+    fn top_n_json(map: &HashMap<String, usize>) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&Self::get_top_n(map, 10))?)
+    }
+
     fn get_top_n(map: &HashMap<String, usize>, n: usize) -> Vec<(String, usize)> {
         let mut items: Vec<_> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
         items.sort_by_key(|item| std::cmp::Reverse(item.1));
@@ -366,6 +599,24 @@ impl RollupManager {
     }
 }
 
+/// 将 `[["key", count], ...]` top-N JSON 合并进计数表
+///
+/// 周 / 月汇总由天汇总的 top-N 合并而来，是近似值：某一天排在 top-N 之外的条目
+/// 不会被计入。
+fn merge_top_n(counts: &mut HashMap<String, usize>, top_n: &Option<String>) {
+    let Some(raw) = top_n.as_deref().filter(|raw| !raw.is_empty()) else {
+        return;
+    };
+    match serde_json::from_str::<Vec<(String, usize)>>(raw) {
+        Ok(items) => {
+            for (key, count) in items {
+                *counts.entry(key).or_insert(0) += count;
+            }
+        }
+        Err(e) => warn!("Failed to parse top-N JSON: {}", e),
+    }
+}
+
 /// 从 ClickDetail 列表聚合数据
 pub fn aggregate_click_details(
     details: &[crate::analytics::ClickDetail],
@@ -404,6 +655,8 @@ pub fn aggregate_click_details(
         } else {
             *agg.sources.entry("direct".to_string()).or_insert(0) += 1;
         }
+
+        count_params(&mut agg.params, &detail.query_params);
     }
 
     result
@@ -1,9 +1,16 @@
-use super::ClickDetail;
+use super::{ClickDetail, RedirectTiming};
 
 /// 点击计数 Sink（聚合模式）
 #[async_trait::async_trait]
 pub trait ClickSink: Send + Sync {
     async fn flush_clicks(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()>;
+
+    /// 只累加 `click_count`，不写小时汇总（`analytics_level = count_only` 的链接）
+    ///
+    /// 默认实现等同 `flush_clicks`，适用于不区分汇总的 Sink。
+    async fn flush_counts_only(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+        self.flush_clicks(updates).await
+    }
 }
 
 /// 详细点击日志 Sink（可选实现）
@@ -16,6 +23,13 @@ pub trait DetailedClickSink: Send + Sync {
     async fn log_clicks_batch(&self, details: Vec<ClickDetail>) -> anyhow::Result<()>;
 }
 
+/// Redirect 耗时样本 Sink
+#[async_trait::async_trait]
+pub trait RedirectTimingSink: Send + Sync {
+    /// 批量写入耗时样本
+    async fn log_timings_batch(&self, timings: Vec<RedirectTiming>) -> anyhow::Result<()>;
+}
+
 pub struct StdoutSink;
 
 #[async_trait::async_trait]
@@ -0,0 +1,226 @@
+//! Redirect 耗时分解采样
+//!
+//! 按 `analytics.timing_sample_rate`（默认 1%）在请求开始时一次性决定是否采样，
+//! 被采样的请求记录各阶段耗时，用于容量规划：
+//!
+//! - `total`：handler 总耗时（不含 `redirect.constant_time_404` 的补齐延迟）
+//! - `cache`：完整缓存查询链（Bloom → negative → L1/L2）
+//! - `bloom`：Bloom Filter 判定（对采样请求额外探测一次）
+//! - `db`：缓存 miss 后的回源查询
+//! - `geo`：GeoIP 解析；redirect 路径目前不做解析，预留列恒为 NULL
+//! - `enqueue`：点击计数与点击事件入队
+//!
+//! 未经过的阶段记为 NULL（如缓存命中时没有 `db`）。样本经 `ClickManager` 缓冲、
+//! 随定时刷盘批量写入 `redirect_timings` 表，热路径只多一次加锁入队；数据保留
+//! `analytics.timing_retention_days` 天，由 `DataRetentionTask` 清理。
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::analytics::global::get_click_manager;
+use crate::config::{get_runtime_config, keys};
+
+/// 默认采样率
+pub const DEFAULT_TIMING_SAMPLE_RATE: f64 = 0.01;
+
+/// 耗时阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingPhase {
+    Total,
+    Cache,
+    Bloom,
+    Db,
+    Geo,
+    Enqueue,
+}
+
+impl TimingPhase {
+    pub const ALL: [Self; 6] = [
+        Self::Total,
+        Self::Cache,
+        Self::Bloom,
+        Self::Db,
+        Self::Geo,
+        Self::Enqueue,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Total => "total",
+            Self::Cache => "cache",
+            Self::Bloom => "bloom",
+            Self::Db => "db",
+            Self::Geo => "geo",
+            Self::Enqueue => "enqueue",
+        }
+    }
+}
+
+/// 一条 redirect 耗时样本（微秒）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectTiming {
+    pub code: String,
+    pub recorded_at: DateTime<Utc>,
+    /// 响应状态码
+    pub status: u16,
+    pub total_us: u32,
+    pub cache_us: u32,
+    pub bloom_us: Option<u32>,
+    pub db_us: Option<u32>,
+    pub geo_us: Option<u32>,
+    pub enqueue_us: Option<u32>,
+}
+
+/// 单个请求的阶段计时器
+///
+/// 未采样时所有方法都是空操作，不调用 `Instant::now()`。
+#[derive(Debug)]
+pub struct RedirectTimer {
+    started: Option<Instant>,
+    cache: Option<Duration>,
+    bloom: Option<Duration>,
+    db: Option<Duration>,
+    geo: Option<Duration>,
+    enqueue: Option<Duration>,
+}
+
+impl RedirectTimer {
+    /// 请求开始时调用，按采样率决定本请求是否计时
+    ///
+    /// 点击统计未启用（没有 `ClickManager` 可写入）时不采样。
+    pub fn start() -> Self {
+        let sampled = get_click_manager().is_some_and(|manager| manager.is_timing_enabled()) && {
+            let rate = get_runtime_config().get_f64_or(
+                keys::ANALYTICS_TIMING_SAMPLE_RATE,
+                DEFAULT_TIMING_SAMPLE_RATE,
+            );
+            rate >= 1.0 || (rate > 0.0 && rand::random::<f64>() < rate)
+        };
+        Self::new(sampled)
+    }
+
+    pub fn new(sampled: bool) -> Self {
+        Self {
+            started: sampled.then(Instant::now),
+            cache: None,
+            bloom: None,
+            db: None,
+            geo: None,
+            enqueue: None,
+        }
+    }
+
+    #[inline]
+    pub fn is_sampled(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// 阶段开始时间点；未采样时返回 `None`
+    #[inline]
+    pub fn mark(&self) -> Option<Instant> {
+        self.started.map(|_| Instant::now())
+    }
+
+    /// 记录从 `mark` 到现在的耗时（同一阶段多次记录时累加）
+    #[inline]
+    pub fn record(&mut self, phase: TimingPhase, mark: Option<Instant>) {
+        let Some(mark) = mark else {
+            return;
+        };
+        let elapsed = mark.elapsed();
+        let slot = match phase {
+            // total 由 finish 计算
+            TimingPhase::Total => return,
+            TimingPhase::Cache => &mut self.cache,
+            TimingPhase::Bloom => &mut self.bloom,
+            TimingPhase::Db => &mut self.db,
+            TimingPhase::Geo => &mut self.geo,
+            TimingPhase::Enqueue => &mut self.enqueue,
+        };
+        *slot = Some(slot.unwrap_or_default() + elapsed);
+    }
+
+    /// 结束计时，生成样本；未采样时返回 `None`
+    pub fn finish(self, code: &str, status: u16) -> Option<RedirectTiming> {
+        let started = self.started?;
+        Some(RedirectTiming {
+            code: code.to_string(),
+            recorded_at: Utc::now(),
+            status,
+            total_us: micros(started.elapsed()),
+            cache_us: self.cache.map(micros).unwrap_or(0),
+            bloom_us: self.bloom.map(micros),
+            db_us: self.db.map(micros),
+            geo_us: self.geo.map(micros),
+            enqueue_us: self.enqueue.map(micros),
+        })
+    }
+
+    /// 结束计时并把样本交给 `ClickManager` 缓冲
+    pub fn submit(self, code: &str, status: u16) {
+        if let Some(timing) = self.finish(code, status)
+            && let Some(manager) = get_click_manager()
+        {
+            manager.record_timing(timing);
+        }
+    }
+}
+
+fn micros(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros()).unwrap_or(u32::MAX)
+}
+
+/// 最近秩法（nearest-rank）分位数，`sorted` 须已升序；空切片返回 `None`
+pub fn percentile(sorted: &[u32], p: f64) -> Option<u32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p.clamp(0.0, 100.0) / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsampled_timer_records_nothing() {
+        let mut timer = RedirectTimer::new(false);
+        let mark = timer.mark();
+        assert!(mark.is_none());
+        timer.record(TimingPhase::Cache, mark);
+        assert!(timer.finish("abc", 307).is_none());
+    }
+
+    #[test]
+    fn test_sampled_timer_leaves_skipped_phases_empty() {
+        let mut timer = RedirectTimer::new(true);
+        let mark = timer.mark();
+        std::thread::sleep(Duration::from_millis(2));
+        timer.record(TimingPhase::Cache, mark);
+        let mark = timer.mark();
+        timer.record(TimingPhase::Enqueue, mark);
+
+        let timing = timer.finish("abc", 307).unwrap();
+        assert_eq!(timing.code, "abc");
+        assert_eq!(timing.status, 307);
+        assert!(timing.cache_us >= 2_000);
+        assert!(timing.total_us >= timing.cache_us);
+        assert!(timing.enqueue_us.is_some());
+        assert_eq!(timing.db_us, None);
+        assert_eq!(timing.bloom_us, None);
+        assert_eq!(timing.geo_us, None);
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<u32> = (1..=100).collect();
+        assert_eq!(percentile(&values, 50.0), Some(50));
+        assert_eq!(percentile(&values, 99.0), Some(99));
+        assert_eq!(percentile(&values, 100.0), Some(100));
+        assert_eq!(percentile(&values, 0.0), Some(1));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+        assert_eq!(percentile(&[], 99.0), None);
+    }
+}
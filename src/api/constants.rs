@@ -10,3 +10,15 @@ pub const REFRESH_COOKIE_NAME: &str = "shortlinker_refresh";
 
 /// CSRF Token Cookie 名称
 pub const CSRF_COOKIE_NAME: &str = "csrf_token";
+
+/// 压测请求标记头（仅在 `server.allow_bench_header = true` 时生效，跳过点击统计）
+pub const BENCH_HEADER: &str = "x-shortlinker-bench";
+
+/// 使用宽限期内旧管理员 Token 时返回的提示头，值为旧 Token 失效时间（RFC3339）
+pub const TOKEN_DEPRECATION_HEADER: &str = "x-token-deprecation";
+
+/// 点击数徽章路由前缀（固定，同时作为保留短码前缀）
+pub const BADGE_PREFIX: &str = "/badge";
+
+/// 管理面板携带的自身版本号，后端据此拒绝不兼容的面板（见 `api::version`）
+pub const PANEL_VERSION_HEADER: &str = "x-panel-version";
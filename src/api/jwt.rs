@@ -22,6 +22,11 @@ pub struct AccessClaims {
     pub exp: i64,
     pub jti: String,
     pub token_type: String,
+    /// 签发时所用管理员 Token 的指纹，见 `utils::admin_token::credential_fingerprint`
+    ///
+    /// 缺失（旧版本签发）的 JWT 会被视为已吊销。
+    #[serde(default)]
+    pub cred: Option<String>,
 }
 
 /// Refresh Token Claims
@@ -32,6 +37,11 @@ pub struct RefreshClaims {
     pub exp: i64,
     pub jti: String,
     pub token_type: String,
+    /// 签发时所用管理员 Token 的指纹，见 `utils::admin_token::credential_fingerprint`
+    ///
+    /// 缺失（旧版本签发）的 JWT 会被视为已吊销。
+    #[serde(default)]
+    pub cred: Option<String>,
 }
 
 /// JWT Service for generating and validating tokens
@@ -77,8 +87,8 @@ impl JwtService {
         )
     }
 
-    /// Generate Access Token (short-lived)
-    pub fn generate_access_token(&self) -> Result<String, jsonwebtoken::errors::Error> {
+    /// Generate Access Token (short-lived), tagged with the admin credential fingerprint
+    pub fn generate_access_token(&self, cred: &str) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
         let claims = AccessClaims {
             sub: "admin".to_string(),
@@ -86,13 +96,17 @@ impl JwtService {
             exp: (now + Duration::minutes(self.access_token_minutes as i64)).timestamp(),
             jti: uuid::Uuid::new_v4().to_string(),
             token_type: "access".to_string(),
+            cred: Some(cred.to_string()),
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
     }
 
-    /// Generate Refresh Token (long-lived)
-    pub fn generate_refresh_token(&self) -> Result<String, jsonwebtoken::errors::Error> {
+    /// Generate Refresh Token (long-lived), tagged with the admin credential fingerprint
+    pub fn generate_refresh_token(
+        &self,
+        cred: &str,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
         let claims = RefreshClaims {
             sub: "admin".to_string(),
@@ -100,6 +114,7 @@ impl JwtService {
             exp: (now + Duration::days(self.refresh_token_days as i64)).timestamp(),
             jti: uuid::Uuid::new_v4().to_string(),
             token_type: "refresh".to_string(),
+            cred: Some(cred.to_string()),
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
@@ -145,6 +160,8 @@ impl JwtService {
 mod tests {
     use super::*;
 
+    const TEST_CRED: &str = "0123456789abcdef";
+
     fn create_test_service() -> JwtService {
         JwtService::new("test_secret_key_32_bytes_long!!", 15, 7)
     }
@@ -152,7 +169,7 @@ mod tests {
     #[test]
     fn test_generate_and_validate_access_token() {
         let service = create_test_service();
-        let token = service.generate_access_token().unwrap();
+        let token = service.generate_access_token(TEST_CRED).unwrap();
         let claims = service.validate_access_token(&token).unwrap();
 
         assert_eq!(claims.sub, "admin");
@@ -163,7 +180,7 @@ mod tests {
     #[test]
     fn test_generate_and_validate_refresh_token() {
         let service = create_test_service();
-        let token = service.generate_refresh_token().unwrap();
+        let token = service.generate_refresh_token(TEST_CRED).unwrap();
         let claims = service.validate_refresh_token(&token).unwrap();
 
         assert_eq!(claims.sub, "admin");
@@ -171,10 +188,35 @@ mod tests {
         assert!(claims.exp > claims.iat);
     }
 
+    #[test]
+    fn test_credential_claim_round_trips() {
+        let service = create_test_service();
+
+        let access = service.generate_access_token("0123456789abcdef").unwrap();
+        let refresh = service.generate_refresh_token("0123456789abcdef").unwrap();
+
+        assert_eq!(
+            service
+                .validate_access_token(&access)
+                .unwrap()
+                .cred
+                .as_deref(),
+            Some("0123456789abcdef")
+        );
+        assert_eq!(
+            service
+                .validate_refresh_token(&refresh)
+                .unwrap()
+                .cred
+                .as_deref(),
+            Some("0123456789abcdef")
+        );
+    }
+
     #[test]
     fn test_access_token_rejected_as_refresh() {
         let service = create_test_service();
-        let access_token = service.generate_access_token().unwrap();
+        let access_token = service.generate_access_token(TEST_CRED).unwrap();
 
         let result = service.validate_refresh_token(&access_token);
         assert!(result.is_err());
@@ -183,7 +225,7 @@ mod tests {
     #[test]
     fn test_refresh_token_rejected_as_access() {
         let service = create_test_service();
-        let refresh_token = service.generate_refresh_token().unwrap();
+        let refresh_token = service.generate_refresh_token(TEST_CRED).unwrap();
 
         let result = service.validate_access_token(&refresh_token);
         assert!(result.is_err());
@@ -205,7 +247,7 @@ mod tests {
         let service1 = create_test_service();
         let service2 = JwtService::new("different_secret_key_32_bytes!!", 15, 7);
 
-        let token = service1.generate_access_token().unwrap();
+        let token = service1.generate_access_token(TEST_CRED).unwrap();
         let result = service2.validate_access_token(&token);
         assert!(result.is_err());
     }
@@ -223,6 +265,7 @@ mod tests {
             exp: (now - chrono::Duration::hours(1)).timestamp(), // 1 小时前过期
             jti: uuid::Uuid::new_v4().to_string(),
             token_type: "access".to_string(),
+            cred: None,
         };
 
         let encoding_key =
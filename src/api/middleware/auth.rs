@@ -3,7 +3,10 @@ use actix_web::{
     Error, HttpMessage, HttpResponse,
     body::EitherBody,
     dev::{ServiceRequest, ServiceResponse},
-    http::{Method, header::CONTENT_TYPE},
+    http::{
+        Method,
+        header::{CONTENT_TYPE, HeaderName, HeaderValue},
+    },
     web,
 };
 use futures_util::future::{LocalBoxFuture, Ready, ready};
@@ -12,10 +15,12 @@ use std::sync::Arc;
 use tracing::{debug, info, trace};
 
 use crate::api::constants;
-use crate::api::jwt::get_jwt_service;
-use crate::api::services::admin::{ApiResponse, ErrorCode};
+use crate::api::jwt::{AccessClaims, get_jwt_service};
+use crate::api::services::admin::{ErrorCode, error_response};
 use crate::config::{get_runtime_config, keys};
 use crate::metrics::MetricsRecorder;
+use crate::services::{API_TOKEN_PREFIX, ApiTokenService};
+use crate::utils::admin_token::{self, CredentialStatus};
 
 /// 认证方式标记，用于 CSRF 中间件判断是否跳过验证
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -26,6 +31,12 @@ pub enum AuthMethod {
     Cookie,
 }
 
+/// 通过团队 API Token 认证的请求标记（值为 token id），主管理员凭据不插入
+///
+/// 链接写入 handler 据此检查并登记配额。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiTokenIdentity(pub String);
+
 /// Admin authentication middleware
 #[derive(Clone)]
 pub struct AdminAuth;
@@ -73,26 +84,18 @@ where
     /// Handle requests when admin token is not configured
     fn handle_missing_token(req: ServiceRequest) -> ServiceResponse<EitherBody<B>> {
         debug!("Admin token not configured - returning 404");
-        req.into_response(
-            HttpResponse::NotFound()
-                .insert_header((CONTENT_TYPE, "text/plain; charset=utf-8"))
-                .body("Not Found")
-                .map_into_right_body(),
-        )
+        req.into_response(error_response(ErrorCode::NotFound, "Not Found").map_into_right_body())
     }
 
     /// Handle unauthorized requests
     fn handle_unauthorized(req: ServiceRequest) -> ServiceResponse<EitherBody<B>> {
         info!("Admin authentication failed - invalid or missing token");
         req.into_response(
-            HttpResponse::Unauthorized()
-                .insert_header((CONTENT_TYPE, "application/json; charset=utf-8"))
-                .json(ApiResponse::<()> {
-                    code: ErrorCode::Unauthorized as i32,
-                    message: "Unauthorized: Invalid or missing token".to_string(),
-                    data: None,
-                })
-                .map_into_right_body(),
+            error_response(
+                ErrorCode::Unauthorized,
+                "Unauthorized: Invalid or missing token",
+            )
+            .map_into_right_body(),
         )
     }
 
@@ -106,17 +109,17 @@ where
     }
 
     /// 验证 Bearer token（使用 JWT）
-    fn validate_bearer_token(token: &str, metrics: &dyn MetricsRecorder) -> bool {
+    fn validate_bearer_token(token: &str, metrics: &dyn MetricsRecorder) -> Option<AccessClaims> {
         let jwt_service = get_jwt_service();
         match jwt_service.validate_access_token(token) {
-            Ok(_claims) => {
+            Ok(claims) => {
                 trace!("Bearer token validation successful");
-                true
+                Some(claims)
             }
             Err(e) => {
                 info!("Bearer token validation failed: {}", e);
                 metrics.inc_auth_failure("bearer");
-                false
+                None
             }
         }
     }
@@ -126,26 +129,104 @@ where
         req: &ServiceRequest,
         cookie_name: &str,
         metrics: &dyn MetricsRecorder,
-    ) -> bool {
+    ) -> Option<AccessClaims> {
         // Try to get the access token from cookie
-        let cookie_token = req.cookie(cookie_name).map(|c| c.value().to_string());
-
-        if let Some(token) = cookie_token {
-            let jwt_service = get_jwt_service();
-            match jwt_service.validate_access_token(&token) {
-                Ok(_claims) => {
-                    trace!("JWT validation successful");
-                    return true;
-                }
-                Err(e) => {
-                    info!("JWT validation failed: {}", e);
-                    metrics.inc_auth_failure("cookie");
-                    return false;
-                }
+        let token = req.cookie(cookie_name)?.value().to_string();
+
+        let jwt_service = get_jwt_service();
+        match jwt_service.validate_access_token(&token) {
+            Ok(claims) => {
+                trace!("JWT validation successful");
+                Some(claims)
+            }
+            Err(e) => {
+                info!("JWT validation failed: {}", e);
+                metrics.inc_auth_failure("cookie");
+                None
             }
         }
+    }
+
+    /// 检查 JWT 所属的管理员凭据：已轮换、吊销或过了宽限期则拒绝，
+    /// 由宽限期内旧 Token 签发时返回需要写入 `X-Token-Deprecation` 的失效时间
+    fn check_credential(
+        claims: &AccessClaims,
+        method: &str,
+        metrics: &dyn MetricsRecorder,
+    ) -> Result<Option<i64>, ()> {
+        match admin_token::credential_status(claims.cred.as_deref()) {
+            CredentialStatus::Current => Ok(None),
+            CredentialStatus::Deprecated(expires_at) => {
+                metrics.inc_auth_deprecated_token(method);
+                Ok(Some(expires_at.timestamp()))
+            }
+            CredentialStatus::Revoked => {
+                info!("JWT issued for a rotated, revoked or expired admin token");
+                metrics.inc_auth_failure(method);
+                Err(())
+            }
+        }
+    }
+
+    /// 调用下游服务，必要时附加 `X-Token-Deprecation` 响应头
+    async fn call_with_deprecation(
+        srv: Rc<S>,
+        req: ServiceRequest,
+        deprecated_at: Option<i64>,
+    ) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+        let mut response = srv.call(req).await?.map_into_left_body();
+        if let Some(expires_at) = deprecated_at
+            && let Ok(value) =
+                HeaderValue::from_str(&admin_token::deprecation_header_value(expires_at))
+        {
+            response.headers_mut().insert(
+                HeaderName::from_static(constants::TOKEN_DEPRECATION_HEADER),
+                value,
+            );
+        }
+        Ok(response)
+    }
+
+    /// 团队 token 只能访问链接、统计与分析接口，以及自身的用量查询
+    ///
+    /// 归档与恢复除外：归档链接不计入链接数、恢复后不保留归属，
+    /// 放开会让团队绕过 `max_links` 配额。
+    fn api_token_may_access(req: &ServiceRequest, admin_prefix: &str, token_id: &str) -> bool {
+        let Some(rest) = req.path().strip_prefix(admin_prefix) else {
+            return false;
+        };
+        if rest == format!("/v1/tokens/{}/usage", token_id) {
+            return true;
+        }
+        if req.method() == Method::POST
+            && (rest == "/v1/links/archive"
+                || rest
+                    .strip_prefix("/v1/links/")
+                    .is_some_and(|tail| tail.ends_with("/unarchive")))
+        {
+            return false;
+        }
+        ["/v1/links", "/v1/stats", "/v1/analytics", "/v1/auth/verify"]
+            .iter()
+            .any(|allowed| {
+                rest.strip_prefix(allowed)
+                    .is_some_and(|tail| tail.is_empty() || tail.starts_with('/'))
+            })
+    }
 
-        false
+    /// 校验团队 API Token（`slk_` 前缀的 Bearer）
+    async fn authenticate_api_token(
+        req: &ServiceRequest,
+        token: &str,
+        metrics: &dyn MetricsRecorder,
+    ) -> Option<String> {
+        let service = req.app_data::<web::Data<Arc<ApiTokenService>>>()?;
+        let id = service.authenticate(token).await;
+        if id.is_none() {
+            info!("API token validation failed");
+            metrics.inc_auth_failure("api_token");
+        }
+        id
     }
 
     /// Check if the request path is the login endpoint
@@ -162,6 +243,13 @@ where
         path == refresh_path
     }
 
+    /// 元数据端点（错误目录等）为静态公开信息，无需认证
+    fn is_meta_endpoint(req: &ServiceRequest, admin_prefix: &str) -> bool {
+        req.path()
+            .strip_prefix(admin_prefix)
+            .is_some_and(|rest| rest.starts_with("/meta/"))
+    }
+
     /// Check if the request path is the logout endpoint
     fn is_logout_endpoint(req: &ServiceRequest, admin_prefix: &str) -> bool {
         let path = req.path();
@@ -232,24 +320,66 @@ where
                 return Ok(response);
             }
 
+            // Allow public metadata endpoints
+            if Self::is_meta_endpoint(&req, &admin_prefix) {
+                trace!("Metadata endpoint accessed - bypassing authentication");
+                let response = srv.call(req).await?.map_into_left_body();
+                return Ok(response);
+            }
+
+            let bearer = Self::extract_bearer_token(&req);
+
+            // 0. 团队 API Token：受配额约束，不能访问配置与 token 管理接口
+            if let Some(token) = bearer.as_deref()
+                && token.starts_with(API_TOKEN_PREFIX)
+            {
+                let Some(token_id) =
+                    Self::authenticate_api_token(&req, token, metrics.as_ref()).await
+                else {
+                    return Ok(Self::handle_unauthorized(req));
+                };
+                if !Self::api_token_may_access(&req, &admin_prefix, &token_id) {
+                    info!("API token '{}' denied access to {}", token_id, req.path());
+                    return Ok(req.into_response(
+                        error_response(
+                            ErrorCode::Forbidden,
+                            "API tokens cannot access this endpoint",
+                        )
+                        .map_into_right_body(),
+                    ));
+                }
+                trace!("Admin authentication successful via API token");
+                req.extensions_mut().insert(AuthMethod::Bearer);
+                req.extensions_mut().insert(ApiTokenIdentity(token_id));
+                return Ok(srv.call(req).await?.map_into_left_body());
+            }
+
             // 1. 先尝试 Bearer Token 认证（API 用户，免 CSRF）
-            if let Some(token) = Self::extract_bearer_token(&req)
-                && Self::validate_bearer_token(&token, metrics.as_ref())
+            if let Some(token) = bearer
+                && let Some(claims) = Self::validate_bearer_token(&token, metrics.as_ref())
             {
+                let Ok(deprecated_at) = Self::check_credential(&claims, "bearer", metrics.as_ref())
+                else {
+                    return Ok(Self::handle_unauthorized(req));
+                };
                 trace!("Admin authentication successful via Bearer token");
                 // 设置认证方式标记，CSRF 中间件会跳过验证
                 req.extensions_mut().insert(AuthMethod::Bearer);
-                let response = srv.call(req).await?.map_into_left_body();
-                return Ok(response);
+                return Self::call_with_deprecation(srv, req, deprecated_at).await;
             }
 
             // 2. 再尝试 Cookie 认证（Web Panel，需要 CSRF 防护）
-            if Self::validate_jwt_cookie(&req, constants::ACCESS_COOKIE_NAME, metrics.as_ref()) {
+            if let Some(claims) =
+                Self::validate_jwt_cookie(&req, constants::ACCESS_COOKIE_NAME, metrics.as_ref())
+            {
+                let Ok(deprecated_at) = Self::check_credential(&claims, "cookie", metrics.as_ref())
+                else {
+                    return Ok(Self::handle_unauthorized(req));
+                };
                 trace!("Admin authentication successful via JWT Cookie");
                 // 设置认证方式标记，CSRF 中间件会验证
                 req.extensions_mut().insert(AuthMethod::Cookie);
-                let response = srv.call(req).await?.map_into_left_body();
-                return Ok(response);
+                return Self::call_with_deprecation(srv, req, deprecated_at).await;
             }
 
             // 两种认证都失败
@@ -9,17 +9,17 @@
 
 use actix_service::{Service, Transform};
 use actix_web::{
-    Error, HttpMessage, HttpResponse,
+    Error, HttpMessage,
     body::EitherBody,
     dev::{ServiceRequest, ServiceResponse},
-    http::{Method, header::CONTENT_TYPE},
+    http::Method,
 };
 use futures_util::future::{LocalBoxFuture, Ready, ready};
 use std::rc::Rc;
 use tracing::{info, trace};
 
 use crate::api::constants;
-use crate::api::services::admin::{ApiResponse, ErrorCode};
+use crate::api::services::admin::{ErrorCode, error_response};
 use crate::config::{get_runtime_config, keys};
 
 use super::auth::AuthMethod;
@@ -78,13 +78,7 @@ where
     fn handle_csrf_error(req: ServiceRequest) -> ServiceResponse<EitherBody<B>> {
         info!("CSRF validation failed");
         req.into_response(
-            HttpResponse::Forbidden()
-                .insert_header((CONTENT_TYPE, "application/json; charset=utf-8"))
-                .json(ApiResponse::<()> {
-                    code: ErrorCode::CsrfInvalid as i32,
-                    message: "CSRF token missing or invalid".to_string(),
-                    data: None,
-                })
+            error_response(ErrorCode::CsrfInvalid, "CSRF token missing or invalid")
                 .map_into_right_body(),
         )
     }
@@ -0,0 +1,192 @@
+//! 请求拦截中间件
+//!
+//! 在 redirect 与 admin 入口按 `firewall.rules` 评估请求特征
+//! （规则引擎见 [`crate::services::firewall`]）。命中 `block` 返回 403，
+//! 命中 `tarpit` 延迟后返回 403；`log_only` 只记录日志与指标。
+//! 规则评估之前先检查 404 自动封禁（见 [`crate::services::auto_ban`]），
+//! 被封禁的 IP 直接返回 403。
+//! 拦截响应为纯文本，不暴露 Admin API 错误信封与 request_id。
+
+use actix_service::{Service, Transform};
+use actix_web::{
+    Error, HttpResponse,
+    body::EitherBody,
+    dev::{ServiceRequest, ServiceResponse},
+    web,
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{Level, debug, info, warn};
+
+use crate::metrics::MetricsRecorder;
+use crate::services::auto_ban::{BanStatus, auto_ban_settings, auto_banner};
+use crate::services::firewall::{FirewallAction, RequestFeatures, active_rules};
+use crate::utils::ip::client_ip;
+use crate::utils::log_sample::LogSampler;
+
+/// 被封禁 IP 的请求日志采样（封禁期间扫描器仍会持续请求）
+static BANNED_LOG: LogSampler = LogSampler::new(1000);
+
+/// `log_only` 命中的日志采样（规则可能匹配大量正常流量）
+static LOG_ONLY_LOG: LogSampler = LogSampler::new(100);
+
+/// block / tarpit 命中的日志采样（扫描器会持续触发同一规则）
+static BLOCKED_LOG: LogSampler = LogSampler::new(100);
+
+/// 请求拦截中间件
+#[derive(Clone)]
+pub struct Firewall;
+
+impl<S, B> Transform<S, ServiceRequest> for Firewall
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = FirewallMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(FirewallMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct FirewallMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for FirewallMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let rules = active_rules();
+        let auto_ban = auto_ban_settings();
+
+        // 无规则且未开启自动封禁时不做任何特征提取
+        if rules.is_empty() && !auto_ban.enabled() {
+            return Box::pin(async move { Ok(srv.call(req).await?.map_into_left_body()) });
+        }
+
+        let ip = if auto_ban.enabled() || rules.needs_client_ip() {
+            client_ip(req.peer_addr(), req.headers())
+        } else {
+            None
+        };
+        let metrics: Option<Arc<dyn MetricsRecorder>> = req
+            .app_data::<web::Data<Arc<dyn MetricsRecorder>>>()
+            .map(|d| d.get_ref().clone());
+
+        if auto_ban.enabled()
+            && let Some(ip) = ip
+            && !auto_ban.is_whitelisted(ip)
+        {
+            match auto_banner().status(ip, Instant::now()) {
+                BanStatus::Banned => {
+                    if tracing::enabled!(Level::DEBUG)
+                        && let Some(suppressed) = BANNED_LOG.sample()
+                    {
+                        debug!(%ip, path = req.path(), suppressed, "auto-banned IP rejected");
+                    }
+                    if let Some(ref metrics) = metrics {
+                        metrics.inc_auto_ban_rejected();
+                    }
+                    return Box::pin(async move {
+                        Ok(req.into_response(
+                            HttpResponse::Forbidden()
+                                .body("Forbidden")
+                                .map_into_right_body(),
+                        ))
+                    });
+                }
+                BanStatus::Expired => {
+                    info!("Auto-ban expired for IP {}", ip);
+                    if let Some(ref metrics) = metrics {
+                        metrics.inc_auto_ban_event("expire");
+                    }
+                }
+                BanStatus::Clear => {}
+            }
+        }
+
+        if rules.is_empty() {
+            return Box::pin(async move { Ok(srv.call(req).await?.map_into_left_body()) });
+        }
+
+        let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+        let features = RequestFeatures {
+            path: req.path(),
+            user_agent: header("user-agent"),
+            referer: header("referer"),
+            client_ip: if rules.needs_client_ip() { ip } else { None },
+        };
+
+        let decision = rules.evaluate(&features, |rule, action| {
+            if let Some(ref metrics) = metrics {
+                metrics.inc_firewall_hit(rule, action.as_str());
+            }
+            // UA / referer 由请求方控制，只以结构化字段输出
+            if action == FirewallAction::LogOnly
+                && let Some(suppressed) = LOG_ONLY_LOG.sample()
+            {
+                info!(
+                    rule,
+                    action = action.as_str(),
+                    path = features.path,
+                    ua = ?features.user_agent,
+                    referer = ?features.referer,
+                    ip = ?features.client_ip,
+                    suppressed,
+                    "firewall rule matched"
+                );
+            }
+        });
+
+        let Some(decision) = decision else {
+            return Box::pin(async move { Ok(srv.call(req).await?.map_into_left_body()) });
+        };
+
+        if let Some(suppressed) = BLOCKED_LOG.sample() {
+            warn!(
+                rule = %decision.rule,
+                action = decision.action.as_str(),
+                path = features.path,
+                ua = ?features.user_agent,
+                ip = ?features.client_ip,
+                suppressed,
+                "firewall rule rejected request"
+            );
+        }
+        let tarpit = (decision.action == FirewallAction::Tarpit).then_some(decision.tarpit);
+
+        Box::pin(async move {
+            if let Some(delay) = tarpit {
+                tokio::time::sleep(delay).await;
+            }
+            Ok(req.into_response(
+                HttpResponse::Forbidden()
+                    .body("Forbidden")
+                    .map_into_right_body(),
+            ))
+        })
+    }
+}
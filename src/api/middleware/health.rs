@@ -14,6 +14,7 @@ use crate::api::constants;
 use crate::api::jwt::get_jwt_service;
 use crate::api::services::admin::{ApiResponse, ErrorCode};
 use crate::config::{get_runtime_config, keys};
+use crate::utils::admin_token::{self, CredentialStatus};
 
 #[derive(Clone)]
 pub struct HealthAuth;
@@ -66,7 +67,14 @@ where
         let cookie_token = req.cookie(cookie_name).map(|c| c.value().to_string());
         if let Some(token) = cookie_token {
             let jwt_service = get_jwt_service();
-            if jwt_service.validate_access_token(&token).is_ok() {
+            // 与 AdminAuth 一致：已轮换或吊销的管理员凭据签发的 JWT 不再有效
+            if jwt_service
+                .validate_access_token(&token)
+                .is_ok_and(|claims| {
+                    admin_token::credential_status(claims.cred.as_deref())
+                        != CredentialStatus::Revoked
+                })
+            {
                 trace!("Health JWT validation successful");
                 return true;
             }
@@ -1,9 +1,15 @@
 pub mod auth;
 pub mod csrf;
+pub mod firewall;
 pub mod frontend;
 pub mod health;
+pub mod panel_version;
+pub mod request_context;
 
-pub use auth::{AdminAuth, AuthMethod};
+pub use auth::{AdminAuth, ApiTokenIdentity, AuthMethod};
 pub use csrf::CsrfGuard;
+pub use firewall::Firewall;
 pub use frontend::FrontendGuard;
 pub use health::HealthAuth;
+pub use panel_version::PanelVersionGuard;
+pub use request_context::RequestContext;
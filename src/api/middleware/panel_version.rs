@@ -0,0 +1,108 @@
+//! 管理面板版本校验中间件
+//!
+//! 面板在每个请求上携带 `X-Panel-Version`。版本不兼容时直接返回 409
+//! （`PanelVersionIncompatible`），避免旧面板对新 API 静默地发出错误请求。
+//!
+//! 跳过规则：
+//! - 未携带该头的请求（CLI、SDK、第三方调用）
+//! - 元数据端点（面板需要通过 `/meta/version` 得知应刷新）
+
+use actix_service::{Service, Transform};
+use actix_web::{
+    Error,
+    body::EitherBody,
+    dev::{ServiceRequest, ServiceResponse},
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+use tracing::{debug, trace};
+
+use crate::api::services::admin::{ErrorCode, error_response};
+use crate::api::{constants, version};
+use crate::config::{get_runtime_config, keys};
+
+/// 管理面板版本校验中间件
+#[derive(Clone)]
+pub struct PanelVersionGuard;
+
+impl<S, B> Transform<S, ServiceRequest> for PanelVersionGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = PanelVersionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        let rt = get_runtime_config();
+        let meta_prefix = format!("{}/meta/", rt.get_or(keys::ROUTES_ADMIN_PREFIX, "/admin"));
+
+        ready(Ok(PanelVersionMiddleware {
+            service: Rc::new(service),
+            meta_prefix,
+        }))
+    }
+}
+
+pub struct PanelVersionMiddleware<S> {
+    service: Rc<S>,
+    meta_prefix: String,
+}
+
+impl<S, B> Service<ServiceRequest> for PanelVersionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let panel_version = req
+            .headers()
+            .get(constants::PANEL_VERSION_HEADER)
+            .map(|v| v.to_str().unwrap_or_default().to_string());
+        let is_meta = req.path().starts_with(&self.meta_prefix);
+
+        Box::pin(async move {
+            if let Some(panel_version) = panel_version
+                && !is_meta
+                && !version::is_panel_compatible(&panel_version)
+            {
+                let min = version::current().min_panel_version;
+                debug!(
+                    "Rejecting request from incompatible panel {} (min {})",
+                    panel_version, min
+                );
+                return Ok(req.into_response(
+                    error_response(
+                        ErrorCode::PanelVersionIncompatible,
+                        &format!(
+                            "Admin panel {} is incompatible with server {} (requires >= {}); reload the panel",
+                            panel_version,
+                            env!("CARGO_PKG_VERSION"),
+                            min
+                        ),
+                    )
+                    .map_into_right_body(),
+                ));
+            }
+
+            trace!("Panel version check passed");
+            let response = srv.call(req).await?.map_into_left_body();
+            Ok(response)
+        })
+    }
+}
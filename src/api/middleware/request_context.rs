@@ -0,0 +1,149 @@
+//! 请求上下文中间件
+//!
+//! 为 Admin API 的每个请求确定 request_id（优先沿用 `X-Request-Id` 请求头，
+//! 否则生成 UUID），在请求处理期间通过 task-local 暴露给错误响应构建函数，
+//! 并回写到响应头，便于把错误响应与服务端日志关联。
+
+use actix_service::{Service, Transform};
+use actix_web::{
+    Error,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+
+use crate::storage::backend::statement_log::with_operation;
+
+/// 请求 ID 头名称
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 外部传入 request_id 的最大长度，超出则重新生成
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 获取当前请求的 request_id
+///
+/// 在中间件作用域之外调用（如单元测试）时生成一个新的 ID。
+pub fn current_request_id() -> String {
+    REQUEST_ID
+        .try_with(|id| id.clone())
+        .unwrap_or_else(|_| generate_request_id())
+}
+
+fn generate_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+fn request_id_from(req: &ServiceRequest) -> String {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| is_valid_request_id(v))
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id)
+}
+
+/// 请求上下文中间件
+#[derive(Clone)]
+pub struct RequestContext;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestContext
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestContextMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestContextMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestContextMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestContextMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let request_id = request_id_from(&req);
+        let operation = format!("{} {}", req.method(), req.path());
+
+        Box::pin(REQUEST_ID.scope(request_id.clone(), async move {
+            // 下游中间件（认证、CSRF、限流）在 call() 中同步构建的错误响应
+            // 也需要读取 request_id，因此 call 必须发生在 scope 内部
+            let mut response = with_operation(operation, srv.call(req)).await?;
+            if !response.headers().contains_key(REQUEST_ID_HEADER)
+                && let Ok(value) = HeaderValue::from_str(&request_id)
+            {
+                response
+                    .headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Ok(response)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_validation() {
+        assert!(is_valid_request_id("3f2b6c1e-9a4d-4f7e-8c21-0d5e6f7a8b9c"));
+        assert!(is_valid_request_id("req_123.abc"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("has space"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_current_request_id_inside_scope() {
+        let id = REQUEST_ID
+            .scope("fixed-id".to_string(), async { current_request_id() })
+            .await;
+        assert_eq!(id, "fixed-id");
+    }
+
+    #[test]
+    fn test_current_request_id_outside_scope_generates() {
+        let a = current_request_id();
+        let b = current_request_id();
+        assert!(!a.is_empty());
+        assert_ne!(a, b);
+    }
+}
@@ -4,3 +4,4 @@ pub mod middleware;
 #[cfg(all(debug_assertions, feature = "openapi"))]
 pub mod openapi;
 pub mod services;
+pub mod version;
@@ -22,9 +22,20 @@ use utoipa::OpenApi;
         crate::api::services::admin::link_crud::update_link,
         crate::api::services::admin::link_crud::delete_link,
         crate::api::services::admin::link_crud::get_stats,
+        crate::api::services::admin::cache::get_cache_stats,
+        crate::api::services::admin::geoip::get_geoip_status,
+        crate::api::services::admin::security::list_auto_bans,
+        crate::api::services::admin::security::delete_auto_ban,
+        crate::api::services::admin::tasks::get_tasks_health,
         crate::api::services::admin::batch_ops::batch_create_links,
         crate::api::services::admin::batch_ops::batch_update_links,
         crate::api::services::admin::batch_ops::batch_delete_links,
+        crate::api::services::admin::batch_ops::batch_extend_links,
+        crate::api::services::admin::batch_ops::generate_links,
+        crate::api::services::admin::archive::archive_links,
+        crate::api::services::admin::archive::get_archived_links,
+        crate::api::services::admin::archive::unarchive_link,
+        crate::api::services::admin::sample::sample_links,
         crate::api::services::admin::export_import::export_links,
         crate::api::services::admin::export_import::import_links,
         crate::api::services::admin::analytics::get_trends,
@@ -33,8 +44,15 @@ use utoipa::OpenApi;
         crate::api::services::admin::analytics::get_geo_stats,
         crate::api::services::admin::analytics::get_link_analytics,
         crate::api::services::admin::analytics::get_link_device_stats,
+        crate::api::services::admin::analytics::get_link_param_stats,
         crate::api::services::admin::analytics::get_device_stats,
+        crate::api::services::admin::analytics::get_redirect_timings,
         crate::api::services::admin::analytics::export_report,
+        crate::api::services::admin::api_tokens::list_api_tokens,
+        crate::api::services::admin::api_tokens::create_api_token,
+        crate::api::services::admin::api_tokens::update_api_token,
+        crate::api::services::admin::api_tokens::delete_api_token,
+        crate::api::services::admin::api_tokens::get_api_token_usage,
         crate::api::services::admin::config_ops::get_all_configs,
         crate::api::services::admin::config_ops::get_config,
         crate::api::services::admin::config_ops::update_config,
@@ -43,13 +61,22 @@ use utoipa::OpenApi;
         crate::api::services::admin::config_ops::get_config_schema,
         crate::api::services::admin::config_ops::execute_config_action,
         crate::api::services::admin::config_ops::execute_and_save_config_action,
+        crate::api::services::admin::meta::get_error_catalog,
+        crate::api::services::admin::meta::get_version,
+        crate::api::services::admin::meta::get_base_url,
     ),
     components(
         schemas(
             crate::api::services::admin::error_code::ErrorCode,
+            crate::api::services::admin::types::ErrorBody,
+            crate::api::services::admin::types::ErrorEnvelope,
+            crate::api::services::admin::types::ErrorCatalogEntry,
+            crate::api::services::admin::types::VersionInfo,
+            crate::api::services::admin::types::BaseUrlInfo,
             crate::api::services::admin::types::LoginCredentials,
             crate::api::services::admin::types::PostNewLink,
             crate::api::services::admin::types::GetLinksQuery,
+            crate::api::services::admin::types::GetArchivedQuery,
             crate::api::services::admin::types::PaginationInfo,
             crate::api::services::admin::types::BatchCreateRequest,
             crate::api::services::admin::types::BatchUpdateRequest,
@@ -57,8 +84,31 @@ use utoipa::OpenApi;
             crate::api::services::admin::types::BatchDeleteRequest,
             crate::api::services::admin::types::BatchResponse,
             crate::api::services::admin::types::BatchFailedItem,
+            crate::api::services::admin::types::BatchExtendFilter,
+            crate::api::services::admin::types::BatchExtendRequest,
+            crate::api::services::admin::types::BatchExtendItem,
+            crate::api::services::admin::types::BatchExtendResponse,
+            crate::api::services::admin::types::GenerateLinksRequest,
+            crate::api::services::admin::types::GenerateLinksResponse,
+            crate::services::TemplateVar,
+            crate::services::TemplateLink,
+            crate::api::services::admin::types::ArchiveLinksRequest,
+            crate::api::services::admin::types::ArchiveLinksResponse,
+            crate::api::services::admin::types::ArchivedLinkResponse,
+            crate::api::services::admin::types::SampleLinksQuery,
+            crate::api::services::admin::types::SampleLinksResponse,
             crate::api::services::admin::types::LinkResponse,
             crate::api::services::admin::types::StatsResponse,
+            crate::api::services::admin::types::PrivacyOptOutResponse,
+            crate::api::services::admin::types::CreationTrendResponse,
+            crate::api::services::admin::types::CacheStatsResponse,
+            crate::api::services::admin::types::AutoBanListResponse,
+            crate::api::services::admin::types::AutoBanResponse,
+            crate::api::services::admin::types::BloomStatsResponse,
+            crate::api::services::admin::types::GeoIpStatusResponse,
+            crate::api::services::admin::types::GeoIpApiStatusResponse,
+            crate::api::services::admin::types::TaskHealthListResponse,
+            crate::api::services::admin::types::TaskHealthResponse,
             crate::api::services::admin::types::MessageResponse,
             crate::api::services::admin::types::AuthSuccessResponse,
             crate::api::services::admin::types::ReloadResponse,
@@ -69,7 +119,14 @@ use utoipa::OpenApi;
             crate::api::services::admin::types::HealthResponse,
             crate::api::services::admin::types::ExportQuery,
             crate::api::services::admin::types::ImportFailedItem,
+            crate::api::services::admin::types::ImportWarningItem,
+            crate::api::services::admin::types::ImportPreviewItem,
             crate::api::services::admin::types::ImportResponse,
+            crate::api::services::admin::types::ApiTokenResponse,
+            crate::api::services::admin::types::CreateApiTokenRequest,
+            crate::api::services::admin::types::CreatedApiTokenResponse,
+            crate::api::services::admin::types::UpdateApiTokenRequest,
+            crate::api::services::admin::types::ApiTokenUsageResponse,
             crate::api::services::admin::analytics::AnalyticsQuery,
             crate::api::services::admin::analytics::GroupBy,
             crate::api::services::admin::analytics::TrendData,
@@ -79,6 +136,12 @@ use utoipa::OpenApi;
             crate::api::services::admin::analytics::LinkAnalytics,
             crate::api::services::admin::analytics::DeviceAnalyticsResponse,
             crate::api::services::admin::analytics::CategoryStatsResponse,
+            crate::api::services::admin::analytics::ParamQuery,
+            crate::api::services::admin::analytics::ParamStatsResponse,
+            crate::api::services::admin::analytics::TimingQuery,
+            crate::api::services::admin::analytics::TimingGroupBy,
+            crate::api::services::admin::analytics::TimingSeries,
+            crate::api::services::admin::analytics::TimingTrends,
             crate::api::services::admin::config_ops::ConfigItemResponse,
             crate::api::services::admin::config_ops::ConfigUpdateRequest,
             crate::api::services::admin::config_ops::ConfigUpdateResponse,
@@ -100,8 +163,14 @@ use utoipa::OpenApi;
         (name = "links", description = "Short link management"),
         (name = "analytics", description = "Click analytics"),
         (name = "auth", description = "Administrator authentication"),
+        (name = "tokens", description = "Team API tokens and quotas"),
         (name = "config", description = "Runtime configuration"),
+        (name = "cache", description = "Cache statistics"),
+        (name = "geoip", description = "GeoIP provider status"),
+        (name = "security", description = "404-flood auto-ban list"),
+        (name = "tasks", description = "Background task health"),
         (name = "health", description = "Service health"),
+        (name = "meta", description = "API metadata"),
     ),
 )]
 pub struct ApiDoc;
@@ -6,6 +6,8 @@
 //! - 来源统计
 //! - 地理位置分布
 //! - 单链接详细统计
+//! - 单链接按自定义 query 参数过滤与分组
+//! - Redirect 各阶段耗时分位数趋势（容量规划）
 //! - 导出报告
 
 use actix_web::{HttpRequest, HttpResponse, Responder, Result as ActixResult, web};
@@ -13,12 +15,14 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::info;
 
+use crate::errors::ShortlinkerError;
 use crate::services::{
     AnalyticsService, CategoryStats as ServiceCategoryStats,
     DeviceAnalytics as ServiceDeviceAnalytics, GeoStats as ServiceGeoStats,
     GroupBy as ServiceGroupBy, LinkAnalytics as ServiceLinkAnalytics,
-    ReferrerStats as ServiceReferrerStats, TopLink as ServiceTopLink,
-    TrendData as ServiceTrendData,
+    ParamStats as ServiceParamStats, ReferrerStats as ServiceReferrerStats,
+    TimingGroupBy as ServiceTimingGroupBy, TimingSeries as ServiceTimingSeries,
+    TimingTrends as ServiceTimingTrends, TopLink as ServiceTopLink, TrendData as ServiceTrendData,
 };
 
 use super::export_import::create_csv_stream;
@@ -67,6 +71,65 @@ impl From<GroupBy> for ServiceGroupBy {
     }
 }
 
+/// 自定义 query 参数统计查询参数
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(
+    all(debug_assertions, feature = "openapi"),
+    derive(utoipa::IntoParams, utoipa::ToSchema)
+)]
+pub struct ParamQuery {
+    /// 分组的参数名（需在 `analytics.captured_query_params` 白名单内才会有数据）
+    pub param: String,
+    /// 过滤条件 `参数名:值`，如 `utm_source:newsletter`
+    pub filter: Option<String>,
+    /// 开始日期 (ISO 8601)
+    pub start_date: Option<String>,
+    /// 结束日期 (ISO 8601)
+    pub end_date: Option<String>,
+    /// 返回的取值数量，默认 20，最大 100
+    pub limit: Option<u32>,
+}
+
+/// Redirect 耗时查询参数
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(
+    all(debug_assertions, feature = "openapi"),
+    derive(utoipa::IntoParams, utoipa::ToSchema)
+)]
+pub struct TimingQuery {
+    /// 开始日期 (ISO 8601)，与 end_date 同时省略时取最近 24 小时
+    pub start_date: Option<String>,
+    /// 结束日期 (ISO 8601)
+    pub end_date: Option<String>,
+    /// 分位数 (0, 100]，默认 99
+    pub percentile: Option<f64>,
+    /// 分组方式，默认 phase
+    pub group_by: Option<TimingGroupBy>,
+    /// 时间桶粒度，默认 hour
+    pub interval: Option<GroupBy>,
+}
+
+/// Redirect 耗时分组方式
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TimingGroupBy {
+    /// 每个阶段（total/cache/bloom/db/geo/enqueue）一条曲线
+    #[default]
+    Phase,
+    /// 按响应状态码分组的总耗时
+    Status,
+}
+
+impl From<TimingGroupBy> for ServiceTimingGroupBy {
+    fn from(g: TimingGroupBy) -> Self {
+        match g {
+            TimingGroupBy::Phase => ServiceTimingGroupBy::Phase,
+            TimingGroupBy::Status => ServiceTimingGroupBy::Status,
+        }
+    }
+}
+
 // ============ 响应结构 ============
 
 /// 点击趋势数据
@@ -209,6 +272,86 @@ impl From<ServiceCategoryStats> for CategoryStatsResponse {
     }
 }
 
+/// 自定义 query 参数取值分布
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ParamStatsResponse {
+    /// 分组的参数名
+    pub param: String,
+    /// 过滤条件（参数名）
+    pub filter_param: Option<String>,
+    /// 过滤条件（值）
+    pub filter_value: Option<String>,
+    /// 各取值的点击数，按点击数降序
+    pub values: Vec<CategoryStatsResponse>,
+    /// 参与统计的点击数
+    pub total: u64,
+    /// 明细过多，只统计了最新的部分
+    pub truncated: bool,
+}
+
+impl From<ServiceParamStats> for ParamStatsResponse {
+    fn from(p: ServiceParamStats) -> Self {
+        let (filter_param, filter_value) = p.filter.unzip();
+        ParamStatsResponse {
+            param: p.param,
+            filter_param,
+            filter_value,
+            values: p.values.into_iter().map(Into::into).collect(),
+            total: p.total,
+            truncated: p.truncated,
+        }
+    }
+}
+
+/// 耗时曲线（微秒）
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TimingSeries {
+    /// 阶段名或状态码
+    pub name: String,
+    /// 各时间桶的分位数，无样本时为 null
+    pub values: Vec<Option<u32>>,
+    /// 各时间桶的样本数
+    pub samples: Vec<u64>,
+}
+
+impl From<ServiceTimingSeries> for TimingSeries {
+    fn from(t: ServiceTimingSeries) -> Self {
+        TimingSeries {
+            name: t.name,
+            values: t.values,
+            samples: t.samples,
+        }
+    }
+}
+
+/// Redirect 耗时分位数趋势
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TimingTrends {
+    pub percentile: f64,
+    /// 时间标签
+    pub labels: Vec<String>,
+    pub series: Vec<TimingSeries>,
+    /// 参与统计的样本数
+    pub sample_count: u64,
+    /// 样本过多，只统计了最新的部分
+    pub truncated: bool,
+}
+
+impl From<ServiceTimingTrends> for TimingTrends {
+    fn from(t: ServiceTimingTrends) -> Self {
+        TimingTrends {
+            percentile: t.percentile,
+            labels: t.labels,
+            series: t.series.into_iter().map(Into::into).collect(),
+            sample_count: t.sample_count,
+            truncated: t.truncated,
+        }
+    }
+}
+
 // ============ API 端点 ============
 
 /// GET /admin/v1/analytics/trends - 获取点击趋势
@@ -400,6 +543,66 @@ pub async fn get_link_device_stats(
     }
 }
 
+/// GET /admin/v1/links/{code}/analytics/params - 按自定义 query 参数分组统计
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/links/{code}/analytics/params",
+        tag = "analytics",
+        operation_id = "get_link_param_stats",
+        params(
+            ("code" = String, Path, description = "Short code"),
+            ParamQuery,
+        ),
+        responses(
+            (status = 200, description = "Click distribution over a captured query parameter", body = super::types::ApiResponse<ParamStatsResponse>),
+            (status = 400, description = "Invalid parameter name, filter or date range"),
+        )
+)]
+pub async fn get_link_param_stats(
+    _req: HttpRequest,
+    code: web::Path<String>,
+    query: web::Query<ParamQuery>,
+    service: web::Data<Arc<AnalyticsService>>,
+) -> ActixResult<impl Responder> {
+    let code = code.into_inner();
+    info!(
+        "Admin API: get_link_param_stats for '{}' with query: {:?}",
+        code, query
+    );
+
+    let filter = match query.filter.as_deref().map(|raw| raw.split_once(':')) {
+        None => None,
+        Some(Some(filter)) => Some(filter),
+        Some(None) => {
+            return Ok(error_from_shortlinker(&ShortlinkerError::validation(
+                "filter must be in the form 'name:value'",
+            )));
+        }
+    };
+    let (start, end) = match AnalyticsService::parse_date_range_strict(
+        query.start_date.as_deref(),
+        query.end_date.as_deref(),
+    ) {
+        Ok(range) => range,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    match service
+        .get_link_param_stats(
+            &code,
+            &query.param,
+            filter,
+            start,
+            end,
+            query.limit.unwrap_or(20),
+        )
+        .await
+    {
+        Ok(stats) => Ok(success_response(ParamStatsResponse::from(stats))),
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
 /// GET /admin/v1/analytics/devices - 获取设备分析
 #[aster_forge_api_docs_macros::path(
         get,
@@ -426,6 +629,53 @@ pub async fn get_device_stats(
     }
 }
 
+/// GET /admin/v1/analytics/timings - 获取 redirect 各阶段耗时的分位数趋势
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/analytics/timings",
+        tag = "analytics",
+        operation_id = "get_redirect_timings",
+        params(TimingQuery),
+        responses(
+            (status = 200, description = "Redirect timing percentiles", body = super::types::ApiResponse<TimingTrends>),
+            (status = 400, description = "Invalid percentile or date range"),
+        )
+)]
+pub async fn get_redirect_timings(
+    _req: HttpRequest,
+    query: web::Query<TimingQuery>,
+    service: web::Data<Arc<AnalyticsService>>,
+) -> ActixResult<impl Responder> {
+    info!("Admin API: get_redirect_timings with query: {:?}", query);
+
+    // 样本默认只保留 7 天，默认范围取最近 24 小时
+    let range = match (query.start_date.as_deref(), query.end_date.as_deref()) {
+        (None, None) => {
+            let end = chrono::Utc::now();
+            Ok((end - chrono::Duration::hours(24), end))
+        }
+        (start, end) => AnalyticsService::parse_date_range_strict(start, end),
+    };
+    let (start, end) = match range {
+        Ok(range) => range,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    match service
+        .get_redirect_timings(
+            start,
+            end,
+            query.percentile.unwrap_or(99.0),
+            query.group_by.unwrap_or_default().into(),
+            query.interval.unwrap_or(GroupBy::Hour).into(),
+        )
+        .await
+    {
+        Ok(trends) => Ok(success_response(TimingTrends::from(trends))),
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
 /// 每批次导出的日志数量
 const EXPORT_BATCH_SIZE: u64 = 10000;
 
@@ -496,6 +746,8 @@ pub fn analytics_routes() -> actix_web::Scope {
         .route("/geo", web::head().to(get_geo_stats))
         .route("/devices", web::get().to(get_device_stats))
         .route("/devices", web::head().to(get_device_stats))
+        .route("/timings", web::get().to(get_redirect_timings))
+        .route("/timings", web::head().to(get_redirect_timings))
         .route("/export", web::get().to(export_report))
         .route("/export", web::head().to(export_report))
 }
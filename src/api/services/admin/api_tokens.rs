@@ -0,0 +1,245 @@
+//! Admin API 团队 Token 管理与配额检查
+//!
+//! Token 管理端点仅主管理员凭据可用；团队 token 只能查询自身用量
+//! （由 `AdminAuth` 中间件限制）。链接写入 handler 通过 [`QuotaScope`]
+//! 在写入前检查、写入后登记配额，并把统计级别收紧到 token 的上限。
+
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Responder, Result as ActixResult, web};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::api::middleware::ApiTokenIdentity;
+use crate::errors::ShortlinkerError;
+use crate::services::{ApiTokenQuotas, ApiTokenService};
+use crate::storage::AnalyticsLevel;
+
+use super::error_code::ErrorCode;
+use super::helpers::{
+    error_from_shortlinker, error_response_with_details, parse_analytics_level, success_response,
+};
+use super::types::{
+    ApiResponse, ApiTokenResponse, ApiTokenUsageResponse, CreateApiTokenRequest,
+    CreatedApiTokenResponse, MessageResponse, UpdateApiTokenRequest,
+};
+
+/// 当前请求的配额上下文；主管理员凭据返回 `None`，不受限
+pub(super) struct QuotaScope {
+    service: Arc<ApiTokenService>,
+    token_id: String,
+}
+
+impl QuotaScope {
+    pub(super) fn from_request(req: &HttpRequest) -> Option<Self> {
+        let token_id = req.extensions().get::<ApiTokenIdentity>()?.0.clone();
+        let service = req
+            .app_data::<web::Data<Arc<ApiTokenService>>>()?
+            .get_ref()
+            .clone();
+        Some(Self { service, token_id })
+    }
+
+    /// 写入前检查；超额时返回 429，`details` 附带当前用量
+    pub(super) async fn check(&self, requested: usize) -> Result<(), HttpResponse> {
+        let err = match self
+            .service
+            .check_quota(&self.token_id, requested as u64)
+            .await
+        {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if !matches!(err, ShortlinkerError::QuotaExceeded(_)) {
+            return Err(error_from_shortlinker(&err));
+        }
+
+        info!("Admin API: {}", err.message());
+        let details = self
+            .service
+            .usage(&self.token_id)
+            .await
+            .ok()
+            .and_then(|usage| serde_json::to_value(ApiTokenUsageResponse::from(usage)).ok());
+        Err(error_response_with_details(
+            ErrorCode::QuotaExceeded,
+            err.message(),
+            details,
+        ))
+    }
+
+    /// 写入成功后登记
+    pub(super) async fn record(&self, codes: &[String]) {
+        self.service.record_created(&self.token_id, codes).await;
+    }
+}
+
+/// 当前请求的统计级别上限；主管理员凭据不受限
+pub(super) async fn analytics_cap(
+    quota: Option<&QuotaScope>,
+) -> Result<Option<AnalyticsLevel>, HttpResponse> {
+    let Some(quota) = quota else {
+        return Ok(None);
+    };
+    quota
+        .service
+        .analytics_cap(&quota.token_id)
+        .await
+        .map_err(|e| error_from_shortlinker(&e))
+}
+
+/// 列出团队 API Token
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/tokens",
+        tag = "tokens",
+        operation_id = "list_api_tokens",
+        responses(
+            (status = 200, description = "API tokens", body = ApiResponse<Vec<ApiTokenResponse>>),
+        )
+)]
+pub async fn list_api_tokens(
+    service: web::Data<Arc<ApiTokenService>>,
+) -> ActixResult<impl Responder> {
+    match service.list().await {
+        Ok(tokens) => Ok(success_response(
+            tokens
+                .into_iter()
+                .map(ApiTokenResponse::from)
+                .collect::<Vec<_>>(),
+        )),
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
+/// 创建团队 API Token（明文仅在响应中返回一次）
+#[aster_forge_api_docs_macros::path(
+        post,
+        path = "/admin/v1/tokens",
+        tag = "tokens",
+        operation_id = "create_api_token",
+        request_body = CreateApiTokenRequest,
+        responses(
+            (status = 201, description = "API token created", body = ApiResponse<CreatedApiTokenResponse>),
+            (status = 400, description = "Invalid token name or analytics level"),
+        )
+)]
+pub async fn create_api_token(
+    body: web::Json<CreateApiTokenRequest>,
+    service: web::Data<Arc<ApiTokenService>>,
+) -> ActixResult<impl Responder> {
+    let body = body.into_inner();
+    let max_analytics_level = match parse_analytics_level(body.max_analytics_level.as_deref()) {
+        Ok(level) => level,
+        Err(resp) => return Ok(resp),
+    };
+    let quotas = ApiTokenQuotas {
+        max_links: body.max_links,
+        max_daily_creates: body.max_daily_creates,
+        max_analytics_level,
+    };
+
+    match service.create(&body.name, quotas).await {
+        Ok(created) => {
+            info!(
+                "Admin API: API token '{}' created ({})",
+                created.token.id, created.token.name
+            );
+            Ok(HttpResponse::Created()
+                .append_header(("Content-Type", "application/json; charset=utf-8"))
+                .json(ApiResponse {
+                    code: ErrorCode::Success as i32,
+                    message: "API token created".to_string(),
+                    data: Some(CreatedApiTokenResponse {
+                        info: ApiTokenResponse::from(created.token),
+                        token: created.plaintext,
+                    }),
+                }))
+        }
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
+/// 更新团队 API Token 配额
+#[aster_forge_api_docs_macros::path(
+        put,
+        path = "/admin/v1/tokens/{id}",
+        tag = "tokens",
+        operation_id = "update_api_token",
+        params(("id" = String, Path, description = "API token id")),
+        request_body = UpdateApiTokenRequest,
+        responses(
+            (status = 200, description = "Updated API token", body = ApiResponse<ApiTokenResponse>),
+            (status = 400, description = "Invalid analytics level"),
+            (status = 404, description = "API token not found"),
+        )
+)]
+pub async fn update_api_token(
+    id: web::Path<String>,
+    body: web::Json<UpdateApiTokenRequest>,
+    service: web::Data<Arc<ApiTokenService>>,
+) -> ActixResult<impl Responder> {
+    let max_analytics_level = match parse_analytics_level(body.max_analytics_level.as_deref()) {
+        Ok(level) => level,
+        Err(resp) => return Ok(resp),
+    };
+    let quotas = ApiTokenQuotas {
+        max_links: body.max_links,
+        max_daily_creates: body.max_daily_creates,
+        max_analytics_level,
+    };
+    match service.update_quotas(&id, quotas).await {
+        Ok(token) => {
+            info!("Admin API: API token '{}' quotas updated", token.id);
+            Ok(success_response(ApiTokenResponse::from(token)))
+        }
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
+/// 删除团队 API Token（名下链接保留）
+#[aster_forge_api_docs_macros::path(
+        delete,
+        path = "/admin/v1/tokens/{id}",
+        tag = "tokens",
+        operation_id = "delete_api_token",
+        params(("id" = String, Path, description = "API token id")),
+        responses(
+            (status = 200, description = "API token deleted", body = ApiResponse<MessageResponse>),
+            (status = 404, description = "API token not found"),
+        )
+)]
+pub async fn delete_api_token(
+    id: web::Path<String>,
+    service: web::Data<Arc<ApiTokenService>>,
+) -> ActixResult<impl Responder> {
+    match service.delete(&id).await {
+        Ok(()) => {
+            info!("Admin API: API token '{}' deleted", id);
+            Ok(success_response(MessageResponse {
+                message: format!("API token '{}' deleted", id),
+            }))
+        }
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
+/// 查询团队 API Token 当前用量
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/tokens/{id}/usage",
+        tag = "tokens",
+        operation_id = "get_api_token_usage",
+        params(("id" = String, Path, description = "API token id")),
+        responses(
+            (status = 200, description = "Current usage", body = ApiResponse<ApiTokenUsageResponse>),
+            (status = 404, description = "API token not found"),
+        )
+)]
+pub async fn get_api_token_usage(
+    id: web::Path<String>,
+    service: web::Data<Arc<ApiTokenService>>,
+) -> ActixResult<impl Responder> {
+    match service.usage(&id).await {
+        Ok(usage) => Ok(success_response(ApiTokenUsageResponse::from(usage))),
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
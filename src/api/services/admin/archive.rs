@@ -0,0 +1,172 @@
+//! Admin API 链接归档
+//!
+//! 归档把链接从主表移入 `short_link_archive`，跳转返回 410 Gone；
+//! 点击统计保留，analytics 接口仍可按原短码查询。
+
+use actix_web::{HttpRequest, HttpResponse, Responder, Result as ActixResult, web};
+use std::sync::Arc;
+use tracing::{info, trace};
+
+use crate::services::{LinkSelection, LinkService};
+
+use super::batch_ops::{MAX_BATCH_SIZE, build_extend_filter};
+use super::error_code::ErrorCode;
+use super::helpers::{error_from_shortlinker, error_response, success_response};
+use super::types::{
+    ArchiveLinksRequest, ArchiveLinksResponse, ArchivedLinkResponse, BatchFailedItem,
+    GetArchivedQuery, LinkResponse, PaginatedResponse, PaginationInfo,
+};
+
+/// 归档链接
+#[aster_forge_api_docs_macros::path(
+        post,
+        path = "/admin/v1/links/archive",
+        tag = "links",
+        operation_id = "archive_links",
+        request_body = ArchiveLinksRequest,
+        responses(
+            (status = 200, description = "Archive result", body = super::types::ApiResponse<ArchiveLinksResponse>),
+            (status = 400, description = "Batch too large or invalid"),
+        )
+)]
+pub async fn archive_links(
+    _req: HttpRequest,
+    body: web::Json<ArchiveLinksRequest>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    let body = body.into_inner();
+
+    // 选择方式：codes 与 filter 二选一
+    let selection = match (body.codes, body.filter) {
+        (Some(codes), None) => {
+            if codes.len() > MAX_BATCH_SIZE {
+                return Ok(error_response(
+                    ErrorCode::BatchSizeTooLarge,
+                    &format!(
+                        "Batch size {} exceeds maximum {}",
+                        codes.len(),
+                        MAX_BATCH_SIZE
+                    ),
+                ));
+            }
+            LinkSelection::Codes(codes)
+        }
+        (None, Some(filter)) => match build_extend_filter(&filter) {
+            Ok(filter) => LinkSelection::Filter(filter),
+            Err(msg) => return Ok(error_response(ErrorCode::InvalidDateFormat, &msg)),
+        },
+        _ => {
+            return Ok(error_response(
+                ErrorCode::BadRequest,
+                "Exactly one of codes or filter must be provided",
+            ));
+        }
+    };
+
+    match &selection {
+        LinkSelection::Codes(codes) => {
+            info!("Admin API: archive request for {} codes", codes.len())
+        }
+        LinkSelection::Filter(filter) => {
+            info!("Admin API: archive request by filter {:?}", filter)
+        }
+    }
+
+    let result = match service.archive_links(selection).await {
+        Ok(r) => r,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    Ok(success_response(ArchiveLinksResponse {
+        archived: result.archived,
+        not_found: result.not_found,
+        failed: result
+            .failed
+            .into_iter()
+            .map(|f| BatchFailedItem {
+                code: f.code,
+                error: f.reason,
+                error_code: Some(ErrorCode::LinkDatabaseError as i32),
+            })
+            .collect(),
+    }))
+}
+
+/// 分页查询归档链接
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/links/archived",
+        tag = "links",
+        operation_id = "list_archived_links",
+        params(GetArchivedQuery),
+        responses(
+            (status = 200, description = "Paginated archived links", body = PaginatedResponse<Vec<ArchivedLinkResponse>>),
+        )
+)]
+pub async fn get_archived_links(
+    _req: HttpRequest,
+    query: web::Query<GetArchivedQuery>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    trace!("Admin API: request to list archived links: {:?}", query);
+
+    let page = query.page.unwrap_or(1).max(1) as u64;
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100) as u64;
+
+    match service
+        .list_archived(query.search.clone(), page, page_size)
+        .await
+    {
+        Ok((links, total)) => {
+            let total = total as usize;
+            let page_size = page_size as usize;
+
+            Ok(HttpResponse::Ok()
+                .append_header(("Content-Type", "application/json; charset=utf-8"))
+                .json(PaginatedResponse {
+                    code: ErrorCode::Success as i32,
+                    message: "OK".to_string(),
+                    data: Some(
+                        links
+                            .into_iter()
+                            .map(ArchivedLinkResponse::from)
+                            .collect::<Vec<_>>(),
+                    ),
+                    pagination: PaginationInfo {
+                        page: page as usize,
+                        page_size,
+                        total,
+                        total_pages: total.div_ceil(page_size),
+                    },
+                }))
+        }
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
+
+/// 从归档恢复链接
+#[aster_forge_api_docs_macros::path(
+        post,
+        path = "/admin/v1/links/{code}/unarchive",
+        tag = "links",
+        operation_id = "unarchive_link",
+        params(("code" = String, Path, description = "Short code")),
+        responses(
+            (status = 200, description = "Restored short link", body = super::types::ApiResponse<LinkResponse>),
+            (status = 404, description = "Archived link not found"),
+            (status = 409, description = "Short code is used by an active link"),
+        )
+)]
+pub async fn unarchive_link(
+    _req: HttpRequest,
+    code: web::Path<String>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    let code = code.into_inner();
+    info!("Admin API: unarchive request for '{}'", code);
+
+    match service.unarchive_link(&code).await {
+        Ok(link) => Ok(success_response(LinkResponse::from(link))),
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
@@ -5,15 +5,20 @@ use actix_web::{HttpRequest, HttpResponse, Responder, Result as ActixResult, web
 use base64::Engine;
 use governor::middleware::NoOpMiddleware;
 use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use crate::api::constants;
 use crate::api::jwt::get_jwt_service;
 use crate::config::{get_runtime_config, keys};
+use crate::metrics::MetricsRecorder;
+use crate::utils::admin_token::{self, AdminTokenMatch, CredentialStatus, PreviousAdminToken};
+use crate::utils::ip::{client_ip, trusted_proxies};
 
 use crate::errors::ShortlinkerError;
 
 use super::error_code::ErrorCode;
-use super::helpers::{CookieBuilder, error_from_shortlinker, success_response};
+use super::helpers::{CookieBuilder, error_envelope, error_from_shortlinker, success_response};
 use super::types::{ApiResponse, AuthSuccessResponse, LoginCredentials, MessageResponse};
 
 /// 生成 CSRF Token（32 bytes = 256 bits，Base64 编码）
@@ -22,13 +27,18 @@ fn generate_csrf_token() -> String {
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
 }
 
-fn trusted_proxies() -> Vec<String> {
-    let mut trusted = get_runtime_config().get_json_or(keys::API_TRUSTED_PROXIES, Vec::new());
-    #[cfg(unix)]
-    if crate::config::get_config().server.unix_socket.is_some() {
-        trusted.extend(["127.0.0.0/8".to_string(), "::1/128".to_string()]);
-    }
-    trusted
+/// 限流拒绝响应，与其他 Admin API 错误使用相同的错误信封
+fn rate_limit_rejection<T: std::fmt::Display + serde::Serialize>(
+    retry_after: T,
+    mut response: actix_web::HttpResponseBuilder,
+) -> HttpResponse {
+    let error_code = ErrorCode::RateLimitExceeded;
+    response.status(error_code.http_status());
+    response.json(error_envelope(
+        error_code,
+        &format!("Too many requests, retry in {retry_after}s"),
+        Some(serde_json::json!({ "retry_after": retry_after })),
+    ))
 }
 
 /// 创建登录限流器
@@ -42,14 +52,7 @@ pub fn login_rate_limiter()
             NonZeroU64::new(1).expect("login interval is non-zero"),
             NonZeroU32::new(5).expect("login burst is non-zero"),
             &trusted_proxies(),
-            |retry_after, mut response| {
-                response.status(actix_web::http::StatusCode::TOO_MANY_REQUESTS);
-                response.json(ApiResponse::<()> {
-                    code: ErrorCode::RateLimitExceeded as i32,
-                    message: format!("Too many requests, retry in {retry_after}s"),
-                    data: None,
-                })
-            },
+            rate_limit_rejection,
         );
 
     debug!("Login rate limiter created: 1 req/s, burst 5");
@@ -68,14 +71,7 @@ pub fn refresh_rate_limiter()
             NonZeroU64::new(10).expect("refresh interval is non-zero"),
             NonZeroU32::new(10).expect("refresh burst is non-zero"),
             &trusted_proxies(),
-            |retry_after, mut response| {
-                response.status(actix_web::http::StatusCode::TOO_MANY_REQUESTS);
-                response.json(ApiResponse::<()> {
-                    code: ErrorCode::RateLimitExceeded as i32,
-                    message: format!("Too many requests, retry in {retry_after}s"),
-                    data: None,
-                })
-            },
+            rate_limit_rejection,
         );
 
     debug!("Refresh rate limiter created: 1 req/10s, burst 10");
@@ -99,55 +95,64 @@ pub async fn check_admin_token(
     req: HttpRequest,
     login_body: web::Json<LoginCredentials>,
 ) -> ActixResult<impl Responder> {
-    let peer = req.peer_addr().map(|address| address.ip());
-    #[cfg(unix)]
-    let peer = peer.or_else(|| {
-        crate::config::get_config()
-            .server
-            .unix_socket
-            .as_ref()
-            .map(|_| std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
-    });
-    let client_ip = peer
-        .map(|peer| {
-            aster_forge_actix_middleware::client_ip::real_ip_from_headers(
-                req.headers(),
-                peer,
-                &trusted_proxies(),
-            )
-            .to_string()
-        })
+    let client_ip = client_ip(req.peer_addr(), req.headers())
+        .map(|ip| ip.to_string())
         .unwrap_or_else(|| "unknown".to_string());
     let rt = get_runtime_config();
     let admin_token = rt.get_or(keys::API_ADMIN_TOKEN, "");
 
-    // 验证密码（启动时已自动迁移明文为哈希）
-    let password_valid =
-        match aster_forge_crypto::verify_password(&login_body.password, &admin_token) {
-            Ok(valid) => valid,
-            Err(e) => {
-                error!("Admin API: password verification error: {}", e);
-                return Ok(error_from_shortlinker(&ShortlinkerError::internal_error(
-                    "Authentication error",
-                )));
-            }
-        };
+    // 验证密码（启动时已自动迁移明文为哈希），轮换宽限期内同时接受上一个 token
+    let previous = PreviousAdminToken::load();
+    let token_match = match admin_token::verify_admin_token(
+        &login_body.password,
+        &admin_token,
+        previous.as_ref(),
+    ) {
+        Ok(token_match) => token_match,
+        Err(e) => {
+            error!("Admin API: password verification error: {}", e);
+            return Ok(error_from_shortlinker(&ShortlinkerError::internal_error(
+                "Authentication error",
+            )));
+        }
+    };
 
-    if !password_valid {
-        warn!(
-            "Admin API: login failed - invalid token (from {})",
-            client_ip
-        );
-        return Ok(error_from_shortlinker(
-            &ShortlinkerError::auth_password_invalid("Invalid admin token"),
-        ));
-    }
+    // JWT 记录所用 Token 的指纹，轮换 / 吊销后据此失效
+    let (cred, cred_exp) = match token_match {
+        AdminTokenMatch::Current => (admin_token::credential_fingerprint(&admin_token), None),
+        AdminTokenMatch::Previous(expires_at) => {
+            warn!(
+                "Admin API: login with previous admin token (from {}), accepted until {}",
+                client_ip,
+                expires_at.to_rfc3339()
+            );
+            if let Some(metrics) = req.app_data::<web::Data<Arc<dyn MetricsRecorder>>>() {
+                metrics.inc_auth_deprecated_token("login");
+            }
+            let previous_hash = previous
+                .as_ref()
+                .map_or("", |previous| previous.hash.as_str());
+            (
+                admin_token::credential_fingerprint(previous_hash),
+                Some(expires_at.timestamp()),
+            )
+        }
+        AdminTokenMatch::Invalid => {
+            warn!(
+                "Admin API: login failed - invalid token (from {})",
+                client_ip
+            );
+            return Ok(error_from_shortlinker(
+                &ShortlinkerError::auth_password_invalid("Invalid admin token"),
+            ));
+        }
+    };
 
     info!("Admin API: login successful (from {})", client_ip);
 
     // Generate JWT tokens using cached service
     let jwt_service = get_jwt_service();
-    let access_token = match jwt_service.generate_access_token() {
+    let access_token = match jwt_service.generate_access_token(&cred) {
         Ok(token) => token,
         Err(e) => {
             error!("Admin API: failed to generate access token: {}", e);
@@ -157,7 +162,7 @@ pub async fn check_admin_token(
         }
     };
 
-    let refresh_token = match jwt_service.generate_refresh_token() {
+    let refresh_token = match jwt_service.generate_refresh_token(&cred) {
         Ok(token) => token,
         Err(e) => {
             error!("Admin API: failed to generate refresh token: {}", e);
@@ -176,7 +181,15 @@ pub async fn check_admin_token(
     let csrf_token = generate_csrf_token();
     let csrf_cookie = cookie_builder.build_csrf_cookie(csrf_token);
 
-    Ok(HttpResponse::Ok()
+    let mut response = HttpResponse::Ok();
+    if let Some(expires_at) = cred_exp {
+        response.append_header((
+            constants::TOKEN_DEPRECATION_HEADER,
+            admin_token::deprecation_header_value(expires_at),
+        ));
+    }
+
+    Ok(response
         .cookie(access_cookie)
         .cookie(refresh_cookie)
         .cookie(csrf_cookie)
@@ -219,17 +232,33 @@ pub async fn refresh_token(req: HttpRequest) -> ActixResult<impl Responder> {
 
     // Validate refresh token using cached service
     let jwt_service = get_jwt_service();
-    if let Err(e) = jwt_service.validate_refresh_token(&refresh_token) {
-        info!("Admin API: invalid refresh token: {}", e);
-        return Ok(error_from_shortlinker(
-            &ShortlinkerError::auth_token_invalid("Invalid refresh token"),
-        ));
-    }
+    let claims = match jwt_service.validate_refresh_token(&refresh_token) {
+        Ok(claims) => claims,
+        Err(e) => {
+            info!("Admin API: invalid refresh token: {}", e);
+            return Ok(error_from_shortlinker(
+                &ShortlinkerError::auth_token_invalid("Invalid refresh token"),
+            ));
+        }
+    };
+
+    // 由旧管理员 token 换取的会话只能续期到宽限期结束，轮换或吊销后立即失效
+    let cred_exp = match admin_token::credential_status(claims.cred.as_deref()) {
+        CredentialStatus::Current => None,
+        CredentialStatus::Deprecated(expires_at) => Some(expires_at.timestamp()),
+        CredentialStatus::Revoked => {
+            info!("Admin API: refresh rejected - admin token rotated, revoked or expired");
+            return Ok(error_from_shortlinker(
+                &ShortlinkerError::auth_token_invalid("Admin token has been rotated"),
+            ));
+        }
+    };
+    let cred = claims.cred.unwrap_or_default();
 
     info!("Admin API: token refresh successful");
 
     // Generate new tokens (sliding expiration)
-    let new_access_token = match jwt_service.generate_access_token() {
+    let new_access_token = match jwt_service.generate_access_token(&cred) {
         Ok(token) => token,
         Err(e) => {
             error!("Admin API: failed to generate access token: {}", e);
@@ -239,7 +268,7 @@ pub async fn refresh_token(req: HttpRequest) -> ActixResult<impl Responder> {
         }
     };
 
-    let new_refresh_token = match jwt_service.generate_refresh_token() {
+    let new_refresh_token = match jwt_service.generate_refresh_token(&cred) {
         Ok(token) => token,
         Err(e) => {
             error!("Admin API: failed to generate refresh token: {}", e);
@@ -257,7 +286,15 @@ pub async fn refresh_token(req: HttpRequest) -> ActixResult<impl Responder> {
     let csrf_token = generate_csrf_token();
     let csrf_cookie = cookie_builder.build_csrf_cookie(csrf_token);
 
-    Ok(HttpResponse::Ok()
+    let mut response = HttpResponse::Ok();
+    if let Some(expires_at) = cred_exp {
+        response.append_header((
+            constants::TOKEN_DEPRECATION_HEADER,
+            admin_token::deprecation_header_value(expires_at),
+        ));
+    }
+
+    Ok(response
         .cookie(access_cookie)
         .cookie(refresh_cookie)
         .cookie(csrf_cookie)
@@ -6,16 +6,26 @@ use actix_web::{HttpRequest, Responder, Result as ActixResult, web};
 use std::sync::Arc;
 use tracing::info;
 
-use crate::services::{CreateLinkRequest, LinkService, UpdateLinkRequest};
+use crate::services::{
+    BatchExtendRequest as ServiceExtendRequest, CreateLinkRequest, ExtendAction,
+    GenerateLinksOptions, LinkSelection, LinkService, LinkTemplate, UpdateLinkRequest,
+};
+use crate::storage::{AnalyticsLevel, CreatedVia, LinkFilter};
 
+use super::api_tokens::{QuotaScope, analytics_cap};
 use super::error_code::ErrorCode;
-use super::helpers::{error_from_shortlinker, error_response, success_response};
+use super::helpers::{
+    error_from_shortlinker, error_response, parse_analytics_level, success_response, update_expiry,
+};
+use super::link_crud::OVERRIDE_COOLDOWN_FORBIDDEN;
 use super::types::{
-    BatchCreateRequest, BatchDeleteRequest, BatchFailedItem, BatchResponse, BatchUpdateRequest,
+    BatchCreateRequest, BatchDeleteRequest, BatchExtendFilter, BatchExtendItem, BatchExtendRequest,
+    BatchExtendResponse, BatchFailedItem, BatchResponse, BatchUpdateRequest, GenerateLinksRequest,
+    GenerateLinksResponse,
 };
 
 /// 批量操作最大条目数
-const MAX_BATCH_SIZE: usize = 5000;
+pub(super) const MAX_BATCH_SIZE: usize = 5000;
 
 /// 批量创建链接
 #[aster_forge_api_docs_macros::path(
@@ -27,17 +37,18 @@ const MAX_BATCH_SIZE: usize = 5000;
         responses(
             (status = 200, description = "Batch create result", body = super::types::ApiResponse<BatchResponse>),
             (status = 400, description = "Batch too large or invalid"),
+            (status = 403, description = "override_cooldown used with a scoped API token"),
+            (status = 429, description = "API token quota exhausted"),
         )
 )]
 pub async fn batch_create_links(
-    _req: HttpRequest,
+    req: HttpRequest,
     batch: web::Json<BatchCreateRequest>,
     service: web::Data<Arc<LinkService>>,
 ) -> ActixResult<impl Responder> {
     // 检查批量大小限制
     if batch.links.len() > MAX_BATCH_SIZE {
         return Ok(error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
             ErrorCode::BatchSizeTooLarge,
             &format!(
                 "Batch size {} exceeds maximum {}",
@@ -52,18 +63,49 @@ pub async fn batch_create_links(
         batch.links.len()
     );
 
-    // 转换为 LinkService 请求格式
-    let requests: Vec<CreateLinkRequest> = batch
-        .links
-        .iter()
-        .map(|l| CreateLinkRequest {
+    // 配额按整批条数预检，写入后按成功条数计入
+    let quota = QuotaScope::from_request(&req);
+    if quota.is_some()
+        && batch
+            .links
+            .iter()
+            .any(|l| l.override_cooldown == Some(true))
+    {
+        return Ok(error_response(
+            ErrorCode::Forbidden,
+            OVERRIDE_COOLDOWN_FORBIDDEN,
+        ));
+    }
+    if let Some(quota) = &quota
+        && let Err(resp) = quota.check(batch.links.len()).await
+    {
+        return Ok(resp);
+    }
+
+    let cap = match analytics_cap(quota.as_ref()).await {
+        Ok(cap) => cap,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 转换为 LinkService 请求格式（统计级别非法时整批拒绝）
+    let mut requests: Vec<CreateLinkRequest> = Vec::with_capacity(batch.links.len());
+    for l in &batch.links {
+        let analytics_level = match parse_analytics_level(l.analytics_level.as_deref()) {
+            Ok(level) => level.unwrap_or_default().capped(cap),
+            Err(resp) => return Ok(resp),
+        };
+        requests.push(CreateLinkRequest {
             code: l.code.clone(),
             target: l.target.clone(),
             force: l.force.unwrap_or(false),
-            expires_at: l.expires_at.clone(),
+            expires_at: l.expires_at.clone().flatten(),
             password: l.password.clone(),
-        })
-        .collect();
+            created_via: CreatedVia::Api,
+            analytics_level,
+            extras: l.extras.as_ref().map(|extras| extras.to_string()),
+            override_cooldown: l.override_cooldown.unwrap_or(false),
+        });
+    }
 
     // 调用 LinkService 批量创建
     let result = match service.batch_create_links(requests).await {
@@ -82,6 +124,9 @@ pub async fn batch_create_links(
             error_code: None,
         })
         .collect();
+    if let Some(quota) = &quota {
+        quota.record(&success).await;
+    }
 
     info!(
         "Admin API: batch create completed - {} success, {} failed",
@@ -105,14 +150,13 @@ pub async fn batch_create_links(
         )
 )]
 pub async fn batch_update_links(
-    _req: HttpRequest,
+    req: HttpRequest,
     batch: web::Json<BatchUpdateRequest>,
     service: web::Data<Arc<LinkService>>,
 ) -> ActixResult<impl Responder> {
     // 检查批量大小限制
     if batch.updates.len() > MAX_BATCH_SIZE {
         return Ok(error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
             ErrorCode::BatchSizeTooLarge,
             &format!(
                 "Batch size {} exceeds maximum {}",
@@ -127,21 +171,29 @@ pub async fn batch_update_links(
         batch.updates.len()
     );
 
-    // 转换为 LinkService 请求格式
-    let updates: Vec<(String, UpdateLinkRequest)> = batch
-        .updates
-        .iter()
-        .map(|u| {
-            (
-                u.code.clone(),
-                UpdateLinkRequest {
-                    target: u.payload.target.clone(),
-                    expires_at: u.payload.expires_at.clone(),
-                    password: u.payload.password.clone(),
-                },
-            )
-        })
-        .collect();
+    let cap = match analytics_cap(QuotaScope::from_request(&req).as_ref()).await {
+        Ok(cap) => cap,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 转换为 LinkService 请求格式（统计级别非法时整批拒绝）
+    let mut updates: Vec<(String, UpdateLinkRequest)> = Vec::with_capacity(batch.updates.len());
+    for u in &batch.updates {
+        let analytics_level = match parse_analytics_level(u.payload.analytics_level.as_deref()) {
+            Ok(level) => level.map(|level| level.capped(cap)),
+            Err(resp) => return Ok(resp),
+        };
+        updates.push((
+            u.code.clone(),
+            UpdateLinkRequest {
+                target: u.payload.target.clone(),
+                expires_at: update_expiry(&u.payload.expires_at),
+                password: u.payload.password.clone(),
+                analytics_level,
+                extras: u.payload.extras.as_ref().map(|extras| extras.to_string()),
+            },
+        ));
+    }
 
     // 调用 LinkService 批量更新
     let result = match service.batch_update_links(updates).await {
@@ -190,7 +242,6 @@ pub async fn batch_delete_links(
     // 检查批量大小限制
     if batch.codes.len() > MAX_BATCH_SIZE {
         return Ok(error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
             ErrorCode::BatchSizeTooLarge,
             &format!(
                 "Batch size {} exceeds maximum {}",
@@ -238,3 +289,222 @@ pub async fn batch_delete_links(
 
     Ok(success_response(BatchResponse { success, failed }))
 }
+
+/// 解析 RFC3339 日期过滤参数
+fn parse_filter_date(
+    name: &str,
+    value: Option<&str>,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    value
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .map_err(|_| {
+                    format!(
+                        "Invalid {}: '{}'. Use RFC3339 (e.g., 2024-01-01T00:00:00Z)",
+                        name, s
+                    )
+                })
+        })
+        .transpose()
+}
+
+/// 将请求中的过滤条件转换为 LinkFilter
+pub(super) fn build_extend_filter(filter: &BatchExtendFilter) -> Result<LinkFilter, String> {
+    let only_expired = filter.only_expired.unwrap_or(false);
+    let only_active = filter.only_active.unwrap_or(false);
+    if only_expired && only_active {
+        return Err("only_expired and only_active are mutually exclusive".to_string());
+    }
+
+    Ok(LinkFilter {
+        search: filter.search.clone(),
+        created_after: parse_filter_date("created_after", filter.created_after.as_deref())?,
+        created_before: parse_filter_date("created_before", filter.created_before.as_deref())?,
+        only_expired,
+        only_active,
+        created_via: None,
+        extras: Vec::new(),
+    })
+}
+
+/// 批量顺延链接过期时间
+#[aster_forge_api_docs_macros::path(
+        post,
+        path = "/admin/v1/links/batch_extend",
+        tag = "links",
+        operation_id = "batch_extend_links",
+        request_body = BatchExtendRequest,
+        responses(
+            (status = 200, description = "Batch extend result", body = super::types::ApiResponse<BatchExtendResponse>),
+            (status = 400, description = "Batch too large or invalid"),
+        )
+)]
+pub async fn batch_extend_links(
+    _req: HttpRequest,
+    body: web::Json<BatchExtendRequest>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    let body = body.into_inner();
+    // 选择方式：codes 与 filter 二选一
+    let selection = match (body.codes, body.filter) {
+        (Some(codes), None) => {
+            if codes.len() > MAX_BATCH_SIZE {
+                return Ok(error_response(
+                    ErrorCode::BatchSizeTooLarge,
+                    &format!(
+                        "Batch size {} exceeds maximum {}",
+                        codes.len(),
+                        MAX_BATCH_SIZE
+                    ),
+                ));
+            }
+            LinkSelection::Codes(codes)
+        }
+        (None, Some(filter)) => match build_extend_filter(&filter) {
+            Ok(filter) => LinkSelection::Filter(filter),
+            Err(msg) => return Ok(error_response(ErrorCode::InvalidDateFormat, &msg)),
+        },
+        _ => {
+            return Ok(error_response(
+                ErrorCode::BadRequest,
+                "Exactly one of codes or filter must be provided",
+            ));
+        }
+    };
+
+    // 调整方式：extend_by 与 new_expires_at 二选一
+    let action = match ExtendAction::from_inputs(
+        body.extend_by.as_deref(),
+        body.new_expires_at.as_deref(),
+    ) {
+        Ok(action) => action,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    let req = ServiceExtendRequest {
+        action,
+        include_permanent: body.include_permanent.unwrap_or(false),
+        revive_from_now: body.revive_from_now.unwrap_or(false),
+        dry_run: body.dry_run.unwrap_or(false),
+    };
+
+    info!("Admin API: batch extend request - {:?}", req);
+
+    let result = match service.batch_extend_links(selection, req).await {
+        Ok(r) => r,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    let to_failed = |f: crate::services::BatchFailedItem| BatchFailedItem {
+        code: f.code,
+        error: f.reason,
+        error_code: None,
+    };
+
+    Ok(success_response(BatchExtendResponse {
+        dry_run: result.dry_run,
+        updated: result
+            .updated
+            .into_iter()
+            .map(|item| BatchExtendItem {
+                code: item.code,
+                old_expires_at: item.old_expires_at.map(|dt| dt.to_rfc3339()),
+                new_expires_at: item.new_expires_at.to_rfc3339(),
+            })
+            .collect(),
+        skipped: result.skipped.into_iter().map(to_failed).collect(),
+        failed: result.failed.into_iter().map(to_failed).collect(),
+    }))
+}
+
+/// 按模板批量生成链接
+#[aster_forge_api_docs_macros::path(
+        post,
+        path = "/admin/v1/links/generate",
+        tag = "links",
+        operation_id = "generate_links",
+        request_body = GenerateLinksRequest,
+        responses(
+            (status = 200, description = "Template generation result", body = super::types::ApiResponse<GenerateLinksResponse>),
+            (status = 400, description = "Invalid template or too many combinations"),
+            (status = 429, description = "API token quota exhausted"),
+        )
+)]
+pub async fn generate_links(
+    req: HttpRequest,
+    body: web::Json<GenerateLinksRequest>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    let body = body.into_inner();
+    let template = LinkTemplate {
+        url: body.url_template,
+        code: body.code_template.filter(|c| !c.is_empty()),
+        vars: body.vars,
+    };
+    let dry_run = body.dry_run.unwrap_or(false);
+
+    // 配额按组合数预检，写入后按成功条数计入
+    let quota = QuotaScope::from_request(&req);
+    if !dry_run
+        && let Some(quota) = &quota
+        && let Some(total) = template.combinations()
+        && let Err(resp) = quota.check(total).await
+    {
+        return Ok(resp);
+    }
+    let analytics_level = match analytics_cap(quota.as_ref()).await {
+        Ok(cap) => AnalyticsLevel::Inherit.capped(cap),
+        Err(resp) => return Ok(resp),
+    };
+
+    let options = GenerateLinksOptions {
+        force: body.force.unwrap_or(false),
+        expires_at: body.expires_at,
+        password: body.password,
+        created_via: CreatedVia::Api,
+        analytics_level,
+        dry_run,
+    };
+    let result = match service.generate_links(template, options).await {
+        Ok(r) => r,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+
+    if let Some(quota) = &quota {
+        let codes: Vec<String> = result
+            .created
+            .iter()
+            .filter_map(|link| link.code.clone())
+            .collect();
+        quota.record(&codes).await;
+    }
+
+    info!(
+        "Admin API: template generate - {} combinations, {} created, {} failed{}",
+        result.planned.len(),
+        result.created.len(),
+        result.failed.len(),
+        if result.dry_run { " (dry-run)" } else { "" }
+    );
+
+    Ok(success_response(GenerateLinksResponse {
+        dry_run: result.dry_run,
+        total: result.planned.len(),
+        links: if result.dry_run {
+            result.planned
+        } else {
+            result.created
+        },
+        existing: result.existing,
+        failed: result
+            .failed
+            .into_iter()
+            .map(|f| BatchFailedItem {
+                code: f.code,
+                error: f.reason,
+                error_code: None,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,36 @@
+//! Admin API 缓存状态端点
+//!
+//! 展示当前这一代 Bloom 的判定统计（进程内计数，多实例部署时只反映被请求的实例）。
+
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, Responder, Result as ActixResult, web};
+use tracing::trace;
+
+use super::helpers::success_response;
+use super::types::{BloomStatsResponse, CacheStatsResponse};
+use crate::services::LinkCache;
+use crate::services::bloom_stats::bloom_alert_threshold;
+
+/// 获取缓存统计
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/cache/stats",
+        tag = "cache",
+        operation_id = "get_cache_stats",
+        responses((status = 200, description = "Cache statistics", body = super::types::ApiResponse<CacheStatsResponse>))
+)]
+pub async fn get_cache_stats(
+    _req: HttpRequest,
+    cache: web::Data<Arc<dyn LinkCache>>,
+) -> ActixResult<impl Responder> {
+    trace!("Admin API: request cache stats");
+
+    let threshold = bloom_alert_threshold();
+    Ok(success_response(CacheStatsResponse {
+        cache_type: crate::config::get_config().cache.cache_type.clone(),
+        bloom: cache
+            .bloom_stats()
+            .map(|stats| BloomStatsResponse::new(&stats, threshold)),
+    }))
+}
@@ -12,6 +12,8 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 /// - 3000-3099: 链接错误
 /// - 4000-4099: 导入导出错误
 /// - 5000-5099: 配置错误
+/// - 6000-6099: Analytics 错误
+/// - 7000-7099: 配额错误
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
 #[cfg_attr(all(debug_assertions, feature = "openapi"), derive(strum::EnumIter))]
 #[repr(i32)]
@@ -28,6 +30,7 @@ pub enum ErrorCode {
     FileTooLarge = 1011,
     InvalidDateFormat = 1012,
     ServiceUnavailable = 1030,
+    PanelVersionIncompatible = 1040,
 
     // 认证错误 2000-2099
     AuthFailed = 2000,
@@ -35,6 +38,7 @@ pub enum ErrorCode {
     TokenInvalid = 2002,
     CsrfInvalid = 2003,
     RateLimitExceeded = 2004,
+    Forbidden = 2005,
 
     // 链接错误 3000-3099
     LinkNotFound = 3000,
@@ -46,6 +50,7 @@ pub enum ErrorCode {
     LinkEmptyCode = 3006,
     LinkInvalidCode = 3007,
     LinkReservedCode = 3008,
+    LinkCodeCoolingDown = 3009,
 
     // 导入导出错误 4000-4099
     ImportFailed = 4000,
@@ -65,6 +70,9 @@ pub enum ErrorCode {
     AnalyticsQueryFailed = 6000,
     AnalyticsLinkNotFound = 6001,
     AnalyticsInvalidDateRange = 6002,
+
+    // 配额错误 7000-7099
+    QuotaExceeded = 7000,
 }
 
 #[cfg(all(debug_assertions, feature = "openapi"))]
@@ -96,3 +104,383 @@ impl utoipa::PartialSchema for ErrorCode {
 
 #[cfg(all(debug_assertions, feature = "openapi"))]
 impl utoipa::ToSchema for ErrorCode {}
+
+impl ErrorCode {
+    /// 全部错误码（按数值升序），用于错误目录端点与测试
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::Success,
+        Self::BadRequest,
+        Self::Unauthorized,
+        Self::NotFound,
+        Self::InternalServerError,
+        Self::BatchSizeTooLarge,
+        Self::FileTooLarge,
+        Self::InvalidDateFormat,
+        Self::ServiceUnavailable,
+        Self::PanelVersionIncompatible,
+        Self::AuthFailed,
+        Self::TokenExpired,
+        Self::TokenInvalid,
+        Self::CsrfInvalid,
+        Self::RateLimitExceeded,
+        Self::Forbidden,
+        Self::LinkNotFound,
+        Self::LinkAlreadyExists,
+        Self::LinkInvalidUrl,
+        Self::LinkInvalidExpireTime,
+        Self::LinkPasswordHashError,
+        Self::LinkDatabaseError,
+        Self::LinkEmptyCode,
+        Self::LinkInvalidCode,
+        Self::LinkReservedCode,
+        Self::LinkCodeCoolingDown,
+        Self::ImportFailed,
+        Self::ExportFailed,
+        Self::InvalidMultipartData,
+        Self::FileReadError,
+        Self::CsvFileMissing,
+        Self::CsvParseError,
+        Self::CsvGenerationError,
+        Self::ConfigNotFound,
+        Self::ConfigUpdateFailed,
+        Self::ConfigReloadFailed,
+        Self::AnalyticsQueryFailed,
+        Self::AnalyticsLinkNotFound,
+        Self::AnalyticsInvalidDateRange,
+        Self::QuotaExceeded,
+    ];
+
+    /// 错误码对应的 HTTP 状态码
+    ///
+    /// 这是 ErrorCode → HTTP 状态的唯一映射，`ShortlinkerError::http_status()`
+    /// 与所有错误响应都经由此处，修改时需同步更新测试中的契约表。
+    pub fn http_status(self) -> actix_web::http::StatusCode {
+        use actix_web::http::StatusCode;
+        match self {
+            Self::Success => StatusCode::OK,
+
+            Self::BadRequest
+            | Self::BatchSizeTooLarge
+            | Self::FileTooLarge
+            | Self::InvalidDateFormat
+            | Self::LinkInvalidUrl
+            | Self::LinkInvalidExpireTime
+            | Self::LinkEmptyCode
+            | Self::LinkInvalidCode
+            | Self::LinkReservedCode
+            | Self::InvalidMultipartData
+            | Self::CsvFileMissing
+            | Self::CsvParseError
+            | Self::AnalyticsInvalidDateRange => StatusCode::BAD_REQUEST,
+
+            Self::Unauthorized | Self::AuthFailed | Self::TokenExpired | Self::TokenInvalid => {
+                StatusCode::UNAUTHORIZED
+            }
+
+            Self::CsrfInvalid | Self::Forbidden => StatusCode::FORBIDDEN,
+
+            Self::NotFound
+            | Self::LinkNotFound
+            | Self::ConfigNotFound
+            | Self::AnalyticsLinkNotFound => StatusCode::NOT_FOUND,
+
+            Self::LinkAlreadyExists
+            | Self::LinkCodeCoolingDown
+            | Self::PanelVersionIncompatible => StatusCode::CONFLICT,
+
+            Self::RateLimitExceeded | Self::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+
+            Self::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+
+            Self::InternalServerError
+            | Self::LinkPasswordHashError
+            | Self::LinkDatabaseError
+            | Self::ImportFailed
+            | Self::ExportFailed
+            | Self::FileReadError
+            | Self::CsvGenerationError
+            | Self::ConfigUpdateFailed
+            | Self::ConfigReloadFailed
+            | Self::AnalyticsQueryFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// 稳定的错误名称（即枚举变体名），供 SDK 生成异常类型
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Success => "Success",
+            Self::BadRequest => "BadRequest",
+            Self::Unauthorized => "Unauthorized",
+            Self::NotFound => "NotFound",
+            Self::InternalServerError => "InternalServerError",
+            Self::BatchSizeTooLarge => "BatchSizeTooLarge",
+            Self::FileTooLarge => "FileTooLarge",
+            Self::InvalidDateFormat => "InvalidDateFormat",
+            Self::ServiceUnavailable => "ServiceUnavailable",
+            Self::PanelVersionIncompatible => "PanelVersionIncompatible",
+            Self::AuthFailed => "AuthFailed",
+            Self::TokenExpired => "TokenExpired",
+            Self::TokenInvalid => "TokenInvalid",
+            Self::CsrfInvalid => "CsrfInvalid",
+            Self::RateLimitExceeded => "RateLimitExceeded",
+            Self::Forbidden => "Forbidden",
+            Self::LinkNotFound => "LinkNotFound",
+            Self::LinkAlreadyExists => "LinkAlreadyExists",
+            Self::LinkInvalidUrl => "LinkInvalidUrl",
+            Self::LinkInvalidExpireTime => "LinkInvalidExpireTime",
+            Self::LinkPasswordHashError => "LinkPasswordHashError",
+            Self::LinkDatabaseError => "LinkDatabaseError",
+            Self::LinkEmptyCode => "LinkEmptyCode",
+            Self::LinkInvalidCode => "LinkInvalidCode",
+            Self::LinkReservedCode => "LinkReservedCode",
+            Self::LinkCodeCoolingDown => "LinkCodeCoolingDown",
+            Self::ImportFailed => "ImportFailed",
+            Self::ExportFailed => "ExportFailed",
+            Self::InvalidMultipartData => "InvalidMultipartData",
+            Self::FileReadError => "FileReadError",
+            Self::CsvFileMissing => "CsvFileMissing",
+            Self::CsvParseError => "CsvParseError",
+            Self::CsvGenerationError => "CsvGenerationError",
+            Self::ConfigNotFound => "ConfigNotFound",
+            Self::ConfigUpdateFailed => "ConfigUpdateFailed",
+            Self::ConfigReloadFailed => "ConfigReloadFailed",
+            Self::AnalyticsQueryFailed => "AnalyticsQueryFailed",
+            Self::AnalyticsLinkNotFound => "AnalyticsLinkNotFound",
+            Self::AnalyticsInvalidDateRange => "AnalyticsInvalidDateRange",
+            Self::QuotaExceeded => "QuotaExceeded",
+        }
+    }
+
+    /// 面向 SDK 使用者的英文描述
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Success => "Request succeeded",
+            Self::BadRequest => "Request parameters are invalid",
+            Self::Unauthorized => "Missing or invalid credentials",
+            Self::NotFound => "Resource not found",
+            Self::InternalServerError => "Unexpected server error",
+            Self::BatchSizeTooLarge => "Batch request exceeds the maximum item count",
+            Self::FileTooLarge => "Uploaded file exceeds the size limit",
+            Self::InvalidDateFormat => "Date or time value could not be parsed",
+            Self::ServiceUnavailable => "Service is temporarily unavailable",
+            Self::PanelVersionIncompatible => {
+                "Admin panel build is incompatible with this server; reload the panel"
+            }
+            Self::AuthFailed => "Administrator credentials rejected",
+            Self::TokenExpired => "Access or refresh token has expired",
+            Self::TokenInvalid => "Access or refresh token is invalid",
+            Self::CsrfInvalid => "CSRF token missing or invalid",
+            Self::RateLimitExceeded => "Too many requests, retry later",
+            Self::Forbidden => "Credential is not allowed to access this resource",
+            Self::LinkNotFound => "Short link not found",
+            Self::LinkAlreadyExists => "Short code is already in use",
+            Self::LinkInvalidUrl => "Target URL is invalid or not allowed",
+            Self::LinkInvalidExpireTime => "Expiration time is invalid",
+            Self::LinkPasswordHashError => "Failed to hash link password",
+            Self::LinkDatabaseError => "Storage error while processing the link",
+            Self::LinkEmptyCode => "Short code must not be empty",
+            Self::LinkInvalidCode => "Short code contains invalid characters",
+            Self::LinkReservedCode => "Short code conflicts with a reserved route",
+            Self::LinkCodeCoolingDown => {
+                "Short code was deleted recently and is still in its reuse cooldown"
+            }
+            Self::ImportFailed => "Import failed",
+            Self::ExportFailed => "Export failed",
+            Self::InvalidMultipartData => "Multipart payload is malformed",
+            Self::FileReadError => "Uploaded file could not be read",
+            Self::CsvFileMissing => "No CSV file was provided",
+            Self::CsvParseError => "CSV content could not be parsed",
+            Self::CsvGenerationError => "CSV output could not be generated",
+            Self::ConfigNotFound => "Configuration key not found",
+            Self::ConfigUpdateFailed => "Configuration update failed",
+            Self::ConfigReloadFailed => "Configuration reload failed",
+            Self::AnalyticsQueryFailed => "Analytics query failed",
+            Self::AnalyticsLinkNotFound => "No analytics data for the requested link",
+            Self::AnalyticsInvalidDateRange => "Analytics date range is invalid",
+            Self::QuotaExceeded => "API token quota exhausted",
+        }
+    }
+
+    /// 管理面板与 CLI 共用的文案 key（与 `admin-panel/src/utils/errorMapping.ts` 一致）
+    pub fn i18n_key(self) -> Option<&'static str> {
+        let key = match self {
+            Self::Success => return None,
+            Self::BadRequest => "errors.badRequest",
+            Self::Unauthorized => "errors.unauthorized",
+            Self::NotFound => "errors.notFound",
+            Self::InternalServerError => "errors.serverError",
+            Self::BatchSizeTooLarge => "errors.batchSizeTooLarge",
+            Self::FileTooLarge => "errors.fileTooLarge",
+            Self::InvalidDateFormat => "errors.invalidDateFormat",
+            Self::ServiceUnavailable => "errors.serviceUnavailable",
+            Self::PanelVersionIncompatible => "errors.panelVersionIncompatible",
+            Self::AuthFailed => "errors.authFailed",
+            Self::TokenExpired => "errors.tokenExpired",
+            Self::TokenInvalid => "errors.tokenInvalid",
+            Self::CsrfInvalid => "errors.csrfInvalid",
+            Self::RateLimitExceeded => "errors.tooManyRequests",
+            Self::Forbidden => "errors.forbidden",
+            Self::LinkNotFound => "errors.linkNotFound",
+            Self::LinkAlreadyExists => "errors.linkAlreadyExists",
+            Self::LinkInvalidUrl => "errors.linkInvalidUrl",
+            Self::LinkInvalidExpireTime => "errors.linkInvalidExpireTime",
+            Self::LinkPasswordHashError => "errors.linkPasswordHashError",
+            Self::LinkDatabaseError => "errors.linkDatabaseError",
+            Self::LinkEmptyCode => "errors.linkEmptyCode",
+            Self::LinkInvalidCode => "errors.linkInvalidCode",
+            Self::LinkReservedCode => "errors.linkReservedCode",
+            Self::LinkCodeCoolingDown => "errors.linkCodeCoolingDown",
+            Self::ImportFailed => "errors.importFailed",
+            Self::ExportFailed => "errors.exportFailed",
+            Self::InvalidMultipartData => "errors.invalidMultipartData",
+            Self::FileReadError => "errors.fileReadError",
+            Self::CsvFileMissing => "errors.csvFileMissing",
+            Self::CsvParseError => "errors.csvParseError",
+            Self::CsvGenerationError => "errors.csvGenerationError",
+            Self::ConfigNotFound => "errors.configNotFound",
+            Self::ConfigUpdateFailed => "errors.configUpdateFailed",
+            Self::ConfigReloadFailed => "errors.configReloadFailed",
+            Self::AnalyticsQueryFailed => "errors.analyticsQueryFailed",
+            Self::AnalyticsLinkNotFound => "errors.analyticsLinkNotFound",
+            Self::AnalyticsInvalidDateRange => "errors.analyticsInvalidDateRange",
+            Self::QuotaExceeded => "errors.quotaExceeded",
+        };
+        Some(key)
+    }
+
+    /// 客户端是否可以原样重试（瞬时故障或限流）
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::ServiceUnavailable
+                | Self::RateLimitExceeded
+                | Self::InternalServerError
+                | Self::LinkDatabaseError
+                | Self::AnalyticsQueryFailed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use std::collections::HashSet;
+
+    /// 对外契约：错误码数值与 HTTP 状态，变更即为破坏性变更
+    const CONTRACT: &[(ErrorCode, i32, u16)] = &[
+        (ErrorCode::Success, 0, 200),
+        (ErrorCode::BadRequest, 1000, 400),
+        (ErrorCode::Unauthorized, 1001, 401),
+        (ErrorCode::NotFound, 1004, 404),
+        (ErrorCode::InternalServerError, 1005, 500),
+        (ErrorCode::BatchSizeTooLarge, 1010, 400),
+        (ErrorCode::FileTooLarge, 1011, 400),
+        (ErrorCode::InvalidDateFormat, 1012, 400),
+        (ErrorCode::ServiceUnavailable, 1030, 503),
+        (ErrorCode::PanelVersionIncompatible, 1040, 409),
+        (ErrorCode::AuthFailed, 2000, 401),
+        (ErrorCode::TokenExpired, 2001, 401),
+        (ErrorCode::TokenInvalid, 2002, 401),
+        (ErrorCode::CsrfInvalid, 2003, 403),
+        (ErrorCode::RateLimitExceeded, 2004, 429),
+        (ErrorCode::Forbidden, 2005, 403),
+        (ErrorCode::LinkNotFound, 3000, 404),
+        (ErrorCode::LinkAlreadyExists, 3001, 409),
+        (ErrorCode::LinkInvalidUrl, 3002, 400),
+        (ErrorCode::LinkInvalidExpireTime, 3003, 400),
+        (ErrorCode::LinkPasswordHashError, 3004, 500),
+        (ErrorCode::LinkDatabaseError, 3005, 500),
+        (ErrorCode::LinkEmptyCode, 3006, 400),
+        (ErrorCode::LinkInvalidCode, 3007, 400),
+        (ErrorCode::LinkReservedCode, 3008, 400),
+        (ErrorCode::LinkCodeCoolingDown, 3009, 409),
+        (ErrorCode::ImportFailed, 4000, 500),
+        (ErrorCode::ExportFailed, 4001, 500),
+        (ErrorCode::InvalidMultipartData, 4002, 400),
+        (ErrorCode::FileReadError, 4003, 500),
+        (ErrorCode::CsvFileMissing, 4004, 400),
+        (ErrorCode::CsvParseError, 4005, 400),
+        (ErrorCode::CsvGenerationError, 4006, 500),
+        (ErrorCode::ConfigNotFound, 5000, 404),
+        (ErrorCode::ConfigUpdateFailed, 5001, 500),
+        (ErrorCode::ConfigReloadFailed, 5002, 500),
+        (ErrorCode::AnalyticsQueryFailed, 6000, 500),
+        (ErrorCode::AnalyticsLinkNotFound, 6001, 404),
+        (ErrorCode::AnalyticsInvalidDateRange, 6002, 400),
+        (ErrorCode::QuotaExceeded, 7000, 429),
+    ];
+
+    #[test]
+    fn test_contract_table_matches_mapping() {
+        for (code, value, status) in CONTRACT {
+            assert_eq!(*code as i32, *value, "{} wire value changed", code.name());
+            assert_eq!(
+                code.http_status(),
+                StatusCode::from_u16(*status).unwrap(),
+                "{} http status changed",
+                code.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_covers_contract() {
+        assert_eq!(ErrorCode::ALL.len(), CONTRACT.len());
+        for (code, _, _) in CONTRACT {
+            assert!(ErrorCode::ALL.contains(code), "{} missing", code.name());
+        }
+    }
+
+    #[test]
+    fn test_codes_and_names_unique() {
+        let values: HashSet<i32> = ErrorCode::ALL.iter().map(|c| *c as i32).collect();
+        let names: HashSet<&str> = ErrorCode::ALL.iter().map(|c| c.name()).collect();
+        assert_eq!(values.len(), ErrorCode::ALL.len());
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+    }
+
+    #[test]
+    fn test_i18n_keys_have_english_text() {
+        for code in ErrorCode::ALL.iter().filter(|c| **c != ErrorCode::Success) {
+            let key = code.i18n_key().unwrap();
+            assert!(
+                crate::i18n::lookup(crate::i18n::Lang::En, key).is_some(),
+                "{} has no text for {}",
+                code.name(),
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_name_matches_debug() {
+        for code in ErrorCode::ALL {
+            assert_eq!(code.name(), format!("{code:?}"));
+        }
+    }
+
+    #[test]
+    fn test_all_sorted_by_value() {
+        assert!(
+            ErrorCode::ALL
+                .windows(2)
+                .all(|w| (w[0] as i32) < (w[1] as i32))
+        );
+    }
+
+    #[test]
+    fn test_retryable_only_for_transient_errors() {
+        for code in ErrorCode::ALL {
+            if code.is_retryable() {
+                assert!(
+                    code.http_status().is_server_error()
+                        || code.http_status() == StatusCode::TOO_MANY_REQUESTS,
+                    "{} is retryable but not transient",
+                    code.name()
+                );
+            }
+        }
+        assert!(!ErrorCode::BadRequest.is_retryable());
+    }
+}
@@ -4,11 +4,10 @@ use actix_multipart::Multipart;
 use actix_web::{HttpRequest, HttpResponse, Responder, Result as ActixResult, web};
 use bytes::Bytes;
 use chrono::Utc;
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::WriterBuilder;
 use futures_util::stream::{Stream, StreamExt};
 use serde::Serialize;
 use std::fmt::Display;
-use std::io::Cursor;
 use std::pin::Pin;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
@@ -16,10 +15,17 @@ use tracing::{debug, error, info, warn};
 use crate::errors::ShortlinkerError;
 use crate::services::{ImportLinkItemRaw, LinkService, validate_import_rows};
 use crate::storage::{LinkFilter, ShortLink};
+use crate::utils::csv_dialect::{DecodedCsv, parse_delimiter};
+use crate::utils::csv_handler::{PREVIEW_ROWS, read_link_rows, schema_metadata_line};
+use crate::utils::csv_transform::ImportTransform;
 
+use super::api_tokens::{QuotaScope, analytics_cap};
 use super::error_code::ErrorCode;
 use super::helpers::{error_from_shortlinker, error_response, success_response};
-use super::types::{CsvLinkRow, ExportQuery, ImportFailedItem, ImportMode, ImportResponse};
+use super::types::{
+    CsvLinkRow, ExportQuery, ImportFailedItem, ImportMode, ImportPreviewItem, ImportResponse,
+    ImportWarningItem,
+};
 
 /// 每批次序列化的链接数量
 const EXPORT_BATCH_SIZE: usize = 10000;
@@ -179,7 +185,6 @@ pub async fn export_links(
             Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
             Err(_) => {
                 return Ok(error_response(
-                    actix_web::http::StatusCode::BAD_REQUEST,
                     ErrorCode::InvalidDateFormat,
                     &format!(
                         "Invalid created_after: '{}'. Use RFC3339 (e.g., 2024-01-01T00:00:00Z)",
@@ -196,7 +201,6 @@ pub async fn export_links(
             Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
             Err(_) => {
                 return Ok(error_response(
-                    actix_web::http::StatusCode::BAD_REQUEST,
                     ErrorCode::InvalidDateFormat,
                     &format!(
                         "Invalid created_before: '{}'. Use RFC3339 (e.g., 2024-01-01T00:00:00Z)",
@@ -215,23 +219,21 @@ pub async fn export_links(
         created_before,
         only_expired: query.only_expired.unwrap_or(false),
         only_active: query.only_active.unwrap_or(false),
+        created_via: None,
+        extras: Vec::new(),
     };
 
     // 获取游标分页流式数据
     let batch_stream = service.export_links_stream(filter, EXPORT_BATCH_SIZE as u64);
 
     // 行映射：ShortLink → CsvLinkRow
-    let row_mapper = |link: ShortLink| CsvLinkRow {
-        code: link.code,
-        target: link.target,
-        created_at: link.created_at.to_rfc3339(),
-        expires_at: link.expires_at.map(|dt| dt.to_rfc3339()),
-        password: link.password,
-        click_count: link.click,
-    };
+    let row_mapper = |link: ShortLink| CsvLinkRow::from(&link);
 
-    // 创建 CSV 流
-    let csv_stream = create_csv_stream(batch_stream, row_mapper, "links");
+    // 创建 CSV 流，首行为 schema 元数据
+    let metadata = futures_util::stream::once(async {
+        Ok::<_, actix_web::Error>(Bytes::from(schema_metadata_line()))
+    });
+    let csv_stream = metadata.chain(create_csv_stream(batch_stream, row_mapper, "links"));
 
     // 生成文件名
     let filename = format!(
@@ -262,10 +264,11 @@ pub async fn export_links(
     responses(
         (status = 200, description = "Import result", body = super::types::ApiResponse<ImportResponse>),
         (status = 400, description = "Invalid CSV or multipart request"),
+        (status = 429, description = "API token quota exhausted"),
     ),
 )]
 pub async fn import_links(
-    _req: HttpRequest,
+    req: HttpRequest,
     mut payload: Multipart,
     service: web::Data<Arc<LinkService>>,
 ) -> ActixResult<impl Responder> {
@@ -273,6 +276,9 @@ pub async fn import_links(
 
     let mut csv_data: Option<Vec<u8>> = None;
     let mut mode = ImportMode::Skip; // 默认模式
+    let mut delimiter: Option<u8> = None; // 未指定时自动嗅探
+    let mut transform: Option<ImportTransform> = None;
+    let mut dry_run = false;
 
     // 解析 multipart form data
     while let Some(item) = payload.next().await {
@@ -301,7 +307,6 @@ pub async fn import_links(
                             // 检查累积大小
                             if data.len() + bytes.len() > MAX_IMPORT_FILE_SIZE {
                                 return Ok(error_response(
-                                    actix_web::http::StatusCode::BAD_REQUEST,
                                     ErrorCode::FileTooLarge,
                                     &format!(
                                         "File size exceeds maximum {} MB",
@@ -337,6 +342,58 @@ pub async fn import_links(
                     _ => ImportMode::Skip,
                 };
             }
+            "delimiter" => {
+                let mut data = Vec::new();
+                while let Some(chunk) = field.next().await {
+                    if let Ok(bytes) = chunk {
+                        data.extend_from_slice(&bytes);
+                    }
+                }
+                let value = String::from_utf8_lossy(&data);
+                if value.is_empty() {
+                    continue;
+                }
+                match parse_delimiter(&value) {
+                    Ok(d) => delimiter = Some(d),
+                    Err(msg) => {
+                        return Ok(error_from_shortlinker(&ShortlinkerError::csv_parse_failed(
+                            msg,
+                        )));
+                    }
+                }
+            }
+            "transform" => {
+                // 内联的 TOML 转换配置
+                let mut data = Vec::new();
+                while let Some(chunk) = field.next().await {
+                    if let Ok(bytes) = chunk {
+                        data.extend_from_slice(&bytes);
+                    }
+                }
+                let text = String::from_utf8_lossy(&data);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                match ImportTransform::from_toml(&text) {
+                    Ok(t) => transform = Some(t),
+                    Err(e) => return Ok(error_from_shortlinker(&e)),
+                }
+            }
+            "dry_run" => {
+                let mut data = Vec::new();
+                while let Some(chunk) = field.next().await {
+                    if let Ok(bytes) = chunk {
+                        data.extend_from_slice(&bytes);
+                    }
+                }
+                dry_run = matches!(
+                    String::from_utf8_lossy(&data)
+                        .trim()
+                        .to_lowercase()
+                        .as_str(),
+                    "true" | "1"
+                );
+            }
             _ => {
                 // 忽略未知字段
             }
@@ -354,30 +411,32 @@ pub async fn import_links(
     };
 
     info!(
-        "Admin API: import mode={:?}, file size={} bytes",
+        "Admin API: import mode={:?}, file size={} bytes, transform={}, dry_run={}",
         mode,
-        csv_data.len()
+        csv_data.len(),
+        transform.is_some(),
+        dry_run
     );
 
-    // 解析 CSV（单次解析，收集所有行）
-    let cursor = Cursor::new(&csv_data);
-    let mut csv_reader = ReaderBuilder::new()
-        .has_headers(true)
-        .flexible(true)
-        .trim(csv::Trim::All)
-        .from_reader(cursor);
+    // 检测方言（编码 / 分隔符 / 表头），然后单次解析收集所有行
+    let decoded = match DecodedCsv::decode(&csv_data, delimiter) {
+        Ok(decoded) => decoded,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+    let detected = decoded.dialect.describe();
+    info!("Admin API: import CSV {}", detected);
 
     let mut total_rows = 0;
     let mut failed_items: Vec<ImportFailedItem> = Vec::new();
     let mut raw_items: Vec<ImportLinkItemRaw> = Vec::new();
+    let mut preview: Vec<ImportPreviewItem> = Vec::new();
     // 记录 code → CSV 行号映射，仅用于回填 service 层返回的冲突失败项行号
     // （验证错误的行号由 ImportLinkItemRaw.row_num 直接携带，不受重复 code 影响）
     let mut code_to_row: std::collections::HashMap<String, usize> =
         std::collections::HashMap::new();
 
-    // Step 1: CSV 解析，收集 raw items（CSV 解析错误留在这层）
-    for (row_idx, result) in csv_reader.deserialize::<CsvLinkRow>().enumerate() {
-        let row_num = row_idx + 2; // CSV 行号（1-based，跳过 header）
+    // Step 1: CSV 解析与字段转换，收集 raw items（解析 / 转换错误留在这层）
+    for (row_num, result) in read_link_rows(&decoded, transform.as_ref()) {
         total_rows += 1;
 
         let row = match result {
@@ -386,13 +445,16 @@ pub async fn import_links(
                 failed_items.push(ImportFailedItem {
                     row: Some(row_num),
                     code: String::new(),
-                    error: format!("CSV parse error: {}", e),
+                    error: e,
                     error_code: Some(ErrorCode::CsvParseError as i32),
                 });
                 continue;
             }
         };
 
+        if dry_run && preview.len() < PREVIEW_ROWS {
+            preview.push(ImportPreviewItem::new(row_num, &row));
+        }
         code_to_row.insert(row.code.clone(), row_num);
         raw_items.push(ImportLinkItemRaw {
             code: row.code,
@@ -401,12 +463,14 @@ pub async fn import_links(
             expires_at: row.expires_at,
             password: row.password,
             click_count: row.click_count,
+            analytics_level: row.analytics_level,
+            extras: row.extras,
             row_num: Some(row_num),
         });
     }
 
     // Step 2: 统一验证（URL、日期、密码、空 code）
-    let (valid_items, row_errors) = validate_import_rows(raw_items);
+    let (mut valid_items, row_errors, row_warnings) = validate_import_rows(raw_items);
 
     for err in row_errors {
         // 验证错误直接使用 row_num（跟随原始数据，不受重复 code 影响）
@@ -419,6 +483,52 @@ pub async fn import_links(
         });
     }
 
+    let warnings: Vec<ImportWarningItem> = row_warnings
+        .into_iter()
+        .map(|w| ImportWarningItem {
+            row: w.row_num,
+            code: w.code,
+            warning: w.message,
+        })
+        .collect();
+
+    // dry-run 只返回解析、转换与校验结果，不检查冲突也不写入
+    if dry_run {
+        let failed_count = failed_items.len();
+        info!(
+            "Admin API: import dry-run - total: {}, valid: {}, failed: {}",
+            total_rows,
+            valid_items.len(),
+            failed_count
+        );
+        return Ok(success_response(ImportResponse {
+            total_rows,
+            success_count: 0,
+            skipped_count: 0,
+            failed_count,
+            failed_items,
+            warnings,
+            detected,
+            dry_run: true,
+            preview,
+        }));
+    }
+
+    // 配额按通过校验的行数预检，写入后只按新建条数计入（覆盖已有短码不计）
+    let quota = QuotaScope::from_request(&req);
+    if let Some(quota) = &quota
+        && let Err(resp) = quota.check(valid_items.len()).await
+    {
+        return Ok(resp);
+    }
+    let cap = match analytics_cap(quota.as_ref()).await {
+        Ok(cap) => cap,
+        Err(resp) => return Ok(resp),
+    };
+    for item in &mut valid_items {
+        item.analytics_level = item.analytics_level.capped(cap);
+    }
+
     // 委托 service 处理冲突检测、去重、批量写入和缓存更新
     let batch_result = match service.import_links_batch(valid_items, mode).await {
         Ok(r) => r,
@@ -427,6 +537,9 @@ pub async fn import_links(
             return Ok(error_from_shortlinker(&e));
         }
     };
+    if let Some(quota) = &quota {
+        quota.record(&batch_result.created_codes).await;
+    }
 
     // 合并 service 返回的失败项，优先使用 item.row_num（精确），回退到 code_to_row
     for item in batch_result.failed_items {
@@ -461,5 +574,9 @@ pub async fn import_links(
         skipped_count,
         failed_count,
         failed_items,
+        warnings,
+        detected,
+        dry_run: false,
+        preview,
     }))
 }
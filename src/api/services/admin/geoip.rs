@@ -0,0 +1,32 @@
+//! Admin API GeoIP 状态端点
+//!
+//! 展示外部 API 保护层的熔断、并发与配额状态，排查 country 为空的原因。
+//! 状态只存本进程内存，多实例部署时只反映被请求的实例。
+
+use std::sync::Arc;
+
+use actix_web::{Responder, Result as ActixResult, web};
+use tracing::trace;
+
+use super::helpers::success_response;
+use super::types::{ApiResponse, GeoIpApiStatusResponse, GeoIpStatusResponse};
+use crate::services::GeoIpProvider;
+
+/// 获取 GeoIP provider 状态
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/geoip/status",
+        tag = "geoip",
+        operation_id = "get_geoip_status",
+        responses((status = 200, description = "GeoIP provider status", body = ApiResponse<GeoIpStatusResponse>))
+)]
+pub async fn get_geoip_status(geoip: web::Data<Arc<GeoIpProvider>>) -> ActixResult<impl Responder> {
+    trace!("Admin API: request GeoIP status");
+
+    Ok(success_response(GeoIpStatusResponse {
+        provider: geoip.provider_name().to_string(),
+        external_api: geoip
+            .external_api_status()
+            .map(GeoIpApiStatusResponse::from),
+    }))
+}
@@ -6,27 +6,75 @@ use actix_web::http::StatusCode;
 use serde::Serialize;
 
 use crate::api::constants;
-use crate::config::{get_runtime_config, keys};
+use crate::api::middleware::request_context::current_request_id;
+use crate::config::{get_runtime_config, keys, try_get_runtime_config};
 use crate::errors::ShortlinkerError;
-use crate::utils::TimeParser;
+use crate::services::link_validation;
+use crate::storage::AnalyticsLevel;
 
 use super::error_code::ErrorCode;
-use super::types::ApiResponse;
+use super::types::{ApiResponse, ErrorBody, ErrorEnvelope};
 
-/// 解析过期时间字符串，支持相对格式（如 '1h', '30m'）和 RFC3339 格式
+/// 解析过期时间字符串，支持相对格式（如 '1h', '30m'）、RFC3339、
+/// 不带偏移的日期时间与只有日期的格式
+///
+/// 与 [`link_validation::parse_expiry`] 一致，空字符串视为非法。
 pub fn parse_expires_at(expire_str: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
-    TimeParser::parse_expire_time(expire_str).or_else(|_| {
-        chrono::DateTime::parse_from_rfc3339(expire_str)
-            .map(|time| time.with_timezone(&chrono::Utc))
-            .map_err(|_| {
-                format!(
-                    "Invalid expires_at format: {}. Use relative format (e.g., '1h', '30m') or RFC3339 format",
-                    expire_str
-                )
-            })
+    link_validation::parse_expiry(expire_str, true).map_err(|_| {
+        format!(
+            "Invalid expires_at format: {}. Use relative format (e.g., '1h', '30m'), RFC3339, 'YYYY-MM-DD HH:MM' or 'YYYY-MM-DD'",
+            expire_str
+        )
+    })
+}
+
+/// 更新请求的 `expires_at`：省略 = 保持不变，显式 `null` 与空字符串、`never` 一样清除过期时间
+pub fn update_expiry(value: &Option<Option<String>>) -> Option<String> {
+    value.as_ref().map(|v| {
+        v.clone()
+            .unwrap_or_else(|| link_validation::EXPIRY_NEVER.to_string())
     })
 }
 
+/// 解析请求中的 `analytics_level`，省略时返回 `None`，非法值返回 400 响应
+pub fn parse_analytics_level(value: Option<&str>) -> Result<Option<AnalyticsLevel>, HttpResponse> {
+    value
+        .map(str::parse::<AnalyticsLevel>)
+        .transpose()
+        .map_err(|e| error_response(ErrorCode::BadRequest, &e))
+}
+
+/// 从原始 query string 中提取 `extras.<key>=<value>` 过滤参数，key 非法时返回 400 响应
+pub fn parse_extras_filter(query_string: &str) -> Result<Vec<(String, String)>, HttpResponse> {
+    let pairs = actix_web::web::Query::<Vec<(String, String)>>::from_query(query_string)
+        .map_err(|e| error_response(ErrorCode::BadRequest, &e.to_string()))?
+        .into_inner();
+
+    let mut filters = Vec::new();
+    for (name, value) in pairs {
+        let Some(key) = name.strip_prefix("extras.") else {
+            continue;
+        };
+        if !link_validation::is_valid_extras_key(key) {
+            return Err(error_response(
+                ErrorCode::BadRequest,
+                &format!("Invalid extras filter key '{}'", key),
+            ));
+        }
+        filters.push((key.to_string(), value));
+    }
+    if filters.len() > link_validation::EXTRAS_MAX_KEYS {
+        return Err(error_response(
+            ErrorCode::BadRequest,
+            &format!(
+                "At most {} extras filters allowed",
+                link_validation::EXTRAS_MAX_KEYS
+            ),
+        ));
+    }
+    Ok(filters)
+}
+
 /// 构建 JSON 响应
 pub fn json_response<T: Serialize>(
     status: StatusCode,
@@ -48,16 +96,47 @@ pub fn success_response<T: Serialize>(data: T) -> HttpResponse {
     json_response(StatusCode::OK, ErrorCode::Success, "OK", Some(data))
 }
 
-/// 构建错误响应
-pub fn error_response(status: StatusCode, error_code: ErrorCode, message: &str) -> HttpResponse {
-    json_response::<()>(status, error_code, message, None)
+/// 构建错误信封（不含 HTTP 状态），供中间件等需要自行组装响应的场景使用
+pub fn error_envelope(
+    error_code: ErrorCode,
+    message: &str,
+    details: Option<serde_json::Value>,
+) -> ErrorEnvelope {
+    let legacy = try_get_runtime_config()
+        .map(|rt| rt.get_bool_or(keys::API_LEGACY_ERROR_FIELDS, true))
+        .unwrap_or(true);
+
+    ErrorEnvelope {
+        error: ErrorBody {
+            code: error_code,
+            message: message.to_string(),
+            details,
+            request_id: current_request_id(),
+        },
+        code: legacy.then_some(error_code as i32),
+        message: legacy.then(|| message.to_string()),
+    }
+}
+
+/// 构建错误响应，HTTP 状态由 `ErrorCode::http_status()` 决定
+pub fn error_response(error_code: ErrorCode, message: &str) -> HttpResponse {
+    error_response_with_details(error_code, message, None)
+}
+
+/// 构建带结构化详情的错误响应
+pub fn error_response_with_details(
+    error_code: ErrorCode,
+    message: &str,
+    details: Option<serde_json::Value>,
+) -> HttpResponse {
+    HttpResponse::build(error_code.http_status())
+        .append_header(("Content-Type", "application/json; charset=utf-8"))
+        .json(error_envelope(error_code, message, details))
 }
 
-/// 从 ShortlinkerError 构建错误响应（自动映射 HTTP 状态码和 ErrorCode）
+/// 从 ShortlinkerError 构建错误响应（自动映射 ErrorCode 与 HTTP 状态码）
 pub fn error_from_shortlinker(err: &ShortlinkerError) -> HttpResponse {
-    let status = err.http_status();
-    let error_code = ErrorCode::from(err.clone());
-    error_response(status, error_code, err.message())
+    error_response(ErrorCode::from(err.clone()), err.message())
 }
 
 /// 统一 Result → HttpResponse 转换
@@ -266,6 +345,13 @@ mod tests {
         assert!(err.contains("Invalid expires_at format"));
     }
 
+    #[test]
+    fn test_parse_expires_at_date_only() {
+        assert!(parse_expires_at("2099-12-31").is_ok());
+        assert!(parse_expires_at("2099-12-31 18:00").is_ok());
+        assert!(parse_expires_at("2099-02-30").is_err());
+    }
+
     #[test]
     fn test_parse_expires_at_empty_string() {
         let result = parse_expires_at("");
@@ -286,31 +372,57 @@ mod tests {
 
     #[test]
     fn test_error_response() {
-        let response = error_response(
-            StatusCode::BAD_REQUEST,
-            ErrorCode::BadRequest,
-            "Something went wrong",
-        );
+        let response = error_response(ErrorCode::BadRequest, "Something went wrong");
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
     #[test]
     fn test_error_response_not_found() {
-        let response = error_response(
-            StatusCode::NOT_FOUND,
-            ErrorCode::NotFound,
-            "Resource not found",
-        );
+        let response = error_response(ErrorCode::NotFound, "Resource not found");
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
     #[test]
     fn test_error_response_internal_error() {
-        let response = error_response(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            ErrorCode::InternalServerError,
-            "Internal error",
-        );
+        let response = error_response(ErrorCode::InternalServerError, "Internal error");
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    #[test]
+    fn test_error_response_status_follows_error_code() {
+        for code in ErrorCode::ALL.iter().filter(|c| **c != ErrorCode::Success) {
+            assert_eq!(error_response(*code, "x").status(), code.http_status());
+        }
+    }
+
+    #[test]
+    fn test_error_from_shortlinker_conflict() {
+        let err = ShortlinkerError::link_already_exists("taken");
+        assert_eq!(error_from_shortlinker(&err).status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_error_envelope_shape() {
+        let envelope = error_envelope(
+            ErrorCode::RateLimitExceeded,
+            "slow down",
+            Some(serde_json::json!({ "retry_after": 3 })),
+        );
+        let value = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(value["error"]["code"], 2004);
+        assert_eq!(value["error"]["message"], "slow down");
+        assert_eq!(value["error"]["details"]["retry_after"], 3);
+        assert!(
+            value["error"]["request_id"]
+                .as_str()
+                .is_some_and(|s| !s.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_error_envelope_omits_empty_details() {
+        let value =
+            serde_json::to_value(error_envelope(ErrorCode::NotFound, "missing", None)).unwrap();
+        assert!(value["error"].get("details").is_none());
+    }
 }
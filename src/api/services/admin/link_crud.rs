@@ -6,16 +6,25 @@ use actix_web::{HttpRequest, HttpResponse, Responder, Result as ActixResult, web
 use std::sync::Arc;
 use tracing::{info, trace};
 
+use crate::analytics::privacy_click_stats;
 use crate::services::{CreateLinkRequest, LinkService, UpdateLinkRequest};
-use crate::storage::LinkFilter;
+use crate::storage::{CreatedVia, LinkFilter, extras_to_value, format_timestamp};
 
+use super::api_tokens::{QuotaScope, analytics_cap};
 use super::error_code::ErrorCode;
-use super::helpers::{error_from_shortlinker, error_response, success_response};
+use super::helpers::{
+    error_from_shortlinker, error_response, parse_analytics_level, parse_extras_filter,
+    success_response, update_expiry,
+};
 use super::types::{
-    ApiResponse, GetLinksQuery, LinkResponse, MessageResponse, PaginatedResponse, PaginationInfo,
-    PostNewLink, StatsResponse,
+    ApiResponse, CreationTrendResponse, GetLinksQuery, LinkResponse, MessageResponse,
+    PaginatedResponse, PaginationInfo, PostNewLink, StatsResponse,
 };
 
+/// 团队 API token 不能绕过删除冷却期
+pub(super) const OVERRIDE_COOLDOWN_FORBIDDEN: &str =
+    "override_cooldown requires administrator credentials";
+
 /// 获取所有链接（支持分页和过滤）
 #[aster_forge_api_docs_macros::path(
         get,
@@ -29,7 +38,7 @@ use super::types::{
         )
 )]
 pub async fn get_all_links(
-    _req: HttpRequest,
+    req: HttpRequest,
     query: web::Query<GetLinksQuery>,
     service: web::Data<Arc<LinkService>>,
 ) -> ActixResult<impl Responder> {
@@ -41,7 +50,6 @@ pub async fn get_all_links(
     // 校验互斥参数：only_expired 和 only_active 不能同时为 true
     if query.only_expired.unwrap_or(false) && query.only_active.unwrap_or(false) {
         return Ok(error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
             ErrorCode::BadRequest,
             "only_expired and only_active are mutually exclusive",
         ));
@@ -56,7 +64,6 @@ pub async fn get_all_links(
             Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
             Err(_) => {
                 return Ok(error_response(
-                    actix_web::http::StatusCode::BAD_REQUEST,
                     ErrorCode::InvalidDateFormat,
                     &format!(
                         "Invalid created_after: '{}'. Use RFC3339 (e.g., 2024-01-01T00:00:00Z)",
@@ -73,7 +80,6 @@ pub async fn get_all_links(
             Ok(dt) => Some(dt.with_timezone(&chrono::Utc)),
             Err(_) => {
                 return Ok(error_response(
-                    actix_web::http::StatusCode::BAD_REQUEST,
                     ErrorCode::InvalidDateFormat,
                     &format!(
                         "Invalid created_before: '{}'. Use RFC3339 (e.g., 2024-01-01T00:00:00Z)",
@@ -85,6 +91,22 @@ pub async fn get_all_links(
         None => None,
     };
 
+    let created_via = match query.created_via.as_deref() {
+        Some(s) => match s.parse::<CreatedVia>() {
+            Ok(via) => Some(via),
+            Err(e) => {
+                return Ok(error_response(ErrorCode::BadRequest, &e));
+            }
+        },
+        None => None,
+    };
+
+    // `extras.<key>=<value>` 不在 GetLinksQuery 中，从原始 query string 读取
+    let extras = match parse_extras_filter(req.query_string()) {
+        Ok(extras) => extras,
+        Err(resp) => return Ok(resp),
+    };
+
     // 构建过滤条件
     let filter = LinkFilter {
         search: query.search.clone(),
@@ -92,6 +114,8 @@ pub async fn get_all_links(
         created_before,
         only_expired: query.only_expired.unwrap_or(false),
         only_active: query.only_active.unwrap_or(false),
+        created_via,
+        extras,
     };
 
     match service.list_links(filter, page, page_size).await {
@@ -140,11 +164,13 @@ pub async fn get_all_links(
         responses(
             (status = 201, description = "Short link created", body = ApiResponse<PostNewLink>),
             (status = 400, description = "Invalid short link"),
-            (status = 409, description = "Short code already exists"),
+            (status = 403, description = "override_cooldown used with a scoped API token"),
+            (status = 409, description = "Short code already exists or is in its reuse cooldown"),
+            (status = 429, description = "API token quota exhausted"),
         )
 )]
 pub async fn post_link(
-    _req: HttpRequest,
+    http_req: HttpRequest,
     link: web::Json<PostNewLink>,
     service: web::Data<Arc<LinkService>>,
 ) -> ActixResult<impl Responder> {
@@ -153,12 +179,39 @@ pub async fn post_link(
         link.code, link.target
     );
 
+    let quota = QuotaScope::from_request(&http_req);
+    let override_cooldown = link.override_cooldown.unwrap_or(false);
+    if override_cooldown && quota.is_some() {
+        return Ok(error_response(
+            ErrorCode::Forbidden,
+            OVERRIDE_COOLDOWN_FORBIDDEN,
+        ));
+    }
+    if let Some(quota) = &quota
+        && let Err(resp) = quota.check(1).await
+    {
+        return Ok(resp);
+    }
+
+    let analytics_level = match parse_analytics_level(link.analytics_level.as_deref()) {
+        Ok(level) => level.unwrap_or_default(),
+        Err(resp) => return Ok(resp),
+    };
+    let analytics_level = match analytics_cap(quota.as_ref()).await {
+        Ok(cap) => analytics_level.capped(cap),
+        Err(resp) => return Ok(resp),
+    };
+
     let req = CreateLinkRequest {
         code: link.code.clone(),
         target: link.target.clone(),
         force: link.force.unwrap_or(false),
-        expires_at: link.expires_at.clone(),
+        expires_at: link.expires_at.clone().flatten(),
         password: link.password.clone(),
+        created_via: CreatedVia::Api,
+        analytics_level,
+        extras: link.extras.as_ref().map(|extras| extras.to_string()),
+        override_cooldown,
     };
 
     match service.create_link(req).await {
@@ -169,6 +222,9 @@ pub async fn post_link(
                 "created"
             };
             info!("Admin API: link {} - {}", action, result.link.code);
+            if let Some(quota) = &quota {
+                quota.record(std::slice::from_ref(&result.link.code)).await;
+            }
 
             Ok(HttpResponse::Created()
                 .append_header(("Content-Type", "application/json; charset=utf-8"))
@@ -178,9 +234,13 @@ pub async fn post_link(
                     data: Some(PostNewLink {
                         code: Some(result.link.code),
                         target: result.link.target,
-                        expires_at: link.expires_at.clone(),
+                        expires_at: Some(result.link.expires_at.as_ref().map(format_timestamp)),
                         password: result.link.password,
                         force: None,
+                        analytics_level: Some(result.link.analytics_level.as_str().to_string()),
+                        extras: extras_to_value(result.link.extras.as_deref()),
+                        override_cooldown: None,
+                        warnings: result.warnings,
                     }),
                 }))
         }
@@ -263,7 +323,7 @@ pub async fn delete_link(
         )
 )]
 pub async fn update_link(
-    _req: HttpRequest,
+    req: HttpRequest,
     code: web::Path<String>,
     link: web::Json<PostNewLink>,
     service: web::Data<Arc<LinkService>>,
@@ -273,27 +333,47 @@ pub async fn update_link(
         code, link.target
     );
 
+    let analytics_level = match parse_analytics_level(link.analytics_level.as_deref()) {
+        Ok(level) => level,
+        Err(resp) => return Ok(resp),
+    };
+    // 团队 token 显式指定的级别收紧到上限，省略时保持链接原有级别
+    let analytics_level = match analytics_cap(QuotaScope::from_request(&req).as_ref()).await {
+        Ok(cap) => analytics_level.map(|level| level.capped(cap)),
+        Err(resp) => return Ok(resp),
+    };
+
     let req = UpdateLinkRequest {
         target: link.target.clone(),
-        expires_at: link.expires_at.clone(),
+        expires_at: update_expiry(&link.expires_at),
         password: link.password.clone(),
+        analytics_level,
+        extras: link.extras.as_ref().map(|extras| extras.to_string()),
     };
 
-    match service.update_link(&code, req).await {
-        Ok(updated_link) => {
+    match service.update_link_checked(&code, req).await {
+        Ok(result) => {
             info!("Admin API: link updated - {}", code);
+            let updated_link = result.link;
             Ok(success_response(PostNewLink {
                 code: Some(updated_link.code),
                 target: updated_link.target,
-                expires_at: updated_link.expires_at.map(|dt| dt.to_rfc3339()),
+                expires_at: Some(updated_link.expires_at.as_ref().map(format_timestamp)),
                 password: updated_link.password,
                 force: None,
+                analytics_level: Some(updated_link.analytics_level.as_str().to_string()),
+                extras: extras_to_value(updated_link.extras.as_deref()),
+                override_cooldown: None,
+                warnings: result.warnings,
             }))
         }
         Err(e) => Ok(error_from_shortlinker(&e)),
     }
 }
 
+/// 统计接口返回的创建趋势天数
+const CREATION_TREND_DAYS: u32 = 30;
+
 /// 获取链接统计信息
 #[aster_forge_api_docs_macros::path(
         get,
@@ -308,19 +388,33 @@ pub async fn get_stats(
 ) -> ActixResult<impl Responder> {
     trace!("Admin API: request to get link stats");
 
-    match service.get_stats().await {
-        Ok(stats) => {
-            info!(
-                "Admin API: returning stats - total: {}, clicks: {}, active: {}",
-                stats.total_links, stats.total_clicks, stats.active_links
-            );
+    let stats = match service.get_stats().await {
+        Ok(stats) => stats,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
+    let creation = match service.get_creation_stats(CREATION_TREND_DAYS).await {
+        Ok(creation) => creation,
+        Err(e) => return Ok(error_from_shortlinker(&e)),
+    };
 
-            Ok(success_response(StatsResponse {
-                total_links: stats.total_links,
-                total_clicks: stats.total_clicks,
-                active_links: stats.active_links,
-            }))
-        }
-        Err(e) => Ok(error_from_shortlinker(&e)),
-    }
+    info!(
+        "Admin API: returning stats - total: {}, clicks: {}, active: {}",
+        stats.total_links, stats.total_clicks, stats.active_links
+    );
+
+    Ok(success_response(StatsResponse {
+        total_links: stats.total_links,
+        total_clicks: stats.total_clicks,
+        active_links: stats.active_links,
+        created_via: creation.by_created_via,
+        created_via_trend: creation
+            .daily
+            .into_iter()
+            .map(|point| CreationTrendResponse {
+                date: point.date,
+                counts: point.counts,
+            })
+            .collect(),
+        privacy_opt_out: privacy_click_stats().into(),
+    }))
 }
@@ -0,0 +1,122 @@
+//! Admin API 元数据端点
+//!
+//! 提供机器可读的错误目录，供第三方 SDK 生成异常类型；面板启动时比对的
+//! 版本握手信息；以及面板拼接完整短链使用的 base URL。
+
+use actix_web::{HttpRequest, HttpResponse, Responder, ResponseError, Result as ActixResult};
+use tracing::trace;
+
+use super::error_code::ErrorCode;
+use super::helpers::{error_response, success_response};
+use super::types::{BaseUrlInfo, ErrorCatalogEntry, VersionInfo};
+use crate::api::version;
+use crate::utils::base_url;
+
+/// 构建错误目录（不含 Success）
+pub fn error_catalog() -> Vec<ErrorCatalogEntry> {
+    ErrorCode::ALL
+        .iter()
+        .filter(|code| **code != ErrorCode::Success)
+        .map(|code| ErrorCatalogEntry {
+            code: *code as i32,
+            name: code.name().to_string(),
+            http_status: code.http_status().as_u16(),
+            description: code.description().to_string(),
+            retryable: code.is_retryable(),
+        })
+        .collect()
+}
+
+/// 获取错误目录
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/meta/errors",
+        tag = "meta",
+        operation_id = "list_error_codes",
+        responses((status = 200, description = "Error code catalog", body = super::types::ApiResponse<Vec<ErrorCatalogEntry>>))
+)]
+pub async fn get_error_catalog(_req: HttpRequest) -> ActixResult<impl Responder> {
+    trace!("Admin API: request error catalog");
+    Ok(success_response(error_catalog()))
+}
+
+/// 构建版本握手信息
+pub fn version_info() -> VersionInfo {
+    let current = version::current();
+    VersionInfo {
+        api_version: version::ADMIN_API_VERSION,
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        min_panel_version: current.min_panel_version.to_string(),
+    }
+}
+
+/// 获取 API 版本与最低兼容面板版本
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/meta/version",
+        tag = "meta",
+        operation_id = "get_api_version",
+        responses((status = 200, description = "API version and panel compatibility", body = super::types::ApiResponse<VersionInfo>))
+)]
+pub async fn get_version(_req: HttpRequest) -> ActixResult<impl Responder> {
+    trace!("Admin API: request version info");
+    Ok(success_response(version_info()))
+}
+
+/// 获取当前请求对应的短链 base URL
+///
+/// 显式配置 `features.public_base_url` 优先，否则按可信代理转发头或 `Host` 推断。
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/meta/base-url",
+        tag = "meta",
+        operation_id = "get_base_url",
+        responses((status = 200, description = "Base URL used for full short link URLs", body = super::types::ApiResponse<BaseUrlInfo>))
+)]
+pub async fn get_base_url(req: HttpRequest) -> ActixResult<impl Responder> {
+    trace!("Admin API: request base URL");
+    let resolved = base_url::resolve(&req);
+    Ok(success_response(BaseUrlInfo {
+        base_url: resolved.url,
+        source: resolved.source.as_str().to_string(),
+    }))
+}
+
+/// Admin 作用域内未匹配路由的兜底响应
+pub async fn admin_not_found(req: HttpRequest) -> HttpResponse {
+    error_response(
+        ErrorCode::NotFound,
+        &format!("No admin route for {} {}", req.method(), req.path()),
+    )
+}
+
+/// 提取器（Json / Query / Path）失败时返回统一错误信封
+pub fn extractor_error<E: ResponseError + 'static>(err: E, _req: &HttpRequest) -> actix_web::Error {
+    let response = error_response(ErrorCode::BadRequest, &err.to_string());
+    actix_web::error::InternalError::from_response(err, response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_catalog_excludes_success() {
+        let catalog = error_catalog();
+        assert_eq!(catalog.len(), ErrorCode::ALL.len() - 1);
+        assert!(catalog.iter().all(|entry| entry.code != 0));
+    }
+
+    #[test]
+    fn test_error_catalog_entry_fields() {
+        let catalog = error_catalog();
+        let entry = catalog
+            .iter()
+            .find(|entry| entry.name == "RateLimitExceeded")
+            .expect("RateLimitExceeded in catalog");
+        assert_eq!(entry.code, 2004);
+        assert_eq!(entry.http_status, 429);
+        assert!(entry.retryable);
+        assert!(!entry.description.is_empty());
+    }
+}
@@ -8,14 +8,22 @@
 //! - 分析统计
 
 pub mod analytics;
+pub(crate) mod api_tokens;
+pub(crate) mod archive;
 pub mod auth;
 pub(crate) mod batch_ops;
+pub(crate) mod cache;
 pub(crate) mod config_ops;
 pub mod error_code;
 pub(crate) mod export_import;
+pub(crate) mod geoip;
 mod helpers;
 pub(crate) mod link_crud;
+pub mod meta;
 pub mod routes;
+pub(crate) mod sample;
+pub(crate) mod security;
+pub(crate) mod tasks;
 pub(crate) mod types;
 
 // 重新导出类型
@@ -23,7 +31,8 @@ pub use types::*;
 
 // 重新导出帮助函数
 pub use helpers::{
-    api_result, error_from_shortlinker, error_response, parse_expires_at, success_response,
+    api_result, error_envelope, error_from_shortlinker, error_response,
+    error_response_with_details, parse_expires_at, success_response,
 };
 
 // 重新导出错误码
@@ -38,9 +47,20 @@ pub use link_crud::{delete_link, get_all_links, get_link, get_stats, post_link,
 // 重新导出批量操作端点
 pub use batch_ops::{batch_create_links, batch_delete_links, batch_update_links};
 
+// 重新导出归档端点
+pub use archive::{archive_links, get_archived_links, unarchive_link};
+
+// 重新导出抽样端点
+pub use sample::sample_links;
+
 // 重新导出导出导入端点
 pub use export_import::{export_links, import_links};
 
+// 重新导出团队 API Token 端点
+pub use api_tokens::{
+    create_api_token, delete_api_token, get_api_token_usage, list_api_tokens, update_api_token,
+};
+
 // 重新导出配置管理端点
 pub use config_ops::{
     ConfigHistoryResponse, ConfigItemResponse, ConfigUpdateRequest, ConfigUpdateResponse,
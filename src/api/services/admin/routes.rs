@@ -4,24 +4,44 @@
 
 use actix_web::web;
 
-use super::analytics::{analytics_routes, get_link_analytics, get_link_device_stats};
+use super::analytics::{
+    analytics_routes, get_link_analytics, get_link_device_stats, get_link_param_stats,
+};
+use super::api_tokens::{
+    create_api_token, delete_api_token, get_api_token_usage, list_api_tokens, update_api_token,
+};
+use super::archive::{archive_links, get_archived_links, unarchive_link};
 use super::auth::{
     check_admin_token, login_rate_limiter, logout, refresh_rate_limiter, refresh_token,
     verify_token,
 };
-use super::batch_ops::{batch_create_links, batch_delete_links, batch_update_links};
+use super::batch_ops::{
+    batch_create_links, batch_delete_links, batch_extend_links, batch_update_links, generate_links,
+};
+use super::cache::get_cache_stats;
 use super::config_ops::{
     execute_and_save_config_action, execute_config_action, get_all_configs, get_config,
     get_config_history, get_config_schema, reload_config, update_config,
 };
 use super::export_import::{export_links, import_links};
+use super::geoip::get_geoip_status;
 use super::link_crud::{delete_link, get_all_links, get_link, get_stats, post_link, update_link};
+use super::meta::{get_base_url, get_error_catalog, get_version};
+use super::sample::sample_links;
+use super::security::{delete_auto_ban, list_auto_bans};
+use super::tasks::get_tasks_health;
 
 /// 链接管理路由 `/links`
 ///
 /// 包含：
 /// - GET/HEAD /links - 获取所有链接
 /// - POST /links - 创建链接
+/// - POST /links/batch_extend - 批量顺延过期时间
+/// - POST /links/generate - 按模板批量生成
+/// - POST /links/archive - 归档链接
+/// - GET /links/archived - 分页查询归档链接
+/// - POST /links/{code}/unarchive - 从归档恢复
+/// - GET /links/sample - 随机抽样
 /// - GET/HEAD /links/{code} - 获取单个链接
 /// - PUT /links/{code} - 更新链接
 /// - DELETE /links/{code} - 删除链接
@@ -36,6 +56,14 @@ pub fn links_routes() -> actix_web::Scope {
         .route("/batch", web::post().to(batch_create_links))
         .route("/batch", web::put().to(batch_update_links))
         .route("/batch", web::delete().to(batch_delete_links))
+        .route("/batch_extend", web::post().to(batch_extend_links))
+        .route("/generate", web::post().to(generate_links))
+        // Archive operations (must be before /{code:.*})
+        .route("/archive", web::post().to(archive_links))
+        .route("/archived", web::get().to(get_archived_links))
+        .route("/{code}/unarchive", web::post().to(unarchive_link))
+        // Random sampling (must be before /{code:.*})
+        .route("/sample", web::get().to(sample_links))
         // Export/Import operations (must be before /{code:.*})
         .route("/export", web::get().to(export_links))
         .route("/import", web::post().to(import_links))
@@ -44,6 +72,10 @@ pub fn links_routes() -> actix_web::Scope {
             "/{code}/analytics/devices",
             web::get().to(get_link_device_stats),
         )
+        .route(
+            "/{code}/analytics/params",
+            web::get().to(get_link_param_stats),
+        )
         .route("/{code}/analytics", web::get().to(get_link_analytics))
         // Single link operations (must be last due to wildcard)
         .route("/{code:.*}", web::get().to(get_link))
@@ -59,6 +91,41 @@ pub fn stats_routes() -> actix_web::Scope {
         .route("", web::head().to(get_stats))
 }
 
+/// 缓存状态路由 `/cache`
+///
+/// 包含：
+/// - GET /cache/stats - Bloom 判定统计与容量建议
+pub fn cache_routes() -> actix_web::Scope {
+    web::scope("/cache").route("/stats", web::get().to(get_cache_stats))
+}
+
+/// GeoIP 状态路由 `/geoip`
+///
+/// 包含：
+/// - GET /geoip/status - 外部 API 熔断、并发与配额状态
+pub fn geoip_routes() -> actix_web::Scope {
+    web::scope("/geoip").route("/status", web::get().to(get_geoip_status))
+}
+
+/// 安全路由 `/security`
+///
+/// 包含：
+/// - GET /security/bans - 当前 404 自动封禁列表
+/// - DELETE /security/bans/{ip} - 手动解除封禁
+pub fn security_routes() -> actix_web::Scope {
+    web::scope("/security")
+        .route("/bans", web::get().to(list_auto_bans))
+        .route("/bans/{ip}", web::delete().to(delete_auto_ban))
+}
+
+/// 后台任务路由 `/tasks`
+///
+/// 包含：
+/// - GET /tasks/health - 后台任务状态、心跳与重启次数
+pub fn tasks_routes() -> actix_web::Scope {
+    web::scope("/tasks").route("/health", web::get().to(get_tasks_health))
+}
+
 /// 认证路由 `/auth`
 ///
 /// 包含：
@@ -80,6 +147,23 @@ pub fn auth_routes() -> actix_web::Scope {
         .route("/verify", web::get().to(verify_token))
 }
 
+/// 团队 API Token 路由 `/tokens`
+///
+/// 包含：
+/// - GET /tokens - 列出 token
+/// - POST /tokens - 创建 token（明文仅返回一次）
+/// - PUT /tokens/{id} - 更新配额
+/// - DELETE /tokens/{id} - 删除 token
+/// - GET /tokens/{id}/usage - 查询当前用量（团队 token 可查询自身）
+pub fn tokens_routes() -> actix_web::Scope {
+    web::scope("/tokens")
+        .route("", web::get().to(list_api_tokens))
+        .route("", web::post().to(create_api_token))
+        .route("/{id}/usage", web::get().to(get_api_token_usage))
+        .route("/{id}", web::put().to(update_api_token))
+        .route("/{id}", web::delete().to(delete_api_token))
+}
+
 /// 配置管理路由 `/config`
 ///
 /// 包含：
@@ -109,6 +193,19 @@ pub fn config_routes() -> actix_web::Scope {
         .route("/{key:.*}", web::put().to(update_config))
 }
 
+/// 元数据路由 `/meta`（不属于版本化 API，无需认证）
+///
+/// 包含：
+/// - GET /meta/errors - 错误码目录
+/// - GET /meta/version - API 版本与最低兼容面板版本
+/// - GET /meta/base-url - 完整短链使用的 base URL
+pub fn meta_routes() -> actix_web::Scope {
+    web::scope("/meta")
+        .route("/errors", web::get().to(get_error_catalog))
+        .route("/version", web::get().to(get_version))
+        .route("/base-url", web::get().to(get_base_url))
+}
+
 /// Admin API v1 路由
 ///
 /// 组合所有子模块路由
@@ -116,7 +213,12 @@ pub fn admin_v1_routes() -> actix_web::Scope {
     web::scope("/v1")
         .service(links_routes())
         .service(stats_routes())
+        .service(cache_routes())
+        .service(geoip_routes())
+        .service(security_routes())
+        .service(tasks_routes())
         .service(auth_routes())
+        .service(tokens_routes())
         .service(config_routes())
         .service(analytics_routes())
 }
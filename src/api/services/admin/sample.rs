@@ -0,0 +1,82 @@
+//! Admin API 链接随机抽样
+//!
+//! 供内容安全抽检使用：在满足过滤条件的链接中均匀抽取 n 条，
+//! 通过 `seed` 复现同一批样本。
+
+use actix_web::{HttpRequest, Responder, Result as ActixResult, web};
+use std::sync::Arc;
+use tracing::{info, trace};
+
+use crate::services::{LinkService, MAX_SAMPLE_SIZE};
+use crate::storage::CreatedVia;
+
+use super::batch_ops::build_extend_filter;
+use super::error_code::ErrorCode;
+use super::helpers::{error_from_shortlinker, error_response, success_response};
+use super::types::{BatchExtendFilter, LinkResponse, SampleLinksQuery, SampleLinksResponse};
+
+/// 默认样本量
+const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// 随机抽样链接
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/links/sample",
+        tag = "links",
+        operation_id = "sample_links",
+        params(SampleLinksQuery),
+        responses(
+            (status = 200, description = "Uniform random sample", body = super::types::ApiResponse<SampleLinksResponse>),
+            (status = 400, description = "Invalid sample size or filter"),
+        )
+)]
+pub async fn sample_links(
+    _req: HttpRequest,
+    query: web::Query<SampleLinksQuery>,
+    service: web::Data<Arc<LinkService>>,
+) -> ActixResult<impl Responder> {
+    trace!("Admin API: sample request: {:?}", query);
+    let query = query.into_inner();
+
+    let n = query.n.unwrap_or(DEFAULT_SAMPLE_SIZE);
+    if n == 0 || n > MAX_SAMPLE_SIZE {
+        return Ok(error_response(
+            ErrorCode::BadRequest,
+            &format!("n must be between 1 and {}", MAX_SAMPLE_SIZE),
+        ));
+    }
+
+    let mut filter = match build_extend_filter(&BatchExtendFilter {
+        search: query.search,
+        created_after: query.created_after,
+        created_before: query.created_before,
+        only_expired: query.only_expired,
+        only_active: query.only_active,
+    }) {
+        Ok(filter) => filter,
+        Err(msg) => return Ok(error_response(ErrorCode::InvalidDateFormat, &msg)),
+    };
+    if let Some(s) = query.created_via.as_deref() {
+        match s.parse::<CreatedVia>() {
+            Ok(via) => filter.created_via = Some(via),
+            Err(e) => return Ok(error_response(ErrorCode::BadRequest, &e)),
+        }
+    }
+
+    match service.sample_links(filter, n, query.seed).await {
+        Ok(sample) => {
+            info!(
+                "Admin API: returning sample of {} / {} links (seed {})",
+                sample.links.len(),
+                sample.population,
+                sample.seed
+            );
+            Ok(success_response(SampleLinksResponse {
+                seed: sample.seed,
+                population: sample.population,
+                links: sample.links.into_iter().map(LinkResponse::from).collect(),
+            }))
+        }
+        Err(e) => Ok(error_from_shortlinker(&e)),
+    }
+}
@@ -0,0 +1,81 @@
+//! Admin API 安全端点：查看与解除 404 自动封禁
+//!
+//! 封禁状态只存本进程内存，多实例部署时只反映、只解除被请求的实例。
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use actix_web::{Responder, Result as ActixResult, web};
+use tracing::{info, trace};
+
+use super::error_code::ErrorCode;
+use super::helpers::{error_response, success_response};
+use super::types::{ApiResponse, AutoBanListResponse, AutoBanResponse, MessageResponse};
+use crate::metrics::MetricsRecorder;
+use crate::services::auto_ban::{auto_ban_settings, auto_banner};
+
+/// 列出当前封禁
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/security/bans",
+        tag = "security",
+        operation_id = "list_auto_bans",
+        responses((status = 200, description = "Active auto-bans", body = ApiResponse<AutoBanListResponse>))
+)]
+pub async fn list_auto_bans() -> ActixResult<impl Responder> {
+    trace!("Admin API: list auto-bans");
+
+    let settings = auto_ban_settings();
+    let now = Instant::now();
+    Ok(success_response(AutoBanListResponse {
+        enabled: settings.enabled(),
+        threshold: settings.threshold,
+        window_secs: settings.window.as_secs(),
+        ban_minutes: settings.ban_duration.as_secs() / 60,
+        bans: auto_banner()
+            .list(now)
+            .iter()
+            .map(|ban| AutoBanResponse::new(ban, now))
+            .collect(),
+    }))
+}
+
+/// 手动解除封禁
+#[aster_forge_api_docs_macros::path(
+        delete,
+        path = "/admin/v1/security/bans/{ip}",
+        tag = "security",
+        operation_id = "delete_auto_ban",
+        params(("ip" = String, Path, description = "Banned IP address")),
+        responses(
+            (status = 200, description = "Ban lifted", body = ApiResponse<MessageResponse>),
+            (status = 400, description = "Invalid IP address"),
+            (status = 404, description = "IP is not banned"),
+        )
+)]
+pub async fn delete_auto_ban(
+    ip: web::Path<String>,
+    metrics: web::Data<Arc<dyn MetricsRecorder>>,
+) -> ActixResult<impl Responder> {
+    let Ok(addr) = ip.parse::<IpAddr>() else {
+        return Ok(error_response(
+            ErrorCode::BadRequest,
+            &format!("Invalid IP address: '{}'", ip),
+        ));
+    };
+
+    match auto_banner().unban(addr) {
+        Some(_) => {
+            info!("Admin API: auto-ban lifted for IP {}", addr);
+            metrics.inc_auto_ban_event("unban");
+            Ok(success_response(MessageResponse {
+                message: format!("Ban lifted for {}", addr),
+            }))
+        }
+        None => Ok(error_response(
+            ErrorCode::NotFound,
+            &format!("IP {} is not banned", addr),
+        )),
+    }
+}
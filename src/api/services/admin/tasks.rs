@@ -0,0 +1,32 @@
+//! Admin API 后台任务健康端点
+//!
+//! 列出 [`crate::runtime::supervisor`] 监控下的后台任务状态。
+//! 状态只存本进程内存，多实例部署时只反映被请求的实例。
+
+use actix_web::{Responder, Result as ActixResult};
+use tracing::trace;
+
+use super::helpers::success_response;
+use super::types::{ApiResponse, TaskHealthListResponse, TaskHealthResponse};
+use crate::runtime::supervisor;
+
+/// 获取后台任务健康状态
+#[aster_forge_api_docs_macros::path(
+        get,
+        path = "/admin/v1/tasks/health",
+        tag = "tasks",
+        operation_id = "get_tasks_health",
+        responses((status = 200, description = "Background task health", body = ApiResponse<TaskHealthListResponse>))
+)]
+pub async fn get_tasks_health() -> ActixResult<impl Responder> {
+    trace!("Admin API: request background task health");
+
+    // 后台任务未启动（如 CLI / 测试环境）时返回空列表
+    let snapshot = supervisor::registry()
+        .map(|registry| registry.snapshot())
+        .unwrap_or_default();
+    Ok(success_response(TaskHealthListResponse {
+        healthy: !snapshot.iter().any(|task| task.state.is_unhealthy()),
+        tasks: snapshot.into_iter().map(TaskHealthResponse::from).collect(),
+    }))
+}
@@ -1,8 +1,15 @@
 //! Admin API 类型定义
 
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
 
-use crate::storage::ShortLink;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::runtime::supervisor::TaskHealth;
+use crate::services::auto_ban::Ban;
+use crate::services::geoip::ApiGuardStatus;
+use crate::services::{ApiTokenUsage, BloomStats, TemplateLink, TemplateVar};
+use crate::storage::{ApiToken, ArchivedLink, ShortLink, extras_to_value, format_timestamp};
 
 // Re-export ValueType from config module
 pub use crate::config::ValueType;
@@ -22,16 +29,105 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
 }
 
+/// 统一错误详情
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ErrorBody {
+    pub code: super::ErrorCode,
+    pub message: String,
+    /// 结构化附加信息（如限流的 retry_after），没有时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    pub request_id: String,
+}
+
+/// Admin API 错误响应
+///
+/// 新结构为 `{ "error": {...} }`；`api.legacy_error_fields` 开启时额外附带
+/// 旧版顶层 `code` / `message` 字段，供旧前端过渡使用。
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+    /// 已废弃：旧版顶层错误码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<i32>,
+    /// 已废弃：旧版顶层错误消息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 错误目录条目（`GET /admin/meta/errors`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ErrorCatalogEntry {
+    pub code: i32,
+    pub name: String,
+    pub http_status: u16,
+    pub description: String,
+    pub retryable: bool,
+}
+
+/// 版本握手信息（`GET /admin/meta/version`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct VersionInfo {
+    /// Admin API 版本（破坏性变更时递增）
+    pub api_version: u32,
+    /// 后端版本
+    pub server_version: String,
+    /// 最低兼容的面板版本
+    pub min_panel_version: String,
+}
+
+/// 短链 base URL（`GET /admin/meta/base-url`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BaseUrlInfo {
+    /// 生成完整短链时使用的 base URL，不带末尾 `/`
+    pub base_url: String,
+    /// 来源：`config`（显式配置）/ `forwarded`（可信代理转发头）/ `host`（Host 头）
+    pub source: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
 pub struct PostNewLink {
     pub code: Option<String>,
     pub target: String,
-    pub expires_at: Option<String>,
+    /// 过期时间：`null`、空字符串或 `never` = 永不过期，`now` = 立即过期；
+    /// 更新时省略字段 = 保持不变，显式 `null` = 清除过期时间
+    #[serde(default, deserialize_with = "deserialize_explicit_null")]
+    #[cfg_attr(
+        all(debug_assertions, feature = "openapi"),
+        schema(value_type = Option<String>)
+    )]
+    pub expires_at: Option<Option<String>>,
     pub password: Option<String>,
     pub force: Option<bool>,
+    /// 点击统计级别：inherit / none / count_only / aggregate / full（更新时省略 = 保持不变）
+    pub analytics_level: Option<String>,
+    /// 自定义扩展字段，必须是 JSON 对象（更新时省略 = 保持不变，`{}` = 清空）
+    pub extras: Option<serde_json::Value>,
+    /// 忽略删除冷却期（`links.code_reuse_cooldown_days`）强制复用短码，仅管理员凭据可用
+    pub override_cooldown: Option<bool>,
+    /// 仅响应：非致命提示，如目标 URL 中检测到凭据（`links.credential_policy`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// 区分"字段缺省"（`None`）与"显式 null"（`Some(None)`）
+fn deserialize_explicit_null<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer).map(Some)
 }
 
+/// 链接列表查询参数
+///
+/// 另支持 `extras.<key>=<value>` 按扩展字段顶层 key 精确匹配（可重复，均需满足）；
+/// 参数名不固定，由 handler 从原始 query string 解析。
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[cfg_attr(
     all(debug_assertions, feature = "openapi"),
@@ -45,6 +141,8 @@ pub struct GetLinksQuery {
     pub only_expired: Option<bool>,
     pub only_active: Option<bool>,
     pub search: Option<String>,
+    /// 按创建渠道过滤：api / cli / tui / import / ipc / bootstrap / unknown
+    pub created_via: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -107,6 +205,188 @@ pub struct BatchFailedItem {
     pub error_code: Option<i32>,
 }
 
+/// 批量顺延的过滤条件（与列表/导出的过滤参数一致）
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BatchExtendFilter {
+    pub search: Option<String>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub only_expired: Option<bool>,
+    pub only_active: Option<bool>,
+}
+
+/// 批量顺延过期时间请求
+///
+/// `codes` 与 `filter` 二选一；`extend_by` 与 `new_expires_at` 二选一。
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BatchExtendRequest {
+    pub codes: Option<Vec<String>>,
+    pub filter: Option<BatchExtendFilter>,
+    /// 相对偏移，如 "7d"、"1d12h"
+    pub extend_by: Option<String>,
+    /// 绝对时间（RFC3339 或相对格式）
+    pub new_expires_at: Option<String>,
+    /// 永不过期的链接是否也处理（默认跳过）
+    pub include_permanent: Option<bool>,
+    /// 已过期链接是否从当前时间起算（默认从原 expires_at 起算）
+    pub revive_from_now: Option<bool>,
+    /// 只返回计算结果，不写入
+    pub dry_run: Option<bool>,
+}
+
+/// 单条顺延结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BatchExtendItem {
+    pub code: String,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub old_expires_at: Option<String>,
+    pub new_expires_at: String,
+}
+
+/// 批量顺延响应
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BatchExtendResponse {
+    pub dry_run: bool,
+    pub updated: Vec<BatchExtendItem>,
+    pub skipped: Vec<BatchFailedItem>,
+    pub failed: Vec<BatchFailedItem>,
+}
+
+/// 模板批量生成请求
+///
+/// `url_template` / `code_template` 中的 `{name}` 引用 `vars` 中的同名变量，
+/// 按变量取值的笛卡尔积展开；未提供 `code_template` 时随机生成短码。
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct GenerateLinksRequest {
+    pub url_template: String,
+    pub code_template: Option<String>,
+    pub vars: Vec<TemplateVar>,
+    /// 覆盖已存在的短码（默认记为失败）
+    pub force: Option<bool>,
+    pub expires_at: Option<String>,
+    pub password: Option<String>,
+    /// 只展开预览，不写入
+    pub dry_run: Option<bool>,
+}
+
+/// 模板批量生成响应
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct GenerateLinksResponse {
+    pub dry_run: bool,
+    /// 组合总数
+    pub total: usize,
+    /// 预览时为展开结果，写入时为实际创建的链接
+    pub links: Vec<TemplateLink>,
+    /// 已存在的短码（仅预览时返回）
+    pub existing: Vec<String>,
+    pub failed: Vec<BatchFailedItem>,
+}
+
+/// 归档链接请求
+///
+/// `codes` 与 `filter` 二选一；`filter` 匹配的链接数量不设上限，按批事务执行。
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ArchiveLinksRequest {
+    pub codes: Option<Vec<String>>,
+    pub filter: Option<BatchExtendFilter>,
+}
+
+/// 归档链接响应
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ArchiveLinksResponse {
+    pub archived: Vec<String>,
+    /// 主表中不存在的短码（含此前已归档的）
+    pub not_found: Vec<String>,
+    pub failed: Vec<BatchFailedItem>,
+}
+
+/// 归档链接列表查询参数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(
+    all(debug_assertions, feature = "openapi"),
+    derive(utoipa::IntoParams, utoipa::ToSchema)
+)]
+pub struct GetArchivedQuery {
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub search: Option<String>,
+}
+
+/// 已归档链接
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ArchivedLinkResponse {
+    pub code: String,
+    pub target: String,
+    pub created_at: String,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub expires_at: Option<String>,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub password: Option<String>,
+    pub click_count: usize,
+    pub created_via: String,
+    pub archived_at: String,
+}
+
+impl From<ArchivedLink> for ArchivedLinkResponse {
+    fn from(archived: ArchivedLink) -> Self {
+        let link = LinkResponse::from(archived.link);
+        Self {
+            code: link.code,
+            target: link.target,
+            created_at: link.created_at,
+            expires_at: link.expires_at,
+            password: link.password,
+            click_count: link.click_count,
+            created_via: link.created_via,
+            archived_at: format_timestamp(&archived.archived_at),
+        }
+    }
+}
+
+/// 随机抽样查询参数
+///
+/// 过滤条件与 `GET /links` 相同；`seed` 缺省时由服务端随机生成并在响应中返回。
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(
+    all(debug_assertions, feature = "openapi"),
+    derive(utoipa::IntoParams, utoipa::ToSchema)
+)]
+pub struct SampleLinksQuery {
+    /// 样本量，默认 100，最大 1000
+    pub n: Option<usize>,
+    /// 随机种子，相同 seed + 相同数据得到相同样本
+    pub seed: Option<u64>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub only_expired: Option<bool>,
+    pub only_active: Option<bool>,
+    pub search: Option<String>,
+    /// 按创建渠道过滤：api / cli / tui / import / ipc / bootstrap / unknown
+    pub created_via: Option<String>,
+}
+
+/// 随机抽样结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct SampleLinksResponse {
+    /// 本次使用的 seed，复查时原样传回
+    pub seed: u64,
+    /// 满足过滤条件的链接总数
+    pub population: u64,
+    /// 样本（按短码排序）
+    pub links: Vec<LinkResponse>,
+}
+
+/// 链接响应，字段与时间格式遵循 [`link_schema`](crate::storage::link_schema)
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
 pub struct LinkResponse {
@@ -118,6 +398,11 @@ pub struct LinkResponse {
     #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
     pub password: Option<String>,
     pub click_count: usize,
+    pub created_via: String,
+    pub analytics_level: String,
+    /// 自定义扩展字段（JSON 对象），未设置时为 null
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub extras: Option<serde_json::Value>,
 }
 
 impl From<ShortLink> for LinkResponse {
@@ -125,10 +410,13 @@ impl From<ShortLink> for LinkResponse {
         Self {
             code: link.code,
             target: link.target,
-            created_at: link.created_at.to_rfc3339(),
-            expires_at: link.expires_at.map(|dt| dt.to_rfc3339()),
+            created_at: format_timestamp(&link.created_at),
+            expires_at: link.expires_at.as_ref().map(format_timestamp),
             password: link.password,
             click_count: link.click,
+            created_via: link.created_via.as_str().to_string(),
+            analytics_level: link.analytics_level.as_str().to_string(),
+            extras: extras_to_value(link.extras.as_deref()),
         }
     }
 }
@@ -140,6 +428,42 @@ pub struct StatsResponse {
     pub total_links: usize,
     pub total_clicks: usize,
     pub active_links: usize,
+    /// 按创建渠道的链接数
+    pub created_via: BTreeMap<String, usize>,
+    /// 近 30 天按天、按渠道的创建数（UTC）
+    pub created_via_trend: Vec<CreationTrendResponse>,
+    /// 本实例启动以来因隐私信号（DNT / GPC）未记录明细的点击
+    pub privacy_opt_out: PrivacyOptOutResponse,
+}
+
+/// 因隐私信号未记录明细的点击计数（进程内，重启清零）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct PrivacyOptOutResponse {
+    /// 进入统计的点击总数
+    pub clicks: u64,
+    /// 其中未记录明细的点击数
+    pub opted_out: u64,
+    /// 占比（0.0-1.0）
+    pub ratio: f64,
+}
+
+impl From<crate::analytics::PrivacyClickStats> for PrivacyOptOutResponse {
+    fn from(stats: crate::analytics::PrivacyClickStats) -> Self {
+        Self {
+            clicks: stats.clicks,
+            opted_out: stats.opted_out,
+            ratio: stats.opted_out_ratio(),
+        }
+    }
+}
+
+/// 单日按创建渠道的创建数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct CreationTrendResponse {
+    pub date: String,
+    pub counts: BTreeMap<String, usize>,
 }
 
 /// 简单消息响应
@@ -251,6 +575,17 @@ pub struct ImportFailedItem {
     pub error_code: Option<i32>,
 }
 
+/// 导入警告项：该行已照常导入，但需要提示（如过期时间已经过去）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ImportWarningItem {
+    /// CSV 行号（1-based）
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub row: Option<usize>,
+    pub code: String,
+    pub warning: String,
+}
+
 /// 导入响应
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
@@ -260,6 +595,350 @@ pub struct ImportResponse {
     pub skipped_count: usize,
     pub failed_count: usize,
     pub failed_items: Vec<ImportFailedItem>,
+    /// 已导入但需要提示的行
+    pub warnings: Vec<ImportWarningItem>,
+    /// CSV 方言检测结果，如 `detected: ; delimiter, latin-1 encoding`
+    pub detected: String,
+    /// 是否为 dry-run（只解析、转换与校验，未写入）
+    #[serde(default)]
+    pub dry_run: bool,
+    /// dry-run 时转换后的前 10 行
+    #[serde(default)]
+    pub preview: Vec<ImportPreviewItem>,
+}
+
+/// 导入 dry-run 预览行（字段转换之后、校验之前的取值，不回显密码）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ImportPreviewItem {
+    /// CSV 原文件行号
+    pub row: usize,
+    pub code: String,
+    pub target: String,
+    pub created_at: String,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub expires_at: Option<String>,
+    pub has_password: bool,
+    pub click_count: usize,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub analytics_level: Option<String>,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub extras: Option<String>,
+}
+
+impl ImportPreviewItem {
+    pub fn new(row: usize, csv: &CsvLinkRow) -> Self {
+        let non_empty = |v: &Option<String>| v.clone().filter(|s| !s.is_empty());
+        Self {
+            row,
+            code: csv.code.clone(),
+            target: csv.target.clone(),
+            created_at: csv.created_at.clone(),
+            expires_at: non_empty(&csv.expires_at),
+            has_password: csv.password.as_deref().is_some_and(|p| !p.is_empty()),
+            click_count: csv.click_count,
+            analytics_level: non_empty(&csv.analytics_level),
+            extras: non_empty(&csv.extras),
+        }
+    }
+}
+
+/// 团队 API Token（不含明文与哈希）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ApiTokenResponse {
+    pub id: String,
+    pub name: String,
+    /// 最大链接数，null 为不限
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub max_links: Option<u64>,
+    /// 每日（UTC）最大创建数，null 为不限
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub max_daily_creates: Option<u64>,
+    /// 名下链接的统计级别上限，null 为不限
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub max_analytics_level: Option<String>,
+    pub created_at: String,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            max_links: token.max_links,
+            max_daily_creates: token.max_daily_creates,
+            max_analytics_level: token
+                .max_analytics_level
+                .map(|level| level.as_str().to_string()),
+            created_at: token.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// 创建 API Token 请求
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub max_links: Option<u64>,
+    pub max_daily_creates: Option<u64>,
+    /// 统计级别上限：none / count_only / aggregate / full
+    pub max_analytics_level: Option<String>,
+}
+
+/// 创建 API Token 响应；`token` 明文仅此一次返回
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub info: ApiTokenResponse,
+    pub token: String,
+}
+
+/// 更新 API Token 配额请求（整体替换，省略或 null 为不限）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct UpdateApiTokenRequest {
+    pub max_links: Option<u64>,
+    pub max_daily_creates: Option<u64>,
+    /// 统计级别上限：none / count_only / aggregate / full
+    pub max_analytics_level: Option<String>,
+}
+
+/// API Token 当前用量
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct ApiTokenUsageResponse {
+    pub token_id: String,
+    /// 每日计数所属日期（UTC，YYYY-MM-DD）
+    pub day: String,
+    /// 名下链接数（实时统计，不含已归档链接）
+    pub links: u64,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub max_links: Option<u64>,
+    pub created_today: u64,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub max_daily_creates: Option<u64>,
+}
+
+impl From<ApiTokenUsage> for ApiTokenUsageResponse {
+    fn from(usage: ApiTokenUsage) -> Self {
+        Self {
+            token_id: usage.token_id,
+            day: usage.day,
+            links: usage.links,
+            max_links: usage.max_links,
+            created_today: usage.created_today,
+            max_daily_creates: usage.max_daily_creates,
+        }
+    }
+}
+
+/// 缓存运行状态（`GET /admin/v1/cache/stats`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct CacheStatsResponse {
+    /// 缓存后端（`memory` / `redis`）
+    pub cache_type: String,
+    /// 当前这一代 Bloom 的判定统计（进程内，重建后清零）
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub bloom: Option<BloomStatsResponse>,
+}
+
+/// Bloom 判定统计与容量建议
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct BloomStatsResponse {
+    /// 重建代数
+    pub generation: u64,
+    /// 计数起点（上次重建完成时间，RFC 3339）
+    pub since: String,
+    /// 构建容量（分片时为本分片）
+    pub capacity: u64,
+    /// 构建时载入的短码数
+    pub loaded: u64,
+    pub target_false_positive_rate: f64,
+    /// Bloom 否定、直接 404 的次数
+    pub rejected: u64,
+    /// Bloom 放行且库中存在
+    pub hits: u64,
+    /// Bloom 放行但库中不存在（假阳性）
+    pub false_positives: u64,
+    /// Bloom 放行但短码已在本代 Bloom 构建后删除 / 归档
+    pub stale: u64,
+    /// `false_positives / (false_positives + rejected)`，无样本时为 null
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub observed_false_positive_rate: Option<f64>,
+    /// 观测假阳率高于目标时按公式反推的建议容量
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub suggested_capacity: Option<u64>,
+    /// 告警阈值（`cache.bloom_fp_alert_rate`，0 = 不告警）
+    pub alert_threshold: f64,
+    /// 样本充足且观测假阳率超过告警阈值
+    pub alerting: bool,
+}
+
+impl BloomStatsResponse {
+    pub fn new(stats: &BloomStats, alert_threshold: f64) -> Self {
+        Self {
+            generation: stats.generation,
+            since: format_timestamp(&stats.since),
+            capacity: stats.capacity,
+            loaded: stats.loaded,
+            target_false_positive_rate: stats.target_false_positive_rate,
+            rejected: stats.rejected,
+            hits: stats.hits,
+            false_positives: stats.false_positives,
+            stale: stats.stale,
+            observed_false_positive_rate: stats.observed_false_positive_rate(),
+            suggested_capacity: stats.suggested_capacity(),
+            alert_threshold,
+            alerting: stats.exceeds(alert_threshold),
+        }
+    }
+}
+
+/// 404 自动封禁列表（`GET /admin/v1/security/bans`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct AutoBanListResponse {
+    /// 是否开启（`security.auto_ban_404_threshold` > 0）；关闭时列表中的封禁不执行
+    pub enabled: bool,
+    pub threshold: u64,
+    pub window_secs: u64,
+    pub ban_minutes: u64,
+    /// 当前未到期的封禁（本进程），按封禁时间倒序
+    pub bans: Vec<AutoBanResponse>,
+}
+
+/// 单个封禁
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct AutoBanResponse {
+    pub ip: String,
+    pub banned_at: String,
+    pub expires_at: String,
+    pub remaining_secs: u64,
+    /// 触发封禁时窗口内的 404 数
+    pub not_found_count: u64,
+}
+
+impl AutoBanResponse {
+    pub fn new(ban: &Ban, now: Instant) -> Self {
+        Self {
+            ip: ban.ip.to_string(),
+            banned_at: format_timestamp(&ban.banned_at),
+            expires_at: format_timestamp(&ban.expires_at),
+            remaining_secs: ban.remaining(now).as_secs(),
+            not_found_count: ban.not_found_count,
+        }
+    }
+}
+
+/// GeoIP 状态（`GET /admin/v1/geoip/status`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct GeoIpStatusResponse {
+    /// 当前 provider（`MaxMind` / `ExternalAPI`）
+    pub provider: String,
+    /// 外部 API 保护层状态（本进程），使用 MaxMind 时为 null
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub external_api: Option<GeoIpApiStatusResponse>,
+}
+
+/// 外部 GeoIP API 的熔断、并发与配额状态
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct GeoIpApiStatusResponse {
+    /// 熔断状态（`closed` / `open` / `half_open`）
+    pub circuit_state: String,
+    pub consecutive_failures: u32,
+    /// 熔断剩余秒数（仅 `open` 时有值）
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub open_remaining_secs: Option<u64>,
+    /// 启动以来的熔断次数
+    pub trips: u64,
+    /// 连续失败阈值（`analytics.geoip_api_failure_threshold`，0 = 不熔断）
+    pub failure_threshold: u32,
+    pub open_minutes: u64,
+    pub in_flight: usize,
+    pub max_concurrency: usize,
+    /// 当前分钟窗口已发出的请求数
+    pub rate_used: u32,
+    /// 每分钟配额（0 = 不限）
+    pub rate_per_minute: u32,
+    /// 启动以来各原因跳过的查询数（`circuit_open` / `busy` / `rate_limited`）
+    pub skipped: BTreeMap<String, u64>,
+}
+
+impl From<ApiGuardStatus> for GeoIpApiStatusResponse {
+    fn from(status: ApiGuardStatus) -> Self {
+        Self {
+            circuit_state: status.circuit.state.as_str().to_string(),
+            consecutive_failures: status.circuit.consecutive_failures,
+            open_remaining_secs: status.circuit.open_remaining.map(|d| d.as_secs()),
+            trips: status.circuit.trips,
+            failure_threshold: status.failure_threshold,
+            open_minutes: status.open_duration.as_secs() / 60,
+            in_flight: status.in_flight,
+            max_concurrency: status.max_concurrency,
+            rate_used: status.rate_used,
+            rate_per_minute: status.rate_per_minute,
+            skipped: status
+                .skipped
+                .into_iter()
+                .map(|(reason, count)| (reason.as_str().to_string(), count))
+                .collect(),
+        }
+    }
+}
+
+/// 后台任务健康状态（`GET /admin/v1/tasks/health`）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TaskHealthListResponse {
+    /// 是否所有任务都处于 `running` / `stopped`
+    pub healthy: bool,
+    pub tasks: Vec<TaskHealthResponse>,
+}
+
+/// 单个后台任务
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TaskHealthResponse {
+    pub name: String,
+    /// `running` / `stalled` / `restarting` / `dead` / `stopped`
+    pub state: String,
+    /// 启动以来因 panic 重启的次数
+    pub restarts: u32,
+    /// 任务声明的心跳间隔（秒），null = 不检查心跳
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub heartbeat_interval_secs: Option<u64>,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub last_heartbeat: Option<String>,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub heartbeat_age_secs: Option<u64>,
+    /// 最近一次 panic 信息
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub last_panic: Option<String>,
+    #[cfg_attr(all(debug_assertions, feature = "openapi"), schema(required))]
+    pub last_panic_at: Option<String>,
+}
+
+impl From<TaskHealth> for TaskHealthResponse {
+    fn from(health: TaskHealth) -> Self {
+        Self {
+            name: health.name.to_string(),
+            state: health.state.as_str().to_string(),
+            restarts: health.restarts,
+            heartbeat_interval_secs: health.heartbeat_interval.map(|d| d.as_secs()),
+            last_heartbeat: health.last_heartbeat.as_ref().map(format_timestamp),
+            heartbeat_age_secs: health.heartbeat_age.map(|d| d.as_secs()),
+            last_panic: health.last_panic,
+            last_panic_at: health.last_panic_at.as_ref().map(format_timestamp),
+        }
+    }
 }
 
 // Re-export CSV row types from shared csv_handler module
@@ -0,0 +1,107 @@
+//! 点击数徽章端点：`GET /badge/{code}.svg`
+//!
+//! 点击数从 Storage 读取：徽章需要持久化的点击数，缓存中的对象是加载时的快照，
+//! 不适合展示计数。查库前先经缓存查询链（Bloom + 负缓存）排除不存在的短码，
+//! 扫描流量不会落到数据库。响应带 `Cache-Control: public, max-age=60`，
+//! 由浏览器 / CDN / GitHub camo 吸收重复请求。
+//!
+//! - 请求徽章不计入点击统计
+//! - 短码不存在、已过期或非法时返回 200 + 灰色 "not found" 徽章，不区分原因
+//! - 查询失败时同样返回 "not found" 徽章，但不允许缓存
+
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use actix_web::{HttpResponse, web};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, trace};
+
+use crate::api::constants::BADGE_PREFIX;
+use crate::services::badge::{
+    BadgeStyle, DEFAULT_BADGE_COLOR, DEFAULT_BADGE_LABEL, NOT_FOUND_COLOR, NOT_FOUND_MESSAGE,
+    format_count, render_badge, resolve_color,
+};
+use crate::services::{LinkCache, LinkCacheLookup};
+use crate::storage::SeaOrmStorage;
+use crate::utils::is_valid_short_code;
+
+/// 徽章响应的缓存时间
+const BADGE_CACHE_CONTROL: &str = "public, max-age=60";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BadgeQuery {
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub style: Option<String>,
+}
+
+pub struct BadgeService;
+
+impl BadgeService {
+    pub async fn badge(
+        code: web::Path<String>,
+        query: web::Query<BadgeQuery>,
+        cache: web::Data<Arc<dyn LinkCache>>,
+        storage: web::Data<Arc<SeaOrmStorage>>,
+    ) -> HttpResponse {
+        let code = code.into_inner();
+        let label = query
+            .label
+            .as_deref()
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or(DEFAULT_BADGE_LABEL);
+        let style = query
+            .style
+            .as_deref()
+            .and_then(BadgeStyle::parse)
+            .unwrap_or_default();
+
+        if !is_valid_short_code(&code) {
+            trace!("Badge requested for invalid short code: {}", code);
+            return Self::not_found(label, style, BADGE_CACHE_CONTROL);
+        }
+
+        match cache.get(&code).await {
+            LinkCacheLookup::NotFound | LinkCacheLookup::Gone => {
+                trace!("Badge requested for unknown short code: {}", code);
+                return Self::not_found(label, style, BADGE_CACHE_CONTROL);
+            }
+            LinkCacheLookup::Found(_) | LinkCacheLookup::Miss => {}
+        }
+
+        match storage.get(&code).await {
+            Ok(Some(link)) if !link.is_expired() => {
+                let color = query
+                    .color
+                    .as_deref()
+                    .and_then(resolve_color)
+                    .unwrap_or_else(|| DEFAULT_BADGE_COLOR.to_string());
+                let svg = render_badge(label, &format_count(link.click as u64), &color, style);
+                Self::svg_response(svg, BADGE_CACHE_CONTROL)
+            }
+            Ok(_) => Self::not_found(label, style, BADGE_CACHE_CONTROL),
+            Err(e) => {
+                error!("Badge lookup failed for {}: {}", code, e);
+                Self::not_found(label, style, "no-cache")
+            }
+        }
+    }
+
+    fn not_found(label: &str, style: BadgeStyle, cache_control: &str) -> HttpResponse {
+        Self::svg_response(
+            render_badge(label, NOT_FOUND_MESSAGE, NOT_FOUND_COLOR, style),
+            cache_control,
+        )
+    }
+
+    fn svg_response(svg: String, cache_control: &str) -> HttpResponse {
+        HttpResponse::Ok()
+            .insert_header((CONTENT_TYPE, "image/svg+xml; charset=utf-8"))
+            .insert_header((CACHE_CONTROL, cache_control.to_string()))
+            .body(svg)
+    }
+}
+
+/// 徽章路由配置
+pub fn badge_routes() -> actix_web::Scope {
+    web::scope(BADGE_PREFIX).route("/{code:.+}.svg", web::get().to(BadgeService::badge))
+}
@@ -16,6 +16,12 @@ pub struct FrontendService;
 /// 自定义前端目录路径
 const CUSTOM_FRONTEND_DIR: &str = "./frontend-panel";
 
+/// 入口 HTML 每次都向服务端确认，升级后浏览器能立即拿到新的资源清单
+const INDEX_CACHE_CONTROL: &str = "no-cache";
+
+/// `assets/` 下为 Vite 带内容哈希的文件名，内容不变即可永久缓存
+const ASSET_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
 impl FrontendService {
     /// 尝试从自定义前端目录加载文件（异步 IO）
     async fn try_load_custom_frontend(file_path: &str) -> Option<Vec<u8>> {
@@ -84,6 +90,7 @@ impl FrontendService {
 
         Ok(HttpResponse::Ok()
             .content_type("text/html; charset=utf-8")
+            .insert_header(("Cache-Control", INDEX_CACHE_CONTROL))
             .body(processed_html))
     }
 
@@ -107,7 +114,10 @@ impl FrontendService {
         let content = Self::load_frontend_file(&asset_path).await;
 
         match content {
-            Some(data) => Ok(HttpResponse::Ok().content_type(content_type).body(data)),
+            Some(data) => Ok(HttpResponse::Ok()
+                .content_type(content_type)
+                .insert_header(("Cache-Control", ASSET_CACHE_CONTROL))
+                .body(data)),
             None => {
                 debug!("Static file not found: {}", path);
                 Ok(HttpResponse::NotFound().body("File not found"))
@@ -1,8 +1,10 @@
 pub mod admin;
+pub mod badge;
 pub mod frontend;
 pub mod health;
 pub mod redirect;
 
+pub use badge::{BadgeService, badge_routes};
 pub use frontend::{FrontendService, frontend_routes};
 pub use health::{AppStartTime, HealthService, health_routes};
 pub use redirect::{RedirectService, redirect_routes};
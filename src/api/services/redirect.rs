@@ -10,6 +10,9 @@
 //!    额外的 service 层间接调用会增加不必要的开销。
 //! 2. **缓存策略**：redirect 使用 `LinkCache` 组合 Forge primitives 的完整查询链
 //!    (Bloom → negative backend → object backend → DB)，这是 cache policy 的核心价值。
+//!    回源 DB 可经 `MissBatcher` 跨短码合并（`cache.miss_batch`）。
+//!    按 `analytics.timing_sample_rate` 采样的请求记录各阶段耗时（`RedirectTimer`）。
+//!    命中链接后、计数之前可经 `plugins.redirect_filter` WASM 插件放行 / 拒绝 / 改写目标。
 //!    LinkService 的 CRUD 操作不需要这个查询链。
 //! 3. **关注点不同**：redirect 的逻辑（缓存查询、点击计数、UTM 透传）
 //!    与 admin CRUD 操作完全不同，强行统一反而增加复杂度。
@@ -17,20 +20,53 @@
 //! ## 维护约定
 //! - 如果需要修改 redirect 的数据访问逻辑，直接在此文件修改
 //! - 不要将 redirect 的 storage 访问移到 LinkService
+//! - 日志按 [`log_sample`](crate::utils::log_sample) 中的热路径约定书写：字段语法、
+//!   每请求必经的分支不打日志、可被外部流量放大的日志采样输出
 
 use std::borrow::Cow;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
 
 use actix_web::http::StatusCode;
-use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, web};
 use std::sync::Arc;
-use tracing::{debug, error, trace};
+use tracing::{Level, debug, error, trace, warn};
 
 use crate::analytics::global::{get_click_manager, is_detailed_logging_stopped};
+use crate::analytics::privacy::{self, DntMode, TRACKING_STATUS_HEADER};
+use crate::analytics::{RedirectTimer, TimingPhase};
+use crate::api::constants::BENCH_HEADER;
 use crate::config::{get_config, get_runtime_config, keys};
 use crate::metrics::MetricsRecorder;
-use crate::services::{GeoIpProvider, LinkCache, LinkCacheLookup};
-use crate::storage::{SeaOrmStorage, ShortLink};
+use crate::services::auto_ban::{AutoBanSettings, auto_ban_settings, auto_banner};
+use crate::services::not_found_pacing::{DEFAULT_NOT_FOUND_DELAY_MS, not_found_pacer};
+use crate::services::{GeoIpProvider, LinkCache, LinkCacheLookup, MissBatcher};
+use crate::storage::{AnalyticsLevel, SeaOrmStorage, ShortLink};
+use crate::utils::ip::client_ip;
 use crate::utils::is_valid_short_code;
+use crate::utils::log_sample::LogSampler;
+use crate::utils::redirect_body::fallback_body;
+
+/// 已归档链接的提示页（`features.archived_page` 开启时返回）
+const ARCHIVED_PAGE_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>410 Gone</title></head>
+<body style="font-family: sans-serif; text-align: center; padding-top: 15vh; color: #444">
+<h1>此链接已归档</h1>
+<p>This link has been archived and is no longer available.</p>
+</body>
+</html>
+"#;
+
+/// 404 类日志的采样：扫描流量可以任意放大，每 1000 条输出一条
+static NOT_FOUND_LOG: LogSampler = LogSampler::new(1000);
+
+/// 回源失败的采样：数据库故障期间每个 miss 都会失败
+static DB_ERROR_LOG: LogSampler = LogSampler::new(100);
+
+/// 过滤插件失败的采样：插件异常时每个命中请求都会失败
+#[cfg(feature = "wasm-plugins")]
+static FILTER_ERROR_LOG: LogSampler = LogSampler::new(100);
 
 pub struct RedirectService {}
 
@@ -41,73 +77,275 @@ impl RedirectService {
         cache: web::Data<Arc<dyn LinkCache>>,
         storage: web::Data<Arc<SeaOrmStorage>>,
         geoip: Option<web::Data<Arc<GeoIpProvider>>>,
+        miss_batcher: Option<web::Data<Arc<MissBatcher>>>,
         metrics: web::Data<Arc<dyn MetricsRecorder>>,
-    ) -> impl Responder {
+    ) -> HttpResponse {
+        let started = Instant::now();
         let captured_path = path.into_inner();
 
-        if captured_path.is_empty() {
+        let response = if captured_path.is_empty() {
             let rt = get_runtime_config();
             let default_url = rt.get_or(keys::FEATURES_DEFAULT_URL, "https://esap.cc/repo");
-            HttpResponse::TemporaryRedirect()
-                .insert_header(("Location", default_url))
-                .finish()
+            Self::redirect_to(HttpResponse::TemporaryRedirect(), &default_url)
         } else if !is_valid_short_code(&captured_path) {
             // 非法短码，直接 404（不进缓存、不进 DashMap）
-            trace!("Invalid short code rejected: {}", &captured_path);
+            Self::log_not_found(&captured_path, "invalid_code");
             Self::not_found_response(&metrics)
         } else {
-            Self::process_redirect(captured_path, req, cache, storage, geoip, metrics).await
+            // 采样在请求开始时一次判定，保证样本包含完整的阶段
+            let mut timer = RedirectTimer::start();
+            let response = Self::process_redirect(
+                &captured_path,
+                &req,
+                cache,
+                storage,
+                geoip,
+                miss_batcher,
+                &metrics,
+                &mut timer,
+            )
+            .await;
+            timer.submit(&captured_path, response.status().as_u16());
+            response
+        };
+
+        if response.status() == StatusCode::NOT_FOUND {
+            let auto_ban = auto_ban_settings();
+            let constant_time =
+                get_runtime_config().get_bool_or(keys::REDIRECT_CONSTANT_TIME_404, false);
+            // 两者都关闭时不解析客户端 IP
+            if auto_ban.enabled() || constant_time {
+                let ip = client_ip(req.peer_addr(), req.headers());
+                if let Some(ip) = ip {
+                    Self::record_not_found(ip, &auto_ban, &metrics);
+                }
+                if constant_time {
+                    Self::pace_not_found(ip, started, &metrics).await;
+                }
+            }
         }
+        response
     }
 
+    /// 404 计入自动封禁；达到阈值的 IP 此后的请求由 Firewall 中间件直接 403
+    fn record_not_found(
+        ip: IpAddr,
+        settings: &AutoBanSettings,
+        metrics: &Arc<dyn MetricsRecorder>,
+    ) {
+        if let Some(ban) = auto_banner().record_not_found(ip, settings, Instant::now()) {
+            warn!(
+                "Auto-banned IP {} for {} minutes: {} 404s within {}s",
+                ip,
+                settings.ban_duration.as_secs() / 60,
+                ban.not_found_count,
+                settings.window.as_secs()
+            );
+            metrics.inc_auto_ban_event("ban");
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn process_redirect(
-        capture_path: String,
-        req: HttpRequest,
+        capture_path: &str,
+        req: &HttpRequest,
         cache: web::Data<Arc<dyn LinkCache>>,
         storage: web::Data<Arc<SeaOrmStorage>>,
         geoip: Option<web::Data<Arc<GeoIpProvider>>>,
-        metrics: web::Data<Arc<dyn MetricsRecorder>>,
+        miss_batcher: Option<web::Data<Arc<MissBatcher>>>,
+        metrics: &Arc<dyn MetricsRecorder>,
+        timer: &mut RedirectTimer,
     ) -> HttpResponse {
-        match cache.get(&capture_path).await {
+        if timer.is_sampled() {
+            // Bloom 判定在缓存查询链内部，采样请求单独探测一次
+            let mark = timer.mark();
+            cache.bloom_check(capture_path).await;
+            timer.record(TimingPhase::Bloom, mark);
+        }
+
+        let mark = timer.mark();
+        let lookup = cache.get(capture_path).await;
+        timer.record(TimingPhase::Cache, mark);
+
+        match lookup {
             LinkCacheLookup::Found(link) => {
-                Self::update_click(&capture_path, &req, geoip);
-                Self::finish_redirect(&req, link, &metrics)
+                Self::redirect_found(capture_path, req, link, geoip, metrics, timer)
             }
             LinkCacheLookup::Miss => {
-                trace!("Cache miss for path: {}", &capture_path);
-                match storage.get(&capture_path).await {
-                    Ok(Some(link)) => match link.cache_ttl(get_config().cache.default_ttl) {
-                        None => {
-                            debug!("Expired link from storage: {}", &capture_path);
-                            cache.mark_not_found(&capture_path).await;
-                            Self::not_found_response(&metrics)
-                        }
-                        Some(ttl) => {
-                            Self::update_click(&capture_path, &req, geoip);
-                            cache.insert(&capture_path, link.clone(), Some(ttl)).await;
-                            Self::finish_redirect(&req, link, &metrics)
+                trace!(code = capture_path, "cache miss");
+                // 查库前取版本：回源期间链接被更新时，旧结果不会回填进缓存
+                let token = cache.fill_token(capture_path);
+                let mark = timer.mark();
+                let loaded = match &miss_batcher {
+                    Some(batcher) => batcher.load(capture_path).await,
+                    None => storage.get(capture_path).await,
+                };
+                timer.record(TimingPhase::Db, mark);
+                match loaded {
+                    Ok(Some(link)) => {
+                        cache.record_origin_lookup(capture_path, true);
+                        match link.cache_ttl(get_config().cache.default_ttl) {
+                            None => {
+                                Self::log_not_found(capture_path, "expired");
+                                cache.fill_not_found(capture_path, token).await;
+                                Self::not_found_response(metrics)
+                            }
+                            Some(ttl) => {
+                                cache
+                                    .fill(capture_path, link.clone(), Some(ttl), token)
+                                    .await;
+                                Self::redirect_found(capture_path, req, link, geoip, metrics, timer)
+                            }
                         }
-                    },
+                    }
                     Ok(None) => {
-                        debug!("Redirect link not found in database: {}", &capture_path);
-                        // Bloom filter false positive: bloom said "maybe exists" but DB says no
-                        metrics.inc_bloom_false_positive();
-                        cache.mark_not_found(&capture_path).await;
-                        Self::not_found_response(&metrics)
+                        // 刚归档的短码仍在 Bloom 中，需与真正不存在的短码区分
+                        let mark = timer.mark();
+                        let archived = matches!(storage.is_archived(capture_path).await, Ok(true));
+                        timer.record(TimingPhase::Db, mark);
+                        if archived {
+                            debug!(code = capture_path, "link is archived");
+                            cache.mark_archived(&[capture_path.to_string()]).await;
+                            cache.record_origin_lookup(capture_path, false);
+                            return Self::gone_response(metrics);
+                        }
+                        Self::log_not_found(capture_path, "not_in_database");
+                        // Bloom 放行但库中没有：由 cache 区分已删除的短码与真正的假阳性
+                        cache.record_origin_lookup(capture_path, false);
+                        cache.fill_not_found(capture_path, token).await;
+                        Self::not_found_response(metrics)
                     }
                     Err(e) => {
-                        error!("Database error during redirect lookup: {}", e);
-                        Self::error_response(&metrics)
+                        if let Some(suppressed) = DB_ERROR_LOG.sample() {
+                            error!(
+                                code = capture_path,
+                                error = %e,
+                                suppressed,
+                                "database error during redirect lookup"
+                            );
+                        }
+                        Self::error_response(metrics)
                     }
                 }
             }
             LinkCacheLookup::NotFound => {
-                debug!("Cache not found for path: {}", &capture_path);
-                Self::not_found_response(&metrics)
+                Self::log_not_found(capture_path, "cached_not_found");
+                Self::not_found_response(metrics)
+            }
+            LinkCacheLookup::Gone => {
+                debug!(code = capture_path, "cache reports link archived");
+                Self::gone_response(metrics)
+            }
+        }
+    }
+
+    /// 命中链接后的收尾：过滤插件 → 点击计数 → 307
+    fn redirect_found(
+        code: &str,
+        req: &HttpRequest,
+        link: ShortLink,
+        geoip: Option<web::Data<Arc<GeoIpProvider>>>,
+        metrics: &Arc<dyn MetricsRecorder>,
+        timer: &mut RedirectTimer,
+    ) -> HttpResponse {
+        #[cfg(feature = "wasm-plugins")]
+        let link = match Self::apply_redirect_filter(code, req, link, metrics) {
+            Ok(link) => link,
+            Err(response) => return response,
+        };
+
+        let mark = timer.mark();
+        let privacy = Self::update_click(code, req, link.analytics_level, metrics, geoip);
+        timer.record(TimingPhase::Enqueue, mark);
+        Self::finish_redirect(req, link, privacy, metrics)
+    }
+
+    /// 调用 `plugins.redirect_filter`：放行、改写目标，或直接返回拒绝响应（不计点击）
+    #[cfg(feature = "wasm-plugins")]
+    fn apply_redirect_filter(
+        code: &str,
+        req: &HttpRequest,
+        mut link: ShortLink,
+        metrics: &Arc<dyn MetricsRecorder>,
+    ) -> Result<ShortLink, HttpResponse> {
+        use crate::services::redirect_filter::{FilterDecision, FilterInput, get_redirect_filter};
+
+        let Some(filter) = get_redirect_filter() else {
+            return Ok(link);
+        };
+        let input = FilterInput {
+            code,
+            target: &link.target,
+            headers: filter.collect_headers(req.headers()),
+            client_ip: client_ip(req.peer_addr(), req.headers()).map(|ip| ip.to_string()),
+        };
+        let decision = match filter.call(&input) {
+            Ok(decision) => {
+                metrics.inc_redirect_filter(decision.as_str());
+                decision
+            }
+            Err(e) => {
+                if let Some(suppressed) = FILTER_ERROR_LOG.sample() {
+                    warn!(
+                        code,
+                        error = %format!("{:#}", e),
+                        suppressed,
+                        "redirect filter plugin failed"
+                    );
+                }
+                metrics.inc_redirect_filter("error");
+                filter.fail_decision()
+            }
+        };
+
+        match decision {
+            FilterDecision::Allow => Ok(link),
+            FilterDecision::Override { target } => {
+                link.target = target;
+                Ok(link)
+            }
+            FilterDecision::Deny { status } => {
+                let status = StatusCode::from_u16(status).unwrap_or(StatusCode::FORBIDDEN);
+                metrics.inc_redirect(status.as_str());
+                Err(HttpResponse::build(status)
+                    .insert_header(("Content-Type", "text/html; charset=utf-8"))
+                    .body(status.canonical_reason().unwrap_or("Denied")))
             }
         }
     }
 
+    /// `redirect.constant_time_404` 开启时把 404 补齐到目标时延，并按 IP 叠加分级 tarpit
+    async fn pace_not_found(
+        ip: Option<IpAddr>,
+        started: Instant,
+        metrics: &Arc<dyn MetricsRecorder>,
+    ) {
+        let target = Duration::from_millis(get_runtime_config().get_u64_or(
+            keys::REDIRECT_NOT_FOUND_DELAY_MS,
+            DEFAULT_NOT_FOUND_DELAY_MS,
+        ));
+        let delay = not_found_pacer().delay_for(target, started.elapsed(), ip, Instant::now());
+        if delay.total().is_zero() {
+            return;
+        }
+        if delay.tarpit.is_zero() {
+            metrics.inc_redirect_delayed("constant_time");
+        } else {
+            metrics.inc_redirect_delayed("tarpit");
+        }
+        tokio::time::sleep(delay.total()).await;
+    }
+
+    /// 404 类调试日志；debug 关闭时不触碰采样计数器
+    #[inline]
+    fn log_not_found(code: &str, reason: &'static str) {
+        if tracing::enabled!(Level::DEBUG)
+            && let Some(suppressed) = NOT_FOUND_LOG.sample()
+        {
+            debug!(code, reason, suppressed, "short link not found");
+        }
+    }
+
     #[inline]
     fn not_found_response(metrics: &Arc<dyn MetricsRecorder>) -> HttpResponse {
         metrics.inc_redirect("404");
@@ -118,6 +356,21 @@ impl RedirectService {
             .body("Not Found")
     }
 
+    /// 已归档链接返回 410，按配置决定是否展示归档提示页
+    fn gone_response(metrics: &Arc<dyn MetricsRecorder>) -> HttpResponse {
+        metrics.inc_redirect("410");
+
+        let body = if get_runtime_config().get_bool_or(keys::FEATURES_ARCHIVED_PAGE, false) {
+            ARCHIVED_PAGE_HTML
+        } else {
+            "Gone"
+        };
+        HttpResponse::build(StatusCode::GONE)
+            .insert_header(("Content-Type", "text/html; charset=utf-8"))
+            .insert_header(("Cache-Control", "public, max-age=60"))
+            .body(body)
+    }
+
     #[inline]
     fn error_response(metrics: &Arc<dyn MetricsRecorder>) -> HttpResponse {
         metrics.inc_redirect("500");
@@ -128,32 +381,62 @@ impl RedirectService {
     }
 
     /// 更新点击计数（通过 channel 异步处理分析逻辑，不阻塞响应）
+    ///
+    /// 返回本次遵守的隐私模式（`DNT` / `Sec-GPC`），用于在响应中回应 `Tk: N`。
+    /// `level` 为链接的统计级别：`none` 不记录，`count_only` 只累加 click_count，
+    /// `aggregate` 不写明细。
     #[inline]
-    fn update_click(code: &str, req: &HttpRequest, _geoip: Option<web::Data<Arc<GeoIpProvider>>>) {
+    fn update_click(
+        code: &str,
+        req: &HttpRequest,
+        level: AnalyticsLevel,
+        metrics: &Arc<dyn MetricsRecorder>,
+        _geoip: Option<web::Data<Arc<GeoIpProvider>>>,
+    ) -> Option<DntMode> {
+        if Self::is_bench_request(req) {
+            return None;
+        }
+
+        let privacy = privacy::requested_mode(req.headers());
+        if level == AnalyticsLevel::None {
+            return privacy;
+        }
+
         let Some(manager) = get_click_manager() else {
-            return;
+            return privacy;
         };
 
+        privacy::record_click(privacy);
+        if let Some(mode) = privacy {
+            metrics.inc_clicks_privacy_opt_out(mode.as_str());
+            // details 模式只计入聚合点击数，不接触 IP/UA/Referer；strict 模式完全不统计
+            if mode == DntMode::Details {
+                manager.record_count(code, level);
+            }
+            return privacy;
+        }
+
         let rt = get_runtime_config();
         let enable_detailed_logging =
             rt.get_bool_or(keys::ANALYTICS_ENABLE_DETAILED_LOGGING, false);
 
         // 检查是否应该停止详细日志（因行数限制）
         if !enable_detailed_logging
+            || !level.allows_details()
             || !manager.is_detailed_logging_enabled()
             || is_detailed_logging_stopped()
         {
-            // 快速路径：只增加 click_count
-            manager.increment(code);
-            return;
+            // 快速路径：只增加 click_count（及按级别的小时汇总）
+            manager.record_count(code, level);
+            return None;
         }
 
         // 采样率检查（在热路径做，避免不必要的字符串 clone）
         let sample_rate = rt.get_f64_or(keys::ANALYTICS_SAMPLE_RATE, 1.0);
         if sample_rate < 1.0 && rand::random::<f64>() >= sample_rate {
             // 不采样，只增加 click_count
-            manager.increment(code);
-            return;
+            manager.record_count(code, level);
+            return None;
         }
 
         // 提取原始数据并发送到 channel（配置读取移到消费者端）
@@ -170,47 +453,25 @@ impl RedirectService {
                 .get("user-agent")
                 .and_then(|h| h.to_str().ok())
                 .map(String::from),
-            ip: {
-                let trusted =
-                    get_runtime_config().get_json_or(keys::API_TRUSTED_PROXIES, Vec::new());
-                #[cfg(unix)]
-                let trusted = if get_config().server.unix_socket.is_some() {
-                    trusted
-                        .into_iter()
-                        .chain(["127.0.0.0/8".to_string(), "::1/128".to_string()])
-                        .collect::<Vec<_>>()
-                } else {
-                    trusted
-                };
-
-                let peer = req.peer_addr().map(|address| address.ip());
-                #[cfg(unix)]
-                let peer = peer.or_else(|| {
-                    get_config()
-                        .server
-                        .unix_socket
-                        .as_ref()
-                        .map(|_| std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
-                });
-
-                peer.map(|peer| {
-                    aster_forge_actix_middleware::client_ip::real_ip_from_headers(
-                        req.headers(),
-                        peer,
-                        &trusted,
-                    )
-                    .to_string()
-                })
-            },
+            ip: client_ip(req.peer_addr(), req.headers()).map(|ip| ip.to_string()),
+            analytics_level: level,
         };
 
         // send_raw_event 内部会调用 increment
         manager.send_raw_event(event);
+        None
+    }
+
+    /// 压测流量不计入点击统计（需显式开启 `server.allow_bench_header`）
+    #[inline]
+    fn is_bench_request(req: &HttpRequest) -> bool {
+        get_config().server.allow_bench_header && req.headers().contains_key(BENCH_HEADER)
     }
 
     fn finish_redirect(
         req: &HttpRequest,
         link: ShortLink,
+        privacy: Option<DntMode>,
         metrics: &Arc<dyn MetricsRecorder>,
     ) -> HttpResponse {
         metrics.inc_redirect("307");
@@ -218,9 +479,24 @@ impl RedirectService {
         // 构建目标 URL，可能需要透传 UTM 参数
         let target_url = Self::build_target_url(req, &link.target);
 
-        HttpResponse::build(StatusCode::TEMPORARY_REDIRECT)
-            .insert_header(("Location", target_url.as_ref()))
-            .finish()
+        let mut response = HttpResponse::build(StatusCode::TEMPORARY_REDIRECT);
+        if privacy.is_some() {
+            response.insert_header(TRACKING_STATUS_HEADER);
+        }
+        Self::redirect_to(response, &target_url)
+    }
+
+    /// 写入 `Location`；开启 `redirect.include_fallback_body` 时附带 meta-refresh 降级 body，
+    /// 供吞掉 `Location` 头的代理后面的浏览器手动跳转
+    fn redirect_to(mut response: HttpResponseBuilder, location: &str) -> HttpResponse {
+        response.insert_header(("Location", location));
+        if get_runtime_config().get_bool_or(keys::REDIRECT_INCLUDE_FALLBACK_BODY, true) {
+            response
+                .content_type("text/html; charset=utf-8")
+                .body(fallback_body(location))
+        } else {
+            response.finish()
+        }
     }
 
     /// 构建目标 URL，根据配置决定是否透传 UTM 参数
@@ -0,0 +1,154 @@
+//! Admin API 版本与管理面板兼容矩阵
+//!
+//! 面板构建时写入自身版本（`package.json` 的 `version`），启动时调用
+//! `GET /admin/meta/version` 比对，并在每个请求上携带 `X-Panel-Version` 头；
+//! 不兼容的请求由 `PanelVersionGuard` 返回 409（`PanelVersionIncompatible`）。
+//!
+//! 发版时：
+//! - Admin API 有破坏性变更 → 递增 [`ADMIN_API_VERSION`]
+//! - 新的 minor 版本 → 在 [`COMPATIBILITY`] 末尾追加一行
+//!
+//! 本模块的测试会校验当前 crate 版本在矩阵中、内嵌面板版本满足要求，漏改即失败。
+
+/// 当前 Admin API 版本（破坏性变更时递增）
+pub const ADMIN_API_VERSION: u32 = 1;
+
+/// 兼容矩阵中的一行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compatibility {
+    /// 后端版本（`major.minor`）
+    pub server: &'static str,
+    /// 该版本提供的 Admin API 版本
+    pub api_version: u32,
+    /// 最低兼容的面板版本
+    pub min_panel_version: &'static str,
+}
+
+/// 兼容矩阵（按后端版本升序）
+pub const COMPATIBILITY: &[Compatibility] = &[Compatibility {
+    server: "0.6",
+    api_version: 1,
+    min_panel_version: "0.6.0",
+}];
+
+/// 当前后端版本对应的矩阵行（未登记时取最后一行）
+pub fn current() -> &'static Compatibility {
+    let server = major_minor(env!("CARGO_PKG_VERSION"));
+    COMPATIBILITY
+        .iter()
+        .find(|entry| server.is_some() && major_minor(entry.server) == server)
+        .unwrap_or_else(|| COMPATIBILITY.last().expect("compatibility matrix is empty"))
+}
+
+/// 面板版本是否与当前后端兼容
+///
+/// 要求不低于最低兼容版本，且 `major.minor` 不高于后端（更新的面板可能调用
+/// 本后端尚不存在的接口）。无法解析的版本号视为不兼容。
+pub fn is_panel_compatible(panel_version: &str) -> bool {
+    let (Some(panel), Some(min), Some(server)) = (
+        parse_version(panel_version),
+        parse_version(current().min_panel_version),
+        major_minor(env!("CARGO_PKG_VERSION")),
+    ) else {
+        return false;
+    };
+    panel >= min && (panel.0, panel.1) <= server
+}
+
+/// 解析 `major.minor[.patch]`，忽略 `-pre` / `+build` 后缀
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?;
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+fn major_minor(version: &str) -> Option<(u64, u64)> {
+    parse_version(version).map(|(major, minor, _)| (major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("0.6.0"), Some((0, 6, 0)));
+        assert_eq!(parse_version("v1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("0.7.0-beta.1"), Some((0, 7, 0)));
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_current_server_version_is_in_matrix() {
+        let (major, minor) = major_minor(env!("CARGO_PKG_VERSION")).unwrap();
+        let server = format!("{}.{}", major, minor);
+        assert!(
+            COMPATIBILITY.iter().any(|entry| entry.server == server),
+            "Cargo version {} is missing from COMPATIBILITY; add a row for {}",
+            env!("CARGO_PKG_VERSION"),
+            server
+        );
+        assert_eq!(
+            current().api_version,
+            ADMIN_API_VERSION,
+            "ADMIN_API_VERSION does not match the matrix row for {}",
+            server
+        );
+    }
+
+    #[test]
+    fn test_matrix_is_well_formed() {
+        for pair in COMPATIBILITY.windows(2) {
+            assert!(parse_version(pair[0].server) < parse_version(pair[1].server));
+            assert!(pair[0].api_version <= pair[1].api_version);
+            assert!(
+                parse_version(pair[0].min_panel_version)
+                    <= parse_version(pair[1].min_panel_version)
+            );
+        }
+        for entry in COMPATIBILITY {
+            assert!(parse_version(entry.server).is_some());
+            assert!(parse_version(entry.min_panel_version).is_some());
+        }
+    }
+
+    #[test]
+    fn test_embedded_panel_version_is_compatible() {
+        let package: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/admin-panel/package.json"
+        )))
+        .unwrap();
+        let panel_version = package["version"].as_str().unwrap();
+        assert!(
+            is_panel_compatible(panel_version),
+            "admin-panel/package.json version {} is not compatible with server {} (min {})",
+            panel_version,
+            env!("CARGO_PKG_VERSION"),
+            current().min_panel_version
+        );
+    }
+
+    #[test]
+    fn test_is_panel_compatible_bounds() {
+        let min = current().min_panel_version;
+        assert!(is_panel_compatible(min));
+        assert!(!is_panel_compatible("0.0.1"));
+        assert!(!is_panel_compatible("999.0.0"));
+        assert!(!is_panel_compatible("garbage"));
+    }
+}
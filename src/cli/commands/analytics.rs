@@ -0,0 +1,274 @@
+//! 点击统计 CLI 命令（导出 / 订正 / 重算周月汇总）
+//!
+//! 直连数据库，不经过 IPC：导出量可能远大于 IPC 单次响应，订正需要在单个事务中
+//! 改写多张表，且 server 未运行时同样可用。
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use colored::Colorize;
+
+use crate::analytics::amend::{AmendRequest, BeforeAfter, ClickAmender};
+use crate::analytics::export::{
+    CountsEncoding, ExportFormat, ExportOptions, ExportTable, export_analytics,
+};
+use crate::analytics::{PeriodGranularity, RollupManager, WeekStart};
+use crate::cli::CliError;
+use crate::cli::commands::object_storage;
+use crate::services::AnalyticsService;
+use crate::storage::SeaOrmStorage;
+use crate::utils::s3;
+
+/// `analytics export` 参数
+pub struct AnalyticsExportArgs {
+    pub table: ExportTable,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub format: ExportFormat,
+    pub counts: CountsEncoding,
+    pub output: Option<String>,
+}
+
+/// 运行 `analytics export`
+pub async fn run_analytics_export(
+    storage: &SeaOrmStorage,
+    args: AnalyticsExportArgs,
+) -> Result<(), CliError> {
+    let (start, end) = parse_range(args.from.as_deref(), args.to.as_deref())?;
+
+    let options = ExportOptions {
+        table: args.table,
+        start,
+        end,
+        format: args.format,
+        counts: args.counts,
+    };
+
+    let mut output = args.output.clone();
+    let summary = match &args.output {
+        Some(path) if s3::is_s3_path(path) => {
+            let settings = object_storage::load_settings(None).await?;
+            let location = object_storage::resolve_location(path, &settings, || {
+                default_export_file_name(&options)
+            })?;
+            let content_type = match options.format {
+                ExportFormat::Csv => "text/csv",
+                ExportFormat::Parquet => "application/vnd.apache.parquet",
+            };
+            let (writer, upload) = s3::start_upload(&location, &settings, content_type)
+                .map_err(object_storage::s3_error)?;
+            let written = export_analytics(storage, &options, writer).await;
+            let (summary, report) = object_storage::complete_upload(upload, written).await?;
+            output = Some(report.location.to_string());
+            summary
+        }
+        Some(path) => {
+            let file = File::create(path).map_err(|e| {
+                CliError::CommandError(format!("Failed to create '{}': {}", path, e))
+            })?;
+            export_analytics(storage, &options, BufWriter::new(file)).await?
+        }
+        None if args.format == ExportFormat::Parquet => {
+            return Err(CliError::CommandError(
+                "Parquet output requires --output".to_string(),
+            ));
+        }
+        None => export_analytics(storage, &options, std::io::stdout()).await?,
+    };
+
+    // 写 stdout 时摘要走 stderr，避免混入 CSV
+    let message = format!(
+        "{} Exported {} rows from {} ({} - {})",
+        "✓".green().bold(),
+        summary.rows,
+        options.table.table_name(),
+        start.format("%Y-%m-%d %H:%M:%S"),
+        end.format("%Y-%m-%d %H:%M:%S"),
+    );
+    match &output {
+        Some(path) => println!("{} to {}", message, path.cyan()),
+        None => eprintln!("{}", message),
+    }
+    Ok(())
+}
+
+/// 输出到对象存储目录时的文件名，如 `click_logs_20261016_120000.parquet`
+fn default_export_file_name(options: &ExportOptions) -> String {
+    let extension = match options.format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Parquet => "parquet",
+    };
+    format!(
+        "{}_{}.{}",
+        options.table.table_name(),
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        extension
+    )
+}
+
+/// 解析 `--from` / `--to`；仅给出日期的 `--to` 包含当天
+fn parse_range(
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>), CliError> {
+    let (start, mut end) = AnalyticsService::parse_date_range_strict(from, to)?;
+    if let Some(to) = to
+        && NaiveDate::parse_from_str(to, "%Y-%m-%d").is_ok()
+    {
+        end += Duration::days(1) - Duration::microseconds(1);
+    }
+    Ok((start, end))
+}
+
+/// `analytics amend` 参数
+pub struct AnalyticsAmendArgs {
+    pub code: String,
+    pub from: String,
+    pub to: String,
+    pub remove_ip_cidrs: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// 运行 `analytics amend`
+pub async fn run_analytics_amend(
+    storage: Arc<SeaOrmStorage>,
+    args: AnalyticsAmendArgs,
+) -> Result<(), CliError> {
+    let (start, end) = parse_range(Some(&args.from), Some(&args.to))?;
+    let request = AmendRequest {
+        code: args.code,
+        start,
+        end,
+        ip_cidrs: args.remove_ip_cidrs,
+        operator: std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok(),
+    };
+
+    let report = ClickAmender::new(storage)
+        .run(&request, args.dry_run)
+        .await
+        .map_err(|e| CliError::CommandError(format!("Analytics amend failed: {}", e)))?;
+
+    let title = if report.dry_run {
+        "Dry run (nothing written, values after are estimates)".yellow()
+    } else {
+        "Amended".green()
+    };
+    println!(
+        "{} {} ({} - {}, {})",
+        title.bold(),
+        request.code.cyan(),
+        start.format("%Y-%m-%d %H:%M:%S"),
+        end.format("%Y-%m-%d %H:%M:%S"),
+        request.ip_cidrs.join(", ")
+    );
+    println!(
+        "  Matching click logs: {} across {} hour(s)",
+        report.removed_rows, report.affected_hours
+    );
+    print_before_after("Link total clicks", report.link_clicks);
+    print_before_after("Click logs in range", report.detail_rows);
+    print_before_after("Hourly rollup clicks", report.hourly_clicks);
+    print_before_after("Daily rollup clicks", report.daily_clicks);
+    if !report.rebuilt_days.is_empty() {
+        println!(
+            "  Daily rollups rebuilt from hourly data: {}",
+            format_days(&report.rebuilt_days)
+        );
+    }
+    if !report.adjusted_days.is_empty() {
+        println!(
+            "  Daily rollups adjusted in place (hourly data expired): {}",
+            format_days(&report.adjusted_days)
+        );
+    }
+    if !report.failed_days.is_empty() {
+        return Err(CliError::CommandError(format!(
+            "Click logs were removed, but rebuilding rollups failed for {}. \
+            Rerun the same command to finish.",
+            format_days(&report.failed_days)
+        )));
+    }
+    Ok(())
+}
+
+/// `analytics rebuild-rollups` 参数
+pub struct AnalyticsRebuildArgs {
+    pub granularity: PeriodGranularity,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// 运行 `analytics rebuild-rollups`
+pub async fn run_analytics_rebuild(
+    storage: Arc<SeaOrmStorage>,
+    args: AnalyticsRebuildArgs,
+) -> Result<(), CliError> {
+    let from = args.from.as_deref().map(parse_day).transpose()?;
+    let to = match args.to.as_deref() {
+        Some(to) => parse_day(to)?,
+        None => Utc::now().date_naive(),
+    };
+    if from.is_some_and(|from| from > to) {
+        return Err(CliError::CommandError(
+            "--from must not be later than --to".to_string(),
+        ));
+    }
+
+    let week_start = WeekStart::current();
+    let report = RollupManager::new(storage)
+        .rebuild_periods(args.granularity, from, to, week_start)
+        .await
+        .map_err(|e| CliError::CommandError(format!("Rollup rebuild failed: {}", e)))?;
+
+    match (report.periods.first(), report.periods.last()) {
+        (Some(first), Some(last)) => println!(
+            "{} Rebuilt {} {} rollup(s) ({} - {}): {} rows written, {} old rows removed",
+            "✓".green().bold(),
+            report.periods.len(),
+            args.granularity.as_str(),
+            first,
+            last,
+            report.rows,
+            report.deleted
+        ),
+        _ => println!("No {} rollups to rebuild", args.granularity.as_str()),
+    }
+    if args.granularity == PeriodGranularity::Week {
+        println!("  Weeks start on {}", week_start.as_str().cyan());
+    }
+    if let Some(skipped) = report.skipped_from {
+        println!(
+            "  {} Periods from {} were skipped: their daily rollups have expired \
+            (analytics.daily_retention_days)",
+            "!".yellow().bold(),
+            skipped
+        );
+    }
+    Ok(())
+}
+
+fn parse_day(value: &str) -> Result<NaiveDate, CliError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+        CliError::CommandError(format!("Invalid date '{}', expected YYYY-MM-DD", value))
+    })
+}
+
+fn print_before_after(label: &str, value: BeforeAfter) {
+    println!(
+        "  {:<22} {} -> {}",
+        format!("{}:", label),
+        value.before,
+        value.after
+    );
+}
+
+fn format_days(days: &[NaiveDate]) -> String {
+    days.iter()
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,435 @@
+//! Bench command - 内置 redirect 压测
+//!
+//! 从本地数据库采样真实短码（zipf 或均匀分布），按比例混入不存在的短码，
+//! 对运行中的服务发起并发 GET 请求，输出 QPS、延迟分位与状态码分布。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use futures_util::StreamExt;
+use serde::Serialize;
+
+use crate::api::constants::BENCH_HEADER;
+use crate::cli::{BenchDistribution, CliError};
+use crate::metrics::NoopMetrics;
+use crate::storage::{LinkFilter, StorageFactory};
+use crate::utils::generate_random_code;
+use crate::utils::sampling::ReservoirSampler;
+
+/// 采样时最多使用的真实短码数量：按游标分批扫描并蓄水池抽样，内存只保留这么多短码
+const MAX_SAMPLED_CODES: usize = 100_000;
+
+/// 采样扫描的每批行数
+const SAMPLE_SCAN_BATCH_SIZE: u64 = 1000;
+
+/// 单个请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// bench 命令参数
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub url: String,
+    pub duration: Duration,
+    pub concurrency: usize,
+    pub distribution: BenchDistribution,
+    pub hit_ratio: f64,
+    pub no_analytics_impact: bool,
+    pub json: bool,
+}
+
+/// 压测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub url: String,
+    pub distribution: String,
+    pub concurrency: usize,
+    pub sampled_codes: usize,
+    pub hit_ratio: f64,
+    pub duration_secs: f64,
+    pub total_requests: u64,
+    pub errors: u64,
+    pub qps: f64,
+    pub latency_ms: LatencySummary,
+    /// 状态码 -> 次数（请求失败记为 "error"）
+    pub status_codes: BTreeMap<String, u64>,
+}
+
+/// 延迟分位（毫秒）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencySummary {
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// 按分布抽取短码
+pub struct CodeSampler {
+    codes: Vec<String>,
+    /// zipf 累积权重（已归一化），uniform 时为空
+    cumulative: Vec<f64>,
+    hit_ratio: f64,
+}
+
+impl CodeSampler {
+    pub fn new(codes: Vec<String>, distribution: BenchDistribution, hit_ratio: f64) -> Self {
+        let cumulative = match distribution {
+            BenchDistribution::Uniform => Vec::new(),
+            BenchDistribution::Zipf => {
+                // s = 1.0 的经典 zipf：第 k 名权重为 1/k
+                let mut acc = 0.0;
+                let mut weights: Vec<f64> = (1..=codes.len())
+                    .map(|rank| {
+                        acc += 1.0 / rank as f64;
+                        acc
+                    })
+                    .collect();
+                if acc > 0.0 {
+                    for w in &mut weights {
+                        *w /= acc;
+                    }
+                }
+                weights
+            }
+        };
+
+        Self {
+            codes,
+            cumulative,
+            hit_ratio: hit_ratio.clamp(0.0, 1.0),
+        }
+    }
+
+    /// 抽取一个短码；返回 `(code, is_hit)`
+    pub fn next_code(&self) -> (String, bool) {
+        if self.codes.is_empty() || rand::random::<f64>() >= self.hit_ratio {
+            return (format!("bench-miss-{}", generate_random_code(12)), false);
+        }
+
+        let index = if self.cumulative.is_empty() {
+            rand::random_range(0..self.codes.len())
+        } else {
+            let r = rand::random::<f64>();
+            self.cumulative
+                .partition_point(|&w| w < r)
+                .min(self.codes.len() - 1)
+        };
+
+        (self.codes[index].clone(), true)
+    }
+}
+
+/// 解析压测时长：`30s`、`5m`、`1h` 或纯秒数
+pub fn parse_bench_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (num, unit) = input.split_at(split);
+    let num: u64 = num
+        .parse()
+        .map_err(|_| format!("Invalid duration: '{}'", input))?;
+
+    let secs = match unit {
+        "" | "s" => num,
+        "m" => num.saturating_mul(60),
+        "h" => num.saturating_mul(3600),
+        _ => return Err(format!("Unsupported duration unit in '{}'", input)),
+    };
+
+    if secs == 0 {
+        return Err("Duration cannot be zero".to_string());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// 计算已排序样本的分位数
+fn percentile(sorted: &[u64], pct: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((pct / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+/// 由微秒样本生成延迟摘要
+fn summarize_latencies(mut samples_us: Vec<u64>) -> LatencySummary {
+    if samples_us.is_empty() {
+        return LatencySummary::default();
+    }
+    samples_us.sort_unstable();
+    let to_ms = |us: u64| us as f64 / 1000.0;
+    let sum: u128 = samples_us.iter().map(|&v| v as u128).sum();
+
+    LatencySummary {
+        mean: sum as f64 / samples_us.len() as f64 / 1000.0,
+        p50: to_ms(percentile(&samples_us, 50.0)),
+        p90: to_ms(percentile(&samples_us, 90.0)),
+        p99: to_ms(percentile(&samples_us, 99.0)),
+        max: to_ms(*samples_us.last().unwrap_or(&0)),
+    }
+}
+
+/// 单个 worker 的统计
+#[derive(Default)]
+struct WorkerStats {
+    latencies_us: Vec<u64>,
+    status_codes: BTreeMap<String, u64>,
+    errors: u64,
+}
+
+async fn run_worker(
+    client: reqwest::Client,
+    base_url: Arc<String>,
+    sampler: Arc<CodeSampler>,
+    deadline: Instant,
+    no_analytics_impact: bool,
+) -> WorkerStats {
+    let mut stats = WorkerStats::default();
+
+    while Instant::now() < deadline {
+        let (code, _) = sampler.next_code();
+        let mut request = client.get(format!("{}/{}", base_url, code));
+        if no_analytics_impact {
+            request = request.header(BENCH_HEADER, "1");
+        }
+
+        let started = Instant::now();
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status().as_u16().to_string();
+                // 读完 body 让连接可以复用
+                let _ = response.bytes().await;
+                stats
+                    .latencies_us
+                    .push(started.elapsed().as_micros() as u64);
+                *stats.status_codes.entry(status).or_default() += 1;
+            }
+            Err(_) => {
+                stats.errors += 1;
+                *stats.status_codes.entry("error".to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    stats
+}
+
+/// 从本地数据库加载待压测的真实短码
+async fn load_sample_codes() -> Result<Vec<String>, CliError> {
+    let storage = StorageFactory::create(NoopMetrics::arc())
+        .await
+        .map_err(|e| CliError::StorageError(e.to_string()))?;
+    let mut sampler = ReservoirSampler::new(MAX_SAMPLED_CODES, rand::random());
+    let mut stream =
+        storage.stream_all_filtered_cursor(LinkFilter::default(), SAMPLE_SCAN_BATCH_SIZE);
+    while let Some(batch) = stream.next().await {
+        let batch = batch.map_err(|e| CliError::StorageError(e.to_string()))?;
+        for link in batch {
+            sampler.offer(link.code);
+        }
+    }
+
+    // 蓄水池未满时保持扫描顺序，打乱后再决定 zipf 热点
+    let mut codes = sampler.into_sample();
+    for i in (1..codes.len()).rev() {
+        let j = rand::random_range(0..=i);
+        codes.swap(i, j);
+    }
+    Ok(codes)
+}
+
+/// 执行 bench 命令
+pub async fn run_bench(options: BenchOptions) -> Result<(), CliError> {
+    if !(0.0..=1.0).contains(&options.hit_ratio) {
+        return Err(CliError::ParseError(
+            "--hit-ratio must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+    if options.concurrency == 0 {
+        return Err(CliError::ParseError(
+            "--concurrency must be greater than 0".to_string(),
+        ));
+    }
+
+    let codes = load_sample_codes().await?;
+    if codes.is_empty() && options.hit_ratio > 0.0 && !options.json {
+        println!(
+            "{} No links in database, all requests will target missing codes",
+            "ℹ".bold().blue()
+        );
+    }
+    let sampled_codes = codes.len();
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .pool_max_idle_per_host(options.concurrency)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| CliError::CommandError(format!("Failed to build HTTP client: {}", e)))?;
+
+    let base_url = Arc::new(options.url.trim_end_matches('/').to_string());
+    let sampler = Arc::new(CodeSampler::new(
+        codes,
+        options.distribution,
+        options.hit_ratio,
+    ));
+
+    if !options.json {
+        println!(
+            "{} Benchmarking {} for {}s with {} workers ({} codes sampled)",
+            "ℹ".bold().blue(),
+            base_url.cyan(),
+            options.duration.as_secs(),
+            options.concurrency,
+            sampled_codes
+        );
+    }
+
+    let started = Instant::now();
+    let deadline = started + options.duration;
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..options.concurrency {
+        workers.spawn(run_worker(
+            client.clone(),
+            base_url.clone(),
+            sampler.clone(),
+            deadline,
+            options.no_analytics_impact,
+        ));
+    }
+
+    let mut latencies = Vec::new();
+    let mut status_codes: BTreeMap<String, u64> = BTreeMap::new();
+    let mut errors = 0u64;
+    while let Some(result) = workers.join_next().await {
+        let stats =
+            result.map_err(|e| CliError::CommandError(format!("Bench worker failed: {}", e)))?;
+        latencies.extend(stats.latencies_us);
+        errors += stats.errors;
+        for (status, count) in stats.status_codes {
+            *status_codes.entry(status).or_default() += count;
+        }
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let total_requests = status_codes.values().sum::<u64>();
+    let report = BenchReport {
+        url: base_url.to_string(),
+        distribution: format!("{:?}", options.distribution).to_lowercase(),
+        concurrency: options.concurrency,
+        sampled_codes,
+        hit_ratio: options.hit_ratio,
+        duration_secs: elapsed,
+        total_requests,
+        errors,
+        qps: if elapsed > 0.0 {
+            total_requests as f64 / elapsed
+        } else {
+            0.0
+        },
+        latency_ms: summarize_latencies(latencies),
+        status_codes,
+    };
+
+    if options.json {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| CliError::CommandError(format!("Failed to serialize report: {}", e)))?;
+        println!("{}", json);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &BenchReport) {
+    println!();
+    println!("{}", "Bench Result".bold().green());
+    println!("  {}:   {}", "Requests".cyan(), report.total_requests);
+    println!("  {}:     {}", "Errors".cyan(), report.errors);
+    println!("  {}:   {:.2}s", "Duration".cyan(), report.duration_secs);
+    println!("  {}:        {:.1}", "QPS".cyan(), report.qps);
+    println!(
+        "  {}:    mean {:.2}ms  p50 {:.2}ms  p90 {:.2}ms  p99 {:.2}ms  max {:.2}ms",
+        "Latency".cyan(),
+        report.latency_ms.mean,
+        report.latency_ms.p50,
+        report.latency_ms.p90,
+        report.latency_ms.p99,
+        report.latency_ms.max
+    );
+    println!("  {}:", "Status codes".cyan());
+    for (status, count) in &report.status_codes {
+        println!("    {} {}", status.yellow(), count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bench_duration() {
+        assert_eq!(
+            parse_bench_duration("30s").unwrap(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(parse_bench_duration("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(
+            parse_bench_duration("2m").unwrap(),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            parse_bench_duration("1h").unwrap(),
+            Duration::from_secs(3600)
+        );
+        assert!(parse_bench_duration("0s").is_err());
+        assert!(parse_bench_duration("10x").is_err());
+        assert!(parse_bench_duration("abc").is_err());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&samples, 50.0), 51);
+        assert_eq!(percentile(&samples, 99.0), 99);
+        assert_eq!(percentile(&samples, 100.0), 100);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn test_zipf_sampler_prefers_head() {
+        let codes: Vec<String> = (0..100).map(|i| format!("c{}", i)).collect();
+        let sampler = CodeSampler::new(codes, BenchDistribution::Zipf, 1.0);
+
+        let mut head = 0;
+        for _ in 0..10_000 {
+            let (code, hit) = sampler.next_code();
+            assert!(hit);
+            if code == "c0" {
+                head += 1;
+            }
+        }
+        // 1/H(100) ≈ 19%，均匀分布下只有 1%
+        assert!(head > 1_000, "zipf head too cold: {}", head);
+    }
+
+    #[test]
+    fn test_sampler_hit_ratio() {
+        let codes: Vec<String> = (0..10).map(|i| format!("c{}", i)).collect();
+        let sampler = CodeSampler::new(codes, BenchDistribution::Uniform, 0.0);
+        for _ in 0..100 {
+            let (code, hit) = sampler.next_code();
+            assert!(!hit);
+            assert!(code.starts_with("bench-miss-"));
+            assert!(crate::utils::is_valid_short_code(&code));
+        }
+
+        let empty = CodeSampler::new(Vec::new(), BenchDistribution::Zipf, 1.0);
+        assert!(!empty.next_code().1);
+    }
+}
@@ -17,6 +17,7 @@ use crate::client::ConfigClient;
 
 pub use config_gen::config_generate;
 pub use get::config_get;
+pub use helpers::notify_config_change;
 pub use import_export::{config_export, config_import};
 pub use list::config_list;
 pub use reset::config_reset;
@@ -0,0 +1,127 @@
+//! 数据库维护 CLI 命令（`db maintain`）
+//!
+//! 直连数据库执行。完整模式会重写表：SQLite 的 VACUUM 需要独占访问，服务运行时拒绝执行；
+//! PostgreSQL / MySQL 会长时间锁表或重建表，执行前需要确认。
+
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use colored::Colorize;
+use sea_orm::{DatabaseBackend, DatabaseConnection};
+
+use crate::cli::CliError;
+use crate::storage::backend::maintenance::{MaintenanceReport, maintain};
+use crate::system::ipc;
+
+/// `db maintain` 参数
+pub struct DbMaintainArgs {
+    pub full: bool,
+    pub yes: bool,
+}
+
+/// 运行 `db maintain [--full]`
+pub async fn run_db_maintain(
+    db: &DatabaseConnection,
+    args: DbMaintainArgs,
+) -> Result<(), CliError> {
+    let backend = db.get_database_backend();
+    if args.full {
+        if backend == DatabaseBackend::Sqlite && ipc::is_server_running() {
+            return Err(CliError::CommandError(
+                "VACUUM needs exclusive access to the SQLite database. Stop the server first, or run `db maintain` without --full while it is online"
+                    .to_string(),
+            ));
+        }
+
+        println!("{} {}", "⚠".bold().yellow(), full_mode_warning(backend));
+        if !args.yes && !confirm("Run full maintenance?")? {
+            println!("{} Maintenance cancelled.", "✗".bold().red());
+            return Ok(());
+        }
+    }
+
+    println!(
+        "{} Running {} maintenance on {}...",
+        "ℹ".bold().blue(),
+        if args.full { "full" } else { "routine" },
+        backend_name(backend)
+    );
+    let report = maintain(db, args.full).await?;
+    print_report(&report);
+    Ok(())
+}
+
+fn full_mode_warning(backend: DatabaseBackend) -> &'static str {
+    match backend {
+        DatabaseBackend::Sqlite => {
+            "VACUUM rewrites the whole database file and needs free disk space about the size of the database."
+        }
+        DatabaseBackend::Postgres => {
+            "VACUUM FULL rewrites every table under an ACCESS EXCLUSIVE lock: redirects and writes block until it finishes, and each table needs free disk space for a full copy."
+        }
+        _ => {
+            "OPTIMIZE TABLE rebuilds every table; large tables take a while and need free disk space for a full copy."
+        }
+    }
+}
+
+fn confirm(question: &str) -> Result<bool, CliError> {
+    print!("{} [y/N] ", question);
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| CliError::CommandError(format!("Failed to read input: {}", e)))?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn print_report(report: &MaintenanceReport) {
+    for statement in &report.statements {
+        println!("  {}", statement.dimmed());
+    }
+
+    println!(
+        "{} Maintenance finished in {}ms",
+        "✓".green().bold(),
+        report.elapsed.as_millis()
+    );
+    match (report.size_before, report.size_after) {
+        (Some(before), Some(after)) => {
+            let change = match after.cmp(&before) {
+                Ordering::Equal => "unchanged".to_string(),
+                Ordering::Less => format!("{} reclaimed", format_size(before - after)),
+                Ordering::Greater => format!("+{}", format_size(after - before)),
+            };
+            println!(
+                "  Size: {} -> {} ({})",
+                format_size(before),
+                format_size(after).cyan(),
+                change
+            );
+        }
+        _ => println!("  Size: {}", "unavailable".dimmed()),
+    }
+}
+
+fn backend_name(backend: DatabaseBackend) -> &'static str {
+    match backend {
+        DatabaseBackend::Sqlite => "SQLite",
+        DatabaseBackend::Postgres => "PostgreSQL",
+        _ => "MySQL",
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -34,10 +34,38 @@ pub fn show_help() {
         "  {} remove <code>              # remove short link",
         program_name.cyan()
     );
+    println!(
+        "  {} archive <code...> | --search <kw> # archive links (410 Gone)",
+        program_name.cyan()
+    );
+    println!(
+        "  {} unarchive <code>           # restore archived link",
+        program_name.cyan()
+    );
+    println!(
+        "  {} sample -n <N> [--output csv]  # random sample for spot checks",
+        program_name.cyan()
+    );
+    println!(
+        "  {} generate --template <url> --var name=a,b [--code-template <tpl>] # one link per combination",
+        program_name.cyan()
+    );
     println!(
         "  {} list                      # list all short links",
         program_name.cyan()
     );
+    println!(
+        "  {} resolve <code>... [--stdin] [--json]  # print target URLs (exit 3 not found, 6 expired)",
+        program_name.cyan()
+    );
+    println!(
+        "  {} audit-expiry --suspect-midnight-utc  # list links expiring exactly at 00:00 UTC",
+        program_name.cyan()
+    );
+    println!(
+        "  {} audit-targets --credentials  # list links whose target URL carries credentials",
+        program_name.cyan()
+    );
     println!(
         "  {} export [file path]           # export links as CSV",
         program_name.cyan()
@@ -50,15 +78,39 @@ pub fn show_help() {
         "  {} config generate [output path]   # generate sample config file",
         program_name.cyan()
     );
+    println!(
+        "  {} analytics export --table <click_log|daily|hourly> [--format parquet -o <file>] # export analytics data",
+        program_name.cyan()
+    );
+    println!(
+        "  {} migrate down --to <VERSION> [--dry-run]   # roll back database migrations",
+        program_name.cyan()
+    );
+    println!(
+        "  {} db maintain [--full]          # reclaim space and refresh database statistics",
+        program_name.cyan()
+    );
+    println!(
+        "  {} generate-deploy --target docker-compose --database postgres -o ./deploy/   # write deployment manifests",
+        program_name.cyan()
+    );
     println!();
     println!("{}", "Options:".bold());
     println!("  {}     force overwrite existing code", "--force".yellow());
     println!(
-        "  {}    set expiration (RFC3339 or relative time)",
+        "  {}    set expiration (RFC3339, YYYY-MM-DD [HH:MM], relative time, now or never)",
         "--expire".yellow()
     );
     println!(
         "  {}  set password protection for the link",
         "--password".yellow()
     );
+    println!(
+        "  {} CSV delimiter for import: , ; or tab (auto-detected)",
+        "--delimiter".yellow()
+    );
+    println!(
+        "  {} TOML column mappings / value transforms for import",
+        "--transform".yellow()
+    );
 }
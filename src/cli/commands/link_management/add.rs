@@ -4,7 +4,10 @@ use colored::Colorize;
 
 use crate::cli::CliError;
 use crate::client::LinkClient;
+use crate::services::link_validation::format_display_time;
+use crate::storage::AnalyticsLevel;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn add_link(
     client: &LinkClient,
     short_code: Option<String>,
@@ -12,6 +15,9 @@ pub async fn add_link(
     force_overwrite: bool,
     expire_time: Option<String>,
     password: Option<String>,
+    analytics_level: Option<AnalyticsLevel>,
+    extras: Option<String>,
+    override_cooldown: bool,
 ) -> Result<(), CliError> {
     let result = client
         .create_link(
@@ -20,6 +26,9 @@ pub async fn add_link(
             force_overwrite,
             expire_time,
             password,
+            analytics_level,
+            extras,
+            override_cooldown,
         )
         .await?;
 
@@ -37,10 +46,7 @@ pub async fn add_link(
             "✓".bold().green(),
             result.link.code.cyan(),
             result.link.target.blue().underline(),
-            expires_at
-                .format("%Y-%m-%d %H:%M:%S UTC")
-                .to_string()
-                .yellow()
+            format_display_time(expires_at).yellow()
         );
     } else {
         println!(
@@ -51,5 +57,9 @@ pub async fn add_link(
         );
     }
 
+    for warning in &result.warnings {
+        println!("{} {}", "⚠".bold().yellow(), warning);
+    }
+
     Ok(())
 }
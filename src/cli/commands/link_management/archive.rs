@@ -0,0 +1,64 @@
+//! Archive / unarchive link commands
+
+use colored::Colorize;
+
+use crate::cli::CliError;
+use crate::client::LinkClient;
+
+pub async fn archive_links(
+    client: &LinkClient,
+    codes: Vec<String>,
+    search: Option<String>,
+) -> Result<(), CliError> {
+    if codes.is_empty() && search.is_none() {
+        return Err(CliError::ParseError(
+            "Specify short codes or --search to select links".to_string(),
+        ));
+    }
+
+    let result = client.archive_links(codes, search).await?;
+
+    for code in &result.archived {
+        println!("{} Archived {}", "✓".bold().green(), code.cyan());
+    }
+    for code in &result.not_found {
+        println!(
+            "{} Skipped {}: {}",
+            "ℹ".bold().blue(),
+            code.cyan(),
+            "not found".dimmed()
+        );
+    }
+    for item in &result.failed {
+        println!(
+            "{} Failed {}: {}",
+            "✗".bold().red(),
+            item.code.cyan(),
+            item.reason
+        );
+    }
+
+    println!();
+    println!(
+        "{} {} archived, {} not found, {} failed",
+        "ℹ".bold().blue(),
+        result.archived.len().to_string().green(),
+        result.not_found.len(),
+        result.failed.len()
+    );
+
+    Ok(())
+}
+
+pub async fn unarchive_link(client: &LinkClient, short_code: String) -> Result<(), CliError> {
+    let link = client.unarchive_link(short_code).await?;
+
+    println!(
+        "{} Restored short link: {} -> {}",
+        "✓".bold().green(),
+        link.code.cyan(),
+        link.target.blue().underline()
+    );
+
+    Ok(())
+}
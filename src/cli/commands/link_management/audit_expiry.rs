@@ -0,0 +1,84 @@
+//! Audit expiry command
+//!
+//! 只读检查：引入 `links.expiry_timezone` 之前，日期选择器提交的日期常被存成当天
+//! UTC 零点，东八区用户设的"12 月 31 日过期"实际在 31 日 08:00 就失效了。
+//! 这里列出这类可疑链接，由运维确认后用 `extend --to` 修正，不自动改数据。
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::Serialize;
+
+use crate::cli::CliError;
+use crate::client::LinkClient;
+use crate::services::link_validation::{expiry_input_rules, format_display_time};
+
+#[derive(Serialize)]
+struct SuspectLink<'a> {
+    code: &'a str,
+    target: &'a str,
+    expires_at: DateTime<Utc>,
+    /// 按 `links.expiry_timezone` 展示的过期时间
+    expires_at_local: String,
+    expired: bool,
+}
+
+/// 过期时间是否恰好为 UTC 零点
+fn is_midnight_utc(expires_at: &DateTime<Utc>) -> bool {
+    expires_at.time() == NaiveTime::MIN
+}
+
+pub async fn audit_midnight_utc_expiry(client: &LinkClient, json: bool) -> Result<(), CliError> {
+    write_midnight_utc_audit(client, json, io::stdout().lock()).await
+}
+
+/// 把过期时间恰好为 UTC 零点的链接写入 `output`，摘要写 stderr
+pub async fn write_midnight_utc_audit(
+    client: &LinkClient,
+    json: bool,
+    mut output: impl Write,
+) -> Result<(), CliError> {
+    let links = client.export_links().await?;
+    let timezone = expiry_input_rules().timezone;
+    let now = Utc::now();
+
+    let mut suspects = 0usize;
+    for link in &links {
+        let Some(expires_at) = link.expires_at.filter(is_midnight_utc) else {
+            continue;
+        };
+        suspects += 1;
+        let suspect = SuspectLink {
+            code: &link.code,
+            target: &link.target,
+            expires_at,
+            expires_at_local: timezone.format(expires_at),
+            expired: expires_at <= now,
+        };
+        write_suspect(&mut output, &suspect, json)
+            .map_err(|e| CliError::CommandError(format!("Failed to write output: {}", e)))?;
+    }
+
+    eprintln!(
+        "{} of {} links expire at exactly 00:00:00 UTC; fix with `extend <CODE> --to <DATE>`",
+        suspects,
+        links.len()
+    );
+    Ok(())
+}
+
+fn write_suspect(output: &mut impl Write, suspect: &SuspectLink, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer(&mut *output, suspect)?;
+        return writeln!(output);
+    }
+    writeln!(
+        output,
+        "{}\t{} ({})\t{}{}",
+        suspect.code,
+        format_display_time(suspect.expires_at),
+        suspect.expires_at_local,
+        suspect.target,
+        if suspect.expired { "\t[expired]" } else { "" }
+    )
+}
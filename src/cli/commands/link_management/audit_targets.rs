@@ -0,0 +1,76 @@
+//! Audit targets command
+//!
+//! 只读检查：`links.credential_policy` 只作用于之后的创建、更新与导入，
+//! 之前存下的带 `?token=` 或 `user:pass@` 的目标 URL 需要单独找出来。
+//! 输出不含凭据本身，只给出命中的参数名和移除凭据后的 URL，由运维确认后用
+//! `update` 修正，不自动改数据。
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cli::CliError;
+use crate::client::LinkClient;
+use crate::services::target_credentials::{
+    credential_rules, detect_credentials, strip_credentials,
+};
+
+#[derive(Serialize)]
+struct CredentialLink<'a> {
+    code: &'a str,
+    /// 命中项，如 `query parameter 'token'`
+    findings: Vec<String>,
+    /// 移除凭据后的目标 URL
+    stripped_target: String,
+}
+
+pub async fn audit_target_credentials(client: &LinkClient, json: bool) -> Result<(), CliError> {
+    write_credential_audit(client, json, io::stdout().lock()).await
+}
+
+/// 把目标 URL 含凭据的链接写入 `output`，摘要写 stderr
+pub async fn write_credential_audit(
+    client: &LinkClient,
+    json: bool,
+    mut output: impl Write,
+) -> Result<(), CliError> {
+    let links = client.export_links().await?;
+    let params = credential_rules().params;
+
+    let mut flagged = 0usize;
+    for link in &links {
+        let findings = detect_credentials(&link.target, &params);
+        if findings.is_empty() {
+            continue;
+        }
+        flagged += 1;
+        let entry = CredentialLink {
+            code: &link.code,
+            findings: findings.iter().map(ToString::to_string).collect(),
+            stripped_target: strip_credentials(&link.target, &params),
+        };
+        write_entry(&mut output, &entry, json)
+            .map_err(|e| CliError::CommandError(format!("Failed to write output: {}", e)))?;
+    }
+
+    eprintln!(
+        "{} of {} links carry credentials in their target URL; fix with `update <CODE> <URL>`",
+        flagged,
+        links.len()
+    );
+    Ok(())
+}
+
+fn write_entry(output: &mut impl Write, entry: &CredentialLink, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer(&mut *output, entry)?;
+        return writeln!(output);
+    }
+    writeln!(
+        output,
+        "{}\t{}\t{}",
+        entry.code,
+        entry.findings.join(", "),
+        entry.stripped_target
+    )
+}
@@ -0,0 +1,69 @@
+//! Extend links command - 批量顺延过期时间
+
+use colored::Colorize;
+
+use crate::cli::CliError;
+use crate::client::{BatchExtendArgs, LinkClient};
+use crate::services::link_validation::format_display_time;
+
+pub async fn extend_links(client: &LinkClient, args: BatchExtendArgs) -> Result<(), CliError> {
+    if args.codes.is_empty() && args.search.is_none() {
+        return Err(CliError::ParseError(
+            "Specify short codes or --search to select links".to_string(),
+        ));
+    }
+
+    let result = client.batch_extend(args).await?;
+
+    let verb = if result.dry_run {
+        "Would extend"
+    } else {
+        "Extended"
+    };
+    for item in &result.updated {
+        let old = item
+            .old_expires_at
+            .map(format_display_time)
+            .unwrap_or_else(|| "never".to_string());
+        println!(
+            "{} {} {}: {} -> {}",
+            "✓".bold().green(),
+            verb,
+            item.code.cyan(),
+            old.dimmed(),
+            format_display_time(item.new_expires_at).yellow()
+        );
+    }
+    for item in &result.skipped {
+        println!(
+            "{} Skipped {}: {}",
+            "ℹ".bold().blue(),
+            item.code.cyan(),
+            item.reason.dimmed()
+        );
+    }
+    for item in &result.failed {
+        println!(
+            "{} Failed {}: {}",
+            "✗".bold().red(),
+            item.code.cyan(),
+            item.reason
+        );
+    }
+
+    println!();
+    println!(
+        "{} {} updated, {} skipped, {} failed{}",
+        "ℹ".bold().blue(),
+        result.updated.len().to_string().green(),
+        result.skipped.len(),
+        result.failed.len(),
+        if result.dry_run {
+            " (dry-run, nothing written)"
+        } else {
+            ""
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! Generate command - 按模板笛卡尔积批量生成链接
+
+use std::io::{self, Write};
+
+use colored::Colorize;
+
+use crate::cli::CliError;
+use crate::client::LinkClient;
+use crate::services::{LinkTemplate, TemplateLink, TemplateVar};
+
+/// `shortlinker generate` 的参数
+#[derive(Debug, Clone)]
+pub struct GenerateArgs {
+    pub template: String,
+    pub code_template: Option<String>,
+    /// `name=v1,v2` 形式的变量
+    pub vars: Vec<String>,
+    pub force: bool,
+    pub expire: Option<String>,
+    pub password: Option<String>,
+    /// 跳过确认
+    pub yes: bool,
+    pub dry_run: bool,
+}
+
+pub async fn generate_links(client: &LinkClient, args: GenerateArgs) -> Result<(), CliError> {
+    let vars = args
+        .vars
+        .iter()
+        .map(|arg| TemplateVar::parse(arg))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CliError::ParseError(e.message().to_string()))?;
+    let template = LinkTemplate {
+        url: args.template,
+        code: args.code_template,
+        vars,
+    };
+
+    // 先预览：展开与校验失败在这里直接报错，不会写入任何链接
+    let preview = client
+        .generate_links(
+            template.clone(),
+            args.force,
+            args.expire.clone(),
+            args.password.clone(),
+            true,
+        )
+        .await?;
+
+    println!("{}", "Links to create:".bold().green());
+    println!();
+    for link in &preview.planned {
+        let exists = link
+            .code
+            .as_ref()
+            .is_some_and(|code| preview.existing.contains(code));
+        print_mapping(link, exists);
+    }
+    println!();
+    println!(
+        "{} {} combinations",
+        "ℹ".bold().blue(),
+        preview.planned.len().to_string().green()
+    );
+    if !preview.existing.is_empty() {
+        let action = if args.force {
+            "will be overwritten"
+        } else {
+            "will fail (use --force to overwrite)"
+        };
+        println!(
+            "{} {} codes already exist and {}",
+            "⚠".bold().yellow(),
+            preview.existing.len().to_string().yellow(),
+            action
+        );
+    }
+
+    if args.dry_run {
+        println!("{} Dry-run, nothing written", "ℹ".bold().blue());
+        return Ok(());
+    }
+
+    if !args.yes {
+        print!("\nCreate {} links? [y/N] ", preview.planned.len());
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| CliError::CommandError(format!("Failed to read input: {}", e)))?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{} Generation cancelled.", "✗".bold().red());
+            return Ok(());
+        }
+    }
+
+    let result = client
+        .generate_links(template, args.force, args.expire, args.password, false)
+        .await?;
+
+    println!();
+    for link in &result.created {
+        print_mapping(link, false);
+    }
+    for item in &result.failed {
+        println!(
+            "{} Failed {}: {}",
+            "✗".bold().red(),
+            item.code.cyan(),
+            item.reason
+        );
+    }
+    println!();
+    println!(
+        "{} {} created, {} failed",
+        "ℹ".bold().blue(),
+        result.created.len().to_string().green(),
+        result.failed.len()
+    );
+
+    Ok(())
+}
+
+fn print_mapping(link: &TemplateLink, exists: bool) {
+    let code = match &link.code {
+        Some(code) => code.cyan().to_string(),
+        None => "<random>".dimmed().to_string(),
+    };
+    let marker = if exists {
+        format!(" {}", "(exists)".yellow())
+    } else {
+        String::new()
+    };
+    println!("  {} -> {}{}", code, link.target.blue().underline(), marker);
+}
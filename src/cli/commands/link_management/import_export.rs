@@ -4,9 +4,23 @@ use colored::Colorize;
 use std::path::Path;
 
 use crate::cli::CliError;
+use crate::cli::commands::object_storage;
 use crate::client::LinkClient;
 use crate::services::ImportLinkItemRich;
-use crate::utils::csv_handler;
+use crate::utils::csv_handler::{self, CsvLinkRow};
+use crate::utils::csv_transform::ImportTransform;
+use crate::utils::s3::{self, S3Location};
+
+/// `shortlinker import` 的参数
+#[derive(Debug, Clone)]
+pub struct ImportArgs {
+    pub file_path: String,
+    pub force: bool,
+    pub delimiter: Option<u8>,
+    pub transform: Option<ImportTransform>,
+    /// 只预览转换与校验结果，不导入
+    pub dry_run: bool,
+}
 
 pub async fn export_links(client: &LinkClient, file_path: Option<String>) -> Result<(), CliError> {
     let links = client.export_links().await?;
@@ -20,8 +34,23 @@ pub async fn export_links(client: &LinkClient, file_path: Option<String>) -> Res
 
     // Convert Vec<ShortLink> to Vec<&ShortLink> for csv_handler
     let link_refs: Vec<&_> = links.iter().collect();
-    csv_handler::export_to_csv(&link_refs, &output_path)
-        .map_err(|e| CliError::CommandError(format!("Failed to export CSV: {}", e)))?;
+    let output_path = if s3::is_s3_path(&output_path) {
+        let settings = object_storage::load_settings(None).await?;
+        let location = object_storage::resolve_location(
+            &output_path,
+            &settings,
+            csv_handler::generate_export_filename,
+        )?;
+        let (writer, upload) =
+            s3::start_upload(&location, &settings, "text/csv").map_err(object_storage::s3_error)?;
+        let written = csv_handler::write_csv(&link_refs, writer);
+        let ((), report) = object_storage::complete_upload(upload, written).await?;
+        report.location.to_string()
+    } else {
+        csv_handler::export_to_csv(&link_refs, &output_path)
+            .map_err(|e| CliError::CommandError(format!("Failed to export CSV: {}", e)))?;
+        output_path
+    };
 
     println!(
         "{} Exported {} short links to: {}",
@@ -33,22 +62,44 @@ pub async fn export_links(client: &LinkClient, file_path: Option<String>) -> Res
     Ok(())
 }
 
-pub async fn import_links(
-    client: &LinkClient,
-    file_path: String,
-    force_overwrite: bool,
-) -> Result<(), CliError> {
-    // Check if file exists
-    if !Path::new(&file_path).exists() {
-        return Err(CliError::CommandError(format!(
-            "Import file not found: {}",
-            file_path
-        )));
-    }
+pub async fn import_links(client: &LinkClient, args: ImportArgs) -> Result<(), CliError> {
+    let ImportArgs {
+        file_path,
+        force,
+        delimiter,
+        transform,
+        dry_run,
+    } = args;
 
     // Read and parse the import file
-    let imported_links = csv_handler::import_from_csv(&file_path)
-        .map_err(|e| CliError::CommandError(format!("Failed to import CSV: {}", e)))?;
+    let imported = if s3::is_s3_path(&file_path) {
+        let settings = object_storage::load_settings(None).await?;
+        let location =
+            S3Location::parse(&file_path, &settings).map_err(object_storage::s3_error)?;
+        let bytes = s3::download(&location, &settings)
+            .await
+            .map_err(object_storage::s3_error)?;
+        csv_handler::import_from_csv_bytes(&bytes, delimiter, transform.as_ref())
+    } else {
+        // Check if file exists
+        if !Path::new(&file_path).exists() {
+            return Err(CliError::CommandError(format!(
+                "Import file not found: {}",
+                file_path
+            )));
+        }
+        csv_handler::import_from_csv(&file_path, delimiter, transform.as_ref())
+    }
+    .map_err(|e| CliError::CommandError(format!("Failed to import CSV: {}", e)))?;
+    println!("{} {}", "ℹ".bold().blue(), imported.dialect.describe());
+    for warning in &imported.warnings {
+        println!("{} {}", "⚠".bold().yellow(), warning);
+    }
+    if dry_run {
+        print_import_preview(&imported);
+        return Ok(());
+    }
+    let imported_links = imported.links;
 
     if imported_links.is_empty() {
         println!("{} Import file is empty", "ℹ".bold().blue());
@@ -65,11 +116,13 @@ pub async fn import_links(
             expires_at: link.expires_at,
             password: link.password,
             click_count: link.click,
+            analytics_level: link.analytics_level,
+            extras: link.extras,
             row_num: None,
         })
         .collect();
 
-    let result = client.import_links(import_items, force_overwrite).await?;
+    let result = client.import_links(import_items, force).await?;
 
     // Print errors if any
     for item in &result.failed_items {
@@ -96,3 +149,52 @@ pub async fn import_links(
 
     Ok(())
 }
+
+/// dry-run：输出转换后的前几行与校验结果
+fn print_import_preview(imported: &csv_handler::CsvImport) {
+    println!();
+    println!(
+        "{} (first {} rows after transform)",
+        "Preview".bold().green(),
+        csv_handler::PREVIEW_ROWS
+    );
+    for (row_num, row) in &imported.preview {
+        print_preview_row(*row_num, row);
+    }
+    for error in &imported.errors {
+        println!("{} {}", "✗".bold().red(), error);
+    }
+    println!();
+    println!(
+        "{} {} valid, {} invalid (dry-run, nothing imported)",
+        "Validation:".bold().green(),
+        imported.links.len().to_string().green(),
+        imported.errors.len().to_string().red()
+    );
+}
+
+fn print_preview_row(row_num: usize, row: &CsvLinkRow) {
+    let mut details = vec![format!("created_at={}", row.created_at)];
+    if let Some(expires_at) = row.expires_at.as_deref().filter(|v| !v.is_empty()) {
+        details.push(format!("expires_at={}", expires_at));
+    }
+    if row.password.as_deref().is_some_and(|v| !v.is_empty()) {
+        details.push("password=***".to_string());
+    }
+    if row.click_count > 0 {
+        details.push(format!("clicks={}", row.click_count));
+    }
+    if let Some(level) = row.analytics_level.as_deref().filter(|v| !v.is_empty()) {
+        details.push(format!("analytics_level={}", level));
+    }
+    if let Some(extras) = row.extras.as_deref().filter(|v| !v.is_empty()) {
+        details.push(format!("extras={}", extras));
+    }
+    println!(
+        "  {} {} → {}  {}",
+        format!("Row {}:", row_num).dimmed(),
+        row.code.cyan(),
+        row.target,
+        details.join(" ").dimmed()
+    );
+}
@@ -4,6 +4,7 @@ use colored::Colorize;
 
 use crate::cli::CliError;
 use crate::client::LinkClient;
+use crate::services::link_validation::format_display_time;
 use crate::storage::ShortLink;
 
 pub async fn list_links(client: &LinkClient) -> Result<(), CliError> {
@@ -29,7 +30,7 @@ pub async fn list_links(client: &LinkClient) -> Result<(), CliError> {
 }
 
 /// Print a single link with colored formatting
-fn print_link(link: &ShortLink) {
+pub(super) fn print_link(link: &ShortLink) {
     let mut info_parts = vec![format!(
         "{} -> {}",
         link.code.cyan(),
@@ -38,7 +39,7 @@ fn print_link(link: &ShortLink) {
 
     if let Some(expires_at) = link.expires_at {
         info_parts.push(
-            format!("(expires: {})", expires_at.format("%Y-%m-%d %H:%M:%S UTC"))
+            format!("(expires: {})", format_display_time(expires_at))
                 .dimmed()
                 .yellow()
                 .to_string(),
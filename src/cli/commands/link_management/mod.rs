@@ -3,13 +3,27 @@
 //! This module provides CLI commands for managing short links.
 
 mod add;
+mod archive;
+mod audit_expiry;
+mod audit_targets;
+mod extend;
+mod generate;
 mod import_export;
 mod list;
 mod remove;
+mod resolve;
+mod sample;
 mod update;
 
 pub use add::add_link;
-pub use import_export::{export_links, import_links};
+pub use archive::{archive_links, unarchive_link};
+pub use audit_expiry::{audit_midnight_utc_expiry, write_midnight_utc_audit};
+pub use audit_targets::{audit_target_credentials, write_credential_audit};
+pub use extend::extend_links;
+pub use generate::{GenerateArgs, generate_links};
+pub use import_export::{ImportArgs, export_links, import_links};
 pub use list::list_links;
 pub use remove::remove_link;
+pub use resolve::{ResolveArgs, resolve_links, write_resolved};
+pub use sample::sample_links;
 pub use update::update_link;
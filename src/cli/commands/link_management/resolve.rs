@@ -0,0 +1,156 @@
+//! Resolve command
+//!
+//! 面向脚本：stdout 只输出数据（每个 code 一行，保持输入顺序），诊断信息全部写 stderr。
+//! 退出码：任一 code 不存在为 3、已过期为 6（以第一个失败的 code 为准）。
+
+use std::io::{self, BufRead, Write};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::CliError;
+use crate::client::LinkClient;
+use crate::storage::{ShortLink, extras_to_value};
+
+/// `resolve` 参数
+pub struct ResolveArgs {
+    pub codes: Vec<String>,
+    /// 从 stdin 逐行读取 code
+    pub stdin: bool,
+    pub json: bool,
+    /// 已过期的链接仍输出目标
+    pub allow_expired: bool,
+    /// 解析失败时输出的占位文本（默认空行）
+    pub error_marker: String,
+}
+
+#[derive(Serialize)]
+struct ResolvedLink<'a> {
+    code: &'a str,
+    target: &'a str,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    expired: bool,
+    click_count: usize,
+    created_via: &'static str,
+    analytics_level: &'static str,
+    password_protected: bool,
+    extras: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct ResolveFailure<'a> {
+    code: &'a str,
+    error: &'static str,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    NotFound,
+    Expired,
+}
+
+impl Failure {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Failure::NotFound => "not_found",
+            Failure::Expired => "expired",
+        }
+    }
+}
+
+pub async fn resolve_links(client: &LinkClient, args: ResolveArgs) -> Result<(), CliError> {
+    let stdin = io::stdin();
+    write_resolved(client, args, stdin.lock(), io::stdout().lock()).await
+}
+
+/// 解析 `args.codes`（或 `input` 中的每一行）并写入 `output`
+pub async fn write_resolved(
+    client: &LinkClient,
+    args: ResolveArgs,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), CliError> {
+    let codes: Box<dyn Iterator<Item = io::Result<String>> + '_> = if args.stdin {
+        Box::new(input.lines())
+    } else {
+        Box::new(args.codes.iter().cloned().map(Ok))
+    };
+
+    let mut first_failure = None;
+    let mut failures = 0usize;
+    for line in codes {
+        let line =
+            line.map_err(|e| CliError::CommandError(format!("Failed to read stdin: {}", e)))?;
+        let code = line.trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        let resolved = match client.get_link(code.to_string()).await? {
+            Some(link) if args.allow_expired || !link.is_expired() => Ok(link),
+            Some(_) => Err(Failure::Expired),
+            None => Err(Failure::NotFound),
+        };
+
+        let written = match resolved {
+            Ok(link) => write_link(&mut output, &link, args.json),
+            Err(failure) => {
+                eprintln!("{}: {}", code, failure.as_str().replace('_', " "));
+                failures += 1;
+                first_failure.get_or_insert(failure);
+                write_failure(&mut output, code, failure, &args)
+            }
+        };
+        written
+            .and_then(|_| output.flush())
+            .map_err(|e| CliError::CommandError(format!("Failed to write output: {}", e)))?;
+    }
+
+    let message = match failures {
+        1 => "1 code could not be resolved".to_string(),
+        n => format!("{} codes could not be resolved", n),
+    };
+    match first_failure {
+        None => Ok(()),
+        Some(Failure::NotFound) => Err(CliError::NotFound(message)),
+        Some(Failure::Expired) => Err(CliError::Expired(message)),
+    }
+}
+
+fn write_link(output: &mut impl Write, link: &ShortLink, json: bool) -> io::Result<()> {
+    if !json {
+        return writeln!(output, "{}", link.target);
+    }
+    let resolved = ResolvedLink {
+        code: &link.code,
+        target: &link.target,
+        created_at: link.created_at,
+        expires_at: link.expires_at,
+        expired: link.is_expired(),
+        click_count: link.click,
+        created_via: link.created_via.as_str(),
+        analytics_level: link.analytics_level.as_str(),
+        password_protected: link.password.is_some(),
+        extras: extras_to_value(link.extras.as_deref()),
+    };
+    serde_json::to_writer(&mut *output, &resolved)?;
+    writeln!(output)
+}
+
+fn write_failure(
+    output: &mut impl Write,
+    code: &str,
+    failure: Failure,
+    args: &ResolveArgs,
+) -> io::Result<()> {
+    if !args.json {
+        return writeln!(output, "{}", args.error_marker);
+    }
+    let failure = ResolveFailure {
+        code,
+        error: failure.as_str(),
+    };
+    serde_json::to_writer(&mut *output, &failure)?;
+    writeln!(output)
+}
@@ -0,0 +1,55 @@
+//! Random sample command
+
+use colored::Colorize;
+
+use crate::cli::{CliError, SampleOutput};
+use crate::client::LinkClient;
+use crate::utils::csv_handler;
+
+use super::list::print_link;
+
+pub async fn sample_links(
+    client: &LinkClient,
+    n: usize,
+    seed: Option<u64>,
+    search: Option<String>,
+    output: SampleOutput,
+) -> Result<(), CliError> {
+    let sample = client.sample_links(n, seed, search).await?;
+
+    match output {
+        SampleOutput::Csv => {
+            // CSV 写 stdout 便于重定向，摘要写 stderr
+            let link_refs: Vec<&_> = sample.links.iter().collect();
+            csv_handler::write_csv(&link_refs, std::io::stdout().lock())
+                .map_err(|e| CliError::CommandError(format!("Failed to write CSV: {}", e)))?;
+            eprintln!(
+                "Sampled {} of {} links (seed {})",
+                sample.links.len(),
+                sample.population,
+                sample.seed
+            );
+        }
+        SampleOutput::Table => {
+            if sample.links.is_empty() {
+                println!("{} No short links match the filter", "ℹ".bold().blue());
+                return Ok(());
+            }
+            println!("{}", "Random sample:".bold().green());
+            println!();
+            for link in &sample.links {
+                print_link(link);
+            }
+            println!();
+            println!(
+                "{} Sampled {} of {} links (seed {}, pass --seed to reproduce)",
+                "ℹ".bold().blue(),
+                sample.links.len().to_string().green(),
+                sample.population,
+                sample.seed.to_string().yellow()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -4,6 +4,8 @@ use colored::Colorize;
 
 use crate::cli::CliError;
 use crate::client::LinkClient;
+use crate::services::link_validation::format_display_time;
+use crate::storage::AnalyticsLevel;
 
 pub async fn update_link(
     client: &LinkClient,
@@ -11,10 +13,20 @@ pub async fn update_link(
     target_url: String,
     expire_time: Option<String>,
     password: Option<String>,
+    analytics_level: Option<AnalyticsLevel>,
+    extras: Option<String>,
 ) -> Result<(), CliError> {
-    let link = client
-        .update_link(short_code, target_url, expire_time, password)
+    let result = client
+        .update_link(
+            short_code,
+            target_url,
+            expire_time,
+            password,
+            analytics_level,
+            extras,
+        )
         .await?;
+    let link = result.link;
 
     println!(
         "{} Short link updated: {} -> {}",
@@ -27,12 +39,13 @@ pub async fn update_link(
         println!(
             "{} Expiration: {}",
             "ℹ".bold().blue(),
-            expires_at
-                .format("%Y-%m-%d %H:%M:%S UTC")
-                .to_string()
-                .yellow()
+            format_display_time(expires_at).yellow()
         );
     }
 
+    for warning in &result.warnings {
+        println!("{} {}", "⚠".bold().yellow(), warning);
+    }
+
     Ok(())
 }
@@ -0,0 +1,286 @@
+//! 数据库迁移 CLI 命令（状态 / 回滚）
+//!
+//! 直连数据库且不自动运行迁移：回滚必须用执行过这些迁移的（新版本）二进制完成，
+//! 之后旧版本二进制即可启动。
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use sea_orm::DatabaseConnection;
+
+use crate::cli::CliError;
+use crate::cli::commands::object_storage;
+use crate::errors::ShortlinkerError;
+use crate::storage::backend::migrate::{
+    DroppedData, RollbackPlan, SchemaCompatibility, backup_sqlite, default_sqlite_backup_path,
+    migration_status, plan_rollback, rollback, schema_compatibility,
+};
+use crate::utils::s3::{self, S3Location, S3Settings};
+use migration::rollback::Dropped;
+
+/// `migrate down` 参数
+pub struct MigrateDownArgs {
+    pub to: String,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub backup: Option<String>,
+}
+
+/// 运行 `migrate status`
+pub async fn run_migrate_status(db: &DatabaseConnection) -> Result<(), CliError> {
+    let states = migration_status(db).await?;
+
+    for state in &states {
+        let (mark, applied) = match (&state.applied_at, state.known) {
+            (Some(at), true) => (
+                "✓".green().bold(),
+                at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+            (Some(at), false) => (
+                "?".yellow().bold(),
+                format!(
+                    "{} (unknown to this binary)",
+                    at.format("%Y-%m-%d %H:%M:%S")
+                ),
+            ),
+            (None, _) => ("·".dimmed(), "pending".to_string()),
+        };
+        println!("{} {:<48} {}", mark, state.name, applied.dimmed());
+    }
+
+    println!();
+    print_compatibility(&schema_compatibility(&states));
+    Ok(())
+}
+
+/// 运行 `migrate down --to <VERSION>`
+pub async fn run_migrate_down(
+    db: &DatabaseConnection,
+    backend: &str,
+    database_url: &str,
+    args: MigrateDownArgs,
+) -> Result<(), CliError> {
+    let plan = plan_rollback(db, &args.to).await?;
+    if plan.steps.is_empty() {
+        println!(
+            "{} Schema is already at {}, nothing to roll back",
+            "ℹ".bold().blue(),
+            plan.target.cyan()
+        );
+        return Ok(());
+    }
+
+    print_plan(&plan);
+
+    let irreversible = plan.irreversible();
+    if !irreversible.is_empty() {
+        for step in &irreversible {
+            println!(
+                "{} {} is irreversible: {}",
+                "✗".bold().red(),
+                step.name.cyan(),
+                step.impact.note
+            );
+        }
+        return Err(CliError::CommandError(
+            "Rollback refused: the plan contains irreversible migrations, restore a backup instead"
+                .to_string(),
+        ));
+    }
+
+    if args.dry_run {
+        println!("{} Dry-run, nothing changed", "ℹ".bold().blue());
+        return Ok(());
+    }
+
+    // 回滚前备份：SQLite 自动 VACUUM INTO，其他数据库提示手动备份
+    let backup = if backend == "sqlite" {
+        match args.backup {
+            Some(path) if s3::is_s3_path(&path) => {
+                let settings = object_storage::load_settings(Some(db)).await?;
+                let local = default_sqlite_backup_path(database_url).ok_or_else(|| {
+                    CliError::CommandError(
+                        "An in-memory database cannot be backed up to S3".to_string(),
+                    )
+                })?;
+                let location = object_storage::resolve_location(&path, &settings, || {
+                    local
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                })?;
+                Some(Backup::S3 {
+                    local,
+                    location,
+                    settings,
+                })
+            }
+            Some(path) => Some(Backup::Local(PathBuf::from(path))),
+            None => default_sqlite_backup_path(database_url).map(Backup::Local),
+        }
+    } else {
+        println!(
+            "{} Automatic backup is only available for SQLite. Back up the {} database manually (e.g. pg_dump / mysqldump) before continuing.",
+            "⚠".bold().yellow(),
+            backend.to_uppercase()
+        );
+        None
+    };
+
+    if !args.yes {
+        let action = match &backup {
+            Some(backup) => format!(
+                "Back up to {} and roll back {} migrations?",
+                backup,
+                plan.steps.len()
+            ),
+            None => format!("Roll back {} migrations?", plan.steps.len()),
+        };
+        print!("\n{} [y/N] ", action);
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| CliError::CommandError(format!("Failed to read input: {}", e)))?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("{} Rollback cancelled.", "✗".bold().red());
+            return Ok(());
+        }
+    }
+
+    if let Some(backup) = &backup {
+        backup.write(db).await?;
+        println!(
+            "{} Backup written to {}",
+            "✓".green().bold(),
+            backup.to_string().cyan()
+        );
+    }
+
+    rollback(db, &plan).await?;
+    println!(
+        "{} Rolled back {} migrations, schema is now at {}",
+        "✓".green().bold(),
+        plan.steps.len(),
+        plan.target.cyan()
+    );
+
+    let states = migration_status(db).await?;
+    print_compatibility(&schema_compatibility(&states));
+    Ok(())
+}
+
+/// 回滚前的 SQLite 备份目标
+enum Backup {
+    Local(PathBuf),
+    /// 先 VACUUM INTO 数据库旁的临时文件，上传后删除
+    S3 {
+        local: PathBuf,
+        location: S3Location,
+        settings: S3Settings,
+    },
+}
+
+impl Backup {
+    async fn write(&self, db: &DatabaseConnection) -> Result<(), CliError> {
+        match self {
+            Backup::Local(path) => Ok(backup_sqlite(db, path).await?),
+            Backup::S3 {
+                local,
+                location,
+                settings,
+            } => {
+                backup_sqlite(db, local).await?;
+                let result = upload_backup(local, location, settings).await;
+                if let Err(e) = std::fs::remove_file(local) {
+                    println!(
+                        "{} Failed to remove temporary backup {}: {}",
+                        "⚠".bold().yellow(),
+                        local.display(),
+                        e
+                    );
+                }
+                result
+            }
+        }
+    }
+}
+
+impl fmt::Display for Backup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backup::Local(path) => write!(f, "{}", path.display()),
+            Backup::S3 { location, .. } => write!(f, "{}", location),
+        }
+    }
+}
+
+async fn upload_backup(
+    path: &Path,
+    location: &S3Location,
+    settings: &S3Settings,
+) -> Result<(), CliError> {
+    let mut file = File::open(path).map_err(|e| {
+        CliError::CommandError(format!("Failed to open '{}': {}", path.display(), e))
+    })?;
+    let (mut writer, upload) = s3::start_upload(location, settings, "application/vnd.sqlite3")
+        .map_err(object_storage::s3_error)?;
+    let written = io::copy(&mut file, &mut writer)
+        .map_err(|e| ShortlinkerError::file_operation(format!("Failed to upload backup: {}", e)));
+    drop(writer);
+    object_storage::complete_upload(upload, written).await?;
+    Ok(())
+}
+
+fn print_plan(plan: &RollbackPlan) {
+    println!(
+        "{} Rolling back to {} ({} migrations, newest first):",
+        "ℹ".bold().blue(),
+        plan.target.cyan(),
+        plan.steps.len()
+    );
+    for step in &plan.steps {
+        println!("  {}", step.name.bold());
+        if !step.impact.note.is_empty() {
+            println!("    {}", step.impact.note.dimmed());
+        }
+        for dropped in &step.dropped {
+            println!("    {} {}", "-".red(), describe_dropped(dropped));
+        }
+    }
+}
+
+fn describe_dropped(dropped: &DroppedData) -> String {
+    let rows = match dropped.rows {
+        Some(rows) => format!("{} rows", rows),
+        None => "row count unavailable".to_string(),
+    };
+    match dropped.object {
+        Dropped::Table(table) => format!("drop table {} ({})", table, rows),
+        Dropped::Column(table, column) => {
+            format!("drop column {}.{} ({} with data)", table, column, rows)
+        }
+    }
+}
+
+fn print_compatibility(compatibility: &SchemaCompatibility) {
+    match compatibility {
+        SchemaCompatibility::UpToDate => {
+            println!("{} Schema matches this binary", "✓".green().bold())
+        }
+        SchemaCompatibility::Pending(count) => println!(
+            "{} {} pending migrations; they are applied automatically when this binary starts. Older binaries whose newest migration is applied can start as-is.",
+            "ℹ".bold().blue(),
+            count
+        ),
+        SchemaCompatibility::Newer(unknown) => println!(
+            "{} Database was migrated by a newer version ({}); this binary will refuse to start until it is rolled back with that version",
+            "⚠".bold().yellow(),
+            unknown.join(", ")
+        ),
+    }
+}
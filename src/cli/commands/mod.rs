@@ -2,13 +2,31 @@
 //!
 //! This module re-exports all CLI command functions.
 
+mod analytics;
+mod bench;
 pub mod config_management;
+mod db;
+mod deploy;
 mod help;
 mod link_management;
+mod migrate;
+mod object_storage;
 mod reset_password;
 mod status;
+mod token;
 
+pub use analytics::{
+    AnalyticsAmendArgs, AnalyticsExportArgs, AnalyticsRebuildArgs, run_analytics_amend,
+    run_analytics_export, run_analytics_rebuild,
+};
+pub use bench::{BenchOptions, parse_bench_duration, run_bench};
+pub use db::{DbMaintainArgs, run_db_maintain};
+pub use deploy::{
+    DeployFile, DeployOptions, render_deploy_files, run_generate_deploy, write_deploy_files,
+};
 pub use help::*;
 pub use link_management::*;
+pub use migrate::{MigrateDownArgs, run_migrate_down, run_migrate_status};
 pub use reset_password::*;
 pub use status::server_status;
+pub use token::run_token_rotate;
@@ -0,0 +1,69 @@
+//! CLI 命令共用的对象存储辅助：读取 S3 设置、解析 `s3:` 路径、收尾上传
+
+use sea_orm::DatabaseConnection;
+
+use crate::cli::CliError;
+use crate::config::{init_runtime_config, try_get_runtime_config};
+use crate::errors::ShortlinkerError;
+use crate::metrics::NoopMetrics;
+use crate::storage::StorageFactory;
+use crate::utils::s3::{S3Location, S3Settings, S3Upload, UploadReport};
+
+/// 读取 S3 设置
+///
+/// `storage.s3_*` 保存在数据库中；CLI 进程还没加载运行时配置时，用给定的连接
+/// （或新建一个）加载一次。
+pub(super) async fn load_settings(db: Option<&DatabaseConnection>) -> Result<S3Settings, CliError> {
+    if try_get_runtime_config().is_none() {
+        match db {
+            Some(db) => init_runtime_config(db.clone()).await?,
+            None => {
+                let storage = StorageFactory::create(NoopMetrics::arc())
+                    .await
+                    .map_err(|e| CliError::StorageError(e.to_string()))?;
+                init_runtime_config(storage.get_db().clone()).await?;
+            }
+        }
+    }
+    let rt = try_get_runtime_config()
+        .ok_or_else(|| CliError::CommandError("Runtime configuration not loaded".to_string()))?;
+    Ok(S3Settings::from_runtime_config(rt))
+}
+
+/// 解析 `s3:` 路径；指向目录时补上默认文件名
+pub(super) fn resolve_location(
+    path: &str,
+    settings: &S3Settings,
+    default_file_name: impl FnOnce() -> String,
+) -> Result<S3Location, CliError> {
+    let location = S3Location::parse(path, settings).map_err(s3_error)?;
+    Ok(if location.is_dir() {
+        location.or_file_name(&default_file_name())
+    } else {
+        location
+    })
+}
+
+/// 写入结束后提交或放弃上传
+///
+/// 上传线程先失败时，写入端只会得到笼统的 I/O 错误，此时报告上传本身的错误；
+/// 否则报告写入端的错误（如数据库读取失败）。
+pub(super) async fn complete_upload<T>(
+    upload: S3Upload,
+    written: Result<T, ShortlinkerError>,
+) -> Result<(T, UploadReport), CliError> {
+    match written {
+        Ok(value) => {
+            let report = upload.finish().await.map_err(s3_error)?;
+            Ok((value, report))
+        }
+        Err(e) => Err(match upload.abort().await {
+            Some(upload_error) => s3_error(upload_error),
+            None => e.into(),
+        }),
+    }
+}
+
+pub(super) fn s3_error(e: ShortlinkerError) -> CliError {
+    CliError::CommandError(e.message().to_string())
+}
@@ -119,6 +119,20 @@ pub async fn run_reset_password(db: DatabaseConnection, password: Option<String>
     let config_store = ConfigStore::new(db);
     match config_store.set(keys::API_ADMIN_TOKEN, &hashed).await {
         Ok(_) => {
+            // 重置视为紧急恢复，同时结束 `token rotate` 留下的旧 token 宽限期
+            for key in [
+                keys::API_ADMIN_TOKEN_PREVIOUS,
+                keys::API_ADMIN_TOKEN_PREVIOUS_EXPIRES_AT,
+            ] {
+                if let Err(e) = config_store.set(key, "").await {
+                    eprintln!(
+                        "{} Failed to clear {}: {}",
+                        "Warning:".yellow().bold(),
+                        key,
+                        e
+                    );
+                }
+            }
             println!("{} Admin password reset successfully", "✓".green().bold());
         }
         Err(e) => {
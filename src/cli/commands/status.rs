@@ -3,7 +3,7 @@
 use colored::Colorize;
 
 use crate::cli::CliError;
-use crate::system::ipc::{self, IpcError, IpcResponse};
+use crate::system::ipc::{self, BackgroundTaskStatus, IpcCommandStats, IpcError, IpcResponse};
 
 /// Display server status via IPC
 pub async fn server_status() -> Result<(), CliError> {
@@ -22,6 +22,9 @@ pub async fn server_status() -> Result<(), CliError> {
             last_data_reload,
             last_config_reload,
             links_count,
+            ipc_in_flight,
+            ipc_commands,
+            background_tasks,
         }) => {
             println!("{}", "Server Status".bold().green());
             println!("  {}:      {}", "Version".cyan(), version);
@@ -54,6 +57,8 @@ pub async fn server_status() -> Result<(), CliError> {
                 println!("  {}:  {}", "Links count".cyan(), links_count);
             }
 
+            print_ipc_stats(ipc_in_flight, &ipc_commands);
+            print_background_tasks(&background_tasks);
             Ok(())
         }
         Ok(IpcResponse::Error { code, message }) => Err(CliError::CommandError(format!(
@@ -77,6 +82,84 @@ pub async fn server_status() -> Result<(), CliError> {
     }
 }
 
+/// Print per-command IPC statistics (empty for servers that do not report them)
+fn print_ipc_stats(in_flight: usize, commands: &[IpcCommandStats]) {
+    if commands.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{} ({} in flight)",
+        "IPC Commands".bold().green(),
+        in_flight
+    );
+    println!(
+        "  {:<18} {:>7} {:>6} {:>5} {:>8} {:>8}  {}",
+        "Command", "Count", "Errors", "Slow", "Avg ms", "Max ms", "Last"
+    );
+    for stats in commands {
+        // 先补齐宽度再着色，避免转义序列影响对齐
+        let errors = format!("{:>6}", stats.errors);
+        let errors = if stats.errors > 0 {
+            errors.red()
+        } else {
+            errors.normal()
+        };
+        let slow = format!("{:>5}", stats.slow);
+        let slow = if stats.slow > 0 {
+            slow.yellow()
+        } else {
+            slow.normal()
+        };
+        println!(
+            "  {} {:>7} {} {} {:>8} {:>8}  {}",
+            format!("{:<18}", stats.command).cyan(),
+            stats.count,
+            errors,
+            slow,
+            stats.avg_ms,
+            stats.max_ms,
+            stats.last_at.dimmed()
+        );
+    }
+}
+
+/// Print supervised background task health (empty for servers that do not report it)
+fn print_background_tasks(tasks: &[BackgroundTaskStatus]) {
+    if tasks.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "Background Tasks".bold().green());
+    println!(
+        "  {:<20} {:<10} {:>8} {:>10}  {}",
+        "Task", "State", "Restarts", "Heartbeat", "Last panic"
+    );
+    for task in tasks {
+        let state = format!("{:<10}", task.state);
+        let state = match task.state.as_str() {
+            "running" => state.green(),
+            "stopped" => state.normal(),
+            "dead" => state.red(),
+            _ => state.yellow(),
+        };
+        let heartbeat = task
+            .heartbeat_age_secs
+            .map(|secs| format!("{} ago", format_duration(secs)))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {} {} {:>8} {:>10}  {}",
+            format!("{:<20}", task.name).cyan(),
+            state,
+            task.restarts,
+            heartbeat,
+            task.last_panic.as_deref().unwrap_or("").dimmed()
+        );
+    }
+}
+
 /// Format duration in human-readable form
 fn format_duration(secs: u64) -> String {
     let days = secs / 86400;
@@ -0,0 +1,123 @@
+//! 管理员 Token 轮换 CLI 命令
+//!
+//! 与 `reset-password` 相同，有意直连 ConfigStore 而非走 IPC：新 token 明文只在
+//! 本进程生成并打印一次，不经过 IPC socket 传输；写入后再通过 IPC 通知 server
+//! 热加载配置。
+//!
+//! 轮换写入的配置变更历史以 [`ROTATE_ACTOR`] 记为操作方（审计记录，可用
+//! `GET /admin/v1/config/api.admin_token/history` 查询），并向 `alerts.webhook_url`
+//! 发送 `admin_token_rotated` 事件。
+
+use chrono::{Duration, SecondsFormat, Utc};
+use colored::Colorize;
+use sea_orm::DatabaseConnection;
+
+use super::config_management::notify_config_change;
+use crate::analytics::anomaly::post_webhook;
+use crate::cli::CliError;
+use crate::config::{get_runtime_config, init_runtime_config, keys};
+use crate::storage::ConfigStore;
+use crate::utils::generate_secure_token;
+use crate::utils::password::process_new_password;
+
+/// 新 token 的随机字节数（hex 编码后 48 个字符）
+const ROTATED_TOKEN_BYTES: usize = 24;
+
+/// 配置变更历史中记录的操作方
+pub const ROTATE_ACTOR: &str = "cli:token-rotate";
+
+/// 运行 `token rotate`
+pub async fn run_token_rotate(
+    db: DatabaseConnection,
+    revoke_now: bool,
+    grace_hours: Option<u64>,
+) -> Result<(), CliError> {
+    init_runtime_config(db.clone()).await?;
+    let rt = get_runtime_config();
+
+    let current_hash = rt.get_or(keys::API_ADMIN_TOKEN, "");
+    if current_hash.is_empty() {
+        return Err(CliError::CommandError(
+            "Admin token is not set, run `shortlinker reset-password` first".to_string(),
+        ));
+    }
+
+    let new_token = generate_secure_token(ROTATED_TOKEN_BYTES);
+    let new_hash = process_new_password(Some(&new_token))
+        .map_err(|e| CliError::CommandError(e.to_string()))?
+        .ok_or_else(|| CliError::CommandError("Generated token is empty".to_string()))?;
+
+    let grace_hours =
+        grace_hours.unwrap_or_else(|| rt.get_u64_or(keys::AUTH_TOKEN_GRACE_HOURS, 72));
+    let previous_expires_at = if revoke_now || grace_hours == 0 {
+        None
+    } else {
+        let expires_at = i64::try_from(grace_hours)
+            .ok()
+            .and_then(Duration::try_hours)
+            .and_then(|grace| Utc::now().checked_add_signed(grace))
+            .ok_or_else(|| CliError::ParseError(format!("Invalid grace period: {grace_hours}h")))?;
+        Some(expires_at.to_rfc3339_opts(SecondsFormat::Secs, true))
+    };
+
+    // 先写入宽限窗口再替换当前 token，避免中途失败导致旧 token 直接失效
+    let config_store = ConfigStore::new(db);
+    let (previous_hash, expires_at) = match &previous_expires_at {
+        Some(expires_at) => (current_hash.as_str(), expires_at.as_str()),
+        None => ("", ""),
+    };
+    let actor = Some(ROTATE_ACTOR);
+    config_store
+        .set_by(keys::API_ADMIN_TOKEN_PREVIOUS_EXPIRES_AT, expires_at, actor)
+        .await?;
+    config_store
+        .set_by(keys::API_ADMIN_TOKEN_PREVIOUS, previous_hash, actor)
+        .await?;
+    config_store
+        .set_by(keys::API_ADMIN_TOKEN, &new_hash, actor)
+        .await?;
+
+    println!("{} Admin token rotated", "✓".green().bold());
+    println!();
+    println!("  {}", new_token.bold());
+    println!();
+    println!(
+        "{} This token is shown only once. Distribute it to all callers now.",
+        "ℹ".blue().bold()
+    );
+    match previous_expires_at {
+        Some(expires_at) => println!(
+            "{} Previous token remains valid until {} (responses carry X-Token-Deprecation)",
+            "ℹ".blue().bold(),
+            expires_at
+        ),
+        None => println!("{} Previous token revoked immediately", "ℹ".blue().bold()),
+    }
+
+    notify_rotation_webhook(previous_expires_at).await;
+    notify_config_change(false).await;
+    Ok(())
+}
+
+/// 向 `alerts.webhook_url` 发送轮换事件；未配置时跳过，失败只提示不中断
+async fn notify_rotation_webhook(previous_valid_until: Option<String>) {
+    let url = get_runtime_config().get_or(keys::ALERTS_WEBHOOK_URL, "");
+    if url.is_empty() {
+        return;
+    }
+    let payload = serde_json::json!({
+        "event": "admin_token_rotated",
+        "rotated_at": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "previous_revoked": previous_valid_until.is_none(),
+        "previous_valid_until": previous_valid_until,
+    });
+    match tokio::task::spawn_blocking(move || post_webhook(&url, payload)).await {
+        Ok(Ok(())) => println!("{} Rotation webhook delivered", "✓".green().bold()),
+        Ok(Err(e)) => println!("{} Rotation webhook failed: {}", "⚠".yellow().bold(), e),
+        Err(e) => println!(
+            "{} Rotation webhook task failed: {}",
+            "⚠".yellow().bold(),
+            e
+        ),
+    }
+}
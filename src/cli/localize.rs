@@ -0,0 +1,197 @@
+//! 把 clap 命令树的帮助文案替换为目标语言
+//!
+//! 英文原文来自 derive 的文档注释；其他语言按 [`crate::i18n`] 中的
+//! `cli.commands.*` / `cli.args.*` 逐项替换，缺少翻译的条目保留英文。
+//! 非英文时同时替换帮助页面的标题（用法、命令、参数、选项）与内置的
+//! `-h` / `-V` 说明。clap 自身生成的错误信息无法本地化，保持英文。
+
+use clap::{Arg, ArgAction, Command};
+
+use crate::i18n::{self, Lang};
+
+/// 本地化整棵命令树（需在 `build` 之前调用）
+pub(super) fn localize(cmd: Command, lang: Lang) -> Command {
+    if lang == Lang::En {
+        return cmd;
+    }
+    let cmd = localize_command(cmd, "", lang);
+    match cmd.get_version() {
+        Some(_) => cmd.disable_version_flag(true).arg(
+            Arg::new("version")
+                .short('V')
+                .long("version")
+                .action(ArgAction::Version)
+                .help(i18n::t(lang, "cli.help.version_flag"))
+                .help_heading(i18n::t(lang, "cli.help.options")),
+        ),
+        None => cmd,
+    }
+}
+
+/// 命令说明的 key：顶层为 `cli.about`，子命令为 `cli.commands.<路径>.about`
+fn about_key(path: &str, suffix: &str) -> String {
+    if path.is_empty() {
+        format!("cli.{}", suffix)
+    } else {
+        format!("cli.commands.{}.{}", path, suffix)
+    }
+}
+
+fn arg_key(path: &str, id: &str) -> String {
+    if path.is_empty() {
+        format!("cli.args.{}", id)
+    } else {
+        format!("cli.args.{}.{}", path, id)
+    }
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn localize_command(mut cmd: Command, path: &str, lang: Lang) -> Command {
+    if let Some(about) = i18n::lookup(lang, &about_key(path, "about")) {
+        cmd = cmd.about(about);
+    }
+    if cmd.get_long_about().is_some()
+        && let Some(long_about) = i18n::lookup(lang, &about_key(path, "long_about"))
+    {
+        cmd = cmd.long_about(long_about);
+    }
+
+    let args: Vec<(String, bool)> = cmd
+        .get_arguments()
+        .map(|arg| (arg.get_id().to_string(), arg.is_positional()))
+        .collect();
+    for (id, positional) in args {
+        let help = i18n::lookup(lang, &arg_key(path, &id));
+        let heading = i18n::t(
+            lang,
+            if positional {
+                "cli.help.arguments"
+            } else {
+                "cli.help.options"
+            },
+        );
+        cmd = cmd.mut_arg(id, |arg| {
+            let arg = match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            };
+            if arg.get_help_heading().is_some() {
+                arg
+            } else {
+                arg.help_heading(heading)
+            }
+        });
+    }
+
+    let template = format!(
+        "{{before-help}}{{about-with-newline}}\n{} {{usage}}\n\n{{all-args}}{{after-help}}",
+        i18n::t(lang, "cli.help.usage")
+    );
+    cmd = cmd
+        .help_template(template)
+        .subcommand_help_heading(i18n::t(lang, "cli.help.commands"))
+        .subcommand_value_name(i18n::t(lang, "cli.help.command_value"))
+        .disable_help_flag(true)
+        .arg(
+            Arg::new("help")
+                .short('h')
+                .long("help")
+                .action(ArgAction::Help)
+                .help(i18n::t(lang, "cli.help.help_flag"))
+                .help_heading(i18n::t(lang, "cli.help.options")),
+        );
+
+    cmd.mut_subcommands(|sub| {
+        let path = child_path(path, sub.get_name());
+        localize_command(sub, &path, lang)
+    })
+}
+
+/// 命令树中所有可翻译文案的 key（用于覆盖率测试）
+#[cfg(test)]
+fn message_keys(cmd: &Command, path: &str, keys: &mut Vec<String>) {
+    if cmd.get_about().is_some() {
+        keys.push(about_key(path, "about"));
+    }
+    if cmd.get_long_about().is_some() {
+        keys.push(about_key(path, "long_about"));
+    }
+    for arg in cmd.get_arguments() {
+        if arg.get_help().is_some() {
+            keys.push(arg_key(path, arg.get_id().as_str()));
+        }
+    }
+    for sub in cmd.get_subcommands() {
+        message_keys(sub, &child_path(path, sub.get_name()), keys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+    use std::collections::HashSet;
+
+    fn command_keys() -> Vec<String> {
+        let mut keys = Vec::new();
+        message_keys(&Cli::command(), "", &mut keys);
+        keys
+    }
+
+    #[test]
+    fn test_every_help_text_is_translated() {
+        let missing: Vec<_> = command_keys()
+            .into_iter()
+            .filter(|key| i18n::lookup(Lang::Zh, key).is_none())
+            .collect();
+        assert!(missing.is_empty(), "missing zh help texts: {:?}", missing);
+    }
+
+    #[test]
+    fn test_no_stale_translations() {
+        // zh 中的 cli.commands / cli.args / cli.about 必须对应命令树中仍存在的条目
+        let live: HashSet<String> = command_keys().into_iter().collect();
+        let stale: Vec<_> = i18n::keys(Lang::Zh)
+            .filter(|key| {
+                *key == "cli.about"
+                    || key.starts_with("cli.commands.")
+                    || key.starts_with("cli.args.")
+            })
+            .filter(|key| !live.contains(*key))
+            .collect();
+        assert!(stale.is_empty(), "stale zh help texts: {:?}", stale);
+    }
+
+    #[test]
+    fn test_english_is_untouched() {
+        let mut cmd = localize(Cli::command(), Lang::En);
+        let help = cmd.render_help().to_string();
+        assert!(help.contains("A high-performance URL shortener service"));
+        assert!(help.contains("Usage:"));
+    }
+
+    #[test]
+    fn test_chinese_help() {
+        let mut cmd = localize(Cli::command(), Lang::Zh);
+        let help = cmd.render_help().to_string();
+        assert!(help.contains("高性能短链接服务"), "{}", help);
+        assert!(help.contains("用法："), "{}", help);
+        assert!(help.contains("添加短链接"), "{}", help);
+        assert!(help.contains("显示帮助"), "{}", help);
+
+        let mut cmd = localize(Cli::command(), Lang::Zh);
+        cmd.build();
+        let generate = cmd.find_subcommand_mut("generate").unwrap();
+        let help = generate.render_long_help().to_string();
+        assert!(help.contains("示例："), "{}", help);
+        assert!(help.contains("目标网址模板"), "{}", help);
+    }
+}
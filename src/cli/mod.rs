@@ -2,22 +2,42 @@
 
 #[cfg(feature = "cli")]
 pub mod commands;
+mod localize;
+mod mode;
+
+pub use mode::{ArgsError, RunMode};
 
 use std::fmt;
 #[cfg(feature = "cli")]
 use std::sync::Arc;
 
+use crate::analytics::PeriodGranularity;
+use crate::analytics::export::{CountsEncoding, ExportFormat, ExportTable};
+#[cfg(feature = "cli")]
+use crate::client::{BatchExtendArgs, ConfigClient, LinkClient, ServiceContext};
 #[cfg(feature = "cli")]
-use crate::client::{ConfigClient, LinkClient, ServiceContext};
+use crate::config::init_runtime_config;
+use crate::i18n::{self, Lang};
 #[cfg(feature = "cli")]
 use crate::metrics::NoopMetrics;
+use crate::storage::AnalyticsLevel;
 #[cfg(feature = "cli")]
 use crate::storage::StorageFactory;
+#[cfg(feature = "cli")]
+use crate::storage::backend::{connect, infer_backend_from_url};
+#[cfg(feature = "cli")]
+use crate::utils::csv_dialect::parse_delimiter;
+#[cfg(feature = "cli")]
+use crate::utils::csv_transform::ImportTransform;
 use clap::{Parser, Subcommand};
 #[cfg(feature = "cli")]
 use commands::{
-    add_link, config_management, export_links, import_links, list_links, remove_link,
-    run_reset_password, server_status, update_link,
+    AnalyticsAmendArgs, AnalyticsExportArgs, AnalyticsRebuildArgs, BenchOptions, DbMaintainArgs,
+    DeployOptions, GenerateArgs, ImportArgs, MigrateDownArgs, ResolveArgs, add_link, archive_links,
+    audit_midnight_utc_expiry, audit_target_credentials, config_management, export_links,
+    extend_links, generate_links, import_links, list_links, parse_bench_duration, remove_link,
+    resolve_links, run_bench, run_generate_deploy, run_reset_password, run_token_rotate,
+    sample_links, server_status, unarchive_link, update_link,
 };
 
 /// Shortlinker command-line arguments.
@@ -26,10 +46,26 @@ use commands::{
 #[command(version)]
 #[command(about = "A high-performance URL shortener service", long_about = None)]
 pub struct Cli {
+    /// Config file to load instead of ./config.toml.
+    #[arg(long, short = 'c', global = true, value_name = "FILE")]
+    pub config: Option<String>,
+
     /// Override IPC socket path (Unix) or named pipe path (Windows).
     #[arg(long, short = 's', global = true)]
     pub socket: Option<String>,
 
+    /// Language for help and messages (en, zh). Defaults to LC_ALL / LC_MESSAGES / LANG.
+    #[arg(long, global = true, value_name = "LANG", value_parser = parse_lang)]
+    pub lang: Option<Lang>,
+
+    /// How commands reach the data: ipc (running server only), direct (database only) or auto.
+    #[arg(long, global = true, value_enum, default_value_t = IpcVia::Auto, value_name = "MODE")]
+    pub via: IpcVia,
+
+    /// Print which path (IPC or direct database) each command actually used.
+    #[arg(long, short = 'v', global = true)]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -40,6 +76,8 @@ pub enum Commands {
     /// Add a short link.
     ///
     /// Usage: add [SHORT_CODE] <TARGET_URL>
+    ///
+    /// Example: add docs https://docs.example.com --expire 7d
     Add {
         /// Positional arguments: `[short_code] <target_url>`.
         #[arg(required = true, num_args = 1..=2)]
@@ -49,13 +87,25 @@ pub enum Commands {
         #[arg(long)]
         force: bool,
 
-        /// Expiration time (RFC3339 or relative, such as `1d` or `2h`).
+        /// Expiration time: RFC3339, `2024-12-31 18:00` or `2024-12-31` (in `links.expiry_timezone`, a date means end of day), relative such as `1d`, `now` to expire immediately or `never`.
         #[arg(long)]
         expire: Option<String>,
 
         /// Password protection.
         #[arg(long)]
         password: Option<String>,
+
+        /// Click analytics level: inherit, none, count_only, aggregate or full.
+        #[arg(long, value_name = "LEVEL")]
+        analytics_level: Option<AnalyticsLevel>,
+
+        /// Custom metadata as a JSON object, such as `{"ticket":"JIRA-123"}`.
+        #[arg(long, value_name = "JSON")]
+        extras: Option<String>,
+
+        /// Reuse a code that is still in its deletion cooldown.
+        #[arg(long)]
+        override_cooldown: bool,
     },
 
     /// Remove a short link.
@@ -72,32 +122,217 @@ pub enum Commands {
         /// New target URL.
         target_url: String,
 
-        /// New expiration time.
+        /// New expiration time (same formats as `add --expire`; kept unchanged when omitted, `never` removes it).
         #[arg(long)]
         expire: Option<String>,
 
         /// New password.
         #[arg(long)]
         password: Option<String>,
+
+        /// New click analytics level (kept unchanged when omitted).
+        #[arg(long, value_name = "LEVEL")]
+        analytics_level: Option<AnalyticsLevel>,
+
+        /// New custom metadata as a JSON object (kept unchanged when omitted, `{}` clears it).
+        #[arg(long, value_name = "JSON")]
+        extras: Option<String>,
+    },
+
+    /// Batch extend link expiration times.
+    Extend {
+        /// Short codes to extend. When omitted, `--search` selects the links.
+        codes: Vec<String>,
+
+        /// Select links whose code or target matches this keyword.
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Offset added to the current expiration (e.g. `7d`, `1d12h`).
+        #[arg(long, required_unless_present = "to", conflicts_with = "to")]
+        by: Option<String>,
+
+        /// Set expiration to this time instead (same formats as `add --expire`).
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Also process links that never expire (`--by` counts from now).
+        #[arg(long)]
+        include_permanent: bool,
+
+        /// Count already-expired links from now instead of their old expiration.
+        #[arg(long)]
+        revive_from_now: bool,
+
+        /// Show the result without writing.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Generate links from a template, one per combination of `--var` values.
+    ///
+    /// Example: generate --template 'https://example.com/?utm_source={source}&utm_content={content}'
+    /// --var source=tv,radio --var content=a,b --code-template 'q4-{source}-{content}'
+    Generate {
+        /// Target URL template; `{name}` is replaced by each value of variable `name`.
+        #[arg(long)]
+        template: String,
+
+        /// Template variable as `name=value1,value2,...` (repeatable).
+        #[arg(long = "var", value_name = "NAME=VALUES", required = true)]
+        vars: Vec<String>,
+
+        /// Short code template (e.g. `q4-{source}`); random codes when omitted.
+        #[arg(long)]
+        code_template: Option<String>,
+
+        /// Overwrite codes that already exist.
+        #[arg(long)]
+        force: bool,
+
+        /// Expiration time for every generated link (same formats as `add --expire`).
+        #[arg(long)]
+        expire: Option<String>,
+
+        /// Password protection for every generated link.
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Create without asking for confirmation.
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Only print the code → URL mapping, do not create.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Archive short links (redirects return 410, click stats are kept).
+    Archive {
+        /// Short codes to archive. When omitted, `--search` selects the links.
+        codes: Vec<String>,
+
+        /// Select links whose code or target matches this keyword.
+        #[arg(long)]
+        search: Option<String>,
+    },
+
+    /// Restore an archived short link.
+    Unarchive {
+        /// Short code to restore.
+        short_code: String,
+    },
+
+    /// Print a uniform random sample of short links (for spot checks).
+    Sample {
+        /// Sample size (max 1000).
+        #[arg(short = 'n', default_value_t = 100)]
+        n: usize,
+
+        /// Random seed. Reusing the seed printed by a previous run reproduces its sample.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Only sample links whose code or target matches this keyword.
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = SampleOutput::Table)]
+        output: SampleOutput,
     },
 
     /// List all short links.
     List,
 
+    /// Print the target URL of short codes, one line each (for scripts).
+    ///
+    /// Exit code 3 if a code does not exist, 6 if a link has expired.
+    /// Diagnostics go to stderr, stdout only carries results.
+    ///
+    /// Example: resolve docs promo --json
+    Resolve {
+        /// Short codes to resolve, printed in the given order.
+        #[arg(required_unless_present = "stdin", conflicts_with = "stdin")]
+        codes: Vec<String>,
+
+        /// Read short codes from stdin, one per line.
+        #[arg(long)]
+        stdin: bool,
+
+        /// Print every field as JSON, one object per line.
+        #[arg(long)]
+        json: bool,
+
+        /// Still print the target of expired links.
+        #[arg(long)]
+        allow_expired: bool,
+
+        /// Line printed on stdout for codes that cannot be resolved (empty by default).
+        #[arg(long, value_name = "TEXT", default_value = "")]
+        error_marker: String,
+    },
+
+    /// Audit link expiration times without changing them.
+    ///
+    /// `--suspect-midnight-utc` lists links that expire at exactly 00:00:00 UTC, which is
+    /// what a picked date often became before `links.expiry_timezone` existed.
+    /// Fix a link with `extend <CODE> --to <DATE>`.
+    ///
+    /// Example: audit-expiry --suspect-midnight-utc --json
+    AuditExpiry {
+        /// List links whose expiration is exactly midnight UTC.
+        #[arg(long, required = true)]
+        suspect_midnight_utc: bool,
+
+        /// Print one JSON object per line.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Audit link target URLs without changing them.
+    ///
+    /// `--credentials` lists links whose target carries credentials (`user:pass@` or a
+    /// parameter from `links.credential_params`), with the URL those credentials removed.
+    /// Fix a link with `update <CODE> <URL>`.
+    ///
+    /// Example: audit-targets --credentials --json
+    AuditTargets {
+        /// List links whose target URL contains credentials.
+        #[arg(long, required = true)]
+        credentials: bool,
+
+        /// Print one JSON object per line.
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Export links to a CSV file.
     Export {
-        /// Output path. Defaults to a timestamped filename.
+        /// Output path or s3://bucket/path (requires the `s3` feature). Defaults to a timestamped filename.
         file_path: Option<String>,
     },
 
     /// Import links from a CSV file.
     Import {
-        /// Input file path.
+        /// Input file path or s3://bucket/path (requires the `s3` feature).
         file_path: String,
 
         /// Force overwrite existing links.
         #[arg(long)]
         force: bool,
+
+        /// Field delimiter: ',', ';' or 'tab' (sniffed from the header line by default).
+        #[arg(long)]
+        delimiter: Option<String>,
+
+        /// TOML file with column mappings and value transforms applied before validation.
+        #[arg(long, value_name = "FILE")]
+        transform: Option<String>,
+
+        /// Print the first 10 rows after transformation and validation results, do not import.
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Show server status through IPC.
@@ -114,11 +349,391 @@ pub enum Commands {
         stdin: bool,
     },
 
+    /// Manage the admin token.
+    Token {
+        #[command(subcommand)]
+        action: TokenCommands,
+    },
+
+    /// Benchmark redirect traffic against a running server.
+    ///
+    /// Samples real short codes from the local database and mixes in missing codes.
+    ///
+    /// Example: bench --url http://127.0.0.1:8080 --duration 1m --concurrency 100
+    Bench {
+        /// Base URL of the server to benchmark.
+        #[arg(long, default_value = "http://127.0.0.1:8080")]
+        url: String,
+
+        /// Benchmark duration (`30s`, `5m`, `1h`).
+        #[arg(long, default_value = "30s")]
+        duration: String,
+
+        /// Number of concurrent workers.
+        #[arg(long, default_value_t = 50)]
+        concurrency: usize,
+
+        /// Short code access distribution.
+        #[arg(long, value_enum, default_value = "zipf")]
+        distribution: BenchDistribution,
+
+        /// Ratio of requests targeting existing codes (0.0 - 1.0).
+        #[arg(long, default_value_t = 0.95)]
+        hit_ratio: f64,
+
+        /// Ask the server to skip click tracking (requires `server.allow_bench_header`).
+        #[arg(long)]
+        no_analytics_impact: bool,
+
+        /// Output the report as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Manage configuration.
     Config {
         #[command(subcommand)]
         action: ConfigCommands,
     },
+
+    /// Generate deployment manifests for this version.
+    ///
+    /// Environment variables come from the built-in startup config schema, so the
+    /// manifests match this binary. Existing files are kept unless --force is given.
+    ///
+    /// Example: generate-deploy --target docker-compose --database postgres -o ./deploy/
+    GenerateDeploy {
+        /// Deployment target.
+        #[arg(long, value_enum)]
+        target: DeployTarget,
+
+        /// Database the manifests are set up for.
+        #[arg(long, value_enum, default_value = "sqlite")]
+        database: DeployDatabase,
+
+        /// Output directory.
+        #[arg(long, short = 'o', default_value = "./deploy")]
+        output: String,
+
+        /// Container image without tag.
+        #[arg(long, default_value = DEFAULT_DEPLOY_IMAGE)]
+        image: String,
+
+        /// Image tag. Defaults to the tag of this version.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// HTTP port the service listens on and is published at.
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// PostgreSQL password written into the manifests (use URL-safe characters).
+        #[arg(long, default_value = "change-me-db-password")]
+        db_password: String,
+
+        /// Bearer token used by the health checks; set the same value as `api.health_token`.
+        #[arg(long, default_value = "change-me-health-token")]
+        health_token: String,
+
+        /// Overwrite existing files.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Analytics data tools.
+    Analytics {
+        #[command(subcommand)]
+        action: AnalyticsCommands,
+    },
+
+    /// Inspect or roll back database migrations.
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateCommands,
+    },
+
+    /// Database maintenance tools.
+    Db {
+        #[command(subcommand)]
+        action: DbCommands,
+    },
+}
+
+/// Short code access distribution used by `bench`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BenchDistribution {
+    /// Long-tail distribution: a few hot codes receive most traffic.
+    Zipf,
+    /// Every sampled code is equally likely.
+    Uniform,
+}
+
+/// Transport selected by the global `--via` option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum IpcVia {
+    /// IPC when the server answers, direct database access otherwise.
+    #[default]
+    Auto,
+    /// Always go through the running server; fail if it is unreachable.
+    Ipc,
+    /// Always access the database directly.
+    Direct,
+}
+
+impl From<IpcVia> for crate::client::Transport {
+    fn from(via: IpcVia) -> Self {
+        match via {
+            IpcVia::Auto => Self::Auto,
+            IpcVia::Ipc => Self::Ipc,
+            IpcVia::Direct => Self::Direct,
+        }
+    }
+}
+
+/// Image used by `generate-deploy` when `--image` is omitted.
+pub const DEFAULT_DEPLOY_IMAGE: &str = "e1saps/shortlinker";
+
+/// Deployment target used by `generate-deploy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeployTarget {
+    /// `docker-compose.yml` plus a `.env` with the parameters.
+    DockerCompose,
+    /// ConfigMap, Deployment and Service skeleton in `shortlinker.yaml`.
+    K8s,
+}
+
+/// Database used by `generate-deploy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeployDatabase {
+    /// PostgreSQL (a `postgres` service for Compose, an existing server for Kubernetes).
+    Postgres,
+    /// SQLite on a persistent volume (single replica).
+    Sqlite,
+}
+
+/// Output format used by `sample`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SampleOutput {
+    /// Colored, human-readable list.
+    Table,
+    /// CSV in the export format, written to stdout.
+    Csv,
+}
+
+/// Analytics table used by `analytics export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnalyticsTable {
+    /// Raw click log rows (`click_logs`).
+    #[value(name = "click_log")]
+    ClickLog,
+    /// Daily rollups (`click_stats_daily`).
+    Daily,
+    /// Hourly rollups (`click_stats_hourly`).
+    Hourly,
+}
+
+/// Rollup granularity rebuilt by `analytics rebuild-rollups`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnalyticsGranularity {
+    /// Weekly rollups (`click_stats_weekly`).
+    Week,
+    /// Monthly rollups (`click_stats_monthly`).
+    Month,
+}
+
+/// Output format used by `analytics export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnalyticsExportFormat {
+    Csv,
+    /// Requires the `parquet` feature.
+    Parquet,
+}
+
+/// How rollup JSON count columns are exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnalyticsCounts {
+    /// Keep the stored JSON string.
+    Json,
+    /// Expand to `list<struct<key, count>>` (Parquet only).
+    Nested,
+}
+
+/// Analytics commands.
+#[derive(Subcommand)]
+pub enum AnalyticsCommands {
+    /// Export an analytics table to CSV or Parquet.
+    Export {
+        /// Table to export.
+        #[arg(long, value_enum)]
+        table: AnalyticsTable,
+
+        /// Range start (RFC3339 or YYYY-MM-DD). Defaults to 30 days ago.
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Range end (RFC3339 or YYYY-MM-DD, inclusive). Defaults to now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value = "csv")]
+        format: AnalyticsExportFormat,
+
+        /// Encoding of rollup count columns.
+        #[arg(long, value_enum, default_value = "json")]
+        counts: AnalyticsCounts,
+
+        /// Output path or s3://bucket/path (requires the `s3` feature). CSV is written to stdout when omitted.
+        #[arg(long, short = 'o')]
+        output: Option<String>,
+    },
+
+    /// Remove polluted click logs of one link and correct its rollups and click count.
+    ///
+    /// Example: analytics amend --code promo --from 2026-10-01 --to 2026-10-07
+    /// --remove-ip-cidr 1.2.3.0/24 --dry-run
+    Amend {
+        /// Short code to amend.
+        #[arg(long)]
+        code: String,
+
+        /// Range start (RFC3339 or YYYY-MM-DD).
+        #[arg(long)]
+        from: String,
+
+        /// Range end (RFC3339 or YYYY-MM-DD, inclusive).
+        #[arg(long)]
+        to: String,
+
+        /// Remove click logs whose client IP is in this CIDR (repeatable).
+        #[arg(long = "remove-ip-cidr", value_name = "CIDR", required = true)]
+        remove_ip_cidrs: Vec<String>,
+
+        /// Only show how many rows would be affected.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Rebuild weekly or monthly rollups from daily rollups.
+    ///
+    /// Run with `--granularity week` after changing `analytics.week_starts_on`.
+    ///
+    /// Example: analytics rebuild-rollups --granularity week --from 2026-01-01
+    RebuildRollups {
+        /// Rollup granularity to rebuild.
+        #[arg(long, value_enum)]
+        granularity: AnalyticsGranularity,
+
+        /// First day to rebuild (YYYY-MM-DD). Defaults to the oldest retained daily rollup.
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Last day to rebuild (YYYY-MM-DD, inclusive). Defaults to today.
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
+impl From<AnalyticsTable> for ExportTable {
+    fn from(table: AnalyticsTable) -> Self {
+        match table {
+            AnalyticsTable::ClickLog => ExportTable::ClickLog,
+            AnalyticsTable::Daily => ExportTable::Daily,
+            AnalyticsTable::Hourly => ExportTable::Hourly,
+        }
+    }
+}
+
+impl From<AnalyticsGranularity> for PeriodGranularity {
+    fn from(granularity: AnalyticsGranularity) -> Self {
+        match granularity {
+            AnalyticsGranularity::Week => PeriodGranularity::Week,
+            AnalyticsGranularity::Month => PeriodGranularity::Month,
+        }
+    }
+}
+
+impl From<AnalyticsExportFormat> for ExportFormat {
+    fn from(format: AnalyticsExportFormat) -> Self {
+        match format {
+            AnalyticsExportFormat::Csv => ExportFormat::Csv,
+            AnalyticsExportFormat::Parquet => ExportFormat::Parquet,
+        }
+    }
+}
+
+impl From<AnalyticsCounts> for CountsEncoding {
+    fn from(counts: AnalyticsCounts) -> Self {
+        match counts {
+            AnalyticsCounts::Json => CountsEncoding::Json,
+            AnalyticsCounts::Nested => CountsEncoding::Nested,
+        }
+    }
+}
+
+/// Database migration commands.
+#[derive(Subcommand)]
+pub enum MigrateCommands {
+    /// Show applied and pending migrations and whether this binary can start.
+    Status,
+
+    /// Roll back every migration applied after `--to`.
+    ///
+    /// Run it with the binary that applied those migrations, then start the older binary.
+    ///
+    /// Example: migrate down --to m20261016_000006 --dry-run
+    Down {
+        /// Migration to keep (full name or unique prefix).
+        #[arg(long, value_name = "VERSION")]
+        to: String,
+
+        /// Only show the plan and the data that would be dropped.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Roll back without asking for confirmation.
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// SQLite backup path or s3://bucket/path. Defaults to `<database file>.<timestamp>.bak`.
+        #[arg(long, value_name = "FILE")]
+        backup: Option<String>,
+    },
+}
+
+/// Database maintenance commands.
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Reclaim space and refresh planner statistics.
+    ///
+    /// SQLite: PRAGMA optimize (VACUUM with --full). PostgreSQL: VACUUM ANALYZE
+    /// (VACUUM FULL with --full). MySQL: ANALYZE TABLE (OPTIMIZE TABLE with --full).
+    ///
+    /// Example: db maintain --full --yes
+    Maintain {
+        /// Rewrite tables to return free space to the OS (locks tables; SQLite needs the server stopped).
+        #[arg(long)]
+        full: bool,
+
+        /// Run --full without asking for confirmation.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+/// Admin token management commands.
+#[derive(Subcommand)]
+pub enum TokenCommands {
+    /// Generate a new admin token; the previous one stays valid for a grace period.
+    Rotate {
+        /// Revoke the previous token immediately instead of keeping a grace period.
+        #[arg(long)]
+        revoke_now: bool,
+
+        /// Grace period in hours. Defaults to `auth.token_grace_hours`.
+        #[arg(long, conflicts_with = "revoke_now")]
+        grace_hours: Option<u64>,
+    },
 }
 
 /// Configuration management commands.
@@ -187,6 +802,17 @@ pub enum ConfigCommands {
     },
 }
 
+/// `--lang` 的取值解析
+fn parse_lang(value: &str) -> Result<Lang, String> {
+    Lang::parse(value).ok_or_else(|| {
+        i18n::t_with(
+            Lang::from_env(),
+            "cli.errors.unsupportedLang",
+            &[("lang", value)],
+        )
+    })
+}
+
 impl Commands {
     /// Parses add-command positional arguments into a short code and target URL.
     pub fn parse_add_args(args: &[String]) -> (Option<String>, String) {
@@ -203,35 +829,52 @@ pub enum CliError {
     StorageError(String),
     ParseError(String),
     CommandError(String),
+    /// Short code does not exist (exit code 3).
+    NotFound(String),
+    /// Link has expired (exit code 6).
+    Expired(String),
 }
 
 impl CliError {
-    /// Format as simple output
-    pub fn format_simple(&self) -> String {
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> i32 {
         match self {
-            CliError::StorageError(msg) => format!("Storage error: {}", msg),
-            CliError::ParseError(msg) => format!("Parse error: {}", msg),
-            CliError::CommandError(msg) => format!("Command error: {}", msg),
+            CliError::NotFound(_) => 3,
+            CliError::Expired(_) => 6,
+            _ => 1,
         }
     }
 
+    /// 错误前缀的文案 key 与错误详情
+    fn parts(&self) -> (&'static str, &str) {
+        match self {
+            CliError::StorageError(msg) => ("cli.errors.storage", msg),
+            CliError::ParseError(msg) => ("cli.errors.parse", msg),
+            CliError::CommandError(msg) => ("cli.errors.command", msg),
+            CliError::NotFound(msg) => ("cli.errors.notFound", msg),
+            CliError::Expired(msg) => ("cli.errors.expired", msg),
+        }
+    }
+
+    /// Format as simple output
+    pub fn format_simple(&self) -> String {
+        let (key, msg) = self.parts();
+        format!("{}: {}", i18n::t(i18n::current(), key), msg)
+    }
+
     /// Format as colored output
     #[cfg(feature = "cli")]
     pub fn format_colored(&self) -> String {
         #[cfg(feature = "server")]
         {
             use colored::Colorize;
-            match self {
-                CliError::StorageError(msg) => {
-                    format!("{} {}", "Storage error:".red().bold(), msg.white())
-                }
-                CliError::ParseError(msg) => {
-                    format!("{} {}", "Parse error:".yellow().bold(), msg.white())
-                }
-                CliError::CommandError(msg) => {
-                    format!("{} {}", "Command error:".red().bold(), msg.white())
-                }
-            }
+            let (key, msg) = self.parts();
+            let prefix = format!("{}:", i18n::t(i18n::current(), key));
+            let prefix = match self {
+                CliError::StorageError(_) | CliError::CommandError(_) => prefix.red().bold(),
+                _ => prefix.yellow().bold(),
+            };
+            format!("{} {}", prefix, msg.white())
         }
         #[cfg(not(feature = "server"))]
         self.format_simple()
@@ -248,7 +891,7 @@ impl std::error::Error for CliError {}
 
 impl From<crate::errors::ShortlinkerError> for CliError {
     fn from(err: crate::errors::ShortlinkerError) -> Self {
-        CliError::StorageError(err.to_string())
+        CliError::StorageError(err.format_localized(i18n::current()))
     }
 }
 
@@ -260,6 +903,55 @@ pub async fn run_cli_command(cmd: Commands) -> Result<(), CliError> {
         return server_status().await;
     }
 
+    // Handle bench command separately (talks to the server over HTTP)
+    if let Commands::Bench {
+        url,
+        duration,
+        concurrency,
+        distribution,
+        hit_ratio,
+        no_analytics_impact,
+        json,
+    } = cmd
+    {
+        let duration = parse_bench_duration(&duration).map_err(CliError::ParseError)?;
+        return run_bench(BenchOptions {
+            url,
+            duration,
+            concurrency,
+            distribution,
+            hit_ratio,
+            no_analytics_impact,
+            json,
+        })
+        .await;
+    }
+
+    // Handle generate-deploy command separately (only writes local files)
+    if let Commands::GenerateDeploy {
+        target,
+        database,
+        output,
+        image,
+        tag,
+        port,
+        db_password,
+        health_token,
+        force,
+    } = cmd
+    {
+        let options = DeployOptions {
+            target,
+            database,
+            image,
+            tag: tag.unwrap_or_else(|| format!("v{}", env!("CARGO_PKG_VERSION"))),
+            port,
+            db_password,
+            health_token,
+        };
+        return run_generate_deploy(options, &output, force);
+    }
+
     // Handle reset-password command separately (needs direct DB access)
     if let Commands::ResetPassword { password, stdin } = cmd {
         let storage = StorageFactory::create(NoopMetrics::arc())
@@ -269,6 +961,112 @@ pub async fn run_cli_command(cmd: Commands) -> Result<(), CliError> {
         return Ok(());
     }
 
+    // Handle token command separately (needs direct DB access, like reset-password)
+    if let Commands::Token { action } = cmd {
+        let storage = StorageFactory::create(NoopMetrics::arc())
+            .await
+            .map_err(|e| CliError::StorageError(e.to_string()))?;
+        return match action {
+            TokenCommands::Rotate {
+                revoke_now,
+                grace_hours,
+            } => run_token_rotate(storage.get_db().clone(), revoke_now, grace_hours).await,
+        };
+    }
+
+    // Handle analytics command separately (streams straight from the database)
+    if let Commands::Analytics { action } = cmd {
+        let storage = StorageFactory::create(NoopMetrics::arc())
+            .await
+            .map_err(|e| CliError::StorageError(e.to_string()))?;
+        // 保留期与周起始日等统计配置保存在数据库中
+        init_runtime_config(storage.get_db().clone()).await?;
+        return match action {
+            AnalyticsCommands::Export {
+                table,
+                from,
+                to,
+                format,
+                counts,
+                output,
+            } => {
+                let args = AnalyticsExportArgs {
+                    table: table.into(),
+                    from,
+                    to,
+                    format: format.into(),
+                    counts: counts.into(),
+                    output,
+                };
+                commands::run_analytics_export(&storage, args).await
+            }
+            AnalyticsCommands::Amend {
+                code,
+                from,
+                to,
+                remove_ip_cidrs,
+                dry_run,
+            } => {
+                let args = AnalyticsAmendArgs {
+                    code,
+                    from,
+                    to,
+                    remove_ip_cidrs,
+                    dry_run,
+                };
+                commands::run_analytics_amend(storage, args).await
+            }
+            AnalyticsCommands::RebuildRollups {
+                granularity,
+                from,
+                to,
+            } => {
+                let args = AnalyticsRebuildArgs {
+                    granularity: granularity.into(),
+                    from,
+                    to,
+                };
+                commands::run_analytics_rebuild(storage, args).await
+            }
+        };
+    }
+
+    // Handle migrate command separately (connects without running migrations)
+    if let Commands::Migrate { action } = cmd {
+        let database_url = crate::config::get_config().database.database_url.clone();
+        let backend = infer_backend_from_url(&database_url)?;
+        let db = connect(&database_url, &backend, NoopMetrics::arc()).await?;
+        return match action {
+            MigrateCommands::Status => commands::run_migrate_status(&db).await,
+            MigrateCommands::Down {
+                to,
+                dry_run,
+                yes,
+                backup,
+            } => {
+                let args = MigrateDownArgs {
+                    to,
+                    dry_run,
+                    yes,
+                    backup,
+                };
+                commands::run_migrate_down(&db, &backend, &database_url, args).await
+            }
+        };
+    }
+
+    // Handle db command separately (connects directly, like migrate)
+    if let Commands::Db { action } = cmd {
+        let database_url = crate::config::get_config().database.database_url.clone();
+        let backend = infer_backend_from_url(&database_url)?;
+        let db = connect(&database_url, &backend, NoopMetrics::arc()).await?;
+        return match action {
+            DbCommands::Maintain { full, yes } => {
+                commands::run_db_maintain(&db, DbMaintainArgs { full, yes }).await
+            }
+        };
+    }
+
     // Create shared context for all other commands
     let ctx = Arc::new(ServiceContext::new());
     let link_client = LinkClient::new(ctx.clone());
@@ -290,6 +1088,9 @@ pub async fn run_cli_command(cmd: Commands) -> Result<(), CliError> {
             force,
             expire,
             password,
+            analytics_level,
+            extras,
+            override_cooldown,
         } => {
             let (short_code, target_url) = Commands::parse_add_args(&args);
             add_link(
@@ -299,6 +1100,9 @@ pub async fn run_cli_command(cmd: Commands) -> Result<(), CliError> {
                 force,
                 expire,
                 password,
+                analytics_level,
+                extras,
+                override_cooldown,
             )
             .await
         }
@@ -310,18 +1114,161 @@ pub async fn run_cli_command(cmd: Commands) -> Result<(), CliError> {
             target_url,
             expire,
             password,
-        } => update_link(&link_client, short_code, target_url, expire, password).await,
+            analytics_level,
+            extras,
+        } => {
+            update_link(
+                &link_client,
+                short_code,
+                target_url,
+                expire,
+                password,
+                analytics_level,
+                extras,
+            )
+            .await
+        }
+
+        Commands::Extend {
+            codes,
+            search,
+            by,
+            to,
+            include_permanent,
+            revive_from_now,
+            dry_run,
+        } => {
+            extend_links(
+                &link_client,
+                BatchExtendArgs {
+                    codes,
+                    search,
+                    extend_by: by,
+                    new_expires_at: to,
+                    include_permanent,
+                    revive_from_now,
+                    dry_run,
+                },
+            )
+            .await
+        }
+
+        Commands::Generate {
+            template,
+            vars,
+            code_template,
+            force,
+            expire,
+            password,
+            yes,
+            dry_run,
+        } => {
+            generate_links(
+                &link_client,
+                GenerateArgs {
+                    template,
+                    code_template,
+                    vars,
+                    force,
+                    expire,
+                    password,
+                    yes,
+                    dry_run,
+                },
+            )
+            .await
+        }
+
+        Commands::Archive { codes, search } => archive_links(&link_client, codes, search).await,
+
+        Commands::Unarchive { short_code } => unarchive_link(&link_client, short_code).await,
+
+        Commands::Sample {
+            n,
+            seed,
+            search,
+            output,
+        } => sample_links(&link_client, n, seed, search, output).await,
 
         Commands::List => list_links(&link_client).await,
 
+        Commands::Resolve {
+            codes,
+            stdin,
+            json,
+            allow_expired,
+            error_marker,
+        } => {
+            resolve_links(
+                &link_client,
+                ResolveArgs {
+                    codes,
+                    stdin,
+                    json,
+                    allow_expired,
+                    error_marker,
+                },
+            )
+            .await
+        }
+
+        Commands::AuditExpiry {
+            suspect_midnight_utc: _,
+            json,
+        } => audit_midnight_utc_expiry(&link_client, json).await,
+
+        Commands::AuditTargets {
+            credentials: _,
+            json,
+        } => audit_target_credentials(&link_client, json).await,
+
         Commands::Export { file_path } => export_links(&link_client, file_path).await,
 
-        Commands::Import { file_path, force } => import_links(&link_client, file_path, force).await,
+        Commands::Import {
+            file_path,
+            force,
+            delimiter,
+            transform,
+            dry_run,
+        } => {
+            let delimiter = delimiter
+                .as_deref()
+                .map(parse_delimiter)
+                .transpose()
+                .map_err(CliError::ParseError)?;
+            let transform = transform
+                .map(ImportTransform::from_file)
+                .transpose()
+                .map_err(|e| CliError::CommandError(e.message().to_string()))?;
+            import_links(
+                &link_client,
+                ImportArgs {
+                    file_path,
+                    force,
+                    delimiter,
+                    transform,
+                    dry_run,
+                },
+            )
+            .await
+        }
 
         Commands::Status => unreachable!("handled above"),
 
         Commands::ResetPassword { .. } => unreachable!("handled above"),
 
+        Commands::Token { .. } => unreachable!("handled above"),
+
+        Commands::Bench { .. } => unreachable!("handled above"),
+
+        Commands::GenerateDeploy { .. } => unreachable!("handled above"),
+
         Commands::Config { .. } => unreachable!("handled above"),
+
+        Commands::Analytics { .. } => unreachable!("handled above"),
+
+        Commands::Migrate { .. } => unreachable!("handled above"),
+
+        Commands::Db { .. } => unreachable!("handled above"),
     }
 }
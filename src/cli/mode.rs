@@ -0,0 +1,388 @@
+//! 运行模式判定：无子命令时启动 HTTP server，否则执行一条管理命令
+//!
+//! 解析本身交给 clap，这里补充三点：
+//! - 任意层级的 `--help` 都附带模式说明
+//! - 顶层出现无法识别的参数时，附加"启动 server 还是执行命令"的引导，
+//!   并列出可用子命令
+//! - 帮助文案与引导按 `--lang` / 环境变量选择语言
+
+use std::ffi::OsString;
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Command, CommandFactory, FromArgMatches};
+
+use super::Cli;
+use super::localize::localize;
+use crate::i18n::{self, Lang};
+
+/// 解析得到的运行模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// 启动 HTTP server
+    Server,
+    /// 执行一条 CLI 子命令
+    Command,
+}
+
+impl Cli {
+    pub fn mode(&self) -> RunMode {
+        if self.command.is_some() {
+            RunMode::Command
+        } else {
+            RunMode::Server
+        }
+    }
+
+    /// 解析命令行参数（第一个元素为程序名）
+    ///
+    /// 失败时返回的 [`ArgsError`] 包含 clap 的原始错误与可选的引导信息，
+    /// `--help` / `--version` 也以错误形式返回，统一交给 [`ArgsError::exit`]。
+    /// 帮助文案的语言取 `--lang`，未指定时按环境变量判定。
+    pub fn parse_args<I, T>(args: I) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Self::parse_args_in(args, Lang::from_env())
+    }
+
+    /// 同 [`Cli::parse_args`]，未指定 `--lang` 时使用 `default_lang`
+    pub fn parse_args_in<I, T>(args: I, default_lang: Lang) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        let lang = lang_from_args(&args).unwrap_or(default_lang);
+        command(lang)
+            .try_get_matches_from(&args)
+            .and_then(|matches| Cli::from_arg_matches(&matches))
+            .map(|mut cli| {
+                cli.lang.get_or_insert(lang);
+                cli
+            })
+            .map_err(|error| {
+                let hint = top_level_hint(&error, &args, lang);
+                ArgsError { error, hint }
+            })
+    }
+}
+
+/// 在 clap 解析之前找出 `--lang`，用于决定帮助与报错的语言
+///
+/// 取值无法识别时返回 `None`，交给 clap 按参数错误报告。
+fn lang_from_args(args: &[OsString]) -> Option<Lang> {
+    let mut iter = args
+        .iter()
+        .skip(1)
+        .filter_map(|arg| arg.to_str())
+        .take_while(|arg| *arg != "--");
+    while let Some(arg) = iter.next() {
+        let value = match arg.strip_prefix("--lang") {
+            Some("") => iter.next(),
+            Some(rest) => rest.strip_prefix('='),
+            None => None,
+        };
+        if let Some(value) = value {
+            return Lang::parse(value);
+        }
+    }
+    None
+}
+
+/// 命令行解析失败
+#[derive(Debug)]
+pub struct ArgsError {
+    error: clap::Error,
+    hint: Option<String>,
+}
+
+impl ArgsError {
+    pub fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+
+    /// 顶层参数无法识别时的引导信息
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    /// 打印错误（及引导）并退出；`--help` / `--version` 以 0 退出
+    pub fn exit(&self) -> ! {
+        let Some(hint) = &self.hint else {
+            self.error.exit()
+        };
+        let _ = self.error.print();
+        eprintln!("\n{}", hint);
+        std::process::exit(self.error.exit_code());
+    }
+}
+
+fn command(lang: Lang) -> Command {
+    with_modes_help(
+        localize(Cli::command(), lang),
+        i18n::t(lang, "cli.help.modes"),
+    )
+}
+
+fn with_modes_help(cmd: Command, modes_help: &'static str) -> Command {
+    cmd.after_help(modes_help)
+        .mut_subcommands(|sub| with_modes_help(sub, modes_help))
+}
+
+fn subcommand_names() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .filter(|sc| !sc.is_hide_set())
+        .map(|sc| sc.get_name().to_string())
+        .collect()
+}
+
+/// 只处理顶层错误；已经给出子命令时错误属于该子命令，由 clap 原样报告
+fn top_level_hint(error: &clap::Error, args: &[OsString], lang: Lang) -> Option<String> {
+    if !matches!(
+        error.kind(),
+        ErrorKind::InvalidSubcommand | ErrorKind::UnknownArgument
+    ) {
+        return None;
+    }
+
+    let commands = subcommand_names();
+    let has_subcommand = args
+        .iter()
+        .skip(1)
+        .filter_map(|arg| arg.to_str())
+        .any(|arg| commands.iter().any(|name| name == arg));
+    if has_subcommand {
+        return None;
+    }
+
+    let offending = [ContextKind::InvalidSubcommand, ContextKind::InvalidArg]
+        .into_iter()
+        .find_map(|kind| match error.get(kind) {
+            Some(ContextValue::String(value)) => Some(value.clone()),
+            _ => None,
+        });
+
+    let mut hint = format!(
+        "{}\n{}",
+        i18n::t(lang, "cli.hint.modes"),
+        i18n::t_with(
+            lang,
+            "cli.hint.valid_commands",
+            &[("commands", &commands.join(", "))]
+        )
+    );
+    if let Some(value) = offending
+        && !value.starts_with('-')
+        && value.ends_with(".toml")
+    {
+        hint.push('\n');
+        hint.push_str(&i18n::t_with(
+            lang,
+            "cli.hint.config_file",
+            &[("file", &value)],
+        ));
+    }
+    Some(hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Commands;
+
+    fn parse(args: &[&str]) -> Result<Cli, ArgsError> {
+        Cli::parse_args_in(
+            std::iter::once("shortlinker").chain(args.iter().copied()),
+            Lang::En,
+        )
+    }
+
+    #[test]
+    fn test_mode_and_global_args() {
+        // (参数, 期望模式, 期望 config, 期望 socket)
+        let cases: &[(&[&str], RunMode, Option<&str>, Option<&str>)] = &[
+            (&[], RunMode::Server, None, None),
+            (
+                &["-c", "prod.toml"],
+                RunMode::Server,
+                Some("prod.toml"),
+                None,
+            ),
+            (
+                &["--config", "prod.toml"],
+                RunMode::Server,
+                Some("prod.toml"),
+                None,
+            ),
+            (
+                &["--config=prod.toml"],
+                RunMode::Server,
+                Some("prod.toml"),
+                None,
+            ),
+            (&["-cprod.toml"], RunMode::Server, Some("prod.toml"), None),
+            (
+                &["-c", "prod.toml", "-s", "/tmp/sl.sock"],
+                RunMode::Server,
+                Some("prod.toml"),
+                Some("/tmp/sl.sock"),
+            ),
+            (&["list"], RunMode::Command, None, None),
+            (
+                &["-c", "prod.toml", "list"],
+                RunMode::Command,
+                Some("prod.toml"),
+                None,
+            ),
+            (
+                &["list", "-c", "prod.toml"],
+                RunMode::Command,
+                Some("prod.toml"),
+                None,
+            ),
+            (
+                &["list", "--config=prod.toml"],
+                RunMode::Command,
+                Some("prod.toml"),
+                None,
+            ),
+            (
+                &["--socket=/tmp/sl.sock", "status", "--config", "prod.toml"],
+                RunMode::Command,
+                Some("prod.toml"),
+                Some("/tmp/sl.sock"),
+            ),
+            (
+                &["config", "list", "-c", "prod.toml"],
+                RunMode::Command,
+                Some("prod.toml"),
+                None,
+            ),
+        ];
+
+        for (args, mode, config, socket) in cases {
+            let cli = parse(args).unwrap_or_else(|e| panic!("{:?} failed: {:?}", args, e));
+            assert_eq!(cli.mode(), *mode, "mode for {:?}", args);
+            assert_eq!(cli.config.as_deref(), *config, "config for {:?}", args);
+            assert_eq!(cli.socket.as_deref(), *socket, "socket for {:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_via_and_verbose_are_global() {
+        use crate::cli::IpcVia;
+
+        let cli = parse(&["list"]).unwrap();
+        assert_eq!(cli.via, IpcVia::Auto);
+        assert!(!cli.verbose);
+
+        let cli = parse(&["--via", "direct", "list", "-v"]).unwrap();
+        assert_eq!(cli.via, IpcVia::Direct);
+        assert!(cli.verbose);
+
+        let cli = parse(&["config", "list", "--via=ipc"]).unwrap();
+        assert_eq!(cli.via, IpcVia::Ipc);
+
+        let err = parse(&["--via", "socket", "list"]).expect_err("should fail");
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_subcommand_is_parsed() {
+        let cli = parse(&["-c", "prod.toml", "remove", "docs"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Remove { ref short_code }) if short_code == "docs"
+        ));
+    }
+
+    #[test]
+    fn test_top_level_errors_include_hint() {
+        // (参数, 期望错误类型, 引导中是否建议 -c)
+        let cases: &[(&[&str], ErrorKind, bool)] = &[
+            (&["prod.toml"], ErrorKind::InvalidSubcommand, true),
+            (&["serve"], ErrorKind::InvalidSubcommand, false),
+            (&["--port", "8080"], ErrorKind::UnknownArgument, false),
+            (&["-x"], ErrorKind::UnknownArgument, false),
+        ];
+
+        for (args, kind, suggests_config) in cases {
+            let err = parse(args).expect_err("should fail");
+            assert_eq!(err.kind(), *kind, "kind for {:?}", args);
+            let hint = err
+                .hint()
+                .unwrap_or_else(|| panic!("missing hint for {:?}", args));
+            assert!(hint.contains("Valid commands:"), "{}", hint);
+            assert!(hint.contains("add"), "{}", hint);
+            assert!(hint.contains("status"), "{}", hint);
+            assert_eq!(
+                hint.contains("shortlinker -c prod.toml"),
+                *suggests_config,
+                "{}",
+                hint
+            );
+        }
+    }
+
+    #[test]
+    fn test_subcommand_errors_have_no_hint() {
+        let cases: &[&[&str]] = &[
+            &["list", "--bogus"],
+            &["remove"],
+            &["config", "frobnicate"],
+            &["-c"],
+        ];
+        for args in cases {
+            let err = parse(args).expect_err("should fail");
+            assert!(err.hint().is_none(), "unexpected hint for {:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_help_describes_modes_at_every_level() {
+        for args in [
+            &["--help"][..],
+            &["add", "--help"],
+            &["config", "set", "--help"],
+        ] {
+            let err = parse(args).expect_err("help is reported as an error");
+            assert_eq!(err.kind(), ErrorKind::DisplayHelp);
+            let rendered = err.error.render().to_string();
+            assert!(rendered.contains("Start the HTTP server"), "{:?}", args);
+            assert!(rendered.contains("-c/--config"), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_lang_option() {
+        let cases: &[(&[&str], Lang)] = &[
+            (&[], Lang::En),
+            (&["--lang", "zh", "list"], Lang::Zh),
+            (&["list", "--lang=zh-CN"], Lang::Zh),
+            (&["-c", "prod.toml", "--lang", "en"], Lang::En),
+        ];
+        for (args, lang) in cases {
+            let cli = parse(args).unwrap_or_else(|e| panic!("{:?} failed: {:?}", args, e));
+            assert_eq!(cli.lang, Some(*lang), "lang for {:?}", args);
+        }
+        assert_eq!(
+            parse(&["--lang", "xx"]).unwrap_err().kind(),
+            ErrorKind::ValueValidation
+        );
+    }
+
+    #[test]
+    fn test_localized_help_and_hint() {
+        let err = parse(&["--lang", "zh", "add", "--help"]).expect_err("help");
+        let rendered = err.error.render().to_string();
+        assert!(rendered.contains("添加短链接"), "{}", rendered);
+        assert!(rendered.contains("运行模式"), "{}", rendered);
+
+        let err = parse(&["--lang", "zh", "prod.toml"]).expect_err("should fail");
+        let hint = err.hint().unwrap();
+        assert!(hint.contains("可用命令："), "{}", hint);
+        assert!(hint.contains("shortlinker -c prod.toml"), "{}", hint);
+    }
+}
@@ -5,6 +5,7 @@
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 
+use crate::config::{init_runtime_config, try_get_runtime_config};
 use crate::errors::ShortlinkerError;
 use crate::metrics::NoopMetrics;
 use crate::services::{ConfigService, ForgeLinkCache, LinkService};
@@ -21,6 +22,8 @@ pub struct ServiceContext {
     storage: OnceCell<Arc<SeaOrmStorage>>,
     link_service: OnceCell<Arc<LinkService>>,
     config_service: OnceCell<Arc<ConfigService>>,
+    /// 创建 LinkService 前从数据库加载运行时配置（过期时间时区等与服务端一致）
+    load_runtime_config: bool,
 }
 
 impl Default for ServiceContext {
@@ -36,12 +39,16 @@ impl ServiceContext {
             storage: OnceCell::new(),
             link_service: OnceCell::new(),
             config_service: OnceCell::new(),
+            load_runtime_config: true,
         }
     }
 
     /// Create a context with pre-injected storage (primarily for tests).
     pub fn with_storage(storage: Arc<SeaOrmStorage>) -> Self {
-        let ctx = Self::new();
+        let ctx = Self {
+            load_runtime_config: false,
+            ..Self::new()
+        };
         let _ = ctx.storage.set(storage);
         ctx
     }
@@ -63,6 +70,13 @@ impl ServiceContext {
         let storage = self.get_storage().await?.clone();
         self.link_service
             .get_or_try_init(|| async {
+                if self.load_runtime_config && try_get_runtime_config().is_none() {
+                    init_runtime_config(storage.get_db().clone())
+                        .await
+                        .map_err(|e| {
+                            ClientError::InitFailed(format!("Runtime config init failed: {}", e))
+                        })?;
+                }
                 let cache = ForgeLinkCache::create(NoopMetrics::arc(), storage.clone())
                     .await
                     .map_err(|error| {
@@ -3,14 +3,29 @@
 use std::sync::Arc;
 
 use crate::services::{
-    CreateLinkRequest, ImportBatchFailedItem, ImportBatchResult, ImportLinkItemRich, ImportMode,
-    LinkCreateResult, UpdateLinkRequest,
+    BatchArchiveResult, BatchExtendRequest, BatchExtendResult, BatchFailedItem, CreateLinkRequest,
+    ExtendAction, GenerateLinksOptions, GenerateLinksResult, ImportBatchFailedItem,
+    ImportBatchResult, ImportLinkItemRich, ImportMode, LinkCreateResult, LinkSample, LinkSelection,
+    LinkTemplate, LinkUpdateResult, UpdateLinkRequest,
 };
-use crate::storage::{LinkFilter, LinkStats, ShortLink};
+use crate::storage::{AnalyticsLevel, CreatedVia, LinkFilter, LinkStats, ShortLink};
 use crate::system::ipc::{self, IpcResponse};
 
 use super::context::ServiceContext;
-use super::{ClientError, ipc_or_fallback};
+use super::{ClientError, Route, choose_route, fallback_after_disconnect, ipc_or_fallback};
+
+/// Arguments for [`LinkClient::batch_extend`]
+#[derive(Debug, Clone, Default)]
+pub struct BatchExtendArgs {
+    /// Explicit codes; when empty, `search` selects the links
+    pub codes: Vec<String>,
+    pub search: Option<String>,
+    pub extend_by: Option<String>,
+    pub new_expires_at: Option<String>,
+    pub include_permanent: bool,
+    pub revive_from_now: bool,
+    pub dry_run: bool,
+}
 
 /// Link operations client.
 ///
@@ -25,6 +40,7 @@ impl LinkClient {
     }
 
     /// Create a new short link
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_link(
         &self,
         code: Option<String>,
@@ -32,6 +48,9 @@ impl LinkClient {
         force: bool,
         expires_at: Option<String>,
         password: Option<String>,
+        analytics_level: Option<AnalyticsLevel>,
+        extras: Option<String>,
+        override_cooldown: bool,
     ) -> Result<LinkCreateResult, ClientError> {
         let ctx = self.ctx.clone();
         let req = CreateLinkRequest {
@@ -40,16 +59,32 @@ impl LinkClient {
             force,
             expires_at: expires_at.clone(),
             password: password.clone(),
+            created_via: CreatedVia::Cli,
+            analytics_level: analytics_level.unwrap_or_default(),
+            extras: extras.clone(),
+            override_cooldown,
         };
         ipc_or_fallback(
-            ipc::add_link(code, target, force, expires_at, password),
+            ipc::add_link(
+                code,
+                target,
+                force,
+                expires_at,
+                password,
+                Some(CreatedVia::Cli),
+                analytics_level,
+                extras,
+                override_cooldown,
+            ),
             |resp| match resp {
                 IpcResponse::LinkCreated {
                     link,
                     generated_code,
+                    warnings,
                 } => Ok(LinkCreateResult {
-                    link,
+                    link: link.into(),
                     generated_code,
+                    warnings,
                 }),
                 other => Err(unexpected_response(other)),
             },
@@ -114,6 +149,223 @@ impl LinkClient {
         .await
     }
 
+    /// Batch extend link expiration times
+    pub async fn batch_extend(
+        &self,
+        args: BatchExtendArgs,
+    ) -> Result<BatchExtendResult, ClientError> {
+        let ctx = self.ctx.clone();
+        let fallback_args = args.clone();
+        let to_failed = |e: crate::system::ipc::ImportErrorData| BatchFailedItem {
+            code: e.code,
+            reason: e.message,
+        };
+        ipc_or_fallback(
+            ipc::batch_extend_links(
+                args.codes,
+                args.search,
+                args.extend_by,
+                args.new_expires_at,
+                args.include_permanent,
+                args.revive_from_now,
+                args.dry_run,
+            ),
+            |resp| match resp {
+                IpcResponse::BatchExtendResult {
+                    updated,
+                    skipped,
+                    failed,
+                    dry_run,
+                } => Ok(BatchExtendResult {
+                    updated,
+                    skipped: skipped.into_iter().map(to_failed).collect(),
+                    failed: failed.into_iter().map(to_failed).collect(),
+                    dry_run,
+                }),
+                other => Err(unexpected_response(other)),
+            },
+            || async move {
+                let service = ctx.get_link_service().await?;
+                let args = fallback_args;
+                let selection = if args.codes.is_empty() {
+                    LinkSelection::Filter(LinkFilter {
+                        search: args.search,
+                        ..Default::default()
+                    })
+                } else {
+                    LinkSelection::Codes(args.codes)
+                };
+                let action = ExtendAction::from_inputs(
+                    args.extend_by.as_deref(),
+                    args.new_expires_at.as_deref(),
+                )?;
+                let req = BatchExtendRequest {
+                    action,
+                    include_permanent: args.include_permanent,
+                    revive_from_now: args.revive_from_now,
+                    dry_run: args.dry_run,
+                };
+                Ok(service.batch_extend_links(selection, req).await?)
+            },
+        )
+        .await
+    }
+
+    /// Archive short links by code, or by `search` when `codes` is empty
+    pub async fn archive_links(
+        &self,
+        codes: Vec<String>,
+        search: Option<String>,
+    ) -> Result<BatchArchiveResult, ClientError> {
+        let ctx = self.ctx.clone();
+        let selection = if codes.is_empty() {
+            LinkSelection::Filter(LinkFilter {
+                search: search.clone(),
+                ..Default::default()
+            })
+        } else {
+            LinkSelection::Codes(codes.clone())
+        };
+        ipc_or_fallback(
+            ipc::archive_links(codes, search),
+            |resp| match resp {
+                IpcResponse::ArchiveResult {
+                    archived,
+                    not_found,
+                    failed,
+                } => Ok(BatchArchiveResult {
+                    archived,
+                    not_found,
+                    failed: failed
+                        .into_iter()
+                        .map(|e| BatchFailedItem {
+                            code: e.code,
+                            reason: e.message,
+                        })
+                        .collect(),
+                }),
+                other => Err(unexpected_response(other)),
+            },
+            || async move {
+                let service = ctx.get_link_service().await?;
+                Ok(service.archive_links(selection).await?)
+            },
+        )
+        .await
+    }
+
+    /// Restore an archived short link
+    pub async fn unarchive_link(&self, code: String) -> Result<ShortLink, ClientError> {
+        let ctx = self.ctx.clone();
+        let code2 = code.clone();
+        ipc_or_fallback(
+            ipc::unarchive_link(code),
+            |resp| match resp {
+                IpcResponse::LinkUnarchived { link } => Ok(link.into()),
+                other => Err(unexpected_response(other)),
+            },
+            || async move {
+                let service = ctx.get_link_service().await?;
+                Ok(service.unarchive_link(&code2).await?)
+            },
+        )
+        .await
+    }
+
+    /// Uniform random sample of links, optionally narrowed by `search`
+    pub async fn sample_links(
+        &self,
+        n: usize,
+        seed: Option<u64>,
+        search: Option<String>,
+    ) -> Result<LinkSample, ClientError> {
+        let ctx = self.ctx.clone();
+        let filter = LinkFilter {
+            search: search.clone(),
+            ..Default::default()
+        };
+        ipc_or_fallback(
+            ipc::sample_links(n, seed, search),
+            |resp| match resp {
+                IpcResponse::SampleResult {
+                    links,
+                    seed,
+                    population,
+                } => Ok(LinkSample {
+                    links: links.into_iter().map(Into::into).collect(),
+                    seed,
+                    population,
+                }),
+                other => Err(unexpected_response(other)),
+            },
+            || async move {
+                let service = ctx.get_link_service().await?;
+                Ok(service.sample_links(filter, n, seed).await?)
+            },
+        )
+        .await
+    }
+
+    /// Expand a link template and create the combinations (or preview with `dry_run`)
+    pub async fn generate_links(
+        &self,
+        template: LinkTemplate,
+        force: bool,
+        expires_at: Option<String>,
+        password: Option<String>,
+        dry_run: bool,
+    ) -> Result<GenerateLinksResult, ClientError> {
+        let ctx = self.ctx.clone();
+        let fallback_template = template.clone();
+        let options = GenerateLinksOptions {
+            force,
+            expires_at: expires_at.clone(),
+            password: password.clone(),
+            created_via: CreatedVia::Cli,
+            analytics_level: AnalyticsLevel::Inherit,
+            dry_run,
+        };
+        ipc_or_fallback(
+            ipc::generate_links(
+                template.url,
+                template.code,
+                template.vars,
+                force,
+                expires_at,
+                password,
+                Some(CreatedVia::Cli),
+                dry_run,
+            ),
+            |resp| match resp {
+                IpcResponse::GenerateResult {
+                    planned,
+                    existing,
+                    created,
+                    failed,
+                    dry_run,
+                } => Ok(GenerateLinksResult {
+                    planned,
+                    existing,
+                    created,
+                    failed: failed
+                        .into_iter()
+                        .map(|e| BatchFailedItem {
+                            code: e.code,
+                            reason: e.message,
+                        })
+                        .collect(),
+                    dry_run,
+                }),
+                other => Err(unexpected_response(other)),
+            },
+            || async move {
+                let service = ctx.get_link_service().await?;
+                Ok(service.generate_links(fallback_template, options).await?)
+            },
+        )
+        .await
+    }
+
     /// Update an existing short link
     pub async fn update_link(
         &self,
@@ -121,23 +373,30 @@ impl LinkClient {
         target: String,
         expires_at: Option<String>,
         password: Option<String>,
-    ) -> Result<ShortLink, ClientError> {
+        analytics_level: Option<AnalyticsLevel>,
+        extras: Option<String>,
+    ) -> Result<LinkUpdateResult, ClientError> {
         let ctx = self.ctx.clone();
         let code2 = code.clone();
         let req = UpdateLinkRequest {
             target: target.clone(),
             expires_at: expires_at.clone(),
             password: password.clone(),
+            analytics_level,
+            extras: extras.clone(),
         };
         ipc_or_fallback(
-            ipc::update_link(code, target, expires_at, password),
+            ipc::update_link(code, target, expires_at, password, analytics_level, extras),
             |resp| match resp {
-                IpcResponse::LinkUpdated { link } => Ok(link),
+                IpcResponse::LinkUpdated { link, warnings } => Ok(LinkUpdateResult {
+                    link: link.into(),
+                    warnings,
+                }),
                 other => Err(unexpected_response(other)),
             },
             || async move {
                 let service = ctx.get_link_service().await?;
-                Ok(service.update_link(&code2, req).await?)
+                Ok(service.update_link_checked(&code2, req).await?)
             },
         )
         .await
@@ -150,7 +409,7 @@ impl LinkClient {
         ipc_or_fallback(
             ipc::get_link(code),
             |resp| match resp {
-                IpcResponse::LinkFound { link } => Ok(link),
+                IpcResponse::LinkFound { link } => Ok(link.map(Into::into)),
                 other => Err(unexpected_response(other)),
             },
             || async move {
@@ -173,7 +432,9 @@ impl LinkClient {
         ipc_or_fallback(
             ipc::list_links(page, page_size, search),
             |resp| match resp {
-                IpcResponse::LinkList { links, total, .. } => Ok((links, total as u64)),
+                IpcResponse::LinkList { links, total, .. } => {
+                    Ok((links.into_iter().map(Into::into).collect(), total as u64))
+                }
                 other => Err(unexpected_response(other)),
             },
             || async move {
@@ -184,6 +445,7 @@ impl LinkClient {
                     created_before: None,
                     only_expired: false,
                     only_active: false,
+                    created_via: None,
                 };
                 Ok(service.list_links(filter, page, page_size).await?)
             },
@@ -237,7 +499,7 @@ impl LinkClient {
         let ctx = self.ctx.clone();
 
         // IPC path
-        if ipc::is_server_running() {
+        if choose_route()? == Route::Ipc {
             let ipc_links: Vec<crate::system::ipc::ImportLinkData> = items
                 .iter()
                 .map(crate::system::ipc::ImportLinkData::from)
@@ -257,7 +519,8 @@ impl LinkClient {
                 }
                 Ok(other) => return Err(unexpected_response(other)),
                 Err(crate::system::ipc::IpcError::ServerNotRunning) => {
-                    // Fall through to fallback
+                    // Fall through to fallback (auto mode only)
+                    fallback_after_disconnect()?;
                 }
                 Err(e) => return Err(ClientError::Ipc(e)),
             }
@@ -281,14 +544,15 @@ impl LinkClient {
     /// Export all links
     pub async fn export_links(&self) -> Result<Vec<ShortLink>, ClientError> {
         let ctx = self.ctx.clone();
-        // IPC path: streaming export returns Vec<ShortLinkData> directly
-        if ipc::is_server_running() {
+        // IPC path: streaming export collects the chunks into Vec<ShortLink>
+        if choose_route()? == Route::Ipc {
             match ipc::export_links().await {
                 Ok(links) => {
                     return Ok(links);
                 }
                 Err(crate::system::ipc::IpcError::ServerNotRunning) => {
-                    // Fall through to fallback
+                    // Fall through to fallback (auto mode only)
+                    fallback_after_disconnect()?;
                 }
                 Err(e) => {
                     return Err(ClientError::Ipc(e));
@@ -356,6 +620,8 @@ fn convert_import_result(
                 row_num: None,
             })
             .collect(),
+        // IPC 不回传写入明细；配额只在 HTTP 路径登记
+        created_codes: Vec::new(),
     }
 }
 
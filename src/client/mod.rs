@@ -12,10 +12,23 @@
 //!
 //! # Fallback Policy
 //!
-//! - `IpcError::ServerNotRunning` → fallback to local service
+//! The route is chosen by the global `--via` flag ([`Transport`]):
+//!
+//! - `auto` (default): probe the socket with a short timeout
+//!   ([`PROBE_TIMEOUT`](crate::system::ipc::platform::PROBE_TIMEOUT)); use IPC when the
+//!   server answers, otherwise the local service. A stale or unresponsive socket prints
+//!   a notice to stderr before falling back.
+//! - `ipc`: never fall back; an unreachable server is `ClientError::Ipc`
+//! - `direct`: skip IPC entirely
+//!
+//! Once a request has been sent over IPC:
+//!
+//! - `IpcError::ServerNotRunning` → fallback to local service (`auto` only)
 //! - `IpcError::Timeout` → **no fallback** (risk of double-writes)
 //! - Other IPC errors → no fallback, return error
 //! - `IpcResponse::Error` → return as `ClientError::ServerError`
+//!
+//! With `--verbose` the route actually taken is printed to stderr.
 
 mod context;
 
@@ -25,14 +38,158 @@ mod system_client;
 
 pub use config_client::ConfigClient;
 pub use context::ServiceContext;
-pub use link_client::LinkClient;
+pub use link_client::{BatchExtendArgs, LinkClient};
 pub use system_client::SystemClient;
 
 use std::fmt;
 use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Once, OnceLock};
+
+use colored::Colorize;
 
 use crate::errors::ShortlinkerError;
-use crate::system::ipc::{IpcError, IpcResponse};
+use crate::system::ipc::platform::{IpcPlatform, PROBE_TIMEOUT, PlatformIpc};
+use crate::system::ipc::{IpcError, IpcResponse, ServerProbe};
+
+// ============ Transport selection ============
+
+/// How CLI commands reach the data (global `--via` flag)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// IPC when the server answers the probe, direct database access otherwise
+    #[default]
+    Auto,
+    /// Always go through the running server; fail if it cannot be reached
+    Ipc,
+    /// Always access the database directly, even if a server is running
+    Direct,
+}
+
+/// The path a client call actually takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Route {
+    Ipc,
+    Direct,
+}
+
+static TRANSPORT: OnceLock<Transport> = OnceLock::new();
+static VERBOSE: OnceLock<bool> = OnceLock::new();
+
+/// Set the transport chosen on the command line
+///
+/// Must be called before the first client call; later calls are ignored.
+pub fn set_transport(transport: Transport) {
+    let _ = TRANSPORT.set(transport);
+}
+
+/// Get the transport (defaults to [`Transport::Auto`])
+pub fn transport() -> Transport {
+    TRANSPORT.get().copied().unwrap_or_default()
+}
+
+/// Print the route actually taken to stderr (`--verbose`)
+pub fn set_verbose(verbose: bool) {
+    let _ = VERBOSE.set(verbose);
+}
+
+fn verbose() -> bool {
+    VERBOSE.get().copied().unwrap_or(false)
+}
+
+/// Decide the route for `transport` given the probe result
+///
+/// `probe` is only called when the transport needs it. Returns the route and the
+/// probe result that led to it (`None` for `Direct`).
+fn resolve_route(
+    transport: Transport,
+    probe: impl FnOnce() -> ServerProbe,
+) -> Result<(Route, Option<ServerProbe>), ClientError> {
+    if transport == Transport::Direct {
+        return Ok((Route::Direct, None));
+    }
+
+    let probed = probe();
+    match (transport, probed) {
+        (_, ServerProbe::Running) => Ok((Route::Ipc, Some(probed))),
+        (Transport::Ipc, ServerProbe::Unresponsive) => Err(ClientError::Ipc(IpcError::Timeout)),
+        (Transport::Ipc, _) => Err(ClientError::Ipc(IpcError::ServerNotRunning)),
+        _ => Ok((Route::Direct, Some(probed))),
+    }
+}
+
+/// Choose the route for the next client call, printing the stale-socket notice and
+/// (with `--verbose`) the route taken
+pub(crate) fn choose_route() -> Result<Route, ClientError> {
+    let (route, probed) = resolve_route(transport(), || crate::system::ipc::probe_server())?;
+
+    match probed {
+        Some(ServerProbe::Stale) => warn_fallback(&format!(
+            "stale socket detected at {}, falling back to direct database access",
+            PlatformIpc::socket_path()
+        )),
+        Some(ServerProbe::Unresponsive) => warn_fallback(&format!(
+            "server at {} did not answer within {}ms, falling back to direct database access \
+             (a running server will not see this change until it reloads; use --via ipc to fail instead)",
+            PlatformIpc::socket_path(),
+            PROBE_TIMEOUT.as_millis()
+        )),
+        _ => {}
+    }
+
+    let reason = match probed {
+        None => "--via direct",
+        Some(ServerProbe::Running) => "server is running",
+        Some(ServerProbe::NotRunning) => "server is not running",
+        Some(ServerProbe::Stale) => "stale socket",
+        Some(ServerProbe::Unresponsive) => "server did not respond",
+    };
+    announce_route(route, reason);
+    Ok(route)
+}
+
+/// Print a fallback notice once per process
+fn warn_fallback(message: &str) {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| eprintln!("{} {}", "⚠".bold().yellow(), message));
+}
+
+/// Print the route under `--verbose`, only when it differs from the last one printed
+fn announce_route(route: Route, reason: &str) {
+    static LAST: AtomicU8 = AtomicU8::new(0);
+
+    if !verbose() {
+        return;
+    }
+    let tag = match route {
+        Route::Ipc => 1,
+        Route::Direct => 2,
+    };
+    if LAST.swap(tag, Ordering::Relaxed) == tag {
+        return;
+    }
+    match route {
+        Route::Ipc => eprintln!(
+            "{} via: ipc ({}, {})",
+            "ℹ".bold().blue(),
+            PlatformIpc::socket_path(),
+            reason
+        ),
+        Route::Direct => eprintln!("{} via: direct database ({})", "ℹ".bold().blue(), reason),
+    }
+}
+
+/// Handle `ServerNotRunning` returned by an IPC call that already passed the probe
+///
+/// The server went away between the probe and the call: fall back in `auto` mode,
+/// surface the error otherwise.
+pub(crate) fn fallback_after_disconnect() -> Result<(), ClientError> {
+    if transport() != Transport::Auto {
+        return Err(ClientError::Ipc(IpcError::ServerNotRunning));
+    }
+    announce_route(Route::Direct, "server went away");
+    Ok(())
+}
 
 // ============ ClientError ============
 
@@ -85,13 +242,19 @@ impl From<ClientError> for ShortlinkerError {
 impl From<ClientError> for crate::cli::CliError {
     fn from(err: ClientError) -> Self {
         use crate::cli::CliError;
+        use crate::i18n::{self, Lang};
+        let lang = i18n::current();
         match err {
             ClientError::Ipc(e) => CliError::CommandError(format!("IPC error: {}", e)),
-            ClientError::Service(e) => CliError::CommandError(e.format_simple()),
+            ClientError::Service(e) => CliError::CommandError(e.format_localized(lang)),
             ClientError::InitFailed(msg) => CliError::StorageError(msg),
-            ClientError::ServerError { code, message } => {
+            ClientError::ServerError { code, message } if lang == Lang::En => {
                 CliError::CommandError(format!("{}: {}", code, message))
             }
+            // 非英文时按错误码还原为业务错误，使用共享文案
+            ClientError::ServerError { code, message } => CliError::CommandError(
+                ShortlinkerError::from_error_code(&code, message).format_localized(lang),
+            ),
         }
     }
 }
@@ -100,9 +263,9 @@ impl From<ClientError> for crate::cli::CliError {
 
 /// Execute an operation with IPC-first, Service-fallback strategy.
 ///
-/// 1. Check if server is running
-/// 2. If yes, send IPC command and parse response
-/// 3. If `ServerNotRunning`, execute fallback (local service)
+/// 1. Choose the route from `--via` and the socket probe ([`choose_route`])
+/// 2. For IPC, send the command and parse the response
+/// 3. If `ServerNotRunning`, execute fallback (local service) in `auto` mode
 /// 4. If `Timeout` or other IPC error, return error (no fallback)
 pub(crate) async fn ipc_or_fallback<T, F, Fut>(
     ipc_call: impl Future<Output = Result<IpcResponse, IpcError>>,
@@ -113,16 +276,17 @@ where
     F: FnOnce() -> Fut,
     Fut: Future<Output = Result<T, ClientError>>,
 {
-    use crate::system::ipc;
-
-    if !ipc::is_server_running() {
+    if choose_route()? == Route::Direct {
         return fallback().await;
     }
 
     match ipc_call.await {
         Ok(IpcResponse::Error { code, message }) => Err(ClientError::ServerError { code, message }),
         Ok(resp) => parse_response(resp),
-        Err(IpcError::ServerNotRunning) => fallback().await,
+        Err(IpcError::ServerNotRunning) => {
+            fallback_after_disconnect()?;
+            fallback().await
+        }
         Err(e) => Err(ClientError::Ipc(e)),
     }
 }
@@ -255,8 +419,54 @@ mod tests {
         }
     }
 
+    // ---- resolve_route tests ----
+
+    #[test]
+    fn test_resolve_route_direct_skips_probe() {
+        let result = resolve_route(Transport::Direct, || panic!("probe must not run"));
+        assert_eq!(result.unwrap(), (Route::Direct, None));
+    }
+
+    #[test]
+    fn test_resolve_route_auto_running_uses_ipc() {
+        let (route, _) = resolve_route(Transport::Auto, || ServerProbe::Running).unwrap();
+        assert_eq!(route, Route::Ipc);
+    }
+
+    #[test]
+    fn test_resolve_route_auto_falls_back_on_stale_and_unresponsive() {
+        for probe in [
+            ServerProbe::NotRunning,
+            ServerProbe::Stale,
+            ServerProbe::Unresponsive,
+        ] {
+            let result = resolve_route(Transport::Auto, || probe).unwrap();
+            assert_eq!(result, (Route::Direct, Some(probe)));
+        }
+    }
+
+    #[test]
+    fn test_resolve_route_ipc_running_uses_ipc() {
+        let (route, _) = resolve_route(Transport::Ipc, || ServerProbe::Running).unwrap();
+        assert_eq!(route, Route::Ipc);
+    }
+
+    #[test]
+    fn test_resolve_route_ipc_unreachable_is_error() {
+        for probe in [ServerProbe::NotRunning, ServerProbe::Stale] {
+            let result = resolve_route(Transport::Ipc, || probe);
+            assert!(
+                matches!(result, Err(ClientError::Ipc(IpcError::ServerNotRunning))),
+                "{:?}",
+                probe
+            );
+        }
+        let result = resolve_route(Transport::Ipc, || ServerProbe::Unresponsive);
+        assert!(matches!(result, Err(ClientError::Ipc(IpcError::Timeout))));
+    }
+
     // ---- ipc_or_fallback tests ----
-    // Note: ipc_or_fallback calls ipc::is_server_running() which needs config initialized.
+    // Note: ipc_or_fallback probes the IPC socket, which needs config initialized.
 
     fn ensure_config() {
         use std::sync::Once;
@@ -269,7 +479,7 @@ mod tests {
     #[tokio::test]
     async fn test_ipc_or_fallback_server_not_running_uses_fallback() {
         ensure_config();
-        // In test environment, is_server_running() returns false,
+        // In test environment no server answers the probe,
         // so the fallback path is always taken.
         let result = ipc_or_fallback(
             async { Err(IpcError::ServerNotRunning) },
@@ -2,7 +2,7 @@
 //!
 //! Server status, reload, and shutdown require a running server.
 
-use crate::system::ipc::{self, IpcCommand, IpcResponse};
+use crate::system::ipc::{self, BackgroundTaskStatus, IpcCommand, IpcCommandStats, IpcResponse};
 use crate::system::reload::ReloadTarget;
 
 use super::ClientError;
@@ -16,6 +16,9 @@ pub struct ServerStatus {
     pub last_data_reload: Option<String>,
     pub last_config_reload: Option<String>,
     pub links_count: usize,
+    pub ipc_in_flight: usize,
+    pub ipc_commands: Vec<IpcCommandStats>,
+    pub background_tasks: Vec<BackgroundTaskStatus>,
 }
 
 /// Reload operation result
@@ -61,6 +64,9 @@ impl SystemClient {
                 last_data_reload,
                 last_config_reload,
                 links_count,
+                ipc_in_flight,
+                ipc_commands,
+                background_tasks,
             } => Ok(ServerStatus {
                 version,
                 uptime_secs,
@@ -68,6 +74,9 @@ impl SystemClient {
                 last_data_reload,
                 last_config_reload,
                 links_count,
+                ipc_in_flight,
+                ipc_commands,
+                background_tasks,
             }),
             IpcResponse::Error { code, message } => Err(ClientError::ServerError { code, message }),
             other => Err(ClientError::Ipc(
@@ -32,17 +32,24 @@ pub mod categories {
     pub const TRACKING: &str = "tracking";
     pub const ANALYTICS: &str = "analytics";
     pub const CACHE: &str = "cache";
+    pub const SECURITY: &str = "security";
+    pub const STORAGE: &str = "storage";
+    pub const DATABASE: &str = "database";
 }
 
 /// Key 常量
 pub mod keys {
     // API 认证
     pub const API_ADMIN_TOKEN: &str = "api.admin_token";
+    pub const API_ADMIN_TOKEN_PREVIOUS: &str = "api.admin_token_previous";
+    pub const API_ADMIN_TOKEN_PREVIOUS_EXPIRES_AT: &str = "api.admin_token_previous_expires_at";
+    pub const AUTH_TOKEN_GRACE_HOURS: &str = "auth.token_grace_hours";
     pub const API_HEALTH_TOKEN: &str = "api.health_token";
     pub const API_JWT_SECRET: &str = "api.jwt_secret";
     pub const API_ACCESS_TOKEN_MINUTES: &str = "api.access_token_minutes";
     pub const API_REFRESH_TOKEN_DAYS: &str = "api.refresh_token_days";
     pub const API_TRUSTED_PROXIES: &str = "api.trusted_proxies";
+    pub const API_LEGACY_ERROR_FIELDS: &str = "api.legacy_error_fields";
 
     // Cookie 配置
     pub const API_COOKIE_SECURE: &str = "api.cookie_secure";
@@ -53,6 +60,16 @@ pub mod keys {
     pub const FEATURES_RANDOM_CODE_LENGTH: &str = "features.random_code_length";
     pub const FEATURES_DEFAULT_URL: &str = "features.default_url";
     pub const FEATURES_ENABLE_ADMIN_PANEL: &str = "features.enable_admin_panel";
+    pub const FEATURES_ARCHIVED_PAGE: &str = "features.archived_page";
+    pub const FEATURES_TEMPLATE_MAX_COMBINATIONS: &str = "features.template_max_combinations";
+    pub const FEATURES_PUBLIC_BASE_URL: &str = "features.public_base_url";
+
+    // 链接生命周期
+    pub const LINKS_CODE_REUSE_COOLDOWN_DAYS: &str = "links.code_reuse_cooldown_days";
+    pub const LINKS_EXPIRY_TIMEZONE: &str = "links.expiry_timezone";
+    pub const LINKS_DATE_ONLY_EXPIRY: &str = "links.date_only_expiry";
+    pub const LINKS_CREDENTIAL_POLICY: &str = "links.credential_policy";
+    pub const LINKS_CREDENTIAL_PARAMS: &str = "links.credential_params";
 
     // 点击统计
     pub const CLICK_ENABLE_TRACKING: &str = "click.enable_tracking";
@@ -70,6 +87,12 @@ pub mod keys {
     pub const ANALYTICS_SAMPLE_RATE: &str = "analytics.sample_rate";
     pub const ANALYTICS_MAX_LOG_ROWS: &str = "analytics.max_log_rows";
     pub const ANALYTICS_MAX_ROWS_ACTION: &str = "analytics.max_rows_action";
+    pub const ANALYTICS_RESPECT_DNT: &str = "analytics.respect_dnt";
+    pub const ANALYTICS_DNT_MODE: &str = "analytics.dnt_mode";
+    pub const ANALYTICS_TIMING_SAMPLE_RATE: &str = "analytics.timing_sample_rate";
+    pub const ANALYTICS_TIMING_RETENTION_DAYS: &str = "analytics.timing_retention_days";
+    pub const ANALYTICS_WEEK_STARTS_ON: &str = "analytics.week_starts_on";
+    pub const ANALYTICS_CAPTURED_QUERY_PARAMS: &str = "analytics.captured_query_params";
 
     // UTM 追踪
     pub const UTM_ENABLE_PASSTHROUGH: &str = "utm.enable_passthrough";
@@ -89,6 +112,48 @@ pub mod keys {
 
     // 缓存配置
     pub const CACHE_BLOOM_REBUILD_INTERVAL: &str = "cache.bloom_rebuild_interval";
+    pub const CACHE_BLOOM_MIN_CAPACITY: &str = "cache.bloom_min_capacity";
+    pub const CACHE_BLOOM_FP_ALERT_RATE: &str = "cache.bloom_fp_alert_rate";
+    pub const CACHE_SHARD_INDEX: &str = "cache.shard_index";
+    pub const CACHE_SHARD_TOTAL: &str = "cache.shard_total";
+    pub const CACHE_SHARD_BACKFILL_FOREIGN: &str = "cache.shard_backfill_foreign";
+
+    // 请求拦截规则
+    pub const FIREWALL_RULES: &str = "firewall.rules";
+
+    // redirect 防枚举
+    pub const REDIRECT_CONSTANT_TIME_404: &str = "redirect.constant_time_404";
+    pub const REDIRECT_NOT_FOUND_DELAY_MS: &str = "redirect.not_found_delay_ms";
+
+    // 404 自动封禁
+    pub const SECURITY_AUTO_BAN_404_THRESHOLD: &str = "security.auto_ban_404_threshold";
+    pub const SECURITY_AUTO_BAN_WINDOW_SECS: &str = "security.auto_ban_window_secs";
+    pub const SECURITY_AUTO_BAN_MINUTES: &str = "security.auto_ban_minutes";
+    pub const SECURITY_AUTO_BAN_WHITELIST: &str = "security.auto_ban_whitelist";
+
+    // redirect 响应体
+    pub const REDIRECT_INCLUDE_FALLBACK_BODY: &str = "redirect.include_fallback_body";
+
+    // 点击异常告警
+    pub const ALERTS_ENABLED: &str = "alerts.enabled";
+    pub const ALERTS_TOP_N: &str = "alerts.top_n";
+    pub const ALERTS_WATCH_CODES: &str = "alerts.watch_codes";
+    pub const ALERTS_SIGMA_K: &str = "alerts.sigma_k";
+    pub const ALERTS_MIN_CLICKS: &str = "alerts.min_clicks";
+    pub const ALERTS_COOLDOWN_MINUTES: &str = "alerts.cooldown_minutes";
+    pub const ALERTS_WEBHOOK_URL: &str = "alerts.webhook_url";
+
+    // 对象存储（S3 兼容）
+    pub const STORAGE_S3_ENDPOINT: &str = "storage.s3_endpoint";
+    pub const STORAGE_S3_REGION: &str = "storage.s3_region";
+    pub const STORAGE_S3_BUCKET: &str = "storage.s3_bucket";
+    pub const STORAGE_S3_ACCESS_KEY: &str = "storage.s3_access_key";
+    pub const STORAGE_S3_SECRET_KEY: &str = "storage.s3_secret_key";
+    pub const STORAGE_S3_PREFIX: &str = "storage.s3_prefix";
+
+    // 数据库调试
+    pub const DATABASE_LOG_STATEMENTS: &str = "database.log_statements";
+    pub const DATABASE_SLOW_STATEMENT_MS: &str = "database.slow_statement_ms";
 }
 
 // 默认值函数
@@ -100,6 +165,10 @@ fn default_admin_token() -> String {
     String::new() // 默认为空，用户需运行 reset-password 手动设置
 }
 
+fn default_auth_token_grace_hours() -> String {
+    "72".to_string()
+}
+
 fn default_jwt_secret() -> String {
     crate::utils::generate_secure_token(32)
 }
@@ -124,6 +193,10 @@ fn default_trusted_proxies() -> String {
     "[]".to_string()
 }
 
+fn default_legacy_error_fields() -> String {
+    "true".to_string()
+}
+
 fn default_random_code_length() -> String {
     "6".to_string()
 }
@@ -136,6 +209,35 @@ fn default_enable_admin_panel() -> String {
     "false".to_string()
 }
 
+fn default_archived_page() -> String {
+    "false".to_string()
+}
+
+fn default_template_max_combinations() -> String {
+    crate::services::link_template::DEFAULT_TEMPLATE_MAX_COMBINATIONS.to_string()
+}
+
+fn default_code_reuse_cooldown_days() -> String {
+    "0".to_string() // 0 = 关闭
+}
+
+fn default_expiry_timezone() -> String {
+    "local".to_string()
+}
+
+fn default_date_only_expiry() -> String {
+    "end_of_day".to_string()
+}
+
+fn default_credential_policy() -> String {
+    "warn".to_string()
+}
+
+fn default_credential_params() -> String {
+    serde_json::to_string(crate::services::target_credentials::DEFAULT_CREDENTIAL_PARAMS)
+        .unwrap_or_else(|_| "[]".to_string())
+}
+
 fn default_enable_tracking() -> String {
     "true".to_string()
 }
@@ -224,6 +326,30 @@ fn default_analytics_max_rows_action() -> String {
     "cleanup".to_string() // 默认自动清理
 }
 
+fn default_analytics_respect_dnt() -> String {
+    "false".to_string()
+}
+
+fn default_analytics_dnt_mode() -> String {
+    "details".to_string() // 默认仍计入聚合点击数
+}
+
+fn default_analytics_timing_sample_rate() -> String {
+    "0.01".to_string() // 默认采样 1% 的 redirect
+}
+
+fn default_analytics_timing_retention_days() -> String {
+    "7".to_string()
+}
+
+fn default_analytics_week_starts_on() -> String {
+    "monday".to_string()
+}
+
+fn default_analytics_captured_query_params() -> String {
+    r#"["utm_*"]"#.to_string()
+}
+
 fn default_utm_enable_passthrough() -> String {
     "false".to_string()
 }
@@ -232,6 +358,90 @@ fn default_bloom_rebuild_interval() -> String {
     "14400".to_string() // 4 hours, 0 = disabled
 }
 
+fn default_bloom_min_capacity() -> String {
+    "0".to_string() // 0 = 按当前链接数
+}
+
+fn default_bloom_fp_alert_rate() -> String {
+    "0.01".to_string() // 0 = 不告警
+}
+
+fn default_firewall_rules() -> String {
+    "[]".to_string()
+}
+
+fn default_shard_index() -> String {
+    "0".to_string()
+}
+
+fn default_shard_total() -> String {
+    "0".to_string() // 0 = 不分片
+}
+
+fn default_shard_backfill_foreign() -> String {
+    "false".to_string()
+}
+
+fn default_constant_time_404() -> String {
+    "false".to_string()
+}
+
+fn default_log_statements() -> String {
+    "off".to_string()
+}
+
+fn default_slow_statement_ms() -> String {
+    "200".to_string()
+}
+
+fn default_not_found_delay_ms() -> String {
+    crate::services::not_found_pacing::DEFAULT_NOT_FOUND_DELAY_MS.to_string()
+}
+
+fn default_auto_ban_404_threshold() -> String {
+    "0".to_string()
+}
+
+fn default_auto_ban_window_secs() -> String {
+    crate::services::auto_ban::DEFAULT_WINDOW_SECS.to_string()
+}
+
+fn default_auto_ban_minutes() -> String {
+    crate::services::auto_ban::DEFAULT_BAN_MINUTES.to_string()
+}
+
+fn default_auto_ban_whitelist() -> String {
+    "[]".to_string()
+}
+
+fn default_include_fallback_body() -> String {
+    "true".to_string()
+}
+
+fn default_alerts_enabled() -> String {
+    "false".to_string()
+}
+
+fn default_alerts_top_n() -> String {
+    "20".to_string()
+}
+
+fn default_alerts_watch_codes() -> String {
+    "[]".to_string()
+}
+
+fn default_alerts_sigma_k() -> String {
+    "3.0".to_string()
+}
+
+fn default_alerts_min_clicks() -> String {
+    "10".to_string()
+}
+
+fn default_alerts_cooldown_minutes() -> String {
+    "360".to_string() // 6 hours
+}
+
 fn normalize_trusted_proxies(
     _lookup: &dyn ConfigValueLookup,
     key: &str,
@@ -246,6 +456,129 @@ fn normalize_trusted_proxies(
     serde_json::to_string(&proxies).map_err(Into::into)
 }
 
+fn normalize_firewall_rules(
+    _lookup: &dyn ConfigValueLookup,
+    _key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    crate::services::firewall::normalize_rules(value).map_err(ConfigCoreError::invalid_value)
+}
+
+fn normalize_auto_ban_whitelist(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let entries = parse_string_array_config_value(value, key)?;
+    crate::services::auto_ban::parse_whitelist(&entries).map_err(ConfigCoreError::invalid_value)?;
+    serde_json::to_string(&entries).map_err(Into::into)
+}
+
+fn normalize_auto_ban_limit(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    use crate::services::auto_ban::{MAX_BAN_MINUTES, MAX_THRESHOLD, MAX_WINDOW_SECS};
+
+    let (min, max) = match key {
+        keys::SECURITY_AUTO_BAN_404_THRESHOLD => (0, MAX_THRESHOLD),
+        keys::SECURITY_AUTO_BAN_WINDOW_SECS => (1, MAX_WINDOW_SECS),
+        keys::SECURITY_AUTO_BAN_MINUTES => (1, MAX_BAN_MINUTES),
+        _ => {
+            return Err(ConfigCoreError::invalid_value(format!(
+                "'{key}' is not an auto-ban configuration"
+            )));
+        }
+    };
+    let number = value.trim().parse::<u64>().map_err(|_| {
+        ConfigCoreError::invalid_value(format!("{key} must be an integer between {min} and {max}"))
+    })?;
+    if !(min..=max).contains(&number) {
+        return Err(ConfigCoreError::invalid_value(format!(
+            "{key} must be an integer between {min} and {max}"
+        )));
+    }
+    Ok(number.to_string())
+}
+
+fn normalize_watch_codes(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let codes = parse_string_array_config_value(value, key)?;
+    serde_json::to_string(&codes).map_err(Into::into)
+}
+
+fn normalize_captured_query_params(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let patterns = parse_string_array_config_value(value, key)?;
+    let patterns = crate::analytics::query_params::normalize_patterns(patterns)
+        .map_err(ConfigCoreError::invalid_value)?;
+    serde_json::to_string(&patterns).map_err(Into::into)
+}
+
+fn normalize_sigma_k(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let k = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| ConfigCoreError::invalid_value(format!("{key} must be a positive number")))?;
+    if !k.is_finite() || k <= 0.0 {
+        return Err(ConfigCoreError::invalid_value(format!(
+            "{key} must be a finite positive number"
+        )));
+    }
+    if k.fract() == 0.0 {
+        Ok(format!("{k:.1}"))
+    } else {
+        Ok(k.to_string())
+    }
+}
+
+fn normalize_webhook_url(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let url = value.trim();
+    if url.is_empty() {
+        return Ok(String::new());
+    }
+    aster_forge_utils::url::parse_http_url(url, key)
+        .map(|_| url.to_string())
+        .map_err(|error| ConfigCoreError::invalid_value(error.to_string()))
+}
+
+fn normalize_public_base_url(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let url = value.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Ok(String::new());
+    }
+    aster_forge_utils::url::parse_http_url(url, key)
+        .map(|_| url.to_string())
+        .map_err(|error| ConfigCoreError::invalid_value(error.to_string()))
+}
+
+fn normalize_s3_prefix(
+    _lookup: &dyn ConfigValueLookup,
+    _key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    Ok(value.trim().trim_matches('/').to_string())
+}
+
 fn normalize_same_site(
     _lookup: &dyn ConfigValueLookup,
     key: &str,
@@ -306,6 +639,96 @@ fn normalize_max_rows_action(
     .map(str::to_string)
 }
 
+fn normalize_dnt_mode(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    parse_single_string_enum_selection(value, key, "details, strict", |raw| {
+        match raw.to_ascii_lowercase().as_str() {
+            "details" => Some("details"),
+            "strict" => Some("strict"),
+            _ => None,
+        }
+    })
+    .map(str::to_string)
+}
+
+fn normalize_week_starts_on(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    parse_single_string_enum_selection(value, key, "monday, sunday", |raw| {
+        match raw.to_ascii_lowercase().as_str() {
+            "monday" => Some("monday"),
+            "sunday" => Some("sunday"),
+            _ => None,
+        }
+    })
+    .map(str::to_string)
+}
+
+fn normalize_expiry_timezone(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    crate::utils::InputTimeZone::parse(value)
+        .map(|timezone| timezone.to_config_value())
+        .ok_or_else(|| {
+            ConfigCoreError::invalid_value(format!(
+                "{key} must be 'local', 'UTC' or an offset such as '+08:00'"
+            ))
+        })
+}
+
+fn normalize_date_only_expiry(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    parse_single_string_enum_selection(value, key, "end_of_day, start_of_day", |raw| {
+        crate::utils::DateOnlyBoundary::parse(raw).map(|boundary| boundary.as_str())
+    })
+    .map(str::to_string)
+}
+
+fn normalize_credential_policy(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    parse_single_string_enum_selection(value, key, "warn, strip, reject", |raw| {
+        crate::services::target_credentials::CredentialPolicy::parse(raw)
+            .map(|policy| policy.as_str())
+    })
+    .map(str::to_string)
+}
+
+fn normalize_log_statements(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    parse_single_string_enum_selection(value, key, "off, slow_only, all", |raw| {
+        crate::storage::backend::statement_log::StatementLogMode::parse(raw)
+            .map(|mode| mode.as_str())
+    })
+    .map(str::to_string)
+}
+
+fn normalize_credential_params(
+    _lookup: &dyn ConfigValueLookup,
+    key: &str,
+    value: &str,
+) -> aster_forge_config::Result<String> {
+    let params = parse_string_array_config_value(value, key)?;
+    let params = crate::services::target_credentials::normalize_credential_params(params)
+        .map_err(ConfigCoreError::invalid_value)?;
+    serde_json::to_string(&params).map_err(Into::into)
+}
+
 fn normalize_unsigned_integer(
     _lookup: &dyn ConfigValueLookup,
     key: &str,
@@ -315,14 +738,27 @@ fn normalize_unsigned_integer(
         keys::API_ACCESS_TOKEN_MINUTES
         | keys::API_REFRESH_TOKEN_DAYS
         | keys::FEATURES_RANDOM_CODE_LENGTH
+        | keys::FEATURES_TEMPLATE_MAX_COMBINATIONS
         | keys::CLICK_FLUSH_INTERVAL
-        | keys::CLICK_MAX_CLICKS_BEFORE_FLUSH => normalize_positive_u64_config_value(key, value),
+        | keys::CLICK_MAX_CLICKS_BEFORE_FLUSH
+        | keys::ALERTS_TOP_N
+        | keys::REDIRECT_NOT_FOUND_DELAY_MS => normalize_positive_u64_config_value(key, value),
         keys::CORS_MAX_AGE
         | keys::ANALYTICS_LOG_RETENTION_DAYS
         | keys::ANALYTICS_HOURLY_RETENTION_DAYS
         | keys::ANALYTICS_DAILY_RETENTION_DAYS
         | keys::ANALYTICS_MAX_LOG_ROWS
-        | keys::CACHE_BLOOM_REBUILD_INTERVAL => normalize_non_negative_u64_config_value(key, value),
+        | keys::ANALYTICS_TIMING_RETENTION_DAYS
+        | keys::AUTH_TOKEN_GRACE_HOURS
+        | keys::CACHE_BLOOM_REBUILD_INTERVAL
+        | keys::CACHE_BLOOM_MIN_CAPACITY
+        | keys::CACHE_SHARD_INDEX
+        | keys::CACHE_SHARD_TOTAL
+        | keys::ALERTS_MIN_CLICKS
+        | keys::ALERTS_COOLDOWN_MINUTES
+        | keys::LINKS_CODE_REUSE_COOLDOWN_DAYS => {
+            normalize_non_negative_u64_config_value(key, value)
+        }
         _ => Err(ConfigCoreError::invalid_value(format!(
             "'{key}' is not an unsigned-integer configuration"
         ))),
@@ -364,6 +800,41 @@ pub static CONFIG_REGISTRY = [
         description: "Admin API authentication token (Argon2 hashed)",
         ..ConfigDefinition::private_system()
     },
+    ConfigDefinition {
+        key: keys::API_ADMIN_TOKEN_PREVIOUS,
+        label_i18n_key: "config.keys.api.admin_token_previous",
+        description_i18n_key: "config.descriptions.api.admin_token_previous",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        requires_restart: false,
+        is_sensitive: true,
+        category: categories::AUTH,
+        description: "Previous admin token kept valid during the rotation grace period (Argon2 hashed)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::API_ADMIN_TOKEN_PREVIOUS_EXPIRES_AT,
+        label_i18n_key: "config.keys.api.admin_token_previous_expires_at",
+        description_i18n_key: "config.descriptions.api.admin_token_previous_expires_at",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        requires_restart: false,
+        category: categories::AUTH,
+        description: "When the previous admin token stops being accepted (RFC3339, empty = no grace window)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::AUTH_TOKEN_GRACE_HOURS,
+        label_i18n_key: "config.keys.auth.token_grace_hours",
+        description_i18n_key: "config.descriptions.auth.token_grace_hours",
+        value_type: ConfigValueType::Number,
+        default_fn: default_auth_token_grace_hours,
+        normalize_fn: Some(normalize_unsigned_integer),
+        requires_restart: false,
+        category: categories::AUTH,
+        description: "Hours the previous admin token stays valid after `token rotate`",
+        ..ConfigDefinition::private_system()
+    },
     ConfigDefinition {
         key: keys::API_HEALTH_TOKEN,
         label_i18n_key: "config.keys.api.health_token",
@@ -399,6 +870,17 @@ pub static CONFIG_REGISTRY = [
         description: "Trusted proxy IPs or CIDRs (e.g., [\"10.0.0.1\", \"192.168.1.0/24\"]). Empty = trust no proxies, use connection IP only.",
         ..ConfigDefinition::private_system()
     },
+    ConfigDefinition {
+        key: keys::API_LEGACY_ERROR_FIELDS,
+        label_i18n_key: "config.keys.api.legacy_error_fields",
+        description_i18n_key: "config.descriptions.api.legacy_error_fields",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_legacy_error_fields,
+        requires_restart: false,
+        category: categories::AUTH,
+        description: "Also include deprecated top-level `code`/`message` in admin API error responses",
+        ..ConfigDefinition::private_system()
+    },
     ConfigDefinition {
         key: keys::API_ACCESS_TOKEN_MINUTES,
         label_i18n_key: "config.keys.api.access_token_minutes",
@@ -488,24 +970,111 @@ pub static CONFIG_REGISTRY = [
         description: "Enable admin panel interface",
         ..ConfigDefinition::private_system()
     },
-    // ========== 点击追踪 (tracking) ==========
     ConfigDefinition {
-        key: keys::CLICK_ENABLE_TRACKING,
-        label_i18n_key: "config.keys.click.enable_tracking",
-        description_i18n_key: "config.descriptions.click.enable_tracking",
+        key: keys::FEATURES_ARCHIVED_PAGE,
+        label_i18n_key: "config.keys.features.archived_page",
+        description_i18n_key: "config.descriptions.features.archived_page",
         value_type: ConfigValueType::Boolean,
-        default_fn: default_enable_tracking,
-        requires_restart: true,
-        category: categories::TRACKING,
-        description: "Enable click tracking and analytics",
+        default_fn: default_archived_page,
+        category: categories::FEATURES,
+        description: "Show an \"archived link\" page for archived short codes (410 Gone)",
         ..ConfigDefinition::private_system()
     },
     ConfigDefinition {
-        key: keys::CLICK_FLUSH_INTERVAL,
-        label_i18n_key: "config.keys.click.flush_interval",
-        description_i18n_key: "config.descriptions.click.flush_interval",
+        key: keys::FEATURES_TEMPLATE_MAX_COMBINATIONS,
+        label_i18n_key: "config.keys.features.template_max_combinations",
+        description_i18n_key: "config.descriptions.features.template_max_combinations",
         value_type: ConfigValueType::Number,
-        default_fn: default_flush_interval,
+        default_fn: default_template_max_combinations,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::FEATURES,
+        description: "Maximum number of links a single template generation may expand to",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::FEATURES_PUBLIC_BASE_URL,
+        label_i18n_key: "config.keys.features.public_base_url",
+        description_i18n_key: "config.descriptions.features.public_base_url",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        normalize_fn: Some(normalize_public_base_url),
+        category: categories::FEATURES,
+        description: "Public base URL for generated short links, e.g. https://s.example.com (empty = infer from request headers)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::LINKS_CODE_REUSE_COOLDOWN_DAYS,
+        label_i18n_key: "config.keys.links.code_reuse_cooldown_days",
+        description_i18n_key: "config.descriptions.links.code_reuse_cooldown_days",
+        value_type: ConfigValueType::Number,
+        default_fn: default_code_reuse_cooldown_days,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::FEATURES,
+        description: "Days a deleted short code cannot be created again (0 = disabled); admins can override per request",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::LINKS_EXPIRY_TIMEZONE,
+        label_i18n_key: "config.keys.links.expiry_timezone",
+        description_i18n_key: "config.descriptions.links.expiry_timezone",
+        value_type: ConfigValueType::String,
+        default_fn: default_expiry_timezone,
+        normalize_fn: Some(normalize_expiry_timezone),
+        category: categories::FEATURES,
+        description: "Time zone for expiration times given without an offset (e.g. '2024-12-31 18:00'): 'local', 'UTC' or '+08:00'",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::LINKS_DATE_ONLY_EXPIRY,
+        label_i18n_key: "config.keys.links.date_only_expiry",
+        description_i18n_key: "config.descriptions.links.date_only_expiry",
+        value_type: ConfigValueType::StringEnum,
+        default_fn: default_date_only_expiry,
+        normalize_fn: Some(normalize_date_only_expiry),
+        category: categories::FEATURES,
+        description: "Which moment a date-only expiration (e.g. '2024-12-31') means: 'end_of_day' (23:59:59) or 'start_of_day' (00:00:00)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::LINKS_CREDENTIAL_POLICY,
+        label_i18n_key: "config.keys.links.credential_policy",
+        description_i18n_key: "config.descriptions.links.credential_policy",
+        value_type: ConfigValueType::StringEnum,
+        default_fn: default_credential_policy,
+        normalize_fn: Some(normalize_credential_policy),
+        category: categories::FEATURES,
+        description: "What to do when a target URL carries credentials (user:pass@ or a sensitive query parameter): 'warn' (create and return a warning), 'strip' (remove them and warn) or 'reject'",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::LINKS_CREDENTIAL_PARAMS,
+        label_i18n_key: "config.keys.links.credential_params",
+        description_i18n_key: "config.descriptions.links.credential_params",
+        value_type: ConfigValueType::StringArray,
+        default_fn: default_credential_params,
+        normalize_fn: Some(normalize_credential_params),
+        category: categories::FEATURES,
+        description: "Query/fragment parameter names treated as credentials (JSON array; case-insensitive, '-' equals '_', a trailing '*' matches a prefix)",
+        ..ConfigDefinition::private_system()
+    },
+    // ========== 点击追踪 (tracking) ==========
+    ConfigDefinition {
+        key: keys::CLICK_ENABLE_TRACKING,
+        label_i18n_key: "config.keys.click.enable_tracking",
+        description_i18n_key: "config.descriptions.click.enable_tracking",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_enable_tracking,
+        requires_restart: true,
+        category: categories::TRACKING,
+        description: "Enable click tracking and analytics",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::CLICK_FLUSH_INTERVAL,
+        label_i18n_key: "config.keys.click.flush_interval",
+        description_i18n_key: "config.descriptions.click.flush_interval",
+        value_type: ConfigValueType::Number,
+        default_fn: default_flush_interval,
         normalize_fn: Some(normalize_unsigned_integer),
         requires_restart: true,
         category: categories::TRACKING,
@@ -736,6 +1305,71 @@ pub static CONFIG_REGISTRY = [
         description: "Action when max_log_rows exceeded: 'cleanup' (delete oldest) or 'stop' (stop logging)",
         ..ConfigDefinition::private_system()
     },
+    ConfigDefinition {
+        key: keys::ANALYTICS_RESPECT_DNT,
+        label_i18n_key: "config.keys.analytics.respect_dnt",
+        description_i18n_key: "config.descriptions.analytics.respect_dnt",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_analytics_respect_dnt,
+        category: categories::ANALYTICS,
+        description: "Honor DNT: 1 / Sec-GPC: 1 request headers (no click details, responds with Tk: N)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ANALYTICS_DNT_MODE,
+        label_i18n_key: "config.keys.analytics.dnt_mode",
+        description_i18n_key: "config.descriptions.analytics.dnt_mode",
+        value_type: ConfigValueType::StringEnum,
+        default_fn: default_analytics_dnt_mode,
+        normalize_fn: Some(normalize_dnt_mode),
+        category: categories::ANALYTICS,
+        description: "How to honor privacy signals: 'details' (skip click details, still count the click) or 'strict' (do not count at all)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ANALYTICS_TIMING_SAMPLE_RATE,
+        label_i18n_key: "config.keys.analytics.timing_sample_rate",
+        description_i18n_key: "config.descriptions.analytics.timing_sample_rate",
+        value_type: ConfigValueType::Number,
+        default_fn: default_analytics_timing_sample_rate,
+        normalize_fn: Some(normalize_sample_rate),
+        category: categories::ANALYTICS,
+        description: "Fraction of redirects whose per-phase timings are recorded to redirect_timings (0.0 = off)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ANALYTICS_TIMING_RETENTION_DAYS,
+        label_i18n_key: "config.keys.analytics.timing_retention_days",
+        description_i18n_key: "config.descriptions.analytics.timing_retention_days",
+        value_type: ConfigValueType::Number,
+        default_fn: default_analytics_timing_retention_days,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::ANALYTICS,
+        description: "Redirect timing sample retention period in days (cleaned by DataRetentionTask)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ANALYTICS_WEEK_STARTS_ON,
+        label_i18n_key: "config.keys.analytics.week_starts_on",
+        description_i18n_key: "config.descriptions.analytics.week_starts_on",
+        value_type: ConfigValueType::StringEnum,
+        default_fn: default_analytics_week_starts_on,
+        normalize_fn: Some(normalize_week_starts_on),
+        category: categories::ANALYTICS,
+        description: "First day of the week for weekly rollups: 'monday' or 'sunday'. Run 'analytics rebuild-rollups --granularity week' after changing",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ANALYTICS_CAPTURED_QUERY_PARAMS,
+        label_i18n_key: "config.keys.analytics.captured_query_params",
+        description_i18n_key: "config.descriptions.analytics.captured_query_params",
+        value_type: ConfigValueType::StringArray,
+        default_fn: default_analytics_captured_query_params,
+        normalize_fn: Some(normalize_captured_query_params),
+        category: categories::ANALYTICS,
+        description: "Query parameter names recorded with each click (JSON array, a trailing '*' matches a prefix). Other query content is discarded",
+        ..ConfigDefinition::private_system()
+    },
     // ========== UTM 追踪 (analytics) ==========
     ConfigDefinition {
         key: keys::UTM_ENABLE_PASSTHROUGH,
@@ -747,6 +1381,84 @@ pub static CONFIG_REGISTRY = [
         description: "Enable UTM parameter passthrough to target URL (utm_source/medium/campaign/term/content)",
         ..ConfigDefinition::private_system()
     },
+    // ========== 点击异常告警 (analytics) ==========
+    ConfigDefinition {
+        key: keys::ALERTS_ENABLED,
+        label_i18n_key: "config.keys.alerts.enabled",
+        description_i18n_key: "config.descriptions.alerts.enabled",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_alerts_enabled,
+        category: categories::ANALYTICS,
+        description: "Enable hourly click anomaly detection (spikes and drops against the same hour of the past 7 days)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_TOP_N,
+        label_i18n_key: "config.keys.alerts.top_n",
+        description_i18n_key: "config.descriptions.alerts.top_n",
+        value_type: ConfigValueType::Number,
+        default_fn: default_alerts_top_n,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::ANALYTICS,
+        description: "Number of most-clicked links (past 7 days) monitored for anomalies",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_WATCH_CODES,
+        label_i18n_key: "config.keys.alerts.watch_codes",
+        description_i18n_key: "config.descriptions.alerts.watch_codes",
+        value_type: ConfigValueType::StringArray,
+        default_fn: default_alerts_watch_codes,
+        normalize_fn: Some(normalize_watch_codes),
+        category: categories::ANALYTICS,
+        description: "Short codes always monitored for anomalies in addition to the top N (JSON array)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_SIGMA_K,
+        label_i18n_key: "config.keys.alerts.sigma_k",
+        description_i18n_key: "config.descriptions.alerts.sigma_k",
+        value_type: ConfigValueType::Number,
+        default_fn: default_alerts_sigma_k,
+        normalize_fn: Some(normalize_sigma_k),
+        category: categories::ANALYTICS,
+        description: "Alert when the hourly click count deviates from the baseline mean by more than k standard deviations",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_MIN_CLICKS,
+        label_i18n_key: "config.keys.alerts.min_clicks",
+        description_i18n_key: "config.descriptions.alerts.min_clicks",
+        value_type: ConfigValueType::Number,
+        default_fn: default_alerts_min_clicks,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::ANALYTICS,
+        description: "Minimum hourly clicks for a spike (current hour) or a drop (baseline mean) to alert; filters low-traffic noise",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_COOLDOWN_MINUTES,
+        label_i18n_key: "config.keys.alerts.cooldown_minutes",
+        description_i18n_key: "config.descriptions.alerts.cooldown_minutes",
+        value_type: ConfigValueType::Number,
+        default_fn: default_alerts_cooldown_minutes,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::ANALYTICS,
+        description: "Suppress repeated alerts for the same link within this many minutes",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::ALERTS_WEBHOOK_URL,
+        label_i18n_key: "config.keys.alerts.webhook_url",
+        description_i18n_key: "config.descriptions.alerts.webhook_url",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        normalize_fn: Some(normalize_webhook_url),
+        is_sensitive: true,
+        category: categories::ANALYTICS,
+        description: "Webhook URL receiving anomaly and API token quota alerts as JSON POST (empty = log only)",
+        ..ConfigDefinition::private_system()
+    },
     // ========== 缓存配置 (cache) ==========
     ConfigDefinition {
         key: keys::CACHE_BLOOM_REBUILD_INTERVAL,
@@ -760,6 +1472,238 @@ pub static CONFIG_REGISTRY = [
         description: "Bloom filter periodic rebuild interval in seconds (0 = disabled)",
         ..ConfigDefinition::private_system()
     },
+    ConfigDefinition {
+        key: keys::CACHE_BLOOM_MIN_CAPACITY,
+        label_i18n_key: "config.keys.cache.bloom_min_capacity",
+        description_i18n_key: "config.descriptions.cache.bloom_min_capacity",
+        value_type: ConfigValueType::Number,
+        default_fn: default_bloom_min_capacity,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::CACHE,
+        description: "Minimum Bloom filter capacity used when rebuilding; leaves headroom for codes created before the next rebuild (0 = size to the current link count)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::CACHE_BLOOM_FP_ALERT_RATE,
+        label_i18n_key: "config.keys.cache.bloom_fp_alert_rate",
+        description_i18n_key: "config.descriptions.cache.bloom_fp_alert_rate",
+        value_type: ConfigValueType::Number,
+        default_fn: default_bloom_fp_alert_rate,
+        normalize_fn: Some(normalize_sample_rate),
+        category: categories::CACHE,
+        description: "Alert when the observed Bloom filter false-positive rate exceeds this ratio (0.0-1.0, 0 = disabled)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::CACHE_SHARD_TOTAL,
+        label_i18n_key: "config.keys.cache.shard_total",
+        description_i18n_key: "config.descriptions.cache.shard_total",
+        value_type: ConfigValueType::Number,
+        default_fn: default_shard_total,
+        normalize_fn: Some(normalize_unsigned_integer),
+        requires_restart: true,
+        category: categories::CACHE,
+        description: "Number of code shards across instances; the Bloom filter and in-process cache only hold this instance's shard (0 or 1 = no sharding)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::CACHE_SHARD_INDEX,
+        label_i18n_key: "config.keys.cache.shard_index",
+        description_i18n_key: "config.descriptions.cache.shard_index",
+        value_type: ConfigValueType::Number,
+        default_fn: default_shard_index,
+        normalize_fn: Some(normalize_unsigned_integer),
+        requires_restart: true,
+        category: categories::CACHE,
+        description: "Shard owned by this instance (0-based, must be less than cache.shard_total)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::CACHE_SHARD_BACKFILL_FOREIGN,
+        label_i18n_key: "config.keys.cache.shard_backfill_foreign",
+        description_i18n_key: "config.descriptions.cache.shard_backfill_foreign",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_shard_backfill_foreign,
+        requires_restart: true,
+        category: categories::CACHE,
+        description: "Also cache links from other shards in the in-process L1 cache after a database lookup",
+        ..ConfigDefinition::private_system()
+    },
+    // ========== 请求拦截 (security) ==========
+    ConfigDefinition {
+        key: keys::FIREWALL_RULES,
+        label_i18n_key: "config.keys.firewall.rules",
+        description_i18n_key: "config.descriptions.firewall.rules",
+        value_type: ConfigValueType::Multiline,
+        default_fn: default_firewall_rules,
+        normalize_fn: Some(normalize_firewall_rules),
+        category: categories::SECURITY,
+        description: "Request filtering rules (JSON array) evaluated in order on redirect and admin requests; actions: block, tarpit, log_only",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::REDIRECT_CONSTANT_TIME_404,
+        label_i18n_key: "config.keys.redirect.constant_time_404",
+        description_i18n_key: "config.descriptions.redirect.constant_time_404",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_constant_time_404,
+        category: categories::SECURITY,
+        description: "Pad not-found redirect responses to a fixed latency and slow down IPs producing many 404s, so code existence cannot be probed by timing",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::REDIRECT_NOT_FOUND_DELAY_MS,
+        label_i18n_key: "config.keys.redirect.not_found_delay_ms",
+        description_i18n_key: "config.descriptions.redirect.not_found_delay_ms",
+        value_type: ConfigValueType::Number,
+        default_fn: default_not_found_delay_ms,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::SECURITY,
+        description: "Target latency (ms, ±20% jitter) for not-found redirect responses when redirect.constant_time_404 is on",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::SECURITY_AUTO_BAN_404_THRESHOLD,
+        label_i18n_key: "config.keys.security.auto_ban_404_threshold",
+        description_i18n_key: "config.descriptions.security.auto_ban_404_threshold",
+        value_type: ConfigValueType::Number,
+        default_fn: default_auto_ban_404_threshold,
+        normalize_fn: Some(normalize_auto_ban_limit),
+        category: categories::SECURITY,
+        description: "Ban a client IP once it produces this many redirect 404s within security.auto_ban_window_secs (0 = disabled)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::SECURITY_AUTO_BAN_WINDOW_SECS,
+        label_i18n_key: "config.keys.security.auto_ban_window_secs",
+        description_i18n_key: "config.descriptions.security.auto_ban_window_secs",
+        value_type: ConfigValueType::Number,
+        default_fn: default_auto_ban_window_secs,
+        normalize_fn: Some(normalize_auto_ban_limit),
+        category: categories::SECURITY,
+        description: "Sliding window (seconds) for counting 404s per client IP",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::SECURITY_AUTO_BAN_MINUTES,
+        label_i18n_key: "config.keys.security.auto_ban_minutes",
+        description_i18n_key: "config.descriptions.security.auto_ban_minutes",
+        value_type: ConfigValueType::Number,
+        default_fn: default_auto_ban_minutes,
+        normalize_fn: Some(normalize_auto_ban_limit),
+        category: categories::SECURITY,
+        description: "How long (minutes) a banned IP receives 403 for every redirect and admin request",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::SECURITY_AUTO_BAN_WHITELIST,
+        label_i18n_key: "config.keys.security.auto_ban_whitelist",
+        description_i18n_key: "config.descriptions.security.auto_ban_whitelist",
+        value_type: ConfigValueType::StringArray,
+        default_fn: default_auto_ban_whitelist,
+        normalize_fn: Some(normalize_auto_ban_whitelist),
+        category: categories::SECURITY,
+        description: "IPs or CIDRs that are never counted or banned (e.g., [\"10.0.0.0/8\"])",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::REDIRECT_INCLUDE_FALLBACK_BODY,
+        label_i18n_key: "config.keys.redirect.include_fallback_body",
+        description_i18n_key: "config.descriptions.redirect.include_fallback_body",
+        value_type: ConfigValueType::Boolean,
+        default_fn: default_include_fallback_body,
+        category: categories::FEATURES,
+        description: "Include a minimal HTML body (meta refresh + link) in redirect responses for proxies that strip the Location header",
+        ..ConfigDefinition::private_system()
+    },
+    // ========== 对象存储配置 (storage) ==========
+    ConfigDefinition {
+        key: keys::STORAGE_S3_ENDPOINT,
+        label_i18n_key: "config.keys.storage.s3_endpoint",
+        description_i18n_key: "config.descriptions.storage.s3_endpoint",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        normalize_fn: Some(normalize_public_base_url),
+        category: categories::STORAGE,
+        description: "S3-compatible endpoint URL, e.g. http://minio:9000 (empty = AWS_ENDPOINT_URL or AWS S3)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::STORAGE_S3_REGION,
+        label_i18n_key: "config.keys.storage.s3_region",
+        description_i18n_key: "config.descriptions.storage.s3_region",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        category: categories::STORAGE,
+        description: "S3 region (empty = AWS_REGION / AWS_DEFAULT_REGION, then us-east-1)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::STORAGE_S3_BUCKET,
+        label_i18n_key: "config.keys.storage.s3_bucket",
+        description_i18n_key: "config.descriptions.storage.s3_bucket",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        category: categories::STORAGE,
+        description: "Default bucket for s3:<path> targets; s3://bucket/path targets name the bucket explicitly",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::STORAGE_S3_ACCESS_KEY,
+        label_i18n_key: "config.keys.storage.s3_access_key",
+        description_i18n_key: "config.descriptions.storage.s3_access_key",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        is_sensitive: true,
+        category: categories::STORAGE,
+        description: "S3 access key ID (empty = AWS credential chain: environment, profile, instance metadata)",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::STORAGE_S3_SECRET_KEY,
+        label_i18n_key: "config.keys.storage.s3_secret_key",
+        description_i18n_key: "config.descriptions.storage.s3_secret_key",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        is_sensitive: true,
+        category: categories::STORAGE,
+        description: "S3 secret access key, used together with storage.s3_access_key",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::STORAGE_S3_PREFIX,
+        label_i18n_key: "config.keys.storage.s3_prefix",
+        description_i18n_key: "config.descriptions.storage.s3_prefix",
+        value_type: ConfigValueType::String,
+        default_fn: default_empty,
+        normalize_fn: Some(normalize_s3_prefix),
+        category: categories::STORAGE,
+        description: "Key prefix applied to s3:<path> targets in the default bucket, e.g. shortlinker/backups",
+        ..ConfigDefinition::private_system()
+    },
+    // ========== 数据库 (database) ==========
+    ConfigDefinition {
+        key: keys::DATABASE_LOG_STATEMENTS,
+        label_i18n_key: "config.keys.database.log_statements",
+        description_i18n_key: "config.descriptions.database.log_statements",
+        value_type: ConfigValueType::StringEnum,
+        default_fn: default_log_statements,
+        normalize_fn: Some(normalize_log_statements),
+        category: categories::DATABASE,
+        description: "Log executed SQL with redacted parameters, duration and caller at trace level (target shortlinker::sql): 'off', 'slow_only' or 'all'",
+        ..ConfigDefinition::private_system()
+    },
+    ConfigDefinition {
+        key: keys::DATABASE_SLOW_STATEMENT_MS,
+        label_i18n_key: "config.keys.database.slow_statement_ms",
+        description_i18n_key: "config.descriptions.database.slow_statement_ms",
+        value_type: ConfigValueType::Number,
+        default_fn: default_slow_statement_ms,
+        normalize_fn: Some(normalize_unsigned_integer),
+        category: categories::DATABASE,
+        description: "Statements taking at least this long (ms) are logged when database.log_statements is 'slow_only'",
+        ..ConfigDefinition::private_system()
+    },
 ];
 }
 
@@ -788,6 +1732,9 @@ mod tests {
                 categories::TRACKING,
                 categories::ANALYTICS,
                 categories::CACHE,
+                categories::SECURITY,
+                categories::STORAGE,
+                categories::DATABASE,
             ])
             .unwrap();
     }
@@ -828,6 +1775,48 @@ mod tests {
                 .normalize_value(&lookup, keys::ANALYTICS_SAMPLE_RATE, "1.01")
                 .is_err()
         );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::ANALYTICS_CAPTURED_QUERY_PARAMS,
+                    r#"[" ch ","utm_*","ch"]"#,
+                )
+                .unwrap(),
+            r#"["ch","utm_*"]"#
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::ANALYTICS_CAPTURED_QUERY_PARAMS, r#"["*"]"#)
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::LINKS_CREDENTIAL_POLICY, " Strip ")
+                .unwrap(),
+            "strip"
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::DATABASE_LOG_STATEMENTS, " Slow_Only ")
+                .unwrap(),
+            "slow_only"
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::DATABASE_LOG_STATEMENTS, "verbose")
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::LINKS_CREDENTIAL_PARAMS,
+                    r#"["X-Auth","token","x_auth"]"#,
+                )
+                .unwrap(),
+            r#"["x_auth","token"]"#
+        );
         assert_eq!(
             CONFIG_REGISTRY
                 .normalize_value(&lookup, keys::ANALYTICS_SAMPLE_RATE, " 1 ")
@@ -840,5 +1829,108 @@ mod tests {
                 .unwrap(),
             "6"
         );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::FIREWALL_RULES,
+                    r#"[{"name":"x","action":"block"}]"#
+                )
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::ALERTS_SIGMA_K, " 3 ")
+                .unwrap(),
+            "3.0"
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::ALERTS_SIGMA_K, "0")
+                .is_err()
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::ALERTS_WEBHOOK_URL, "ftp://example.com/hook")
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::ALERTS_WEBHOOK_URL, " ")
+                .unwrap(),
+            ""
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::FEATURES_PUBLIC_BASE_URL,
+                    " https://s.example.com/ "
+                )
+                .unwrap(),
+            "https://s.example.com"
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::FEATURES_PUBLIC_BASE_URL, "s.example.com")
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::SECURITY_AUTO_BAN_404_THRESHOLD, " 0200 ")
+                .unwrap(),
+            "200"
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::SECURITY_AUTO_BAN_WINDOW_SECS, "0")
+                .is_err()
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::SECURITY_AUTO_BAN_MINUTES, "-5")
+                .is_err()
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::SECURITY_AUTO_BAN_WHITELIST,
+                    r#"["10.0.0.0/8"]"#
+                )
+                .is_ok()
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(
+                    &lookup,
+                    keys::SECURITY_AUTO_BAN_WHITELIST,
+                    r#"["10.0.0.0/40"]"#
+                )
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::LINKS_EXPIRY_TIMEZONE, " utc ")
+                .unwrap(),
+            "UTC"
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::LINKS_EXPIRY_TIMEZONE, "+08:00")
+                .unwrap(),
+            "+08:00"
+        );
+        assert!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::LINKS_EXPIRY_TIMEZONE, "Asia/Shanghai")
+                .is_err()
+        );
+        assert_eq!(
+            CONFIG_REGISTRY
+                .normalize_value(&lookup, keys::LINKS_DATE_ONLY_EXPIRY, "START_OF_DAY")
+                .unwrap(),
+            "start_of_day"
+        );
     }
 }
@@ -9,6 +9,9 @@ static CONFIG: OnceLock<ArcSwap<StaticConfig>> = OnceLock::new();
 /// CLI override for IPC socket path
 static IPC_SOCKET_OVERRIDE: OnceLock<String> = OnceLock::new();
 
+/// CLI override for the config file path
+static CONFIG_PATH_OVERRIDE: OnceLock<String> = OnceLock::new();
+
 /// Get the global configuration instance
 ///
 /// Returns an Arc pointer to the configuration, which is cheap to clone
@@ -22,7 +25,8 @@ pub fn get_config() -> Arc<StaticConfig> {
 
 /// Initialize the global configuration
 ///
-/// Loads configuration from "config.toml" in the current directory.
+/// Loads configuration from "config.toml" in the current directory, or from
+/// the path set by [`set_config_path_override`].
 /// If the file doesn't exist, uses in-memory defaults.
 ///
 /// # Examples
@@ -45,3 +49,15 @@ pub fn set_ipc_socket_override(path: String) {
 pub fn get_ipc_socket_override() -> Option<&'static String> {
     IPC_SOCKET_OVERRIDE.get()
 }
+
+/// Set CLI override for the config file path
+///
+/// Must be called before [`init_config`] if --config is specified.
+pub fn set_config_path_override(path: String) {
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
+}
+
+/// Get CLI override for the config file path (if set)
+pub fn get_config_path_override() -> Option<&'static String> {
+    CONFIG_PATH_OVERRIDE.get()
+}
@@ -5,7 +5,10 @@ pub mod schema;
 mod structs;
 pub mod types;
 
-pub use r#impl::{get_config, get_ipc_socket_override, init_config, set_ipc_socket_override};
+pub use r#impl::{
+    get_config, get_config_path_override, get_ipc_socket_override, init_config,
+    set_config_path_override, set_ipc_socket_override,
+};
 pub use runtime_config::{
     RuntimeConfig, get_runtime_config, init_runtime_config, keys, try_get_runtime_config,
 };
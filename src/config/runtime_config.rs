@@ -1,6 +1,7 @@
 use sea_orm::DatabaseConnection;
 use std::collections::HashMap;
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::info;
 
 use crate::errors::{Result, ShortlinkerError};
@@ -22,6 +23,8 @@ static RUNTIME_CONFIG: OnceLock<RuntimeConfig> = OnceLock::new();
 pub struct RuntimeConfig {
     cache: aster_forge_config::SyncRuntimeConfig<ConfigItem>,
     store: ConfigStore,
+    /// 缓存内容变更计数，供派生状态（如预编译的防火墙规则）判断是否需要重建
+    version: AtomicU64,
 }
 
 impl RuntimeConfig {
@@ -30,6 +33,7 @@ impl RuntimeConfig {
         Self {
             cache: aster_forge_config::SyncRuntimeConfig::new(),
             store: ConfigStore::new(db),
+            version: AtomicU64::new(0),
         }
     }
 
@@ -40,6 +44,7 @@ impl RuntimeConfig {
 
         // 更新内部缓存
         self.cache.replace(configs.into_values().collect());
+        self.version.fetch_add(1, Ordering::Release);
 
         info!("Loaded {} runtime configuration items", count);
         Ok(())
@@ -52,11 +57,17 @@ impl RuntimeConfig {
 
         // 更新内部缓存
         self.cache.replace(configs.into_values().collect());
+        self.version.fetch_add(1, Ordering::Release);
 
         info!("Reloaded {} runtime configuration items", count);
         Ok(())
     }
 
+    /// 当前缓存版本，每次 load / reload / set 生效后递增
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
     /// 获取配置值
     pub fn get(&self, key: &str) -> Option<String> {
         self.cache.get(key)
@@ -194,6 +205,7 @@ impl RuntimeConfig {
             item.value = std::sync::Arc::new(normalized);
             item.updated_at = chrono::Utc::now();
             self.cache.apply(item);
+            self.version.fetch_add(1, Ordering::Release);
         } else {
             // 不应该发生：如果 load() 正确初始化了缓存
             tracing::warn!(
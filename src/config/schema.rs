@@ -86,6 +86,11 @@ fn get_enum_options(def: &ConfigDefinition) -> Option<Vec<EnumOption>> {
         keys::API_COOKIE_SAME_SITE => Some(same_site_options()),
         keys::CORS_ALLOWED_METHODS => Some(http_method_options()),
         keys::ANALYTICS_MAX_ROWS_ACTION => Some(max_rows_action_options()),
+        keys::ANALYTICS_DNT_MODE => Some(dnt_mode_options()),
+        keys::ANALYTICS_WEEK_STARTS_ON => Some(week_starts_on_options()),
+        keys::LINKS_DATE_ONLY_EXPIRY => Some(date_only_expiry_options()),
+        keys::LINKS_CREDENTIAL_POLICY => Some(credential_policy_options()),
+        keys::DATABASE_LOG_STATEMENTS => Some(log_statements_options()),
         _ if def.value_type == ConfigValueType::Boolean => Some(bool_options()),
         _ => None,
     }
@@ -165,6 +170,115 @@ fn max_rows_action_options() -> Vec<EnumOption> {
     ]
 }
 
+fn dnt_mode_options() -> Vec<EnumOption> {
+    vec![
+        EnumOption {
+            value: "details".to_string(),
+            label: "Skip details".to_string(),
+            label_i18n_key: Some("enums.dntMode.details.label".to_string()),
+            description: Some("Do not record click details, still count the click".to_string()),
+            description_i18n_key: Some("enums.dntMode.details.description".to_string()),
+        },
+        EnumOption {
+            value: "strict".to_string(),
+            label: "Strict".to_string(),
+            label_i18n_key: Some("enums.dntMode.strict.label".to_string()),
+            description: Some("Do not count the click at all".to_string()),
+            description_i18n_key: Some("enums.dntMode.strict.description".to_string()),
+        },
+    ]
+}
+
+fn week_starts_on_options() -> Vec<EnumOption> {
+    vec![
+        EnumOption {
+            value: "monday".to_string(),
+            label: "Monday".to_string(),
+            label_i18n_key: Some("enums.weekStartsOn.monday.label".to_string()),
+            description: Some("Weeks run Monday to Sunday".to_string()),
+            description_i18n_key: Some("enums.weekStartsOn.monday.description".to_string()),
+        },
+        EnumOption {
+            value: "sunday".to_string(),
+            label: "Sunday".to_string(),
+            label_i18n_key: Some("enums.weekStartsOn.sunday.label".to_string()),
+            description: Some("Weeks run Sunday to Saturday".to_string()),
+            description_i18n_key: Some("enums.weekStartsOn.sunday.description".to_string()),
+        },
+    ]
+}
+
+fn date_only_expiry_options() -> Vec<EnumOption> {
+    vec![
+        EnumOption {
+            value: "end_of_day".to_string(),
+            label: "End of day".to_string(),
+            label_i18n_key: Some("enums.dateOnlyExpiry.end_of_day.label".to_string()),
+            description: Some("A date means 23:59:59 of that day".to_string()),
+            description_i18n_key: Some("enums.dateOnlyExpiry.end_of_day.description".to_string()),
+        },
+        EnumOption {
+            value: "start_of_day".to_string(),
+            label: "Start of day".to_string(),
+            label_i18n_key: Some("enums.dateOnlyExpiry.start_of_day.label".to_string()),
+            description: Some("A date means 00:00:00 of that day".to_string()),
+            description_i18n_key: Some("enums.dateOnlyExpiry.start_of_day.description".to_string()),
+        },
+    ]
+}
+
+fn credential_policy_options() -> Vec<EnumOption> {
+    vec![
+        EnumOption {
+            value: "warn".to_string(),
+            label: "Warn".to_string(),
+            label_i18n_key: Some("enums.credentialPolicy.warn.label".to_string()),
+            description: Some("Create the link and return a warning".to_string()),
+            description_i18n_key: Some("enums.credentialPolicy.warn.description".to_string()),
+        },
+        EnumOption {
+            value: "strip".to_string(),
+            label: "Strip".to_string(),
+            label_i18n_key: Some("enums.credentialPolicy.strip.label".to_string()),
+            description: Some("Remove the credentials, then create the link".to_string()),
+            description_i18n_key: Some("enums.credentialPolicy.strip.description".to_string()),
+        },
+        EnumOption {
+            value: "reject".to_string(),
+            label: "Reject".to_string(),
+            label_i18n_key: Some("enums.credentialPolicy.reject.label".to_string()),
+            description: Some("Refuse to create the link".to_string()),
+            description_i18n_key: Some("enums.credentialPolicy.reject.description".to_string()),
+        },
+    ]
+}
+
+fn log_statements_options() -> Vec<EnumOption> {
+    vec![
+        EnumOption {
+            value: "off".to_string(),
+            label: "Off".to_string(),
+            label_i18n_key: Some("enums.logStatements.off.label".to_string()),
+            description: Some("Do not log SQL statements".to_string()),
+            description_i18n_key: Some("enums.logStatements.off.description".to_string()),
+        },
+        EnumOption {
+            value: "slow_only".to_string(),
+            label: "Slow only".to_string(),
+            label_i18n_key: Some("enums.logStatements.slow_only.label".to_string()),
+            description: Some("Log statements slower than database.slow_statement_ms".to_string()),
+            description_i18n_key: Some("enums.logStatements.slow_only.description".to_string()),
+        },
+        EnumOption {
+            value: "all".to_string(),
+            label: "All".to_string(),
+            label_i18n_key: Some("enums.logStatements.all.label".to_string()),
+            description: Some("Log every statement".to_string()),
+            description_i18n_key: Some("enums.logStatements.all.description".to_string()),
+        },
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +120,8 @@ impl std::str::FromStr for HttpMethod {
 /// - logging: 日志配置
 /// - analytics: 分析统计配置
 /// - ipc: IPC 服务器配置
+/// - system: 运行时可写目录
+/// - plugins: 实验性插件（需 `wasm-plugins` feature）
 ///
 /// 运行时配置（api, routes, features, click_manager, cors）存储在数据库中，
 /// 通过 Admin Panel 或 API 进行管理，使用 RuntimeConfig 读取。
@@ -137,6 +139,10 @@ pub struct StaticConfig {
     pub analytics: AnalyticsConfig,
     #[serde(default)]
     pub ipc: IpcConfig,
+    #[serde(default)]
+    pub system: SystemConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
 }
 
 impl StaticConfig {
@@ -145,14 +151,17 @@ impl StaticConfig {
     /// 优先级：ENV > config.toml > 默认值
     /// ENV 前缀：SL，分隔符：__
     /// 示例：SL__SERVER__PORT=9999
+    ///
+    /// 通过 `-c/--config` 指定的文件必须存在，默认的 `config.toml` 可缺省。
     pub fn load() -> Self {
         use config::{Config, Environment, File};
 
-        let path = "config.toml";
+        let explicit = super::get_config_path_override();
+        let path = explicit.map_or("config.toml", String::as_str);
 
         let builder = Config::builder()
-            // 1. 从 TOML 文件加载（可选）
-            .add_source(File::with_name(path).required(false))
+            // 1. 从 TOML 文件加载
+            .add_source(File::with_name(path).required(explicit.is_some()))
             // 2. 从环境变量覆盖，前缀 SL，分隔符 __
             .add_source(
                 Environment::with_prefix("SL")
@@ -180,6 +189,11 @@ impl StaticConfig {
         }
     }
 
+    /// 实际的 IPC socket 路径（Unix 默认位于 `system.runtime_dir` 下）
+    pub fn ipc_socket_path(&self) -> String {
+        self.ipc.effective_socket_path(&self.system)
+    }
+
     /// 生成示例 TOML 配置文件
     pub fn generate_sample_config() -> String {
         let sample_config = Self::default();
@@ -187,6 +201,31 @@ impl StaticConfig {
             .unwrap_or_else(|e| format!("Error generating sample config: {}", e))
     }
 
+    /// 全部静态配置项对应的环境变量及默认值（`SL__SECTION__KEY`）
+    ///
+    /// 与 `config generate` 同源，`generate-deploy` 据此生成部署清单。数组项无法用
+    /// 单个环境变量表达，跳过；默认为空的可选项不出现。
+    pub fn env_var_defaults() -> Vec<(String, String)> {
+        fn walk(prefix: &str, value: &toml::Value, out: &mut Vec<(String, String)>) {
+            match value {
+                toml::Value::Table(table) => {
+                    for (key, value) in table {
+                        walk(&format!("{}__{}", prefix, key.to_uppercase()), value, out);
+                    }
+                }
+                toml::Value::Array(_) => {}
+                toml::Value::String(s) => out.push((prefix.to_string(), s.clone())),
+                other => out.push((prefix.to_string(), other.to_string())),
+            }
+        }
+
+        let mut out = Vec::new();
+        if let Ok(value) = toml::Value::try_from(Self::default()) {
+            walk("SL", &value, &mut out);
+        }
+        out
+    }
+
     /// 保存配置到 TOML 文件
     pub fn save_to_file<P: AsRef<std::path::Path>>(
         &self,
@@ -217,6 +256,13 @@ pub struct ServerConfig {
     pub unix_socket: Option<String>,
     #[serde(default = "default_cpu_count")]
     pub cpu_count: usize,
+    /// 是否识别 `bench --no-analytics-impact` 发送的压测标记头
+    /// 开启后带该头的 redirect 请求不计入点击统计，默认关闭以防滥用
+    #[serde(default)]
+    pub allow_bench_header: bool,
+    /// 有序关闭的总超时（秒），各阶段超时不会超过剩余时间
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
 }
 
 /// 数据库连接配置
@@ -232,6 +278,9 @@ pub struct DatabaseConfig {
     pub retry_base_delay_ms: u64,
     #[serde(default = "default_retry_max_delay_ms")]
     pub retry_max_delay_ms: u64,
+    /// 周期维护间隔（小时），只执行在线安全的轻量维护；0 表示关闭
+    #[serde(default)]
+    pub maintenance_interval_hours: u64,
 }
 
 /// 缓存系统配置
@@ -242,8 +291,36 @@ pub struct CacheConfig {
     pub cache_type: String,
     #[serde(default = "default_cache_ttl")]
     pub default_ttl: u64,
+    /// 进程内 L1 缓存的字节上限（0 表示不按字节限制）
+    #[serde(default)]
+    pub l1_max_bytes: u64,
+    /// 进程内 L1 缓存的条目上限（0 表示不按条数限制），与字节上限取先到者
+    #[serde(default)]
+    pub l1_max_entries: u64,
+    /// 单条缓存对象的大小阈值（字节），超过后不进入 L1（0 表示不限制）
+    #[serde(default = "default_cache_max_entry_bytes")]
+    pub max_entry_bytes: u64,
+    /// 超限对象的处理策略：`l2`（仅写入 Redis）/ `skip`（完全不缓存）
+    #[serde(default = "default_cache_oversize_policy")]
+    pub oversize_policy: String,
     #[serde(default)]
     pub redis: RedisConfig,
+    #[serde(default)]
+    pub miss_batch: MissBatchConfig,
+}
+
+/// 缓存 miss 回源的微批量合并配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissBatchConfig {
+    /// 是否启用（关闭时每个 miss 各自单查）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 收集窗口（毫秒）
+    #[serde(default = "default_miss_batch_window_ms")]
+    pub window_ms: u64,
+    /// 单批最多合并的短码数，凑满立即查询
+    #[serde(default = "default_miss_batch_max_size")]
+    pub max_batch_size: usize,
 }
 
 /// Redis 配置
@@ -271,6 +348,10 @@ fn default_cpu_count() -> usize {
     num_cpus::get()
 }
 
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
 fn default_database_url() -> String {
     "sqlite://shortlinks.db?mode=rwc".to_string()
 }
@@ -295,10 +376,26 @@ fn default_cache_type() -> String {
     "memory".to_string()
 }
 
+fn default_cache_max_entry_bytes() -> u64 {
+    8 * 1024
+}
+
+fn default_cache_oversize_policy() -> String {
+    "l2".to_string()
+}
+
 fn default_cache_ttl() -> u64 {
     3600
 }
 
+fn default_miss_batch_window_ms() -> u64 {
+    2
+}
+
+fn default_miss_batch_max_size() -> usize {
+    50
+}
+
 fn default_redis_url() -> String {
     "redis://127.0.0.1:6379/".to_string()
 }
@@ -318,6 +415,8 @@ impl Default for ServerConfig {
             port: default_server_port(),
             unix_socket: None,
             cpu_count: default_cpu_count(),
+            allow_bench_header: false,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
         }
     }
 }
@@ -330,6 +429,7 @@ impl Default for DatabaseConfig {
             retry_count: default_retry_count(),
             retry_base_delay_ms: default_retry_base_delay_ms(),
             retry_max_delay_ms: default_retry_max_delay_ms(),
+            maintenance_interval_hours: 0,
         }
     }
 }
@@ -339,7 +439,22 @@ impl Default for CacheConfig {
         Self {
             cache_type: default_cache_type(),
             default_ttl: default_cache_ttl(),
+            l1_max_bytes: 0,
+            l1_max_entries: 0,
+            max_entry_bytes: default_cache_max_entry_bytes(),
+            oversize_policy: default_cache_oversize_policy(),
             redis: RedisConfig::default(),
+            miss_batch: MissBatchConfig::default(),
+        }
+    }
+}
+
+impl Default for MissBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: default_miss_batch_window_ms(),
+            max_batch_size: default_miss_batch_max_size(),
         }
     }
 }
@@ -365,17 +480,63 @@ pub struct AnalyticsConfig {
     /// 使用 {ip} 作为占位符，例如: http://ip-api.com/json/{ip}?fields=status,countryCode,city
     #[serde(default = "default_geoip_api_url")]
     pub geoip_api_url: String,
+
+    /// 外部 API 同时在途的请求上限，满了直接跳过（不排队）
+    #[serde(default = "default_geoip_api_max_concurrency")]
+    pub geoip_api_max_concurrency: usize,
+
+    /// 外部 API 单次请求超时（毫秒）
+    #[serde(default = "default_geoip_api_timeout_ms")]
+    pub geoip_api_timeout_ms: u64,
+
+    /// 连续失败多少次后熔断，0 = 不熔断
+    #[serde(default = "default_geoip_api_failure_threshold")]
+    pub geoip_api_failure_threshold: u32,
+
+    /// 熔断持续时间（分钟），到期后放行一个探测请求
+    #[serde(default = "default_geoip_api_open_minutes")]
+    pub geoip_api_open_minutes: u64,
+
+    /// 每分钟最多发出的外部 API 请求数，0 = 不限
+    /// ip-api.com 免费档为 45 次/分钟，超出会被封 IP
+    #[serde(default = "default_geoip_api_rate_per_minute")]
+    pub geoip_api_rate_per_minute: u32,
 }
 
 fn default_geoip_api_url() -> String {
     "http://ip-api.com/json/{ip}?fields=status,countryCode,city".to_string()
 }
 
+fn default_geoip_api_max_concurrency() -> usize {
+    4
+}
+
+fn default_geoip_api_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_geoip_api_failure_threshold() -> u32 {
+    5
+}
+
+fn default_geoip_api_open_minutes() -> u64 {
+    5
+}
+
+fn default_geoip_api_rate_per_minute() -> u32 {
+    45
+}
+
 impl Default for AnalyticsConfig {
     fn default() -> Self {
         Self {
             maxminddb_path: None,
             geoip_api_url: default_geoip_api_url(),
+            geoip_api_max_concurrency: default_geoip_api_max_concurrency(),
+            geoip_api_timeout_ms: default_geoip_api_timeout_ms(),
+            geoip_api_failure_threshold: default_geoip_api_failure_threshold(),
+            geoip_api_open_minutes: default_geoip_api_open_minutes(),
+            geoip_api_rate_per_minute: default_geoip_api_rate_per_minute(),
         }
     }
 }
@@ -389,7 +550,7 @@ pub struct IpcConfig {
     pub enabled: bool,
 
     /// Socket 路径（Unix）或命名管道路径（Windows）
-    /// Unix 默认: "./shortlinker.sock"
+    /// Unix 默认: "{system.runtime_dir}/shortlinker.sock"
     /// Windows 默认: r"\\.\pipe\shortlinker"
     #[serde(default)]
     pub socket_path: Option<String>,
@@ -409,13 +570,20 @@ pub struct IpcConfig {
     /// 批量操作（导入/导出）超时（秒）
     #[serde(default = "default_ipc_bulk_timeout")]
     pub bulk_timeout: u64,
+
+    /// 慢命令阈值（毫秒），处理超过该时长的命令记录 warn 日志；0 表示关闭
+    #[serde(default = "default_ipc_slow_command_ms")]
+    pub slow_command_ms: u64,
 }
 
 impl IpcConfig {
     /// 获取实际的 socket 路径
     ///
     /// 优先级: CLI --socket 参数 > config.toml > 平台默认值
-    pub fn effective_socket_path(&self) -> String {
+    pub fn effective_socket_path(
+        &self,
+        #[cfg_attr(windows, allow(unused_variables))] system: &SystemConfig,
+    ) -> String {
         // 1. CLI 参数覆盖
         if let Some(override_path) = crate::config::get_ipc_socket_override() {
             return override_path.clone();
@@ -429,7 +597,10 @@ impl IpcConfig {
         // 3. 平台默认值
         #[cfg(unix)]
         {
-            "./shortlinker.sock".to_string()
+            system
+                .runtime_path("shortlinker.sock")
+                .to_string_lossy()
+                .into_owned()
         }
         #[cfg(windows)]
         {
@@ -468,6 +639,9 @@ fn default_ipc_reload_timeout() -> u64 {
 fn default_ipc_bulk_timeout() -> u64 {
     60
 }
+fn default_ipc_slow_command_ms() -> u64 {
+    1000
+}
 
 impl Default for IpcConfig {
     fn default() -> Self {
@@ -478,6 +652,105 @@ impl Default for IpcConfig {
             timeout: default_ipc_timeout(),
             reload_timeout: default_ipc_reload_timeout(),
             bulk_timeout: default_ipc_bulk_timeout(),
+            slow_command_ms: default_ipc_slow_command_ms(),
+        }
+    }
+}
+
+/// 系统路径配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemConfig {
+    /// 运行时可写目录：PID / 锁文件、IPC socket（Unix 默认路径）、崩溃日志
+    ///
+    /// 只读根文件系统的容器中应挂载可写卷（如 emptyDir）到此目录；
+    /// 配置文件与内嵌前端资源只需可读。
+    #[serde(default = "default_runtime_dir")]
+    pub runtime_dir: String,
+}
+
+impl SystemConfig {
+    /// 拼接 runtime_dir 下的文件路径
+    pub fn runtime_path(&self, name: &str) -> std::path::PathBuf {
+        std::path::Path::new(&self.runtime_dir).join(name)
+    }
+}
+
+fn default_runtime_dir() -> String {
+    ".".to_string()
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self {
+            runtime_dir: default_runtime_dir(),
+        }
+    }
+}
+
+/// 插件配置（实验性）
+///
+/// 需要以 `wasm-plugins` feature 编译；未启用该 feature 时配置了插件会在启动时
+/// 按 `fail_mode` 处理（`closed` 拒绝启动，`open` 仅告警）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// redirect 决策阶段调用的 WASM 模块路径（`.wasm` 或 `.wat`），未配置时不启用
+    #[serde(default)]
+    pub redirect_filter: Option<String>,
+
+    /// 插件出错（超时、超内存、trap、输出非法）时的处理：
+    /// `open` 按放行处理，`closed` 返回 503
+    #[serde(default = "default_plugin_fail_mode")]
+    pub fail_mode: String,
+
+    /// 单次调用的执行时间上限（毫秒）
+    #[serde(default = "default_plugin_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// 单个实例的线性内存上限（MiB）
+    #[serde(default = "default_plugin_max_memory_mb")]
+    pub max_memory_mb: u64,
+
+    /// 实例池保留的空闲实例数
+    #[serde(default = "default_plugin_pool_size")]
+    pub pool_size: usize,
+
+    /// 传给插件的请求头（不区分大小写），其余请求头不会暴露给插件
+    #[serde(default = "default_plugin_forward_headers")]
+    pub forward_headers: Vec<String>,
+}
+
+fn default_plugin_fail_mode() -> String {
+    "open".to_string()
+}
+
+fn default_plugin_timeout_ms() -> u64 {
+    10
+}
+
+fn default_plugin_max_memory_mb() -> u64 {
+    16
+}
+
+fn default_plugin_pool_size() -> usize {
+    16
+}
+
+fn default_plugin_forward_headers() -> Vec<String> {
+    ["user-agent", "referer", "accept-language"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            redirect_filter: None,
+            fail_mode: default_plugin_fail_mode(),
+            timeout_ms: default_plugin_timeout_ms(),
+            max_memory_mb: default_plugin_max_memory_mb(),
+            pool_size: default_plugin_pool_size(),
+            forward_headers: default_plugin_forward_headers(),
         }
     }
 }
@@ -557,4 +830,44 @@ mod tests {
         assert!(serialized["database"].get("timeout").is_none());
         assert!(serialized["cache"].get("memory").is_none());
     }
+
+    #[test]
+    fn env_var_defaults_cover_nested_and_renamed_keys() {
+        let vars: std::collections::HashMap<_, _> =
+            StaticConfig::env_var_defaults().into_iter().collect();
+        assert_eq!(vars["SL__SERVER__PORT"], "8080");
+        assert_eq!(
+            vars["SL__DATABASE__DATABASE_URL"],
+            "sqlite://shortlinks.db?mode=rwc"
+        );
+        assert_eq!(vars["SL__CACHE__TYPE"], "memory");
+        assert_eq!(vars["SL__CACHE__MISS_BATCH__ENABLED"], "false");
+        assert!(!vars.contains_key("SL__SERVER__UNIX_SOCKET"));
+    }
+
+    #[test]
+    fn runtime_dir_defaults_to_working_directory() {
+        let config = StaticConfig::default();
+        assert_eq!(config.system.runtime_dir, ".");
+        assert_eq!(
+            config.system.runtime_path("shortlinker.pid"),
+            std::path::Path::new("./shortlinker.pid")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ipc_socket_defaults_under_runtime_dir() {
+        let mut config = StaticConfig::default();
+        assert_eq!(config.ipc_socket_path(), "./shortlinker.sock");
+
+        config.system.runtime_dir = "/run/shortlinker".to_string();
+        assert_eq!(
+            config.ipc_socket_path(),
+            "/run/shortlinker/shortlinker.sock"
+        );
+
+        config.ipc.socket_path = Some("/tmp/custom.sock".to_string());
+        assert_eq!(config.ipc_socket_path(), "/tmp/custom.sock");
+    }
 }
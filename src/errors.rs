@@ -59,6 +59,7 @@ define_shortlinker_errors! {
     AuthTokenExpired("E012", "Token Expired"),
     AuthTokenInvalid("E013", "Token Invalid"),
     AuthRateLimitExceeded("E014", "Rate Limit Exceeded"),
+    AuthForbidden("E015", "Forbidden"),
 
     // ========== E020-E029: 链接业务错误 ==========
     LinkInvalidUrl("E020", "Invalid URL"),
@@ -67,6 +68,7 @@ define_shortlinker_errors! {
     LinkPasswordHashError("E023", "Password Hash Error"),
     LinkInvalidCode("E024", "Invalid Short Code"),
     LinkReservedCode("E025", "Reserved Short Code"),
+    LinkCodeCoolingDown("E026", "Short Code Cooling Down"),
 
     // ========== E030-E039: 导入导出错误（保留，未来实现） ==========
     CsvParseFailed("E030", "CSV Parse Error"),
@@ -90,6 +92,9 @@ define_shortlinker_errors! {
     AnalyticsQueryFailed("E060", "Analytics Query Failed"),
     AnalyticsLinkNotFound("E061", "Analytics Link Not Found"),
     AnalyticsInvalidDateRange("E062", "Analytics Invalid Date Range"),
+
+    // ========== E070-E079: 配额错误 ==========
+    QuotaExceeded("E070", "Quota Exceeded"),
 }
 
 impl ShortlinkerError {
@@ -111,44 +116,35 @@ impl ShortlinkerError {
         format!("{}: {}", self.error_type(), self.message())
     }
 
+    /// 按语言格式化（用于 CLI 模式）
+    ///
+    /// 英文与 [`Self::format_simple`] 相同；其他语言下业务错误以管理面板共用的
+    /// `errors.*` 文案作标题，基础设施错误没有对应文案，仍输出英文类型名。
+    pub fn format_localized(&self, lang: crate::i18n::Lang) -> String {
+        #[cfg(feature = "server")]
+        {
+            use crate::api::services::admin::error_code::ErrorCode;
+            let code = ErrorCode::from(self.clone());
+            if lang != crate::i18n::Lang::En
+                && code != ErrorCode::InternalServerError
+                && let Some(title) = code
+                    .i18n_key()
+                    .and_then(|key| crate::i18n::lookup(lang, key))
+            {
+                return format!("{}: {}", title, self.message());
+            }
+        }
+        #[cfg(not(feature = "server"))]
+        let _ = lang;
+        self.format_simple()
+    }
+
     /// 获取对应的 HTTP 状态码
+    ///
+    /// 经由 ErrorCode 映射，保证与 Admin API 错误目录一致
     #[cfg(feature = "server")]
     pub fn http_status(&self) -> actix_web::http::StatusCode {
-        use actix_web::http::StatusCode;
-        match self {
-            // 400 Bad Request
-            Self::Validation(_)
-            | Self::LinkInvalidUrl(_)
-            | Self::LinkInvalidExpireTime(_)
-            | Self::LinkInvalidCode(_)
-            | Self::LinkReservedCode(_)
-            | Self::InvalidMultipartData(_)
-            | Self::CsvFileMissing(_)
-            | Self::CsvParseFailed(_)
-            | Self::AnalyticsInvalidDateRange(_) => StatusCode::BAD_REQUEST,
-
-            // 401 Unauthorized
-            Self::AuthPasswordInvalid(_)
-            | Self::AuthTokenExpired(_)
-            | Self::AuthTokenInvalid(_) => StatusCode::UNAUTHORIZED,
-
-            // 404 Not Found
-            Self::NotFound(_) | Self::ConfigNotFound(_) | Self::AnalyticsLinkNotFound(_) => {
-                StatusCode::NOT_FOUND
-            }
-
-            // 409 Conflict
-            Self::LinkAlreadyExists(_) => StatusCode::CONFLICT,
-
-            // 429 Too Many Requests
-            Self::AuthRateLimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
-
-            // 503 Service Unavailable
-            Self::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
-
-            // 500 Internal Server Error (default)
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        }
+        crate::api::services::admin::error_code::ErrorCode::from(self.clone()).http_status()
     }
 }
 
@@ -221,6 +217,10 @@ impl ShortlinkerError {
         ShortlinkerError::AuthRateLimitExceeded(msg.into())
     }
 
+    pub fn auth_forbidden<T: Into<String>>(msg: T) -> Self {
+        ShortlinkerError::AuthForbidden(msg.into())
+    }
+
     // 链接业务错误
     pub fn link_invalid_url<T: Into<String>>(msg: T) -> Self {
         ShortlinkerError::LinkInvalidUrl(msg.into())
@@ -246,6 +246,10 @@ impl ShortlinkerError {
         ShortlinkerError::LinkReservedCode(msg.into())
     }
 
+    pub fn link_code_cooling_down<T: Into<String>>(msg: T) -> Self {
+        ShortlinkerError::LinkCodeCoolingDown(msg.into())
+    }
+
     // 导入导出错误
     pub fn csv_parse_failed<T: Into<String>>(msg: T) -> Self {
         ShortlinkerError::CsvParseFailed(msg.into())
@@ -310,6 +314,11 @@ impl ShortlinkerError {
         ShortlinkerError::AnalyticsInvalidDateRange(msg.into())
     }
 
+    // 配额错误
+    pub fn quota_exceeded<T: Into<String>>(msg: T) -> Self {
+        ShortlinkerError::QuotaExceeded(msg.into())
+    }
+
     /// 根据 IPC 传输的 error_code 重建具体错误变体
     ///
     /// 用于 IPC 客户端：将序列化传输的 (error_code, message) 还原为对应的枚举变体，
@@ -332,6 +341,7 @@ impl ShortlinkerError {
             "E012" => ShortlinkerError::AuthTokenExpired(message),
             "E013" => ShortlinkerError::AuthTokenInvalid(message),
             "E014" => ShortlinkerError::AuthRateLimitExceeded(message),
+            "E015" => ShortlinkerError::AuthForbidden(message),
             // 链接业务
             "E020" => ShortlinkerError::LinkInvalidUrl(message),
             "E021" => ShortlinkerError::LinkAlreadyExists(message),
@@ -339,6 +349,7 @@ impl ShortlinkerError {
             "E023" => ShortlinkerError::LinkPasswordHashError(message),
             "E024" => ShortlinkerError::LinkInvalidCode(message),
             "E025" => ShortlinkerError::LinkReservedCode(message),
+            "E026" => ShortlinkerError::LinkCodeCoolingDown(message),
             // 导入导出
             "E030" => ShortlinkerError::CsvParseFailed(message),
             "E031" => ShortlinkerError::CsvGenerationFailed(message),
@@ -358,6 +369,8 @@ impl ShortlinkerError {
             "E060" => ShortlinkerError::AnalyticsQueryFailed(message),
             "E061" => ShortlinkerError::AnalyticsLinkNotFound(message),
             "E062" => ShortlinkerError::AnalyticsInvalidDateRange(message),
+            // 配额
+            "E070" => ShortlinkerError::QuotaExceeded(message),
             _ => ShortlinkerError::InternalError(message),
         }
     }
@@ -403,6 +416,7 @@ impl From<ShortlinkerError> for crate::api::services::admin::error_code::ErrorCo
             ShortlinkerError::AuthTokenExpired(_) => ErrorCode::TokenExpired,
             ShortlinkerError::AuthTokenInvalid(_) => ErrorCode::TokenInvalid,
             ShortlinkerError::AuthRateLimitExceeded(_) => ErrorCode::RateLimitExceeded,
+            ShortlinkerError::AuthForbidden(_) => ErrorCode::Forbidden,
 
             // 链接错误
             ShortlinkerError::LinkInvalidUrl(_) => ErrorCode::LinkInvalidUrl,
@@ -411,6 +425,7 @@ impl From<ShortlinkerError> for crate::api::services::admin::error_code::ErrorCo
             ShortlinkerError::LinkPasswordHashError(_) => ErrorCode::LinkPasswordHashError,
             ShortlinkerError::LinkInvalidCode(_) => ErrorCode::LinkInvalidCode,
             ShortlinkerError::LinkReservedCode(_) => ErrorCode::LinkReservedCode,
+            ShortlinkerError::LinkCodeCoolingDown(_) => ErrorCode::LinkCodeCoolingDown,
 
             // 导入导出错误
             ShortlinkerError::CsvParseFailed(_) => ErrorCode::CsvParseError,
@@ -436,6 +451,9 @@ impl From<ShortlinkerError> for crate::api::services::admin::error_code::ErrorCo
             ShortlinkerError::AnalyticsLinkNotFound(_) => ErrorCode::AnalyticsLinkNotFound,
             ShortlinkerError::AnalyticsInvalidDateRange(_) => ErrorCode::AnalyticsInvalidDateRange,
 
+            // 配额错误
+            ShortlinkerError::QuotaExceeded(_) => ErrorCode::QuotaExceeded,
+
             // 其他基础设施错误 → InternalServerError
             _ => ErrorCode::InternalServerError,
         }
@@ -463,7 +481,7 @@ mod tests {
         assert_eq!(ShortlinkerError::serialization("test").code(), "E009");
         assert_eq!(ShortlinkerError::notify_server("test").code(), "E010");
 
-        // 认证错误 E011-E014
+        // 认证错误 E011-E015
         assert_eq!(
             ShortlinkerError::auth_password_invalid("test").code(),
             "E011"
@@ -474,6 +492,7 @@ mod tests {
             ShortlinkerError::auth_rate_limit_exceeded("test").code(),
             "E014"
         );
+        assert_eq!(ShortlinkerError::auth_forbidden("test").code(), "E015");
 
         // 链接业务错误 E020-E023
         assert_eq!(ShortlinkerError::link_invalid_url("test").code(), "E020");
@@ -516,6 +535,9 @@ mod tests {
         // 通用 HTTP 错误 E050-E051
         assert_eq!(ShortlinkerError::service_unavailable("test").code(), "E050");
         assert_eq!(ShortlinkerError::internal_error("test").code(), "E051");
+
+        // 配额错误 E070
+        assert_eq!(ShortlinkerError::quota_exceeded("test").code(), "E070");
     }
 
     #[test]
@@ -632,6 +654,9 @@ mod tests {
         let err = ShortlinkerError::from_error_code("E025", "reserved".into());
         assert_eq!(err.code(), "E025");
 
+        let err = ShortlinkerError::from_error_code("E026", "cooling down".into());
+        assert_eq!(err.code(), "E026");
+
         let err = ShortlinkerError::from_error_code("E022", "bad time".into());
         assert_eq!(err.code(), "E022");
     }
@@ -655,4 +680,50 @@ mod tests {
         assert_eq!(reconstructed.code(), original.code());
         assert_eq!(reconstructed.message(), original.message());
     }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_http_status_via_error_code() {
+        use actix_web::http::StatusCode;
+        let cases = [
+            (ShortlinkerError::validation("x"), StatusCode::BAD_REQUEST),
+            (
+                ShortlinkerError::link_invalid_url("x"),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                ShortlinkerError::auth_token_expired("x"),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (ShortlinkerError::not_found("x"), StatusCode::NOT_FOUND),
+            (
+                ShortlinkerError::link_already_exists("x"),
+                StatusCode::CONFLICT,
+            ),
+            (
+                ShortlinkerError::link_code_cooling_down("x"),
+                StatusCode::CONFLICT,
+            ),
+            (
+                ShortlinkerError::auth_rate_limit_exceeded("x"),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (ShortlinkerError::auth_forbidden("x"), StatusCode::FORBIDDEN),
+            (
+                ShortlinkerError::quota_exceeded("x"),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (
+                ShortlinkerError::service_unavailable("x"),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                ShortlinkerError::database_operation("x"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+        for (err, status) in cases {
+            assert_eq!(err.http_status(), status, "{}", err.code());
+        }
+    }
 }
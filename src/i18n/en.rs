@@ -0,0 +1,131 @@
+//! 英文文案（基准语言）
+//!
+//! `errors.*` 与管理面板 `en.json` 保持一致；CLI 子命令与参数的英文说明
+//! 来自 clap 文档注释，不在这里重复登记。
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // ========== 错误码（与面板共用） ==========
+    ("errors.badRequest", "Bad request - please check your input"),
+    ("errors.unauthorized", "Unauthorized - please log in again"),
+    ("errors.notFound", "Resource not found"),
+    (
+        "errors.tooManyRequests",
+        "Too many requests - please try again later",
+    ),
+    (
+        "errors.serverError",
+        "Server error - please try again later",
+    ),
+    (
+        "errors.batchSizeTooLarge",
+        "Batch size too large (max 5000 items)",
+    ),
+    ("errors.fileTooLarge", "File too large (max 10MB)"),
+    (
+        "errors.invalidDateFormat",
+        "Invalid date format (use RFC3339, e.g., 2024-01-01T00:00:00Z)",
+    ),
+    (
+        "errors.serviceUnavailable",
+        "Service temporarily unavailable - please try again later",
+    ),
+    (
+        "errors.panelVersionIncompatible",
+        "The admin panel is out of date - reload the page to get the latest version",
+    ),
+    (
+        "errors.authFailed",
+        "Authentication failed - wrong password",
+    ),
+    (
+        "errors.tokenExpired",
+        "Session expired - please log in again",
+    ),
+    ("errors.tokenInvalid", "Invalid session token"),
+    (
+        "errors.csrfInvalid",
+        "Security verification failed - please refresh the page",
+    ),
+    (
+        "errors.forbidden",
+        "Forbidden - you don't have permission to access this resource",
+    ),
+    ("errors.linkNotFound", "Link not found"),
+    ("errors.linkAlreadyExists", "Short code already exists"),
+    ("errors.linkInvalidUrl", "Invalid target URL format"),
+    ("errors.linkInvalidExpireTime", "Invalid expiration time"),
+    ("errors.linkPasswordHashError", "Password processing failed"),
+    ("errors.linkDatabaseError", "Database operation failed"),
+    ("errors.linkEmptyCode", "Short code cannot be empty"),
+    (
+        "errors.linkInvalidCode",
+        "Invalid short code format (only alphanumeric, underscore, hyphen, dot, and slash allowed)",
+    ),
+    (
+        "errors.linkReservedCode",
+        "Short code conflicts with reserved system routes",
+    ),
+    (
+        "errors.linkCodeCoolingDown",
+        "Short code was deleted recently and cannot be reused until its cooldown ends",
+    ),
+    ("errors.importFailed", "Import failed"),
+    ("errors.exportFailed", "Export failed"),
+    ("errors.invalidMultipartData", "Invalid upload data format"),
+    ("errors.fileReadError", "Failed to read file"),
+    ("errors.csvFileMissing", "No CSV file provided"),
+    ("errors.csvParseError", "CSV parse error"),
+    ("errors.csvGenerationError", "CSV generation error"),
+    ("errors.configNotFound", "Configuration not found"),
+    ("errors.configUpdateFailed", "Configuration update failed"),
+    ("errors.configReloadFailed", "Configuration reload failed"),
+    ("errors.analyticsQueryFailed", "Analytics query failed"),
+    (
+        "errors.analyticsLinkNotFound",
+        "Link not found, cannot query analytics",
+    ),
+    ("errors.analyticsInvalidDateRange", "Invalid date range"),
+    ("errors.quotaExceeded", "API token quota exceeded"),
+    // ========== CLI 错误前缀 ==========
+    ("cli.errors.storage", "Storage error"),
+    ("cli.errors.parse", "Parse error"),
+    ("cli.errors.command", "Command error"),
+    ("cli.errors.notFound", "Not found"),
+    ("cli.errors.expired", "Expired"),
+    (
+        "cli.errors.unsupportedLang",
+        "unsupported language '{{lang}}' (expected en or zh)",
+    ),
+    // ========== 帮助页面 ==========
+    ("cli.help.usage", "Usage:"),
+    ("cli.help.commands", "Commands"),
+    ("cli.help.command_value", "COMMAND"),
+    ("cli.help.arguments", "Arguments"),
+    ("cli.help.options", "Options"),
+    ("cli.help.help_flag", "Print help"),
+    ("cli.help.version_flag", "Print version"),
+    (
+        "cli.help.modes",
+        "\
+Modes:
+  shortlinker [OPTIONS]                      Start the HTTP server (default)
+  shortlinker [OPTIONS] <COMMAND> [ARGS]...  Run a management command and exit
+
+Global options (-c/--config, -s/--socket, --via, -v/--verbose, --lang) may appear before or after the command.
+Configuration priority: SL__* environment variables > config file > defaults.
+The config file is ./config.toml unless -c/--config is given.",
+    ),
+    // ========== 顶层参数错误的引导 ==========
+    (
+        "cli.hint.modes",
+        "\
+shortlinker either starts the server or runs a single command:
+  start the server:  shortlinker [-c <FILE>]
+  run a command:     shortlinker [-c <FILE>] <COMMAND> [ARGS]...",
+    ),
+    ("cli.hint.valid_commands", "Valid commands: {{commands}}"),
+    (
+        "cli.hint.config_file",
+        "To start the server with this config file, run: shortlinker -c {{file}}",
+    ),
+];
@@ -0,0 +1,196 @@
+//! 文案目录：CLI 帮助文本与错误提示的本地化
+//!
+//! key 与管理面板（`admin-panel/src/i18n/locales`）共用一套命名：错误码沿用面板的
+//! `errors.*`（见 `ErrorCode::i18n_key`），CLI 专属文案放在 `cli.*` 下，占位符同样
+//! 写作 `{{name}}`。英文是基准语言，其他语言缺少某个 key 时回退英文。
+//!
+//! CLI 子命令与参数的英文说明就是 clap derive 的文档注释，目录里只登记翻译：
+//! - `cli.about`、`cli.args.<参数 id>`：顶层命令
+//! - `cli.commands.<路径>.about` / `.long_about`：子命令说明（含 `--help` 中的示例）
+//! - `cli.args.<路径>.<参数 id>`：子命令参数
+//!
+//! 路径为以 `.` 连接的子命令名，如 `analytics.rebuild-rollups`。
+
+mod en;
+mod zh;
+
+use std::sync::OnceLock;
+
+/// 支持的输出语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Zh,
+}
+
+impl Lang {
+    pub const ALL: &'static [Lang] = &[Lang::En, Lang::Zh];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+        }
+    }
+
+    /// 解析语言标签：`zh`、`zh-CN`、`zh_CN.UTF-8` 均视为中文，`C` / `POSIX` 视为英文
+    pub fn parse(tag: &str) -> Option<Lang> {
+        let primary = tag
+            .trim()
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "zh" => Some(Lang::Zh),
+            _ => None,
+        }
+    }
+
+    /// 按 POSIX 优先级读取 `LC_ALL` > `LC_MESSAGES` > `LANG`，未设置或不支持时为英文
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+            .and_then(|value| Lang::parse(&value))
+            .unwrap_or_default()
+    }
+
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => en::MESSAGES,
+            Lang::Zh => zh::MESSAGES,
+        }
+    }
+}
+
+static CURRENT: OnceLock<Lang> = OnceLock::new();
+
+/// 设置进程的输出语言，启动时调用一次，之后的调用被忽略
+pub fn init(lang: Lang) {
+    let _ = CURRENT.set(lang);
+}
+
+/// 当前输出语言；未调用 [`init`] 时按环境变量判定
+pub fn current() -> Lang {
+    *CURRENT.get_or_init(Lang::from_env)
+}
+
+/// 查找文案，缺少翻译时回退英文，英文也没有时返回 `None`
+///
+/// 目录只有几百条，且只在解析参数与报错时查询，线性查找足够。
+pub fn lookup(lang: Lang, key: &str) -> Option<&'static str> {
+    find(lang.messages(), key).or_else(|| find(en::MESSAGES, key))
+}
+
+/// 同 [`lookup`]，找不到时返回 key 本身，便于在输出中发现遗漏
+pub fn t(lang: Lang, key: &'static str) -> &'static str {
+    lookup(lang, key).unwrap_or(key)
+}
+
+/// 查找文案并替换 `{{name}}` 占位符
+pub fn t_with(lang: Lang, key: &'static str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(t(lang, key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
+
+/// 某个语言目录中登记的全部 key（用于覆盖率测试）
+pub fn keys(lang: Lang) -> impl Iterator<Item = &'static str> {
+    lang.messages().iter().map(|(key, _)| *key)
+}
+
+fn find(messages: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    messages
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_lang_tags() {
+        for (tag, expected) in [
+            ("zh", Some(Lang::Zh)),
+            ("zh-CN", Some(Lang::Zh)),
+            ("zh_CN.UTF-8", Some(Lang::Zh)),
+            ("ZH_TW", Some(Lang::Zh)),
+            ("en_US.UTF-8", Some(Lang::En)),
+            ("C", Some(Lang::En)),
+            ("C.UTF-8", Some(Lang::En)),
+            ("POSIX", Some(Lang::En)),
+            ("fr_FR.UTF-8", None),
+            ("", None),
+        ] {
+            assert_eq!(Lang::parse(tag), expected, "{:?}", tag);
+        }
+    }
+
+    #[test]
+    fn test_fallback_to_english() {
+        assert_eq!(lookup(Lang::Zh, "errors.linkNotFound"), Some("链接不存在"));
+        assert_eq!(
+            lookup(Lang::En, "errors.linkNotFound"),
+            Some("Link not found")
+        );
+        assert_eq!(lookup(Lang::Zh, "no.such.key"), None);
+        assert_eq!(t(Lang::Zh, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(
+            t_with(
+                Lang::En,
+                "cli.hint.valid_commands",
+                &[("commands", "add, list")]
+            ),
+            "Valid commands: add, list"
+        );
+    }
+
+    #[test]
+    fn test_catalogs_have_unique_keys() {
+        for lang in Lang::ALL {
+            let mut seen = HashSet::new();
+            for key in keys(*lang) {
+                assert!(seen.insert(key), "duplicate key {} in {}", key, lang.code());
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_english_key_is_translated() {
+        let zh: HashSet<_> = keys(Lang::Zh).collect();
+        let missing: Vec<_> = keys(Lang::En).filter(|key| !zh.contains(key)).collect();
+        assert!(missing.is_empty(), "missing zh translations: {:?}", missing);
+    }
+
+    #[test]
+    fn test_placeholders_match_english() {
+        fn placeholders(text: &str) -> Vec<&str> {
+            let mut found: Vec<_> = text
+                .split("{{")
+                .skip(1)
+                .filter_map(|rest| rest.split_once("}}").map(|(name, _)| name))
+                .collect();
+            found.sort_unstable();
+            found
+        }
+        for key in keys(Lang::En) {
+            assert_eq!(
+                placeholders(t(Lang::En, key)),
+                placeholders(t(Lang::Zh, key)),
+                "placeholders differ for {}",
+                key
+            );
+        }
+    }
+}
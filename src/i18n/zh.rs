@@ -0,0 +1,541 @@
+//! 简体中文文案
+//!
+//! `errors.*` 与管理面板 `zh.json` 保持一致；`cli.commands.*` / `cli.args.*`
+//! 对应 clap 文档注释的翻译，覆盖率由 `cli::localize` 的测试检查。
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // ========== 错误码（与面板共用） ==========
+    ("errors.badRequest", "错误的请求 - 请检查您的输入"),
+    ("errors.unauthorized", "未授权 - 请重新登录"),
+    ("errors.notFound", "资源未找到"),
+    ("errors.tooManyRequests", "请求过于频繁 - 请稍后重试"),
+    ("errors.serverError", "服务器错误 - 请稍后重试"),
+    (
+        "errors.batchSizeTooLarge",
+        "批量操作数量过大（最多 5000 条）",
+    ),
+    ("errors.fileTooLarge", "文件过大（最大 10MB）"),
+    (
+        "errors.invalidDateFormat",
+        "日期格式无效（使用 RFC3339，如 2024-01-01T00:00:00Z）",
+    ),
+    ("errors.serviceUnavailable", "服务暂时不可用 - 请稍后重试"),
+    (
+        "errors.panelVersionIncompatible",
+        "管理面板版本过旧 - 请刷新页面加载最新版本",
+    ),
+    ("errors.authFailed", "认证失败 - 密码错误"),
+    ("errors.tokenExpired", "登录已过期 - 请重新登录"),
+    ("errors.tokenInvalid", "登录凭证无效"),
+    ("errors.csrfInvalid", "安全验证失败 - 请刷新页面重试"),
+    ("errors.forbidden", "禁止访问 - 您没有访问此资源的权限"),
+    ("errors.linkNotFound", "链接不存在"),
+    ("errors.linkAlreadyExists", "短代码已存在"),
+    ("errors.linkInvalidUrl", "目标网址格式无效"),
+    ("errors.linkInvalidExpireTime", "过期时间无效"),
+    ("errors.linkPasswordHashError", "密码处理失败"),
+    ("errors.linkDatabaseError", "数据库操作失败"),
+    ("errors.linkEmptyCode", "短代码不能为空"),
+    (
+        "errors.linkInvalidCode",
+        "短代码格式无效（仅支持字母、数字、下划线、连字符、点和斜杠）",
+    ),
+    ("errors.linkReservedCode", "短代码与系统保留路由冲突"),
+    (
+        "errors.linkCodeCoolingDown",
+        "该短码刚被删除，冷却期结束前不能重新使用",
+    ),
+    ("errors.importFailed", "导入失败"),
+    ("errors.exportFailed", "导出失败"),
+    ("errors.invalidMultipartData", "无效的上传数据格式"),
+    ("errors.fileReadError", "文件读取失败"),
+    ("errors.csvFileMissing", "未提供 CSV 文件"),
+    ("errors.csvParseError", "CSV 解析错误"),
+    ("errors.csvGenerationError", "CSV 生成错误"),
+    ("errors.configNotFound", "配置项不存在"),
+    ("errors.configUpdateFailed", "配置更新失败"),
+    ("errors.configReloadFailed", "配置重载失败"),
+    ("errors.analyticsQueryFailed", "分析数据查询失败"),
+    (
+        "errors.analyticsLinkNotFound",
+        "链接不存在，无法查询统计数据",
+    ),
+    ("errors.analyticsInvalidDateRange", "日期范围无效"),
+    ("errors.quotaExceeded", "API Token 配额已用尽"),
+    // ========== CLI 错误前缀 ==========
+    ("cli.errors.storage", "存储错误"),
+    ("cli.errors.parse", "解析错误"),
+    ("cli.errors.command", "命令错误"),
+    ("cli.errors.notFound", "未找到"),
+    ("cli.errors.expired", "已过期"),
+    (
+        "cli.errors.unsupportedLang",
+        "不支持的语言 '{{lang}}'（可选 en 或 zh）",
+    ),
+    // ========== 帮助页面 ==========
+    ("cli.help.usage", "用法："),
+    ("cli.help.commands", "命令"),
+    ("cli.help.command_value", "命令"),
+    ("cli.help.arguments", "参数"),
+    ("cli.help.options", "选项"),
+    ("cli.help.help_flag", "显示帮助"),
+    ("cli.help.version_flag", "显示版本"),
+    (
+        "cli.help.modes",
+        "\
+运行模式：
+  shortlinker [选项]                   启动 HTTP 服务（默认）
+  shortlinker [选项] <命令> [参数]...  执行一条管理命令后退出
+
+全局选项（-c/--config、-s/--socket、--via、-v/--verbose、--lang）可以写在命令之前或之后。
+配置优先级：SL__* 环境变量 > 配置文件 > 默认值。
+未指定 -c/--config 时读取 ./config.toml。",
+    ),
+    // ========== 顶层参数错误的引导 ==========
+    (
+        "cli.hint.modes",
+        "\
+shortlinker 要么启动服务，要么执行一条命令：
+  启动服务：  shortlinker [-c <文件>]
+  执行命令：  shortlinker [-c <文件>] <命令> [参数]...",
+    ),
+    ("cli.hint.valid_commands", "可用命令：{{commands}}"),
+    (
+        "cli.hint.config_file",
+        "如需使用该配置文件启动服务，请运行：shortlinker -c {{file}}",
+    ),
+    // ========== 顶层命令 ==========
+    ("cli.about", "高性能短链接服务"),
+    ("cli.args.config", "加载指定的配置文件，代替 ./config.toml"),
+    (
+        "cli.args.socket",
+        "覆盖 IPC socket 路径（Unix）或命名管道路径（Windows）",
+    ),
+    (
+        "cli.args.via",
+        "命令访问数据的方式：ipc（只经运行中的服务）、direct（只直连数据库）或 auto",
+    ),
+    (
+        "cli.args.verbose",
+        "输出每条命令实际使用的路径（IPC 或直连数据库）",
+    ),
+    (
+        "cli.args.lang",
+        "帮助与提示信息的语言（en、zh），默认按 LC_ALL / LC_MESSAGES / LANG 判定",
+    ),
+    // add
+    ("cli.commands.add.about", "添加短链接"),
+    (
+        "cli.commands.add.long_about",
+        "添加短链接。\n\n用法：add [短码] <目标网址>\n\n示例：add docs https://docs.example.com --expire 7d",
+    ),
+    ("cli.args.add.args", "位置参数：`[短码] <目标网址>`"),
+    ("cli.args.add.force", "强制覆盖已存在的短码"),
+    (
+        "cli.args.add.expire",
+        "过期时间：RFC3339、`2024-12-31 18:00` 或 `2024-12-31`（按 `links.expiry_timezone` 解释，只有日期时为当天结束）、相对时间如 `1d`、`now`（立即过期）或 `never`（永不过期）",
+    ),
+    ("cli.args.add.password", "访问密码"),
+    (
+        "cli.args.add.analytics_level",
+        "点击统计级别：inherit、none、count_only、aggregate 或 full",
+    ),
+    (
+        "cli.args.add.extras",
+        "自定义扩展字段，JSON 对象，如 `{\"ticket\":\"JIRA-123\"}`",
+    ),
+    (
+        "cli.args.add.override_cooldown",
+        "复用仍处于删除冷却期的短码",
+    ),
+    // remove
+    ("cli.commands.remove.about", "删除短链接"),
+    ("cli.args.remove.short_code", "要删除的短码"),
+    // update
+    ("cli.commands.update.about", "更新短链接"),
+    ("cli.args.update.short_code", "要更新的短码"),
+    ("cli.args.update.target_url", "新的目标网址"),
+    (
+        "cli.args.update.expire",
+        "新的过期时间（格式同 `add --expire`；省略时保持不变，`never` 清除过期时间）",
+    ),
+    ("cli.args.update.password", "新的访问密码"),
+    (
+        "cli.args.update.analytics_level",
+        "新的点击统计级别（省略时保持不变）",
+    ),
+    (
+        "cli.args.update.extras",
+        "新的自定义扩展字段，JSON 对象（省略时保持不变，`{}` 清空）",
+    ),
+    // extend
+    ("cli.commands.extend.about", "批量延长链接的过期时间"),
+    (
+        "cli.args.extend.codes",
+        "要延期的短码；省略时由 `--search` 选择链接",
+    ),
+    (
+        "cli.args.extend.search",
+        "选择短码或目标网址包含该关键字的链接",
+    ),
+    (
+        "cli.args.extend.by",
+        "在当前过期时间上增加的时长（如 `7d`、`1d12h`）",
+    ),
+    (
+        "cli.args.extend.to",
+        "直接把过期时间设为该时间（格式同 `add --expire`）",
+    ),
+    (
+        "cli.args.extend.include_permanent",
+        "同时处理永不过期的链接（`--by` 从当前时间起算）",
+    ),
+    (
+        "cli.args.extend.revive_from_now",
+        "已过期的链接从当前时间起算，而不是从原过期时间起算",
+    ),
+    ("cli.args.extend.dry_run", "只显示结果，不写入"),
+    // generate
+    (
+        "cli.commands.generate.about",
+        "按模板生成链接，`--var` 取值的每种组合生成一条",
+    ),
+    (
+        "cli.commands.generate.long_about",
+        "按模板生成链接，`--var` 取值的每种组合生成一条。\n\n示例：generate --template 'https://example.com/?utm_source={source}&utm_content={content}' --var source=tv,radio --var content=a,b --code-template 'q4-{source}-{content}'",
+    ),
+    (
+        "cli.args.generate.template",
+        "目标网址模板；`{name}` 依次替换为变量 `name` 的每个取值",
+    ),
+    (
+        "cli.args.generate.vars",
+        "模板变量，格式为 `name=value1,value2,...`（可重复）",
+    ),
+    (
+        "cli.args.generate.code_template",
+        "短码模板（如 `q4-{source}`）；省略时使用随机短码",
+    ),
+    ("cli.args.generate.force", "覆盖已存在的短码"),
+    (
+        "cli.args.generate.expire",
+        "所有生成链接的过期时间（格式同 `add --expire`）",
+    ),
+    ("cli.args.generate.password", "所有生成链接的访问密码"),
+    ("cli.args.generate.yes", "创建前不再确认"),
+    (
+        "cli.args.generate.dry_run",
+        "只打印短码 → 网址的对应关系，不创建",
+    ),
+    // archive / unarchive
+    (
+        "cli.commands.archive.about",
+        "归档短链接（跳转返回 410，保留点击统计）",
+    ),
+    (
+        "cli.args.archive.codes",
+        "要归档的短码；省略时由 `--search` 选择链接",
+    ),
+    (
+        "cli.args.archive.search",
+        "选择短码或目标网址包含该关键字的链接",
+    ),
+    ("cli.commands.unarchive.about", "恢复已归档的短链接"),
+    ("cli.args.unarchive.short_code", "要恢复的短码"),
+    // sample
+    (
+        "cli.commands.sample.about",
+        "均匀随机抽样输出短链接（用于抽查）",
+    ),
+    ("cli.args.sample.n", "样本数量（最多 1000）"),
+    (
+        "cli.args.sample.seed",
+        "随机种子；复用上次输出的种子可以得到相同的样本",
+    ),
+    (
+        "cli.args.sample.search",
+        "只在短码或目标网址包含该关键字的链接中抽样",
+    ),
+    ("cli.args.sample.output", "输出格式"),
+    // list
+    ("cli.commands.list.about", "列出所有短链接"),
+    // resolve
+    (
+        "cli.commands.resolve.about",
+        "逐行打印短码对应的目标网址（供脚本使用）",
+    ),
+    (
+        "cli.commands.resolve.long_about",
+        "逐行打印短码对应的目标网址（供脚本使用）。\n\n短码不存在时退出码为 3，链接已过期时为 6。\n诊断信息输出到 stderr，stdout 只包含结果。\n\n示例：resolve docs promo --json",
+    ),
+    ("cli.args.resolve.codes", "要解析的短码，按给定顺序输出"),
+    ("cli.args.resolve.stdin", "从标准输入读取短码，每行一个"),
+    (
+        "cli.args.resolve.json",
+        "以 JSON 输出全部字段，每行一个对象",
+    ),
+    (
+        "cli.args.resolve.allow_expired",
+        "已过期的链接仍然输出目标网址",
+    ),
+    (
+        "cli.args.resolve.error_marker",
+        "无法解析的短码在 stdout 上输出的内容（默认为空行）",
+    ),
+    // audit-expiry
+    (
+        "cli.commands.audit-expiry.about",
+        "检查链接的过期时间，不做修改",
+    ),
+    (
+        "cli.commands.audit-expiry.long_about",
+        "检查链接的过期时间，不做修改。\n\n`--suspect-midnight-utc` 列出恰好在 UTC 00:00:00 过期的链接——在引入\n`links.expiry_timezone` 之前，选择的日期常被存成这个时刻。\n用 `extend <短码> --to <日期>` 修正。\n\n示例：audit-expiry --suspect-midnight-utc --json",
+    ),
+    (
+        "cli.args.audit-expiry.suspect_midnight_utc",
+        "列出过期时间恰好为 UTC 零点的链接",
+    ),
+    ("cli.args.audit-expiry.json", "以 JSON 输出，每行一个对象"),
+    // audit-targets
+    (
+        "cli.commands.audit-targets.about",
+        "检查链接的目标网址，不做修改",
+    ),
+    (
+        "cli.commands.audit-targets.long_about",
+        "检查链接的目标网址，不做修改。\n\n`--credentials` 列出目标网址带有凭据（`user:pass@` 或\n`links.credential_params` 中的参数）的链接，并给出移除凭据后的网址。\n用 `update <短码> <网址>` 修正。\n\n示例：audit-targets --credentials --json",
+    ),
+    (
+        "cli.args.audit-targets.credentials",
+        "列出目标网址包含凭据的链接",
+    ),
+    ("cli.args.audit-targets.json", "以 JSON 输出，每行一个对象"),
+    // export / import
+    ("cli.commands.export.about", "导出链接到 CSV 文件"),
+    (
+        "cli.args.export.file_path",
+        "输出路径或 s3://bucket/path（需启用 `s3` feature），默认使用带时间戳的文件名",
+    ),
+    ("cli.commands.import.about", "从 CSV 文件导入链接"),
+    (
+        "cli.args.import.file_path",
+        "输入文件路径或 s3://bucket/path（需启用 `s3` feature）",
+    ),
+    ("cli.args.import.force", "强制覆盖已存在的链接"),
+    (
+        "cli.args.import.delimiter",
+        "字段分隔符：','、';' 或 'tab'（默认根据表头自动识别）",
+    ),
+    (
+        "cli.args.import.transform",
+        "TOML 转换配置文件：列名映射与取值转换，在校验前逐行应用",
+    ),
+    (
+        "cli.args.import.dry_run",
+        "只输出转换后的前 10 行与校验结果，不导入",
+    ),
+    // status
+    ("cli.commands.status.about", "通过 IPC 查看服务状态"),
+    // reset-password
+    ("cli.commands.reset-password.about", "重置管理员密码"),
+    (
+        "cli.args.reset-password.password",
+        "新密码，省略时交互式输入",
+    ),
+    ("cli.args.reset-password.stdin", "从标准输入读取密码"),
+    // token
+    ("cli.commands.token.about", "管理 Admin Token"),
+    (
+        "cli.commands.token.rotate.about",
+        "生成新的 Admin Token，旧 Token 在宽限期内仍然有效",
+    ),
+    (
+        "cli.args.token.rotate.revoke_now",
+        "立即吊销旧 Token，不保留宽限期",
+    ),
+    (
+        "cli.args.token.rotate.grace_hours",
+        "宽限期（小时），默认取 `auth.token_grace_hours`",
+    ),
+    // bench
+    ("cli.commands.bench.about", "对运行中的服务进行跳转压测"),
+    (
+        "cli.commands.bench.long_about",
+        "对运行中的服务进行跳转压测。\n\n从本地数据库抽样真实短码，并混入不存在的短码。\n\n示例：bench --url http://127.0.0.1:8080 --duration 1m --concurrency 100",
+    ),
+    ("cli.args.bench.url", "被压测服务的基础 URL"),
+    ("cli.args.bench.duration", "压测时长（`30s`、`5m`、`1h`）"),
+    ("cli.args.bench.concurrency", "并发 worker 数"),
+    ("cli.args.bench.distribution", "短码访问分布"),
+    (
+        "cli.args.bench.hit_ratio",
+        "命中已存在短码的请求比例（0.0 - 1.0）",
+    ),
+    (
+        "cli.args.bench.no_analytics_impact",
+        "要求服务跳过点击统计（需开启 `server.allow_bench_header`）",
+    ),
+    ("cli.args.bench.json", "以 JSON 输出报告"),
+    // generate-deploy
+    (
+        "cli.commands.generate-deploy.about",
+        "生成当前版本的部署清单",
+    ),
+    (
+        "cli.commands.generate-deploy.long_about",
+        "生成当前版本的部署清单。\n\n环境变量取自内置的启动配置 schema，与当前二进制一致。\n已存在的文件不会被覆盖，除非指定 --force。\n\n示例：generate-deploy --target docker-compose --database postgres -o ./deploy/",
+    ),
+    ("cli.args.generate-deploy.target", "部署目标"),
+    ("cli.args.generate-deploy.database", "清单使用的数据库"),
+    ("cli.args.generate-deploy.output", "输出目录"),
+    ("cli.args.generate-deploy.image", "容器镜像（不含 tag）"),
+    (
+        "cli.args.generate-deploy.tag",
+        "镜像 tag，默认为当前版本对应的 tag",
+    ),
+    (
+        "cli.args.generate-deploy.port",
+        "服务监听并对外暴露的 HTTP 端口",
+    ),
+    (
+        "cli.args.generate-deploy.db_password",
+        "写入清单的 PostgreSQL 密码（请使用 URL 安全字符）",
+    ),
+    (
+        "cli.args.generate-deploy.health_token",
+        "健康检查使用的 Bearer Token，需与 `api.health_token` 设为相同值",
+    ),
+    ("cli.args.generate-deploy.force", "覆盖已存在的文件"),
+    // config
+    ("cli.commands.config.about", "管理配置"),
+    ("cli.commands.config.generate.about", "生成示例配置文件"),
+    (
+        "cli.args.config.generate.output_path",
+        "输出路径，默认为 `config.example.toml`",
+    ),
+    ("cli.args.config.generate.force", "不经确认直接覆盖"),
+    ("cli.commands.config.list.about", "列出配置项"),
+    ("cli.args.config.list.category", "按分类筛选"),
+    ("cli.args.config.list.json", "以 JSON 输出"),
+    ("cli.commands.config.get.about", "读取配置项"),
+    ("cli.args.config.get.key", "配置键"),
+    ("cli.args.config.get.json", "以 JSON 输出"),
+    ("cli.commands.config.set.about", "修改配置项"),
+    ("cli.args.config.set.key", "配置键"),
+    ("cli.args.config.set.value", "新的值"),
+    ("cli.commands.config.reset.about", "把配置项恢复为默认值"),
+    ("cli.args.config.reset.key", "配置键"),
+    ("cli.commands.config.export.about", "导出配置"),
+    (
+        "cli.args.config.export.file_path",
+        "输出文件路径，默认输出到 stdout",
+    ),
+    ("cli.commands.config.import.about", "导入配置"),
+    ("cli.args.config.import.file_path", "输入文件路径"),
+    ("cli.args.config.import.force", "不经确认直接覆盖"),
+    // analytics
+    ("cli.commands.analytics.about", "点击统计数据工具"),
+    (
+        "cli.commands.analytics.export.about",
+        "把统计表导出为 CSV 或 Parquet",
+    ),
+    ("cli.args.analytics.export.table", "要导出的表"),
+    (
+        "cli.args.analytics.export.from",
+        "起始时间（RFC3339 或 YYYY-MM-DD），默认为 30 天前",
+    ),
+    (
+        "cli.args.analytics.export.to",
+        "结束时间（RFC3339 或 YYYY-MM-DD，含当天），默认为当前时间",
+    ),
+    ("cli.args.analytics.export.format", "输出格式"),
+    ("cli.args.analytics.export.counts", "汇总表计数列的编码方式"),
+    (
+        "cli.args.analytics.export.output",
+        "输出路径或 s3://bucket/path（需启用 `s3` feature）；省略时 CSV 输出到 stdout",
+    ),
+    (
+        "cli.commands.analytics.amend.about",
+        "删除某个短链的污染点击日志，并订正其汇总与点击数",
+    ),
+    (
+        "cli.commands.analytics.amend.long_about",
+        "删除某个短链的污染点击日志，并订正其汇总与点击数。\n\n示例：analytics amend --code promo --from 2026-10-01 --to 2026-10-07 --remove-ip-cidr 1.2.3.0/24 --dry-run",
+    ),
+    ("cli.args.analytics.amend.code", "要订正的短码"),
+    (
+        "cli.args.analytics.amend.from",
+        "起始时间（RFC3339 或 YYYY-MM-DD）",
+    ),
+    (
+        "cli.args.analytics.amend.to",
+        "结束时间（RFC3339 或 YYYY-MM-DD，含当天）",
+    ),
+    (
+        "cli.args.analytics.amend.remove_ip_cidrs",
+        "删除客户端 IP 属于该网段的点击日志（可重复）",
+    ),
+    ("cli.args.analytics.amend.dry_run", "只显示会影响的行数"),
+    (
+        "cli.commands.analytics.rebuild-rollups.about",
+        "从天汇总重建周汇总或月汇总",
+    ),
+    (
+        "cli.commands.analytics.rebuild-rollups.long_about",
+        "从天汇总重建周汇总或月汇总。\n\n修改 `analytics.week_starts_on` 后执行 `--granularity week`。\n\n示例：analytics rebuild-rollups --granularity week --from 2026-01-01",
+    ),
+    (
+        "cli.args.analytics.rebuild-rollups.granularity",
+        "要重建的汇总粒度",
+    ),
+    (
+        "cli.args.analytics.rebuild-rollups.from",
+        "重建的第一天（YYYY-MM-DD），默认为保留的最早一天天汇总",
+    ),
+    (
+        "cli.args.analytics.rebuild-rollups.to",
+        "重建的最后一天（YYYY-MM-DD，含当天），默认为今天",
+    ),
+    // migrate
+    ("cli.commands.migrate.about", "查看或回滚数据库迁移"),
+    (
+        "cli.commands.migrate.status.about",
+        "显示已应用与待执行的迁移，以及当前程序能否启动",
+    ),
+    (
+        "cli.commands.migrate.down.about",
+        "回滚 `--to` 之后应用的所有迁移",
+    ),
+    (
+        "cli.commands.migrate.down.long_about",
+        "回滚 `--to` 之后应用的所有迁移。\n\n请使用应用了这些迁移的程序版本执行回滚，再启动旧版本。\n\n示例：migrate down --to m20261016_000006 --dry-run",
+    ),
+    (
+        "cli.args.migrate.down.to",
+        "要保留的迁移（完整名称或唯一前缀）",
+    ),
+    (
+        "cli.args.migrate.down.dry_run",
+        "只显示回滚计划和将被删除的数据",
+    ),
+    ("cli.args.migrate.down.yes", "回滚前不再确认"),
+    (
+        "cli.args.migrate.down.backup",
+        "SQLite 备份路径或 s3://bucket/path，默认为 `<数据库文件>.<时间戳>.bak`",
+    ),
+    // db
+    ("cli.commands.db.about", "数据库维护工具"),
+    (
+        "cli.commands.db.maintain.about",
+        "回收空间并刷新查询规划器统计信息",
+    ),
+    (
+        "cli.commands.db.maintain.long_about",
+        "回收空间并刷新查询规划器统计信息。\n\nSQLite：PRAGMA optimize（--full 时为 VACUUM）。PostgreSQL：VACUUM ANALYZE（--full 时为 VACUUM FULL）。MySQL：ANALYZE TABLE（--full 时为 OPTIMIZE TABLE）。\n\n示例：db maintain --full --yes",
+    ),
+    (
+        "cli.args.db.maintain.full",
+        "重写表以把空闲空间归还给操作系统（会锁表；SQLite 需要先停止服务）",
+    ),
+    ("cli.args.db.maintain.yes", "执行 --full 前不再确认"),
+];
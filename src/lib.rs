@@ -7,6 +7,7 @@
 //! - **server**: HTTP server mode (default)
 //! - **cli**: Command-line interface
 //! - **metrics**: Prometheus metrics export
+//! - **wasm-plugins**: Experimental WASM redirect filter plugins
 //! - **full**: All features enabled
 //!
 //! # Architecture
@@ -16,6 +17,7 @@
 //! - `api`: HTTP services and middleware
 //! - `interfaces`: Command-line interface
 //! - `config`: Configuration management
+//! - `i18n`: Localized CLI help and error messages
 //! - `runtime`: Application lifecycle and execution modes
 //! - `system`: Platform abstraction and system utilities
 
@@ -25,6 +27,7 @@ pub mod cli;
 pub mod client;
 pub mod config;
 pub mod errors;
+pub mod i18n;
 pub mod metrics;
 pub mod runtime;
 pub mod services;
@@ -8,7 +8,6 @@
 
 use aster_forge_logging::init_logging;
 use aster_forge_panic::PanicHookConfig;
-use clap::Parser;
 
 use shortlinker::cli::Cli;
 
@@ -17,9 +16,11 @@ use shortlinker::cli::Cli;
 /// # Mode Selection
 /// - `./shortlinker <command>` -> CLI mode (if compiled with cli feature)
 /// - `./shortlinker` -> Server mode (default, if compiled with server feature)
+/// - Unrecognized top-level arguments are rejected with a list of valid commands
 ///
 /// # Configuration
 /// Priority: ENV > .env > config.toml > default values
+/// - `-c/--config <FILE>` replaces `config.toml` (the file must exist)
 /// - `.env` file in current directory (if exists)
 /// - Environment variables with prefix "SL__" override TOML values
 /// - Example: SL__SERVER__PORT=9999
@@ -29,25 +30,44 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     // Parse command-line arguments using clap
-    let cli = Cli::parse();
+    let cli = Cli::parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit());
+    shortlinker::i18n::init(cli.lang.unwrap_or_default());
 
+    // Apply CLI config path override before loading configuration
+    if let Some(config_path) = &cli.config {
+        if !std::path::Path::new(config_path).is_file() {
+            eprintln!("Error: config file not found: {}", config_path);
+            std::process::exit(2);
+        }
+        shortlinker::config::set_config_path_override(config_path.clone());
+    }
+
+    // Initialize configuration system
+    shortlinker::config::init_config();
+    let config = shortlinker::config::get_config();
+
+    // Crash log lives in the runtime directory (writable even with a read-only root fs)
     aster_forge_panic::install_panic_hook(
         PanicHookConfig::new(
             "shortlinker",
             env!("CARGO_PKG_VERSION"),
             "https://github.com/AptS-1547/shortlinker",
         )
-        .with_crash_log_path("crash.log"),
+        .with_crash_log_path(
+            config
+                .system
+                .runtime_path("crash.log")
+                .to_string_lossy()
+                .into_owned(),
+        ),
     );
 
-    // Initialize configuration system
-    shortlinker::config::init_config();
-    let config = shortlinker::config::get_config();
-
     // Apply CLI socket override if specified
     if let Some(socket_path) = cli.socket {
         shortlinker::config::set_ipc_socket_override(socket_path);
     }
+    shortlinker::client::set_transport(cli.via.into());
+    shortlinker::client::set_verbose(cli.verbose);
 
     // Run appropriate mode based on command
     match cli.command {
@@ -56,7 +76,7 @@ async fn main() -> anyhow::Result<()> {
             {
                 if let Err(e) = shortlinker::cli::run_cli_command(cmd).await {
                     eprintln!("{}", e.format_colored());
-                    std::process::exit(1);
+                    std::process::exit(e.exit_code());
                 }
             }
 
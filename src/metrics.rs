@@ -22,6 +22,8 @@ pub trait MetricsRecorder: Send + Sync {
 
     fn inc_clicks_flush(&self, trigger: &str, status: &str) {}
 
+    fn inc_clicks_privacy_opt_out(&self, mode: &str) {}
+
     fn inc_cache_hit(&self, layer: &str) {}
 
     fn inc_cache_miss(&self, layer: &str) {}
@@ -30,11 +32,53 @@ pub trait MetricsRecorder: Send + Sync {
 
     fn set_cache_entries(&self, layer: &str, count: f64) {}
 
+    fn inc_cache_oversize_skipped(&self, policy: &str) {}
+
+    fn inc_cache_stale_fill(&self, kind: &str) {}
+
     fn inc_bloom_false_positive(&self) {}
 
+    fn inc_bloom_lookup(&self, outcome: &str) {}
+
+    fn set_bloom_false_positive_rate(&self, rate: f64) {}
+
+    fn inc_bloom_capacity_alert(&self) {}
+
+    fn inc_cache_miss_load(&self, mode: &str) {}
+
+    fn observe_miss_batch_size(&self, size: f64) {}
+
     fn inc_redirect(&self, status: &str) {}
 
+    fn inc_redirect_delayed(&self, reason: &str) {}
+
+    fn inc_redirect_filter(&self, result: &str) {}
+
     fn inc_auth_failure(&self, method: &str) {}
+
+    fn inc_auth_deprecated_token(&self, method: &str) {}
+
+    fn inc_firewall_hit(&self, rule: &str, action: &str) {}
+
+    fn inc_auto_ban_event(&self, event: &str) {}
+
+    fn inc_auto_ban_rejected(&self) {}
+
+    fn inc_click_anomaly_alert(&self, kind: &str) {}
+
+    fn set_geoip_circuit_state(&self, state: f64) {}
+
+    fn inc_geoip_lookup_skipped(&self, reason: &str) {}
+
+    fn set_background_task_state(&self, task: &str, state: f64) {}
+
+    fn inc_background_task_panic(&self, task: &str) {}
+
+    fn inc_ipc_command(&self, command: &str, status: &str) {}
+
+    fn observe_ipc_command_duration(&self, command: &str, duration_secs: f64) {}
+
+    fn set_ipc_commands_in_flight(&self, count: f64) {}
 }
 
 /// Metrics implementation used by tests and builds without the `metrics` feature.
@@ -83,6 +127,12 @@ mod product {
                 "Total click events dropped before persistence.",
                 &["reason"],
             ),
+            clicks_privacy_opt_out_total: counter(
+                "shortlinker_clicks",
+                "privacy_opt_out_total",
+                "Total clicks not recorded in detail because of DNT or GPC request headers.",
+                &["mode"],
+            ),
             cache_hits_total: counter(
                 "shortlinker_cache",
                 "hits_total",
@@ -108,24 +158,152 @@ mod product {
                 "Current number of cache entries by cache layer.",
                 &["layer"],
             ),
+            cache_oversize_skipped_total: counter(
+                "shortlinker_cache",
+                "oversize_skipped_total",
+                "Total cache entries kept out of L1 for exceeding the entry size limit.",
+                &["policy"],
+            ),
+            cache_stale_fills_total: counter(
+                "shortlinker_cache",
+                "stale_fills_total",
+                "Total storage backfills discarded because the link was written while loading.",
+                &["kind"],
+            ),
+            cache_miss_loads_total: counter(
+                "shortlinker_cache",
+                "miss_loads_total",
+                "Total database lookups issued for cache misses by mode.",
+                &["mode"],
+            ),
+            cache_miss_batch_size: histogram_with_buckets(
+                "shortlinker_cache",
+                "miss_batch_size",
+                "Number of short codes merged into one micro-batched miss lookup.",
+                &[],
+                &[1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0],
+            ),
             redirects_total: counter(
                 "shortlinker_redirects",
                 "total",
                 "Total redirect responses by status.",
                 &["status"],
             ),
+            redirects_delayed_total: counter(
+                "shortlinker_redirects",
+                "delayed_total",
+                "Total not-found redirect responses delayed by constant-time padding or tarpit.",
+                &["reason"],
+            ),
+            redirect_filter_decisions_total: counter(
+                "shortlinker_redirect_filter",
+                "decisions_total",
+                "Total redirect filter plugin calls by result.",
+                &["result"],
+            ),
             bloom_filter_false_positives_total: counter(
                 "shortlinker_bloom_filter",
                 "false_positives_total",
                 "Total Bloom filter false positives.",
                 &[],
             ),
+            bloom_filter_lookups_total: counter(
+                "shortlinker_bloom_filter",
+                "lookups_total",
+                "Total Bloom filter decisions on the redirect path by outcome.",
+                &["outcome"],
+            ),
+            bloom_filter_false_positive_rate: gauge(
+                "shortlinker_bloom_filter",
+                "false_positive_rate",
+                "Observed Bloom filter false-positive rate since the last rebuild.",
+                &[],
+            ),
+            bloom_filter_capacity_alerts_total: counter(
+                "shortlinker_bloom_filter",
+                "capacity_alerts_total",
+                "Total alerts raised for a Bloom filter false-positive rate above the threshold.",
+                &[],
+            ),
             auth_failures_total: counter(
                 "shortlinker_auth",
                 "failures_total",
                 "Total authentication failures by method.",
                 &["method"],
             ),
+            auth_deprecated_token_total: counter(
+                "shortlinker_auth",
+                "deprecated_token_total",
+                "Total authentications using the previous admin token during its grace period.",
+                &["method"],
+            ),
+            firewall_hits_total: counter(
+                "shortlinker_firewall",
+                "hits_total",
+                "Total firewall rule hits by rule name and action.",
+                &["rule", "action"],
+            ),
+            auto_ban_events_total: counter(
+                "shortlinker_auto_ban",
+                "events_total",
+                "Total 404-flood auto-ban events (ban, expire, unban).",
+                &["event"],
+            ),
+            auto_ban_rejected_total: counter(
+                "shortlinker_auto_ban",
+                "rejected_total",
+                "Total requests rejected because the client IP is auto-banned.",
+                &[],
+            ),
+            click_anomaly_alerts_total: counter(
+                "shortlinker_click_anomaly",
+                "alerts_total",
+                "Total click anomaly alerts fired by kind.",
+                &["kind"],
+            ),
+            geoip_circuit_state: gauge(
+                "shortlinker_geoip",
+                "circuit_state",
+                "External GeoIP API circuit breaker state (0 = closed, 1 = open, 2 = half-open).",
+                &[],
+            ),
+            geoip_lookups_skipped_total: counter(
+                "shortlinker_geoip",
+                "lookups_skipped_total",
+                "Total external GeoIP API lookups skipped by the guard by reason.",
+                &["reason"],
+            ),
+            background_task_state: gauge(
+                "shortlinker_background_task",
+                "state",
+                "Background task state (0 = running, 1 = stalled, 2 = restarting, 3 = dead, 4 = stopped).",
+                &["task"],
+            ),
+            background_task_panics_total: counter(
+                "shortlinker_background_task",
+                "panics_total",
+                "Total panics caught by the background task supervisor.",
+                &["task"],
+            ),
+            ipc_commands_total: counter(
+                "shortlinker_ipc",
+                "commands_total",
+                "Total IPC commands handled by command and status.",
+                &["command", "status"],
+            ),
+            ipc_command_duration_seconds: histogram_with_buckets(
+                "shortlinker_ipc",
+                "command_duration_seconds",
+                "IPC command handling duration in seconds.",
+                &["command"],
+                &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0],
+            ),
+            ipc_commands_in_flight: gauge(
+                "shortlinker_ipc",
+                "commands_in_flight",
+                "Current number of IPC commands being handled.",
+                &[],
+            ),
             build_info: gauge(
                 "shortlinker_build",
                 "info",
@@ -144,14 +322,42 @@ mod product {
         match result {
             Ok(metrics) => {
                 metrics.build_info.set(&[env!("CARGO_PKG_VERSION")], 1.0);
-                for layer in ["bloom_filter", "negative_cache", "object_cache"] {
+                for layer in ["bloom_filter", "negative_cache", "l1_cache", "object_cache"] {
                     metrics.cache_hits_total.inc(&[layer], 0);
                     metrics.cache_misses_total.inc(&[layer], 0);
                     metrics.cache_entries.set(&[layer], 0.0);
                 }
-                for status in ["307", "404", "500"] {
+                for policy in ["l2", "skip"] {
+                    metrics.cache_oversize_skipped_total.inc(&[policy], 0);
+                }
+                for kind in ["link", "not_found"] {
+                    metrics.cache_stale_fills_total.inc(&[kind], 0);
+                }
+                for outcome in crate::services::BloomOutcome::ALL {
+                    metrics
+                        .bloom_filter_lookups_total
+                        .inc(&[outcome.as_str()], 0);
+                }
+                for mode in ["direct", "single", "batch"] {
+                    metrics.cache_miss_loads_total.inc(&[mode], 0);
+                }
+                for mode in ["details", "strict"] {
+                    metrics.clicks_privacy_opt_out_total.inc(&[mode], 0);
+                }
+                for status in ["307", "404", "410", "500"] {
                     metrics.redirects_total.inc(&[status], 0);
                 }
+                for reason in ["constant_time", "tarpit"] {
+                    metrics.redirects_delayed_total.inc(&[reason], 0);
+                }
+                for event in ["ban", "expire", "unban"] {
+                    metrics.auto_ban_events_total.inc(&[event], 0);
+                }
+                for reason in crate::services::geoip::SkipReason::ALL {
+                    metrics
+                        .geoip_lookups_skipped_total
+                        .inc(&[reason.as_str()], 0);
+                }
                 Some(metrics)
             }
             Err(error) => {
@@ -201,6 +407,12 @@ impl MetricsRecorder for ShortlinkerMetricsRecorder {
         }
     }
 
+    fn inc_clicks_privacy_opt_out(&self, mode: &str) {
+        if let Some(product) = self.product {
+            product.clicks_privacy_opt_out_total.inc(&[mode], 1);
+        }
+    }
+
     fn inc_cache_hit(&self, layer: &str) {
         if let Some(product) = self.product {
             product.cache_hits_total.inc(&[layer], 1);
@@ -227,23 +439,151 @@ impl MetricsRecorder for ShortlinkerMetricsRecorder {
         }
     }
 
+    fn inc_cache_oversize_skipped(&self, policy: &str) {
+        if let Some(product) = self.product {
+            product.cache_oversize_skipped_total.inc(&[policy], 1);
+        }
+    }
+
+    fn inc_cache_stale_fill(&self, kind: &str) {
+        if let Some(product) = self.product {
+            product.cache_stale_fills_total.inc(&[kind], 1);
+        }
+    }
+
     fn inc_bloom_false_positive(&self) {
         if let Some(product) = self.product {
             product.bloom_filter_false_positives_total.inc(&[], 1);
         }
     }
 
+    fn inc_bloom_lookup(&self, outcome: &str) {
+        if let Some(product) = self.product {
+            product.bloom_filter_lookups_total.inc(&[outcome], 1);
+        }
+    }
+
+    fn set_bloom_false_positive_rate(&self, rate: f64) {
+        if let Some(product) = self.product {
+            product.bloom_filter_false_positive_rate.set(&[], rate);
+        }
+    }
+
+    fn inc_bloom_capacity_alert(&self) {
+        if let Some(product) = self.product {
+            product.bloom_filter_capacity_alerts_total.inc(&[], 1);
+        }
+    }
+
+    fn inc_cache_miss_load(&self, mode: &str) {
+        if let Some(product) = self.product {
+            product.cache_miss_loads_total.inc(&[mode], 1);
+        }
+    }
+
+    fn observe_miss_batch_size(&self, size: f64) {
+        if let Some(product) = self.product {
+            product.cache_miss_batch_size.observe(&[], size);
+        }
+    }
+
     fn inc_redirect(&self, status: &str) {
         if let Some(product) = self.product {
             product.redirects_total.inc(&[status], 1);
         }
     }
 
+    fn inc_redirect_delayed(&self, reason: &str) {
+        if let Some(product) = self.product {
+            product.redirects_delayed_total.inc(&[reason], 1);
+        }
+    }
+
+    fn inc_redirect_filter(&self, result: &str) {
+        if let Some(product) = self.product {
+            product.redirect_filter_decisions_total.inc(&[result], 1);
+        }
+    }
+
     fn inc_auth_failure(&self, method: &str) {
         if let Some(product) = self.product {
             product.auth_failures_total.inc(&[method], 1);
         }
     }
+
+    fn inc_auth_deprecated_token(&self, method: &str) {
+        if let Some(product) = self.product {
+            product.auth_deprecated_token_total.inc(&[method], 1);
+        }
+    }
+
+    fn inc_firewall_hit(&self, rule: &str, action: &str) {
+        if let Some(product) = self.product {
+            product.firewall_hits_total.inc(&[rule, action], 1);
+        }
+    }
+
+    fn inc_auto_ban_event(&self, event: &str) {
+        if let Some(product) = self.product {
+            product.auto_ban_events_total.inc(&[event], 1);
+        }
+    }
+
+    fn inc_auto_ban_rejected(&self) {
+        if let Some(product) = self.product {
+            product.auto_ban_rejected_total.inc(&[], 1);
+        }
+    }
+
+    fn inc_click_anomaly_alert(&self, kind: &str) {
+        if let Some(product) = self.product {
+            product.click_anomaly_alerts_total.inc(&[kind], 1);
+        }
+    }
+
+    fn set_geoip_circuit_state(&self, state: f64) {
+        if let Some(product) = self.product {
+            product.geoip_circuit_state.set(&[], state);
+        }
+    }
+
+    fn inc_geoip_lookup_skipped(&self, reason: &str) {
+        if let Some(product) = self.product {
+            product.geoip_lookups_skipped_total.inc(&[reason], 1);
+        }
+    }
+
+    fn set_background_task_state(&self, task: &str, state: f64) {
+        if let Some(product) = self.product {
+            product.background_task_state.set(&[task], state);
+        }
+    }
+
+    fn inc_background_task_panic(&self, task: &str) {
+        if let Some(product) = self.product {
+            product.background_task_panics_total.inc(&[task], 1);
+        }
+    }
+
+    fn inc_ipc_command(&self, command: &str, status: &str) {
+        if let Some(product) = self.product {
+            product.ipc_commands_total.inc(&[command, status], 1);
+        }
+    }
+
+    fn observe_ipc_command_duration(&self, command: &str, duration_secs: f64) {
+        if let Some(product) = self.product {
+            product
+                .ipc_command_duration_seconds
+                .observe(&[command], duration_secs);
+        }
+    }
+
+    fn set_ipc_commands_in_flight(&self, count: f64) {
+        if let Some(product) = self.product {
+            product.ipc_commands_in_flight.set(&[], count);
+        }
+    }
 }
 
 /// Creates the metrics recorder selected by this build.
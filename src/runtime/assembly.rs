@@ -1,41 +1,141 @@
+use std::future::Future;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
 use aster_forge_runtime::{AsterRuntime, RuntimeComponentKind, shutdown_resource_component_after};
 use aster_forge_tasks::background_task_component_from_shutdown;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
 
+use crate::runtime::lifecycle::{self, HookContext, HookFailurePolicy, LifecycleHooks};
+use crate::runtime::shutdown::ServerShutdown;
 use crate::runtime::{components, startup, tasks};
 
+/// HTTP 的 runtime 关闭回调等待 `pre_shutdown` 与有序关闭的额外余量
+const DRAIN_WAIT_MARGIN: Duration = Duration::from_secs(1);
+
+/// Server 入口，支持注册生命周期 hook
+///
+/// ```ignore
+/// ShortlinkerBuilder::new()
+///     .on_post_start("register", HookFailurePolicy::Abort, |ctx| async move {
+///         discovery::register().await
+///     })
+///     .on_pre_shutdown("deregister", |_| async { discovery::deregister().await })
+///     .run()
+///     .await
+/// ```
+#[derive(Default)]
+pub struct ShortlinkerBuilder {
+    hooks: LifecycleHooks,
+}
+
+impl ShortlinkerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册 HTTP 监听就绪后按序执行的 hook
+    pub fn on_post_start<F, Fut>(
+        mut self,
+        name: impl Into<String>,
+        policy: HookFailurePolicy,
+        hook: F,
+    ) -> Self
+    where
+        F: FnOnce(HookContext) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.hooks.on_post_start(name, policy, hook);
+        self
+    }
+
+    /// 注册收到关闭信号后、HTTP 停止前按序执行的 hook
+    pub fn on_pre_shutdown<F, Fut>(mut self, name: impl Into<String>, hook: F) -> Self
+    where
+        F: FnOnce(HookContext) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.hooks.on_pre_shutdown(name, hook);
+        self
+    }
+
+    /// `pre_shutdown` hooks 的总超时（默认 10 秒）
+    pub fn pre_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.hooks.set_pre_shutdown_timeout(timeout);
+        self
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let mut hooks = self.hooks;
+        let startup = startup::prepare_server_startup()
+            .await
+            .context("server startup failed")?;
+        let background_resources = tasks::BackgroundTaskResources::from(&startup);
+        let hook_context = HookContext::from(&startup);
+
+        // 关闭编排完成（或驱动任务异常退出）后取消；各组件的关闭阶段都是它的子 token
+        let finished = CancellationToken::new();
+        let shutdown_timeout = Duration::from_secs(
+            crate::config::get_config()
+                .server
+                .shutdown_timeout_secs
+                .max(1),
+        );
+        let shutdown = ServerShutdown::new(finished.clone(), &startup, shutdown_timeout);
+        let drain_wait = hooks.pre_shutdown_timeout() + shutdown_timeout + DRAIN_WAIT_MARGIN;
+        let builtin_gates = tasks::BuiltinGates::default();
+        tasks::register_builtin_hooks(&mut hooks, &builtin_gates, &shutdown);
+
+        let http_shutdown = shutdown.clone();
+        let builder = AsterRuntime::builder().component(
+            aster_forge_runtime::try_runtime_component_with_shutdown(|shutdown_token| {
+                components::http_component(&startup, shutdown_token, &http_shutdown, drain_wait)
+            }),
+        )?;
+        let lifecycle_shutdown = shutdown.clone();
+        let builder = builder.component(
+            aster_forge_runtime::try_runtime_component_with_shutdown(|shutdown_token| {
+                anyhow::Ok(lifecycle::lifecycle_component(
+                    hooks,
+                    hook_context,
+                    shutdown_token,
+                    lifecycle_shutdown,
+                ))
+            }),
+        )?;
+        let process_guard = startup.process_guard;
+        builder
+            .component(background_task_component_from_shutdown(
+                move |_shutdown_token| {
+                    tasks::spawn_background_tasks(
+                        background_resources,
+                        shutdown,
+                        finished.child_token(),
+                        builtin_gates,
+                    )
+                },
+            ))
+            .component(shutdown_resource_component_after(
+                "process_guard",
+                RuntimeComponentKind::Core,
+                "release_process_guard",
+                &[aster_forge_tasks::BACKGROUND_TASKS_COMPONENT, "http"],
+                process_guard,
+                |guard| async move {
+                    drop(guard);
+                    info!("Lifecycle: process guard released");
+                    Ok(())
+                },
+            ))
+            .run()
+            .await
+            .context("runtime failed")??;
+
+        Ok(())
+    }
+}
+
 pub async fn run_server() -> Result<()> {
-    let startup = startup::prepare_server_startup()
-        .await
-        .context("server startup failed")?;
-    let background_resources = tasks::BackgroundTaskResources::from(&startup);
-
-    let builder = AsterRuntime::builder().component(
-        aster_forge_runtime::try_runtime_component_with_shutdown(|shutdown_token| {
-            components::http_component(&startup, shutdown_token)
-        }),
-    )?;
-    let process_guard = startup.process_guard;
-    builder
-        .component(background_task_component_from_shutdown(
-            move |shutdown_token| {
-                tasks::spawn_background_tasks(background_resources, shutdown_token)
-            },
-        ))
-        .component(shutdown_resource_component_after(
-            "process_guard",
-            RuntimeComponentKind::Core,
-            "release_process_guard",
-            &[aster_forge_tasks::BACKGROUND_TASKS_COMPONENT, "http"],
-            process_guard,
-            |guard| async move {
-                drop(guard);
-                Ok(())
-            },
-        ))
-        .run()
-        .await
-        .context("runtime failed")??;
-
-    Ok(())
+    ShortlinkerBuilder::new().run().await
 }
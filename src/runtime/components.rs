@@ -17,13 +17,66 @@ use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
-use crate::api::middleware::{AdminAuth, CsrfGuard, FrontendGuard, HealthAuth};
+use crate::api::middleware::{
+    AdminAuth, CsrfGuard, Firewall, FrontendGuard, HealthAuth, PanelVersionGuard, RequestContext,
+};
 use crate::api::services::{
-    AppStartTime, admin::routes::admin_v1_routes, frontend_routes, health_routes, redirect_routes,
+    AppStartTime,
+    admin::meta::{admin_not_found, extractor_error},
+    admin::routes::{admin_v1_routes, meta_routes},
+    badge_routes, frontend_routes, health_routes, redirect_routes,
 };
-use crate::config::{HttpMethod, get_runtime_config, keys};
+use crate::config::{HttpMethod, PluginsConfig, get_runtime_config, keys};
+use crate::runtime::shutdown::{ServerShutdown, ShutdownPhase};
 use crate::runtime::startup::StartupContext;
-use crate::services::GeoIpProvider;
+use crate::services::{GeoIpProvider, MissBatcher};
+
+/// 加载 `plugins.redirect_filter`
+///
+/// 加载失败（含未启用 `wasm-plugins` feature）时，`plugins.fail_mode = "closed"` 拒绝启动，
+/// 否则告警后不启用插件。
+fn load_redirect_filter(config: &PluginsConfig) -> Result<()> {
+    let Some(path) = config.redirect_filter.as_deref().filter(|p| !p.is_empty()) else {
+        return Ok(());
+    };
+
+    #[cfg(feature = "wasm-plugins")]
+    let error = {
+        use crate::services::redirect_filter::{RedirectFilter, set_redirect_filter};
+
+        match RedirectFilter::load(config) {
+            Ok(Some(filter)) => {
+                let settings = filter.settings();
+                info!(
+                    "Redirect filter plugin loaded: {} (fail mode {}, timeout {:?}, max memory {} MiB, pool {})",
+                    path,
+                    settings.fail_mode.as_str(),
+                    settings.timeout,
+                    config.max_memory_mb,
+                    settings.pool_size
+                );
+                set_redirect_filter(filter);
+                return Ok(());
+            }
+            Ok(None) => return Ok(()),
+            Err(e) => e,
+        }
+    };
+    #[cfg(not(feature = "wasm-plugins"))]
+    let error = anyhow::anyhow!(
+        "plugins.redirect_filter is set to '{}' but this binary was built without the `wasm-plugins` feature",
+        path
+    );
+
+    if config.fail_mode == "closed" {
+        return Err(error);
+    }
+    warn!(
+        "{:#}; redirect filter disabled (plugins.fail_mode = \"{}\")",
+        error, config.fail_mode
+    );
+    Ok(())
+}
 
 /// CORS configuration loaded from RuntimeConfig
 #[derive(Clone, Debug)]
@@ -51,6 +104,7 @@ impl CorsSettings {
                 "Authorization".to_string(),
                 "Accept".to_string(),
                 "X-CSRF-Token".to_string(),
+                "X-Panel-Version".to_string(),
             ],
         );
 
@@ -146,10 +200,17 @@ fn build_cors_middleware(cors_config: &CorsSettings) -> Cors {
 /// 3. Configures and starts the HTTP server
 /// 4. Listens for graceful shutdown signals
 ///
+/// On shutdown the server keeps serving until the ordered shutdown reaches it:
+/// accepting is paused in `stop_accepting` and in-flight requests are drained in
+/// `drain_in_flight`. The runtime shutdown callback only waits for the ordered
+/// shutdown to finish (at most `drain_wait`) and stops the server as a fallback.
+///
 /// **Note**: Logging system must be initialized before calling this function
-pub fn http_component(
+pub(crate) fn http_component(
     startup: &StartupContext,
     shutdown_token: CancellationToken,
+    shutdown: &ServerShutdown,
+    drain_wait: std::time::Duration,
 ) -> Result<RuntimeServiceComponent<actix_web::dev::Server>> {
     // Record application start time
     let app_start_time = AppStartTime {
@@ -160,6 +221,7 @@ pub fn http_component(
     let storage = startup.storage.clone();
     let link_service = startup.link_service.clone();
     let analytics_service = startup.analytics_service.clone();
+    let api_token_service = startup.api_token_service.clone();
     let config_service = startup.config_service.clone();
     let route = startup.route_config.clone();
     let metrics = startup.metrics.clone();
@@ -178,7 +240,23 @@ pub fn http_component(
     // GeoIP provider is startup-config driven and can be toggled at runtime via
     // `analytics.enable_geo_lookup` (runtime config). We always initialize it here so
     // toggling doesn't require a restart; actual lookup only happens when enabled.
-    let geoip_provider = Arc::new(GeoIpProvider::new(&config.analytics));
+    let geoip_provider = Arc::new(GeoIpProvider::new(&config.analytics, metrics.clone()));
+
+    // 缓存 miss 回源的微批量收集器（所有 worker 共享，关闭时直接单查）
+    let miss_batcher = Arc::new(MissBatcher::new(
+        storage.clone(),
+        &config.cache.miss_batch,
+        metrics.clone(),
+    ));
+    if miss_batcher.is_enabled() {
+        info!(
+            "Cache miss micro-batching enabled (window {} ms, max batch {})",
+            config.cache.miss_batch.window_ms, config.cache.miss_batch.max_batch_size
+        );
+    }
+
+    // 实验性 WASM redirect 过滤插件（`[plugins]`）
+    load_redirect_filter(&config.plugins)?;
 
     let forge_metrics = metrics.forge_recorder();
 
@@ -244,8 +322,10 @@ pub fn http_component(
             .app_data(web::Data::new(storage.clone()))
             .app_data(web::Data::new(link_service.clone()))
             .app_data(web::Data::new(analytics_service.clone()))
+            .app_data(web::Data::new(api_token_service.clone()))
             .app_data(web::Data::new(config_service.clone()))
             .app_data(web::Data::new(geoip_provider.clone()))
+            .app_data(web::Data::new(miss_batcher.clone()))
             .app_data(web::Data::new(app_start_time.clone()))
             .app_data(web::PayloadConfig::new(1024 * 1024))
             .app_data(web::Data::new(metrics_for_server.clone()))
@@ -262,9 +342,18 @@ pub fn http_component(
             )
             .service(
                 web::scope(&admin_prefix)
+                    // 提取器失败也返回统一错误信封
+                    .app_data(web::JsonConfig::default().error_handler(extractor_error))
+                    .app_data(web::QueryConfig::default().error_handler(extractor_error))
+                    .app_data(web::PathConfig::default().error_handler(extractor_error))
                     .wrap(CsrfGuard)
                     .wrap(AdminAuth)
-                    .service(admin_v1_routes()),
+                    .wrap(PanelVersionGuard)
+                    .wrap(RequestContext)
+                    .wrap(Firewall)
+                    .service(meta_routes())
+                    .service(admin_v1_routes())
+                    .default_service(web::to(admin_not_found)),
             )
             .service(
                 web::scope(&health_prefix)
@@ -276,9 +365,11 @@ pub fn http_component(
                     .wrap(FrontendGuard)
                     .service(frontend_routes()),
             )
-            .service(redirect_routes())
+            .service(badge_routes().wrap(Firewall))
+            .service(redirect_routes().wrap(Firewall))
     })
     .disable_signals()
+    .shutdown_timeout(ShutdownPhase::DrainInFlight.default_timeout().as_secs())
     .keep_alive(std::time::Duration::from_secs(30))
     .client_request_timeout(std::time::Duration::from_millis(5000))
     .client_disconnect_timeout(std::time::Duration::from_millis(1000))
@@ -312,13 +403,34 @@ pub fn http_component(
     .run();
     let server_handle = server.handle();
 
+    let accept = shutdown.http_accept.clone();
+    let drain = shutdown.http_drain.clone();
+    let (paused, stopped) = (accept.guard(), drain.guard());
+    let handle = server_handle.clone();
+    tokio::spawn(async move {
+        accept.requested().await;
+        handle.pause().await;
+        drop(paused);
+        drain.requested().await;
+        handle.stop(true).await;
+        drop(stopped);
+    });
+
+    let shutdown = shutdown.clone();
     Ok(RuntimeServiceComponent::new(
         "http",
         RuntimeComponentKind::Product,
         server,
         shutdown_token,
         move || async move {
-            server_handle.stop(true).await;
+            if tokio::time::timeout(drain_wait, shutdown.finished())
+                .await
+                .is_err()
+            {
+                warn!("Lifecycle: ordered shutdown did not finish in time, stopping HTTP server");
+            }
+            server_handle.stop(false).await;
+            info!("Lifecycle: HTTP server stopped");
         },
     ))
 }
@@ -0,0 +1,615 @@
+//! Server 生命周期 hook
+//!
+//! 启动：存储 / 缓存初始化 → HTTP 开始监听 → 内置 `post_start` hooks
+//! （`builtin:ipc_server` → `builtin:click_manager` → `builtin:scheduler`，各自带超时）
+//! → 用户 `post_start` hooks（按注册顺序）。内置组件的后台任务在 `StartGate` 处等待，
+//! 由对应的内置 hook 放行并等待其就绪。
+//!
+//! 关闭：收到信号 → 用户 `pre_shutdown` hooks（按注册顺序）→ 内置 `builtin:scheduler`
+//! （停止调度任务），两者共享总超时 → 按阶段有序关闭（停止接收 → drain 在途请求 →
+//! 刷写点击统计 → 停止其余后台任务 → 关闭数据库 → 清理文件，见 [`crate::runtime::shutdown`]）
+//! → 释放进程锁。IPC server 与 ClickManager 必须在 HTTP drain 之后停止，因此它们的停止
+//! 留在有序关闭阶段里，不注册为 `pre_shutdown` hook。
+//!
+//! `pre_shutdown` 完成前 HTTP 保持运行，hook 可以先从负载均衡摘除再关闭。
+//! 每一步都以 `Lifecycle:` 前缀写日志，便于确认实际顺序。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use aster_forge_runtime::{RuntimeComponentKind, RuntimeServiceComponent};
+use tokio_util::sync::{CancellationToken, DropGuard};
+use tracing::{error, info, warn};
+
+use crate::runtime::shutdown::{ServerShutdown, wait_for_shutdown};
+use crate::runtime::startup::StartupContext;
+use crate::services::{LinkCache, LinkService};
+use crate::storage::SeaOrmStorage;
+
+/// `pre_shutdown` hooks 的默认总超时
+pub const DEFAULT_PRE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Hook 返回的 future
+pub type HookFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+type HookFn<C> = Box<dyn FnOnce(C) -> HookFuture + Send>;
+
+/// `post_start` hook 失败时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HookFailurePolicy {
+    /// 记录错误，继续执行后续 hook
+    #[default]
+    Continue,
+    /// 中止启动：跳过后续 hook 并让 server 以错误退出
+    Abort,
+}
+
+/// 传给 hook 的服务组件
+#[derive(Clone)]
+pub struct HookContext {
+    pub storage: Arc<SeaOrmStorage>,
+    pub cache: Arc<dyn LinkCache>,
+    pub link_service: Arc<LinkService>,
+}
+
+impl From<&StartupContext> for HookContext {
+    fn from(startup: &StartupContext) -> Self {
+        Self {
+            storage: startup.storage.clone(),
+            cache: startup.cache.clone(),
+            link_service: startup.link_service.clone(),
+        }
+    }
+}
+
+struct Hook<C> {
+    name: String,
+    policy: HookFailurePolicy,
+    /// 单个 hook 的超时，超时视为失败；`None` 只受总超时约束
+    timeout: Option<Duration>,
+    builtin: bool,
+    run: HookFn<C>,
+}
+
+impl<C> Hook<C> {
+    async fn run(self, ctx: C) -> (String, HookFailurePolicy, anyhow::Result<()>) {
+        let result = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, (self.run)(ctx)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!("timed out after {:?}", timeout)),
+            },
+            None => (self.run)(ctx).await,
+        };
+        (self.name, self.policy, result)
+    }
+}
+
+/// 一组 hook 的执行结果
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HookReport {
+    /// 成功完成的 hook
+    pub completed: Vec<String>,
+    /// 返回错误的 hook
+    pub failed: Vec<String>,
+    /// 因中止或超时未执行完的 hook
+    pub skipped: Vec<String>,
+}
+
+/// 已注册的生命周期 hook
+pub struct LifecycleHooks<C = HookContext> {
+    post_start: Vec<Hook<C>>,
+    pre_shutdown: Vec<Hook<C>>,
+    pre_shutdown_timeout: Duration,
+}
+
+impl<C> Default for LifecycleHooks<C> {
+    fn default() -> Self {
+        Self {
+            post_start: Vec::new(),
+            pre_shutdown: Vec::new(),
+            pre_shutdown_timeout: DEFAULT_PRE_SHUTDOWN_TIMEOUT,
+        }
+    }
+}
+
+impl<C: Clone + Send + 'static> LifecycleHooks<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册 HTTP 监听就绪后执行的 hook
+    pub fn on_post_start<F, Fut>(
+        &mut self,
+        name: impl Into<String>,
+        policy: HookFailurePolicy,
+        hook: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(C) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.post_start.push(Hook {
+            name: name.into(),
+            policy,
+            timeout: None,
+            builtin: false,
+            run: Box::new(move |ctx| Box::pin(hook(ctx))),
+        });
+        self
+    }
+
+    /// 注册收到关闭信号后、HTTP 停止前执行的 hook（失败只记录）
+    pub fn on_pre_shutdown<F, Fut>(&mut self, name: impl Into<String>, hook: F) -> &mut Self
+    where
+        F: FnOnce(C) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        // 用户 hook 排在内置 hook 之前：先从负载均衡摘除，再停止内置组件
+        let at = self
+            .pre_shutdown
+            .iter()
+            .position(|hook| hook.builtin)
+            .unwrap_or(self.pre_shutdown.len());
+        self.pre_shutdown.insert(
+            at,
+            Hook {
+                name: name.into(),
+                policy: HookFailurePolicy::Continue,
+                timeout: None,
+                builtin: false,
+                run: Box::new(move |ctx| Box::pin(hook(ctx))),
+            },
+        );
+        self
+    }
+
+    /// 注册内置组件的启动 hook：排在所有用户 `post_start` hook 之前，内置 hook 之间按注册顺序
+    pub(crate) fn on_builtin_post_start<F, Fut>(
+        &mut self,
+        name: &str,
+        timeout: Duration,
+        hook: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(C) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let at = self
+            .post_start
+            .iter()
+            .take_while(|hook| hook.builtin)
+            .count();
+        self.post_start.insert(
+            at,
+            Hook {
+                name: format!("builtin:{name}"),
+                policy: HookFailurePolicy::Continue,
+                timeout: Some(timeout),
+                builtin: true,
+                run: Box::new(move |ctx| Box::pin(hook(ctx))),
+            },
+        );
+        self
+    }
+
+    /// 注册内置组件的关闭 hook：排在所有用户 `pre_shutdown` hook 之后
+    pub(crate) fn on_builtin_pre_shutdown<F, Fut>(
+        &mut self,
+        name: &str,
+        timeout: Duration,
+        hook: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(C) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.pre_shutdown.push(Hook {
+            name: format!("builtin:{name}"),
+            policy: HookFailurePolicy::Continue,
+            timeout: Some(timeout),
+            builtin: true,
+            run: Box::new(move |ctx| Box::pin(hook(ctx))),
+        });
+        self
+    }
+
+    /// 设置 `pre_shutdown` hooks 的总超时
+    pub fn set_pre_shutdown_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.pre_shutdown_timeout = timeout;
+        self
+    }
+
+    pub fn pre_shutdown_timeout(&self) -> Duration {
+        self.pre_shutdown_timeout
+    }
+
+    /// 按注册顺序执行 `post_start` hooks
+    ///
+    /// `Abort` 策略的 hook 失败时返回错误，后续 hook 记入 `skipped`。
+    pub async fn run_post_start(&mut self, ctx: C) -> (HookReport, anyhow::Result<()>) {
+        let hooks = std::mem::take(&mut self.post_start);
+        let total = hooks.len();
+        let mut report = HookReport::default();
+        let mut hooks = hooks.into_iter().enumerate();
+
+        while let Some((index, hook)) = hooks.next() {
+            info!(
+                "Lifecycle: post_start hook '{}' ({}/{})",
+                hook.name,
+                index + 1,
+                total
+            );
+            let started = Instant::now();
+            match hook.run(ctx.clone()).await {
+                (name, _, Ok(())) => {
+                    info!(
+                        "Lifecycle: post_start hook '{}' completed in {:?}",
+                        name,
+                        started.elapsed()
+                    );
+                    report.completed.push(name);
+                }
+                (name, HookFailurePolicy::Abort, Err(e)) => {
+                    error!(
+                        "Lifecycle: post_start hook '{}' failed, aborting startup: {:#}",
+                        name, e
+                    );
+                    report
+                        .skipped
+                        .extend(hooks.by_ref().map(|(_, hook)| hook.name));
+                    let error = e.context(format!("post_start hook '{}' failed", name));
+                    report.failed.push(name);
+                    return (report, Err(error));
+                }
+                (name, HookFailurePolicy::Continue, Err(e)) => {
+                    warn!(
+                        "Lifecycle: post_start hook '{}' failed, continuing: {:#}",
+                        name, e
+                    );
+                    report.failed.push(name);
+                }
+            }
+        }
+
+        (report, Ok(()))
+    }
+
+    /// 按注册顺序执行 `pre_shutdown` hooks，总耗时不超过 `pre_shutdown_timeout`
+    pub async fn run_pre_shutdown(&mut self, ctx: C) -> HookReport {
+        let hooks = std::mem::take(&mut self.pre_shutdown);
+        let total = hooks.len();
+        let mut names: Vec<String> = hooks.iter().map(|hook| hook.name.clone()).collect();
+        let mut report = HookReport::default();
+        let mut finished = 0;
+
+        let run_all = async {
+            for (index, hook) in hooks.into_iter().enumerate() {
+                info!(
+                    "Lifecycle: pre_shutdown hook '{}' ({}/{})",
+                    hook.name,
+                    index + 1,
+                    total
+                );
+                match hook.run(ctx.clone()).await {
+                    (name, _, Ok(())) => report.completed.push(name),
+                    (name, _, Err(e)) => {
+                        warn!("Lifecycle: pre_shutdown hook '{}' failed: {:#}", name, e);
+                        report.failed.push(name);
+                    }
+                }
+                finished += 1;
+            }
+        };
+
+        if tokio::time::timeout(self.pre_shutdown_timeout, run_all)
+            .await
+            .is_err()
+        {
+            report.skipped = names.split_off(finished);
+            warn!(
+                "Lifecycle: pre_shutdown hooks exceeded {:?}, skipped: {:?}",
+                self.pre_shutdown_timeout, report.skipped
+            );
+        }
+        report
+    }
+}
+
+/// 内置组件的启动闸门
+///
+/// 组件的后台任务先在 [`StartGate::wait_open`] 等待，对应的内置 `post_start` hook
+/// 调用 [`StartGate::open`] 放行并等待组件调用 [`StartGate::ready`]。组件退出时 drop
+/// [`StartGate::exit_guard`]，未就绪即退出时 hook 立即失败而不是等到超时。
+#[derive(Clone, Default)]
+pub(crate) struct StartGate {
+    opened: CancellationToken,
+    ready: CancellationToken,
+    exited: CancellationToken,
+}
+
+impl StartGate {
+    /// 组件侧：等待放行；`stop` 先取消（启动完成前收到关闭）时返回 false
+    pub async fn wait_open(&self, stop: &CancellationToken) -> bool {
+        tokio::select! {
+            biased;
+            _ = self.opened.cancelled() => true,
+            _ = stop.cancelled() => false,
+        }
+    }
+
+    /// 组件侧：标记已就绪
+    pub fn ready(&self) {
+        self.ready.cancel();
+    }
+
+    /// 组件侧：就绪 token，交给需要在内部确认就绪的组件
+    pub fn ready_token(&self) -> CancellationToken {
+        self.ready.clone()
+    }
+
+    /// 组件侧：drop 时标记组件已退出
+    pub fn exit_guard(&self) -> DropGuard {
+        self.exited.clone().drop_guard()
+    }
+
+    /// hook 侧：放行并等待就绪（超时由 hook 控制）
+    pub async fn open(&self) -> anyhow::Result<()> {
+        self.opened.cancel();
+        tokio::select! {
+            biased;
+            _ = self.ready.cancelled() => Ok(()),
+            _ = self.exited.cancelled() => Err(anyhow!("component exited before becoming ready")),
+        }
+    }
+}
+
+/// 创建驱动 hooks 与有序关闭的 runtime 组件
+///
+/// 组件创建时 HTTP 已完成监听绑定。驱动任务独立 spawn，先执行 `post_start`，
+/// 等待关闭信号（[`wait_for_shutdown`] 统一入口）后执行 `pre_shutdown`，再按
+/// [`ServerShutdown::plan`] 逐阶段关闭各组件，最后标记编排结束：HTTP 的 runtime
+/// 关闭回调等待该标记，从而保证 hook 与有序关闭先于 runtime 的收尾执行。
+pub(crate) fn lifecycle_component(
+    mut hooks: LifecycleHooks,
+    ctx: HookContext,
+    shutdown_token: CancellationToken,
+    shutdown: ServerShutdown,
+) -> RuntimeServiceComponent<Pin<Box<dyn Future<Output = std::io::Result<()>> + Send>>> {
+    let driver_token = shutdown_token.clone();
+    let driver = tokio::spawn(async move {
+        // 任何退出路径（含 hook panic）都标记编排结束，未执行的阶段由各组件兜底退出
+        let _finished = shutdown.finish_guard();
+
+        info!("Lifecycle: HTTP listening, starting built-in components");
+        tokio::select! {
+            (_, result) = hooks.run_post_start(ctx.clone()) => {
+                if let Err(e) = result {
+                    shutdown.plan().run().await;
+                    return Err(e);
+                }
+                info!("Lifecycle: startup complete");
+                let source = wait_for_shutdown(&driver_token).await;
+                info!("Lifecycle: shutdown requested ({})", source);
+            }
+            source = wait_for_shutdown(&driver_token) => {
+                warn!(
+                    "Lifecycle: shutdown requested ({}) while post_start hooks were running",
+                    source
+                );
+            }
+        }
+
+        hooks.run_pre_shutdown(ctx).await;
+        info!("Lifecycle: pre_shutdown complete, starting ordered shutdown");
+        shutdown.plan().run().await;
+        anyhow::Ok(())
+    });
+
+    let future: Pin<Box<dyn Future<Output = std::io::Result<()>> + Send>> = Box::pin(async move {
+        match driver.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(std::io::Error::other(format!("{e:#}"))),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    });
+
+    RuntimeServiceComponent::new(
+        "lifecycle",
+        RuntimeComponentKind::Product,
+        future,
+        shutdown_token,
+        move || async move {},
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn recorder(log: &Log, entry: &'static str) -> impl FnOnce(()) -> HookFuture + Send + use<> {
+        let log = log.clone();
+        move |()| {
+            Box::pin(async move {
+                log.lock().unwrap().push(entry.to_string());
+                Ok(())
+            })
+        }
+    }
+
+    fn failing(_: ()) -> HookFuture {
+        Box::pin(async { Err(anyhow::anyhow!("boom")) })
+    }
+
+    #[tokio::test]
+    async fn test_post_start_runs_in_registration_order() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_post_start("a", HookFailurePolicy::Continue, recorder(&log, "a"))
+            .on_post_start("b", HookFailurePolicy::Abort, recorder(&log, "b"))
+            .on_post_start("c", HookFailurePolicy::Continue, recorder(&log, "c"));
+
+        let (report, result) = hooks.run_post_start(()).await;
+        assert!(result.is_ok());
+        assert_eq!(*log.lock().unwrap(), vec!["a", "b", "c"]);
+        assert_eq!(report.completed, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_post_start_continue_policy_keeps_going() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_post_start("bad", HookFailurePolicy::Continue, failing)
+            .on_post_start("next", HookFailurePolicy::Continue, recorder(&log, "next"));
+
+        let (report, result) = hooks.run_post_start(()).await;
+        assert!(result.is_ok());
+        assert_eq!(report.failed, vec!["bad"]);
+        assert_eq!(report.completed, vec!["next"]);
+    }
+
+    #[tokio::test]
+    async fn test_post_start_abort_policy_stops_startup() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_post_start(
+                "first",
+                HookFailurePolicy::Continue,
+                recorder(&log, "first"),
+            )
+            .on_post_start("register", HookFailurePolicy::Abort, failing)
+            .on_post_start("last", HookFailurePolicy::Continue, recorder(&log, "last"));
+
+        let (report, result) = hooks.run_post_start(()).await;
+        let error = result.unwrap_err();
+        assert!(format!("{error:#}").contains("post_start hook 'register' failed"));
+        assert_eq!(report.completed, vec!["first"]);
+        assert_eq!(report.failed, vec!["register"]);
+        assert_eq!(report.skipped, vec!["last"]);
+        assert_eq!(*log.lock().unwrap(), vec!["first"]);
+    }
+
+    #[tokio::test]
+    async fn test_pre_shutdown_failures_do_not_stop_later_hooks() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_pre_shutdown("deregister", failing)
+            .on_pre_shutdown("flush", recorder(&log, "flush"));
+
+        let report = hooks.run_pre_shutdown(()).await;
+        assert_eq!(report.failed, vec!["deregister"]);
+        assert_eq!(report.completed, vec!["flush"]);
+        assert!(report.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pre_shutdown_total_timeout() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .set_pre_shutdown_timeout(Duration::from_millis(50))
+            .on_pre_shutdown("quick", recorder(&log, "quick"))
+            .on_pre_shutdown("stuck", |()| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(())
+            })
+            .on_pre_shutdown("never", recorder(&log, "never"));
+
+        let started = Instant::now();
+        let report = hooks.run_pre_shutdown(()).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(report.completed, vec!["quick"]);
+        assert_eq!(report.skipped, vec!["stuck", "never"]);
+        assert_eq!(*log.lock().unwrap(), vec!["quick"]);
+    }
+
+    #[tokio::test]
+    async fn test_builtin_hooks_wrap_user_hooks() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_post_start("user", HookFailurePolicy::Continue, recorder(&log, "user"))
+            .on_builtin_post_start("ipc", Duration::from_secs(1), recorder(&log, "ipc"))
+            .on_builtin_post_start("sched", Duration::from_secs(1), recorder(&log, "sched"))
+            .on_builtin_pre_shutdown("sched", Duration::from_secs(1), recorder(&log, "stop"))
+            .on_pre_shutdown("deregister", recorder(&log, "deregister"));
+
+        let (report, result) = hooks.run_post_start(()).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            report.completed,
+            vec!["builtin:ipc", "builtin:sched", "user"]
+        );
+        let report = hooks.run_pre_shutdown(()).await;
+        assert_eq!(report.completed, vec!["deregister", "builtin:sched"]);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["ipc", "sched", "user", "deregister", "stop"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builtin_hook_timeout_is_a_failure() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks
+            .on_builtin_post_start("slow", Duration::from_millis(20), |()| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(())
+            })
+            .on_post_start("user", HookFailurePolicy::Continue, recorder(&log, "user"));
+
+        let (report, result) = hooks.run_post_start(()).await;
+        assert!(result.is_ok());
+        assert_eq!(report.failed, vec!["builtin:slow"]);
+        assert_eq!(report.completed, vec!["user"]);
+    }
+
+    #[tokio::test]
+    async fn test_start_gate_waits_for_ready_or_exit() {
+        let stop = CancellationToken::new();
+        let gate = StartGate::default();
+        let worker = gate.clone();
+        let worker_stop = stop.clone();
+        tokio::spawn(async move {
+            assert!(worker.wait_open(&worker_stop).await);
+            worker.ready();
+        });
+        gate.open().await.unwrap();
+
+        let gate = StartGate::default();
+        let worker = gate.clone();
+        tokio::spawn(async move {
+            let _exited = worker.exit_guard();
+            assert!(worker.wait_open(&stop).await);
+        });
+        assert!(gate.open().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_gate_stays_closed_on_shutdown() {
+        let stop = CancellationToken::new();
+        stop.cancel();
+        assert!(!StartGate::default().wait_open(&stop).await);
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_once() {
+        let log: Log = Arc::default();
+        let mut hooks = LifecycleHooks::<()>::new();
+        hooks.on_post_start("once", HookFailurePolicy::Continue, recorder(&log, "once"));
+
+        hooks.run_post_start(()).await.1.unwrap();
+        hooks.run_post_start(()).await.1.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+}
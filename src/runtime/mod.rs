@@ -1,6 +1,11 @@
 mod assembly;
 pub mod components;
+pub mod lifecycle;
+pub mod shutdown;
 pub mod startup;
+pub mod supervisor;
 mod tasks;
 
-pub use assembly::run_server;
+pub use assembly::{ShortlinkerBuilder, run_server};
+pub use lifecycle::{HookContext, HookFailurePolicy};
+pub use shutdown::{ShutdownPhase, ShutdownPlan, ShutdownReport};
@@ -0,0 +1,609 @@
+//! 有序关闭编排
+//!
+//! 关闭信号统一由 [`wait_for_shutdown`] 接收（Unix：SIGTERM / SIGINT；Windows：
+//! Ctrl-C / Ctrl-Break / 关闭控制台 / 系统关机；以及 runtime 内部触发的关闭）。
+//! `pre_shutdown` hooks 完成后按阶段依次关闭：
+//!
+//! 1. `stop_accepting`：HTTP 暂停 accept，IPC 不再接受新连接
+//! 2. `drain_in_flight`：等待在途 HTTP 请求与 IPC 命令完成
+//! 3. `flush_analytics`：ClickManager 与 UserAgent 缓冲最终刷写
+//! 4. `stop_background_tasks`：停止其余后台任务（调度任务通常已由内置 `pre_shutdown`
+//!    hook 停止），中止仍未结束的 IPC 连接
+//! 5. `close_database`：关闭数据库连接池
+//! 6. `cleanup`：删除 IPC socket 与 HTTP Unix socket 文件
+//!
+//! 同一阶段内的组件并发执行。每个阶段有独立超时，且整体不超过
+//! `server.shutdown_timeout_secs`；超时时记录未完成的组件并进入下一阶段，
+//! 总超时耗尽后剩余阶段直接跳过。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use tokio_util::sync::{CancellationToken, DropGuard};
+use tracing::{info, warn};
+
+use crate::runtime::startup::StartupContext;
+use crate::system::ipc::platform::{IpcPlatform, PlatformIpc};
+
+/// 关闭阶段，按声明顺序执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    StopAccepting,
+    DrainInFlight,
+    FlushAnalytics,
+    StopBackgroundTasks,
+    CloseDatabase,
+    Cleanup,
+}
+
+impl ShutdownPhase {
+    pub const ALL: [Self; 6] = [
+        Self::StopAccepting,
+        Self::DrainInFlight,
+        Self::FlushAnalytics,
+        Self::StopBackgroundTasks,
+        Self::CloseDatabase,
+        Self::Cleanup,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StopAccepting => "stop_accepting",
+            Self::DrainInFlight => "drain_in_flight",
+            Self::FlushAnalytics => "flush_analytics",
+            Self::StopBackgroundTasks => "stop_background_tasks",
+            Self::CloseDatabase => "close_database",
+            Self::Cleanup => "cleanup",
+        }
+    }
+
+    /// 阶段的默认超时（实际不超过剩余的总超时）
+    pub fn default_timeout(&self) -> Duration {
+        match self {
+            Self::StopAccepting => Duration::from_secs(5),
+            Self::DrainInFlight => Duration::from_secs(15),
+            Self::FlushAnalytics => Duration::from_secs(10),
+            Self::StopBackgroundTasks => Duration::from_secs(5),
+            Self::CloseDatabase => Duration::from_secs(5),
+            Self::Cleanup => Duration::from_secs(2),
+        }
+    }
+}
+
+/// 关闭步骤返回的 future
+pub type StepFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct ShutdownStep {
+    phase: ShutdownPhase,
+    component: String,
+    run: StepFuture,
+}
+
+/// 单个阶段的执行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseReport {
+    pub phase: ShutdownPhase,
+    /// 按完成先后排列
+    pub completed: Vec<String>,
+    /// 阶段超时时仍未完成的组件
+    pub timed_out: Vec<String>,
+    /// 总超时已耗尽、未执行的组件
+    pub skipped: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// 一次关闭的执行结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// 按执行顺序排列，不含没有组件的阶段
+    pub phases: Vec<PhaseReport>,
+    pub elapsed: Duration,
+}
+
+impl ShutdownReport {
+    /// 所有组件都在超时内完成
+    pub fn is_clean(&self) -> bool {
+        self.phases
+            .iter()
+            .all(|phase| phase.timed_out.is_empty() && phase.skipped.is_empty())
+    }
+
+    /// 未完成（超时或跳过）的组件，按阶段顺序排列
+    pub fn unfinished(&self) -> Vec<(ShutdownPhase, &str)> {
+        self.phases
+            .iter()
+            .flat_map(|phase| {
+                phase
+                    .timed_out
+                    .iter()
+                    .chain(&phase.skipped)
+                    .map(move |component| (phase.phase, component.as_str()))
+            })
+            .collect()
+    }
+
+    pub fn phase(&self, phase: ShutdownPhase) -> Option<&PhaseReport> {
+        self.phases.iter().find(|report| report.phase == phase)
+    }
+}
+
+/// 按阶段登记的关闭步骤
+///
+/// ```ignore
+/// let mut plan = ShutdownPlan::new(Duration::from_secs(30));
+/// plan.step(ShutdownPhase::FlushAnalytics, "click_manager", async move {
+///     manager.flush().await
+/// })
+/// .step(ShutdownPhase::CloseDatabase, "database", async move {
+///     let _ = db.close().await;
+/// });
+/// let report = plan.run().await;
+/// ```
+pub struct ShutdownPlan {
+    total_timeout: Duration,
+    phase_timeouts: [Duration; 6],
+    steps: Vec<ShutdownStep>,
+}
+
+impl ShutdownPlan {
+    pub fn new(total_timeout: Duration) -> Self {
+        Self {
+            total_timeout,
+            phase_timeouts: ShutdownPhase::ALL.map(|phase| phase.default_timeout()),
+            steps: Vec::new(),
+        }
+    }
+
+    /// 覆盖某个阶段的超时
+    pub fn set_phase_timeout(&mut self, phase: ShutdownPhase, timeout: Duration) -> &mut Self {
+        self.phase_timeouts[phase as usize] = timeout;
+        self
+    }
+
+    /// 登记一个组件在某阶段的关闭步骤；同阶段的步骤并发执行
+    pub fn step<F>(
+        &mut self,
+        phase: ShutdownPhase,
+        component: impl Into<String>,
+        run: F,
+    ) -> &mut Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.steps.push(ShutdownStep {
+            phase,
+            component: component.into(),
+            run: Box::pin(run),
+        });
+        self
+    }
+
+    /// 依次执行各阶段
+    ///
+    /// 阶段超时后未完成的步骤被丢弃（不再等待），直接进入下一阶段。
+    pub async fn run(self) -> ShutdownReport {
+        let started = Instant::now();
+        let deadline = started + self.total_timeout;
+        let mut steps = self.steps;
+        let total_phases = ShutdownPhase::ALL
+            .iter()
+            .filter(|phase| steps.iter().any(|step| step.phase == **phase))
+            .count();
+        let mut report = ShutdownReport::default();
+
+        info!(
+            "Lifecycle: ordered shutdown started ({} phases, timeout {:?})",
+            total_phases, self.total_timeout
+        );
+        for phase in ShutdownPhase::ALL {
+            let (current, rest): (Vec<_>, Vec<_>) =
+                steps.into_iter().partition(|step| step.phase == phase);
+            steps = rest;
+            if current.is_empty() {
+                continue;
+            }
+
+            let index = report.phases.len() + 1;
+            let names: Vec<String> = current.iter().map(|step| step.component.clone()).collect();
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                warn!(
+                    "Lifecycle: shutdown timeout exhausted, skipping phase '{}' ({}/{}): {}",
+                    phase.as_str(),
+                    index,
+                    total_phases,
+                    names.join(", ")
+                );
+                report.phases.push(PhaseReport {
+                    phase,
+                    completed: Vec::new(),
+                    timed_out: Vec::new(),
+                    skipped: names,
+                    elapsed: Duration::ZERO,
+                });
+                continue;
+            }
+
+            let timeout = self.phase_timeouts[phase as usize].min(remaining);
+            info!(
+                "Lifecycle: shutdown phase '{}' ({}/{}): {}",
+                phase.as_str(),
+                index,
+                total_phases,
+                names.join(", ")
+            );
+            let phase_started = Instant::now();
+            let mut pending: FuturesUnordered<_> = current
+                .into_iter()
+                .map(|step| async move {
+                    step.run.await;
+                    step.component
+                })
+                .collect();
+            let mut completed = Vec::new();
+            let finished = tokio::time::timeout(timeout, async {
+                while let Some(component) = pending.next().await {
+                    completed.push(component);
+                }
+            })
+            .await
+            .is_ok();
+            drop(pending);
+
+            let elapsed = phase_started.elapsed();
+            let timed_out: Vec<String> = names
+                .into_iter()
+                .filter(|name| !completed.contains(name))
+                .collect();
+            if finished {
+                info!(
+                    "Lifecycle: shutdown phase '{}' completed in {:?}",
+                    phase.as_str(),
+                    elapsed
+                );
+            } else {
+                warn!(
+                    "Lifecycle: shutdown phase '{}' timed out after {:?}, unfinished: {}",
+                    phase.as_str(),
+                    timeout,
+                    timed_out.join(", ")
+                );
+            }
+            report.phases.push(PhaseReport {
+                phase,
+                completed,
+                timed_out,
+                skipped: Vec::new(),
+                elapsed,
+            });
+        }
+
+        report.elapsed = started.elapsed();
+        if report.is_clean() {
+            info!(
+                "Lifecycle: ordered shutdown completed in {:?}",
+                report.elapsed
+            );
+        } else {
+            let unfinished: Vec<String> = report
+                .unfinished()
+                .into_iter()
+                .map(|(phase, component)| format!("{}/{}", phase.as_str(), component))
+                .collect();
+            warn!(
+                "Lifecycle: ordered shutdown finished in {:?} with unfinished components: {}",
+                report.elapsed,
+                unfinished.join(", ")
+            );
+        }
+        report
+    }
+}
+
+/// 组件侧的关闭步骤：编排器触发，组件完成后确认
+#[derive(Clone)]
+pub struct ShutdownStage {
+    trigger: CancellationToken,
+    done: CancellationToken,
+}
+
+impl ShutdownStage {
+    /// `parent` 取消时同样触发本步骤，作为编排器未运行时的兜底
+    pub fn new(parent: &CancellationToken) -> Self {
+        Self {
+            trigger: parent.child_token(),
+            done: CancellationToken::new(),
+        }
+    }
+
+    /// 组件侧监听的触发 token
+    pub fn token(&self) -> CancellationToken {
+        self.trigger.clone()
+    }
+
+    pub async fn requested(&self) {
+        self.trigger.cancelled().await
+    }
+
+    /// 返回的 guard 在 drop 时确认完成；组件提前退出也不会让编排器空等
+    pub fn guard(&self) -> DropGuard {
+        self.done.clone().drop_guard()
+    }
+
+    /// 编排器侧：只触发，不等待确认
+    pub fn request(&self) {
+        self.trigger.cancel();
+    }
+
+    /// 编排器侧：等待组件确认
+    pub fn completed(&self) -> impl Future<Output = ()> + Send + 'static {
+        let done = self.done.clone();
+        async move { done.cancelled().await }
+    }
+
+    /// 编排器侧：触发并等待组件确认
+    pub fn request_and_wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        let stage = self.clone();
+        async move {
+            stage.request();
+            stage.done.cancelled().await
+        }
+    }
+}
+
+/// 统一的关闭入口：等待进程信号或 runtime 内部触发的关闭，并取消 `token`
+///
+/// 返回触发来源，用于日志。
+pub async fn wait_for_shutdown(token: &CancellationToken) -> &'static str {
+    let source = tokio::select! {
+        _ = token.cancelled() => "runtime",
+        signal = shutdown_signal() => signal,
+    };
+    token.cancel();
+    source
+}
+
+#[cfg(unix)]
+async fn shutdown_signal() -> &'static str {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    match (
+        signal(SignalKind::terminate()),
+        signal(SignalKind::interrupt()),
+    ) {
+        (Ok(mut terminate), Ok(mut interrupt)) => tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = interrupt.recv() => "SIGINT",
+        },
+        (Err(error), _) | (_, Err(error)) => {
+            warn!(%error, "Lifecycle: failed to install signal handlers, only ctrl-c is handled");
+            ctrl_c().await
+        }
+    }
+}
+
+#[cfg(windows)]
+async fn shutdown_signal() -> &'static str {
+    use tokio::signal::windows;
+
+    match (
+        windows::ctrl_c(),
+        windows::ctrl_break(),
+        windows::ctrl_close(),
+        windows::ctrl_shutdown(),
+    ) {
+        (Ok(mut ctrl_c), Ok(mut ctrl_break), Ok(mut ctrl_close), Ok(mut ctrl_shutdown)) => {
+            tokio::select! {
+                _ = ctrl_c.recv() => "CTRL_C",
+                _ = ctrl_break.recv() => "CTRL_BREAK",
+                _ = ctrl_close.recv() => "CTRL_CLOSE",
+                _ = ctrl_shutdown.recv() => "CTRL_SHUTDOWN",
+            }
+        }
+        _ => {
+            warn!("Lifecycle: failed to install console handlers, only ctrl-c is handled");
+            ctrl_c().await
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn shutdown_signal() -> &'static str {
+    ctrl_c().await
+}
+
+async fn ctrl_c() -> &'static str {
+    match tokio::signal::ctrl_c().await {
+        Ok(()) => "ctrl-c",
+        Err(error) => {
+            warn!(%error, "Lifecycle: failed to listen for ctrl-c");
+            std::future::pending().await
+        }
+    }
+}
+
+/// Server 各组件的关闭协调
+///
+/// 组件在启动时取得自己的 [`ShutdownStage`] 并监听；[`ServerShutdown::plan`]
+/// 按阶段触发并等待它们。所有触发 token 都是 `root` 的子 token：编排器因故
+/// 未运行时，`root` 取消会让各组件同时退出。
+#[derive(Clone)]
+pub(crate) struct ServerShutdown {
+    root: CancellationToken,
+    /// HTTP 暂停 accept
+    pub http_accept: ShutdownStage,
+    /// HTTP 等待在途请求后停止
+    pub http_drain: ShutdownStage,
+    /// 触发：IPC 停止接受新连接；确认：在途连接全部结束
+    pub ipc: ShutdownStage,
+    /// ClickManager 停止并最终刷写；未启用点击统计时为 `None`
+    pub analytics: Option<ShutdownStage>,
+    /// 后台任务的停止 token，同时中止仍未结束的 IPC 连接
+    pub background: CancellationToken,
+    /// 调度任务的停止 token（`background` 的子 token），由内置 `pre_shutdown` hook 提前触发
+    pub scheduler: CancellationToken,
+    tasks: Arc<Mutex<Vec<(&'static str, CancellationToken)>>>,
+    database: sea_orm::DatabaseConnection,
+    http_unix_socket: Option<String>,
+    total_timeout: Duration,
+}
+
+impl ServerShutdown {
+    pub fn new(root: CancellationToken, startup: &StartupContext, total_timeout: Duration) -> Self {
+        let config = crate::config::get_config();
+        let background = root.child_token();
+        Self {
+            http_accept: ShutdownStage::new(&root),
+            http_drain: ShutdownStage::new(&root),
+            ipc: ShutdownStage::new(&root),
+            analytics: startup
+                .click_manager
+                .as_ref()
+                .map(|_| ShutdownStage::new(&root)),
+            scheduler: background.child_token(),
+            background,
+            tasks: Arc::default(),
+            database: startup.storage.get_db().clone(),
+            http_unix_socket: config.server.unix_socket.clone(),
+            total_timeout,
+            root,
+        }
+    }
+
+    /// 编排结束（或兜底触发）时完成
+    pub async fn finished(&self) {
+        self.root.cancelled().await
+    }
+
+    /// 返回在 drop 时标记编排结束的 guard
+    pub fn finish_guard(&self) -> DropGuard {
+        self.root.clone().drop_guard()
+    }
+
+    /// 登记一个在 `stop_background_tasks` 阶段等待退出的后台任务
+    pub fn track_task(&self, name: &'static str) -> DropGuard {
+        let done = CancellationToken::new();
+        self.tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, done.clone()));
+        done.drop_guard()
+    }
+
+    /// 停止调度任务并等待已登记的任务全部退出
+    pub fn stop_scheduler(&self) -> impl Future<Output = ()> + Send + 'static {
+        let scheduler = self.scheduler.clone();
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        async move {
+            scheduler.cancel();
+            for (_, done) in tasks {
+                done.cancelled().await;
+            }
+        }
+    }
+
+    /// 生成本次关闭的编排计划
+    pub fn plan(&self) -> ShutdownPlan {
+        use ShutdownPhase::*;
+
+        let mut plan = ShutdownPlan::new(self.total_timeout);
+        plan.step(StopAccepting, "http", self.http_accept.request_and_wait());
+        let ipc = self.ipc.clone();
+        plan.step(StopAccepting, "ipc_server", async move { ipc.request() });
+
+        plan.step(DrainInFlight, "http", self.http_drain.request_and_wait());
+        plan.step(DrainInFlight, "ipc_server", self.ipc.completed());
+
+        if let Some(analytics) = &self.analytics {
+            plan.step(
+                FlushAnalytics,
+                "click_manager",
+                analytics.request_and_wait(),
+            );
+        }
+        let database = self.database.clone();
+        plan.step(FlushAnalytics, "user_agent_store", async move {
+            if let Some(store) = crate::services::get_user_agent_store()
+                && let Err(error) = store.flush_pending(&database).await
+            {
+                warn!(%error, "final UserAgent flush failed");
+            }
+        });
+
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        for (name, done) in tasks {
+            let background = self.background.clone();
+            plan.step(StopBackgroundTasks, name, async move {
+                background.cancel();
+                done.cancelled().await
+            });
+        }
+
+        let database = self.database.clone();
+        plan.step(CloseDatabase, "database", async move {
+            if let Err(error) = database.close().await {
+                warn!(%error, "failed to close database connections");
+            }
+        });
+
+        plan.step(Cleanup, "ipc_socket", async { PlatformIpc::cleanup() });
+        if let Some(path) = self.http_unix_socket.clone() {
+            plan.step(Cleanup, "http_socket", async move {
+                if let Err(error) = std::fs::remove_file(&path)
+                    && error.kind() != std::io::ErrorKind::NotFound
+                {
+                    warn!(%error, "failed to remove HTTP Unix socket {}", path);
+                }
+            });
+        }
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stage_guard_confirms_completion() {
+        let root = CancellationToken::new();
+        let stage = ShutdownStage::new(&root);
+        let guard = stage.guard();
+        let worker = stage.clone();
+        tokio::spawn(async move {
+            worker.requested().await;
+            drop(guard);
+        });
+
+        tokio::time::timeout(Duration::from_secs(1), stage.request_and_wait())
+            .await
+            .expect("stage should complete once requested");
+    }
+
+    #[tokio::test]
+    async fn test_root_cancel_triggers_every_stage() {
+        let root = CancellationToken::new();
+        let stages = [ShutdownStage::new(&root), ShutdownStage::new(&root)];
+        root.cancel();
+        for stage in &stages {
+            tokio::time::timeout(Duration::from_secs(1), stage.requested())
+                .await
+                .expect("root cancel should trigger the stage");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_phases_are_not_reported() {
+        let mut plan = ShutdownPlan::new(Duration::from_secs(1));
+        plan.step(ShutdownPhase::Cleanup, "files", async {});
+
+        let report = plan.run().await;
+        assert_eq!(report.phases.len(), 1);
+        assert_eq!(report.phases[0].phase, ShutdownPhase::Cleanup);
+        assert!(report.is_clean());
+    }
+}
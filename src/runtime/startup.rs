@@ -1,10 +1,11 @@
 use crate::analytics::global::set_global_click_manager;
 use crate::analytics::manager::ClickManager;
+use crate::analytics::query_params::extract_captured_params;
 use crate::analytics::{ClickDetail, DataRetentionTask, RawClickEvent, RollupManager};
 use crate::config::{get_runtime_config, init_runtime_config, keys};
 use crate::services::{
-    AnalyticsService, ConfigService, ForgeLinkCache, LinkCache, LinkService, UserAgentStore,
-    get_user_agent_store, set_global_user_agent_store,
+    AnalyticsService, ApiTokenService, ConfigService, ForgeLinkCache, LinkCache, LinkService,
+    UserAgentStore, get_user_agent_store, set_global_user_agent_store,
 };
 use crate::storage::{SeaOrmStorage, StorageFactory};
 use anyhow::{Context, Result};
@@ -22,6 +23,7 @@ pub struct StartupContext {
     pub cache: Arc<dyn LinkCache>,
     pub link_service: Arc<LinkService>,
     pub analytics_service: Arc<AnalyticsService>,
+    pub api_token_service: Arc<ApiTokenService>,
     pub config_service: Arc<ConfigService>,
     pub route_config: RouteConfig,
     pub metrics: Arc<dyn MetricsRecorder>,
@@ -44,6 +46,10 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
     let start_time = std::time::Instant::now();
     debug!("Starting pre-startup processing...");
 
+    // 运行时写路径自检（只读根文件系统下需挂载可写卷）
+    let runtime_dir = crate::system::runtime_dir::ensure_writable().map_err(anyhow::Error::msg)?;
+    debug!("Runtime directory: {}", runtime_dir.display());
+
     let process_guard = crate::system::platform::ProcessGuard::acquire()
         .context("Failed to acquire process guard")?;
 
@@ -106,7 +112,7 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
                     max_clicks_before_flush as usize,
                     metrics.clone(),
                 );
-                let mgr = Arc::new(manager);
+                let mgr = Arc::new(manager.with_timing_sink(storage.clone()));
                 raw_event_receiver = Some(rx);
 
                 mgr
@@ -117,7 +123,7 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
                     max_clicks_before_flush as usize,
                     metrics.clone(),
                 );
-                Arc::new(manager)
+                Arc::new(manager.with_timing_sink(storage.clone()))
             };
 
             set_global_click_manager(mgr.clone());
@@ -158,6 +164,9 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
     // Create AnalyticsService for analytics queries
     let analytics_service = Arc::new(AnalyticsService::new(storage.clone()));
 
+    // Create ApiTokenService for team tokens and quotas
+    let api_token_service = Arc::new(ApiTokenService::new(storage.clone()));
+
     // Create ConfigService for runtime configuration management
     let config_service = Arc::new(ConfigService::new().context("Failed to create ConfigService")?);
 
@@ -185,6 +194,9 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
     // Initialize IPC start time. The runtime task group owns the server loop.
     crate::system::ipc::handler::init_start_time();
 
+    // IPC command metrics (slow-command logs and status statistics work without it)
+    crate::system::ipc::stats::init_metrics(metrics.clone());
+
     // 提取路由配置（从 RuntimeConfig 读取）
     let rt = get_runtime_config();
     let route_config = RouteConfig {
@@ -207,6 +219,7 @@ pub async fn prepare_server_startup() -> Result<StartupContext> {
         cache,
         link_service,
         analytics_service,
+        api_token_service,
         config_service,
         route_config,
         metrics,
@@ -231,6 +244,15 @@ fn check_component_enabled(route_config: &RouteConfig) {
         );
     }
 
+    // 检查短链公开地址
+    if crate::utils::base_url::configured_base_url().is_none() {
+        warn!(
+            "features.public_base_url is not set. Full short link URLs (panel, QR codes) \
+            will be inferred per request from Forwarded / X-Forwarded-* headers sent by \
+            api.trusted_proxies, otherwise from the Host header. Set it explicitly in production."
+        );
+    }
+
     // 检查 Admin API 是否启用
     let admin_token = rt.get_or(keys::API_ADMIN_TOKEN, "");
     if admin_token.is_empty() {
@@ -292,6 +314,8 @@ pub(crate) fn process_raw_click_event(event: RawClickEvent) -> ClickDetail {
     // 在消费者端读取配置（不在热路径读取）
     let rt = get_runtime_config();
     let enable_ip_logging = rt.get_bool_or(keys::ANALYTICS_ENABLE_IP_LOGGING, true);
+    let captured_params: Vec<String> =
+        rt.get_json_or(keys::ANALYTICS_CAPTURED_QUERY_PARAMS, Vec::new());
 
     // derive_source: utm_source > ref:{domain} > direct
     let source = derive_source_from_raw(&event.query, &event.referrer);
@@ -304,6 +328,13 @@ pub(crate) fn process_raw_click_event(event: RawClickEvent) -> ClickDetail {
 
     let ip_address = if enable_ip_logging { event.ip } else { None };
 
+    // 只保留白名单内的参数，其余 query 内容随事件一起丢弃
+    let query_params = event
+        .query
+        .as_deref()
+        .map(|query| extract_captured_params(query, &captured_params))
+        .unwrap_or_default();
+
     ClickDetail {
         code: event.code,
         timestamp: Utc::now(),
@@ -313,6 +344,7 @@ pub(crate) fn process_raw_click_event(event: RawClickEvent) -> ClickDetail {
         country: None, // GeoIP 查询暂不在 channel 处理器中做
         city: None,
         source,
+        query_params,
     }
 }
 
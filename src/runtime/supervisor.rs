@@ -0,0 +1,701 @@
+//! 后台任务监控
+//!
+//! 所有长期运行的后台任务都经由 [`TaskRegistry::supervise`] 启动：
+//!
+//! - 任务在独立的 tokio task 中运行，panic 不再被静默吞掉，而是记录下来并按
+//!   [`RestartPolicy`] 退避重启；连续重启超过上限后标记为 `dead` 并告警
+//! - 任务通过 [`Heartbeat`] 定期上报心跳，超过声明间隔的 [`STALL_FACTOR`] 倍仍未上报
+//!   即视为 `stalled`，由 watchdog 告警（日志、指标与 `alerts.webhook_url`）
+//! - 注册表供 `GET /admin/v1/tasks/health` 与 IPC `status` 展示
+//!
+//! 状态只存本进程内存，重启进程后清零。
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::metrics::MetricsRecorder;
+use crate::storage::backend::statement_log::with_operation;
+
+/// 心跳超过声明间隔的多少倍视为 stalled（允许错过一次）
+pub const STALL_FACTOR: u32 = 2;
+
+/// watchdog 检查心跳的间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(30);
+
+static REGISTRY: OnceLock<Arc<TaskRegistry>> = OnceLock::new();
+
+/// 初始化进程级任务注册表（重复调用返回同一个）
+pub fn init(metrics: Arc<dyn MetricsRecorder>) -> Arc<TaskRegistry> {
+    REGISTRY.get_or_init(|| TaskRegistry::new(metrics)).clone()
+}
+
+/// 进程级任务注册表，server 未启动后台任务时为 `None`
+pub fn registry() -> Option<&'static Arc<TaskRegistry>> {
+    REGISTRY.get()
+}
+
+/// 后台任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Running,
+    /// 心跳超时
+    Stalled,
+    /// panic 后等待退避重启
+    Restarting,
+    /// 重启次数用尽，不再重启
+    Dead,
+    /// 正常退出（关闭流程）
+    Stopped,
+}
+
+impl TaskState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Stalled => "stalled",
+            Self::Restarting => "restarting",
+            Self::Dead => "dead",
+            Self::Stopped => "stopped",
+        }
+    }
+
+    /// 指标用的数值：0 = running，1 = stalled，2 = restarting，3 = dead，4 = stopped
+    pub fn as_gauge(&self) -> f64 {
+        match self {
+            Self::Running => 0.0,
+            Self::Stalled => 1.0,
+            Self::Restarting => 2.0,
+            Self::Dead => 3.0,
+            Self::Stopped => 4.0,
+        }
+    }
+
+    /// 是否需要关注（stalled / restarting / dead）
+    pub fn is_unhealthy(&self) -> bool {
+        matches!(self, Self::Stalled | Self::Restarting | Self::Dead)
+    }
+}
+
+/// panic 后的重启策略
+///
+/// 退避从 `initial_backoff` 开始每次翻倍，不超过 `max_backoff`。一次运行持续超过
+/// `max_backoff` 后连续 panic 计数清零，偶发 panic 不会慢慢耗尽重启次数。
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// 连续 panic 后最多重启次数，0 = 不重启
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// 第 `attempt` 次（从 0 起）重启前的等待时间
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// 后台任务声明
+#[derive(Debug, Clone, Copy)]
+pub struct TaskSpec {
+    pub name: &'static str,
+    /// 预期心跳间隔，`None` = 不检查心跳（只监控 panic）
+    pub heartbeat_interval: Option<Duration>,
+    pub restart: RestartPolicy,
+}
+
+impl TaskSpec {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            heartbeat_interval: None,
+            restart: RestartPolicy::default(),
+        }
+    }
+
+    /// 声明任务至少每隔 `interval` 上报一次心跳
+    pub fn heartbeat_every(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = Some(interval);
+        self
+    }
+
+    pub fn restart_policy(mut self, restart: RestartPolicy) -> Self {
+        self.restart = restart;
+        self
+    }
+}
+
+/// 单个任务的健康快照
+#[derive(Debug, Clone)]
+pub struct TaskHealth {
+    pub name: &'static str,
+    pub state: TaskState,
+    /// 启动以来的重启次数
+    pub restarts: u32,
+    pub heartbeat_interval: Option<Duration>,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    /// 距上次心跳的时长
+    pub heartbeat_age: Option<Duration>,
+    pub last_panic: Option<String>,
+    pub last_panic_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct EntryState {
+    state: TaskState,
+    restarts: u32,
+    last_panic: Option<(DateTime<Utc>, String)>,
+    /// 当前这次 stalled 是否已告警
+    stall_alerted: bool,
+}
+
+#[derive(Debug)]
+struct TaskEntry {
+    name: &'static str,
+    epoch: Instant,
+    /// 预期心跳间隔（毫秒），0 = 不检查
+    interval_ms: AtomicU64,
+    /// 最近心跳相对 `epoch` 的毫秒数，`u64::MAX` = 尚未上报
+    last_beat_ms: AtomicU64,
+    state: Mutex<EntryState>,
+}
+
+impl TaskEntry {
+    fn lock(&self) -> std::sync::MutexGuard<'_, EntryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        match self.interval_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    fn beat_at(&self, now: Instant) {
+        let ms = now.saturating_duration_since(self.epoch).as_millis() as u64;
+        self.last_beat_ms.store(ms, Ordering::Relaxed);
+    }
+
+    fn last_beat(&self) -> Option<Instant> {
+        match self.last_beat_ms.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            ms => Some(self.epoch + Duration::from_millis(ms)),
+        }
+    }
+
+    /// 心跳是否超时（只对 running 状态有意义）
+    fn is_overdue(&self, now: Instant) -> bool {
+        let (Some(interval), Some(last)) = (self.interval(), self.last_beat()) else {
+            return false;
+        };
+        now.saturating_duration_since(last) > interval.saturating_mul(STALL_FACTOR)
+    }
+
+    fn effective_state(&self, stored: TaskState, now: Instant) -> TaskState {
+        if stored == TaskState::Running && self.is_overdue(now) {
+            TaskState::Stalled
+        } else {
+            stored
+        }
+    }
+}
+
+/// 任务内部上报心跳的句柄
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    entry: Arc<TaskEntry>,
+}
+
+impl Heartbeat {
+    /// 上报一次心跳
+    pub fn beat(&self) {
+        self.entry.beat_at(Instant::now());
+    }
+
+    /// 调整预期心跳间隔（间隔随运行时配置变化的任务），`None` = 暂停检查
+    pub fn set_interval(&self, interval: Option<Duration>) {
+        let ms = interval.map_or(0, |d| (d.as_millis() as u64).max(1));
+        self.entry.interval_ms.store(ms, Ordering::Relaxed);
+    }
+}
+
+/// stalled 状态变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StallEvent {
+    Stalled(&'static str),
+    Recovered(&'static str),
+}
+
+/// 后台任务注册表
+pub struct TaskRegistry {
+    epoch: Instant,
+    epoch_wall: DateTime<Utc>,
+    tasks: Mutex<Vec<Arc<TaskEntry>>>,
+    metrics: Arc<dyn MetricsRecorder>,
+}
+
+impl TaskRegistry {
+    pub fn new(metrics: Arc<dyn MetricsRecorder>) -> Arc<Self> {
+        Arc::new(Self {
+            epoch: Instant::now(),
+            epoch_wall: Utc::now(),
+            tasks: Mutex::new(Vec::new()),
+            metrics,
+        })
+    }
+
+    fn register(&self, spec: &TaskSpec) -> Arc<TaskEntry> {
+        let entry = Arc::new(TaskEntry {
+            name: spec.name,
+            epoch: self.epoch,
+            interval_ms: AtomicU64::new(0),
+            last_beat_ms: AtomicU64::new(u64::MAX),
+            state: Mutex::new(EntryState {
+                state: TaskState::Running,
+                restarts: 0,
+                last_panic: None,
+                stall_alerted: false,
+            }),
+        });
+        Heartbeat {
+            entry: entry.clone(),
+        }
+        .set_interval(spec.heartbeat_interval);
+
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|t| t.name != spec.name);
+        tasks.push(entry.clone());
+        entry
+    }
+
+    fn to_wall(&self, at: Instant) -> DateTime<Utc> {
+        let offset = at.saturating_duration_since(self.epoch);
+        self.epoch_wall
+            + chrono::Duration::from_std(offset).unwrap_or_else(|_| chrono::Duration::zero())
+    }
+
+    fn set_state(&self, entry: &TaskEntry, state: TaskState) {
+        entry.lock().state = state;
+        self.metrics
+            .set_background_task_state(entry.name, state.as_gauge());
+    }
+
+    /// 所有任务的健康快照（按注册顺序）
+    pub fn snapshot(&self) -> Vec<TaskHealth> {
+        self.snapshot_at(Instant::now())
+    }
+
+    pub fn snapshot_at(&self, now: Instant) -> Vec<TaskHealth> {
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        tasks
+            .iter()
+            .map(|entry| {
+                let state = entry.lock();
+                let last_beat = entry.last_beat();
+                TaskHealth {
+                    name: entry.name,
+                    state: entry.effective_state(state.state, now),
+                    restarts: state.restarts,
+                    heartbeat_interval: entry.interval(),
+                    last_heartbeat: last_beat.map(|at| self.to_wall(at)),
+                    heartbeat_age: last_beat.map(|at| now.saturating_duration_since(at)),
+                    last_panic: state.last_panic.as_ref().map(|(_, msg)| msg.clone()),
+                    last_panic_at: state.last_panic.as_ref().map(|(at, _)| *at),
+                }
+            })
+            .collect()
+    }
+
+    /// 检查心跳，返回本次新进入或离开 stalled 的任务（每次 stalled 只告警一次）
+    pub fn check_stalled(&self, now: Instant) -> Vec<StallEvent> {
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut events = Vec::new();
+        for entry in tasks {
+            let mut state = entry.lock();
+            let stalled = entry.effective_state(state.state, now) == TaskState::Stalled;
+            if stalled == state.stall_alerted {
+                continue;
+            }
+            state.stall_alerted = stalled;
+            drop(state);
+            if stalled {
+                self.metrics
+                    .set_background_task_state(entry.name, TaskState::Stalled.as_gauge());
+                events.push(StallEvent::Stalled(entry.name));
+            } else {
+                let current = entry.lock().state;
+                self.metrics
+                    .set_background_task_state(entry.name, current.as_gauge());
+                events.push(StallEvent::Recovered(entry.name));
+            }
+        }
+        events
+    }
+
+    /// 周期检查心跳并告警，直到 `shutdown` 被取消
+    pub async fn run_watchdog(self: Arc<Self>, shutdown: CancellationToken) {
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = tokio::time::sleep(WATCHDOG_INTERVAL) => {}
+            }
+            let now = Instant::now();
+            for event in self.check_stalled(now) {
+                match event {
+                    StallEvent::Stalled(name) => {
+                        let health = self.snapshot_at(now).into_iter().find(|t| t.name == name);
+                        let interval = health.as_ref().and_then(|t| t.heartbeat_interval);
+                        let age = health.as_ref().and_then(|t| t.heartbeat_age);
+                        warn!(
+                            "Background task '{}' stalled: no heartbeat for {:?} (expected every {:?})",
+                            name,
+                            age.unwrap_or_default(),
+                            interval.unwrap_or_default()
+                        );
+                        post_alert(serde_json::json!({
+                            "event": "background_task_stalled",
+                            "task": name,
+                            "last_heartbeat": health.and_then(|t| t.last_heartbeat),
+                            "heartbeat_interval_secs": interval.map(|d| d.as_secs()),
+                        }))
+                        .await;
+                    }
+                    StallEvent::Recovered(name) => {
+                        info!("Background task '{}' recovered, heartbeat resumed", name);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 在监控下运行后台任务
+    ///
+    /// `factory` 每次（首次与每次重启）创建一个新的任务 future，传入该任务的
+    /// [`Heartbeat`]。任务正常返回视为停止；panic 时按策略重启，`shutdown` 取消后
+    /// 不再重启。返回的 future 被丢弃时一并中止正在运行的任务。
+    pub fn supervise<F, Fut>(
+        self: &Arc<Self>,
+        spec: TaskSpec,
+        shutdown: CancellationToken,
+        mut factory: F,
+    ) -> impl Future<Output = ()> + Send + 'static
+    where
+        F: FnMut(Heartbeat) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let registry = self.clone();
+        let entry = registry.register(&spec);
+        registry.set_state(&entry, TaskState::Running);
+        async move {
+            let heartbeat = Heartbeat {
+                entry: entry.clone(),
+            };
+            let mut consecutive = 0u32;
+            loop {
+                heartbeat.beat();
+                let started = Instant::now();
+                // 任务内执行的 SQL 在语句日志中记为 `task:<name>`
+                let operation = format!("task:{}", spec.name);
+                let mut task = AbortOnDrop(tokio::spawn(with_operation(
+                    operation,
+                    factory(heartbeat.clone()),
+                )));
+                let panic = match (&mut task.0).await {
+                    Ok(()) => None,
+                    Err(e) if e.is_panic() => Some(panic_message(e.into_panic())),
+                    // 运行时关闭时被取消
+                    Err(_) => None,
+                };
+                let Some(message) = panic else {
+                    registry.set_state(&entry, TaskState::Stopped);
+                    return;
+                };
+
+                error!("Background task '{}' panicked: {}", spec.name, message);
+                registry.metrics.inc_background_task_panic(spec.name);
+                if started.elapsed() > spec.restart.max_backoff {
+                    consecutive = 0;
+                }
+                {
+                    let mut state = entry.lock();
+                    state.last_panic = Some((Utc::now(), message.clone()));
+                }
+
+                if shutdown.is_cancelled() {
+                    registry.set_state(&entry, TaskState::Stopped);
+                    return;
+                }
+                if consecutive >= spec.restart.max_restarts {
+                    registry.set_state(&entry, TaskState::Dead);
+                    error!(
+                        "Background task '{}' is dead after {} consecutive restarts",
+                        spec.name, consecutive
+                    );
+                    post_alert(serde_json::json!({
+                        "event": "background_task_dead",
+                        "task": spec.name,
+                        "restarts": entry.lock().restarts,
+                        "last_panic": message,
+                    }))
+                    .await;
+                    return;
+                }
+
+                let backoff = spec.restart.backoff(consecutive);
+                registry.set_state(&entry, TaskState::Restarting);
+                warn!(
+                    "Background task '{}' restarting in {:?} ({}/{})",
+                    spec.name,
+                    backoff,
+                    consecutive + 1,
+                    spec.restart.max_restarts
+                );
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        registry.set_state(&entry, TaskState::Stopped);
+                        return;
+                    }
+                    _ = tokio::time::sleep(backoff) => {}
+                }
+                consecutive += 1;
+                entry.lock().restarts += 1;
+                registry.set_state(&entry, TaskState::Running);
+            }
+        }
+    }
+}
+
+/// 被丢弃时中止任务，避免监控 future 被取消后任务脱离管理继续运行
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// 投递到 `alerts.webhook_url`（未配置时只有日志与指标）
+async fn post_alert(payload: serde_json::Value) {
+    let Some(url) = try_get_runtime_config()
+        .map(|rt| rt.get_or(keys::ALERTS_WEBHOOK_URL, ""))
+        .filter(|url| !url.is_empty())
+    else {
+        return;
+    };
+    let result =
+        tokio::task::spawn_blocking(move || crate::analytics::anomaly::post_webhook(&url, payload))
+            .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("Background task alert webhook failed: {}", e),
+        Err(e) => warn!("Background task alert webhook task failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::NoopMetrics;
+    use std::sync::atomic::AtomicU32;
+
+    fn fast_policy(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    fn health(registry: &TaskRegistry, name: &str) -> TaskHealth {
+        registry
+            .snapshot()
+            .into_iter()
+            .find(|t| t.name == name)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_is_restarted() {
+        let registry = TaskRegistry::new(NoopMetrics::arc());
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+
+        // 第一次运行 panic，重启后正常返回
+        registry
+            .supervise(
+                TaskSpec::new("flaky").restart_policy(fast_policy(3)),
+                CancellationToken::new(),
+                move |_| {
+                    let run = counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        if run == 0 {
+                            panic!("rollup exploded");
+                        }
+                    }
+                },
+            )
+            .await;
+
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        let task = health(&registry, "flaky");
+        assert_eq!(task.restarts, 1);
+        assert_eq!(task.state, TaskState::Stopped);
+        assert_eq!(task.last_panic.as_deref(), Some("rollup exploded"));
+        assert!(task.last_panic_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_task_dead_after_max_restarts() {
+        let registry = TaskRegistry::new(NoopMetrics::arc());
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+
+        registry
+            .supervise(
+                TaskSpec::new("broken").restart_policy(fast_policy(2)),
+                CancellationToken::new(),
+                move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { panic!("always") }
+                },
+            )
+            .await;
+
+        // 首次运行 + 2 次重启
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        let task = health(&registry, "broken");
+        assert_eq!(task.state, TaskState::Dead);
+        assert_eq!(task.restarts, 2);
+        assert!(task.state.is_unhealthy());
+    }
+
+    #[tokio::test]
+    async fn test_no_restart_after_shutdown() {
+        let registry = TaskRegistry::new(NoopMetrics::arc());
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+
+        registry
+            .supervise(
+                TaskSpec::new("closing").restart_policy(fast_policy(5)),
+                shutdown,
+                |_| async { panic!("during shutdown") },
+            )
+            .await;
+
+        let task = health(&registry, "closing");
+        assert_eq!(task.state, TaskState::Stopped);
+        assert_eq!(task.restarts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_heartbeat_marks_stalled() {
+        let registry = TaskRegistry::new(NoopMetrics::arc());
+        let shutdown = CancellationToken::new();
+        let interval = Duration::from_secs(30);
+        let task = tokio::spawn(registry.supervise(
+            TaskSpec::new("ticker").heartbeat_every(interval),
+            shutdown.clone(),
+            |heartbeat| async move {
+                heartbeat.beat();
+                std::future::pending::<()>().await;
+            },
+        ));
+        tokio::task::yield_now().await;
+
+        let now = Instant::now();
+        assert_eq!(registry.snapshot_at(now)[0].state, TaskState::Running);
+        assert!(registry.check_stalled(now).is_empty());
+
+        // 超过声明间隔的 STALL_FACTOR 倍
+        let later = now + interval * STALL_FACTOR + Duration::from_secs(1);
+        assert_eq!(registry.snapshot_at(later)[0].state, TaskState::Stalled);
+        assert_eq!(
+            registry.check_stalled(later),
+            vec![StallEvent::Stalled("ticker")]
+        );
+        // 同一次 stalled 只告警一次
+        assert!(registry.check_stalled(later).is_empty());
+
+        // 心跳恢复
+        let entry = registry.tasks.lock().unwrap()[0].clone();
+        entry.beat_at(later);
+        assert_eq!(
+            registry.check_stalled(later),
+            vec![StallEvent::Recovered("ticker")]
+        );
+
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_interval_can_be_disabled() {
+        let registry = TaskRegistry::new(NoopMetrics::arc());
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let mut ready_tx = Some(ready_tx);
+        let task = tokio::spawn(registry.supervise(
+            TaskSpec::new("idle").heartbeat_every(Duration::from_secs(1)),
+            CancellationToken::new(),
+            move |heartbeat| {
+                let ready = ready_tx.take();
+                async move {
+                    heartbeat.set_interval(None);
+                    if let Some(ready) = ready {
+                        let _ = ready.send(());
+                    }
+                    std::future::pending::<()>().await;
+                }
+            },
+        ));
+        ready_rx.await.unwrap();
+
+        let later = Instant::now() + Duration::from_secs(3600);
+        let snapshot = registry.snapshot_at(later);
+        assert_eq!(snapshot[0].state, TaskState::Running);
+        assert_eq!(snapshot[0].heartbeat_interval, None);
+        task.abort();
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RestartPolicy {
+            max_restarts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(8));
+        assert_eq!(policy.backoff(4), Duration::from_secs(10));
+        assert_eq!(policy.backoff(40), Duration::from_secs(10));
+    }
+}
@@ -1,17 +1,24 @@
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
 use aster_forge_tasks::BackgroundTasks;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
-use crate::analytics::{ClickManager, DataRetentionTask, RawClickEvent};
+use crate::analytics::{AnomalyDetectionTask, ClickManager, DataRetentionTask, RawClickEvent};
+use crate::runtime::lifecycle::{LifecycleHooks, StartGate};
+use crate::runtime::shutdown::ServerShutdown;
 use crate::runtime::startup::{StartupContext, process_raw_click_event};
-use crate::services::LinkCache;
+use crate::runtime::supervisor::{self, Heartbeat, TaskRegistry, TaskSpec};
+use crate::services::bloom_stats::{bloom_alert_threshold, emit_capacity_alert};
+use crate::services::{LinkCache, REPLAY_GRACE, SideEffectRunner};
+use crate::storage::SeaOrmStorage;
 
 pub struct BackgroundTaskResources {
     metrics: Arc<dyn crate::metrics::MetricsRecorder>,
     database: sea_orm::DatabaseConnection,
+    storage: Arc<SeaOrmStorage>,
     cache: Arc<dyn LinkCache>,
     click_manager: Option<Arc<ClickManager>>,
     raw_event_receiver: Option<crossbeam_channel::Receiver<RawClickEvent>>,
@@ -23,6 +30,7 @@ impl From<&StartupContext> for BackgroundTaskResources {
         Self {
             metrics: startup.metrics.clone(),
             database: startup.storage.get_db().clone(),
+            storage: startup.storage.clone(),
             cache: startup.cache.clone(),
             click_manager: startup.click_manager.clone(),
             raw_event_receiver: startup.raw_event_receiver.clone(),
@@ -31,61 +39,273 @@ impl From<&StartupContext> for BackgroundTaskResources {
     }
 }
 
-pub fn spawn_background_tasks(
+/// 内置组件启动 hook 的超时
+const BUILTIN_START_TIMEOUT: Duration = Duration::from_secs(5);
+/// 内置调度器停止 hook 的超时
+const SCHEDULER_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 内置组件（IPC server、ClickManager、调度器）的启动闸门
+#[derive(Clone, Default)]
+pub(crate) struct BuiltinGates {
+    ipc_server: StartGate,
+    click_manager: StartGate,
+    scheduler: StartGate,
+}
+
+/// 把内置组件注册为生命周期 hook
+///
+/// 启动顺序：`builtin:ipc_server`（socket 绑定完成）→ `builtin:click_manager` →
+/// `builtin:scheduler`，均在用户 `post_start` hook 之前，各自超时 5 秒；超时或失败只记录，
+/// 组件就绪后照常运行。关闭时 `builtin:scheduler` 在用户 `pre_shutdown` hook 之后停止
+/// 调度任务，HTTP drain 期间不再开始新的周期任务；IPC server 与 ClickManager 需要在
+/// HTTP drain 之后停止，仍由有序关闭阶段负责。
+pub(crate) fn register_builtin_hooks(
+    hooks: &mut LifecycleHooks,
+    gates: &BuiltinGates,
+    shutdown: &ServerShutdown,
+) {
+    let gate = gates.ipc_server.clone();
+    hooks.on_builtin_post_start("ipc_server", BUILTIN_START_TIMEOUT, move |_| async move {
+        gate.open().await
+    });
+    if shutdown.analytics.is_some() {
+        let gate = gates.click_manager.clone();
+        hooks.on_builtin_post_start(
+            "click_manager",
+            BUILTIN_START_TIMEOUT,
+            move |_| async move { gate.open().await },
+        );
+    }
+    let gate = gates.scheduler.clone();
+    hooks.on_builtin_post_start("scheduler", BUILTIN_START_TIMEOUT, move |_| async move {
+        gate.open().await
+    });
+
+    let shutdown = shutdown.clone();
+    hooks.on_builtin_pre_shutdown("scheduler", SCHEDULER_STOP_TIMEOUT, move |_| async move {
+        shutdown.stop_scheduler().await;
+        Ok(())
+    });
+}
+
+/// 创建后台任务
+///
+/// IPC server、ClickManager 与调度任务先在各自的 [`StartGate`] 等待，由
+/// [`register_builtin_hooks`] 注册的内置 hook 按序放行。各任务监听 [`ServerShutdown`]
+/// 中属于自己的阶段，由关闭编排按顺序停止：IPC server 随 HTTP 一起停止接收并 drain，
+/// ClickManager 在 `flush_analytics` 阶段刷写，调度任务由内置 `pre_shutdown` hook 停止
+/// （兜底为 `stop_background_tasks` 阶段）。
+///
+/// 除 aster-forge 提供的系统指标任务外，所有任务都在 [`supervisor`] 监控下运行：
+/// panic 后退避重启，心跳超时标记 stalled。
+pub(crate) fn spawn_background_tasks(
     resources: BackgroundTaskResources,
+    shutdown: ServerShutdown,
     shutdown_token: CancellationToken,
+    gates: BuiltinGates,
 ) -> BackgroundTasks {
-    let mut tasks = BackgroundTasks::with_shutdown_token(shutdown_token.clone());
+    let mut tasks = BackgroundTasks::with_shutdown_token(shutdown_token);
+    let background = shutdown.background.clone();
+    let registry = supervisor::init(resources.metrics.clone());
+    tasks.push(named(
+        "task_watchdog",
+        registry.clone().run_watchdog(background.clone()),
+    ));
 
     if let Some(task) = resources
         .metrics
         .forge_recorder()
-        .system_metrics_updater_task(shutdown_token.clone())
+        .system_metrics_updater_task(background.clone())
     {
         tasks.push(task);
     }
 
-    tasks.push(run_user_agent_flush(
-        resources.database.clone(),
-        shutdown_token.clone(),
+    let ipc = shutdown.ipc.clone();
+    let ipc_done = ipc.guard();
+    let ipc_gate = gates.ipc_server;
+    let ipc_server = registry.supervise(TaskSpec::new("ipc_server"), ipc.token(), {
+        let (ipc, ipc_abort, listening) = (ipc.clone(), background.clone(), ipc_gate.ready_token());
+        move |_| {
+            crate::system::ipc::server::run_ipc_server_with_drain(
+                ipc.token(),
+                ipc_abort.clone(),
+                listening.clone(),
+            )
+        }
+    });
+    tasks.push(named("ipc_server", async move {
+        let _exited = ipc_gate.exit_guard();
+        if ipc_gate.wait_open(&ipc.token()).await {
+            ipc_server.await;
+        }
+        drop(ipc_done);
+    }));
+
+    let database = resources.database.clone();
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        TaskSpec::new("user_agent_flush").heartbeat_every(USER_AGENT_FLUSH_INTERVAL),
+        move |token, heartbeat| run_user_agent_flush(database.clone(), token, heartbeat),
     ));
-    tasks.push(run_bloom_rebuild(resources.cache, shutdown_token.clone()));
-    tasks.push(crate::system::ipc::server::run_ipc_server(
-        shutdown_token.clone(),
+    let (storage, cache) = (resources.storage.clone(), resources.cache.clone());
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        TaskSpec::new("side_effect_replay").heartbeat_every(SIDE_EFFECT_REPLAY_INTERVAL),
+        move |token, heartbeat| {
+            let runner = SideEffectRunner::new(storage.clone(), cache.clone());
+            run_side_effect_replay(runner, token, heartbeat)
+        },
+    ));
+    let database = resources.database.clone();
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        TaskSpec::new("db_maintenance"),
+        move |token, heartbeat| run_db_maintenance(database.clone(), token, heartbeat),
+    ));
+    let (cache, metrics) = (resources.cache.clone(), resources.metrics.clone());
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        TaskSpec::new("bloom_fp_check").heartbeat_every(BLOOM_FP_CHECK_INTERVAL),
+        move |token, heartbeat| {
+            run_bloom_fp_check(cache.clone(), metrics.clone(), token, heartbeat)
+        },
+    ));
+    let cache = resources.cache.clone();
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        TaskSpec::new("bloom_rebuild"),
+        move |token, heartbeat| run_bloom_rebuild(cache.clone(), token, heartbeat),
+    ));
+    let (storage, metrics) = (resources.storage.clone(), resources.metrics.clone());
+    tasks.push(tracked(
+        &shutdown,
+        &registry,
+        &gates.scheduler,
+        // 每个整点后运行一次
+        TaskSpec::new("anomaly_detection").heartbeat_every(Duration::from_secs(60 * 60)),
+        move |token, heartbeat| {
+            let task = AnomalyDetectionTask::new(storage.clone(), metrics.clone());
+            run_anomaly_detection(task, token, heartbeat)
+        },
     ));
 
     if let Some(retention_task) = resources.retention_task {
-        tasks.push(run_retention(retention_task, shutdown_token.clone()));
-    }
-    if let Some(click_manager) = resources.click_manager {
-        tasks.push(run_click_manager(
-            click_manager,
-            resources.raw_event_receiver,
-            shutdown_token,
+        tasks.push(tracked(
+            &shutdown,
+            &registry,
+            &gates.scheduler,
+            TaskSpec::new("data_retention").heartbeat_every(RETENTION_INTERVAL),
+            move |token, heartbeat| run_retention(retention_task.clone(), token, heartbeat),
         ));
     }
+    if let (Some(click_manager), Some(stage)) = (resources.click_manager, shutdown.analytics) {
+        let flushed = stage.guard();
+        let gate = gates.click_manager;
+        tasks.push(named("click_manager", async move {
+            let _exited = gate.exit_guard();
+            if gate.wait_open(&stage.token()).await {
+                run_click_manager(
+                    click_manager,
+                    resources.raw_event_receiver,
+                    stage.token(),
+                    registry,
+                    &gate,
+                )
+                .await;
+            }
+            drop(flushed);
+        }));
+    }
 
     tasks
 }
 
+/// 在监控下运行的调度任务：由 `builtin:scheduler` hook 放行与停止，
+/// 并登记到 `stop_background_tasks` 阶段兜底
+///
+/// `factory` 在首次启动与每次 panic 重启时调用，传入关闭 token 与心跳句柄。
+fn tracked<F, Fut>(
+    shutdown: &ServerShutdown,
+    registry: &Arc<TaskRegistry>,
+    gate: &StartGate,
+    spec: TaskSpec,
+    mut factory: F,
+) -> impl Future<Output = ()> + Send + 'static
+where
+    F: FnMut(CancellationToken, Heartbeat) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let stopped = shutdown.track_task(spec.name);
+    let token = shutdown.scheduler.clone();
+    let gate = gate.clone();
+    let task = registry.supervise(spec, token.clone(), {
+        let token = token.clone();
+        move |heartbeat| factory(token.clone(), heartbeat)
+    });
+    named(spec.name, async move {
+        if gate.wait_open(&token).await {
+            gate.ready();
+            task.await;
+        }
+        drop(stopped);
+    })
+}
+
+/// 为后台任务输出统一格式的启停日志
+fn named<F>(name: &'static str, task: F) -> impl Future<Output = ()> + Send + 'static
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    async move {
+        info!("Lifecycle: background task '{}' started", name);
+        task.await;
+        info!("Lifecycle: background task '{}' stopped", name);
+    }
+}
+
 async fn run_click_manager(
     manager: Arc<ClickManager>,
     raw_event_receiver: Option<crossbeam_channel::Receiver<RawClickEvent>>,
     shutdown_token: CancellationToken,
+    registry: Arc<TaskRegistry>,
+    gate: &StartGate,
 ) {
     let mut workers = tokio::task::JoinSet::new();
     let background_manager = manager.clone();
-    workers.spawn(async move {
-        background_manager.start_background_task().await;
-    });
+    workers.spawn(registry.supervise(
+        TaskSpec::new("click_flush").heartbeat_every(manager.flush_interval()),
+        shutdown_token.clone(),
+        move |heartbeat| {
+            let manager = background_manager.clone();
+            async move { manager.start_background_task(&heartbeat).await }
+        },
+    ));
     if let Some(receiver) = raw_event_receiver {
         let event_manager = manager.clone();
-        workers.spawn(async move {
-            event_manager
-                .start_event_processor(receiver, process_raw_click_event)
-                .await;
-        });
+        workers.spawn(registry.supervise(
+            TaskSpec::new("click_events").heartbeat_every(Duration::from_secs(60)),
+            shutdown_token.clone(),
+            move |heartbeat| {
+                let (manager, receiver) = (event_manager.clone(), receiver.clone());
+                async move {
+                    manager
+                        .start_event_processor(receiver, process_raw_click_event, &heartbeat)
+                        .await
+                }
+            },
+        ));
     }
+    gate.ready();
 
     shutdown_token.cancelled().await;
     manager.cancel();
@@ -93,14 +313,21 @@ async fn run_click_manager(
     manager.flush().await;
 }
 
+const USER_AGENT_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+const SIDE_EFFECT_REPLAY_INTERVAL: Duration = Duration::from_secs(30);
+const BLOOM_FP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const RETENTION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
 async fn run_user_agent_flush(
     database: sea_orm::DatabaseConnection,
     shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
 ) {
     loop {
+        heartbeat.beat();
         tokio::select! {
             _ = shutdown_token.cancelled() => break,
-            _ = tokio::time::sleep(Duration::from_secs(30)) => {
+            _ = tokio::time::sleep(USER_AGENT_FLUSH_INTERVAL) => {
                 if let Some(store) = crate::services::get_user_agent_store()
                     && let Err(error) = store.flush_pending(&database).await
                 {
@@ -111,17 +338,47 @@ async fn run_user_agent_flush(
     }
 }
 
-async fn run_bloom_rebuild(cache: Arc<dyn LinkCache>, shutdown_token: CancellationToken) {
+/// 启动时立即补偿一次上次崩溃残留的副作用，之后每 30 秒扫描一次
+async fn run_side_effect_replay(
+    runner: SideEffectRunner,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
+    loop {
+        if let Err(error) = runner.replay_pending(REPLAY_GRACE).await {
+            error!(%error, "pending side effect replay failed");
+        }
+        heartbeat.beat();
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            _ = tokio::time::sleep(SIDE_EFFECT_REPLAY_INTERVAL) => {}
+        }
+    }
+}
+
+async fn run_bloom_rebuild(
+    cache: Arc<dyn LinkCache>,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
     loop {
         let interval = crate::config::get_runtime_config()
             .get_u64_or(crate::config::keys::CACHE_BLOOM_REBUILD_INTERVAL, 0);
         if interval == 0 {
+            heartbeat.set_interval(None);
             shutdown_token.cancelled().await;
             return;
         }
+        heartbeat.set_interval(Some(Duration::from_secs(interval)));
+        heartbeat.beat();
         tokio::select! {
             _ = shutdown_token.cancelled() => return,
             _ = tokio::time::sleep(Duration::from_secs(interval)) => {
+                // 批量导入期间跳过：重建与逐块 Bloom 插入交错只会白白扫描数据库
+                if cache.bulk_write_in_progress() {
+                    info!("bulk write in progress, skipping periodic Bloom filter rebuild");
+                    continue;
+                }
                 if let Err(error) = cache.rebuild_all().await {
                     error!(%error, "periodic Bloom filter rebuild failed");
                 }
@@ -130,7 +387,68 @@ async fn run_bloom_rebuild(cache: Arc<dyn LinkCache>, shutdown_token: Cancellati
     }
 }
 
-async fn run_retention(task: Arc<DataRetentionTask>, shutdown_token: CancellationToken) {
+/// 每小时检查一次 Bloom 观测假阳率（`cache.bloom_fp_alert_rate`，0 = 关闭）
+///
+/// 每代 Bloom 最多告警一次；重建后计数清零，若容量仍不足会在新一代再次告警。
+async fn run_bloom_fp_check(
+    cache: Arc<dyn LinkCache>,
+    metrics: Arc<dyn crate::metrics::MetricsRecorder>,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
+    let mut alerted_generation = None;
+    loop {
+        heartbeat.beat();
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            _ = tokio::time::sleep(BLOOM_FP_CHECK_INTERVAL) => {}
+        }
+        let Some(stats) = cache.bloom_stats() else {
+            continue;
+        };
+        let threshold = bloom_alert_threshold();
+        if alerted_generation == Some(stats.generation) || !stats.exceeds(threshold) {
+            continue;
+        }
+        alerted_generation = Some(stats.generation);
+        emit_capacity_alert(&stats, threshold, metrics.as_ref()).await;
+    }
+}
+
+/// 周期数据库维护（`database.maintenance_interval_hours`，0 = 关闭）
+///
+/// 只执行轻量模式：VACUUM / VACUUM FULL / OPTIMIZE TABLE 会锁表或需要独占，只能手动执行。
+async fn run_db_maintenance(
+    database: sea_orm::DatabaseConnection,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
+    let hours = crate::config::get_config()
+        .database
+        .maintenance_interval_hours;
+    if hours == 0 {
+        shutdown_token.cancelled().await;
+        return;
+    }
+    let interval = Duration::from_secs(hours.saturating_mul(60 * 60));
+    heartbeat.set_interval(Some(interval));
+    loop {
+        heartbeat.beat();
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            _ = tokio::time::sleep(interval) => {}
+        }
+        if let Err(error) = crate::storage::backend::maintenance::maintain(&database, false).await {
+            error!(%error, "periodic database maintenance failed");
+        }
+    }
+}
+
+async fn run_retention(
+    task: Arc<DataRetentionTask>,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
     tokio::select! {
         _ = shutdown_token.cancelled() => return,
         _ = tokio::time::sleep(Duration::from_secs(300)) => {}
@@ -139,9 +457,32 @@ async fn run_retention(task: Arc<DataRetentionTask>, shutdown_token: Cancellatio
         if let Err(error) = task.run_cleanup().await {
             error!(%error, "data retention task failed");
         }
+        heartbeat.beat();
         tokio::select! {
             _ = shutdown_token.cancelled() => return,
-            _ = tokio::time::sleep(Duration::from_secs(24 * 60 * 60)) => {}
+            _ = tokio::time::sleep(RETENTION_INTERVAL) => {}
+        }
+    }
+}
+
+async fn run_anomaly_detection(
+    mut task: AnomalyDetectionTask,
+    shutdown_token: CancellationToken,
+    heartbeat: Heartbeat,
+) {
+    loop {
+        heartbeat.beat();
+        let delay = crate::analytics::anomaly::until_next_check(chrono::Utc::now());
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        // 每轮读取开关，修改 alerts.enabled 无需重启
+        if !AnomalyDetectionTask::enabled() {
+            continue;
+        }
+        if let Err(error) = task.run_once(chrono::Utc::now()).await {
+            error!(%error, "click anomaly detection failed");
         }
     }
 }
@@ -8,18 +8,28 @@
 //! - 原始查询（get_trends, get_top_links 等）：从 click_logs 表实时聚合，适用于小数据量
 //! - v2 查询（get_trends_v2 等）：从汇总表读取，性能更好，适用于大数据量
 //!
-//! 调用方可根据数据规模选择使用哪套方法。
+//! 调用方可根据数据规模选择使用哪套方法。Week / Month 粒度的趋势总是读取物化的
+//! 周 / 月汇总（`click_stats_weekly` / `click_stats_monthly`），周边界遵循
+//! `analytics.week_starts_on`。
 
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
 use std::sync::Arc;
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Utc};
 use futures_util::{Stream, StreamExt};
 use sea_orm::{DbBackend, sea_query::Expr};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
+use crate::analytics::query_params::MAX_NAME_LEN;
+use crate::analytics::timing::percentile;
+use crate::analytics::{PeriodGranularity, TimingPhase, WeekStart};
 use crate::errors::ShortlinkerError;
 use crate::storage::SeaOrmStorage;
+use crate::storage::backend::{PeriodTrendRow, TrendRow};
+
+/// 耗时趋势单次查询读取的样本上限（超出时只统计最新的样本）
+pub const MAX_TIMING_SAMPLES: u64 = 200_000;
 
 // ============ 公共类型定义 ============
 
@@ -91,6 +101,53 @@ pub struct CategoryStats {
     pub percentage: f64,
 }
 
+/// 自定义 query 参数取值分布
+#[derive(Debug, Clone)]
+pub struct ParamStats {
+    /// 分组的参数名
+    pub param: String,
+    /// 过滤条件 `(参数名, 值)`
+    pub filter: Option<(String, String)>,
+    /// 各取值的点击数，按点击数降序
+    pub values: Vec<CategoryStats>,
+    /// 参与统计的点击数（所有取值之和，截断前）
+    pub total: u64,
+    /// 过滤查询扫描的明细达到上限，只统计了最新的部分
+    pub truncated: bool,
+}
+
+/// Redirect 耗时趋势的分组方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimingGroupBy {
+    /// 每个阶段一条曲线
+    #[default]
+    Phase,
+    /// 按响应状态码分组的总耗时曲线
+    Status,
+}
+
+/// 一条耗时曲线（微秒）
+#[derive(Debug, Clone)]
+pub struct TimingSeries {
+    pub name: String,
+    /// 各时间桶的分位数，桶内没有样本时为 `None`
+    pub values: Vec<Option<u32>>,
+    /// 各时间桶的样本数
+    pub samples: Vec<u64>,
+}
+
+/// Redirect 耗时分位数趋势
+#[derive(Debug, Clone)]
+pub struct TimingTrends {
+    pub percentile: f64,
+    pub labels: Vec<String>,
+    pub series: Vec<TimingSeries>,
+    /// 参与统计的样本数
+    pub sample_count: u64,
+    /// 样本数超过 [`MAX_TIMING_SAMPLES`]，只统计了最新的部分
+    pub truncated: bool,
+}
+
 // ============ AnalyticsService ============
 
 /// Analytics 服务
@@ -211,6 +268,11 @@ impl AnalyticsService {
             start, end, group_by
         );
 
+        // 周 / 月粒度读取物化汇总，周边界遵循 analytics.week_starts_on
+        if matches!(group_by, GroupBy::Week | GroupBy::Month) {
+            return self.get_trends_v2(start, end, group_by).await;
+        }
+
         let date_expr = self.date_format_expr(group_by);
         let results = self
             .storage
@@ -637,39 +699,16 @@ impl AnalyticsService {
                     .await
             }
             GroupBy::Week | GroupBy::Month => {
-                // 从天汇总读取，在 service 层做二次聚合
-                let start_date = start.date_naive();
-                let end_date = end.date_naive();
-                let daily_results = self
-                    .storage
-                    .get_global_trend_from_daily(start_date, end_date)
+                let granularity = Self::period_granularity(group_by);
+                let week_start = WeekStart::current();
+                self.storage
+                    .get_global_trend_from_period(
+                        granularity,
+                        granularity.period_start(start.date_naive(), week_start),
+                        end.date_naive(),
+                    )
                     .await
-                    .map_err(|e| {
-                        ShortlinkerError::analytics_query_failed(format!(
-                            "Daily trend query failed: {}",
-                            e
-                        ))
-                    })?;
-
-                let format_str = match group_by {
-                    GroupBy::Week => "%G-W%V",
-                    GroupBy::Month => "%Y-%m",
-                    _ => unreachable!(),
-                };
-
-                use std::collections::BTreeMap;
-                let mut grouped: BTreeMap<String, i64> = BTreeMap::new();
-                for row in &daily_results {
-                    if let Ok(date) = chrono::NaiveDate::parse_from_str(&row.label, "%Y-%m-%d") {
-                        let key = date.format(format_str).to_string();
-                        *grouped.entry(key).or_insert(0) += row.count;
-                    }
-                }
-
-                Ok(grouped
-                    .into_iter()
-                    .map(|(label, count)| crate::storage::backend::TrendRow { label, count })
-                    .collect())
+                    .map(|rows| Self::period_trend_rows(rows, granularity, week_start))
             }
         }
         .map_err(|e| {
@@ -710,13 +749,26 @@ impl AnalyticsService {
                     .get_link_trend_from_hourly(code, start, end)
                     .await
             }
-            _ => {
+            GroupBy::Day => {
                 let start_date = start.date_naive();
                 let end_date = end.date_naive();
                 self.storage
                     .get_link_trend_from_daily(code, start_date, end_date)
                     .await
             }
+            GroupBy::Week | GroupBy::Month => {
+                let granularity = Self::period_granularity(group_by);
+                let week_start = WeekStart::current();
+                self.storage
+                    .get_link_trend_from_period(
+                        code,
+                        granularity,
+                        granularity.period_start(start.date_naive(), week_start),
+                        end.date_naive(),
+                    )
+                    .await
+                    .map(|rows| Self::period_trend_rows(rows, granularity, week_start))
+            }
         }
         .map_err(|e| {
             ShortlinkerError::analytics_query_failed(format!("Link trend query failed: {}", e))
@@ -737,6 +789,46 @@ impl AnalyticsService {
         Ok(TrendData { labels, values })
     }
 
+    fn period_granularity(group_by: GroupBy) -> PeriodGranularity {
+        match group_by {
+            GroupBy::Month => PeriodGranularity::Month,
+            _ => PeriodGranularity::Week,
+        }
+    }
+
+    /// 周 / 月汇总行转为趋势行：周以起始日期（`YYYY-MM-DD`）为标签，月为 `YYYY-MM`
+    ///
+    /// 周起始日与当前配置不符的行（修改 `analytics.week_starts_on` 后尚未重算）被忽略。
+    fn period_trend_rows(
+        rows: Vec<PeriodTrendRow>,
+        granularity: PeriodGranularity,
+        week_start: WeekStart,
+    ) -> Vec<TrendRow> {
+        let total = rows.len();
+        let rows: Vec<TrendRow> = rows
+            .into_iter()
+            .filter(|row| {
+                granularity == PeriodGranularity::Month
+                    || row.period_start.weekday() == week_start.weekday()
+            })
+            .map(|row| TrendRow {
+                label: match granularity {
+                    PeriodGranularity::Week => row.period_start.format("%Y-%m-%d").to_string(),
+                    PeriodGranularity::Month => row.period_start.format("%Y-%m").to_string(),
+                },
+                count: row.count,
+            })
+            .collect();
+        if rows.len() < total {
+            warn!(
+                "Ignored {} weekly rollup rows not aligned to week_starts_on={}; run 'shortlinker analytics rebuild-rollups --granularity week'",
+                total - rows.len(),
+                week_start.as_str()
+            );
+        }
+        rows
+    }
+
     /// 获取来源统计（从汇总表）
     pub async fn get_link_referrers_v2(
         &self,
@@ -829,6 +921,79 @@ impl AnalyticsService {
         Ok(geo_stats)
     }
 
+    /// 按自定义 query 参数分组统计单链接点击
+    ///
+    /// 无过滤条件时读取小时汇总（范围限制 90 天）；带过滤条件时需要同一次点击内
+    /// 多个参数的组合，改为扫描明细表，最多
+    /// [`MAX_PARAM_FILTER_ROWS`](crate::storage::backend::MAX_PARAM_FILTER_ROWS) 条。
+    pub async fn get_link_param_stats(
+        &self,
+        code: &str,
+        param: &str,
+        filter: Option<(&str, &str)>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<ParamStats, ShortlinkerError> {
+        info!(
+            "Analytics: get_link_param_stats for '{}' param={} filter={:?} from {} to {}, limit={}",
+            code, param, filter, start, end, limit
+        );
+
+        for name in std::iter::once(param).chain(filter.map(|(name, _)| name)) {
+            if name.is_empty() || name.len() > MAX_NAME_LEN {
+                return Err(ShortlinkerError::validation(format!(
+                    "Query parameter name must be 1-{} bytes",
+                    MAX_NAME_LEN
+                )));
+            }
+        }
+
+        // 先取全部取值计算总数，再截断
+        let limit = limit.clamp(1, 100) as usize;
+        let (rows, truncated) = match filter {
+            None => self
+                .storage
+                .get_link_param_values_from_rollup(code, param, start, end, usize::MAX)
+                .await
+                .map(|rows| (rows, false)),
+            Some(filter) => {
+                self.storage
+                    .get_link_param_values_filtered(code, param, filter, start, end, usize::MAX)
+                    .await
+            }
+        }
+        .map_err(|e| {
+            ShortlinkerError::analytics_query_failed(format!("Query param stats failed: {}", e))
+        })?;
+
+        let total: u64 = rows.iter().map(|r| r.count.max(0) as u64).sum();
+        let values = rows
+            .into_iter()
+            .take(limit)
+            .map(|row| {
+                let count = row.count.max(0) as u64;
+                CategoryStats {
+                    name: row.value,
+                    count,
+                    percentage: if total > 0 {
+                        (count as f64 / total as f64) * 100.0
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+
+        Ok(ParamStats {
+            param: param.to_string(),
+            filter: filter.map(|(name, value)| (name.to_string(), value.to_string())),
+            values,
+            total,
+            truncated,
+        })
+    }
+
     /// 获取热门链接（从汇总表）
     pub async fn get_top_links_v2(
         &self,
@@ -868,4 +1033,125 @@ impl AnalyticsService {
 
         Ok(top_links)
     }
+
+    /// 获取 redirect 各阶段耗时的分位数趋势（从 redirect_timings 采样表）
+    pub async fn get_redirect_timings(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        percentile_rank: f64,
+        group_by: TimingGroupBy,
+        interval: GroupBy,
+    ) -> Result<TimingTrends, ShortlinkerError> {
+        info!(
+            "Analytics: get_redirect_timings from {} to {}, p{}, group_by={:?}, interval={:?}",
+            start, end, percentile_rank, group_by, interval
+        );
+
+        if !percentile_rank.is_finite() || percentile_rank <= 0.0 || percentile_rank > 100.0 {
+            return Err(ShortlinkerError::validation(
+                "percentile must be greater than 0 and at most 100",
+            ));
+        }
+
+        let rows = self
+            .storage
+            .get_redirect_timings(start, end, MAX_TIMING_SAMPLES)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::analytics_query_failed(format!("Timing query failed: {}", e))
+            })?;
+        let truncated = rows.len() as u64 >= MAX_TIMING_SAMPLES;
+
+        let label_format = match interval {
+            GroupBy::Hour => "%Y-%m-%d %H:00",
+            GroupBy::Day => "%Y-%m-%d",
+            GroupBy::Week => "%G-W%V",
+            GroupBy::Month => "%Y-%m",
+        };
+
+        // 桶标签 → 曲线名 → 样本
+        let mut buckets: BTreeMap<String, HashMap<String, Vec<u32>>> = BTreeMap::new();
+        let mut series_names: Vec<String> = match group_by {
+            TimingGroupBy::Phase => TimingPhase::ALL
+                .iter()
+                .map(|phase| phase.as_str().to_string())
+                .collect(),
+            TimingGroupBy::Status => Vec::new(),
+        };
+        for row in &rows {
+            let bucket = buckets
+                .entry(row.recorded_at.format(label_format).to_string())
+                .or_default();
+            match group_by {
+                TimingGroupBy::Phase => {
+                    let phases = [
+                        (TimingPhase::Total, Some(row.total_us)),
+                        (TimingPhase::Cache, Some(row.cache_us)),
+                        (TimingPhase::Bloom, row.bloom_us),
+                        (TimingPhase::Db, row.db_us),
+                        (TimingPhase::Geo, row.geo_us),
+                        (TimingPhase::Enqueue, row.enqueue_us),
+                    ];
+                    for (phase, value) in phases {
+                        if let Some(value) = value {
+                            bucket
+                                .entry(phase.as_str().to_string())
+                                .or_default()
+                                .push(value.max(0) as u32);
+                        }
+                    }
+                }
+                TimingGroupBy::Status => {
+                    let status = row.status.to_string();
+                    if !series_names.contains(&status) {
+                        series_names.push(status.clone());
+                    }
+                    bucket
+                        .entry(status)
+                        .or_default()
+                        .push(row.total_us.max(0) as u32);
+                }
+            }
+        }
+        if group_by == TimingGroupBy::Status {
+            series_names.sort_by_key(|name| name.parse::<u16>().unwrap_or(u16::MAX));
+        }
+
+        let labels: Vec<String> = buckets.keys().cloned().collect();
+        let series = series_names
+            .into_iter()
+            .map(|name| {
+                let mut values = Vec::with_capacity(labels.len());
+                let mut samples = Vec::with_capacity(labels.len());
+                for bucket in buckets.values_mut() {
+                    let bucket_values = bucket.get_mut(&name);
+                    samples.push(bucket_values.as_ref().map_or(0, |v| v.len() as u64));
+                    values.push(bucket_values.and_then(|v| {
+                        v.sort_unstable();
+                        percentile(v, percentile_rank)
+                    }));
+                }
+                TimingSeries {
+                    name,
+                    values,
+                    samples,
+                }
+            })
+            .collect();
+
+        debug!(
+            "Analytics: get_redirect_timings aggregated {} samples into {} buckets",
+            rows.len(),
+            labels.len()
+        );
+
+        Ok(TimingTrends {
+            percentile: percentile_rank,
+            labels,
+            series,
+            sample_count: rows.len() as u64,
+            truncated,
+        })
+    }
 }
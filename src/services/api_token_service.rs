@@ -0,0 +1,397 @@
+//! 团队 API Token 与配额
+//!
+//! 主管理员凭据不受限；团队 token 以 `slk_<id>_<secret>` 形式作为 Bearer 使用，
+//! 可分别限制最大链接数与每日（UTC）创建数：
+//!
+//! - 链接数按 `short_links.owner_token` 实时 COUNT，删除链接即释放额度
+//! - 每日创建数按日期累加，删除不回退；批量创建按成功条数计入，导入只计新建的短码
+//!   （覆盖已有短码不计）
+//! - 归档 / 恢复仅主管理员可用：归档链接不计入链接数，恢复时也不保留归属
+//! - 统计级别上限：经团队 token 创建或更新的链接，`analytics_level` 收紧到不超过
+//!   上限（`inherit` 按 `full` 计），超出时不报错
+//!
+//! 配额为软限制：写入前按请求条数检查，并发请求可能略微超出上限。
+//! 任一用量首次达到上限的 90% 时，当天向 `alerts.webhook_url` 发送一次提醒。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use moka::sync::Cache;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::errors::ShortlinkerError;
+use crate::storage::{AnalyticsLevel, ApiToken, SeaOrmStorage};
+
+/// 团队 token 前缀
+pub const API_TOKEN_PREFIX: &str = "slk_";
+
+/// 用量达到上限的该百分比时发送提醒
+pub const QUOTA_ALERT_PERCENT: u64 = 90;
+
+/// 校验通过的 token 缓存时间，避免每个请求都做一次 argon2
+const VERIFIED_TTL: Duration = Duration::from_secs(60);
+
+/// token 配额，`None` 为不限
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApiTokenQuotas {
+    pub max_links: Option<u64>,
+    pub max_daily_creates: Option<u64>,
+    /// 统计级别上限；`inherit` 视为不限
+    pub max_analytics_level: Option<AnalyticsLevel>,
+}
+
+impl ApiTokenQuotas {
+    fn analytics_cap(&self) -> Option<AnalyticsLevel> {
+        self.max_analytics_level
+            .filter(|level| *level != AnalyticsLevel::Inherit)
+    }
+}
+
+/// 新建的 token；`plaintext` 仅此一次可见
+#[derive(Debug, Clone)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub plaintext: String,
+}
+
+/// token 当前用量
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ApiTokenUsage {
+    pub token_id: String,
+    /// 每日计数所属日期（UTC，YYYY-MM-DD）
+    pub day: String,
+    pub links: u64,
+    pub max_links: Option<u64>,
+    pub created_today: u64,
+    pub max_daily_creates: Option<u64>,
+}
+
+impl ApiTokenUsage {
+    /// 任一用量达到上限的 [`QUOTA_ALERT_PERCENT`]
+    pub fn near_limit(&self) -> bool {
+        let reached = |used: u64, max: Option<u64>| {
+            max.is_some_and(|max| {
+                used.saturating_mul(100) >= max.saturating_mul(QUOTA_ALERT_PERCENT)
+            })
+        };
+        reached(self.links, self.max_links) || reached(self.created_today, self.max_daily_creates)
+    }
+}
+
+/// 将 `slk_<id>_<secret>` 拆分为 (id, secret)
+pub fn parse_api_token(token: &str) -> Option<(&str, &str)> {
+    let (id, secret) = token.strip_prefix(API_TOKEN_PREFIX)?.split_once('_')?;
+    (!id.is_empty() && !secret.is_empty()).then_some((id, secret))
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// 团队 API Token 服务
+pub struct ApiTokenService {
+    storage: Arc<SeaOrmStorage>,
+    /// 明文 token → token id
+    verified: Cache<String, String>,
+}
+
+impl ApiTokenService {
+    pub fn new(storage: Arc<SeaOrmStorage>) -> Self {
+        Self {
+            storage,
+            verified: Cache::builder()
+                .time_to_live(VERIFIED_TTL)
+                .max_capacity(1000)
+                .build(),
+        }
+    }
+
+    /// 创建 token，返回仅此一次可见的明文
+    pub async fn create(
+        &self,
+        name: &str,
+        quotas: ApiTokenQuotas,
+    ) -> Result<CreatedApiToken, ShortlinkerError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(ShortlinkerError::validation(
+                "Token name must be 1-64 characters",
+            ));
+        }
+
+        let id = crate::utils::generate_secure_token(6);
+        let plaintext = format!(
+            "{}{}_{}",
+            API_TOKEN_PREFIX,
+            id,
+            crate::utils::generate_secure_token(24)
+        );
+        let token_hash = aster_forge_crypto::hash_password(&plaintext).map_err(|e| {
+            ShortlinkerError::internal_error(format!("Failed to hash API token: {}", e))
+        })?;
+
+        let token = ApiToken {
+            id,
+            name: name.to_string(),
+            token_hash,
+            max_links: quotas.max_links,
+            max_daily_creates: quotas.max_daily_creates,
+            max_analytics_level: quotas.analytics_cap(),
+            alert_sent_on: None,
+            created_at: Utc::now(),
+        };
+        self.storage.insert_api_token(&token).await?;
+        Ok(CreatedApiToken { token, plaintext })
+    }
+
+    pub async fn list(&self) -> Result<Vec<ApiToken>, ShortlinkerError> {
+        self.storage.list_api_tokens().await
+    }
+
+    pub async fn update_quotas(
+        &self,
+        id: &str,
+        quotas: ApiTokenQuotas,
+    ) -> Result<ApiToken, ShortlinkerError> {
+        if !self
+            .storage
+            .update_api_token_quotas(
+                id,
+                quotas.max_links,
+                quotas.max_daily_creates,
+                quotas.analytics_cap(),
+            )
+            .await?
+        {
+            return Err(Self::not_found(id));
+        }
+        self.storage
+            .get_api_token(id)
+            .await?
+            .ok_or_else(|| Self::not_found(id))
+    }
+
+    /// 删除 token；名下链接保留，不再计入任何配额
+    pub async fn delete(&self, id: &str) -> Result<(), ShortlinkerError> {
+        if !self.storage.delete_api_token(id).await? {
+            return Err(Self::not_found(id));
+        }
+        // 仅清本实例缓存；其他实例最迟在 VERIFIED_TTL 后失效
+        self.verified.invalidate_all();
+        Ok(())
+    }
+
+    /// 校验明文 token，返回 token id
+    pub async fn authenticate(&self, plaintext: &str) -> Option<String> {
+        if let Some(id) = self.verified.get(plaintext) {
+            return Some(id);
+        }
+
+        let (id, _) = parse_api_token(plaintext)?;
+        let token = match self.storage.get_api_token(id).await {
+            Ok(Some(token)) => token,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!("Failed to load API token '{}': {}", id, e);
+                return None;
+            }
+        };
+        match aster_forge_crypto::verify_password(plaintext, &token.token_hash) {
+            Ok(true) => {
+                self.verified
+                    .insert(plaintext.to_string(), token.id.clone());
+                Some(token.id)
+            }
+            Ok(false) => None,
+            Err(e) => {
+                warn!("Failed to verify API token '{}': {}", id, e);
+                None
+            }
+        }
+    }
+
+    /// 查询当前用量
+    pub async fn usage(&self, id: &str) -> Result<ApiTokenUsage, ShortlinkerError> {
+        let token = self
+            .storage
+            .get_api_token(id)
+            .await?
+            .ok_or_else(|| Self::not_found(id))?;
+        self.usage_of(&token).await
+    }
+
+    async fn usage_of(&self, token: &ApiToken) -> Result<ApiTokenUsage, ShortlinkerError> {
+        let day = today();
+        Ok(ApiTokenUsage {
+            links: self.storage.count_links_by_owner(&token.id).await?,
+            created_today: self.storage.daily_created_count(&token.id, &day).await?,
+            token_id: token.id.clone(),
+            day,
+            max_links: token.max_links,
+            max_daily_creates: token.max_daily_creates,
+        })
+    }
+
+    /// 统计级别上限，`None` 为不限
+    pub async fn analytics_cap(
+        &self,
+        id: &str,
+    ) -> Result<Option<AnalyticsLevel>, ShortlinkerError> {
+        let token = self
+            .storage
+            .get_api_token(id)
+            .await?
+            .ok_or_else(|| Self::not_found(id))?;
+        Ok(token.max_analytics_level)
+    }
+
+    /// 写入前检查：再创建 `requested` 条是否会超出配额
+    pub async fn check_quota(&self, id: &str, requested: u64) -> Result<(), ShortlinkerError> {
+        let usage = self.usage(id).await?;
+
+        if let Some(max) = usage.max_links
+            && usage.links.saturating_add(requested) > max
+        {
+            return Err(ShortlinkerError::quota_exceeded(format!(
+                "Link quota exceeded for API token '{}': {} of {} links used, {} requested",
+                id, usage.links, max, requested
+            )));
+        }
+        if let Some(max) = usage.max_daily_creates
+            && usage.created_today.saturating_add(requested) > max
+        {
+            return Err(ShortlinkerError::quota_exceeded(format!(
+                "Daily create quota exceeded for API token '{}': {} of {} created on {} (UTC), {} requested",
+                id, usage.created_today, max, usage.day, requested
+            )));
+        }
+        Ok(())
+    }
+
+    /// 写入成功后登记：尚无归属的链接计入该 token，并累加当天创建数
+    ///
+    /// 不返回错误：链接已写入，计数失败只记录日志。
+    pub async fn record_created(&self, id: &str, codes: &[String]) {
+        if codes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.storage.assign_link_owner(id, codes).await {
+            warn!(
+                "Failed to assign {} links to API token '{}': {}",
+                codes.len(),
+                id,
+                e
+            );
+        }
+        if let Err(e) = self
+            .storage
+            .add_daily_created(id, &today(), codes.len() as u64)
+            .await
+        {
+            warn!("Failed to record daily usage for API token '{}': {}", id, e);
+        }
+        self.maybe_alert(id).await;
+    }
+
+    /// 用量接近上限时每天提醒一次
+    async fn maybe_alert(&self, id: &str) {
+        let usage = match self.usage(id).await {
+            Ok(usage) if usage.near_limit() => usage,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to check quota usage for API token '{}': {}", id, e);
+                return;
+            }
+        };
+        match self.storage.mark_api_token_alert_sent(id, &usage.day).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                warn!("Failed to record quota alert for API token '{}': {}", id, e);
+                return;
+            }
+        }
+
+        warn!(
+            "API token '{}' is near its quota: {} links (max {:?}), {} created today (max {:?})",
+            id, usage.links, usage.max_links, usage.created_today, usage.max_daily_creates
+        );
+
+        let Some(url) = try_get_runtime_config()
+            .map(|rt| rt.get_or(keys::ALERTS_WEBHOOK_URL, ""))
+            .filter(|url| !url.is_empty())
+        else {
+            return;
+        };
+        let payload = serde_json::json!({
+            "event": "quota_near_limit",
+            "threshold_percent": QUOTA_ALERT_PERCENT,
+            "usage": usage,
+        });
+        // 不阻塞写请求
+        tokio::spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                crate::analytics::anomaly::post_webhook(&url, payload)
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => info!(
+                    "Quota alert webhook delivered for API token '{}'",
+                    usage.token_id
+                ),
+                Ok(Err(e)) => warn!(
+                    "Quota alert webhook failed for API token '{}': {}",
+                    usage.token_id, e
+                ),
+                Err(e) => warn!("Quota alert webhook task failed: {}", e),
+            }
+        });
+    }
+
+    fn not_found(id: &str) -> ShortlinkerError {
+        ShortlinkerError::not_found(format!("API token not found: {}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(
+        links: u64,
+        max_links: Option<u64>,
+        today: u64,
+        max_daily: Option<u64>,
+    ) -> ApiTokenUsage {
+        ApiTokenUsage {
+            token_id: "t".to_string(),
+            day: "2026-10-16".to_string(),
+            links,
+            max_links,
+            created_today: today,
+            max_daily_creates: max_daily,
+        }
+    }
+
+    #[test]
+    fn test_parse_api_token() {
+        assert_eq!(parse_api_token("slk_abc_def"), Some(("abc", "def")));
+        assert_eq!(parse_api_token("slk_abc_de_f"), Some(("abc", "de_f")));
+        assert_eq!(parse_api_token("slk__def"), None);
+        assert_eq!(parse_api_token("slk_abc"), None);
+        assert_eq!(parse_api_token("eyJhbGciOi.x.y"), None);
+    }
+
+    #[test]
+    fn test_near_limit() {
+        assert!(!usage(89, Some(100), 0, None).near_limit());
+        assert!(usage(90, Some(100), 0, None).near_limit());
+        assert!(usage(0, None, 9, Some(10)).near_limit());
+        assert!(!usage(1000, None, 1000, None).near_limit());
+        // 上限为 0 时视为已达上限
+        assert!(usage(0, Some(0), 0, None).near_limit());
+    }
+}
@@ -0,0 +1,494 @@
+//! 404 洪泛来源 IP 自动封禁（fail2ban 式）
+//!
+//! redirect 每产生一次 404 就按客户端 IP 计数；滑动窗口
+//! （`security.auto_ban_window_secs`）内的 404 数达到
+//! `security.auto_ban_404_threshold` 后封禁该 IP `security.auto_ban_minutes` 分钟。
+//! 封禁期间由 [`Firewall`](crate::api::middleware::Firewall) 中间件在进入 handler
+//! 之前直接返回 403，不查缓存、不查库。
+//!
+//! - 阈值为 0 时功能关闭：不计数，已有封禁也不再执行
+//! - `security.auto_ban_whitelist` 中的 CIDR 永不计数、永不封禁
+//! - 窗口为滑动窗口计数：当前窗口计数加上一窗口计数按剩余比例加权，
+//!   每个 IP 只占常数内存，跨窗口边界的连续扫描不会因窗口重置而漏判
+//! - 封禁状态只存本进程内存（带上限），多实例各自独立，重启后清空
+
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use tracing::{info, warn};
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::utils::cidr::Cidr;
+
+/// 统计窗口默认值（秒）
+pub const DEFAULT_WINDOW_SECS: u64 = 60;
+
+/// 封禁时长默认值（分钟）
+pub const DEFAULT_BAN_MINUTES: u64 = 30;
+
+/// 阈值上限
+pub const MAX_THRESHOLD: u64 = 1_000_000;
+
+/// 统计窗口上限（秒）
+pub const MAX_WINDOW_SECS: u64 = 86_400;
+
+/// 封禁时长上限（分钟，一年）
+pub const MAX_BAN_MINUTES: u64 = 525_600;
+
+/// 跟踪 404 计数的 IP 数上限，超过后清理过期窗口
+const MAX_TRACKED_IPS: usize = 100_000;
+
+/// 同时封禁的 IP 数上限，超过后清理已到期的封禁，仍然满时不再新增
+pub const MAX_BANS: usize = 10_000;
+
+/// 自动封禁参数（来自运行时配置）
+#[derive(Debug, Clone)]
+pub struct AutoBanSettings {
+    /// 窗口内 404 数阈值，0 = 关闭
+    pub threshold: u64,
+    pub window: Duration,
+    pub ban_duration: Duration,
+    pub whitelist: Vec<Cidr>,
+}
+
+impl Default for AutoBanSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0,
+            window: Duration::from_secs(DEFAULT_WINDOW_SECS),
+            ban_duration: Duration::from_secs(DEFAULT_BAN_MINUTES * 60),
+            whitelist: Vec::new(),
+        }
+    }
+}
+
+impl AutoBanSettings {
+    pub fn enabled(&self) -> bool {
+        self.threshold > 0
+    }
+
+    pub fn is_whitelisted(&self, ip: IpAddr) -> bool {
+        self.whitelist.iter().any(|cidr| cidr.contains(ip))
+    }
+}
+
+/// 当前封禁
+#[derive(Debug, Clone)]
+pub struct Ban {
+    pub ip: IpAddr,
+    pub banned_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 触发封禁时窗口内的 404 估计数
+    pub not_found_count: u64,
+    until: Instant,
+}
+
+impl Ban {
+    /// 剩余封禁时长
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.until.saturating_duration_since(now)
+    }
+}
+
+/// 请求来源的封禁状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanStatus {
+    /// 未封禁
+    Clear,
+    /// 封禁中，直接 403
+    Banned,
+    /// 封禁刚好到期，本次查询时已解除
+    Expired,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IpWindow {
+    started: Instant,
+    current: u64,
+    previous: u64,
+}
+
+impl IpWindow {
+    /// 滑动到 `now` 所在的窗口
+    fn advance(&mut self, now: Instant, window: Duration) {
+        let elapsed = now.saturating_duration_since(self.started);
+        if elapsed < window {
+            return;
+        }
+        if elapsed < window * 2 {
+            self.previous = self.current;
+            self.started += window;
+        } else {
+            self.previous = 0;
+            self.started = now;
+        }
+        self.current = 0;
+    }
+
+    /// 滑动窗口内的 404 估计数
+    fn estimate(&self, now: Instant, window: Duration) -> u64 {
+        let elapsed = now.saturating_duration_since(self.started);
+        let weight = 1.0 - (elapsed.as_secs_f64() / window.as_secs_f64()).min(1.0);
+        self.current + (self.previous as f64 * weight) as u64
+    }
+}
+
+/// 按 IP 统计 404 并维护封禁列表
+#[derive(Debug, Default)]
+pub struct AutoBanner {
+    windows: DashMap<IpAddr, IpWindow>,
+    bans: DashMap<IpAddr, Ban>,
+}
+
+impl AutoBanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次 404，达到阈值时封禁并返回新的封禁
+    pub fn record_not_found(
+        &self,
+        ip: IpAddr,
+        settings: &AutoBanSettings,
+        now: Instant,
+    ) -> Option<Ban> {
+        if !settings.enabled() || settings.is_whitelisted(ip) || self.bans.contains_key(&ip) {
+            return None;
+        }
+
+        if self.windows.len() >= MAX_TRACKED_IPS {
+            self.windows
+                .retain(|_, w| now.saturating_duration_since(w.started) < settings.window * 2);
+        }
+
+        let count = {
+            let mut window = self.windows.entry(ip).or_insert(IpWindow {
+                started: now,
+                current: 0,
+                previous: 0,
+            });
+            window.advance(now, settings.window);
+            window.current = window.current.saturating_add(1);
+            window.estimate(now, settings.window)
+        };
+        if count < settings.threshold {
+            return None;
+        }
+
+        if self.bans.len() >= MAX_BANS {
+            self.bans.retain(|_, ban| ban.until > now);
+            if self.bans.len() >= MAX_BANS {
+                warn!(
+                    "Auto-ban list is full ({} entries), not banning {} ({} 404s)",
+                    MAX_BANS, ip, count
+                );
+                return None;
+            }
+        }
+
+        self.windows.remove(&ip);
+        let banned_at = Utc::now();
+        let ban = Ban {
+            ip,
+            banned_at,
+            expires_at: banned_at
+                + chrono::Duration::seconds(settings.ban_duration.as_secs() as i64),
+            not_found_count: count,
+            until: now + settings.ban_duration,
+        };
+        self.bans.insert(ip, ban.clone());
+        Some(ban)
+    }
+
+    /// 查询 IP 的封禁状态，已到期的封禁在此解除
+    pub fn status(&self, ip: IpAddr, now: Instant) -> BanStatus {
+        let expired = match self.bans.get(&ip) {
+            None => return BanStatus::Clear,
+            Some(ban) => ban.until <= now,
+        };
+        if !expired {
+            return BanStatus::Banned;
+        }
+        // 并发请求可能同时发现到期，只有真正移除的一方报告 Expired
+        match self.bans.remove_if(&ip, |_, ban| ban.until <= now) {
+            Some(_) => BanStatus::Expired,
+            None => BanStatus::Clear,
+        }
+    }
+
+    /// 当前未到期的封禁，按封禁时间倒序
+    pub fn list(&self, now: Instant) -> Vec<Ban> {
+        let mut bans: Vec<Ban> = self
+            .bans
+            .iter()
+            .filter(|ban| ban.until > now)
+            .map(|ban| ban.clone())
+            .collect();
+        bans.sort_by(|a, b| b.banned_at.cmp(&a.banned_at));
+        bans
+    }
+
+    /// 手动解除封禁，同时清空该 IP 的计数；IP 未被封禁时返回 `None`
+    pub fn unban(&self, ip: IpAddr) -> Option<Ban> {
+        self.windows.remove(&ip);
+        self.bans.remove(&ip).map(|(_, ban)| ban)
+    }
+}
+
+/// 全局封禁列表（Firewall 中间件与 redirect handler 共用）
+pub fn auto_banner() -> &'static AutoBanner {
+    static BANNER: LazyLock<AutoBanner> = LazyLock::new(AutoBanner::new);
+    &BANNER
+}
+
+/// 校验 `security.auto_ban_whitelist` 中的 CIDR
+pub fn parse_whitelist(entries: &[String]) -> Result<Vec<Cidr>, String> {
+    entries
+        .iter()
+        .map(|entry| {
+            Cidr::parse(entry)
+                .ok_or_else(|| format!("invalid IP or CIDR in whitelist: '{}'", entry))
+        })
+        .collect()
+}
+
+struct ActiveSettings {
+    version: u64,
+    settings: Arc<AutoBanSettings>,
+}
+
+static ACTIVE_SETTINGS: LazyLock<ArcSwap<ActiveSettings>> = LazyLock::new(|| {
+    ArcSwap::from_pointee(ActiveSettings {
+        version: u64::MAX,
+        settings: Arc::new(AutoBanSettings::default()),
+    })
+});
+
+/// 获取当前生效的封禁参数（运行时配置版本变化时重新读取）
+pub fn auto_ban_settings() -> Arc<AutoBanSettings> {
+    let Some(rt) = try_get_runtime_config() else {
+        return ACTIVE_SETTINGS.load().settings.clone();
+    };
+
+    let version = rt.version();
+    let current = ACTIVE_SETTINGS.load();
+    if current.version == version {
+        return current.settings.clone();
+    }
+
+    let whitelist: Vec<String> = rt.get_json_or(keys::SECURITY_AUTO_BAN_WHITELIST, Vec::new());
+    let whitelist = match parse_whitelist(&whitelist) {
+        Ok(whitelist) => whitelist,
+        Err(e) => {
+            warn!(
+                "Invalid security.auto_ban_whitelist ({}), keeping previous whitelist",
+                e
+            );
+            current.settings.whitelist.clone()
+        }
+    };
+    let settings = Arc::new(AutoBanSettings {
+        threshold: rt
+            .get_u64_or(keys::SECURITY_AUTO_BAN_404_THRESHOLD, 0)
+            .min(MAX_THRESHOLD),
+        window: Duration::from_secs(
+            rt.get_u64_or(keys::SECURITY_AUTO_BAN_WINDOW_SECS, DEFAULT_WINDOW_SECS)
+                .clamp(1, MAX_WINDOW_SECS),
+        ),
+        ban_duration: Duration::from_secs(
+            rt.get_u64_or(keys::SECURITY_AUTO_BAN_MINUTES, DEFAULT_BAN_MINUTES)
+                .clamp(1, MAX_BAN_MINUTES)
+                * 60,
+        ),
+        whitelist,
+    });
+    if settings.enabled() != current.settings.enabled() {
+        info!(
+            "404 auto-ban {} (threshold {} per {}s, ban {}m)",
+            if settings.enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            settings.threshold,
+            settings.window.as_secs(),
+            settings.ban_duration.as_secs() / 60
+        );
+    }
+
+    ACTIVE_SETTINGS.store(Arc::new(ActiveSettings {
+        version,
+        settings: settings.clone(),
+    }));
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(threshold: u64, whitelist: &[&str]) -> AutoBanSettings {
+        AutoBanSettings {
+            threshold,
+            window: Duration::from_secs(60),
+            ban_duration: Duration::from_secs(600),
+            whitelist: whitelist.iter().map(|c| Cidr::parse(c).unwrap()).collect(),
+        }
+    }
+
+    fn ip(raw: &str) -> IpAddr {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn test_threshold_triggers_ban() {
+        let banner = AutoBanner::new();
+        let settings = settings(5, &[]);
+        let now = Instant::now();
+        let attacker = ip("203.0.113.7");
+
+        for _ in 0..4 {
+            assert!(banner.record_not_found(attacker, &settings, now).is_none());
+        }
+        assert_eq!(banner.status(attacker, now), BanStatus::Clear);
+
+        let ban = banner.record_not_found(attacker, &settings, now).unwrap();
+        assert_eq!(ban.ip, attacker);
+        assert_eq!(ban.not_found_count, 5);
+        assert_eq!(banner.status(attacker, now), BanStatus::Banned);
+        // 其他 IP 不受影响
+        assert_eq!(banner.status(ip("203.0.113.8"), now), BanStatus::Clear);
+        // 封禁期间不再重复封禁
+        assert!(banner.record_not_found(attacker, &settings, now).is_none());
+        assert_eq!(banner.list(now).len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_when_threshold_is_zero() {
+        let banner = AutoBanner::new();
+        let settings = settings(0, &[]);
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert!(
+                banner
+                    .record_not_found(ip("203.0.113.7"), &settings, now)
+                    .is_none()
+            );
+        }
+        assert!(banner.list(now).is_empty());
+    }
+
+    #[test]
+    fn test_window_slides() {
+        let banner = AutoBanner::new();
+        let settings = settings(5, &[]);
+        let start = Instant::now();
+        let attacker = ip("203.0.113.7");
+
+        // 上一窗口的计数随时间线性衰减
+        for _ in 0..4 {
+            assert!(
+                banner
+                    .record_not_found(attacker, &settings, start)
+                    .is_none()
+            );
+        }
+        let half_later = start + Duration::from_secs(90);
+        assert!(
+            banner
+                .record_not_found(attacker, &settings, half_later)
+                .is_none()
+        );
+        // 两个窗口之后旧计数完全清零
+        let much_later = start + Duration::from_secs(200);
+        for _ in 0..4 {
+            assert!(
+                banner
+                    .record_not_found(attacker, &settings, much_later)
+                    .is_none()
+            );
+        }
+
+        // 跨窗口边界的连续扫描不会因窗口重置而漏判
+        let other = ip("198.51.100.1");
+        let before_edge = start + Duration::from_secs(54);
+        let after_edge = start + Duration::from_secs(66);
+        assert!(banner.record_not_found(other, &settings, start).is_none());
+        for _ in 0..2 {
+            assert!(
+                banner
+                    .record_not_found(other, &settings, before_edge)
+                    .is_none()
+            );
+        }
+        assert!(
+            banner
+                .record_not_found(other, &settings, after_edge)
+                .is_none()
+        );
+        assert!(
+            banner
+                .record_not_found(other, &settings, after_edge)
+                .is_none()
+        );
+        assert!(
+            banner
+                .record_not_found(other, &settings, after_edge)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_whitelist_is_never_banned() {
+        let banner = AutoBanner::new();
+        let settings = settings(3, &["10.0.0.0/8", "2001:db8::/32"]);
+        let now = Instant::now();
+
+        for raw in ["10.1.2.3", "2001:db8::1", "::ffff:10.9.9.9"] {
+            for _ in 0..10 {
+                assert!(banner.record_not_found(ip(raw), &settings, now).is_none());
+            }
+            assert_eq!(banner.status(ip(raw), now), BanStatus::Clear, "{}", raw);
+        }
+        assert!(
+            banner
+                .record_not_found(ip("11.0.0.1"), &settings, now)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_ban_expires_and_manual_unban() {
+        let banner = AutoBanner::new();
+        let settings = settings(1, &[]);
+        let now = Instant::now();
+        let attacker = ip("203.0.113.7");
+
+        let ban = banner.record_not_found(attacker, &settings, now).unwrap();
+        assert_eq!(ban.remaining(now), Duration::from_secs(600));
+
+        let expiry = now + settings.ban_duration;
+        assert!(banner.list(expiry).is_empty());
+        assert_eq!(banner.status(attacker, expiry), BanStatus::Expired);
+        assert_eq!(banner.status(attacker, expiry), BanStatus::Clear);
+
+        banner
+            .record_not_found(attacker, &settings, expiry)
+            .unwrap();
+        assert!(banner.unban(attacker).is_some());
+        assert!(banner.unban(attacker).is_none());
+        assert_eq!(banner.status(attacker, expiry), BanStatus::Clear);
+    }
+
+    #[test]
+    fn test_parse_whitelist() {
+        let entries = vec!["10.0.0.0/8".to_string(), "::1".to_string()];
+        assert_eq!(parse_whitelist(&entries).unwrap().len(), 2);
+        assert!(parse_whitelist(&["10.0.0.0/33".to_string()]).is_err());
+        assert!(parse_whitelist(&["not-an-ip".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! 点击数徽章（shields.io 风格 SVG）
+//!
+//! 纯字符串模板生成，不依赖字体文件：文本宽度按 Verdana 11px 的近似字宽估算，
+//! 左侧为 label、右侧为 message，支持 `flat` / `flat-square` 两种样式。所有
+//! 写入 SVG 的文本都经过 XML 转义。
+
+use std::fmt::Write;
+
+/// 默认 label
+pub const DEFAULT_BADGE_LABEL: &str = "clicks";
+
+/// 默认 message 背景色（`blue`）
+pub const DEFAULT_BADGE_COLOR: &str = "#007ec6";
+
+/// label 最大字符数，超出截断
+pub const MAX_BADGE_LABEL_CHARS: usize = 48;
+
+/// 短码不存在时的 message 与颜色
+pub const NOT_FOUND_MESSAGE: &str = "not found";
+pub const NOT_FOUND_COLOR: &str = "#9f9f9f";
+
+/// 左侧 label 背景色
+const LABEL_COLOR: &str = "#555";
+
+/// 文本左右内边距
+const HORIZONTAL_PADDING: f64 = 6.0;
+
+/// 徽章样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeStyle {
+    /// 圆角 + 渐变高光
+    #[default]
+    Flat,
+    /// 直角、无渐变
+    FlatSquare,
+}
+
+impl BadgeStyle {
+    /// 解析 `style` 参数，未知值返回 `None`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "flat" => Some(Self::Flat),
+            "flat-square" => Some(Self::FlatSquare),
+            _ => None,
+        }
+    }
+}
+
+/// 解析颜色：shields.io 命名色或 3/6 位十六进制（可带 `#`），无效时返回 `None`
+pub fn resolve_color(value: &str) -> Option<String> {
+    let named = match value {
+        "brightgreen" => Some("#4c1"),
+        "green" => Some("#97ca00"),
+        "yellowgreen" => Some("#a4a61d"),
+        "yellow" => Some("#dfb317"),
+        "orange" => Some("#fe7d37"),
+        "red" => Some("#e05d44"),
+        "blue" => Some("#007ec6"),
+        "lightgrey" | "lightgray" => Some("#9f9f9f"),
+        "grey" | "gray" => Some("#555"),
+        "success" => Some("#4c1"),
+        "important" => Some("#fe7d37"),
+        "critical" => Some("#e05d44"),
+        "informational" => Some("#007ec6"),
+        "inactive" => Some("#9f9f9f"),
+        _ => None,
+    };
+    if let Some(hex) = named {
+        return Some(hex.to_string());
+    }
+
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if matches!(hex.len(), 3 | 6) && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Some(format!("#{}", hex.to_ascii_lowercase()))
+    } else {
+        None
+    }
+}
+
+/// 点击数的紧凑格式：`999`、`1.2k`、`3.4M`、`5B`
+pub fn format_count(count: u64) -> String {
+    const UNITS: [&str; 4] = ["", "k", "M", "B"];
+    if count < 1000 {
+        return count.to_string();
+    }
+
+    let mut value = count as f64;
+    let mut unit = 0;
+    // 按四舍五入后的值进位，避免出现 "1000k"
+    while unit < UNITS.len() - 1 && (value * 10.0).round() / 10.0 >= 1000.0 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{}{}", rounded as u64, UNITS[unit])
+    } else {
+        format!("{:.1}{}", rounded, UNITS[unit])
+    }
+}
+
+/// 生成徽章 SVG
+///
+/// `color` 须为 [`resolve_color`] 的返回值；`label` 超过
+/// [`MAX_BADGE_LABEL_CHARS`] 时截断。
+pub fn render_badge(label: &str, message: &str, color: &str, style: BadgeStyle) -> String {
+    let label: String = label.chars().take(MAX_BADGE_LABEL_CHARS).collect();
+    let label_width = (text_width(&label) + 2.0 * HORIZONTAL_PADDING).round();
+    let message_width = (text_width(message) + 2.0 * HORIZONTAL_PADDING).round();
+    let total_width = label_width + message_width;
+    let label_x = label_width / 2.0;
+    let message_x = label_width + message_width / 2.0;
+
+    let label = escape_xml(&label);
+    let message = escape_xml(message);
+    let color = escape_xml(color);
+    let radius = match style {
+        BadgeStyle::Flat => 3,
+        BadgeStyle::FlatSquare => 0,
+    };
+
+    let mut svg = String::with_capacity(1024);
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total_width}" height="20" role="img" aria-label="{label}: {message}"><title>{label}: {message}</title>"##
+    );
+    if style == BadgeStyle::Flat {
+        svg.push_str(
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+        );
+    }
+    let _ = write!(
+        svg,
+        r##"<clipPath id="r"><rect width="{total_width}" height="20" rx="{radius}" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="{LABEL_COLOR}"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>"##
+    );
+    if style == BadgeStyle::Flat {
+        let _ = write!(
+            svg,
+            r##"<rect width="{total_width}" height="20" fill="url(#s)"/>"##
+        );
+    }
+    svg.push_str(
+        r##"</g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+    );
+    for (x, text) in [(label_x, &label), (message_x, &message)] {
+        if style == BadgeStyle::Flat {
+            let _ = write!(
+                svg,
+                r##"<text x="{x}" y="15" fill="#010101" fill-opacity=".3">{text}</text>"##
+            );
+        }
+        let _ = write!(svg, r##"<text x="{x}" y="14">{text}</text>"##);
+    }
+    svg.push_str("</g></svg>");
+    svg
+}
+
+/// Verdana 11px 下的近似文本宽度（像素）
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | ';' | '!' | '|' | '\'' => 3.5,
+            'I' | 'f' | 't' | 'r' | ' ' | '(' | ')' | '[' | ']' | '/' | '-' => 4.5,
+            'm' | 'w' => 10.0,
+            'M' | 'W' | '@' | '%' => 11.0,
+            'A'..='Z' => 7.5,
+            '0'..='9' | 'a'..='z' => 7.0,
+            c if c.is_ascii() => 7.0,
+            // CJK 等宽字符
+            _ => 11.0,
+        })
+        .sum()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 不允许的控制字符直接丢弃
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1000), "1k");
+        assert_eq!(format_count(1234), "1.2k");
+        assert_eq!(format_count(999_949), "999.9k");
+        assert_eq!(format_count(999_950), "1M");
+        assert_eq!(format_count(3_460_000), "3.5M");
+        assert_eq!(format_count(7_000_000_000), "7B");
+        assert!(format_count(u64::MAX).ends_with('B'));
+    }
+
+    #[test]
+    fn test_resolve_color() {
+        assert_eq!(resolve_color("blue").as_deref(), Some("#007ec6"));
+        assert_eq!(resolve_color("ABC").as_deref(), Some("#abc"));
+        assert_eq!(resolve_color("#00ff7f").as_deref(), Some("#00ff7f"));
+        assert_eq!(resolve_color("12345"), None);
+        assert_eq!(resolve_color("red\" onload=\"x"), None);
+    }
+
+    #[test]
+    fn test_render_escapes_text() {
+        let svg = render_badge("<a&b>", "1.2k", "#007ec6", BadgeStyle::Flat);
+        assert!(svg.contains("&lt;a&amp;b&gt;: 1.2k"));
+        assert!(!svg.contains("<a&b>"));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_render_width_grows_with_text() {
+        let short = render_badge("dl", "1", "#4c1", BadgeStyle::Flat);
+        let long = render_badge("downloads", "1.2M", "#4c1", BadgeStyle::Flat);
+        let width = |svg: &str| -> f64 {
+            let start = svg.find("width=\"").unwrap() + 7;
+            let end = start + svg[start..].find('"').unwrap();
+            svg[start..end].parse().unwrap()
+        };
+        assert!(width(&long) > width(&short));
+    }
+
+    #[test]
+    fn test_flat_square_has_no_gradient() {
+        let flat = render_badge("clicks", "42", "#007ec6", BadgeStyle::Flat);
+        let square = render_badge("clicks", "42", "#007ec6", BadgeStyle::FlatSquare);
+        assert!(flat.contains("linearGradient") && flat.contains("rx=\"3\""));
+        assert!(!square.contains("linearGradient") && square.contains("rx=\"0\""));
+    }
+}
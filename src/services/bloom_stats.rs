@@ -0,0 +1,338 @@
+//! Bloom filter 回源结果统计与容量建议
+//!
+//! 回源路径上的每次 Bloom 判定归为四类：
+//! - `rejected`：Bloom 否定，直接 404，不查库
+//! - `hit`：Bloom 通过，数据库中存在
+//! - `false_positive`：Bloom 通过，数据库中不存在，且不是已删除的短码——真正的假阳性代价
+//! - `stale`：Bloom 通过，数据库中不存在，但短码在上次重建后被删除或归档
+//!
+//! Bloom 不支持删除，已删除的短码在下次重建前仍会通过判定；这类查询是"集合过期"
+//! 而非假阳性，单独计为 `stale`，不计入假阳率。
+//!
+//! 观测假阳率 = `false_positive / (false_positive + rejected)`：不在集合中的短码被
+//! 误放行的比例。计数在每次重建后清零，反映当前这一代 Bloom 的状态。
+
+use std::f64::consts::LN_2;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::metrics::MetricsRecorder;
+
+/// 判定告警前至少需要的样本数（`rejected + false_positive`）
+pub const BLOOM_ALERT_MIN_SAMPLES: u64 = 1_000;
+
+/// 建议容量在反推出的元素数上预留的余量，容纳下次重建前新增的短码
+const CAPACITY_HEADROOM: f64 = 1.25;
+
+/// 一次 Bloom 判定在回源路径上的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloomOutcome {
+    Rejected,
+    Hit,
+    FalsePositive,
+    Stale,
+}
+
+impl BloomOutcome {
+    pub const ALL: [BloomOutcome; 4] = [
+        BloomOutcome::Rejected,
+        BloomOutcome::Hit,
+        BloomOutcome::FalsePositive,
+        BloomOutcome::Stale,
+    ];
+
+    /// metrics 标签值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BloomOutcome::Rejected => "rejected",
+            BloomOutcome::Hit => "hit",
+            BloomOutcome::FalsePositive => "false_positive",
+            BloomOutcome::Stale => "stale",
+        }
+    }
+}
+
+/// 当前这一代 Bloom 的统计快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomStats {
+    /// 重建代数，每次重建递增
+    pub generation: u64,
+    /// 本代 Bloom 生效（计数清零）的时间
+    pub since: DateTime<Utc>,
+    /// 构建时的容量（分片时为本分片的容量）
+    pub capacity: u64,
+    /// 构建时载入的短码数
+    pub loaded: u64,
+    /// 构建时的目标假阳率
+    pub target_false_positive_rate: f64,
+    pub rejected: u64,
+    pub hits: u64,
+    pub false_positives: u64,
+    pub stale: u64,
+}
+
+impl BloomStats {
+    /// 参与假阳率计算的样本数：所有不在集合中的短码
+    pub fn samples(&self) -> u64 {
+        self.rejected + self.false_positives
+    }
+
+    /// 观测假阳率，尚无样本时为 `None`
+    pub fn observed_false_positive_rate(&self) -> Option<f64> {
+        let samples = self.samples();
+        (samples > 0).then(|| self.false_positives as f64 / samples as f64)
+    }
+
+    /// 观测假阳率高于目标时，按公式反推的建议容量
+    pub fn suggested_capacity(&self) -> Option<u64> {
+        let observed = self.observed_false_positive_rate()?;
+        (observed > self.target_false_positive_rate).then(|| {
+            suggested_bloom_capacity(self.capacity, self.target_false_positive_rate, observed)
+        })
+    }
+
+    /// 样本足够且观测假阳率超过阈值（`threshold <= 0` 表示不告警）
+    pub fn exceeds(&self, threshold: f64) -> bool {
+        threshold > 0.0
+            && self.samples() >= BLOOM_ALERT_MIN_SAMPLES
+            && self
+                .observed_false_positive_rate()
+                .is_some_and(|observed| observed > threshold)
+    }
+}
+
+/// 告警阈值（`cache.bloom_fp_alert_rate`），未加载运行时配置时不告警
+pub fn bloom_alert_threshold() -> f64 {
+    try_get_runtime_config().map_or(0.0, |rt| {
+        rt.get_f64_or(keys::CACHE_BLOOM_FP_ALERT_RATE, 0.0)
+    })
+}
+
+/// 观测假阳率超过阈值时告警：日志、metrics，配置了 `alerts.webhook_url` 时投递 Webhook
+pub async fn emit_capacity_alert(
+    stats: &BloomStats,
+    threshold: f64,
+    metrics: &dyn MetricsRecorder,
+) {
+    let observed = stats.observed_false_positive_rate().unwrap_or_default();
+    let suggested = stats.suggested_capacity().unwrap_or(stats.capacity);
+    warn!(
+        "Bloom filter false-positive rate {:.4} exceeds {:.4} ({} of {} absent codes queried the database since {}); \
+         capacity {} looks undersized, set {} to {} (applied on the next Bloom rebuild)",
+        observed,
+        threshold,
+        stats.false_positives,
+        stats.samples(),
+        stats.since.format("%Y-%m-%d %H:%M:%S UTC"),
+        stats.capacity,
+        keys::CACHE_BLOOM_MIN_CAPACITY,
+        suggested
+    );
+    metrics.inc_bloom_capacity_alert();
+
+    let Some(url) = try_get_runtime_config()
+        .map(|rt| rt.get_or(keys::ALERTS_WEBHOOK_URL, ""))
+        .filter(|url| !url.is_empty())
+    else {
+        return;
+    };
+    let payload = serde_json::json!({
+        "event": "bloom_false_positive_rate",
+        "observed_rate": observed,
+        "threshold": threshold,
+        "false_positives": stats.false_positives,
+        "samples": stats.samples(),
+        "since": stats.since,
+        "capacity": stats.capacity,
+        "suggested_capacity": suggested,
+    });
+    let result =
+        tokio::task::spawn_blocking(move || crate::analytics::anomaly::post_webhook(&url, payload))
+            .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("Bloom filter alert webhook failed: {}", e),
+        Err(e) => warn!("Bloom filter alert webhook task failed: {}", e),
+    }
+}
+
+/// 由观测假阳率反推 Bloom 中的实际元素数，给出达到目标假阳率所需的容量
+///
+/// 按容量 `C`、目标假阳率 `p0` 构建的 Bloom 有 `m = -C·ln(p0) / (ln 2)²` 位、
+/// `k = round(m / C · ln 2)` 个哈希；装入 `n` 个元素后的假阳率为
+/// `p = (1 - e^(-k·n/m))^k`，反解得 `n = -(m / k)·ln(1 - p^(1/k))`。
+/// 建议容量为 `n` 加上余量，且不小于原容量。
+pub fn suggested_bloom_capacity(capacity: u64, target_rate: f64, observed_rate: f64) -> u64 {
+    let capacity_f = capacity.max(1) as f64;
+    let bits = -capacity_f * target_rate.ln() / (LN_2 * LN_2);
+    let hashes = (bits / capacity_f * LN_2).round().max(1.0);
+    // 观测值为 1 时反推结果为无穷大，截断到略小于 1
+    let per_hash = observed_rate
+        .clamp(f64::MIN_POSITIVE, 0.999_999)
+        .powf(1.0 / hashes);
+    let items = -(bits / hashes) * (1.0 - per_hash).ln();
+    ((items * CAPACITY_HEADROOM).ceil() as u64).max(capacity)
+}
+
+/// 进程内的 Bloom 结果计数，随 Bloom 重建清零
+pub(crate) struct BloomCounters {
+    generation: AtomicU64,
+    since: AtomicI64,
+    capacity: AtomicU64,
+    loaded: AtomicU64,
+    target_false_positive_rate: f64,
+    rejected: AtomicU64,
+    hits: AtomicU64,
+    false_positives: AtomicU64,
+    stale: AtomicU64,
+}
+
+impl BloomCounters {
+    pub(crate) fn new(capacity: u64, target_false_positive_rate: f64) -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            since: AtomicI64::new(Utc::now().timestamp()),
+            capacity: AtomicU64::new(capacity),
+            loaded: AtomicU64::new(0),
+            target_false_positive_rate,
+            rejected: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            false_positives: AtomicU64::new(0),
+            stale: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn record(&self, outcome: BloomOutcome) {
+        let counter = match outcome {
+            BloomOutcome::Rejected => &self.rejected,
+            BloomOutcome::Hit => &self.hits,
+            BloomOutcome::FalsePositive => &self.false_positives,
+            BloomOutcome::Stale => &self.stale,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 重建提交后开始新一代统计
+    pub(crate) fn reset(&self, capacity: u64, loaded: u64) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        self.loaded.store(loaded, Ordering::Relaxed);
+        for counter in [
+            &self.rejected,
+            &self.hits,
+            &self.false_positives,
+            &self.stale,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.since.store(Utc::now().timestamp(), Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> BloomStats {
+        BloomStats {
+            generation: self.generation.load(Ordering::Relaxed),
+            since: DateTime::from_timestamp(self.since.load(Ordering::Relaxed), 0)
+                .unwrap_or_default(),
+            capacity: self.capacity.load(Ordering::Relaxed),
+            loaded: self.loaded.load(Ordering::Relaxed),
+            target_false_positive_rate: self.target_false_positive_rate,
+            rejected: self.rejected.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            false_positives: self.false_positives.load(Ordering::Relaxed),
+            stale: self.stale.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 容量 `capacity`、目标 `target` 的 Bloom 装入 `items` 个元素后的理论假阳率
+    fn theoretical_rate(capacity: u64, target: f64, items: u64) -> f64 {
+        let capacity = capacity as f64;
+        let bits = -capacity * target.ln() / (LN_2 * LN_2);
+        let hashes = (bits / capacity * LN_2).round();
+        (1.0 - (-hashes * items as f64 / bits).exp()).powf(hashes)
+    }
+
+    fn stats(rejected: u64, false_positives: u64) -> BloomStats {
+        BloomStats {
+            generation: 1,
+            since: Utc::now(),
+            capacity: 10_000,
+            loaded: 10_000,
+            target_false_positive_rate: 0.001,
+            rejected,
+            hits: 500,
+            false_positives,
+            stale: 40,
+        }
+    }
+
+    #[test]
+    fn test_suggested_capacity_recovers_item_count() {
+        // 容量 1 万的 Bloom 实际装了 3 万个短码
+        let observed = theoretical_rate(10_000, 0.001, 30_000);
+        assert!(observed > 0.05, "{}", observed);
+        let suggested = suggested_bloom_capacity(10_000, 0.001, observed);
+        let expected = 30_000.0 * CAPACITY_HEADROOM;
+        assert!(
+            (suggested as f64 - expected).abs() / expected < 0.01,
+            "{}",
+            suggested
+        );
+    }
+
+    #[test]
+    fn test_suggested_capacity_never_shrinks() {
+        assert_eq!(suggested_bloom_capacity(10_000, 0.001, 0.000_01), 10_000);
+        // 观测值为 1 时给出有限的结果
+        assert!(suggested_bloom_capacity(10_000, 0.001, 1.0) > 10_000);
+    }
+
+    #[test]
+    fn test_stale_and_hits_do_not_affect_rate() {
+        let stats = stats(980, 20);
+        assert_eq!(stats.samples(), 1_000);
+        assert_eq!(stats.observed_false_positive_rate(), Some(0.02));
+        assert!(stats.suggested_capacity().is_some());
+
+        let healthy = stats(1_000, 0);
+        assert_eq!(healthy.observed_false_positive_rate(), Some(0.0));
+        assert_eq!(healthy.suggested_capacity(), None);
+        // 只有命中与残留时没有样本
+        assert_eq!(stats(0, 0).observed_false_positive_rate(), None);
+    }
+
+    #[test]
+    fn test_alert_requires_threshold_and_samples() {
+        assert!(stats(980, 20).exceeds(0.01));
+        assert!(!stats(980, 20).exceeds(0.05));
+        assert!(!stats(980, 20).exceeds(0.0));
+        // 样本不足时不告警
+        assert!(!stats(98, 2).exceeds(0.01));
+    }
+
+    #[test]
+    fn test_counters_reset_on_rebuild() {
+        let counters = BloomCounters::new(100, 0.001);
+        counters.record(BloomOutcome::Rejected);
+        counters.record(BloomOutcome::FalsePositive);
+        counters.record(BloomOutcome::Stale);
+        let before = counters.snapshot();
+        assert_eq!(
+            (before.rejected, before.false_positives, before.stale),
+            (1, 1, 1)
+        );
+
+        counters.reset(5_000, 4_000);
+        let after = counters.snapshot();
+        assert_eq!(after.generation, before.generation + 1);
+        assert_eq!((after.capacity, after.loaded), (5_000, 4_000));
+        assert_eq!(after.samples() + after.hits + after.stale, 0);
+    }
+}
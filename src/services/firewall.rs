@@ -0,0 +1,487 @@
+//! 请求特征拦截规则（简版 WAF）
+//!
+//! 规则来自运行时配置 `firewall.rules`（JSON 数组），按数组顺序评估：
+//! - 一条规则内的所有条件同时满足才算命中（未填写的条件不参与匹配）
+//! - `log_only` 命中只记录日志与指标，继续评估后续规则
+//! - `block` / `tarpit` 命中即终止评估，返回 403（`tarpit` 先延迟再返回）
+//!
+//! 正则在解析时预编译；配置变更后由 [`active_rules`] 按
+//! [`RuntimeConfig::version`](crate::config::RuntimeConfig::version) 惰性重建，
+//! 请求路径上只有一次原子读和一次 `ArcSwap` 读。
+
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::utils::cidr::Cidr;
+
+/// 规则数量上限（每个请求都会线性评估）
+pub const MAX_RULES: usize = 64;
+
+/// tarpit 默认延迟
+const DEFAULT_TARPIT_MS: u64 = 2_000;
+
+/// tarpit 延迟上限，避免长期占用连接
+const MAX_TARPIT_MS: u64 = 30_000;
+
+/// 单个正则编译后的大小上限
+const REGEX_SIZE_LIMIT: usize = 256 * 1024;
+
+/// 规则名最大长度（规则名会作为 metrics 标签）
+const MAX_RULE_NAME_LEN: usize = 64;
+
+/// 命中后的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirewallAction {
+    /// 直接返回 403
+    Block,
+    /// 延迟 `tarpit_ms` 后返回 403，拖慢扫描器
+    Tarpit,
+    /// 只记录，不拦截
+    LogOnly,
+}
+
+impl FirewallAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Tarpit => "tarpit",
+            Self::LogOnly => "log_only",
+        }
+    }
+}
+
+/// 配置中的单条规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FirewallRule {
+    pub name: String,
+    /// User-Agent 包含该子串（不区分大小写）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent_contains: Option<String>,
+    /// User-Agent 匹配该正则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent_regex: Option<String>,
+    /// 客户端 IP 落在任一 CIDR 内（单个 IP 视为 /32 或 /128）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_cidrs: Vec<String>,
+    /// Referer 匹配该正则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referer_regex: Option<String>,
+    /// 请求路径以该前缀开头
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    pub action: FirewallAction,
+    /// tarpit 延迟（毫秒），仅 `tarpit` 动作可用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tarpit_ms: Option<u64>,
+}
+
+/// 参与匹配的请求特征
+///
+/// 缺失的 User-Agent / Referer 按空字符串匹配，因此 `^$` 可以匹配空 UA。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestFeatures<'a> {
+    pub path: &'a str,
+    pub user_agent: Option<&'a str>,
+    pub referer: Option<&'a str>,
+    pub client_ip: Option<IpAddr>,
+}
+
+/// 终止评估的命中结果（`block` / `tarpit`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirewallDecision<'a> {
+    pub rule: &'a str,
+    pub action: FirewallAction,
+    pub tarpit: Duration,
+}
+
+/// 预编译后的规则
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    action: FirewallAction,
+    tarpit: Duration,
+    user_agent: Vec<Regex>,
+    referer: Option<Regex>,
+    path_prefix: Option<String>,
+    cidrs: Vec<Cidr>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &FirewallRule) -> Result<Self, String> {
+        let name = rule.name.as_str();
+        if name.is_empty() || name.len() > MAX_RULE_NAME_LEN {
+            return Err(format!(
+                "rule name must be 1-{} characters",
+                MAX_RULE_NAME_LEN
+            ));
+        }
+        if !name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.'))
+        {
+            return Err(format!(
+                "rule '{}': name may only contain letters, digits, '_', '-' and '.'",
+                name
+            ));
+        }
+
+        let compile = |field: &str, pattern: &str| {
+            RegexBuilder::new(pattern)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map_err(|e| format!("rule '{}': invalid {}: {}", name, field, e))
+        };
+
+        let mut user_agent = Vec::new();
+        if let Some(ref needle) = rule.user_agent_contains {
+            if needle.is_empty() {
+                return Err(format!(
+                    "rule '{}': user_agent_contains must not be empty",
+                    name
+                ));
+            }
+            user_agent.push(compile(
+                "user_agent_contains",
+                &format!("(?i){}", regex::escape(needle)),
+            )?);
+        }
+        if let Some(ref pattern) = rule.user_agent_regex {
+            user_agent.push(compile("user_agent_regex", pattern)?);
+        }
+        let referer = rule
+            .referer_regex
+            .as_deref()
+            .map(|pattern| compile("referer_regex", pattern))
+            .transpose()?;
+
+        let cidrs = rule
+            .ip_cidrs
+            .iter()
+            .map(|raw| {
+                Cidr::parse(raw)
+                    .ok_or_else(|| format!("rule '{}': invalid IP or CIDR '{}'", name, raw))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let path_prefix = rule.path_prefix.clone().filter(|p| !p.is_empty());
+
+        if user_agent.is_empty() && referer.is_none() && cidrs.is_empty() && path_prefix.is_none() {
+            return Err(format!(
+                "rule '{}': at least one match condition is required",
+                name
+            ));
+        }
+
+        let tarpit_ms = match (rule.action, rule.tarpit_ms) {
+            (FirewallAction::Tarpit, Some(ms)) if ms == 0 || ms > MAX_TARPIT_MS => {
+                return Err(format!(
+                    "rule '{}': tarpit_ms must be between 1 and {}",
+                    name, MAX_TARPIT_MS
+                ));
+            }
+            (FirewallAction::Tarpit, ms) => ms.unwrap_or(DEFAULT_TARPIT_MS),
+            (_, Some(_)) => {
+                return Err(format!(
+                    "rule '{}': tarpit_ms is only valid for tarpit rules",
+                    name
+                ));
+            }
+            (_, None) => 0,
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            action: rule.action,
+            tarpit: Duration::from_millis(tarpit_ms),
+            user_agent,
+            referer,
+            path_prefix,
+            cidrs,
+        })
+    }
+
+    fn matches(&self, req: &RequestFeatures<'_>) -> bool {
+        if let Some(ref prefix) = self.path_prefix
+            && !req.path.starts_with(prefix.as_str())
+        {
+            return false;
+        }
+        if !self.cidrs.is_empty() {
+            let Some(ip) = req.client_ip else {
+                return false;
+            };
+            if !self.cidrs.iter().any(|cidr| cidr.contains(ip)) {
+                return false;
+            }
+        }
+        let user_agent = req.user_agent.unwrap_or("");
+        if !self.user_agent.iter().all(|re| re.is_match(user_agent)) {
+            return false;
+        }
+        if let Some(ref re) = self.referer
+            && !re.is_match(req.referer.unwrap_or(""))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// 一组预编译规则
+#[derive(Debug, Default)]
+pub struct FirewallRules {
+    rules: Vec<CompiledRule>,
+    needs_client_ip: bool,
+}
+
+impl FirewallRules {
+    /// 解析并预编译规则；空字符串视为空规则集
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        Self::compile(&parse_rule_list(raw)?)
+    }
+
+    fn compile(rules: &[FirewallRule]) -> Result<Self, String> {
+        if rules.len() > MAX_RULES {
+            return Err(format!(
+                "at most {} rules are allowed, got {}",
+                MAX_RULES,
+                rules.len()
+            ));
+        }
+
+        let mut compiled: Vec<CompiledRule> = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let rule = CompiledRule::compile(rule)?;
+            if compiled.iter().any(|r| r.name == rule.name) {
+                return Err(format!("duplicate rule name '{}'", rule.name));
+            }
+            compiled.push(rule);
+        }
+
+        let needs_client_ip = compiled.iter().any(|r| !r.cidrs.is_empty());
+        Ok(Self {
+            rules: compiled,
+            needs_client_ip,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// 是否有规则依赖客户端 IP（没有时中间件可跳过 IP 解析）
+    pub fn needs_client_ip(&self) -> bool {
+        self.needs_client_ip
+    }
+
+    /// 按顺序评估规则
+    ///
+    /// 每条命中的规则（含 `log_only`）都会回调 `on_hit(rule, action)`；
+    /// 遇到 `block` / `tarpit` 时停止并返回该结果。
+    pub fn evaluate(
+        &self,
+        req: &RequestFeatures<'_>,
+        mut on_hit: impl FnMut(&str, FirewallAction),
+    ) -> Option<FirewallDecision<'_>> {
+        for rule in &self.rules {
+            if !rule.matches(req) {
+                continue;
+            }
+            on_hit(&rule.name, rule.action);
+            if rule.action != FirewallAction::LogOnly {
+                return Some(FirewallDecision {
+                    rule: &rule.name,
+                    action: rule.action,
+                    tarpit: rule.tarpit,
+                });
+            }
+        }
+        None
+    }
+}
+
+fn parse_rule_list(raw: &str) -> Result<Vec<FirewallRule>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(raw).map_err(|e| format!("invalid rule JSON: {}", e))
+}
+
+/// 校验 `firewall.rules` 并输出规范化 JSON（配置写入时调用）
+pub fn normalize_rules(raw: &str) -> Result<String, String> {
+    let rules = parse_rule_list(raw)?;
+    FirewallRules::compile(&rules)?;
+    serde_json::to_string(&rules).map_err(|e| e.to_string())
+}
+
+struct ActiveRules {
+    version: u64,
+    rules: Arc<FirewallRules>,
+}
+
+static ACTIVE_RULES: LazyLock<ArcSwap<ActiveRules>> = LazyLock::new(|| {
+    ArcSwap::from_pointee(ActiveRules {
+        version: u64::MAX,
+        rules: Arc::new(FirewallRules::default()),
+    })
+});
+
+/// 获取当前生效的规则
+///
+/// 运行时配置版本变化时重新解析；解析失败（如数据库中残留旧版本写入的非法值）
+/// 时保留上一版规则并告警。
+pub fn active_rules() -> Arc<FirewallRules> {
+    let Some(rt) = try_get_runtime_config() else {
+        return ACTIVE_RULES.load().rules.clone();
+    };
+
+    let version = rt.version();
+    let current = ACTIVE_RULES.load();
+    if current.version == version {
+        return current.rules.clone();
+    }
+
+    let raw = rt.get_or(keys::FIREWALL_RULES, "[]");
+    let rules = match FirewallRules::parse(&raw) {
+        Ok(rules) => {
+            if !rules.is_empty() || !current.rules.is_empty() {
+                info!("Firewall rules loaded: {} active rules", rules.len());
+            }
+            Arc::new(rules)
+        }
+        Err(e) => {
+            warn!(
+                "Invalid firewall.rules ({}), keeping previous {} rules",
+                e,
+                current.rules.len()
+            );
+            current.rules.clone()
+        }
+    };
+
+    ACTIVE_RULES.store(Arc::new(ActiveRules {
+        version,
+        rules: rules.clone(),
+    }));
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features<'a>(path: &'a str, ua: Option<&'a str>) -> RequestFeatures<'a> {
+        RequestFeatures {
+            path,
+            user_agent: ua,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_log_only_continues_and_block_stops() {
+        let rules = FirewallRules::parse(
+            r#"[
+                {"name":"observe","user_agent_contains":"Scanner","action":"log_only"},
+                {"name":"block-scanner","user_agent_regex":"scanner/\\d+","action":"block"},
+                {"name":"never","path_prefix":"/","action":"tarpit"}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut hits = Vec::new();
+        let decision = rules
+            .evaluate(
+                &features("/abc", Some("evil-scanner/2.0")),
+                |rule, action| hits.push((rule.to_string(), action)),
+            )
+            .unwrap();
+
+        assert_eq!(decision.rule, "block-scanner");
+        assert_eq!(decision.action, FirewallAction::Block);
+        assert_eq!(
+            hits,
+            vec![
+                ("observe".to_string(), FirewallAction::LogOnly),
+                ("block-scanner".to_string(), FirewallAction::Block),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conditions_are_combined() {
+        let rules = FirewallRules::parse(
+            r#"[{"name":"spam","referer_regex":"spam\\.example$","path_prefix":"/promo","ip_cidrs":["192.0.2.0/24"],"action":"tarpit","tarpit_ms":500}]"#,
+        )
+        .unwrap();
+        assert!(rules.needs_client_ip());
+
+        let mut req = RequestFeatures {
+            path: "/promo/x",
+            user_agent: None,
+            referer: Some("https://spam.example"),
+            client_ip: Some("192.0.2.7".parse().unwrap()),
+        };
+        let decision = rules.evaluate(&req, |_, _| {}).unwrap();
+        assert_eq!(decision.tarpit, Duration::from_millis(500));
+
+        req.client_ip = Some("198.51.100.1".parse().unwrap());
+        assert!(rules.evaluate(&req, |_, _| {}).is_none());
+        req.client_ip = None;
+        assert!(rules.evaluate(&req, |_, _| {}).is_none());
+    }
+
+    #[test]
+    fn test_missing_user_agent_matches_empty_pattern() {
+        let rules =
+            FirewallRules::parse(r#"[{"name":"no-ua","user_agent_regex":"^$","action":"block"}]"#)
+                .unwrap();
+        assert!(rules.evaluate(&features("/a", None), |_, _| {}).is_some());
+        assert!(
+            rules
+                .evaluate(&features("/a", Some("Mozilla/5.0")), |_, _| {})
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        for raw in [
+            r#"{"name":"x"}"#,
+            r#"[{"name":"x","action":"block"}]"#,
+            r#"[{"name":"x","user_agent_regex":"(","action":"block"}]"#,
+            r#"[{"name":"x","ip_cidrs":["1.2.3.4/40"],"action":"block"}]"#,
+            r#"[{"name":"x","path_prefix":"/a","action":"block","tarpit_ms":100}]"#,
+            r#"[{"name":"x","path_prefix":"/a","action":"tarpit","tarpit_ms":999999}]"#,
+            r#"[{"name":"has space","path_prefix":"/a","action":"block"}]"#,
+            r#"[{"name":"x","path_prefix":"/a","action":"drop"}]"#,
+            r#"[{"name":"x","path_prefix":"/a","action":"block","unknown":1}]"#,
+            r#"[{"name":"x","path_prefix":"/a","action":"block"},{"name":"x","path_prefix":"/b","action":"block"}]"#,
+        ] {
+            assert!(FirewallRules::parse(raw).is_err(), "accepted: {}", raw);
+        }
+    }
+
+    #[test]
+    fn test_normalize_rules_canonicalizes() {
+        assert_eq!(normalize_rules("").unwrap(), "[]");
+        assert_eq!(
+            normalize_rules(r#"[ {"action":"block", "name":"a", "path_prefix":"/x"} ]"#).unwrap(),
+            r#"[{"name":"a","path_prefix":"/x","action":"block"}]"#
+        );
+    }
+}
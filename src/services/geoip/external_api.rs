@@ -1,35 +1,37 @@
 //! 外部 GeoIP API 实现
 //!
 //! 使用外部 HTTP API 进行 IP 地理位置查询（如 ip-api.com）
-//! 内置 LRU 缓存 + Singleflight 语义，避免重复查询
+//! 内置 LRU 缓存 + Singleflight 语义，避免重复查询；
+//! 缓存未命中的请求经过 [`ApiGuard`] 限流 / 熔断后才真正发出
 
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use moka::future::Cache;
 use tracing::{trace, warn};
 use ureq::Agent;
 
+use super::guard::{ApiGuard, SkipReason};
 use super::provider::{GeoInfo, GeoIpLookup};
 
 /// GeoIP 缓存 TTL（15 分钟）
 const GEOIP_CACHE_TTL_SECS: u64 = 15 * 60;
 /// GeoIP 缓存最大容量
 const GEOIP_CACHE_MAX_CAPACITY: u64 = 10_000;
-/// HTTP 请求超时时间
-const HTTP_TIMEOUT_SECS: u64 = 2;
-
-/// 全局 HTTP Agent（ureq 的 Agent 是 Send + Sync）
-static HTTP_AGENT: OnceLock<Agent> = OnceLock::new();
-
-fn get_agent() -> &'static Agent {
-    HTTP_AGENT.get_or_init(|| {
-        Agent::config_builder()
-            .timeout_global(Some(Duration::from_secs(HTTP_TIMEOUT_SECS)))
-            .build()
-            .into()
-    })
+
+fn build_agent(timeout: Duration) -> Agent {
+    Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .build()
+        .into()
+}
+
+/// 未拿到可缓存结果的原因（不写入缓存，下次重新查询）
+#[derive(Debug)]
+enum FetchError {
+    Skipped(SkipReason),
+    Failed,
 }
 
 /// 外部 API GeoIP Provider
@@ -38,8 +40,12 @@ fn get_agent() -> &'static Agent {
 /// - LRU 淘汰策略，最大 10000 条
 /// - TTL 15 分钟
 /// - Singleflight：同一 IP 的并发请求只发一次 HTTP
+///
+/// API 明确返回无结果（`status: fail`）时负缓存；请求失败或被保护层跳过时不缓存
 pub struct ExternalApiProvider {
     api_url_template: String,
+    agent: Agent,
+    guard: Arc<ApiGuard>,
     /// IP → GeoInfo 缓存（Option 用于负缓存）
     cache: Cache<String, Option<GeoInfo>>,
 }
@@ -49,7 +55,7 @@ impl ExternalApiProvider {
     ///
     /// `api_url_template` 使用 `{ip}` 作为占位符
     /// 例如: `http://ip-api.com/json/{ip}?fields=status,countryCode,city`
-    pub fn new(api_url_template: &str) -> Self {
+    pub fn new(api_url_template: &str, guard: Arc<ApiGuard>) -> Self {
         let cache = Cache::builder()
             .time_to_live(Duration::from_secs(GEOIP_CACHE_TTL_SECS))
             .max_capacity(GEOIP_CACHE_MAX_CAPACITY)
@@ -57,36 +63,32 @@ impl ExternalApiProvider {
 
         Self {
             api_url_template: api_url_template.to_string(),
+            agent: build_agent(guard.settings().timeout),
+            guard,
             cache,
         }
     }
 
     /// 从外部 API 获取 GeoIP 信息（同步，在 spawn_blocking 中调用）
-    fn fetch_from_api_sync(url: String) -> Option<GeoInfo> {
-        let agent = get_agent();
-
-        let resp = match agent.get(&url).call() {
-            Ok(r) => r,
-            Err(e) => {
-                warn!("GeoIP API request to \"{}\" failed: {}", url, e);
-                return None;
-            }
-        };
-
-        let json: serde_json::Value = match resp.into_body().read_json() {
-            Ok(j) => j,
-            Err(e) => {
-                warn!("GeoIP API response from \"{}\" parse failed: {}", url, e);
-                return None;
-            }
-        };
+    ///
+    /// `Ok(None)` 表示 API 明确返回无结果，`Err` 表示请求或解析失败
+    fn fetch_from_api_sync(agent: &Agent, url: &str) -> Result<Option<GeoInfo>, String> {
+        let resp = agent
+            .get(url)
+            .call()
+            .map_err(|e| format!("request to \"{}\" failed: {}", url, e))?;
+
+        let json: serde_json::Value = resp
+            .into_body()
+            .read_json()
+            .map_err(|e| format!("response from \"{}\" parse failed: {}", url, e))?;
 
         // ip-api.com 返回格式: {"countryCode": "CN", "city": "Beijing"}
         // 失败时返回: {"status": "fail", ...}
         // 也支持其他 API 的常见字段名
         if json["status"].as_str() == Some("fail") {
             trace!("External API returned fail status");
-            return None;
+            return Ok(None);
         }
 
         let country = json["countryCode"]
@@ -102,20 +104,35 @@ impl ExternalApiProvider {
             country, city
         );
 
-        Some(GeoInfo { country, city })
+        Ok(Some(GeoInfo { country, city }))
     }
 
-    /// 从外部 API 获取 GeoIP 信息（异步包装）
-    async fn fetch_from_api(&self, ip: &str) -> Option<GeoInfo> {
+    /// 经保护层从外部 API 获取 GeoIP 信息（异步包装）
+    async fn fetch_from_api(&self, ip: &str) -> Result<Option<GeoInfo>, FetchError> {
+        let permit = self
+            .guard
+            .acquire(Instant::now())
+            .map_err(FetchError::Skipped)?;
+
         let url = self.api_url_template.replace("{ip}", ip);
+        let agent = self.agent.clone();
 
         // 使用 spawn_blocking 在线程池中执行同步 HTTP 请求
-        tokio::task::spawn_blocking(move || Self::fetch_from_api_sync(url))
+        let result = tokio::task::spawn_blocking(move || Self::fetch_from_api_sync(&agent, &url))
             .await
-            .unwrap_or_else(|e| {
-                warn!("GeoIP spawn_blocking failed: {}", e);
-                None
-            })
+            .unwrap_or_else(|e| Err(format!("spawn_blocking failed: {}", e)));
+
+        match result {
+            Ok(info) => {
+                permit.success();
+                Ok(info)
+            }
+            Err(e) => {
+                warn!("GeoIP API {}", e);
+                permit.failure(Instant::now());
+                Err(FetchError::Failed)
+            }
+        }
     }
 }
 
@@ -124,20 +141,25 @@ impl GeoIpLookup for ExternalApiProvider {
     /// 查询 IP 地理位置（带缓存 + Singleflight）
     ///
     /// - 缓存命中：直接返回
-    /// - 缓存未命中：发起 HTTP 请求并缓存结果
+    /// - 缓存未命中：经保护层发起 HTTP 请求并缓存结果
     /// - 并发请求同一 IP：只有一个发起请求，其他等待结果
+    /// - 被跳过或请求失败：返回 `None`，不缓存
     async fn lookup(&self, ip: &str) -> Option<GeoInfo> {
         let ip_key = ip.to_string();
 
-        // get_with 自带 singleflight 语义：
-        // 同一 key 的并发调用只会执行一次闭包，其他等待结果
-        // 返回类型是 Option<GeoInfo>（缓存的值类型）
+        // try_get_with 同样带 singleflight 语义，且 Err 不会写入缓存
         self.cache
-            .get_with(ip_key, async {
+            .try_get_with(ip_key, async {
                 trace!("GeoIP cache miss for {}, fetching from API", ip);
                 self.fetch_from_api(ip).await
             })
             .await
+            .unwrap_or_else(|e| {
+                if let FetchError::Skipped(reason) = e.as_ref() {
+                    trace!("GeoIP lookup for {} skipped ({})", ip, reason.as_str());
+                }
+                None
+            })
     }
 
     fn name(&self) -> &'static str {
@@ -148,13 +170,39 @@ impl GeoIpLookup for ExternalApiProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::AnalyticsConfig;
+    use crate::metrics::NoopMetrics;
+    use crate::services::geoip::guard::ApiGuardSettings;
+
+    const IP_API_URL: &str = "http://ip-api.com/json/{ip}?fields=status,countryCode,city";
+
+    fn provider() -> ExternalApiProvider {
+        let settings = ApiGuardSettings::from_config(&AnalyticsConfig::default());
+        ExternalApiProvider::new(
+            IP_API_URL,
+            Arc::new(ApiGuard::new(settings, NoopMetrics::arc())),
+        )
+    }
+
+    /// 熔断中的查询立即返回 None，且不写入负缓存
+    #[tokio::test]
+    async fn test_skipped_lookup_is_not_cached() {
+        let provider = provider();
+        let now = Instant::now();
+        for _ in 0..5 {
+            provider.guard.acquire(now).unwrap().failure(now);
+        }
+
+        assert!(provider.lookup("8.8.8.8").await.is_none());
+        assert!(provider.cache.get("8.8.8.8").await.is_none());
+    }
 
     /// 测试 ureq 基本 HTTP 请求
     /// 依赖外部网络服务，CI 环境可能失败
     #[test]
     #[ignore]
     fn test_ureq_basic_request() {
-        let agent = get_agent();
+        let agent = build_agent(Duration::from_secs(2));
 
         // 用 httpbin 测试基本连通性
         let resp = agent.get("https://httpbin.org/json").call();
@@ -174,13 +222,14 @@ mod tests {
     #[ignore]
     fn test_fetch_from_api_sync_real() {
         // 用 Google DNS 的 IP 测试（稳定、公开）
-        let url = "http://ip-api.com/json/8.8.8.8?fields=status,countryCode,city".to_string();
-
-        let result = ExternalApiProvider::fetch_from_api_sync(url);
+        let url = IP_API_URL.replace("{ip}", "8.8.8.8");
 
-        assert!(result.is_some(), "Should get GeoIP result for 8.8.8.8");
+        let result =
+            ExternalApiProvider::fetch_from_api_sync(&build_agent(Duration::from_secs(2)), &url);
 
-        let geo = result.unwrap();
+        let geo = result
+            .expect("request should succeed")
+            .expect("Should get GeoIP result for 8.8.8.8");
         assert_eq!(
             geo.country,
             Some("US".to_string()),
@@ -194,8 +243,7 @@ mod tests {
     #[tokio::test]
     #[ignore]
     async fn test_external_api_provider_lookup() {
-        let provider =
-            ExternalApiProvider::new("http://ip-api.com/json/{ip}?fields=status,countryCode,city");
+        let provider = provider();
 
         // 第一次查询（缓存未命中，发起 HTTP 请求）
         let result1 = provider.lookup("8.8.8.8").await;
@@ -213,8 +261,7 @@ mod tests {
     #[tokio::test]
     #[ignore]
     async fn test_external_api_provider_invalid_ip() {
-        let provider =
-            ExternalApiProvider::new("http://ip-api.com/json/{ip}?fields=status,countryCode,city");
+        let provider = provider();
 
         // 私有 IP 查询（ip-api.com 返回 {"status":"fail",...}）
         let result = provider.lookup("192.168.1.1").await;
@@ -229,11 +276,12 @@ mod tests {
     #[ignore]
     fn test_timeout_handling() {
         // 用一个不存在的地址测试超时
-        let url = "http://192.0.2.1/timeout-test".to_string(); // TEST-NET, 不可路由
+        let url = "http://192.0.2.1/timeout-test"; // TEST-NET, 不可路由
 
-        let result = ExternalApiProvider::fetch_from_api_sync(url);
+        let result =
+            ExternalApiProvider::fetch_from_api_sync(&build_agent(Duration::from_secs(2)), url);
 
-        // 应该在 2 秒内超时并返回 None
-        assert!(result.is_none(), "Should timeout and return None");
+        // 应该在 2 秒内超时并返回错误（计入熔断，不缓存）
+        assert!(result.is_err(), "Should timeout and return error");
     }
 }
@@ -0,0 +1,361 @@
+//! 外部 GeoIP API 保护层
+//!
+//! 位于外部 API Provider 的缓存与 HTTP 请求之间，只保护真正发出去的请求
+//! （缓存命中不受影响）。被跳过的查询不等待、不缓存，调用方拿到 `None`：
+//!
+//! - 熔断：连续失败达到阈值后断开一段时间（[`CircuitBreaker`]）
+//! - 并发上限：在途请求满了直接跳过，不排队占用 blocking 线程池
+//! - 每分钟配额：固定窗口计数，避免超出免费档（ip-api.com 为 45 次/分钟）被封
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::{info, trace, warn};
+
+use crate::config::AnalyticsConfig;
+use crate::metrics::MetricsRecorder;
+use crate::utils::circuit_breaker::{CircuitBreaker, CircuitSnapshot, CircuitState};
+
+/// 配额窗口
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 保护层参数（来自启动配置 `[analytics]`）
+#[derive(Debug, Clone)]
+pub struct ApiGuardSettings {
+    pub max_concurrency: usize,
+    pub timeout: Duration,
+    /// 连续失败阈值，0 = 不熔断
+    pub failure_threshold: u32,
+    pub open_duration: Duration,
+    /// 每分钟请求上限，0 = 不限
+    pub rate_per_minute: u32,
+}
+
+impl ApiGuardSettings {
+    pub fn from_config(config: &AnalyticsConfig) -> Self {
+        Self {
+            max_concurrency: config
+                .geoip_api_max_concurrency
+                .clamp(1, Semaphore::MAX_PERMITS),
+            timeout: Duration::from_millis(config.geoip_api_timeout_ms.max(1)),
+            failure_threshold: config.geoip_api_failure_threshold,
+            open_duration: Duration::from_secs(config.geoip_api_open_minutes.max(1) * 60),
+            rate_per_minute: config.geoip_api_rate_per_minute,
+        }
+    }
+}
+
+/// 查询被跳过的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// 熔断中
+    CircuitOpen,
+    /// 在途请求已达上限
+    Busy,
+    /// 本分钟配额用完
+    RateLimited,
+}
+
+impl SkipReason {
+    pub const ALL: [Self; 3] = [Self::CircuitOpen, Self::Busy, Self::RateLimited];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CircuitOpen => "circuit_open",
+            Self::Busy => "busy",
+            Self::RateLimited => "rate_limited",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Self::CircuitOpen => 0,
+            Self::Busy => 1,
+            Self::RateLimited => 2,
+        }
+    }
+}
+
+/// 保护层状态快照
+#[derive(Debug, Clone)]
+pub struct ApiGuardStatus {
+    pub circuit: CircuitSnapshot,
+    pub failure_threshold: u32,
+    pub open_duration: Duration,
+    pub in_flight: usize,
+    pub max_concurrency: usize,
+    /// 当前分钟窗口已用配额
+    pub rate_used: u32,
+    pub rate_per_minute: u32,
+    /// 启动以来各原因跳过的次数
+    pub skipped: Vec<(SkipReason, u64)>,
+}
+
+#[derive(Debug)]
+struct RateWindow {
+    started: Instant,
+    used: u32,
+}
+
+/// 外部 API 请求保护层
+pub struct ApiGuard {
+    settings: ApiGuardSettings,
+    semaphore: Semaphore,
+    window: Mutex<RateWindow>,
+    breaker: CircuitBreaker,
+    skipped: [AtomicU64; 3],
+    metrics: Arc<dyn MetricsRecorder>,
+}
+
+/// 一次放行的请求
+///
+/// 必须以 [`success`](Self::success) 或 [`failure`](Self::failure) 结束；
+/// 未记录结果就被丢弃（请求被取消）时只归还名额，不计入熔断。
+pub struct GuardPermit<'a> {
+    guard: &'a ApiGuard,
+    _permit: SemaphorePermit<'a>,
+    finished: bool,
+}
+
+impl GuardPermit<'_> {
+    pub fn success(mut self) {
+        self.finished = true;
+        if self.guard.breaker.record_success() == Some(CircuitState::Closed) {
+            info!("GeoIP external API recovered, circuit closed");
+            self.guard.report_state(CircuitState::Closed);
+        }
+    }
+
+    pub fn failure(mut self, now: Instant) {
+        self.finished = true;
+        if self.guard.breaker.record_failure(now) == Some(CircuitState::Open) {
+            warn!(
+                "GeoIP external API failing ({} consecutive failures), skipping lookups for {}m",
+                self.guard.breaker.snapshot(now).consecutive_failures,
+                self.guard.settings.open_duration.as_secs() / 60
+            );
+            self.guard.report_state(CircuitState::Open);
+        }
+    }
+}
+
+impl Drop for GuardPermit<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.guard.breaker.abandon();
+        }
+    }
+}
+
+impl ApiGuard {
+    pub fn new(settings: ApiGuardSettings, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        metrics.set_geoip_circuit_state(CircuitState::Closed.as_gauge());
+        Self {
+            semaphore: Semaphore::new(settings.max_concurrency),
+            window: Mutex::new(RateWindow {
+                started: Instant::now(),
+                used: 0,
+            }),
+            breaker: CircuitBreaker::new(settings.failure_threshold, settings.open_duration),
+            skipped: Default::default(),
+            settings,
+            metrics,
+        }
+    }
+
+    pub fn settings(&self) -> &ApiGuardSettings {
+        &self.settings
+    }
+
+    /// 申请发出一次请求，不放行时立即返回跳过原因
+    pub fn acquire(&self, now: Instant) -> Result<GuardPermit<'_>, SkipReason> {
+        if !self.breaker.try_acquire(now) {
+            return Err(self.skip(SkipReason::CircuitOpen));
+        }
+        if self.breaker.snapshot(now).state == CircuitState::HalfOpen {
+            self.report_state(CircuitState::HalfOpen);
+        }
+        let Ok(permit) = self.semaphore.try_acquire() else {
+            self.breaker.abandon();
+            return Err(self.skip(SkipReason::Busy));
+        };
+        if !self.take_quota(now) {
+            self.breaker.abandon();
+            return Err(self.skip(SkipReason::RateLimited));
+        }
+        Ok(GuardPermit {
+            guard: self,
+            _permit: permit,
+            finished: false,
+        })
+    }
+
+    fn take_quota(&self, now: Instant) -> bool {
+        if self.settings.rate_per_minute == 0 {
+            return true;
+        }
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        if now.saturating_duration_since(window.started) >= RATE_WINDOW {
+            window.started = now;
+            window.used = 0;
+        }
+        if window.used >= self.settings.rate_per_minute {
+            return false;
+        }
+        window.used += 1;
+        true
+    }
+
+    fn skip(&self, reason: SkipReason) -> SkipReason {
+        trace!("GeoIP external API lookup skipped: {}", reason.as_str());
+        self.skipped[reason.index()].fetch_add(1, Ordering::Relaxed);
+        self.metrics.inc_geoip_lookup_skipped(reason.as_str());
+        reason
+    }
+
+    fn report_state(&self, state: CircuitState) {
+        self.metrics.set_geoip_circuit_state(state.as_gauge());
+    }
+
+    pub fn status(&self, now: Instant) -> ApiGuardStatus {
+        let rate_used = {
+            let window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+            if now.saturating_duration_since(window.started) >= RATE_WINDOW {
+                0
+            } else {
+                window.used
+            }
+        };
+        ApiGuardStatus {
+            circuit: self.breaker.snapshot(now),
+            failure_threshold: self.settings.failure_threshold,
+            open_duration: self.settings.open_duration,
+            in_flight: self.settings.max_concurrency - self.semaphore.available_permits(),
+            max_concurrency: self.settings.max_concurrency,
+            rate_used,
+            rate_per_minute: self.settings.rate_per_minute,
+            skipped: SkipReason::ALL
+                .iter()
+                .map(|reason| {
+                    (
+                        *reason,
+                        self.skipped[reason.index()].load(Ordering::Relaxed),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::NoopMetrics;
+
+    fn guard(max_concurrency: usize, failure_threshold: u32, rate_per_minute: u32) -> ApiGuard {
+        ApiGuard::new(
+            ApiGuardSettings {
+                max_concurrency,
+                timeout: Duration::from_secs(2),
+                failure_threshold,
+                open_duration: Duration::from_secs(60),
+                rate_per_minute,
+            },
+            NoopMetrics::arc(),
+        )
+    }
+
+    fn skipped(guard: &ApiGuard, reason: SkipReason, now: Instant) -> u64 {
+        guard
+            .status(now)
+            .skipped
+            .into_iter()
+            .find(|(r, _)| *r == reason)
+            .map(|(_, count)| count)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_concurrency_limit_skips_instead_of_waiting() {
+        let guard = guard(2, 5, 0);
+        let now = Instant::now();
+
+        let first = guard.acquire(now).unwrap();
+        let _second = guard.acquire(now).unwrap();
+        assert_eq!(guard.status(now).in_flight, 2);
+        assert_eq!(guard.acquire(now).err(), Some(SkipReason::Busy));
+
+        first.success();
+        assert!(guard.acquire(now).is_ok());
+        assert_eq!(skipped(&guard, SkipReason::Busy, now), 1);
+    }
+
+    #[test]
+    fn test_rate_limit_per_minute() {
+        let guard = guard(10, 5, 3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            guard.acquire(now).unwrap().success();
+        }
+        assert_eq!(guard.acquire(now).err(), Some(SkipReason::RateLimited));
+        assert_eq!(guard.status(now).rate_used, 3);
+
+        let next_minute = now + RATE_WINDOW;
+        assert_eq!(guard.status(next_minute).rate_used, 0);
+        assert!(guard.acquire(next_minute).is_ok());
+    }
+
+    #[test]
+    fn test_failures_open_circuit() {
+        let guard = guard(10, 2, 0);
+        let now = Instant::now();
+
+        guard.acquire(now).unwrap().failure(now);
+        guard.acquire(now).unwrap().failure(now);
+        assert_eq!(guard.status(now).circuit.state, CircuitState::Open);
+        assert_eq!(guard.acquire(now).err(), Some(SkipReason::CircuitOpen));
+
+        // 到期后放行一个探测请求，成功后恢复
+        let later = now + Duration::from_secs(60);
+        let probe = guard.acquire(later).unwrap();
+        assert_eq!(guard.acquire(later).err(), Some(SkipReason::CircuitOpen));
+        probe.success();
+        assert_eq!(guard.status(later).circuit.state, CircuitState::Closed);
+        assert_eq!(skipped(&guard, SkipReason::CircuitOpen, later), 2);
+    }
+
+    #[test]
+    fn test_dropped_probe_is_not_counted() {
+        let guard = guard(1, 1, 0);
+        let now = Instant::now();
+        guard.acquire(now).unwrap().failure(now);
+
+        let later = now + Duration::from_secs(60);
+        drop(guard.acquire(later).unwrap());
+        let status = guard.status(later);
+        assert_eq!(status.circuit.state, CircuitState::HalfOpen);
+        assert_eq!(status.in_flight, 0);
+        assert!(guard.acquire(later).is_ok());
+    }
+
+    #[test]
+    fn test_settings_from_config() {
+        let settings = ApiGuardSettings::from_config(&AnalyticsConfig::default());
+        assert_eq!(settings.max_concurrency, 4);
+        assert_eq!(settings.timeout, Duration::from_secs(2));
+        assert_eq!(settings.failure_threshold, 5);
+        assert_eq!(settings.open_duration, Duration::from_secs(300));
+        assert_eq!(settings.rate_per_minute, 45);
+
+        let settings = ApiGuardSettings::from_config(&AnalyticsConfig {
+            geoip_api_max_concurrency: 0,
+            geoip_api_open_minutes: 0,
+            ..AnalyticsConfig::default()
+        });
+        assert_eq!(settings.max_concurrency, 1);
+        assert_eq!(settings.open_duration, Duration::from_secs(60));
+    }
+}
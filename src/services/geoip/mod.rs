@@ -2,10 +2,12 @@
 //!
 //! 提供 IP 地址地理位置查询功能，支持：
 //! - MaxMind GeoLite2 本地数据库
-//! - 外部 API fallback (ip-api.com)
+//! - 外部 API fallback (ip-api.com)，带并发上限、超时、每分钟配额与熔断
 
 mod external_api;
+mod guard;
 mod maxmind;
 mod provider;
 
+pub use guard::{ApiGuardStatus, SkipReason};
 pub use provider::{GeoInfo, GeoIpLookup, GeoIpProvider};
@@ -3,16 +3,19 @@
 //! 统一的 GeoIP 查询接口，根据配置自动选择实现：
 //! 1. 检查 maxminddb_path 是否配置且文件可读
 //! 2. 可读 → MaxMindProvider
-//! 3. 不可读 → ExternalApiProvider
+//! 3. 不可读 → ExternalApiProvider（外面包一层 [`ApiGuard`] 限流 / 熔断）
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use tracing::{debug, info, warn};
 
 use super::external_api::ExternalApiProvider;
+use super::guard::{ApiGuard, ApiGuardSettings, ApiGuardStatus};
 use super::maxmind::MaxMindProvider;
 use crate::config::AnalyticsConfig;
+use crate::metrics::MetricsRecorder;
 
 /// 地理位置信息
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -38,6 +41,8 @@ pub trait GeoIpLookup: Send + Sync {
 /// 启动时根据配置自动选择实现
 pub struct GeoIpProvider {
     inner: Arc<dyn GeoIpLookup>,
+    /// 使用外部 API 时的保护层（用于状态查询）
+    api_guard: Option<Arc<ApiGuard>>,
 }
 
 impl GeoIpProvider {
@@ -46,28 +51,42 @@ impl GeoIpProvider {
     /// 1. 检查 maxminddb_path 是否配置且文件可读
     /// 2. 可读 → MaxMindProvider
     /// 3. 不可读 → ExternalApiProvider
-    pub fn new(config: &AnalyticsConfig) -> Self {
-        let inner: Arc<dyn GeoIpLookup> = if let Some(ref path) = config.maxminddb_path {
+    pub fn new(config: &AnalyticsConfig, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        let external = || {
+            let settings = ApiGuardSettings::from_config(config);
+            info!(
+                "GeoIP: External API limited to {} concurrent / {} per minute, circuit opens after {} failures",
+                settings.max_concurrency, settings.rate_per_minute, settings.failure_threshold
+            );
+            let guard = Arc::new(ApiGuard::new(settings, metrics.clone()));
+            let provider: Arc<dyn GeoIpLookup> = Arc::new(ExternalApiProvider::new(
+                &config.geoip_api_url,
+                guard.clone(),
+            ));
+            (provider, Some(guard))
+        };
+
+        let (inner, api_guard) = if let Some(ref path) = config.maxminddb_path {
             match MaxMindProvider::new(path) {
                 Ok(provider) => {
                     info!("GeoIP: Using MaxMind database at {}", path);
-                    Arc::new(provider)
+                    (Arc::new(provider) as Arc<dyn GeoIpLookup>, None)
                 }
                 Err(e) => {
                     warn!(
                         "GeoIP: Failed to load MaxMind database at {}: {}, falling back to external API",
                         path, e
                     );
-                    Arc::new(ExternalApiProvider::new(&config.geoip_api_url))
+                    external()
                 }
             }
         } else {
             debug!("GeoIP: No MaxMind database configured, using external API");
-            Arc::new(ExternalApiProvider::new(&config.geoip_api_url))
+            external()
         };
 
         info!("GeoIP: Initialized with {} provider", inner.name());
-        Self { inner }
+        Self { inner, api_guard }
     }
 
     /// 查询 IP 地址的地理位置
@@ -79,12 +98,20 @@ impl GeoIpProvider {
     pub fn provider_name(&self) -> &'static str {
         self.inner.name()
     }
+
+    /// 外部 API 保护层状态（使用 MaxMind 时为 `None`）
+    pub fn external_api_status(&self) -> Option<ApiGuardStatus> {
+        self.api_guard
+            .as_ref()
+            .map(|guard| guard.status(Instant::now()))
+    }
 }
 
 impl Clone for GeoIpProvider {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            api_guard: self.api_guard.clone(),
         }
     }
 }
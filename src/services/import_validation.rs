@@ -8,6 +8,10 @@ use tracing::warn;
 
 use crate::errors::ShortlinkerError;
 use crate::services::ImportLinkItemRich;
+use crate::services::link_validation::{
+    FieldError, LinkField, LinkInput, ValidationProfile, validate_new_link,
+};
+use crate::storage::AnalyticsLevel;
 use crate::system::ipc::types::ImportLinkData;
 use crate::utils::password::process_imported_password;
 
@@ -22,6 +26,10 @@ pub struct ImportLinkItemRaw {
     pub expires_at: Option<String>,
     pub password: Option<String>,
     pub click_count: usize,
+    /// 缺省或空值为 `inherit`
+    pub analytics_level: Option<String>,
+    /// JSON 对象文本，缺省或空值为无扩展字段
+    pub extras: Option<String>,
     /// CSV 行号（1-based），仅 Admin API 设置，IPC/CSV 路径为 None
     pub row_num: Option<usize>,
 }
@@ -35,6 +43,8 @@ impl From<ImportLinkData> for ImportLinkItemRaw {
             expires_at: l.expires_at,
             password: l.password,
             click_count: l.click_count,
+            analytics_level: l.analytics_level,
+            extras: l.extras,
             row_num: None,
         }
     }
@@ -49,34 +59,75 @@ pub struct ImportRowError {
     pub row_num: Option<usize>,
 }
 
+/// 单行警告：该行照常导入，但结果里提示调用方
+#[derive(Debug, Clone)]
+pub struct ImportRowWarning {
+    pub code: String,
+    pub message: String,
+    /// 来源行号，直接从 `ImportLinkItemRaw.row_num` 透传
+    pub row_num: Option<usize>,
+}
+
+/// 验证通过的导入行及其警告
+#[derive(Debug, Clone)]
+pub struct ValidatedImportRow {
+    pub item: ImportLinkItemRich,
+    pub warnings: Vec<ImportRowWarning>,
+}
+
+/// 验证并转换单个导入行，不关心警告
+pub fn validate_import_row(raw: ImportLinkItemRaw) -> Result<ImportLinkItemRich, ImportRowError> {
+    validate_import_row_checked(raw).map(|row| row.item)
+}
+
 /// 验证并转换单个导入行
 ///
-/// 验证顺序：
+/// 字段校验使用 [`ValidationProfile::IMPORT`]，错误优先级：
 /// 1. code 非空
-/// 2. URL 有效
+/// 2. URL 有效（凭据按 `links.credential_policy` 处理：warning、移除后 warning 或报错）
 /// 3. created_at 解析（失败 fallback 到 now）
-/// 4. expires_at 解析（失败忽略）
-/// 5. 密码处理（已哈希保留，明文哈希）
-pub fn validate_import_row(raw: ImportLinkItemRaw) -> Result<ImportLinkItemRich, ImportRowError> {
+/// 4. expires_at 解析（仅绝对时间，语义见 [`parse_expiry_value`]；非法值报错，
+///    已经过去的时间照常导入并给出 warning）
+/// 5. extras 校验（非法值报错）
+/// 6. 密码处理（已哈希保留，明文哈希）
+/// 7. analytics_level 解析（非法值报错）
+///
+/// [`parse_expiry_value`]: crate::services::link_validation::parse_expiry_value
+pub fn validate_import_row_checked(
+    raw: ImportLinkItemRaw,
+) -> Result<ValidatedImportRow, ImportRowError> {
     let row_num = raw.row_num;
 
-    // 1. 空 code 检查
-    if raw.code.is_empty() {
-        return Err(ImportRowError {
-            code: raw.code,
-            error: ShortlinkerError::link_invalid_code("Empty code"),
-            row_num,
-        });
-    }
-
-    // 2. URL 验证
-    if let Err(e) = aster_forge_utils::url::parse_http_url(&raw.target, "target URL") {
-        return Err(ImportRowError {
-            code: raw.code,
-            error: ShortlinkerError::link_invalid_url(format!("Invalid URL: {}", e)),
-            row_num,
-        });
-    }
+    // 1-2, 4-5. 字段校验（code 错误优先于 URL 错误）
+    let validated = match validate_new_link(
+        LinkInput {
+            code: Some(&raw.code),
+            target: &raw.target,
+            expires_at: raw.expires_at.as_deref(),
+            extras: raw.extras.as_deref(),
+        },
+        ValidationProfile::IMPORT,
+    ) {
+        Ok(v) => v,
+        Err(errors) => {
+            let FieldError { field, error } = errors
+                .iter()
+                .find(|e| e.field == LinkField::Code)
+                .unwrap_or(&errors[0])
+                .clone();
+            let error = match field {
+                LinkField::Target => {
+                    ShortlinkerError::link_invalid_url(format!("Invalid URL: {}", error.message()))
+                }
+                _ => error,
+            };
+            return Err(ImportRowError {
+                code: raw.code,
+                error,
+                row_num,
+            });
+        }
+    };
 
     // 3. 解析 created_at
     let created_at = DateTime::parse_from_rfc3339(&raw.created_at)
@@ -89,18 +140,7 @@ pub fn validate_import_row(raw: ImportLinkItemRaw) -> Result<ImportLinkItemRich,
             Utc::now()
         });
 
-    // 4. 解析 expires_at
-    let expires_at = raw.expires_at.as_ref().and_then(|s| {
-        if s.is_empty() {
-            None
-        } else {
-            DateTime::parse_from_rfc3339(s)
-                .ok()
-                .map(|dt| dt.with_timezone(&Utc))
-        }
-    });
-
-    // 5. 密码处理
+    // 6. 密码处理
     let password = match process_imported_password(raw.password.as_deref()) {
         Ok(pwd) => pwd,
         Err(e) => {
@@ -115,32 +155,83 @@ pub fn validate_import_row(raw: ImportLinkItemRaw) -> Result<ImportLinkItemRich,
         }
     };
 
-    Ok(ImportLinkItemRich {
-        code: raw.code,
-        target: raw.target,
-        created_at,
-        expires_at,
-        password,
-        click_count: raw.click_count,
-        row_num,
+    // 7. 解析 analytics_level
+    let analytics_level = match raw.analytics_level.as_deref().map(str::trim) {
+        None | Some("") => AnalyticsLevel::Inherit,
+        Some(value) => match value.parse() {
+            Ok(level) => level,
+            Err(e) => {
+                return Err(ImportRowError {
+                    code: raw.code,
+                    error: ShortlinkerError::validation(e),
+                    row_num,
+                });
+            }
+        },
+    };
+
+    let mut warnings = Vec::new();
+    if let Some(message) = validated.credential_warning {
+        warnings.push(ImportRowWarning {
+            code: raw.code.clone(),
+            message,
+            row_num,
+        });
+    }
+    if let Some(expires_at) = validated.expires_at
+        && validated.expiry_in_past
+    {
+        warnings.push(ImportRowWarning {
+            code: raw.code.clone(),
+            message: format!(
+                "expires_at {} is in the past; imported as already expired",
+                expires_at.to_rfc3339()
+            ),
+            row_num,
+        });
+    }
+
+    Ok(ValidatedImportRow {
+        item: ImportLinkItemRich {
+            code: raw.code,
+            target: validated.target,
+            created_at,
+            expires_at: validated.expires_at,
+            password,
+            click_count: raw.click_count,
+            analytics_level,
+            extras: validated.extras,
+            row_num,
+        },
+        warnings,
     })
 }
 
-/// 批量验证导入行，返回 (成功项, 失败项)
+/// 批量验证导入行，返回 (成功项, 失败项, 警告)
+///
+/// 带警告的行同时出现在成功项里。
 pub fn validate_import_rows(
     rows: Vec<ImportLinkItemRaw>,
-) -> (Vec<ImportLinkItemRich>, Vec<ImportRowError>) {
+) -> (
+    Vec<ImportLinkItemRich>,
+    Vec<ImportRowError>,
+    Vec<ImportRowWarning>,
+) {
     let mut valid = Vec::with_capacity(rows.len());
     let mut errors = Vec::new();
+    let mut warnings = Vec::new();
 
     for raw in rows {
-        match validate_import_row(raw) {
-            Ok(item) => valid.push(item),
+        match validate_import_row_checked(raw) {
+            Ok(row) => {
+                valid.push(row.item);
+                warnings.extend(row.warnings);
+            }
             Err(e) => errors.push(e),
         }
     }
 
-    (valid, errors)
+    (valid, errors, warnings)
 }
 
 #[cfg(test)]
@@ -155,10 +246,45 @@ mod tests {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: None,
+            extras: None,
             row_num: None,
         }
     }
 
+    #[test]
+    fn test_analytics_level() {
+        let mut raw = make_raw("level", "https://example.com");
+        raw.analytics_level = Some("count_only".to_string());
+        let rich = validate_import_row(raw).unwrap();
+        assert_eq!(rich.analytics_level, AnalyticsLevel::CountOnly);
+
+        let mut raw = make_raw("level", "https://example.com");
+        raw.analytics_level = Some(String::new());
+        let rich = validate_import_row(raw).unwrap();
+        assert_eq!(rich.analytics_level, AnalyticsLevel::Inherit);
+
+        let mut raw = make_raw("level", "https://example.com");
+        raw.analytics_level = Some("detailed".to_string());
+        assert!(validate_import_row(raw).is_err());
+    }
+
+    #[test]
+    fn test_extras() {
+        let mut raw = make_raw("extras", "https://example.com");
+        raw.extras = Some(r#"{ "ticket": "JIRA-123" }"#.to_string());
+        let rich = validate_import_row(raw).unwrap();
+        assert_eq!(rich.extras.as_deref(), Some(r#"{"ticket":"JIRA-123"}"#));
+
+        let mut raw = make_raw("extras", "https://example.com");
+        raw.extras = Some(String::new());
+        assert_eq!(validate_import_row(raw).unwrap().extras, None);
+
+        let mut raw = make_raw("extras", "https://example.com");
+        raw.extras = Some("[1, 2]".to_string());
+        assert!(validate_import_row(raw).is_err());
+    }
+
     #[test]
     fn test_valid_row() {
         let raw = make_raw("test", "https://example.com");
@@ -203,9 +329,10 @@ mod tests {
             make_raw("bad-url", "not-a-url"),
             make_raw("also-good", "https://test.com"),
         ];
-        let (valid, errors) = validate_import_rows(rows);
+        let (valid, errors, warnings) = validate_import_rows(rows);
         assert_eq!(valid.len(), 2);
         assert_eq!(errors.len(), 2);
+        assert!(warnings.is_empty());
     }
 
     // ---- row_num propagation tests ----
@@ -242,7 +369,7 @@ mod tests {
         let mut row10 = make_raw("dup", "not-a-url");
         row10.row_num = Some(10);
 
-        let (valid, errors) = validate_import_rows(vec![row5, row10]);
+        let (valid, errors, _) = validate_import_rows(vec![row5, row10]);
         assert_eq!(valid.len(), 1);
         assert_eq!(errors.len(), 1);
 
@@ -253,6 +380,95 @@ mod tests {
         assert_eq!(errors[0].code, "dup");
     }
 
+    #[test]
+    fn test_empty_code_reported_before_invalid_url() {
+        let err = validate_import_row(make_raw("", "not-a-url")).unwrap_err();
+        assert_eq!(err.error.code(), "E024");
+    }
+
+    #[test]
+    fn test_import_expiry_semantics() {
+        // 空值与 never 为永不过期
+        for expires in ["", "never"] {
+            let mut raw = make_raw("test", "https://example.com");
+            raw.expires_at = Some(expires.to_string());
+            assert_eq!(validate_import_row(raw).unwrap().expires_at, None);
+        }
+
+        // 相对时间、0 与非法值是该行的错误，不再静默当作永不过期
+        for expires in ["1d", "0", "garbage", "2023-02-30"] {
+            let mut raw = make_raw("test", "https://example.com");
+            raw.row_num = Some(7);
+            raw.expires_at = Some(expires.to_string());
+            let err = validate_import_row(raw).unwrap_err();
+            assert!(
+                matches!(err.error, ShortlinkerError::LinkInvalidExpireTime(_)),
+                "{}",
+                expires
+            );
+            assert_eq!(err.row_num, Some(7));
+        }
+
+        let mut raw = make_raw("test", "https://example.com");
+        raw.expires_at = Some("now".to_string());
+        let row = validate_import_row_checked(raw).unwrap();
+        assert!(row.item.expires_at.unwrap() <= Utc::now());
+        assert!(row.warnings.is_empty());
+
+        let mut raw = make_raw("test", "https://example.com");
+        raw.expires_at = Some("2030-01-01T00:00:00Z".to_string());
+        assert!(validate_import_row(raw).unwrap().expires_at.is_some());
+
+        // 只有日期的值与 API、CLI 一样按配置时区解释
+        let mut raw = make_raw("test", "https://example.com");
+        raw.expires_at = Some("2030-01-01".to_string());
+        assert!(validate_import_row(raw).unwrap().expires_at.is_some());
+    }
+
+    #[test]
+    fn test_past_expiry_imported_with_warning() {
+        let mut past = make_raw("archived", "https://example.com");
+        past.row_num = Some(4);
+        past.expires_at = Some("2020-01-01T00:00:00Z".to_string());
+        let mut future = make_raw("current", "https://example.com");
+        future.expires_at = Some("2099-01-01T00:00:00Z".to_string());
+
+        let (valid, errors, warnings) = validate_import_rows(vec![past, future]);
+        assert_eq!(valid.len(), 2);
+        assert!(errors.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "archived");
+        assert_eq!(warnings[0].row_num, Some(4));
+        assert!(warnings[0].message.contains("2020-01-01"));
+    }
+
+    #[test]
+    fn test_credentials_in_target_annotated_per_row() {
+        let mut leaky = make_raw("leaky", "https://admin:pw@example.com/?a=1");
+        leaky.row_num = Some(2);
+        leaky.expires_at = Some("2020-01-01T00:00:00Z".to_string());
+        let clean = make_raw("clean", "https://example.com/?a=1");
+
+        let (valid, errors, warnings) = validate_import_rows(vec![leaky, clean]);
+        assert_eq!(valid.len(), 2);
+        assert!(errors.is_empty());
+        // 同一行的凭据与过期两条警告都保留
+        assert_eq!(warnings.len(), 2);
+        assert!(
+            warnings
+                .iter()
+                .all(|w| w.code == "leaky" && w.row_num == Some(2))
+        );
+        assert!(warnings[0].message.contains("userinfo"));
+    }
+
+    #[test]
+    fn test_import_does_not_check_code_charset() {
+        // 导入历史上只检查非空，保留路由与字符集不校验
+        let raw = make_raw("admin", "https://example.com");
+        assert!(validate_import_row(raw).is_ok());
+    }
+
     #[test]
     fn test_empty_code_error_carries_row_num() {
         let mut raw = make_raw("", "https://example.com");
@@ -1,20 +1,34 @@
 //! Short-link cache policy built directly on AsterForge cache primitives.
+//!
+//! 回源回填带版本校验：读路径 miss 后在查库前取 [`FillToken`]，写路径在写缓存前
+//! 递增该短码的写入版本。回填时版本已变化（回源期间有写入）则丢弃结果，避免把
+//! 回源时读到的旧行写回缓存、一直提供到 TTL 过期。
+//!
+//! Bloom 判定与回源结果按 [`BloomOutcome`] 分类计数（见 `bloom_stats`）。Bloom 不支持
+//! 删除，本进程删除 / 归档的短码记录在 `removed` 中直到下次重建提交，回源未命中时
+//! 据此区分"集合过期"与真正的假阳性。
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::Instant;
 
 use async_trait::async_trait;
+use dashmap::{DashMap, DashSet};
 use futures_util::StreamExt;
 
+use super::bloom_stats::{BloomCounters, BloomOutcome, BloomStats};
+use super::link_l1_cache::{L1CacheLimits, L1Insert, L1LinkCache};
 use crate::errors::{Result, ShortlinkerError};
 use crate::metrics::MetricsRecorder;
 use crate::storage::{SeaOrmStorage, ShortLink};
+use crate::utils::shard::{CodeShard, code_hash};
 
 const INITIAL_BLOOM_CAPACITY: usize = 100;
 const BLOOM_FALSE_POSITIVE_RATE: f64 = 0.001;
 const NEGATIVE_CACHE_PREFIX: &str = "shortlink:negative:";
 const NEGATIVE_CACHE_TTL_SECS: u64 = 60;
 const BLOOM_REBUILD_BATCH_SIZE: u64 = 10_000;
+const WRITE_VERSION_STRIPES: usize = 1024;
 
 /// Result of looking up a short link through the cache query chain.
 #[derive(Debug, Clone)]
@@ -25,8 +39,17 @@ pub enum LinkCacheLookup {
     Miss,
     /// A valid cached short link was found.
     Found(ShortLink),
+    /// The short code no longer resolves because the link was archived.
+    Gone,
 }
 
+/// Write version of a short code observed before loading it from storage.
+///
+/// Obtained from [`LinkCache::fill_token`] (read path) or [`LinkCache::invalidate`]
+/// (write path) and handed back to [`LinkCache::fill`] / [`LinkCache::fill_not_found`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FillToken(u64);
+
 /// Cache health data presented by the shortlinker health endpoint.
 #[derive(Debug, Clone)]
 pub struct LinkCacheHealth {
@@ -48,16 +71,192 @@ pub trait LinkCache: Send + Sync {
     async fn mark_not_found(&self, key: &str);
     async fn bloom_check(&self, key: &str) -> bool;
     async fn health_check(&self) -> LinkCacheHealth;
+
+    /// Snapshot of the write version of `key`, taken before loading it from storage.
+    fn fill_token(&self, _key: &str) -> FillToken {
+        FillToken::default()
+    }
+
+    /// Backfill a link loaded from storage.
+    ///
+    /// Discarded when `key` was written after `token` was taken, so a load that read
+    /// the old row cannot overwrite a concurrent update.
+    async fn fill(&self, key: &str, value: ShortLink, ttl_secs: Option<u64>, _token: FillToken) {
+        self.insert(key, value, ttl_secs).await;
+    }
+
+    /// Negative-cache a code storage reported missing, guarded like [`LinkCache::fill`].
+    async fn fill_not_found(&self, key: &str, _token: FillToken) {
+        self.mark_not_found(key).await;
+    }
+
+    /// Drops the cached payload of a just-written code before reloading it.
+    ///
+    /// Returns the token for the reload; backfills started before the call are discarded.
+    async fn invalidate(&self, key: &str) -> FillToken {
+        self.remove(key).await;
+        self.fill_token(key)
+    }
+
+    /// Write-through for a batch of links: Bloom membership first, then object payloads.
+    async fn insert_batch(&self, entries: Vec<(ShortLink, Option<u64>)>) {
+        for (link, ttl_secs) in entries {
+            let code = link.code.clone();
+            self.insert(&code, link, ttl_secs).await;
+        }
+    }
+
+    /// Register existence of freshly written codes without caching their payloads.
+    ///
+    /// Bulk imports use this per chunk together with [`LinkCache::invalidate_codes`].
+    async fn insert_codes(&self, _codes: &[String]) {}
+
+    /// Drops cached payloads and not-found markers of codes written behind the cache.
+    ///
+    /// Pending backfills of these codes are discarded.
+    async fn invalidate_codes(&self, codes: &[String]) {
+        for code in codes {
+            self.invalidate(code).await;
+        }
+    }
+
+    /// Marks the start of a bulk write; periodic Bloom rebuilds are skipped meanwhile.
+    fn begin_bulk_write(&self) {}
+
+    /// Marks the end of a bulk write started with [`LinkCache::begin_bulk_write`].
+    fn end_bulk_write(&self) {}
+
+    /// Whether any bulk write is currently in progress.
+    fn bulk_write_in_progress(&self) -> bool {
+        false
+    }
+
+    /// Drops cached payloads for archived codes so lookups report them as gone.
+    async fn mark_archived(&self, codes: &[String]) {
+        for code in codes {
+            self.remove(code).await;
+        }
+    }
+
+    /// Clears the archived marker after a link is restored.
+    async fn unmark_archived(&self, _key: &str) {}
+
+    /// Records whether a code that passed the Bloom check was found in storage.
+    fn record_origin_lookup(&self, _key: &str, _found: bool) {}
+
+    /// Bloom outcome counters for the current filter generation.
+    fn bloom_stats(&self) -> Option<BloomStats> {
+        None
+    }
+}
+
+/// Handling of objects that exceed `cache.max_entry_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizePolicy {
+    /// Keep the object out of L1 but still write it to the L2 object backend.
+    L2,
+    /// Do not cache the object at all.
+    Skip,
+}
+
+impl OversizePolicy {
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "l2" => Self::L2,
+            "skip" => Self::Skip,
+            other => {
+                tracing::warn!(
+                    value = other,
+                    "unknown cache.oversize_policy, falling back to \"l2\""
+                );
+                Self::L2
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::L2 => "l2",
+            Self::Skip => "skip",
+        }
+    }
+
+    /// Policy actually applied: without an L2 object backend `l2` degrades to `skip`.
+    pub fn effective(self, l2_enabled: bool) -> Self {
+        if l2_enabled { self } else { Self::Skip }
+    }
+}
+
+/// 按短码哈希分条的写入版本
+///
+/// 内存固定，不随短码数量增长；不同短码落在同一条上只会多丢弃一次回填。
+/// 版本只在进程内有效：多实例共享 Redis 时，其他实例的回填不受本实例写入约束。
+struct WriteVersions(Box<[AtomicU64]>);
+
+impl WriteVersions {
+    fn new() -> Self {
+        Self(
+            (0..WRITE_VERSION_STRIPES)
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+        )
+    }
+
+    fn stripe(&self, key: &str) -> &AtomicU64 {
+        &self.0[(code_hash(key) % self.0.len() as u64) as usize]
+    }
+
+    fn current(&self, key: &str) -> FillToken {
+        FillToken(self.stripe(key).load(Ordering::SeqCst))
+    }
+
+    fn bump(&self, key: &str) -> FillToken {
+        FillToken(self.stripe(key).fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn bump_all(&self) {
+        for version in self.0.iter() {
+            version.fetch_add(1, Ordering::SeqCst);
+        }
+    }
 }
 
 /// Production cache policy using Forge object, negative, and Bloom primitives.
+///
+/// 对象缓存分两层：L1 为进程内按字节计重的 [`L1LinkCache`]，L2 为 Forge 对象后端。
+/// `memory` 后端下 L1 直接替代 Forge 内存对象缓存；`redis` 后端仅在配置了
+/// `cache.l1_max_bytes` / `cache.l1_max_entries` 时才在 Redis 前增加 L1。
+///
+/// 配置了 `cache.shard_total` 时，Bloom 只收录本分片的短码，L1 默认只缓存本分片的
+/// 链接；非本分片的短码跳过 Bloom 判定，照常经负缓存 / L2 回源。
 pub struct ForgeLinkCache {
     bloom: Arc<aster_forge_cache::bloom::BloomFilter>,
+    l1: Option<L1LinkCache>,
     objects: Arc<dyn aster_forge_cache::CacheBackend>,
+    l2_enabled: bool,
+    oversize_policy: OversizePolicy,
     negatives: Arc<dyn aster_forge_cache::CacheBackend>,
     object_prefix: String,
     metrics: Arc<dyn MetricsRecorder>,
     storage: Arc<SeaOrmStorage>,
+    /// Serializes Bloom rebuilds with batched inserts so a committed rebuild
+    /// never drops codes inserted while it was streaming the database.
+    rebuild_lock: tokio::sync::Mutex<()>,
+    bulk_writers: AtomicUsize,
+    /// Archived codes, consulted only when a lookup would otherwise report
+    /// NotFound so archived links answer 410 instead of 404.
+    archived: DashSet<String>,
+    /// 本实例负责的短码分片，`None` 表示不分片
+    shard: Option<CodeShard>,
+    /// 非本分片的链接回源后是否写入 L1
+    backfill_foreign: bool,
+    /// 写路径递增、回填时校验的写入版本
+    versions: WriteVersions,
+    /// 本进程删除 / 归档、但仍留在 Bloom 中的短码，值为删除时的重建轮次
+    removed: DashMap<String, u64>,
+    /// 重建轮次，每次开始重建时递增
+    removal_epoch: AtomicU64,
+    bloom_counters: BloomCounters,
 }
 
 impl ForgeLinkCache {
@@ -82,16 +281,69 @@ impl ForgeLinkCache {
             ))
             .map_err(|error| ShortlinkerError::cache_connection(error.to_string()))?;
 
+        let l1_limits = L1CacheLimits::from_config(&config.cache);
+        let in_process = config.cache.cache_type == "memory";
+        let l1 = (in_process || l1_limits.max_bytes > 0 || l1_limits.max_entries > 0)
+            .then(|| L1LinkCache::new(l1_limits));
+
+        let shard = CodeShard::from_runtime_config();
+        let backfill_foreign = crate::config::try_get_runtime_config().is_some_and(|rt| {
+            rt.get_bool_or(crate::config::keys::CACHE_SHARD_BACKFILL_FOREIGN, false)
+        });
+        if let Some(shard) = shard {
+            tracing::info!(
+                index = shard.index(),
+                total = shard.total(),
+                backfill_foreign,
+                "link cache sharding enabled"
+            );
+        }
+
+        let configured_policy = OversizePolicy::from_config(&config.cache.oversize_policy);
+        let oversize_policy = configured_policy.effective(!in_process);
+        if oversize_policy != configured_policy {
+            tracing::debug!(
+                configured = configured_policy.as_str(),
+                effective = oversize_policy.as_str(),
+                "no L2 object backend, oversize objects are not cached"
+            );
+        }
+
         Ok(Arc::new(Self {
             bloom: Arc::new(bloom),
+            l1,
             objects,
+            l2_enabled: !in_process,
+            oversize_policy,
             negatives,
             object_prefix: config.cache.redis.key_prefix.clone(),
             metrics,
             storage,
+            rebuild_lock: tokio::sync::Mutex::new(()),
+            bulk_writers: AtomicUsize::new(0),
+            archived: DashSet::new(),
+            shard,
+            backfill_foreign,
+            versions: WriteVersions::new(),
+            removed: DashMap::new(),
+            removal_epoch: AtomicU64::new(0),
+            bloom_counters: BloomCounters::new(
+                INITIAL_BLOOM_CAPACITY as u64,
+                BLOOM_FALSE_POSITIVE_RATE,
+            ),
         }))
     }
 
+    /// 短码是否归本实例负责（未分片时恒为 true）
+    fn owns(&self, key: &str) -> bool {
+        self.shard.is_none_or(|shard| shard.owns(key))
+    }
+
+    /// Bloom 判定：非本分片的短码不在 Bloom 中，只能视为"可能存在"
+    fn may_exist(&self, key: &str) -> bool {
+        !self.owns(key) || self.bloom.contains(key)
+    }
+
     fn object_key(&self, key: &str) -> String {
         format!("{}{}", self.object_prefix, key)
     }
@@ -99,20 +351,110 @@ impl ForgeLinkCache {
     fn negative_key(key: &str) -> String {
         format!("{NEGATIVE_CACHE_PREFIX}{key}")
     }
+
+    fn not_found_or_gone(&self, key: &str) -> LinkCacheLookup {
+        if self.archived.contains(key) {
+            LinkCacheLookup::Gone
+        } else {
+            LinkCacheLookup::NotFound
+        }
+    }
+
+    /// 计入一次 Bloom 判定结果，并刷新假阳率 gauge
+    fn record_bloom(&self, outcome: BloomOutcome) {
+        self.bloom_counters.record(outcome);
+        self.metrics.inc_bloom_lookup(outcome.as_str());
+        match outcome {
+            BloomOutcome::FalsePositive => self.metrics.inc_bloom_false_positive(),
+            BloomOutcome::Hit | BloomOutcome::Stale => return,
+            BloomOutcome::Rejected => {}
+        }
+        if let Some(rate) = self
+            .bloom_counters
+            .snapshot()
+            .observed_false_positive_rate()
+        {
+            self.metrics.set_bloom_false_positive_rate(rate);
+        }
+    }
+
+    /// Drops the object payload from L1 / L2 without touching the negative cache.
+    async fn drop_object(&self, key: &str) {
+        if let Some(l1) = &self.l1 {
+            l1.remove(key).await;
+        }
+        self.objects.delete(&self.object_key(key)).await;
+    }
+
+    /// 回填结果已过期（回源期间有写入）时记录并返回 true
+    fn stale_fill(&self, key: &str, token: FillToken, kind: &str) -> bool {
+        if self.versions.current(key) == token {
+            return false;
+        }
+        self.metrics.inc_cache_stale_fill(kind);
+        tracing::debug!(
+            key,
+            kind,
+            "discarding cache backfill, link was written while loading"
+        );
+        true
+    }
+
+    /// Clears the negative entry and writes the object payload to L1 / L2.
+    async fn store_object(&self, key: &str, value: ShortLink, ttl_secs: Option<u64>) {
+        self.negatives.delete(&Self::negative_key(key)).await;
+
+        let payload = self.l2_enabled.then(|| serde_json::to_vec(&value));
+
+        let mut oversize = false;
+        if let Some(l1) = &self.l1
+            && (self.backfill_foreign || self.owns(key))
+        {
+            oversize = l1.insert(key, value, ttl_secs).await == L1Insert::Oversize;
+            self.metrics
+                .set_cache_entries("l1_cache", l1.entry_count() as f64);
+        }
+        if oversize {
+            self.metrics
+                .inc_cache_oversize_skipped(self.oversize_policy.as_str());
+            tracing::debug!(
+                key,
+                policy = self.oversize_policy.as_str(),
+                "short link exceeds cache.max_entry_bytes, kept out of L1"
+            );
+        }
+
+        if let Some(payload) = payload {
+            let object_key = self.object_key(key);
+            if oversize && self.oversize_policy == OversizePolicy::Skip {
+                self.objects.delete(&object_key).await;
+            } else {
+                match payload {
+                    Ok(bytes) => {
+                        self.objects.set_bytes(&object_key, bytes, ttl_secs).await;
+                    }
+                    Err(error) => {
+                        tracing::error!(key, error = %error, "failed to serialize short link cache payload");
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl LinkCache for ForgeLinkCache {
     async fn get(&self, key: &str) -> LinkCacheLookup {
         let bloom_start = Instant::now();
-        if !self.bloom.contains(key) {
+        if !self.may_exist(key) {
             self.metrics.observe_cache_operation(
                 "get",
                 "bloom_filter",
                 bloom_start.elapsed().as_secs_f64(),
             );
             self.metrics.inc_cache_hit("bloom_filter");
-            return LinkCacheLookup::NotFound;
+            self.record_bloom(BloomOutcome::Rejected);
+            return self.not_found_or_gone(key);
         }
 
         let negative_start = Instant::now();
@@ -128,7 +470,26 @@ impl LinkCache for ForgeLinkCache {
                 negative_start.elapsed().as_secs_f64(),
             );
             self.metrics.inc_cache_hit("negative_cache");
-            return LinkCacheLookup::NotFound;
+            return self.not_found_or_gone(key);
+        }
+
+        if let Some(l1) = &self.l1 {
+            let l1_start = Instant::now();
+            let cached = l1.get(key).await;
+            self.metrics.observe_cache_operation(
+                "get",
+                "l1_cache",
+                l1_start.elapsed().as_secs_f64(),
+            );
+            if let Some(link) = cached {
+                self.metrics.inc_cache_hit("l1_cache");
+                return LinkCacheLookup::Found(link);
+            }
+            self.metrics.inc_cache_miss("l1_cache");
+        }
+
+        if !self.l2_enabled {
+            return LinkCacheLookup::Miss;
         }
 
         let object_start = Instant::now();
@@ -151,7 +512,7 @@ impl LinkCache for ForgeLinkCache {
         );
         match result {
             LinkCacheLookup::Found(_) => self.metrics.inc_cache_hit("object_cache"),
-            LinkCacheLookup::Miss | LinkCacheLookup::NotFound => {
+            LinkCacheLookup::Miss | LinkCacheLookup::NotFound | LinkCacheLookup::Gone => {
                 self.metrics.inc_cache_miss("object_cache");
             }
         }
@@ -160,19 +521,12 @@ impl LinkCache for ForgeLinkCache {
 
     async fn insert(&self, key: &str, value: ShortLink, ttl_secs: Option<u64>) {
         let start = Instant::now();
-        self.negatives.delete(&Self::negative_key(key)).await;
-        self.bloom.insert(key);
-
-        match serde_json::to_vec(&value) {
-            Ok(bytes) => {
-                self.objects
-                    .set_bytes(&self.object_key(key), bytes, ttl_secs)
-                    .await;
-            }
-            Err(error) => {
-                tracing::error!(key, error = %error, "failed to serialize short link cache payload");
-            }
+        self.versions.bump(key);
+        if self.owns(key) {
+            self.bloom.insert(key);
         }
+        self.removed.remove(key);
+        self.store_object(key, value, ttl_secs).await;
         self.metrics.observe_cache_operation(
             "insert",
             "object_cache",
@@ -182,7 +536,12 @@ impl LinkCache for ForgeLinkCache {
 
     async fn remove(&self, key: &str) {
         let start = Instant::now();
-        self.objects.delete(&self.object_key(key)).await;
+        self.versions.bump(key);
+        if self.owns(key) {
+            self.removed
+                .insert(key.to_string(), self.removal_epoch.load(Ordering::SeqCst));
+        }
+        self.drop_object(key).await;
         self.negatives
             .set_bytes(
                 &Self::negative_key(key),
@@ -198,6 +557,10 @@ impl LinkCache for ForgeLinkCache {
     }
 
     async fn invalidate_all(&self) {
+        self.versions.bump_all();
+        if let Some(l1) = &self.l1 {
+            l1.clear();
+        }
         self.objects.invalidate_prefix(&self.object_prefix).await;
         self.negatives
             .invalidate_prefix(NEGATIVE_CACHE_PREFIX)
@@ -205,9 +568,22 @@ impl LinkCache for ForgeLinkCache {
     }
 
     async fn rebuild_all(&self) -> Result<()> {
-        let count = usize::try_from(self.storage.count().await?).map_err(|_| {
+        let _rebuild = self.rebuild_lock.lock().await;
+        let total = self.storage.count().await?;
+        // 分片后 Bloom 只需容纳约 1/shard_total 的短码
+        let expected = match self.shard {
+            Some(shard) => total.div_ceil(shard.total()),
+            None => total,
+        };
+        let count = usize::try_from(expected).map_err(|_| {
             ShortlinkerError::cache_connection("link count exceeds Bloom filter capacity")
         })?;
+        let min_capacity = crate::config::try_get_runtime_config().map_or(0, |rt| {
+            rt.get_u64_or(crate::config::keys::CACHE_BLOOM_MIN_CAPACITY, 0)
+        });
+        let count = count.max(usize::try_from(min_capacity).unwrap_or(usize::MAX));
+        // 此后删除的短码可能已被流式读入新 Bloom，提交后仍需保留
+        let epoch = self.removal_epoch.fetch_add(1, Ordering::SeqCst);
         let mut rebuild = self
             .bloom
             .start_rebuild(aster_forge_cache::bloom::BloomConfig::new(
@@ -220,10 +596,29 @@ impl LinkCache for ForgeLinkCache {
             .stream_all_codes_cursor(BLOOM_REBUILD_BATCH_SIZE);
         while let Some(batch) = code_stream.next().await {
             let batch = batch?;
-            rebuild.insert_many(batch.iter().map(String::as_str));
+            rebuild.insert_many(
+                batch
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|code| self.owns(code)),
+            );
         }
         let loaded = rebuild.commit();
         tracing::debug!(loaded, "Bloom filter rebuild completed");
+        self.removed.retain(|_, removed_in| *removed_in > epoch);
+        self.bloom_counters.reset(count as u64, loaded as u64);
+        self.metrics.set_bloom_false_positive_rate(0.0);
+
+        let archived: std::collections::HashSet<String> = self
+            .storage
+            .load_archived_codes()
+            .await?
+            .into_iter()
+            .collect();
+        self.archived.retain(|code| archived.contains(code));
+        for code in archived {
+            self.archived.insert(code);
+        }
 
         self.invalidate_all().await;
         Ok(())
@@ -240,7 +635,48 @@ impl LinkCache for ForgeLinkCache {
     }
 
     async fn bloom_check(&self, key: &str) -> bool {
-        self.bloom.contains(key)
+        self.may_exist(key)
+    }
+
+    fn fill_token(&self, key: &str) -> FillToken {
+        self.versions.current(key)
+    }
+
+    async fn fill(&self, key: &str, value: ShortLink, ttl_secs: Option<u64>, token: FillToken) {
+        if self.stale_fill(key, token, "link") {
+            return;
+        }
+        let start = Instant::now();
+        if self.owns(key) {
+            self.bloom.insert(key);
+        }
+        self.removed.remove(key);
+        self.store_object(key, value, ttl_secs).await;
+        // 校验与写入之间可能插入了写路径：再校验一次，撤回本次回填（最多多一次 miss）
+        if self.stale_fill(key, token, "link") {
+            self.drop_object(key).await;
+        }
+        self.metrics
+            .observe_cache_operation("fill", "object_cache", start.elapsed().as_secs_f64());
+    }
+
+    async fn fill_not_found(&self, key: &str, token: FillToken) {
+        if self.stale_fill(key, token, "not_found") {
+            return;
+        }
+        let negative_key = Self::negative_key(key);
+        self.negatives
+            .set_bytes(&negative_key, Vec::new(), Some(NEGATIVE_CACHE_TTL_SECS))
+            .await;
+        if self.stale_fill(key, token, "not_found") {
+            self.negatives.delete(&negative_key).await;
+        }
+    }
+
+    async fn invalidate(&self, key: &str) -> FillToken {
+        let token = self.versions.bump(key);
+        self.drop_object(key).await;
+        token
     }
 
     async fn health_check(&self) -> LinkCacheHealth {
@@ -262,6 +698,107 @@ impl LinkCache for ForgeLinkCache {
             },
         }
     }
+    async fn insert_batch(&self, entries: Vec<(ShortLink, Option<u64>)>) {
+        if entries.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+        {
+            let _rebuild = self.rebuild_lock.lock().await;
+            for (link, _) in &entries {
+                if self.owns(&link.code) {
+                    self.bloom.insert(&link.code);
+                }
+                self.removed.remove(&link.code);
+            }
+        }
+        for (link, ttl_secs) in entries {
+            let code = link.code.clone();
+            self.versions.bump(&code);
+            self.store_object(&code, link, ttl_secs).await;
+        }
+        self.metrics.observe_cache_operation(
+            "insert_batch",
+            "object_cache",
+            start.elapsed().as_secs_f64(),
+        );
+    }
+
+    async fn insert_codes(&self, codes: &[String]) {
+        if codes.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+        let _rebuild = self.rebuild_lock.lock().await;
+        for code in codes.iter().filter(|code| self.owns(code)) {
+            self.bloom.insert(code);
+            self.removed.remove(code);
+        }
+        self.metrics.observe_cache_operation(
+            "insert_codes",
+            "bloom_filter",
+            start.elapsed().as_secs_f64(),
+        );
+    }
+
+    async fn invalidate_codes(&self, codes: &[String]) {
+        if codes.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+        for code in codes {
+            self.versions.bump(code);
+            self.drop_object(code).await;
+            self.negatives.delete(&Self::negative_key(code)).await;
+        }
+        self.metrics.observe_cache_operation(
+            "invalidate_codes",
+            "object_cache",
+            start.elapsed().as_secs_f64(),
+        );
+    }
+
+    fn begin_bulk_write(&self) {
+        self.bulk_writers.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn end_bulk_write(&self) {
+        self.bulk_writers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn bulk_write_in_progress(&self) -> bool {
+        self.bulk_writers.load(Ordering::SeqCst) > 0
+    }
+
+    async fn mark_archived(&self, codes: &[String]) {
+        for code in codes {
+            self.archived.insert(code.clone());
+            self.remove(code).await;
+        }
+    }
+
+    async fn unmark_archived(&self, key: &str) {
+        self.archived.remove(key);
+    }
+
+    fn record_origin_lookup(&self, key: &str, found: bool) {
+        // 非本分片的短码未经 Bloom 判定
+        if !self.owns(key) {
+            return;
+        }
+        let outcome = if found {
+            BloomOutcome::Hit
+        } else if self.removed.contains_key(key) || self.archived.contains(key) {
+            BloomOutcome::Stale
+        } else {
+            BloomOutcome::FalsePositive
+        };
+        self.record_bloom(outcome);
+    }
+
+    fn bloom_stats(&self) -> Option<BloomStats> {
+        Some(self.bloom_counters.snapshot())
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +811,7 @@ mod tests {
 
     use super::*;
     use crate::metrics::NoopMetrics;
+    use crate::storage::{AnalyticsLevel, CreatedVia};
 
     static INIT: Once = Once::new();
 
@@ -285,6 +823,9 @@ mod tests {
             expires_at: None,
             password: None,
             click: 0,
+            created_via: CreatedVia::Unknown,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         }
     }
 
@@ -313,16 +854,50 @@ mod tests {
         (
             ForgeLinkCache {
                 bloom,
+                l1: None,
                 objects,
+                l2_enabled: true,
+                oversize_policy: OversizePolicy::L2,
                 negatives,
                 object_prefix: object_prefix.to_string(),
                 metrics: NoopMetrics::arc(),
                 storage,
+                rebuild_lock: tokio::sync::Mutex::new(()),
+                bulk_writers: AtomicUsize::new(0),
+                archived: DashSet::new(),
+                shard: None,
+                backfill_foreign: false,
+                versions: WriteVersions::new(),
+                removed: DashMap::new(),
+                removal_epoch: AtomicU64::new(0),
+                bloom_counters: BloomCounters::new(100, BLOOM_FALSE_POSITIVE_RATE),
             },
             temp_dir,
         )
     }
 
+    async fn test_cache_with_l1(
+        max_entry_bytes: u64,
+        oversize_policy: OversizePolicy,
+    ) -> (ForgeLinkCache, TempDir) {
+        let (mut cache, temp_dir) = test_cache("links:").await;
+        cache.l1 = Some(L1LinkCache::new(L1CacheLimits {
+            max_bytes: 64 * 1024,
+            max_entries: 0,
+            max_entry_bytes,
+            default_ttl: 60,
+        }));
+        cache.oversize_policy = oversize_policy;
+        (cache, temp_dir)
+    }
+
+    fn long_link(code: &str) -> ShortLink {
+        ShortLink {
+            target: format!("https://sso.example.com/?state={}", "A".repeat(4096)),
+            ..test_link(code)
+        }
+    }
+
     #[tokio::test]
     async fn bloom_negative_short_circuits_lookup() {
         let (cache, _temp_dir) = test_cache("links:").await;
@@ -518,6 +1093,118 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn archived_code_is_reported_gone_until_restored() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache
+            .insert("retired", test_link("retired"), Some(60))
+            .await;
+
+        cache.mark_archived(&["retired".to_string()]).await;
+        assert!(matches!(cache.get("retired").await, LinkCacheLookup::Gone));
+        assert!(matches!(
+            cache.get("unknown").await,
+            LinkCacheLookup::NotFound
+        ));
+
+        cache.unmark_archived("retired").await;
+        cache
+            .insert("retired", test_link("retired"), Some(60))
+            .await;
+        assert!(matches!(
+            cache.get("retired").await,
+            LinkCacheLookup::Found(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rebuild_loads_archived_codes_from_storage() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache
+            .storage
+            .set(test_link("to-archive"))
+            .await
+            .expect("database link should be stored");
+        cache
+            .storage
+            .archive_batch(&["to-archive".to_string()])
+            .await
+            .expect("archive should succeed");
+        cache.archived.insert("stale-archived".to_string());
+
+        cache
+            .rebuild_all()
+            .await
+            .expect("Bloom rebuild should succeed");
+
+        assert!(!cache.bloom_check("to-archive").await);
+        assert!(matches!(
+            cache.get("to-archive").await,
+            LinkCacheLookup::Gone
+        ));
+        assert!(matches!(
+            cache.get("stale-archived").await,
+            LinkCacheLookup::NotFound
+        ));
+    }
+
+    #[tokio::test]
+    async fn l1_hit_is_served_without_the_object_backend() {
+        let (cache, _temp_dir) = test_cache_with_l1(1024, OversizePolicy::L2).await;
+        cache.insert("small", test_link("small"), Some(60)).await;
+        cache.objects.delete(&cache.object_key("small")).await;
+
+        assert!(matches!(
+            cache.get("small").await,
+            LinkCacheLookup::Found(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn oversize_entry_skips_l1_and_falls_back_to_l2() {
+        let (cache, _temp_dir) = test_cache_with_l1(1024, OversizePolicy::L2).await;
+
+        cache.insert("long", long_link("long"), Some(60)).await;
+
+        assert!(cache.l1.as_ref().unwrap().get("long").await.is_none());
+        assert!(
+            cache
+                .objects
+                .get_bytes(&cache.object_key("long"))
+                .await
+                .is_some()
+        );
+        assert!(matches!(cache.get("long").await, LinkCacheLookup::Found(_)));
+    }
+
+    #[tokio::test]
+    async fn oversize_entry_with_skip_policy_is_not_cached() {
+        let (cache, _temp_dir) = test_cache_with_l1(1024, OversizePolicy::Skip).await;
+        cache.insert("long", test_link("long"), Some(60)).await;
+
+        cache.insert("long", long_link("long"), Some(60)).await;
+
+        assert!(cache.bloom_check("long").await);
+        assert!(matches!(cache.get("long").await, LinkCacheLookup::Miss));
+    }
+
+    #[test]
+    fn l2_oversize_policy_degrades_to_skip_without_l2() {
+        assert_eq!(OversizePolicy::L2.effective(true), OversizePolicy::L2);
+        assert_eq!(OversizePolicy::L2.effective(false), OversizePolicy::Skip);
+        assert_eq!(OversizePolicy::Skip.effective(true), OversizePolicy::Skip);
+    }
+
+    #[tokio::test]
+    async fn invalidate_all_clears_l1() {
+        let (cache, _temp_dir) = test_cache_with_l1(1024, OversizePolicy::L2).await;
+        cache.insert("owned", test_link("owned"), Some(60)).await;
+
+        cache.invalidate_all().await;
+
+        assert!(matches!(cache.get("owned").await, LinkCacheLookup::Miss));
+    }
+
     #[tokio::test]
     async fn memory_backend_health_reports_active_capabilities() {
         let (cache, _temp_dir) = test_cache("links:").await;
@@ -530,4 +1217,359 @@ mod tests {
         assert!(health.negative_cache_enabled);
         assert!(health.error.is_none());
     }
+
+    #[tokio::test]
+    async fn insert_batch_populates_bloom_and_objects() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache.mark_not_found("batch-b").await;
+
+        cache
+            .insert_batch(vec![
+                (test_link("batch-a"), Some(60)),
+                (test_link("batch-b"), Some(60)),
+            ])
+            .await;
+
+        for code in ["batch-a", "batch-b"] {
+            assert!(cache.bloom_check(code).await);
+            assert!(matches!(
+                cache.get(code).await,
+                LinkCacheLookup::Found(link) if link.code == code
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn insert_codes_registers_bloom_membership_only() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+
+        cache
+            .insert_codes(&["imported-1".to_string(), "imported-2".to_string()])
+            .await;
+
+        assert!(cache.bloom_check("imported-1").await);
+        assert!(cache.bloom_check("imported-2").await);
+        assert!(matches!(
+            cache.get("imported-1").await,
+            LinkCacheLookup::Miss
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalidate_codes_drops_objects_and_negative_markers_only_for_given_codes() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache.insert("stale", test_link("stale"), Some(60)).await;
+        cache
+            .insert("untouched", test_link("untouched"), Some(60))
+            .await;
+        cache.bloom.insert("was-missing");
+        cache.mark_not_found("was-missing").await;
+
+        cache
+            .invalidate_codes(&["stale".to_string(), "was-missing".to_string()])
+            .await;
+
+        assert!(matches!(cache.get("stale").await, LinkCacheLookup::Miss));
+        assert!(matches!(
+            cache.get("was-missing").await,
+            LinkCacheLookup::Miss
+        ));
+        assert!(matches!(
+            cache.get("untouched").await,
+            LinkCacheLookup::Found(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn bulk_write_flag_tracks_nested_writers() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        assert!(!cache.bulk_write_in_progress());
+
+        cache.begin_bulk_write();
+        cache.begin_bulk_write();
+        cache.end_bulk_write();
+        assert!(cache.bulk_write_in_progress());
+
+        cache.end_bulk_write();
+        assert!(!cache.bulk_write_in_progress());
+    }
+
+    #[tokio::test]
+    async fn sharded_cache_only_tracks_owned_codes() {
+        let (mut cache, _temp_dir) = test_cache_with_l1(0, OversizePolicy::L2).await;
+        let shard = CodeShard::new(0, 2).unwrap();
+        cache.shard = Some(shard);
+        let code = |owned: bool| {
+            (0..)
+                .map(|i| format!("shard-{i}"))
+                .find(|code| shard.owns(code) == owned)
+                .unwrap()
+        };
+        let (owned, foreign) = (code(true), code(false));
+
+        // 空 Bloom：本分片短码直接判定不存在，非本分片短码必须回源
+        assert!(matches!(cache.get(&owned).await, LinkCacheLookup::NotFound));
+        assert!(matches!(cache.get(&foreign).await, LinkCacheLookup::Miss));
+        assert!(cache.bloom_check(&foreign).await);
+
+        cache.insert(&owned, test_link(&owned), Some(60)).await;
+        cache.insert(&foreign, test_link(&foreign), Some(60)).await;
+
+        let l1 = cache.l1.as_ref().unwrap();
+        assert!(cache.bloom.contains(&owned) && l1.get(&owned).await.is_some());
+        assert!(!cache.bloom.contains(&foreign) && l1.get(&foreign).await.is_none());
+        // 非本分片仍可由 L2 命中
+        assert!(matches!(
+            cache.get(&foreign).await,
+            LinkCacheLookup::Found(link) if link.code == foreign
+        ));
+
+        cache.backfill_foreign = true;
+        cache.insert(&foreign, test_link(&foreign), Some(60)).await;
+        assert!(cache.l1.as_ref().unwrap().get(&foreign).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn deleted_code_is_counted_stale_not_false_positive() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache.insert("live", test_link("live"), Some(60)).await;
+        cache
+            .insert("deleted", test_link("deleted"), Some(60))
+            .await;
+        cache.remove("deleted").await;
+        // 从未写入过、仅因哈希碰撞通过 Bloom 的短码
+        cache.bloom.insert("phantom");
+
+        assert!(matches!(
+            cache.get("absent").await,
+            LinkCacheLookup::NotFound
+        ));
+        cache.record_origin_lookup("live", true);
+        cache.record_origin_lookup("deleted", false);
+        cache.record_origin_lookup("phantom", false);
+
+        let stats = cache.bloom_stats().unwrap();
+        assert_eq!(
+            (
+                stats.rejected,
+                stats.hits,
+                stats.stale,
+                stats.false_positives
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(stats.observed_false_positive_rate(), Some(0.5));
+
+        // 重新创建后不再视为已删除
+        cache
+            .insert("deleted", test_link("deleted"), Some(60))
+            .await;
+        cache.record_origin_lookup("deleted", false);
+        assert_eq!(cache.bloom_stats().unwrap().false_positives, 2);
+    }
+
+    #[tokio::test]
+    async fn archived_code_is_counted_stale() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache.bloom.insert("retired");
+        cache.mark_archived(&["retired".to_string()]).await;
+        cache.record_origin_lookup("retired", false);
+
+        let stats = cache.bloom_stats().unwrap();
+        assert_eq!((stats.stale, stats.false_positives), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn rebuild_resets_bloom_stats_and_keeps_later_removals() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        cache.remove("before-rebuild").await;
+        // 模拟重建开始后才删除的短码：它可能已被读入新 Bloom
+        cache.removed.insert("during-rebuild".to_string(), 1);
+        cache.record_origin_lookup("before-rebuild", false);
+        let before = cache.bloom_stats().unwrap();
+
+        cache
+            .rebuild_all()
+            .await
+            .expect("Bloom rebuild should succeed");
+
+        let after = cache.bloom_stats().unwrap();
+        assert_eq!(after.generation, before.generation + 1);
+        assert_eq!(after.stale, 0);
+        assert!(!cache.removed.contains_key("before-rebuild"));
+        assert!(cache.removed.contains_key("during-rebuild"));
+    }
+
+    #[tokio::test]
+    async fn foreign_shard_lookups_are_not_classified() {
+        let (mut cache, _temp_dir) = test_cache("links:").await;
+        let shard = CodeShard::new(0, 2).unwrap();
+        cache.shard = Some(shard);
+        let foreign = (0..)
+            .map(|i| format!("shard-{i}"))
+            .find(|code| !shard.owns(code))
+            .unwrap();
+
+        assert!(matches!(cache.get(&foreign).await, LinkCacheLookup::Miss));
+        cache.record_origin_lookup(&foreign, false);
+
+        let stats = cache.bloom_stats().unwrap();
+        assert_eq!(stats.samples() + stats.hits + stats.stale, 0);
+    }
+
+    #[tokio::test]
+    async fn backfill_after_a_write_is_discarded() {
+        let (cache, _temp_dir) = test_cache_with_l1(0, OversizePolicy::L2).await;
+        let old = test_link("raced");
+        let new = ShortLink {
+            target: "https://new.example.com".to_string(),
+            ..old.clone()
+        };
+
+        // 读路径取得版本后读到旧行，写路径在回填前完成了更新
+        let token = cache.fill_token("raced");
+        cache.insert("raced", new.clone(), Some(60)).await;
+        cache.fill("raced", old.clone(), Some(60), token).await;
+        assert!(matches!(
+            cache.get("raced").await,
+            LinkCacheLookup::Found(link) if link.target == new.target
+        ));
+
+        // 回源时不存在、回填前被创建：负缓存同样丢弃
+        let token = cache.fill_token("created");
+        cache
+            .insert("created", test_link("created"), Some(60))
+            .await;
+        cache.fill_not_found("created", token).await;
+        assert!(matches!(
+            cache.get("created").await,
+            LinkCacheLookup::Found(_)
+        ));
+
+        // invalidate 之后取得的版本仍然有效
+        let token = cache.invalidate("raced").await;
+        assert!(matches!(cache.get("raced").await, LinkCacheLookup::Miss));
+        cache.fill("raced", old.clone(), Some(60), token).await;
+        assert!(matches!(
+            cache.get("raced").await,
+            LinkCacheLookup::Found(link) if link.target == old.target
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalidate_all_discards_pending_backfills() {
+        let (cache, _temp_dir) = test_cache("links:").await;
+        let token = cache.fill_token("pending");
+
+        cache.invalidate_all().await;
+        cache
+            .fill("pending", test_link("pending"), Some(60), token)
+            .await;
+
+        assert!(
+            cache
+                .objects
+                .get_bytes(&cache.object_key("pending"))
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_updates_never_leave_a_stale_target() {
+        use std::sync::Mutex;
+        use std::sync::atomic::AtomicBool;
+        use std::time::Duration;
+
+        use crate::services::SideEffectRunner;
+        use crate::storage::SideEffect;
+
+        const CODE: &str = "contended";
+        const ROUNDS: usize = 3;
+        const UPDATES_PER_ROUND: usize = 20;
+        const READERS: usize = 8;
+        const STALE_AFTER: Duration = Duration::from_secs(1);
+
+        fn version_link(version: usize) -> ShortLink {
+            ShortLink {
+                target: format!("https://example.com/v{version}"),
+                ..test_link(CODE)
+            }
+        }
+
+        fn version_of(target: &str) -> usize {
+            target.rsplit('v').next().unwrap().parse().unwrap()
+        }
+
+        let (cache, _temp_dir) = test_cache_with_l1(0, OversizePolicy::L2).await;
+        let cache = Arc::new(cache);
+        let storage = cache.storage.clone();
+        let runner = SideEffectRunner::new(storage.clone(), cache.clone());
+        storage.set(version_link(0)).await.unwrap();
+
+        // done[i]：版本 i 的写入（含缓存刷新）完成时刻
+        let done = Arc::new(Mutex::new(vec![Instant::now()]));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..READERS)
+            .map(|reader| {
+                let (cache, done, stop) = (cache.clone(), done.clone(), stop.clone());
+                tokio::spawn(async move {
+                    let mut stale = Vec::new();
+                    let mut n = reader as u64;
+                    while !stop.load(Ordering::SeqCst) {
+                        let started = Instant::now();
+                        // 与 redirect 相同的读路径；回源与回填之间插入延迟以放大竞态窗口
+                        let target = match cache.get(CODE).await {
+                            LinkCacheLookup::Found(link) => link.target,
+                            _ => {
+                                let token = cache.fill_token(CODE);
+                                let link = cache.storage.get(CODE).await.unwrap().unwrap();
+                                n += 1;
+                                tokio::time::sleep(Duration::from_micros((n % 4) * 500)).await;
+                                let target = link.target.clone();
+                                cache.fill(CODE, link, Some(60), token).await;
+                                target
+                            }
+                        };
+                        let version = version_of(&target);
+                        let superseded_at = done.lock().unwrap().get(version + 1).copied();
+                        if let Some(at) = superseded_at
+                            && started.saturating_duration_since(at) >= STALE_AFTER
+                        {
+                            stale.push(version);
+                        }
+                        tokio::task::yield_now().await;
+                    }
+                    stale
+                })
+            })
+            .collect();
+
+        let mut version = 0;
+        for _ in 0..ROUNDS {
+            for _ in 0..UPDATES_PER_ROUND {
+                version += 1;
+                storage.set(version_link(version)).await.unwrap();
+                runner
+                    .execute(&SideEffect::cache_refresh(CODE))
+                    .await
+                    .unwrap();
+                done.lock().unwrap().push(Instant::now());
+            }
+            // 更新静默期间读者继续读取：旧值必须在 STALE_AFTER 内消失
+            tokio::time::sleep(STALE_AFTER + Duration::from_millis(200)).await;
+        }
+        stop.store(true, Ordering::SeqCst);
+
+        for reader in readers {
+            let stale = reader.await.unwrap();
+            assert!(stale.is_empty(), "stale versions served: {stale:?}");
+        }
+        match cache.get(CODE).await {
+            LinkCacheLookup::Found(link) => assert_eq!(version_of(&link.target), version),
+            LinkCacheLookup::Miss => {}
+            other => panic!("unexpected lookup result {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,284 @@
+//! In-process L1 object cache with byte-weighted eviction.
+//!
+//! 按条数计数的内存缓存无法约束超长 target 的实际内存占用，这里按
+//! [`ShortLink::estimated_size`] 为每个条目计重，并同时支持字节上限与条数上限
+//! （取先到者）。
+
+use std::time::{Duration, Instant};
+
+use moka::Expiry;
+use moka::future::Cache;
+
+use crate::storage::ShortLink;
+
+/// 两项上限均未配置时的默认条目上限，沿用旧版 `cache.memory.max_capacity` 的取值
+pub const DEFAULT_L1_MAX_ENTRIES: u64 = 50_000;
+
+/// Limits applied to the L1 object cache. A zero value disables that limit.
+///
+/// When both `max_bytes` and `max_entries` are zero the cache is still bounded by
+/// [`DEFAULT_L1_MAX_ENTRIES`].
+#[derive(Debug, Clone, Copy)]
+pub struct L1CacheLimits {
+    pub max_bytes: u64,
+    pub max_entries: u64,
+    pub max_entry_bytes: u64,
+    pub default_ttl: u64,
+}
+
+impl L1CacheLimits {
+    pub fn from_config(config: &crate::config::CacheConfig) -> Self {
+        Self {
+            max_bytes: config.l1_max_bytes,
+            max_entries: config.l1_max_entries,
+            max_entry_bytes: config.max_entry_bytes,
+            default_ttl: config.default_ttl,
+        }
+    }
+
+    /// Whether an entry of `weight` bytes exceeds the per-entry threshold.
+    pub fn is_oversize(&self, weight: u64) -> bool {
+        (self.max_entry_bytes > 0 && weight > self.max_entry_bytes)
+            || (self.max_bytes > 0 && weight > self.max_bytes)
+    }
+
+    /// Weighted capacity and the minimum weight charged per entry.
+    ///
+    /// 同时配置字节与条数上限时，每个条目至少按 `max_bytes / max_entries` 计重，
+    /// 这样总权重不超过 `max_bytes` 即同时保证条数不超过 `max_entries`。
+    fn capacity(&self) -> (u64, u64) {
+        match (self.max_bytes, self.max_entries) {
+            (0, 0) => (DEFAULT_L1_MAX_ENTRIES, 0),
+            (0, entries) => (entries, 0),
+            (bytes, 0) => (bytes, 1),
+            (bytes, entries) => (bytes, (bytes / entries).max(1)),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct L1Entry {
+    link: ShortLink,
+    weight: u32,
+    ttl: Duration,
+}
+
+struct L1Expiry;
+
+impl Expiry<String, L1Entry> for L1Expiry {
+    fn expire_after_create(&self, _key: &String, value: &L1Entry, _: Instant) -> Option<Duration> {
+        Some(value.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &L1Entry,
+        _: Instant,
+        _: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.ttl)
+    }
+}
+
+/// Outcome of an L1 insert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1Insert {
+    Stored,
+    /// The entry exceeds `max_entry_bytes` and was not admitted.
+    Oversize,
+}
+
+/// Byte-weighted in-process cache for decoded short links.
+pub struct L1LinkCache {
+    entries: Cache<String, L1Entry>,
+    limits: L1CacheLimits,
+}
+
+impl L1LinkCache {
+    pub fn new(limits: L1CacheLimits) -> Self {
+        let (capacity, min_weight) = limits.capacity();
+        // 仅条数上限时 min_weight 为 0，每个条目计重 1
+        let min_weight = u32::try_from(min_weight).unwrap_or(u32::MAX);
+        let entries = Cache::builder()
+            .expire_after(L1Expiry)
+            .max_capacity(capacity)
+            .weigher(move |_key: &String, entry: &L1Entry| {
+                if min_weight == 0 {
+                    1
+                } else {
+                    entry.weight.max(min_weight)
+                }
+            })
+            .build();
+
+        Self { entries, limits }
+    }
+
+    /// Weight charged for caching `link` under `key`.
+    pub fn weigh(key: &str, link: &ShortLink) -> u64 {
+        (key.len() + link.estimated_size()) as u64
+    }
+
+    pub async fn get(&self, key: &str) -> Option<ShortLink> {
+        self.entries.get(key).await.map(|entry| entry.link)
+    }
+
+    pub async fn insert(&self, key: &str, link: ShortLink, ttl_secs: Option<u64>) -> L1Insert {
+        let weight = Self::weigh(key, &link);
+        if self.limits.is_oversize(weight) {
+            // 旧值可能仍在 L1 中，必须移除以免读到过期数据
+            self.entries.invalidate(key).await;
+            return L1Insert::Oversize;
+        }
+
+        let ttl = Duration::from_secs(ttl_secs.unwrap_or(self.limits.default_ttl));
+        if ttl.is_zero() {
+            self.entries.invalidate(key).await;
+            return L1Insert::Stored;
+        }
+
+        self.entries
+            .insert(
+                key.to_string(),
+                L1Entry {
+                    link,
+                    weight: u32::try_from(weight).unwrap_or(u32::MAX),
+                    ttl,
+                },
+            )
+            .await;
+        L1Insert::Stored
+    }
+
+    pub async fn remove(&self, key: &str) {
+        self.entries.invalidate(key).await;
+    }
+
+    pub fn clear(&self) {
+        self.entries.invalidate_all();
+    }
+
+    /// Approximate entry count; pending evictions may not be reflected yet.
+    pub fn entry_count(&self) -> u64 {
+        self.entries.entry_count()
+    }
+
+    /// Applies pending evictions and returns `(entry_count, weighted_size)`.
+    #[cfg(test)]
+    pub async fn usage(&self) -> (u64, u64) {
+        self.entries.run_pending_tasks().await;
+        (self.entries.entry_count(), self.entries.weighted_size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::storage::{AnalyticsLevel, CreatedVia};
+
+    fn link(code: &str, target_len: usize) -> ShortLink {
+        ShortLink {
+            code: code.to_string(),
+            target: format!("https://sso.example.com/?s={}", "x".repeat(target_len)),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 0,
+            created_via: CreatedVia::Unknown,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        }
+    }
+
+    fn limits(max_bytes: u64, max_entries: u64, max_entry_bytes: u64) -> L1CacheLimits {
+        L1CacheLimits {
+            max_bytes,
+            max_entries,
+            max_entry_bytes,
+            default_ttl: 60,
+        }
+    }
+
+    #[tokio::test]
+    async fn mixed_size_load_respects_byte_limit() {
+        let max_bytes = 64 * 1024;
+        let cache = L1LinkCache::new(limits(max_bytes, 0, 8 * 1024));
+
+        let mut oversize = 0;
+        for i in 0..2_000 {
+            // 大部分为短链接，夹杂 2KB / 6KB / 12KB 的长 target
+            let target_len = match i % 10 {
+                0 => 12 * 1024,
+                1 | 2 => 6 * 1024,
+                3 => 2 * 1024,
+                _ => 32,
+            };
+            let code = format!("code{i}");
+            if cache.insert(&code, link(&code, target_len), None).await == L1Insert::Oversize {
+                oversize += 1;
+            }
+        }
+
+        let (entries, weighted) = cache.usage().await;
+        assert_eq!(oversize, 200);
+        assert!(entries > 0);
+        assert!(
+            weighted <= max_bytes,
+            "weighted size {weighted} exceeds limit {max_bytes}"
+        );
+    }
+
+    #[tokio::test]
+    async fn entry_limit_applies_when_reached_before_byte_limit() {
+        let cache = L1LinkCache::new(limits(1024 * 1024, 10, 0));
+
+        for i in 0..100 {
+            let code = format!("code{i}");
+            cache.insert(&code, link(&code, 16), None).await;
+        }
+
+        let (entries, weighted) = cache.usage().await;
+        assert!(entries <= 10, "entry count {entries} exceeds limit");
+        assert!(weighted <= 1024 * 1024);
+    }
+
+    #[tokio::test]
+    async fn unconfigured_limits_fall_back_to_default_entry_bound() {
+        let cache = L1LinkCache::new(limits(0, 0, 0));
+
+        for i in 0..DEFAULT_L1_MAX_ENTRIES + 500 {
+            let code = format!("code{i}");
+            cache.insert(&code, link(&code, 16), None).await;
+        }
+
+        let (entries, _) = cache.usage().await;
+        assert!(
+            entries <= DEFAULT_L1_MAX_ENTRIES,
+            "entry count {entries} exceeds default bound"
+        );
+    }
+
+    #[tokio::test]
+    async fn oversize_insert_evicts_the_previous_value() {
+        let cache = L1LinkCache::new(limits(0, 0, 1024));
+        cache.insert("grow", link("grow", 16), None).await;
+        assert!(cache.get("grow").await.is_some());
+
+        let result = cache.insert("grow", link("grow", 4096), None).await;
+
+        assert_eq!(result, L1Insert::Oversize);
+        assert!(cache.get("grow").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_is_not_stored() {
+        let cache = L1LinkCache::new(limits(0, 0, 0));
+
+        cache.insert("expired", link("expired", 16), Some(0)).await;
+
+        assert!(cache.get("expired").await.is_none());
+    }
+}
@@ -10,15 +10,27 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use futures_util::Stream;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::config::{get_config, keys, try_get_runtime_config};
 use crate::errors::ShortlinkerError;
-use crate::services::LinkCache;
-use crate::storage::{LinkFilter, SeaOrmStorage, ShortLink};
+use crate::services::link_template::{
+    DEFAULT_TEMPLATE_MAX_COMBINATIONS, LinkTemplate, TemplateLink,
+};
+use crate::services::link_validation::{
+    ExpiryValue, FieldError, LinkField, LinkInput, ValidationProfile, parse_expiry_value,
+    validate_expires_at, validate_extras, validate_new_link, validate_target,
+};
+use crate::services::target_credentials::credential_rules;
+use crate::services::{LinkCache, SideEffectRunner};
+use crate::storage::{
+    AnalyticsLevel, ArchivedLink, CreatedVia, CreationStats, CreationTrendPoint, LinkFilter,
+    SeaOrmStorage, ShortLink, SideEffect,
+};
 use crate::utils::TimeParser;
 use crate::utils::generate_random_code;
 use crate::utils::password::process_new_password;
+use crate::utils::sampling::ReservoirSampler;
 
 // ============ Request/Response DTOs ============
 
@@ -35,6 +47,14 @@ pub struct CreateLinkRequest {
     pub expires_at: Option<String>,
     /// Password protection (plaintext or already hashed)
     pub password: Option<String>,
+    /// Creation channel recorded on new links (kept unchanged on overwrite)
+    pub created_via: CreatedVia,
+    /// Per-link analytics level (`Inherit` = follow the global analytics config)
+    pub analytics_level: AnalyticsLevel,
+    /// Custom metadata as a JSON object (validated and normalized on write)
+    pub extras: Option<String>,
+    /// Reuse a code that is still in its deletion cooldown (admin only)
+    pub override_cooldown: bool,
 }
 
 /// Request to update an existing link
@@ -42,10 +62,14 @@ pub struct CreateLinkRequest {
 pub struct UpdateLinkRequest {
     /// New target URL
     pub target: String,
-    /// New expiration time (None = keep existing)
+    /// New expiration time (None = keep existing, Some("") / Some("never") = remove)
     pub expires_at: Option<String>,
     /// New password (None = keep existing, Some("") = remove)
     pub password: Option<String>,
+    /// New analytics level (None = keep existing)
+    pub analytics_level: Option<AnalyticsLevel>,
+    /// New custom metadata (None = keep existing, Some("") or Some("{}") = clear)
+    pub extras: Option<String>,
 }
 
 /// Result of link creation
@@ -55,6 +79,17 @@ pub struct LinkCreateResult {
     pub link: ShortLink,
     /// Whether the code was auto-generated
     pub generated_code: bool,
+    /// Non-fatal notices, e.g. credentials detected in the target URL
+    pub warnings: Vec<String>,
+}
+
+/// Result of link update
+#[derive(Debug, Clone)]
+pub struct LinkUpdateResult {
+    /// The updated link
+    pub link: ShortLink,
+    /// Non-fatal notices, e.g. credentials detected in the target URL
+    pub warnings: Vec<String>,
 }
 
 /// Import conflict resolution mode
@@ -111,6 +146,9 @@ pub struct ImportLinkItemRich {
     pub expires_at: Option<DateTime<Utc>>,
     pub password: Option<String>,
     pub click_count: usize,
+    pub analytics_level: AnalyticsLevel,
+    /// 已校验、规范化的 extras
+    pub extras: Option<String>,
     /// 来源行号（仅 CSV 导入路径设置），用于错误报告
     pub row_num: Option<usize>,
 }
@@ -121,6 +159,8 @@ pub struct ImportBatchResult {
     pub success_count: usize,
     pub skipped_count: usize,
     pub failed_items: Vec<ImportBatchFailedItem>,
+    /// 新建的短码（不含覆盖已有短码），供配额登记使用
+    pub created_codes: Vec<String>,
 }
 
 /// 批量导入失败项
@@ -163,6 +203,204 @@ pub struct BatchDeleteResult {
     pub errors: Vec<BatchFailedItem>,
 }
 
+// ============ Template Generate DTOs ============
+
+/// 模板批量生成的写入选项
+///
+/// 冲突策略与批量创建一致：`force` 时覆盖，否则已存在的短码记为失败。
+#[derive(Debug, Clone)]
+pub struct GenerateLinksOptions {
+    pub force: bool,
+    pub expires_at: Option<String>,
+    pub password: Option<String>,
+    pub created_via: CreatedVia,
+    pub analytics_level: AnalyticsLevel,
+    /// 只展开预览，不写入
+    pub dry_run: bool,
+}
+
+/// 模板批量生成结果
+#[derive(Debug, Clone, Default)]
+pub struct GenerateLinksResult {
+    /// 展开得到的全部链接（无短码模板时短码为空）
+    pub planned: Vec<TemplateLink>,
+    /// 预览时已存在的短码（非 force 写入时会失败）
+    pub existing: Vec<String>,
+    /// 实际创建的链接
+    pub created: Vec<TemplateLink>,
+    pub failed: Vec<BatchFailedItem>,
+    pub dry_run: bool,
+}
+
+// ============ Batch Extend DTOs ============
+
+/// 批量顺延的单次最大匹配条数（与 Admin API 批量上限一致）
+pub const MAX_EXTEND_BATCH_SIZE: usize = 5000;
+
+/// 批量操作（顺延、归档）的目标链接选择方式
+#[derive(Debug, Clone)]
+pub enum LinkSelection {
+    /// 显式指定短码列表
+    Codes(Vec<String>),
+    /// 按过滤条件匹配
+    Filter(LinkFilter),
+}
+
+/// 过期时间的调整方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtendAction {
+    /// 在原 expires_at 基础上增加偏移
+    By(chrono::Duration),
+    /// 直接设置为指定时间
+    SetTo(DateTime<Utc>),
+}
+
+impl ExtendAction {
+    /// 从用户输入解析：`extend_by`（如 "7d"）与 `new_expires_at` 二选一
+    pub fn from_inputs(
+        extend_by: Option<&str>,
+        new_expires_at: Option<&str>,
+    ) -> Result<Self, ShortlinkerError> {
+        match (extend_by, new_expires_at) {
+            (Some(by), None) => TimeParser::parse_duration(by)
+                .map(ExtendAction::By)
+                .map_err(|e| {
+                    ShortlinkerError::link_invalid_expire_time(format!("Invalid extend_by: {}", e))
+                }),
+            (None, Some(at)) => match parse_expiry_value(Some(at), true) {
+                Ok(ExpiryValue::Now(dt) | ExpiryValue::At(dt)) => Ok(ExtendAction::SetTo(dt)),
+                Ok(ExpiryValue::Never) => Err(ShortlinkerError::link_invalid_expire_time(
+                    "new_expires_at must be a time; clear the expiration by updating the link with expires_at 'never'",
+                )),
+                Err(e) => Err(ShortlinkerError::link_invalid_expire_time(format!(
+                    "Invalid new_expires_at: {}",
+                    e
+                ))),
+            },
+            _ => Err(ShortlinkerError::validation(
+                "Exactly one of extend_by or new_expires_at must be provided",
+            )),
+        }
+    }
+}
+
+/// 批量顺延请求
+#[derive(Debug, Clone, Copy)]
+pub struct BatchExtendRequest {
+    pub action: ExtendAction,
+    /// 永不过期的链接是否也处理（`By` 时设置为 now + 偏移），默认跳过
+    pub include_permanent: bool,
+    /// 已过期的链接是否从 now 起算（"复活"），否则从原 expires_at 起算
+    pub revive_from_now: bool,
+    /// 只计算结果，不写入存储
+    pub dry_run: bool,
+}
+
+/// 单条顺延结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchExtendItem {
+    pub code: String,
+    pub old_expires_at: Option<DateTime<Utc>>,
+    pub new_expires_at: DateTime<Utc>,
+}
+
+/// 批量顺延结果
+#[derive(Debug, Clone, Default)]
+pub struct BatchExtendResult {
+    pub updated: Vec<BatchExtendItem>,
+    /// 按规则跳过的链接（如永不过期）
+    pub skipped: Vec<BatchFailedItem>,
+    pub failed: Vec<BatchFailedItem>,
+    pub dry_run: bool,
+}
+
+/// 计算单条链接顺延后的过期时间
+///
+/// 返回 `Err(reason)` 表示该链接应被跳过。
+pub fn compute_extended_expiry(
+    current: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    req: &BatchExtendRequest,
+) -> Result<DateTime<Utc>, String> {
+    if current.is_none() && !req.include_permanent {
+        return Err("Link never expires".to_string());
+    }
+
+    match req.action {
+        ExtendAction::SetTo(target) => Ok(target),
+        ExtendAction::By(offset) => {
+            let base = match current {
+                None => now,
+                Some(expires_at) if expires_at <= now && req.revive_from_now => now,
+                Some(expires_at) => expires_at,
+            };
+            base.checked_add_signed(offset)
+                .ok_or_else(|| "Extended expiration time is out of range".to_string())
+        }
+    }
+}
+
+// ============ Archive DTOs ============
+
+/// 归档时每个事务处理的短码数
+pub const ARCHIVE_BATCH_SIZE: usize = 500;
+
+/// 批量归档结果
+#[derive(Debug, Clone, Default)]
+pub struct BatchArchiveResult {
+    pub archived: Vec<String>,
+    /// 主表中不存在的短码（含已归档的）
+    pub not_found: Vec<String>,
+    /// 所在批次事务失败的短码
+    pub failed: Vec<BatchFailedItem>,
+}
+
+// ============ Sampling DTOs ============
+
+/// 单次抽样的最大样本量
+pub const MAX_SAMPLE_SIZE: usize = 1000;
+
+/// 抽样时每批扫描的行数
+const SAMPLE_SCAN_BATCH_SIZE: u64 = 1000;
+
+/// 随机抽样结果
+#[derive(Debug, Clone)]
+pub struct LinkSample {
+    /// 样本（按短码排序）
+    pub links: Vec<ShortLink>,
+    /// 实际使用的 seed，传回即可复现同一样本
+    pub seed: u64,
+    /// 满足过滤条件的总体大小
+    pub population: u64,
+}
+
+/// 批量写入期间的标记，Drop 时自动结束（含错误返回路径）
+struct BulkWriteGuard<'a> {
+    cache: &'a dyn LinkCache,
+}
+
+impl<'a> BulkWriteGuard<'a> {
+    fn new(cache: &'a dyn LinkCache) -> Self {
+        cache.begin_bulk_write();
+        Self { cache }
+    }
+}
+
+impl Drop for BulkWriteGuard<'_> {
+    fn drop(&mut self) {
+        self.cache.end_bulk_write();
+    }
+}
+
+/// 冷却期拒绝创建时的错误说明（含解禁时间）
+fn cooldown_message(code: &str, release_at: DateTime<Utc>) -> String {
+    format!(
+        "Code '{}' was deleted recently and cannot be reused until {} (links.code_reuse_cooldown_days). Administrators can override the cooldown.",
+        code,
+        release_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    )
+}
+
 // ============ LinkService Implementation ============
 
 /// Service for link management operations
@@ -172,12 +410,18 @@ pub struct BatchDeleteResult {
 pub struct LinkService {
     storage: Arc<SeaOrmStorage>,
     cache: Arc<dyn LinkCache>,
+    side_effects: SideEffectRunner,
 }
 
 impl LinkService {
     /// Create a new LinkService instance
     pub fn new(storage: Arc<SeaOrmStorage>, cache: Arc<dyn LinkCache>) -> Self {
-        Self { storage, cache }
+        let side_effects = SideEffectRunner::new(storage.clone(), cache.clone());
+        Self {
+            storage,
+            cache,
+            side_effects,
+        }
     }
 
     /// Get the configured random code length
@@ -187,6 +431,71 @@ impl LinkService {
             .unwrap_or(6)
     }
 
+    /// 删除冷却期（`links.code_reuse_cooldown_days`），关闭时为 `None`
+    fn code_reuse_cooldown(&self) -> Option<chrono::Duration> {
+        let days = try_get_runtime_config()
+            .and_then(|rt| rt.get_u64(keys::LINKS_CODE_REUSE_COOLDOWN_DAYS))
+            .unwrap_or(0);
+        if days == 0 {
+            return None;
+        }
+        Some(
+            i64::try_from(days)
+                .ok()
+                .and_then(chrono::Duration::try_days)
+                .unwrap_or(chrono::Duration::MAX),
+        )
+    }
+
+    /// 仍在冷却期内的短码及其解禁时间（冷却期关闭时为空）
+    async fn cooling_down_codes(
+        &self,
+        codes: &[&str],
+    ) -> Result<HashMap<String, DateTime<Utc>>, ShortlinkerError> {
+        let Some(cooldown) = self.code_reuse_cooldown() else {
+            return Ok(HashMap::new());
+        };
+        let retired = self.storage.get_retired_codes(codes).await?;
+        let now = Utc::now();
+        Ok(retired
+            .into_iter()
+            .filter_map(|(code, retired_at)| {
+                let release = retired_at
+                    .checked_add_signed(cooldown)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                (release > now).then_some((code, release))
+            })
+            .collect())
+    }
+
+    /// 开启冷却期时登记被删除的短码；登记失败只记录日志，不影响删除结果
+    async fn retire_codes(&self, codes: &[String]) {
+        if codes.is_empty() || self.code_reuse_cooldown().is_none() {
+            return;
+        }
+        if let Err(e) = self.storage.retire_codes(codes, Utc::now()).await {
+            warn!(
+                "LinkService: failed to start reuse cooldown for {} deleted codes: {}",
+                codes.len(),
+                e
+            );
+        }
+    }
+
+    /// 强制复用后清除冷却记录，避免已存在的短码继续被当作冷却中
+    async fn release_codes(&self, codes: &[String]) {
+        if codes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.storage.release_retired_codes(codes).await {
+            warn!(
+                "LinkService: failed to clear reuse cooldown for {} codes: {}",
+                codes.len(),
+                e
+            );
+        }
+    }
+
     /// Get the default cache TTL
     fn default_cache_ttl(&self) -> u64 {
         get_config().cache.default_ttl
@@ -200,25 +509,22 @@ impl LinkService {
         })
     }
 
-    /// Parse expiration time from flexible format
-    fn parse_expires_at(
-        &self,
-        expires_at: Option<&str>,
-    ) -> Result<Option<DateTime<Utc>>, ShortlinkerError> {
-        match expires_at {
-            Some(s) if !s.is_empty() => TimeParser::parse_expire_time(s)
-                .map(Some)
-                .map_err(|e| ShortlinkerError::link_invalid_expire_time(e.to_string())),
-            _ => Ok(None),
-        }
-    }
-
     /// Update cache with a link
     async fn update_cache(&self, link: &ShortLink) {
         let ttl = link.cache_ttl(self.default_cache_ttl());
         self.cache.insert(&link.code, link.clone(), ttl).await;
     }
 
+    /// Update cache with a batch of links (single Bloom pass)
+    async fn update_cache_batch(&self, links: &[ShortLink]) {
+        let default_ttl = self.default_cache_ttl();
+        let entries = links
+            .iter()
+            .map(|link| (link.clone(), link.cache_ttl(default_ttl)))
+            .collect();
+        self.cache.insert_batch(entries).await;
+    }
+
     // ============ CRUD Operations ============
 
     /// Create a new short link
@@ -226,32 +532,29 @@ impl LinkService {
         &self,
         req: CreateLinkRequest,
     ) -> Result<LinkCreateResult, ShortlinkerError> {
-        // Validate URL
-        aster_forge_utils::url::parse_http_url(&req.target, "target URL")
-            .map_err(|error| ShortlinkerError::link_invalid_url(error.to_string()))?;
-
-        // Generate code if not provided, or validate user-provided code
-        let (code, generated) = match req.code.filter(|c| !c.is_empty()) {
-            Some(c) => {
-                // Validate short code format
-                if !crate::utils::is_valid_short_code(&c) {
-                    return Err(ShortlinkerError::link_invalid_code(format!(
-                        "Invalid short code '{}'. Only alphanumeric, underscore, hyphen, dot, and slash allowed.",
-                        c
-                    )));
-                }
-                // Check reserved route conflicts (reads from RuntimeConfig)
-                if crate::utils::is_reserved_short_code(&c) {
-                    return Err(ShortlinkerError::link_reserved_code(format!(
-                        "Short code '{}' conflicts with reserved routes",
-                        c
-                    )));
-                }
-                (c, false)
-            }
+        // Validate target, code, expiration and extras (first error wins)
+        let validated = validate_new_link(
+            LinkInput {
+                code: req.code.as_deref(),
+                target: &req.target,
+                expires_at: req.expires_at.as_deref(),
+                extras: req.extras.as_deref(),
+            },
+            ValidationProfile::INTERACTIVE,
+        )
+        .map_err(|mut errors| errors.remove(0).error)?;
+
+        // Generate code if not provided
+        let (code, generated) = match validated.code {
+            Some(c) => (c, false),
             None => (generate_random_code(self.random_code_length()), true),
         };
 
+        let warnings: Vec<String> = validated.credential_warning.into_iter().collect();
+        for warning in &warnings {
+            warn!("LinkService: create '{}': {}", code, warning);
+        }
+
         // Check if code already exists
         let existing = self.storage.get(&code).await.map_err(|e| {
             ShortlinkerError::database_operation(format!("Failed to check existing link: {}", e))
@@ -264,35 +567,67 @@ impl LinkService {
             )));
         }
 
-        // Parse expiration time
-        let expires_at = self.parse_expires_at(req.expires_at.as_deref())?;
+        // 删除冷却期：新建（而非覆盖）时检查
+        let cooling = if existing.is_none() {
+            self.cooling_down_codes(&[code.as_str()])
+                .await?
+                .remove(&code)
+        } else {
+            None
+        };
+        if let Some(release_at) = cooling
+            && !req.override_cooldown
+        {
+            return Err(ShortlinkerError::link_code_cooling_down(cooldown_message(
+                &code, release_at,
+            )));
+        }
+
+        let expires_at = validated.expires_at;
 
         // Process password
         let password = self.process_password(req.password.as_deref())?;
 
-        // Preserve original created_at and click count if overwriting
-        let (created_at, click) = if let Some(ref existing_link) = existing {
-            (existing_link.created_at, existing_link.click)
+        // Preserve original created_at, click count and channel if overwriting
+        let (created_at, click, created_via) = if let Some(ref existing_link) = existing {
+            (
+                existing_link.created_at,
+                existing_link.click,
+                existing_link.created_via,
+            )
         } else {
-            (Utc::now(), 0)
+            (Utc::now(), 0, req.created_via)
         };
 
         let new_link = ShortLink {
             code: code.clone(),
-            target: req.target,
+            target: validated.target,
             created_at,
             expires_at,
             password,
             click,
+            created_via,
+            analytics_level: req.analytics_level,
+            extras: validated.extras,
         };
 
-        // Save to storage
-        self.storage.set(new_link.clone()).await.map_err(|e| {
-            ShortlinkerError::database_operation(format!("Failed to save link: {}", e))
-        })?;
-
-        // Update cache
-        self.update_cache(&new_link).await;
+        // Save to storage together with the cache refresh (outbox), then run it
+        let effects = [SideEffect::cache_refresh(&code)];
+        let pending = self
+            .storage
+            .set_with_effects(new_link.clone(), &effects)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!("Failed to save link: {}", e))
+            })?;
+        self.side_effects.run_committed(pending, &effects).await;
+        if cooling.is_some() {
+            warn!(
+                "LinkService: code '{}' reused during its deletion cooldown (override)",
+                code
+            );
+            self.release_codes(std::slice::from_ref(&code)).await;
+        }
 
         let action = if existing.is_some() {
             "overwrote"
@@ -307,6 +642,7 @@ impl LinkService {
         Ok(LinkCreateResult {
             link: new_link,
             generated_code: generated,
+            warnings,
         })
     }
 
@@ -316,9 +652,29 @@ impl LinkService {
         code: &str,
         req: UpdateLinkRequest,
     ) -> Result<ShortLink, ShortlinkerError> {
-        // Validate URL
-        aster_forge_utils::url::parse_http_url(&req.target, "target URL")
-            .map_err(|error| ShortlinkerError::link_invalid_url(error.to_string()))?;
+        self.update_link_checked(code, req)
+            .await
+            .map(|result| result.link)
+    }
+
+    /// Update an existing link, also returning warnings (credentials in the target URL)
+    pub async fn update_link_checked(
+        &self,
+        code: &str,
+        req: UpdateLinkRequest,
+    ) -> Result<LinkUpdateResult, ShortlinkerError> {
+        // Validate URL (credentials handled per `links.credential_policy`) and extras
+        validate_target(&req.target)?;
+        let target = credential_rules().apply(&req.target)?;
+        let warnings: Vec<String> = target.warning.into_iter().collect();
+        for warning in &warnings {
+            warn!("LinkService: update '{}': {}", code, warning);
+        }
+        let extras = req
+            .extras
+            .as_deref()
+            .map(|raw| validate_extras(Some(raw)))
+            .transpose()?;
 
         // Get existing link
         let existing = self
@@ -332,7 +688,7 @@ impl LinkService {
 
         // Parse expiration time (None = keep existing)
         let expires_at = if req.expires_at.is_some() {
-            self.parse_expires_at(req.expires_at.as_deref())?
+            validate_expires_at(req.expires_at.as_deref(), ValidationProfile::INTERACTIVE)?
         } else {
             existing.expires_at
         };
@@ -349,32 +705,46 @@ impl LinkService {
 
         let updated_link = ShortLink {
             code: code.to_string(),
-            target: req.target,
+            target: target.target,
             created_at: existing.created_at,
             expires_at,
             password,
             click: existing.click,
+            created_via: existing.created_via,
+            analytics_level: req.analytics_level.unwrap_or(existing.analytics_level),
+            extras: extras.unwrap_or(existing.extras),
         };
 
-        // Save to storage
-        self.storage.set(updated_link.clone()).await.map_err(|e| {
-            ShortlinkerError::database_operation(format!("Failed to update link: {}", e))
-        })?;
-
-        // Update cache
-        self.update_cache(&updated_link).await;
+        // Save to storage together with the cache refresh (outbox), then run it
+        let effects = [SideEffect::cache_refresh(code)];
+        let pending = self
+            .storage
+            .set_with_effects(updated_link.clone(), &effects)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!("Failed to update link: {}", e))
+            })?;
+        self.side_effects.run_committed(pending, &effects).await;
 
         info!("LinkService: updated '{}'", code);
-        Ok(updated_link)
+        Ok(LinkUpdateResult {
+            link: updated_link,
+            warnings,
+        })
     }
 
     /// Delete a link
     pub async fn delete_link(&self, code: &str) -> Result<(), ShortlinkerError> {
-        self.storage.remove(code).await.map_err(|e| {
-            ShortlinkerError::database_operation(format!("Failed to remove link: {}", e))
-        })?;
-
-        self.cache.remove(code).await;
+        let effects = [SideEffect::cache_refresh(code)];
+        let pending = self
+            .storage
+            .remove_with_effects(code, &effects)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!("Failed to remove link: {}", e))
+            })?;
+        self.side_effects.run_committed(pending, &effects).await;
+        self.retire_codes(&[code.to_string()]).await;
 
         info!("LinkService: deleted '{}'", code);
         Ok(())
@@ -413,6 +783,52 @@ impl LinkService {
         })
     }
 
+    /// Get creation-channel stats: totals per `created_via` and a daily
+    /// per-channel trend over the last `days` days (UTC, zero-filled).
+    pub async fn get_creation_stats(&self, days: u32) -> Result<CreationStats, ShortlinkerError> {
+        let mut stats = CreationStats::default();
+
+        for row in self.storage.count_by_created_via().await? {
+            let via = CreatedVia::from_db(&row.created_via);
+            *stats
+                .by_created_via
+                .entry(via.as_str().to_string())
+                .or_default() += usize::try_from(row.count).unwrap_or(0);
+        }
+
+        let today = Utc::now().date_naive();
+        let first_day = today - chrono::Duration::days(i64::from(days.max(1)) - 1);
+        let since = first_day.and_time(chrono::NaiveTime::MIN).and_utc();
+
+        let mut daily: HashMap<String, CreationTrendPoint> = HashMap::new();
+        for row in self.storage.get_creation_trend(since).await? {
+            let via = CreatedVia::from_db(&row.created_via);
+            *daily
+                .entry(row.label.clone())
+                .or_insert_with(|| CreationTrendPoint {
+                    date: row.label,
+                    ..Default::default()
+                })
+                .counts
+                .entry(via.as_str().to_string())
+                .or_default() += usize::try_from(row.count).unwrap_or(0);
+        }
+
+        stats.daily = first_day
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| {
+                let date = day.format("%Y-%m-%d").to_string();
+                daily.remove(&date).unwrap_or(CreationTrendPoint {
+                    date,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(stats)
+    }
+
     // ============ Batch Operations ============
 
     /// 流式导出链接（游标分页）
@@ -470,30 +886,25 @@ impl LinkService {
         let all_codes: Vec<String> = items.iter().map(|item| item.code.clone()).collect();
 
         // 2. 冲突检测：Bloom filter 预筛选 + 精确查询
-        let existing_codes: HashSet<String> = match mode {
-            ImportMode::Overwrite => HashSet::new(),
-            ImportMode::Skip | ImportMode::Error => {
-                let mut maybe_exist = Vec::new();
-                for code in &all_codes {
-                    if self.cache.bloom_check(code).await {
-                        maybe_exist.push(code.clone());
-                    }
-                }
-
-                if maybe_exist.is_empty() {
-                    HashSet::new()
-                } else {
-                    self.storage
-                        .batch_check_codes_exist(&maybe_exist)
-                        .await
-                        .map_err(|e| {
-                            ShortlinkerError::database_operation(format!(
-                                "Failed to check existing codes: {}",
-                                e
-                            ))
-                        })?
-                }
+        // 覆盖模式同样需要，用于区分新建与覆盖（配额只计新建）
+        let mut maybe_exist = Vec::new();
+        for code in &all_codes {
+            if self.cache.bloom_check(code).await {
+                maybe_exist.push(code.clone());
             }
+        }
+        let existing_codes: HashSet<String> = if maybe_exist.is_empty() {
+            HashSet::new()
+        } else {
+            self.storage
+                .batch_check_codes_exist(&maybe_exist)
+                .await
+                .map_err(|e| {
+                    ShortlinkerError::database_operation(format!(
+                        "Failed to check existing codes: {}",
+                        e
+                    ))
+                })?
         };
 
         debug!(
@@ -502,11 +913,27 @@ impl LinkService {
             existing_codes.len()
         );
 
+        // 冷却期内的短码不允许通过导入重新创建
+        let codes_refs: Vec<&str> = all_codes.iter().map(String::as_str).collect();
+        let cooling = self.cooling_down_codes(&codes_refs).await?;
+
         // 3. CSV 内去重 + 冲突处理
         let mut links_to_insert: HashMap<String, ShortLink> = HashMap::new();
         let mut processed_codes: HashSet<String> = HashSet::new();
 
         for item in items {
+            if let Some(release_at) = cooling.get(&item.code) {
+                result.failed_items.push(ImportBatchFailedItem {
+                    error: ShortlinkerError::link_code_cooling_down(cooldown_message(
+                        &item.code,
+                        *release_at,
+                    )),
+                    code: item.code,
+                    row_num: item.row_num,
+                });
+                continue;
+            }
+
             let exists =
                 existing_codes.contains(&item.code) || processed_codes.contains(&item.code);
             if exists {
@@ -539,6 +966,9 @@ impl LinkService {
                 expires_at: item.expires_at,
                 password: item.password,
                 click: item.click_count,
+                created_via: CreatedVia::Import,
+                analytics_level: item.analytics_level,
+                extras: item.extras,
             };
 
             processed_codes.insert(item.code.clone());
@@ -551,21 +981,30 @@ impl LinkService {
         let total = links_vec.len();
 
         if !links_vec.is_empty() {
-            let default_ttl = self.default_cache_ttl();
+            // 导入期间暂停周期性 Bloom 重建；Drop 时自动恢复（含错误返回路径）
+            let _bulk = BulkWriteGuard::new(self.cache.as_ref());
             let mut processed = 0usize;
 
             for chunk in links_vec.chunks(chunk_size) {
-                self.storage.batch_set(chunk.to_vec()).await.map_err(|e| {
+                let codes: Vec<String> = chunk.iter().map(|link| link.code.clone()).collect();
+                let written = self.storage.batch_set(chunk.to_vec()).await;
+
+                // 逐块失效本块短码的对象缓存与负缓存，并登记 Bloom 保证新短码立即可被重定向命中。
+                // 写入失败时同样处理：失败的批次可能已部分落库，之前的块已在各自轮次失效
+                self.cache.invalidate_codes(&codes).await;
+                self.cache.insert_codes(&codes).await;
+                written.map_err(|e| {
                     ShortlinkerError::database_operation(format!(
                         "Failed to batch insert links: {}",
                         e
                     ))
                 })?;
 
-                for link in chunk {
-                    let ttl = link.cache_ttl(default_ttl);
-                    self.cache.insert(&link.code, link.clone(), ttl).await;
-                }
+                result.created_codes.extend(
+                    codes
+                        .into_iter()
+                        .filter(|code| !existing_codes.contains(code)),
+                );
 
                 processed += chunk.len();
                 if let Some(cb) = &on_chunk_written {
@@ -598,41 +1037,63 @@ impl LinkService {
         struct ValidatedRequest {
             code: String,
             target: String,
-            expires_at: Option<String>,
+            expires_at: Option<DateTime<Utc>>,
             password: Option<String>,
             force: bool,
+            created_via: CreatedVia,
+            analytics_level: AnalyticsLevel,
+            extras: Option<String>,
+            override_cooldown: bool,
         }
 
         let mut codes_to_check: Vec<String> = Vec::new();
         let mut valid_requests: Vec<ValidatedRequest> = Vec::new();
 
         for req in requests {
-            // Validate URL first
-            if let Err(e) = aster_forge_utils::url::parse_http_url(&req.target, "target URL") {
-                let code = req
-                    .code
-                    .clone()
-                    .unwrap_or_else(|| "<generated>".to_string());
-                result.failed.push(BatchFailedItem {
-                    code,
-                    reason: format!("Invalid URL: {}", e),
-                });
-                continue;
-            }
+            let validated = match validate_new_link(
+                LinkInput {
+                    code: req.code.as_deref(),
+                    target: &req.target,
+                    expires_at: req.expires_at.as_deref(),
+                    extras: req.extras.as_deref(),
+                },
+                ValidationProfile::BATCH_CREATE,
+            ) {
+                Ok(v) => v,
+                Err(mut errors) => {
+                    let FieldError { field, error } = errors.remove(0);
+                    let reason = match field {
+                        LinkField::Target => format!("Invalid URL: {}", error.message()),
+                        LinkField::ExpiresAt => format!("Invalid expires_at: {}", error),
+                        LinkField::Code => error.message().to_string(),
+                        LinkField::Extras => format!("Invalid extras: {}", error.message()),
+                    };
+                    let code = req.code.unwrap_or_else(|| "<generated>".to_string());
+                    result.failed.push(BatchFailedItem { code, reason });
+                    continue;
+                }
+            };
 
             // Generate code if not provided
-            let code = match req.code.filter(|c| !c.is_empty()) {
+            let code = match validated.code {
                 Some(c) => c,
                 None => generate_random_code(self.random_code_length()),
             };
+            if let Some(warning) = &validated.credential_warning {
+                warn!("LinkService: batch create '{}': {}", code, warning);
+            }
 
             codes_to_check.push(code.clone());
             valid_requests.push(ValidatedRequest {
                 code,
-                target: req.target,
-                expires_at: req.expires_at,
+                target: validated.target,
+                expires_at: validated.expires_at,
                 password: req.password,
                 force: req.force,
+                created_via: req.created_via,
+                analytics_level: req.analytics_level,
+                extras: validated.extras,
+                override_cooldown: req.override_cooldown,
             });
         }
 
@@ -641,9 +1102,16 @@ impl LinkService {
         let existing_map = self.storage.batch_get(&codes_refs).await.map_err(|e| {
             ShortlinkerError::database_operation(format!("Failed to batch check codes: {}", e))
         })?;
+        let new_codes: Vec<&str> = codes_refs
+            .iter()
+            .copied()
+            .filter(|code| !existing_map.contains_key(*code))
+            .collect();
+        let cooling = self.cooling_down_codes(&new_codes).await?;
 
         // Step 3: Process each request
         let mut links_to_save: Vec<ShortLink> = Vec::new();
+        let mut released: Vec<String> = Vec::new();
 
         for req in valid_requests {
             let existing = existing_map.get(&req.code);
@@ -657,17 +1125,14 @@ impl LinkService {
                 continue;
             }
 
-            // Parse expiration time
-            let expires_at = match self.parse_expires_at(req.expires_at.as_deref()) {
-                Ok(dt) => dt,
-                Err(e) => {
-                    result.failed.push(BatchFailedItem {
-                        code: req.code,
-                        reason: format!("Invalid expires_at: {}", e),
-                    });
-                    continue;
-                }
-            };
+            let cooling_down = existing.is_none() && cooling.contains_key(&req.code);
+            if cooling_down && !req.override_cooldown {
+                result.failed.push(BatchFailedItem {
+                    reason: cooldown_message(&req.code, cooling[&req.code]),
+                    code: req.code,
+                });
+                continue;
+            }
 
             // Process password
             let password = match self.process_password(req.password.as_deref()) {
@@ -681,22 +1146,32 @@ impl LinkService {
                 }
             };
 
-            // Preserve created_at and click if overwriting
-            let (created_at, click) = if let Some(existing_link) = existing {
-                (existing_link.created_at, existing_link.click)
+            // Preserve created_at, click and channel if overwriting
+            let (created_at, click, created_via) = if let Some(existing_link) = existing {
+                (
+                    existing_link.created_at,
+                    existing_link.click,
+                    existing_link.created_via,
+                )
             } else {
-                (Utc::now(), 0)
+                (Utc::now(), 0, req.created_via)
             };
 
             let new_link = ShortLink {
                 code: req.code.clone(),
                 target: req.target,
                 created_at,
-                expires_at,
+                expires_at: req.expires_at,
                 password,
                 click,
+                created_via,
+                analytics_level: req.analytics_level,
+                extras: req.extras,
             };
 
+            if cooling_down {
+                released.push(new_link.code.clone());
+            }
             links_to_save.push(new_link);
         }
 
@@ -709,9 +1184,16 @@ impl LinkService {
                     ShortlinkerError::database_operation(format!("Failed to batch save: {}", e))
                 })?;
 
-            // Update cache for each saved link
+            self.update_cache_batch(&links_to_save).await;
+            if !released.is_empty() {
+                warn!(
+                    "LinkService: {} codes reused during their deletion cooldown (override): {}",
+                    released.len(),
+                    released.join(", ")
+                );
+                self.release_codes(&released).await;
+            }
             for link in &links_to_save {
-                self.update_cache(link).await;
                 result.success.push(BatchSuccessItem {
                     code: link.code.clone(),
                     link: link.clone(),
@@ -728,6 +1210,82 @@ impl LinkService {
         Ok(result)
     }
 
+    /// 按模板展开并批量创建链接
+    ///
+    /// 模板或任一组合非法时整批拒绝；写入走 [`Self::batch_create_links`]。
+    pub async fn generate_links(
+        &self,
+        template: LinkTemplate,
+        options: GenerateLinksOptions,
+    ) -> Result<GenerateLinksResult, ShortlinkerError> {
+        let planned = template.expand(self.template_max_combinations())?;
+        validate_expires_at(
+            options.expires_at.as_deref(),
+            ValidationProfile::BATCH_CREATE,
+        )?;
+
+        if options.dry_run {
+            let codes: Vec<&str> = planned.iter().filter_map(|l| l.code.as_deref()).collect();
+            let existing_map = self.storage.batch_get(&codes).await.map_err(|e| {
+                ShortlinkerError::database_operation(format!("Failed to batch check codes: {}", e))
+            })?;
+            let existing = codes
+                .into_iter()
+                .filter(|code| existing_map.contains_key(*code))
+                .map(str::to_string)
+                .collect();
+            return Ok(GenerateLinksResult {
+                planned,
+                existing,
+                dry_run: true,
+                ..Default::default()
+            });
+        }
+
+        let requests = planned
+            .iter()
+            .map(|link| CreateLinkRequest {
+                code: link.code.clone(),
+                target: link.target.clone(),
+                force: options.force,
+                expires_at: options.expires_at.clone(),
+                password: options.password.clone(),
+                created_via: options.created_via,
+                analytics_level: options.analytics_level,
+                extras: None,
+                override_cooldown: false,
+            })
+            .collect();
+        let result = self.batch_create_links(requests).await?;
+
+        info!(
+            "LinkService: template generated {} links, {} failed",
+            result.success.len(),
+            result.failed.len()
+        );
+
+        Ok(GenerateLinksResult {
+            planned,
+            existing: Vec::new(),
+            created: result
+                .success
+                .into_iter()
+                .map(|item| TemplateLink {
+                    code: Some(item.code),
+                    target: item.link.target,
+                })
+                .collect(),
+            failed: result.failed,
+            dry_run: false,
+        })
+    }
+
+    fn template_max_combinations(&self) -> usize {
+        try_get_runtime_config()
+            .and_then(|rt| rt.get_usize(keys::FEATURES_TEMPLATE_MAX_COMBINATIONS))
+            .unwrap_or(DEFAULT_TEMPLATE_MAX_COMBINATIONS)
+    }
+
     /// Batch update links
     ///
     /// Updates multiple links in a single operation. Each update is validated
@@ -744,27 +1302,51 @@ impl LinkService {
             target: String,
             expires_at: Option<String>,
             password: Option<String>,
+            analytics_level: Option<AnalyticsLevel>,
+            /// `None` = keep existing; `Some(None)` = clear
+            extras: Option<Option<String>>,
         }
 
         let mut codes_to_check: Vec<String> = Vec::new();
         let mut valid_updates: Vec<ValidatedUpdate> = Vec::new();
 
         for (code, req) in updates {
-            // Validate URL first
-            if let Err(e) = aster_forge_utils::url::parse_http_url(&req.target, "target URL") {
-                result.failed.push(BatchFailedItem {
-                    code,
-                    reason: format!("Invalid URL: {}", e),
-                });
-                continue;
+            // Validate URL first (credentials handled per `links.credential_policy`)
+            let target = match validate_target(&req.target)
+                .and_then(|()| credential_rules().apply(&req.target))
+            {
+                Ok(target) => target,
+                Err(e) => {
+                    result.failed.push(BatchFailedItem {
+                        code,
+                        reason: format!("Invalid URL: {}", e.message()),
+                    });
+                    continue;
+                }
+            };
+            if let Some(warning) = &target.warning {
+                warn!("LinkService: batch update '{}': {}", code, warning);
             }
+            let extras = match req.extras.as_deref().map(|raw| validate_extras(Some(raw))) {
+                None => None,
+                Some(Ok(extras)) => Some(extras),
+                Some(Err(e)) => {
+                    result.failed.push(BatchFailedItem {
+                        code,
+                        reason: format!("Invalid extras: {}", e.message()),
+                    });
+                    continue;
+                }
+            };
 
             codes_to_check.push(code.clone());
             valid_updates.push(ValidatedUpdate {
                 code,
-                target: req.target,
+                target: target.target,
                 expires_at: req.expires_at,
                 password: req.password,
+                analytics_level: req.analytics_level,
+                extras,
             });
         }
 
@@ -791,7 +1373,10 @@ impl LinkService {
 
             // Parse expiration time (None = keep existing)
             let expires_at = if update.expires_at.is_some() {
-                match self.parse_expires_at(update.expires_at.as_deref()) {
+                match validate_expires_at(
+                    update.expires_at.as_deref(),
+                    ValidationProfile::INTERACTIVE,
+                ) {
                     Ok(dt) => dt,
                     Err(e) => {
                         result.failed.push(BatchFailedItem {
@@ -827,6 +1412,9 @@ impl LinkService {
                 expires_at,
                 password,
                 click: existing.click,
+                created_via: existing.created_via,
+                analytics_level: update.analytics_level.unwrap_or(existing.analytics_level),
+                extras: update.extras.unwrap_or_else(|| existing.extras.clone()),
             };
 
             links_to_save.push(updated_link);
@@ -841,9 +1429,8 @@ impl LinkService {
                     ShortlinkerError::database_operation(format!("Failed to batch update: {}", e))
                 })?;
 
-            // Update cache for each saved link
+            self.update_cache_batch(&links_to_save).await;
             for link in &links_to_save {
-                self.update_cache(link).await;
                 result.success.push(BatchSuccessItem {
                     code: link.code.clone(),
                     link: link.clone(),
@@ -899,6 +1486,7 @@ impl LinkService {
             for code in &codes_to_delete {
                 self.cache.remove(code).await;
             }
+            self.retire_codes(&codes_to_delete).await;
 
             result.deleted = codes_to_delete;
         }
@@ -911,4 +1499,283 @@ impl LinkService {
 
         Ok(result)
     }
+
+    /// 批量顺延过期时间
+    ///
+    /// 按短码列表或过滤条件选取链接，对 expires_at 做偏移或直接设置，
+    /// 返回逐条结果。`dry_run` 时只计算不写入。
+    pub async fn batch_extend_links(
+        &self,
+        selection: LinkSelection,
+        req: BatchExtendRequest,
+    ) -> Result<BatchExtendResult, ShortlinkerError> {
+        let mut result = BatchExtendResult {
+            dry_run: req.dry_run,
+            ..Default::default()
+        };
+
+        // Step 1: Resolve target links
+        let links: Vec<ShortLink> = match selection {
+            LinkSelection::Codes(codes) => {
+                let codes_refs: Vec<&str> = codes.iter().map(|s| s.as_str()).collect();
+                let mut existing_map = self.storage.batch_get(&codes_refs).await.map_err(|e| {
+                    ShortlinkerError::database_operation(format!("Failed to batch fetch: {}", e))
+                })?;
+
+                let mut links = Vec::with_capacity(existing_map.len());
+                for code in codes {
+                    match existing_map.remove(&code) {
+                        Some(link) => links.push(link),
+                        None => result.failed.push(BatchFailedItem {
+                            code,
+                            reason: "Link not found".to_string(),
+                        }),
+                    }
+                }
+                links
+            }
+            LinkSelection::Filter(filter) => {
+                use futures_util::StreamExt;
+
+                let mut stream = self.storage.stream_all_filtered_cursor(filter, 1000);
+                let mut links = Vec::new();
+                while let Some(batch) = stream.next().await {
+                    links.extend(batch?);
+                    if links.len() > MAX_EXTEND_BATCH_SIZE {
+                        return Err(ShortlinkerError::validation(format!(
+                            "Filter matches more than {} links, narrow it down",
+                            MAX_EXTEND_BATCH_SIZE
+                        )));
+                    }
+                }
+                links
+            }
+        };
+
+        // Step 2: Compute new expiration times
+        let now = Utc::now();
+        let mut links_to_save: Vec<ShortLink> = Vec::new();
+
+        for mut link in links {
+            match compute_extended_expiry(link.expires_at, now, &req) {
+                Ok(new_expires_at) => {
+                    result.updated.push(BatchExtendItem {
+                        code: link.code.clone(),
+                        old_expires_at: link.expires_at,
+                        new_expires_at,
+                    });
+                    link.expires_at = Some(new_expires_at);
+                    links_to_save.push(link);
+                }
+                Err(reason) => result.skipped.push(BatchFailedItem {
+                    code: link.code,
+                    reason,
+                }),
+            }
+        }
+
+        // Step 3: Batch save and refresh cache
+        if !req.dry_run && !links_to_save.is_empty() {
+            self.storage
+                .batch_set(links_to_save.clone())
+                .await
+                .map_err(|e| {
+                    ShortlinkerError::database_operation(format!("Failed to batch extend: {}", e))
+                })?;
+
+            let (expired, active): (Vec<ShortLink>, Vec<ShortLink>) = links_to_save
+                .into_iter()
+                .partition(|link| link.is_expired());
+            for link in &expired {
+                self.cache.remove(&link.code).await;
+            }
+            self.update_cache_batch(&active).await;
+        }
+
+        info!(
+            "LinkService: batch extend{} - {} updated, {} skipped, {} failed",
+            if req.dry_run { " (dry-run)" } else { "" },
+            result.updated.len(),
+            result.skipped.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+
+    // ============ Archive Operations ============
+
+    /// 归档链接
+    ///
+    /// 按 [`ARCHIVE_BATCH_SIZE`] 分批，每批在一个事务内从主表移入归档表，
+    /// 随后清理该批的缓存；单批失败只记入 `failed`，不影响其余批次。
+    /// 点击日志与汇总数据保留，analytics 仍可按原短码查询。
+    pub async fn archive_links(
+        &self,
+        selection: LinkSelection,
+    ) -> Result<BatchArchiveResult, ShortlinkerError> {
+        let start = std::time::Instant::now();
+        let mut result = BatchArchiveResult::default();
+
+        match selection {
+            LinkSelection::Codes(codes) => {
+                let mut seen = HashSet::new();
+                let codes: Vec<String> = codes
+                    .into_iter()
+                    .filter(|code| seen.insert(code.clone()))
+                    .collect();
+                for chunk in codes.chunks(ARCHIVE_BATCH_SIZE) {
+                    self.archive_chunk(chunk, &mut result).await;
+                }
+            }
+            LinkSelection::Filter(filter) => {
+                use futures_util::StreamExt;
+
+                // 游标按 short_code 递增，已归档（删除）的行不影响后续分页
+                let mut stream = self
+                    .storage
+                    .stream_all_filtered_cursor(filter, ARCHIVE_BATCH_SIZE as u64);
+                while let Some(batch) = stream.next().await {
+                    let batch = batch.inspect_err(|e| {
+                        error!(
+                            "LinkService: archive by filter aborted after {} links: {}",
+                            result.archived.len(),
+                            e
+                        );
+                    })?;
+                    let codes: Vec<String> = batch.into_iter().map(|link| link.code).collect();
+                    self.archive_chunk(&codes, &mut result).await;
+                }
+            }
+        }
+
+        info!(
+            "LinkService: archived {} links ({} not found, {} failed) in {:?}",
+            result.archived.len(),
+            result.not_found.len(),
+            result.failed.len(),
+            start.elapsed()
+        );
+
+        Ok(result)
+    }
+
+    /// 归档单批短码并清理缓存
+    async fn archive_chunk(&self, codes: &[String], result: &mut BatchArchiveResult) {
+        match self.storage.archive_batch(codes).await {
+            Ok((archived, not_found)) => {
+                self.cache.mark_archived(&archived).await;
+                debug!("LinkService: archived batch [{}]", archived.join(", "));
+                result.archived.extend(archived);
+                result.not_found.extend(not_found);
+            }
+            Err(e) => {
+                error!(
+                    "LinkService: archive batch of {} links failed: {}",
+                    codes.len(),
+                    e
+                );
+                let reason = e.to_string();
+                result
+                    .failed
+                    .extend(codes.iter().map(|code| BatchFailedItem {
+                        code: code.clone(),
+                        reason: reason.clone(),
+                    }));
+            }
+        }
+    }
+
+    /// 从归档恢复链接
+    ///
+    /// 主表中已有同名短码（归档后被复用）时返回 `LinkAlreadyExists`。
+    pub async fn unarchive_link(&self, code: &str) -> Result<ShortLink, ShortlinkerError> {
+        if self.storage.get(code).await?.is_some() {
+            return Err(ShortlinkerError::link_already_exists(format!(
+                "Short code '{}' is already used by an active link",
+                code
+            )));
+        }
+
+        let link = self.storage.unarchive(code).await?.ok_or_else(|| {
+            ShortlinkerError::not_found(format!("Archived link not found: {}", code))
+        })?;
+
+        self.cache.unmark_archived(code).await;
+        if !link.is_expired() {
+            self.update_cache(&link).await;
+        }
+
+        info!("LinkService: unarchived '{}'", code);
+        Ok(link)
+    }
+
+    /// 分页查询归档链接
+    pub async fn list_archived(
+        &self,
+        search: Option<String>,
+        page: u64,
+        page_size: u64,
+    ) -> Result<(Vec<ArchivedLink>, u64), ShortlinkerError> {
+        let page = page.max(1);
+        let page_size = page_size.clamp(1, 100);
+
+        self.storage
+            .load_archived_paginated(page, page_size, search)
+            .await
+    }
+
+    // ============ Sampling ============
+
+    /// 在满足过滤条件的链接中均匀随机抽取至多 `n` 条
+    ///
+    /// 按短码游标分批扫描并做蓄水池抽样，不依赖数据库的 `ORDER BY RANDOM()`；
+    /// 扫描顺序确定，因此数据不变时同一 `seed` 得到同一样本。
+    /// 未指定 `seed` 时随机生成（限制在 53 位内，JSON 数字可无损往返）。
+    pub async fn sample_links(
+        &self,
+        filter: LinkFilter,
+        n: usize,
+        seed: Option<u64>,
+    ) -> Result<LinkSample, ShortlinkerError> {
+        use futures_util::StreamExt;
+
+        if n == 0 || n > MAX_SAMPLE_SIZE {
+            return Err(ShortlinkerError::validation(format!(
+                "Sample size must be between 1 and {}",
+                MAX_SAMPLE_SIZE
+            )));
+        }
+
+        let seed = seed.unwrap_or_else(|| rand::random::<u64>() >> 11);
+        let start = std::time::Instant::now();
+        let mut sampler = ReservoirSampler::new(n, seed);
+
+        let mut stream = self
+            .storage
+            .stream_all_filtered_cursor(filter, SAMPLE_SCAN_BATCH_SIZE);
+        while let Some(batch) = stream.next().await {
+            for link in batch? {
+                sampler.offer(link);
+            }
+        }
+
+        let population = sampler.seen();
+        let mut links = sampler.into_sample();
+        links.sort_by(|a, b| a.code.cmp(&b.code));
+
+        info!(
+            "LinkService: sampled {} of {} links (seed {}) in {:?}",
+            links.len(),
+            population,
+            seed,
+            start.elapsed()
+        );
+
+        Ok(LinkSample {
+            links,
+            seed,
+            population,
+        })
+    }
 }
@@ -0,0 +1,370 @@
+//! 模板批量生成链接：按变量笛卡尔积展开 URL 模板与短码模板
+//!
+//! 模板中的 `{name}` 引用同名变量。URL 中的变量值按 URL 组件编码后替换，
+//! 短码模板原样替换。展开在写入前整体校验，任一组合非法则整批拒绝：
+//!
+//! - 模板只能引用已定义的变量，每个变量至少被一个模板引用
+//! - 变量值非空且不重复，组合数不超过上限（`features.template_max_combinations`）
+//! - 每个组合的 URL 与短码都通过单条创建同等的校验，短码两两不同
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ShortlinkerError;
+
+use super::link_validation::{LinkInput, ValidationProfile, validate_new_link};
+
+/// 组合数上限的默认值
+pub const DEFAULT_TEMPLATE_MAX_COMBINATIONS: usize = 1000;
+
+/// 模板变量及其取值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TemplateVar {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl TemplateVar {
+    /// 解析 CLI 的 `name=v1,v2,v3`
+    pub fn parse(arg: &str) -> Result<Self, ShortlinkerError> {
+        let (name, values) = arg.split_once('=').ok_or_else(|| {
+            ShortlinkerError::validation(format!(
+                "Invalid variable '{}': expected name=value1,value2",
+                arg
+            ))
+        })?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            values: values.split(',').map(|v| v.trim().to_string()).collect(),
+        })
+    }
+}
+
+/// 待展开的模板
+#[derive(Debug, Clone)]
+pub struct LinkTemplate {
+    /// 目标 URL 模板
+    pub url: String,
+    /// 短码模板；为空时每条链接随机生成短码
+    pub code: Option<String>,
+    pub vars: Vec<TemplateVar>,
+}
+
+/// 展开得到的一条链接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(debug_assertions, feature = "openapi"), derive(utoipa::ToSchema))]
+pub struct TemplateLink {
+    /// 短码；未提供短码模板时为空（创建时随机生成）
+    pub code: Option<String>,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Var(usize),
+}
+
+impl LinkTemplate {
+    /// 校验并展开全部组合，顺序为第一个变量在最外层
+    pub fn expand(&self, max_combinations: usize) -> Result<Vec<TemplateLink>, ShortlinkerError> {
+        self.validate_vars()?;
+
+        let url = parse_template("URL", &self.url, &self.vars)?;
+        let code = self
+            .code
+            .as_deref()
+            .map(|code| parse_template("code", code, &self.vars))
+            .transpose()?;
+
+        for (index, var) in self.vars.iter().enumerate() {
+            let used = |segments: &[Segment]| segments.contains(&Segment::Var(index));
+            if !used(&url) && !code.as_deref().is_some_and(used) {
+                return Err(ShortlinkerError::validation(format!(
+                    "Variable '{}' is not used in the URL or code template",
+                    var.name
+                )));
+            }
+        }
+
+        let total = self.combinations().filter(|n| *n <= max_combinations);
+        let Some(total) = total else {
+            return Err(ShortlinkerError::validation(format!(
+                "Template expands to {} combinations, exceeding the limit of {}",
+                self.combinations()
+                    .map_or_else(|| "too many".to_string(), |n| n.to_string()),
+                max_combinations
+            )));
+        };
+
+        let mut links = Vec::with_capacity(total);
+        let mut seen_codes = std::collections::HashSet::new();
+        let mut indices = vec![0usize; self.vars.len()];
+        for _ in 0..total {
+            let values: Vec<&str> = indices
+                .iter()
+                .zip(&self.vars)
+                .map(|(i, var)| var.values[*i].as_str())
+                .collect();
+
+            let target = render(&url, &values, |v| urlencoding::encode(v).into_owned());
+            let code = code
+                .as_ref()
+                .map(|segments| render(segments, &values, str::to_string));
+            self.check_link(code.as_deref(), &target, &values)?;
+            if let Some(code) = &code
+                && !seen_codes.insert(code.clone())
+            {
+                return Err(ShortlinkerError::validation(format!(
+                    "Code template produces duplicate code '{}'",
+                    code
+                )));
+            }
+            links.push(TemplateLink { code, target });
+
+            // 末位变量变化最快
+            for pos in (0..indices.len()).rev() {
+                indices[pos] += 1;
+                if indices[pos] < self.vars[pos].values.len() {
+                    break;
+                }
+                indices[pos] = 0;
+            }
+        }
+        Ok(links)
+    }
+
+    /// 组合总数；溢出时为 `None`
+    pub fn combinations(&self) -> Option<usize> {
+        self.vars
+            .iter()
+            .try_fold(1usize, |acc, var| acc.checked_mul(var.values.len()))
+    }
+
+    fn validate_vars(&self) -> Result<(), ShortlinkerError> {
+        if self.vars.is_empty() {
+            return Err(ShortlinkerError::validation(
+                "At least one template variable is required",
+            ));
+        }
+        for (index, var) in self.vars.iter().enumerate() {
+            if !is_valid_var_name(&var.name) {
+                return Err(ShortlinkerError::validation(format!(
+                    "Invalid variable name '{}': use letters, digits and '_'",
+                    var.name
+                )));
+            }
+            if self.vars[..index].iter().any(|v| v.name == var.name) {
+                return Err(ShortlinkerError::validation(format!(
+                    "Variable '{}' is defined more than once",
+                    var.name
+                )));
+            }
+            if var.values.is_empty() || var.values.iter().any(|v| v.is_empty()) {
+                return Err(ShortlinkerError::validation(format!(
+                    "Variable '{}' has an empty value",
+                    var.name
+                )));
+            }
+            for (i, value) in var.values.iter().enumerate() {
+                if var.values[..i].contains(value) {
+                    return Err(ShortlinkerError::validation(format!(
+                        "Variable '{}' has duplicate value '{}'",
+                        var.name, value
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_link(
+        &self,
+        code: Option<&str>,
+        target: &str,
+        values: &[&str],
+    ) -> Result<(), ShortlinkerError> {
+        let input = LinkInput {
+            code,
+            target,
+            expires_at: None,
+            extras: None,
+        };
+        validate_new_link(input, ValidationProfile::INTERACTIVE)
+            .map(|_| ())
+            .map_err(|mut errors| {
+                let combination = self
+                    .vars
+                    .iter()
+                    .zip(values)
+                    .map(|(var, value)| format!("{}={}", var.name, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ShortlinkerError::validation(format!(
+                    "Combination ({}) is invalid: {}",
+                    combination,
+                    errors.remove(0).error.message()
+                ))
+            })
+    }
+}
+
+fn is_valid_var_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_template(
+    kind: &str,
+    template: &str,
+    vars: &[TemplateVar],
+) -> Result<Vec<Segment>, ShortlinkerError> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        let after = &rest[start + 1..];
+        let end = after.find('}').ok_or_else(|| {
+            ShortlinkerError::validation(format!("Unclosed '{{' in {} template", kind))
+        })?;
+        let name = &after[..end];
+        let index = vars.iter().position(|v| v.name == name).ok_or_else(|| {
+            ShortlinkerError::validation(format!(
+                "{} template references undefined variable '{{{}}}'",
+                kind, name
+            ))
+        })?;
+        segments.push(Segment::Var(index));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+fn render(segments: &[Segment], values: &[&str], encode: impl Fn(&str) -> String) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text.clone(),
+            Segment::Var(index) => encode(values[*index]),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str, values: &[&str]) -> TemplateVar {
+        TemplateVar {
+            name: name.to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    fn template(url: &str, code: Option<&str>, vars: Vec<TemplateVar>) -> LinkTemplate {
+        LinkTemplate {
+            url: url.to_string(),
+            code: code.map(str::to_string),
+            vars,
+        }
+    }
+
+    #[test]
+    fn test_parse_var_arg() {
+        assert_eq!(
+            TemplateVar::parse("source=tv, radio,web").unwrap(),
+            var("source", &["tv", "radio", "web"])
+        );
+        assert!(TemplateVar::parse("source").is_err());
+    }
+
+    #[test]
+    fn test_expand_cartesian_product() {
+        let t = template(
+            "https://example.com/landing?utm_source={source}&utm_content={content}",
+            Some("q4-{source}-{content}"),
+            vec![
+                var("source", &["tv", "radio", "web", "app", "mail"]),
+                var("content", &["a", "b", "c", "d"]),
+            ],
+        );
+        let links = t.expand(DEFAULT_TEMPLATE_MAX_COMBINATIONS).unwrap();
+        assert_eq!(links.len(), 20);
+        assert_eq!(links[0].code.as_deref(), Some("q4-tv-a"));
+        assert_eq!(
+            links[0].target,
+            "https://example.com/landing?utm_source=tv&utm_content=a"
+        );
+        assert_eq!(links[1].code.as_deref(), Some("q4-tv-b"));
+        assert_eq!(links[19].code.as_deref(), Some("q4-mail-d"));
+    }
+
+    #[test]
+    fn test_expand_encodes_url_values_only() {
+        let t = template(
+            "https://example.com/?c={campaign}",
+            None,
+            vec![var("campaign", &["spring sale", "a&b"])],
+        );
+        let links = t.expand(10).unwrap();
+        assert_eq!(links[0].target, "https://example.com/?c=spring%20sale");
+        assert_eq!(links[1].target, "https://example.com/?c=a%26b");
+        assert!(links.iter().all(|l| l.code.is_none()));
+    }
+
+    #[test]
+    fn test_expand_rejects_invalid_templates() {
+        let cases = [
+            template(
+                "https://example.com/{missing}",
+                None,
+                vec![var("a", &["x"])],
+            ),
+            template("https://example.com/{a", None, vec![var("a", &["x"])]),
+            template("https://example.com/", None, vec![var("a", &["x"])]),
+            template("https://example.com/{a}", None, vec![]),
+            template("https://example.com/{a}", None, vec![var("a", &["x", ""])]),
+            template("https://example.com/{a}", None, vec![var("a", &["x", "x"])]),
+            template(
+                "https://example.com/{a}",
+                None,
+                vec![var("a", &["x"]), var("a", &["y"])],
+            ),
+            template("https://example.com/{a-b}", None, vec![var("a-b", &["x"])]),
+            // 短码模板未引用全部变量，产生重复短码
+            template(
+                "https://example.com/{a}/{b}",
+                Some("c-{a}"),
+                vec![var("a", &["x"]), var("b", &["1", "2"])],
+            ),
+            // 展开后的短码含非法字符
+            template(
+                "https://example.com/{a}",
+                Some("{a}"),
+                vec![var("a", &["x y"])],
+            ),
+            // 展开后的 URL 非法
+            template("{a}", None, vec![var("a", &["ftp://example.com"])]),
+        ];
+        for t in cases {
+            assert!(t.expand(100).is_err(), "expected error for {:?}", t);
+        }
+    }
+
+    #[test]
+    fn test_expand_enforces_combination_limit() {
+        let t = template(
+            "https://example.com/{a}/{b}",
+            None,
+            vec![var("a", &["1", "2", "3"]), var("b", &["1", "2", "3"])],
+        );
+        assert_eq!(t.combinations(), Some(9));
+        assert_eq!(t.expand(9).unwrap().len(), 9);
+        let err = t.expand(8).unwrap_err();
+        assert!(err.message().contains("9 combinations"));
+    }
+}
@@ -0,0 +1,681 @@
+//! 链接字段校验与展示格式化
+//!
+//! Admin API、IPC、CLI、批量创建与导入共用同一套 target / code / expires_at / extras 校验。
+//! 各入口历史上的行为差异（是否接受相对时间、是否检查短码字符集与保留路由）
+//! 通过 [`ValidationProfile`] 显式表达，而不是各自实现一遍。
+//! 过期时间的"永不过期 / 立即过期"语义见 [`parse_expiry_value`]，所有入口一致。
+//! 目标 URL 中的凭据按 `links.credential_policy` 处理，见 [`target_credentials`](super::target_credentials)。
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::errors::ShortlinkerError;
+use crate::services::target_credentials::credential_rules;
+use crate::utils::{
+    DateOnlyBoundary, ExpiryInputRules, InputTimeZone, TimeParser, is_reserved_short_code,
+    is_valid_short_code,
+};
+
+/// 展示用的时间格式（CLI 输出）
+pub const DISPLAY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+/// extras 规范化后的最大字节数
+pub const EXTRAS_MAX_BYTES: usize = 4096;
+/// extras 顶层 key 的最大数量
+pub const EXTRAS_MAX_KEYS: usize = 20;
+/// extras 顶层 key 的最大长度
+pub const EXTRAS_MAX_KEY_LEN: usize = 64;
+/// extras 的最大嵌套深度（顶层对象为第 1 层）
+pub const EXTRAS_MAX_DEPTH: usize = 3;
+
+/// 短码校验方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeCheck {
+    /// 字符集 + 保留路由；空值表示由服务端生成
+    Strict,
+    /// 不检查；空值表示由服务端生成
+    Unchecked,
+    /// 只要求非空（导入）
+    NonEmpty,
+}
+
+/// 入口的校验策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationProfile {
+    pub code: CodeCheck,
+    /// 是否接受 `1d`、`2h30m` 等相对时间（否则只接受绝对时间）
+    pub relative_expiry: bool,
+}
+
+impl ValidationProfile {
+    /// 单条创建（Admin API / IPC / CLI）
+    pub const INTERACTIVE: Self = Self {
+        code: CodeCheck::Strict,
+        relative_expiry: true,
+    };
+
+    /// 批量创建：历史上不检查短码格式，保持不变
+    pub const BATCH_CREATE: Self = Self {
+        code: CodeCheck::Unchecked,
+        relative_expiry: true,
+    };
+
+    /// CSV / IPC 导入：只接受绝对时间（相对时间在导入数据里没有意义）
+    pub const IMPORT: Self = Self {
+        code: CodeCheck::NonEmpty,
+        relative_expiry: false,
+    };
+}
+
+/// 出错的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkField {
+    Target,
+    Code,
+    ExpiresAt,
+    Extras,
+}
+
+impl LinkField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkField::Target => "target",
+            LinkField::Code => "code",
+            LinkField::ExpiresAt => "expires_at",
+            LinkField::Extras => "extras",
+        }
+    }
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone)]
+pub struct FieldError {
+    pub field: LinkField,
+    pub error: ShortlinkerError,
+}
+
+/// 待校验的新链接字段
+#[derive(Debug, Clone, Copy)]
+pub struct LinkInput<'a> {
+    pub code: Option<&'a str>,
+    pub target: &'a str,
+    pub expires_at: Option<&'a str>,
+    pub extras: Option<&'a str>,
+}
+
+/// 校验通过的链接字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedLink {
+    /// `None` 表示未提供短码，由调用方生成
+    pub code: Option<String>,
+    /// `links.credential_policy = "strip"` 时为移除凭据后的 URL
+    pub target: String,
+    /// 目标 URL 中检测到凭据时的提示（`warn` / `strip` 策略）
+    pub credential_warning: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 给出的具体过期时间已经过去（显式 `now` 不算），导入结果据此给出 warning
+    pub expiry_in_past: bool,
+    /// 规范化后的 extras，`None` 表示未设置
+    pub extras: Option<String>,
+}
+
+/// 校验目标 URL（仅允许 http/https）
+pub fn validate_target(target: &str) -> Result<(), ShortlinkerError> {
+    aster_forge_utils::url::parse_http_url(target, "target URL")
+        .map(|_| ())
+        .map_err(|error| ShortlinkerError::link_invalid_url(error.to_string()))
+}
+
+/// 按策略校验短码，返回 `None` 表示需要生成
+pub fn validate_code(
+    code: Option<&str>,
+    check: CodeCheck,
+) -> Result<Option<String>, ShortlinkerError> {
+    let code = code.filter(|c| !c.is_empty());
+    match (code, check) {
+        (None, CodeCheck::NonEmpty) => Err(ShortlinkerError::link_invalid_code("Empty code")),
+        (None, _) => Ok(None),
+        (Some(c), CodeCheck::Strict) => {
+            if !is_valid_short_code(c) {
+                return Err(ShortlinkerError::link_invalid_code(format!(
+                    "Invalid short code '{}'. Only alphanumeric, underscore, hyphen, dot, and slash allowed.",
+                    c
+                )));
+            }
+            // 保留路由从 RuntimeConfig 读取
+            if is_reserved_short_code(c) {
+                return Err(ShortlinkerError::link_reserved_code(format!(
+                    "Short code '{}' conflicts with reserved routes",
+                    c
+                )));
+            }
+            Ok(Some(c.to_string()))
+        }
+        (Some(c), _) => Ok(Some(c.to_string())),
+    }
+}
+
+/// 当前生效的过期时间解释规则（`links.expiry_timezone`、`links.date_only_expiry`）
+///
+/// 未加载运行时配置时使用默认值：服务器本地时区、当天结束。
+pub fn expiry_input_rules() -> ExpiryInputRules {
+    let Some(rt) = try_get_runtime_config() else {
+        return ExpiryInputRules::default();
+    };
+    ExpiryInputRules {
+        timezone: InputTimeZone::parse(&rt.get_or(keys::LINKS_EXPIRY_TIMEZONE, "local"))
+            .unwrap_or_default(),
+        date_only: DateOnlyBoundary::parse(&rt.get_or(keys::LINKS_DATE_ONLY_EXPIRY, "end_of_day"))
+            .unwrap_or_default(),
+    }
+}
+
+/// 解析非空的具体过期时间字符串（`never` / `now` 关键字见 [`parse_expiry_value`]）
+///
+/// 所有入口共用同一套语义：带偏移的时间原样使用，不带偏移的日期时间按
+/// [`expiry_input_rules`] 的时区解释，只有日期时取该时区当天结束（或开始）。
+/// `allow_relative` 为 false 时（导入）不接受 `7d` 这类相对时间。
+pub fn parse_expiry(input: &str, allow_relative: bool) -> Result<DateTime<Utc>, String> {
+    let rules = expiry_input_rules();
+    if allow_relative {
+        TimeParser::parse_expire_time_with(input, &rules)
+    } else {
+        TimeParser::parse_absolute_time(input, &rules)
+    }
+}
+
+/// 表示永不过期的关键字
+pub const EXPIRY_NEVER: &str = "never";
+/// 表示立即过期的关键字
+pub const EXPIRY_NOW: &str = "now";
+
+/// 过期时间输入的显式语义
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryValue {
+    /// 永不过期
+    Never,
+    /// 立即过期（显式 `now`）
+    Now(DateTime<Utc>),
+    /// 具体时间，可能已经过去
+    At(DateTime<Utc>),
+}
+
+impl ExpiryValue {
+    /// 写入存储的过期时间，`None` 为永不过期
+    pub fn expires_at(self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Never => None,
+            Self::Now(dt) | Self::At(dt) => Some(dt),
+        }
+    }
+
+    /// 给出的具体时间在 `now` 或之前（显式 `now` 不算）
+    pub fn is_past(self, now: DateTime<Utc>) -> bool {
+        matches!(self, Self::At(dt) if dt <= now)
+    }
+}
+
+/// 解析可选的过期时间输入
+///
+/// 所有入口（Admin API、IPC、CLI、批量、模板生成、CSV 导入）共用的语义：
+///
+/// | 输入 | 结果 |
+/// |------|------|
+/// | 缺省、JSON `null`、空字符串、`never`（不区分大小写） | 永不过期 |
+/// | `now`（不区分大小写） | 立即过期 |
+/// | 绝对时间（见 [`parse_expiry`]） | 该时间；已经过去也允许（导入历史归档数据），导入结果给出 warning |
+/// | 相对时间 `1d`、`2h30m`（`allow_relative` 为 false 时不接受） | 当前时间 + 偏移 |
+/// | `0` 及其他无法解析的值 | 错误 |
+///
+/// 更新链接时"缺省"表示保持不变，由调用方在调用前区分。
+pub fn parse_expiry_value(
+    input: Option<&str>,
+    allow_relative: bool,
+) -> Result<ExpiryValue, String> {
+    let Some(input) = input.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(ExpiryValue::Never);
+    };
+    if input.eq_ignore_ascii_case(EXPIRY_NEVER) {
+        return Ok(ExpiryValue::Never);
+    }
+    if input.eq_ignore_ascii_case(EXPIRY_NOW) {
+        return Ok(ExpiryValue::Now(Utc::now()));
+    }
+    if input == "0" {
+        return Err(format!(
+            "'0' is ambiguous: use '{}' (or leave it empty) for no expiry, '{}' to expire immediately",
+            EXPIRY_NEVER, EXPIRY_NOW
+        ));
+    }
+    parse_expiry(input, allow_relative).map(ExpiryValue::At)
+}
+
+/// 按策略解析可选过期时间，语义见 [`parse_expiry_value`]
+pub fn validate_expiry(
+    expires_at: Option<&str>,
+    profile: ValidationProfile,
+) -> Result<ExpiryValue, ShortlinkerError> {
+    parse_expiry_value(expires_at, profile.relative_expiry)
+        .map_err(ShortlinkerError::link_invalid_expire_time)
+}
+
+/// 按策略解析可选过期时间，`None` 为永不过期
+pub fn validate_expires_at(
+    expires_at: Option<&str>,
+    profile: ValidationProfile,
+) -> Result<Option<DateTime<Utc>>, ShortlinkerError> {
+    validate_expiry(expires_at, profile).map(ExpiryValue::expires_at)
+}
+
+/// 是否可作为 extras 顶层 key（也用于 `extras.<key>` 过滤参数）
+///
+/// 只允许字母、数字、`_`、`-`，长度 1-64，保证能安全拼入各数据库的 JSON 路径。
+pub fn is_valid_extras_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= EXTRAS_MAX_KEY_LEN
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// 校验并规范化 extras
+///
+/// 必须是 JSON 对象：顶层 key 不超过 [`EXTRAS_MAX_KEYS`] 个且满足
+/// [`is_valid_extras_key`]，嵌套不超过 [`EXTRAS_MAX_DEPTH`] 层，
+/// 规范化（紧凑格式）后不超过 [`EXTRAS_MAX_BYTES`] 字节。
+/// 空字符串与 `{}` 视为未设置，返回 `None`。
+pub fn validate_extras(extras: Option<&str>) -> Result<Option<String>, ShortlinkerError> {
+    let Some(raw) = extras.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let value: Value = serde_json::from_str(raw)
+        .map_err(|e| ShortlinkerError::validation(format!("extras is not valid JSON: {}", e)))?;
+    let Value::Object(map) = &value else {
+        return Err(ShortlinkerError::validation("extras must be a JSON object"));
+    };
+    if map.is_empty() {
+        return Ok(None);
+    }
+    if map.len() > EXTRAS_MAX_KEYS {
+        return Err(ShortlinkerError::validation(format!(
+            "extras has {} keys, at most {} allowed",
+            map.len(),
+            EXTRAS_MAX_KEYS
+        )));
+    }
+    if let Some(key) = map.keys().find(|key| !is_valid_extras_key(key)) {
+        return Err(ShortlinkerError::validation(format!(
+            "Invalid extras key '{}'. Only alphanumeric, underscore and hyphen allowed (1-{} chars).",
+            key, EXTRAS_MAX_KEY_LEN
+        )));
+    }
+    if json_depth(&value) > EXTRAS_MAX_DEPTH {
+        return Err(ShortlinkerError::validation(format!(
+            "extras is nested too deeply (max depth {})",
+            EXTRAS_MAX_DEPTH
+        )));
+    }
+
+    let normalized = value.to_string();
+    if normalized.len() > EXTRAS_MAX_BYTES {
+        return Err(ShortlinkerError::validation(format!(
+            "extras is {} bytes, at most {} allowed",
+            normalized.len(),
+            EXTRAS_MAX_BYTES
+        )));
+    }
+    Ok(Some(normalized))
+}
+
+/// JSON 值的嵌套深度：标量为 0，对象 / 数组为子元素最大深度 + 1
+fn json_depth(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => 1 + map.values().map(json_depth).max().unwrap_or(0),
+        Value::Array(items) => 1 + items.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// 校验新链接的全部字段
+///
+/// 收集所有字段错误，顺序固定为 target、code、expires_at、extras。
+/// 目标 URL 中的凭据按 `links.credential_policy` 处理，`reject` 时记为 target 错误。
+pub fn validate_new_link(
+    input: LinkInput<'_>,
+    profile: ValidationProfile,
+) -> Result<ValidatedLink, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut field = |field, result: Result<_, ShortlinkerError>| {
+        result
+            .map_err(|error| errors.push(FieldError { field, error }))
+            .ok()
+    };
+
+    let target = field(
+        LinkField::Target,
+        validate_target(input.target).and_then(|()| credential_rules().apply(input.target)),
+    );
+    let code = field(LinkField::Code, validate_code(input.code, profile.code));
+    let expiry = field(
+        LinkField::ExpiresAt,
+        validate_expiry(input.expires_at, profile),
+    );
+    let extras = field(LinkField::Extras, validate_extras(input.extras));
+
+    match (target, code, expiry, extras) {
+        (Some(target), Some(code), Some(expiry), Some(extras)) => Ok(ValidatedLink {
+            code,
+            target: target.target,
+            credential_warning: target.warning,
+            expires_at: expiry.expires_at(),
+            expiry_in_past: expiry.is_past(Utc::now()),
+            extras,
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// 格式化时间用于展示，如 `2025-01-01 08:00:00 UTC`
+pub fn format_display_time(dt: DateTime<Utc>) -> String {
+    dt.format(DISPLAY_TIME_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>(
+        code: Option<&'a str>,
+        target: &'a str,
+        expires_at: Option<&'a str>,
+    ) -> LinkInput<'a> {
+        LinkInput {
+            code,
+            target,
+            expires_at,
+            extras: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_link_all_profiles() {
+        for profile in [
+            ValidationProfile::INTERACTIVE,
+            ValidationProfile::BATCH_CREATE,
+            ValidationProfile::IMPORT,
+        ] {
+            let link = validate_new_link(
+                input(
+                    Some("abc"),
+                    "https://example.com",
+                    Some("2030-01-01T00:00:00Z"),
+                ),
+                profile,
+            )
+            .unwrap();
+            assert_eq!(link.code.as_deref(), Some("abc"));
+            assert_eq!(
+                link.expires_at.unwrap().to_rfc3339(),
+                "2030-01-01T00:00:00+00:00"
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors_collected_in_field_order() {
+        let errors = validate_new_link(
+            LinkInput {
+                extras: Some("[1]"),
+                ..input(Some("bad code"), "javascript:alert(1)", Some("soon"))
+            },
+            ValidationProfile::INTERACTIVE,
+        )
+        .unwrap_err();
+        let fields: Vec<_> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(
+            fields,
+            vec![
+                LinkField::Target,
+                LinkField::Code,
+                LinkField::ExpiresAt,
+                LinkField::Extras
+            ]
+        );
+    }
+
+    #[test]
+    fn test_credentials_in_target_warn_by_default() {
+        let link = validate_new_link(
+            input(None, "https://example.com/a?Token=abc", None),
+            ValidationProfile::INTERACTIVE,
+        )
+        .unwrap();
+        assert_eq!(link.target, "https://example.com/a?Token=abc");
+        assert!(link.credential_warning.unwrap().contains("'Token'"));
+
+        let link = validate_new_link(
+            input(None, "https://example.com/a", None),
+            ValidationProfile::INTERACTIVE,
+        )
+        .unwrap();
+        assert!(link.credential_warning.is_none());
+    }
+
+    // ---- extras ----
+
+    #[test]
+    fn test_extras_normalized() {
+        assert_eq!(
+            validate_extras(Some(
+                r#" { "ticket" : "JIRA-123", "meta": {"tags": ["a", 1]} } "#
+            ))
+            .unwrap()
+            .as_deref(),
+            Some(r#"{"meta":{"tags":["a",1]},"ticket":"JIRA-123"}"#)
+        );
+        for empty in [None, Some(""), Some("  "), Some("{}")] {
+            assert_eq!(validate_extras(empty).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_extras_rejects_non_object_and_bad_keys() {
+        for invalid in [
+            "[1, 2]",
+            r#""text""#,
+            "42",
+            "null",
+            "{not json",
+            r#"{"bad key": 1}"#,
+            r#"{"": 1}"#,
+        ] {
+            assert!(validate_extras(Some(invalid)).is_err(), "{}", invalid);
+        }
+        let long_key = format!(r#"{{"{}": 1}}"#, "k".repeat(EXTRAS_MAX_KEY_LEN + 1));
+        assert!(validate_extras(Some(&long_key)).is_err());
+    }
+
+    #[test]
+    fn test_extras_limits() {
+        assert!(validate_extras(Some(r#"{"a": {"b": [1]}}"#)).is_ok());
+        assert!(validate_extras(Some(r#"{"a": {"b": [[1]]}}"#)).is_err());
+
+        let keys = |n: usize| {
+            let fields: Vec<_> = (0..n).map(|i| format!(r#""k{}": {}"#, i, i)).collect();
+            format!("{{{}}}", fields.join(","))
+        };
+        assert!(validate_extras(Some(&keys(EXTRAS_MAX_KEYS))).is_ok());
+        assert!(validate_extras(Some(&keys(EXTRAS_MAX_KEYS + 1))).is_err());
+
+        let big = format!(r#"{{"note": "{}"}}"#, "x".repeat(EXTRAS_MAX_BYTES));
+        assert!(validate_extras(Some(&big)).is_err());
+    }
+
+    // ---- 历史分歧：相对时间 ----
+
+    #[test]
+    fn test_relative_expiry_accepted_by_interactive_and_batch() {
+        for profile in [
+            ValidationProfile::INTERACTIVE,
+            ValidationProfile::BATCH_CREATE,
+        ] {
+            let expires = validate_expires_at(Some("1d"), profile).unwrap().unwrap();
+            let hours = (expires - Utc::now()).num_hours();
+            assert!((23..=24).contains(&hours));
+        }
+    }
+
+    #[test]
+    fn test_relative_expiry_rejected_by_import() {
+        assert!(matches!(
+            validate_expires_at(Some("1d"), ValidationProfile::IMPORT),
+            Err(ShortlinkerError::LinkInvalidExpireTime(_))
+        ));
+    }
+
+    #[test]
+    fn test_absolute_expiry_same_for_every_profile() {
+        // 日期、不带偏移的日期时间在所有入口按同一规则解释
+        let rules = expiry_input_rules();
+        for input in [
+            "2024-12-31",
+            "2024-12-31 18:00",
+            "2024-12-31T18:00:00+08:00",
+        ] {
+            let expected = TimeParser::parse_absolute_time(input, &rules).unwrap();
+            for profile in [
+                ValidationProfile::INTERACTIVE,
+                ValidationProfile::BATCH_CREATE,
+                ValidationProfile::IMPORT,
+            ] {
+                assert_eq!(
+                    validate_expires_at(Some(input), profile).unwrap(),
+                    Some(expected),
+                    "{}",
+                    input
+                );
+            }
+        }
+    }
+
+    // ---- 过期时间语义表（所有入口一致） ----
+
+    const ALL_PROFILES: [ValidationProfile; 3] = [
+        ValidationProfile::INTERACTIVE,
+        ValidationProfile::BATCH_CREATE,
+        ValidationProfile::IMPORT,
+    ];
+
+    #[test]
+    fn test_never_expire_inputs() {
+        for profile in ALL_PROFILES {
+            for input in [None, Some(""), Some("  "), Some("never"), Some("NEVER")] {
+                assert_eq!(
+                    validate_expiry(input, profile).unwrap(),
+                    ExpiryValue::Never,
+                    "{:?}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_now_expires_immediately() {
+        for profile in ALL_PROFILES {
+            for input in ["now", "NOW", " now "] {
+                let before = Utc::now();
+                let value = validate_expiry(Some(input), profile).unwrap();
+                assert!(matches!(value, ExpiryValue::Now(dt) if dt >= before && dt <= Utc::now()));
+                // 显式 now 不算"过去的时间"
+                assert!(!value.is_past(Utc::now()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_past_time_allowed_and_flagged() {
+        for profile in ALL_PROFILES {
+            let value = validate_expiry(Some("2000-01-01T00:00:00Z"), profile).unwrap();
+            assert_eq!(
+                value.expires_at().unwrap().to_rfc3339(),
+                "2000-01-01T00:00:00+00:00"
+            );
+            assert!(value.is_past(Utc::now()));
+
+            let link = validate_new_link(
+                input(Some("old"), "https://example.com", Some("2000-01-01")),
+                profile,
+            )
+            .unwrap();
+            assert!(link.expiry_in_past);
+        }
+        let future = validate_expiry(Some("2099-01-01"), ValidationProfile::IMPORT).unwrap();
+        assert!(!future.is_past(Utc::now()));
+    }
+
+    #[test]
+    fn test_invalid_expiry_rejected_by_every_profile() {
+        for profile in ALL_PROFILES {
+            for input in ["0", "garbage", "2023-02-30", "never-ever"] {
+                assert!(
+                    matches!(
+                        validate_expires_at(Some(input), profile),
+                        Err(ShortlinkerError::LinkInvalidExpireTime(_))
+                    ),
+                    "{}",
+                    input
+                );
+            }
+        }
+        let err = parse_expiry_value(Some("0"), true).unwrap_err();
+        assert!(err.contains("never") && err.contains("now"));
+    }
+
+    // ---- 历史分歧：短码检查 ----
+
+    #[test]
+    fn test_strict_code_rejects_charset_and_reserved() {
+        assert!(matches!(
+            validate_code(Some("bad code"), CodeCheck::Strict),
+            Err(ShortlinkerError::LinkInvalidCode(_))
+        ));
+        // RuntimeConfig 未初始化时使用默认保留前缀
+        assert!(matches!(
+            validate_code(Some("admin"), CodeCheck::Strict),
+            Err(ShortlinkerError::LinkReservedCode(_))
+        ));
+    }
+
+    #[test]
+    fn test_batch_create_does_not_check_code_format() {
+        assert_eq!(
+            validate_code(Some("bad code"), ValidationProfile::BATCH_CREATE.code).unwrap(),
+            Some("bad code".to_string())
+        );
+    }
+
+    #[test]
+    fn test_import_requires_code_but_not_format() {
+        assert!(matches!(
+            validate_code(Some(""), CodeCheck::NonEmpty),
+            Err(ShortlinkerError::LinkInvalidCode(_))
+        ));
+        assert!(validate_code(Some("admin"), CodeCheck::NonEmpty).is_ok());
+    }
+
+    #[test]
+    fn test_missing_code_means_generate() {
+        assert_eq!(validate_code(None, CodeCheck::Strict).unwrap(), None);
+        assert_eq!(validate_code(Some(""), CodeCheck::Unchecked).unwrap(), None);
+    }
+
+    #[test]
+    fn test_format_display_time() {
+        let dt = DateTime::parse_from_rfc3339("2025-01-02T03:04:05+08:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(format_display_time(dt), "2025-01-01 19:04:05 UTC");
+    }
+}
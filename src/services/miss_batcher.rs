@@ -0,0 +1,224 @@
+//! 缓存 miss 回源的微批量合并
+//!
+//! 高并发下大量不同短码同时 miss 时，逐个单行查询会占满连接池。启用
+//! `cache.miss_batch` 后，回源请求进入收集器：在 `window_ms` 窗口内或凑满
+//! `max_batch_size` 个短码后合并为一次 `IN` 查询，结果分发回各等待者；同一
+//! 短码的并发请求共享同一个结果。
+//!
+//! - 低负载时（当前没有其他回源在进行）直接单查，不引入窗口延迟；窗口结束时
+//!   只收集到 1 个短码同样走单查
+//! - 批查询在独立任务中执行，发起者被取消不影响同批的其他等待者
+//! - 执行前剔除已取消（接收端关闭）的等待者；等待超过 [`MISS_BATCH_WAIT_TIMEOUT`]
+//!   视为失败
+//! - 合并结果不绕过回填版本校验：调用方在 `load` 之前取 `FillToken`，批内任一
+//!   短码在查询期间被写入，其结果回填时同样会被丢弃
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tracing::{debug, trace};
+
+use crate::config::MissBatchConfig;
+use crate::errors::{Result, ShortlinkerError};
+use crate::metrics::MetricsRecorder;
+use crate::storage::{SeaOrmStorage, ShortLink};
+
+/// 等待批查询结果的上限
+pub const MISS_BATCH_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Waiter = oneshot::Sender<Result<Option<ShortLink>>>;
+
+/// 收集中的一批短码
+struct PendingBatch {
+    id: u64,
+    waiters: HashMap<String, Vec<Waiter>>,
+    /// 凑满时把本批交给执行任务，不再等待窗口结束
+    flush_now: oneshot::Sender<HashMap<String, Vec<Waiter>>>,
+}
+
+struct Inner {
+    storage: Arc<SeaOrmStorage>,
+    metrics: Arc<dyn MetricsRecorder>,
+    window: Duration,
+    max_batch_size: usize,
+    pending: Mutex<Option<PendingBatch>>,
+    next_id: AtomicU64,
+}
+
+/// 缓存 miss 回源的微批量收集器
+pub struct MissBatcher {
+    inner: Arc<Inner>,
+    enabled: bool,
+    /// 正在进行的回源数（含单查与等待批结果）
+    in_flight: AtomicUsize,
+}
+
+impl MissBatcher {
+    pub fn new(
+        storage: Arc<SeaOrmStorage>,
+        config: &MissBatchConfig,
+        metrics: Arc<dyn MetricsRecorder>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                storage,
+                metrics,
+                window: Duration::from_millis(config.window_ms),
+                max_batch_size: config.max_batch_size.max(1),
+                pending: Mutex::new(None),
+                next_id: AtomicU64::new(0),
+            }),
+            enabled: config.enabled,
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 回源查询一个短码
+    pub async fn load(&self, code: &str) -> Result<Option<ShortLink>> {
+        let concurrent = self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let _guard = InFlightGuard(&self.in_flight);
+
+        if !self.enabled || concurrent == 0 {
+            self.inner.metrics.inc_cache_miss_load("direct");
+            return self.inner.storage.get(code).await;
+        }
+
+        let rx = self.inner.enqueue(code);
+        match tokio::time::timeout(MISS_BATCH_WAIT_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(ShortlinkerError::database_operation(
+                "Micro-batched lookup was dropped before completing",
+            )),
+            Err(_) => Err(ShortlinkerError::database_operation(format!(
+                "Micro-batched lookup for '{}' timed out after {:?}",
+                code, MISS_BATCH_WAIT_TIMEOUT
+            ))),
+        }
+    }
+}
+
+struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Inner {
+    /// 把短码加入当前批；没有收集中的批时开启新批并启动执行任务
+    fn enqueue(self: &Arc<Self>, code: &str) -> oneshot::Receiver<Result<Option<ShortLink>>> {
+        let (tx, rx) = oneshot::channel();
+        let mut slot = self.pending.lock().expect("miss batch lock poisoned");
+
+        let batch = slot.get_or_insert_with(|| {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let (flush_now, flush_rx) = oneshot::channel();
+            tokio::spawn(self.clone().run_batch(id, flush_rx));
+            PendingBatch {
+                id,
+                waiters: HashMap::new(),
+                flush_now,
+            }
+        });
+        batch.waiters.entry(code.to_string()).or_default().push(tx);
+
+        if batch.waiters.len() >= self.max_batch_size
+            && let Some(PendingBatch {
+                waiters, flush_now, ..
+            }) = slot.take()
+        {
+            trace!(codes = waiters.len(), "miss batch full, flushing early");
+            let _ = flush_now.send(waiters);
+        }
+        rx
+    }
+
+    /// 等待窗口结束或批次凑满，然后执行查询并分发结果
+    async fn run_batch(
+        self: Arc<Self>,
+        id: u64,
+        mut flush_rx: oneshot::Receiver<HashMap<String, Vec<Waiter>>>,
+    ) {
+        let early = tokio::select! {
+            biased;
+            waiters = &mut flush_rx => waiters.ok(),
+            _ = tokio::time::sleep(self.window) => None,
+        };
+        let waiters = match early {
+            Some(waiters) => waiters,
+            None => {
+                let taken = {
+                    let mut slot = self.pending.lock().expect("miss batch lock poisoned");
+                    if slot.as_ref().is_some_and(|batch| batch.id == id) {
+                        slot.take().map(|batch| batch.waiters)
+                    } else {
+                        None
+                    }
+                };
+                // 窗口结束的同时批次被凑满：结果已在 flush 通道中
+                match taken {
+                    Some(waiters) => waiters,
+                    None => match flush_rx.await {
+                        Ok(waiters) => waiters,
+                        Err(_) => return,
+                    },
+                }
+            }
+        };
+        self.execute(waiters).await;
+    }
+
+    async fn execute(&self, mut waiters: HashMap<String, Vec<Waiter>>) {
+        // 已取消或超时的等待者不再占用查询
+        waiters.retain(|_, senders| {
+            senders.retain(|tx| !tx.is_closed());
+            !senders.is_empty()
+        });
+        if waiters.is_empty() {
+            return;
+        }
+
+        self.metrics.observe_miss_batch_size(waiters.len() as f64);
+        let result = if waiters.len() == 1 {
+            self.metrics.inc_cache_miss_load("single");
+            let code = waiters.keys().next().expect("one waiter");
+            self.storage.get(code).await.map(|link| {
+                link.map(|l| HashMap::from([(code.clone(), l)]))
+                    .unwrap_or_default()
+            })
+        } else {
+            self.metrics.inc_cache_miss_load("batch");
+            let codes: Vec<&str> = waiters.keys().map(String::as_str).collect();
+            self.storage.batch_get(&codes).await
+        };
+
+        match result {
+            Ok(found) => {
+                debug!(
+                    codes = waiters.len(),
+                    found = found.len(),
+                    "miss batch resolved"
+                );
+                for (code, senders) in waiters {
+                    let link = found.get(&code);
+                    for tx in senders {
+                        let _ = tx.send(Ok(link.cloned()));
+                    }
+                }
+            }
+            Err(e) => {
+                for tx in waiters.into_values().flatten() {
+                    let _ = tx.send(Err(e.clone()));
+                }
+            }
+        }
+    }
+}
@@ -10,26 +10,62 @@
 //! ## 例外（已文档化）
 //! - `redirect` handler：热路径，直连 Storage + Cache（见 `api/services/redirect.rs`）
 //! - `health` handler：基础设施路径，直连 Storage + Cache（见 `api/services/health.rs`）
+//! - `badge` handler：只读点击数，直连 Storage（见 `api/services/badge.rs`）
 //!
 //! ## Service 清单
 //! - [`LinkService`]：链接 CRUD、批量操作、导入导出
 //! - [`AnalyticsService`]：点击分析、趋势、导出
 //! - [`ConfigService`]：运行时配置管理
+//! - [`link_validation`]：链接字段校验（各入口通过 `ValidationProfile` 显式区分行为）
+//! - [`link_template`]：模板批量生成的变量展开与校验
+//! - [`target_credentials`]：目标 URL 中的凭据检测（`links.credential_policy`）
+//! - [`firewall`]：请求特征拦截规则（redirect / admin 入口中间件使用）
+//! - [`not_found_pacing`]：redirect 404 的恒定时延与按 IP 分级 tarpit
+//! - [`auto_ban`]：404 洪泛来源 IP 的自动封禁（Firewall 中间件执行）
+//! - [`badge`]：点击数徽章 SVG 的生成（`/badge/{code}.svg` 使用）
+//! - [`MissBatcher`]：redirect 缓存 miss 回源的跨短码微批量合并
+//! - [`bloom_stats`]：Bloom 判定结果分类、观测假阳率与容量建议
+//! - `redirect_filter`：redirect 决策阶段的 WASM 过滤插件（实验性，需 `wasm-plugins` feature）
+//! - [`SideEffectRunner`]：写操作收尾副作用（缓存刷新）的即时执行与崩溃后重放
+//! - [`ApiTokenService`]：团队 API Token 与按 token 的链接配额
 
 mod analytics_service;
+mod api_token_service;
+pub mod auto_ban;
+pub mod badge;
+pub mod bloom_stats;
 mod config_service;
+pub mod firewall;
 pub mod geoip;
 pub mod import_validation;
 mod link_cache;
+mod link_l1_cache;
 mod link_service;
+pub mod link_template;
+pub mod link_validation;
+mod miss_batcher;
+pub mod not_found_pacing;
+#[cfg(feature = "wasm-plugins")]
+pub mod redirect_filter;
+mod side_effects;
+pub mod target_credentials;
 mod user_agent_store;
 
 pub use analytics_service::*;
+pub use api_token_service::{
+    API_TOKEN_PREFIX, ApiTokenQuotas, ApiTokenService, ApiTokenUsage, CreatedApiToken,
+    QUOTA_ALERT_PERCENT, parse_api_token,
+};
+pub use bloom_stats::{BloomOutcome, BloomStats};
 pub use config_service::*;
 pub use geoip::{GeoInfo, GeoIpLookup, GeoIpProvider};
 pub use import_validation::{
-    ImportLinkItemRaw, ImportRowError, validate_import_row, validate_import_rows,
+    ImportLinkItemRaw, ImportRowError, ImportRowWarning, ValidatedImportRow, validate_import_row,
+    validate_import_row_checked, validate_import_rows,
 };
 pub use link_cache::*;
 pub use link_service::*;
+pub use link_template::{LinkTemplate, TemplateLink, TemplateVar};
+pub use miss_batcher::{MISS_BATCH_WAIT_TIMEOUT, MissBatcher};
+pub use side_effects::{MAX_REPLAY_ATTEMPTS, REPLAY_GRACE, ReplayReport, SideEffectRunner};
 pub use user_agent_store::{UserAgentStore, get_user_agent_store, set_global_user_agent_store};
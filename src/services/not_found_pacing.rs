@@ -0,0 +1,187 @@
+//! 不存在短码的恒定时延响应（防枚举侧信道）
+//!
+//! redirect 判定 404 的路径耗时差异明显：负缓存 / Bloom 否定在亚毫秒内返回，
+//! Bloom 假阳需要查库（数毫秒）。开启 `redirect.constant_time_404` 后，所有 404
+//! 在返回前补齐到 `redirect.not_found_delay_ms`（±20% 抖动），外部无法再通过
+//! 时延区分请求走了哪条路径。补齐用异步 sleep，不占 worker 线程；307 路径不受影响。
+//!
+//! 同一 IP 在 [`TARPIT_WINDOW`] 内产生的 404 超过阈值后按 [`TARPIT_TIERS`]
+//! 追加延迟（分级 tarpit），拖慢批量枚举。按 IP 的计数存放在有容量上限的 moka 缓存里，
+//! 闲置超过一个窗口的条目自动过期，超出 `MAX_TRACKED_IPS` 时由 moka 淘汰，
+//! 记录一次 404 的开销与跟踪的 IP 数无关。
+
+use std::net::IpAddr;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use moka::sync::Cache;
+
+/// 404 目标时延的默认值（毫秒）
+pub const DEFAULT_NOT_FOUND_DELAY_MS: u64 = 5;
+
+/// 目标时延的抖动比例
+const JITTER_RATIO: f64 = 0.2;
+
+/// 按 IP 统计 404 的窗口
+pub const TARPIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// 分级 tarpit：窗口内 404 数达到阈值后追加的延迟（按阈值升序）
+pub const TARPIT_TIERS: [(u32, Duration); 3] = [
+    (20, Duration::from_millis(100)),
+    (50, Duration::from_millis(500)),
+    (100, Duration::from_secs(2)),
+];
+
+/// 跟踪的 IP 数上限，超过后由缓存淘汰
+const MAX_TRACKED_IPS: u64 = 100_000;
+
+/// 一次 404 需要补齐的延迟
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotFoundDelay {
+    /// 补齐到目标时延的部分
+    pub pad: Duration,
+    /// 分级 tarpit 追加的部分
+    pub tarpit: Duration,
+}
+
+impl NotFoundDelay {
+    pub fn total(&self) -> Duration {
+        self.pad + self.tarpit
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IpWindow {
+    started: Instant,
+    count: u32,
+}
+
+/// 按 IP 统计 404 并计算补齐延迟
+#[derive(Debug)]
+pub struct NotFoundPacer {
+    windows: Cache<IpAddr, IpWindow>,
+}
+
+impl Default for NotFoundPacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NotFoundPacer {
+    pub fn new() -> Self {
+        Self {
+            // 闲置满一个窗口的计数必然已过期，可以直接丢弃
+            windows: Cache::builder()
+                .max_capacity(MAX_TRACKED_IPS)
+                .time_to_idle(TARPIT_WINDOW)
+                .build(),
+        }
+    }
+
+    /// 记录一次 404，返回应补齐的延迟
+    ///
+    /// `elapsed` 为请求已耗费的时间；客户端 IP 未知时只补齐、不计 tarpit。
+    pub fn delay_for(
+        &self,
+        target: Duration,
+        elapsed: Duration,
+        ip: Option<IpAddr>,
+        now: Instant,
+    ) -> NotFoundDelay {
+        let tarpit = ip.map_or(Duration::ZERO, |ip| self.record(ip, now));
+        NotFoundDelay {
+            pad: jittered(target).saturating_sub(elapsed),
+            tarpit,
+        }
+    }
+
+    /// 记录一次 404，返回该 IP 当前档位的 tarpit 延迟
+    pub fn record(&self, ip: IpAddr, now: Instant) -> Duration {
+        let count = self
+            .windows
+            .entry(ip)
+            .and_upsert_with(|existing| match existing.map(|entry| entry.into_value()) {
+                Some(window) if now.duration_since(window.started) < TARPIT_WINDOW => IpWindow {
+                    started: window.started,
+                    count: window.count.saturating_add(1),
+                },
+                _ => IpWindow {
+                    started: now,
+                    count: 1,
+                },
+            })
+            .into_value()
+            .count;
+
+        TARPIT_TIERS
+            .iter()
+            .rev()
+            .find(|(threshold, _)| count > *threshold)
+            .map_or(Duration::ZERO, |(_, delay)| *delay)
+    }
+}
+
+/// 全局 404 计数器（redirect handler 使用）
+pub fn not_found_pacer() -> &'static NotFoundPacer {
+    static PACER: LazyLock<NotFoundPacer> = LazyLock::new(NotFoundPacer::new);
+    &PACER
+}
+
+fn jittered(target: Duration) -> Duration {
+    let factor = 1.0 - JITTER_RATIO + rand::random::<f64>() * 2.0 * JITTER_RATIO;
+    target.mul_f64(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_stays_within_jitter_band() {
+        let pacer = NotFoundPacer::new();
+        let now = Instant::now();
+        let target = Duration::from_millis(10);
+        for _ in 0..100 {
+            let delay = pacer.delay_for(target, Duration::from_millis(1), None, now);
+            assert!(delay.pad >= Duration::from_millis(7));
+            assert!(delay.pad <= Duration::from_millis(11));
+            assert_eq!(delay.tarpit, Duration::ZERO);
+        }
+        // 已超过目标时延的请求不再补齐
+        let slow = pacer.delay_for(target, Duration::from_millis(50), None, now);
+        assert_eq!(slow.pad, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_tarpit_escalates_per_ip_and_resets_after_window() {
+        let pacer = NotFoundPacer::new();
+        let now = Instant::now();
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "203.0.113.8".parse().unwrap();
+
+        let delays: Vec<Duration> = (0..101).map(|_| pacer.record(ip, now)).collect();
+        assert_eq!(delays[19], Duration::ZERO);
+        assert_eq!(delays[20], Duration::from_millis(100));
+        assert_eq!(delays[50], Duration::from_millis(500));
+        assert_eq!(delays[100], Duration::from_secs(2));
+
+        // 其他 IP 不受影响
+        assert_eq!(pacer.record(other, now), Duration::ZERO);
+        // 窗口过期后重新计数
+        assert_eq!(pacer.record(ip, now + TARPIT_WINDOW), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_tracked_ips_stay_bounded() {
+        let pacer = NotFoundPacer::new();
+        let now = Instant::now();
+        for i in 0..MAX_TRACKED_IPS + 1_000 {
+            let ip = IpAddr::from(std::net::Ipv6Addr::from(u128::from(i)));
+            pacer.record(ip, now);
+        }
+
+        pacer.windows.run_pending_tasks();
+        assert!(pacer.windows.entry_count() <= MAX_TRACKED_IPS);
+    }
+}
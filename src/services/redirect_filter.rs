@@ -0,0 +1,398 @@
+//! WASM redirect 过滤插件（实验性，需 `wasm-plugins` feature）
+//!
+//! 配置 `plugins.redirect_filter` 后，redirect 在找到链接之后、点击计数之前调用
+//! 插件，由插件决定放行、拒绝或改写目标。插件用 wasmtime 执行，每次调用受
+//! `plugins.timeout_ms`（epoch 中断，精度 1ms）与 `plugins.max_memory_mb`（线性内存
+//! 上限）约束；超时、超内存、trap 或输出非法时按 `plugins.fail_mode` 放行（`open`）
+//! 或返回 503（`closed`）。
+//!
+//! # 模块约定（JSON in / JSON out）
+//!
+//! 模块不能有导入，需导出：
+//!
+//! - `memory`：线性内存
+//! - `alloc(len: i32) -> i32`：分配 `len` 字节并返回指针，宿主把输入 JSON 写到这里
+//! - `filter(ptr: i32, len: i32) -> i64`：处理输入，返回 `(out_ptr << 32) | out_len`
+//! - `dealloc(ptr: i32, len: i32)`（可选）：调用结束后宿主用它释放输入与输出缓冲区
+//!
+//! 输入：
+//!
+//! ```json
+//! {"code":"promo","target":"https://example.com/","headers":{"user-agent":"..."},"client_ip":"203.0.113.7"}
+//! ```
+//!
+//! `headers` 只包含 `plugins.forward_headers` 中列出且请求实际携带的头（名称小写，
+//! 多值以 `, ` 连接）；`client_ip` 按 `api.trusted_proxies` 解析，无法确定时为 `null`。
+//!
+//! 输出：
+//!
+//! ```json
+//! {"action":"allow"}
+//! {"action":"deny","status":403}
+//! {"action":"override","target":"https://example.com/other"}
+//! ```
+//!
+//! `deny` 的 `status` 缺省为 403，只接受 4xx / 5xx；`override` 的目标须为 http(s) URL。
+//!
+//! 实例在池中复用（模块内的全局状态会跨请求保留），调用出错的实例直接丢弃。
+//! 调用在 redirect 所在的 worker 线程上同步执行，最长阻塞 `timeout_ms`。
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use actix_web::http::header::HeaderMap;
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+use wasmtime::{
+    Config, Engine, InstancePre, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+use crate::config::PluginsConfig;
+
+/// epoch 递增间隔，即超时判定的精度
+const EPOCH_TICK: Duration = Duration::from_millis(1);
+
+/// `deny` 未指定状态码时使用
+const DEFAULT_DENY_STATUS: u16 = 403;
+
+/// fail-close 时返回的状态码
+pub const FAIL_CLOSED_STATUS: u16 = 503;
+
+/// 插件单次输出的字节上限
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+static REDIRECT_FILTER: OnceLock<RedirectFilter> = OnceLock::new();
+
+/// 设置全局 redirect 过滤插件（仅首次调用生效）
+pub fn set_redirect_filter(filter: RedirectFilter) {
+    let _ = REDIRECT_FILTER.set(filter);
+}
+
+/// 全局 redirect 过滤插件，未配置时返回 `None`
+#[inline]
+pub fn get_redirect_filter() -> Option<&'static RedirectFilter> {
+    REDIRECT_FILTER.get()
+}
+
+/// 插件出错时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailMode {
+    /// 按放行处理
+    Open,
+    /// 返回 [`FAIL_CLOSED_STATUS`]
+    Closed,
+}
+
+impl FailMode {
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "open" => Self::Open,
+            "closed" => Self::Closed,
+            other => {
+                tracing::warn!(
+                    value = other,
+                    "unknown plugins.fail_mode, falling back to \"open\""
+                );
+                Self::Open
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+/// 插件执行参数
+#[derive(Debug, Clone)]
+pub struct FilterSettings {
+    pub fail_mode: FailMode,
+    pub timeout: Duration,
+    pub max_memory_bytes: usize,
+    pub pool_size: usize,
+    /// 小写的请求头名称
+    pub forward_headers: Vec<String>,
+}
+
+impl FilterSettings {
+    pub fn from_config(config: &PluginsConfig) -> Self {
+        Self {
+            fail_mode: FailMode::from_config(&config.fail_mode),
+            timeout: Duration::from_millis(config.timeout_ms.max(1)),
+            max_memory_bytes: usize::try_from(config.max_memory_mb.saturating_mul(1024 * 1024))
+                .unwrap_or(usize::MAX),
+            pool_size: config.pool_size,
+            forward_headers: config
+                .forward_headers
+                .iter()
+                .map(|name| name.trim().to_ascii_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// 传给插件的输入
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterInput<'a> {
+    pub code: &'a str,
+    pub target: &'a str,
+    pub headers: BTreeMap<String, String>,
+    pub client_ip: Option<String>,
+}
+
+/// 插件的决策
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FilterDecision {
+    Allow,
+    Deny {
+        #[serde(default = "default_deny_status")]
+        status: u16,
+    },
+    Override {
+        target: String,
+    },
+}
+
+fn default_deny_status() -> u16 {
+    DEFAULT_DENY_STATUS
+}
+
+impl FilterDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny { .. } => "deny",
+            Self::Override { .. } => "override",
+        }
+    }
+
+    fn validate(self) -> anyhow::Result<Self> {
+        match &self {
+            Self::Allow => {}
+            Self::Deny { status } => {
+                if !(400..=599).contains(status) {
+                    bail!("deny status must be 4xx or 5xx, got {}", status);
+                }
+            }
+            Self::Override { target } => {
+                aster_forge_utils::url::parse_http_url(target, "override target")?;
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// 池中的一个插件实例
+struct PluginInstance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    filter: TypedFunc<(i32, i32), i64>,
+    dealloc: Option<TypedFunc<(i32, i32), ()>>,
+}
+
+impl PluginInstance {
+    fn call(&mut self, payload: &[u8], deadline_ticks: u64) -> anyhow::Result<Vec<u8>> {
+        self.store.set_epoch_deadline(deadline_ticks);
+
+        let len = i32::try_from(payload.len()).context("input too large")?;
+        let ptr = self.alloc.call(&mut self.store, len)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, payload)
+            .context("alloc returned an out-of-bounds buffer")?;
+
+        let packed = self.filter.call(&mut self.store, (ptr, len))? as u64;
+        let out_ptr = (packed >> 32) as usize;
+        let out_len = (packed & 0xffff_ffff) as usize;
+        if out_len > MAX_OUTPUT_BYTES {
+            bail!("output exceeds {} bytes", MAX_OUTPUT_BYTES);
+        }
+        let mut output = vec![0u8; out_len];
+        self.memory
+            .read(&self.store, out_ptr, &mut output)
+            .context("filter returned an out-of-bounds buffer")?;
+
+        if let Some(dealloc) = self.dealloc {
+            dealloc.call(&mut self.store, (ptr, len))?;
+            dealloc.call(&mut self.store, (out_ptr as i32, out_len as i32))?;
+        }
+        Ok(output)
+    }
+}
+
+/// redirect 过滤插件：编译后的模块加实例池
+pub struct RedirectFilter {
+    engine: Engine,
+    pre: InstancePre<StoreLimits>,
+    pool: Mutex<Vec<PluginInstance>>,
+    settings: FilterSettings,
+}
+
+impl std::fmt::Debug for RedirectFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedirectFilter")
+            .field("settings", &self.settings)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedirectFilter {
+    /// 按 `[plugins]` 配置加载，未配置 `redirect_filter` 时返回 `None`
+    pub fn load(config: &PluginsConfig) -> anyhow::Result<Option<Self>> {
+        let Some(path) = config.redirect_filter.as_deref().filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        Self::from_file(path, FilterSettings::from_config(config)).map(Some)
+    }
+
+    /// 从 `.wasm` / `.wat` 文件加载
+    pub fn from_file(path: &str, settings: FilterSettings) -> anyhow::Result<Self> {
+        let engine = Self::engine()?;
+        let module = Module::from_file(&engine, path)
+            .with_context(|| format!("failed to load redirect filter plugin '{}'", path))?;
+        Self::new(engine, module, settings)
+    }
+
+    /// 从内存中的 WASM 二进制或 WAT 文本加载
+    pub fn from_bytes(bytes: &[u8], settings: FilterSettings) -> anyhow::Result<Self> {
+        let engine = Self::engine()?;
+        let module = Module::new(&engine, bytes).context("failed to compile redirect filter")?;
+        Self::new(engine, module, settings)
+    }
+
+    fn engine() -> anyhow::Result<Engine> {
+        let mut config = Config::new();
+        config.epoch_interruption(true);
+        Engine::new(&config)
+    }
+
+    fn new(engine: Engine, module: Module, settings: FilterSettings) -> anyhow::Result<Self> {
+        // 不提供任何宿主函数，带导入的模块在这里即失败
+        let pre = Linker::new(&engine)
+            .instantiate_pre(&module)
+            .context("redirect filter plugin must not have imports")?;
+        spawn_epoch_ticker(&engine)?;
+
+        let filter = Self {
+            engine,
+            pre,
+            pool: Mutex::new(Vec::new()),
+            settings,
+        };
+        // 预先实例化一次，尽早暴露缺少导出、初始内存超限等问题
+        let instance = filter.instantiate()?;
+        filter.release(instance);
+        Ok(filter)
+    }
+
+    pub fn settings(&self) -> &FilterSettings {
+        &self.settings
+    }
+
+    /// 出错时按 `fail_mode` 采用的决策
+    pub fn fail_decision(&self) -> FilterDecision {
+        match self.settings.fail_mode {
+            FailMode::Open => FilterDecision::Allow,
+            FailMode::Closed => FilterDecision::Deny {
+                status: FAIL_CLOSED_STATUS,
+            },
+        }
+    }
+
+    /// 提取 `forward_headers` 中列出的请求头
+    pub fn collect_headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut collected = BTreeMap::new();
+        for name in &self.settings.forward_headers {
+            let values: Vec<&str> = headers
+                .get_all(name.as_str())
+                .filter_map(|value| value.to_str().ok())
+                .collect();
+            if !values.is_empty() {
+                collected.insert(name.clone(), values.join(", "));
+            }
+        }
+        collected
+    }
+
+    /// 调用插件，出错时返回 `Err`（由调用方按 [`Self::fail_decision`] 处理）
+    pub fn call(&self, input: &FilterInput<'_>) -> anyhow::Result<FilterDecision> {
+        let payload = serde_json::to_vec(input)?;
+        let pooled = self.pool.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut instance = match pooled {
+            Some(instance) => instance,
+            None => self.instantiate()?,
+        };
+
+        // trap / 超时 / 超内存后实例状态不可信，直接丢弃
+        let output = instance.call(&payload, self.deadline_ticks())?;
+        self.release(instance);
+
+        serde_json::from_slice::<FilterDecision>(&output)
+            .context("invalid redirect filter output")?
+            .validate()
+    }
+
+    fn instantiate(&self) -> anyhow::Result<PluginInstance> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.settings.max_memory_bytes)
+            .instances(1)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        // 模块的 start 函数同样受超时约束
+        store.set_epoch_deadline(self.deadline_ticks());
+
+        let instance = self.pre.instantiate(&mut store)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("redirect filter plugin must export `memory`")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let filter = instance.get_typed_func::<(i32, i32), i64>(&mut store, "filter")?;
+        let dealloc = instance
+            .get_typed_func::<(i32, i32), ()>(&mut store, "dealloc")
+            .ok();
+        Ok(PluginInstance {
+            store,
+            memory,
+            alloc,
+            filter,
+            dealloc,
+        })
+    }
+
+    fn release(&self, instance: PluginInstance) {
+        let mut pool = self.pool.lock().unwrap_or_else(|e| e.into_inner());
+        if pool.len() < self.settings.pool_size {
+            pool.push(instance);
+        }
+    }
+
+    fn deadline_ticks(&self) -> u64 {
+        let ticks = self.settings.timeout.as_nanos() / EPOCH_TICK.as_nanos();
+        u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+    }
+}
+
+/// 后台线程按 [`EPOCH_TICK`] 递增 epoch，engine 释放后退出
+fn spawn_epoch_ticker(engine: &Engine) -> anyhow::Result<()> {
+    let weak = engine.weak();
+    std::thread::Builder::new()
+        .name("wasm-epoch-ticker".to_string())
+        .spawn(move || {
+            while let Some(engine) = weak.upgrade() {
+                engine.increment_epoch();
+                drop(engine);
+                std::thread::sleep(EPOCH_TICK);
+            }
+        })
+        .context("failed to spawn wasm epoch ticker")?;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! 写操作收尾副作用的执行与补偿（outbox）
+//!
+//! 关键写路径在写事务内同时登记副作用（见 `storage::backend::outbox`），
+//! 提交后由 [`SideEffectRunner::run_committed`] 立即执行并删除条目。
+//! 进程在提交与执行之间崩溃、或执行失败时，条目留在表中，
+//! 由后台任务周期性调用 [`SideEffectRunner::replay_pending`] 重放。
+//!
+//! 所有副作用都按"收敛到数据库当前状态"的方式实现，重复执行无害。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{error, warn};
+
+use crate::config::get_config;
+use crate::errors::ShortlinkerError;
+use crate::services::LinkCache;
+use crate::storage::{SeaOrmStorage, SideEffect};
+
+/// 即时执行窗口：创建时间在此之内的条目视为仍由写入方处理，重放时跳过
+pub const REPLAY_GRACE: Duration = Duration::from_secs(10);
+
+/// 单轮重放处理的条目数
+const REPLAY_BATCH_SIZE: u64 = 500;
+
+/// 失败达到该次数后停止重放，条目保留供人工排查
+pub const MAX_REPLAY_ATTEMPTS: i32 = 20;
+
+/// 一次重放的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub replayed: usize,
+    pub failed: usize,
+}
+
+/// 副作用执行器
+#[derive(Clone)]
+pub struct SideEffectRunner {
+    storage: Arc<SeaOrmStorage>,
+    cache: Arc<dyn LinkCache>,
+}
+
+impl SideEffectRunner {
+    pub fn new(storage: Arc<SeaOrmStorage>, cache: Arc<dyn LinkCache>) -> Self {
+        Self { storage, cache }
+    }
+
+    /// 执行单个副作用（幂等）
+    pub async fn execute(&self, effect: &SideEffect) -> Result<(), ShortlinkerError> {
+        match effect {
+            SideEffect::CacheRefresh { code } => {
+                // 先失效再回源：与之竞争的回填（包括并发写入的刷新）若读到更早的行会被丢弃
+                let token = self.cache.invalidate(code).await;
+                match self.storage.get(code).await? {
+                    Some(link) => {
+                        let ttl = link.cache_ttl(get_config().cache.default_ttl);
+                        self.cache.fill(code, link, ttl, token).await;
+                    }
+                    None => self.cache.fill_not_found(code, token).await,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 写事务提交后立即执行；成功的条目删除，失败的留给后台重放
+    ///
+    /// 不返回错误：数据已提交，收尾失败不应让写操作本身失败。
+    pub async fn run_committed(&self, ids: Vec<i64>, effects: &[SideEffect]) {
+        let mut done = Vec::with_capacity(ids.len());
+        for (id, effect) in ids.into_iter().zip(effects) {
+            match self.execute(effect).await {
+                Ok(()) => done.push(id),
+                Err(e) => {
+                    warn!(
+                        "Side effect {:?} failed, will be replayed in background: {}",
+                        effect, e
+                    );
+                    self.record_failure(id, &e.to_string()).await;
+                }
+            }
+        }
+        if let Err(e) = self.storage.delete_side_effects(&done).await {
+            // 条目残留只会导致一次无害的重放
+            warn!("Failed to clear executed side effects: {}", e);
+        }
+    }
+
+    /// 重放创建时间早于 `grace` 的残留条目，直到没有剩余
+    pub async fn replay_pending(&self, grace: Duration) -> Result<ReplayReport, ShortlinkerError> {
+        let created_before = Utc::now()
+            - chrono::Duration::from_std(grace).unwrap_or_else(|_| chrono::Duration::zero());
+        let mut report = ReplayReport::default();
+
+        loop {
+            let pending = self
+                .storage
+                .load_pending_side_effects(created_before, MAX_REPLAY_ATTEMPTS, REPLAY_BATCH_SIZE)
+                .await?;
+            let batch_len = pending.len() as u64;
+
+            let mut done = Vec::with_capacity(pending.len());
+            for entry in pending {
+                let result = match entry.decode() {
+                    Ok(effect) => self.execute(&effect).await.map_err(|e| e.to_string()),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => {
+                        done.push(entry.id);
+                        report.replayed += 1;
+                    }
+                    Err(e) => {
+                        report.failed += 1;
+                        if entry.attempts + 1 >= MAX_REPLAY_ATTEMPTS {
+                            error!(
+                                "Side effect #{} ({}) failed {} times, giving up: {}",
+                                entry.id, entry.effect_type, MAX_REPLAY_ATTEMPTS, e
+                            );
+                        } else {
+                            warn!(
+                                "Side effect #{} ({}) replay failed: {}",
+                                entry.id, entry.effect_type, e
+                            );
+                        }
+                        self.record_failure(entry.id, &e).await;
+                    }
+                }
+            }
+            self.storage.delete_side_effects(&done).await?;
+
+            // 本批有失败项时停止，避免在同一轮内反复读到它们
+            if batch_len < REPLAY_BATCH_SIZE || report.failed > 0 {
+                break;
+            }
+        }
+
+        if report.replayed > 0 || report.failed > 0 {
+            warn!(
+                "Replayed {} pending side effects left by interrupted writes ({} failed)",
+                report.replayed, report.failed
+            );
+        }
+        Ok(report)
+    }
+
+    async fn record_failure(&self, id: i64, error: &str) {
+        if let Err(e) = self.storage.record_side_effect_failure(id, error).await {
+            warn!("Failed to record side effect #{} failure: {}", id, e);
+        }
+    }
+}
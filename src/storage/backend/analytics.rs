@@ -11,16 +11,22 @@ use std::pin::Pin;
 use chrono::{DateTime, NaiveDate, Utc};
 use futures_util::stream::Stream;
 use sea_orm::{
-    ColumnTrait, EntityTrait, ExprTrait, FromQueryResult, JoinType, PaginatorTrait, QueryFilter,
-    QueryOrder, QuerySelect, sea_query::Expr,
+    ColumnTrait, DatabaseConnection, EntityTrait, ExprTrait, FromQueryResult, JoinType,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+    sea_query::{Expr, LikeExpr},
 };
 use tracing::warn;
 
+use crate::analytics::PeriodGranularity;
+use crate::analytics::query_params::{parse_param_counts, parse_params};
 use migration::entities::{
     click_log, click_stats_daily, click_stats_global_daily, click_stats_global_hourly,
-    click_stats_hourly, user_agent,
+    click_stats_hourly, click_stats_monthly, click_stats_weekly, redirect_timing, user_agent,
 };
 
+/// 按自定义参数过滤时单次扫描的明细上限（超出时只统计最新的点击）
+pub const MAX_PARAM_FILTER_ROWS: u64 = 100_000;
+
 // ============ 查询结果类型 ============
 
 /// 趋势查询结果行
@@ -30,6 +36,13 @@ pub struct TrendRow {
     pub count: i64,
 }
 
+/// 周 / 月汇总趋势查询结果行
+#[derive(Debug, FromQueryResult, Clone)]
+pub struct PeriodTrendRow {
+    pub period_start: NaiveDate,
+    pub count: i64,
+}
+
 /// 来源查询结果行
 #[derive(Debug, FromQueryResult, Clone)]
 pub struct ReferrerRow {
@@ -52,6 +65,14 @@ pub struct TopLinkRow {
     pub count: i64,
 }
 
+/// 小时点击数查询结果行
+#[derive(Debug, FromQueryResult, Clone)]
+pub struct HourlyCountRow {
+    pub short_code: String,
+    pub hour_bucket: DateTime<Utc>,
+    pub click_count: i64,
+}
+
 /// UA 统计查询结果行
 #[derive(Debug, FromQueryResult, Clone)]
 pub struct UaStatsRow {
@@ -59,6 +80,13 @@ pub struct UaStatsRow {
     pub count: i64,
 }
 
+/// 自定义 query 参数值统计结果行
+#[derive(Debug, Clone)]
+pub struct ParamValueRow {
+    pub value: String,
+    pub count: i64,
+}
+
 /// Bot 统计原始查询结果
 #[derive(Debug, FromQueryResult)]
 struct BotStatsRaw {
@@ -75,6 +103,29 @@ pub enum GroupBy {
     Month,
 }
 
+/// 按计数降序（计数相同按值）取前 `limit` 个参数值
+fn top_param_values(aggregated: HashMap<String, i64>, limit: usize) -> Vec<ParamValueRow> {
+    let mut items: Vec<_> = aggregated.into_iter().collect();
+    items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    items.truncate(limit);
+    items
+        .into_iter()
+        .map(|(value, count)| ParamValueRow { value, count })
+        .collect()
+}
+
+/// 转义 LIKE 模式中的通配符（以反斜杠为转义符）
+fn escape_like(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 // ============ SeaOrmStorage Analytics 方法 ============
 
 impl super::SeaOrmStorage {
@@ -613,6 +664,87 @@ impl super::SeaOrmStorage {
             .collect())
     }
 
+    /// 从周 / 月汇总表获取链接点击趋势
+    ///
+    /// 返回起始日期落在 `[start, end]` 内的周期，由调用方过滤周起始日。
+    pub async fn get_link_trend_from_period(
+        &self,
+        code: &str,
+        granularity: PeriodGranularity,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> anyhow::Result<Vec<PeriodTrendRow>> {
+        let rows = match granularity {
+            PeriodGranularity::Week => click_stats_weekly::Entity::find()
+                .filter(click_stats_weekly::Column::ShortCode.eq(code))
+                .filter(click_stats_weekly::Column::WeekStart.gte(start))
+                .filter(click_stats_weekly::Column::WeekStart.lte(end))
+                .order_by_asc(click_stats_weekly::Column::WeekStart)
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|r| PeriodTrendRow {
+                    period_start: r.week_start,
+                    count: r.click_count,
+                })
+                .collect(),
+            PeriodGranularity::Month => click_stats_monthly::Entity::find()
+                .filter(click_stats_monthly::Column::ShortCode.eq(code))
+                .filter(click_stats_monthly::Column::MonthStart.gte(start))
+                .filter(click_stats_monthly::Column::MonthStart.lte(end))
+                .order_by_asc(click_stats_monthly::Column::MonthStart)
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|r| PeriodTrendRow {
+                    period_start: r.month_start,
+                    count: r.click_count,
+                })
+                .collect(),
+        };
+        Ok(rows)
+    }
+
+    /// 从周 / 月汇总表获取全局趋势（SQL GROUP BY + SUM）
+    ///
+    /// 返回起始日期落在 `[start, end]` 内的周期，由调用方过滤周起始日。
+    pub async fn get_global_trend_from_period(
+        &self,
+        granularity: PeriodGranularity,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> anyhow::Result<Vec<PeriodTrendRow>> {
+        let rows = match granularity {
+            PeriodGranularity::Week => {
+                click_stats_weekly::Entity::find()
+                    .select_only()
+                    .column_as(click_stats_weekly::Column::WeekStart, "period_start")
+                    .column_as(click_stats_weekly::Column::ClickCount.sum(), "count")
+                    .filter(click_stats_weekly::Column::WeekStart.gte(start))
+                    .filter(click_stats_weekly::Column::WeekStart.lte(end))
+                    .group_by(click_stats_weekly::Column::WeekStart)
+                    .order_by_asc(click_stats_weekly::Column::WeekStart)
+                    .into_model::<PeriodTrendRow>()
+                    .all(&self.db)
+                    .await?
+            }
+            PeriodGranularity::Month => {
+                click_stats_monthly::Entity::find()
+                    .select_only()
+                    .column_as(click_stats_monthly::Column::MonthStart, "period_start")
+                    .column_as(click_stats_monthly::Column::ClickCount.sum(), "count")
+                    .filter(click_stats_monthly::Column::MonthStart.gte(start))
+                    .filter(click_stats_monthly::Column::MonthStart.lte(end))
+                    .group_by(click_stats_monthly::Column::MonthStart)
+                    .order_by_asc(click_stats_monthly::Column::MonthStart)
+                    .into_model::<PeriodTrendRow>()
+                    .all(&self.db)
+                    .await?
+            }
+        };
+        Ok(rows)
+    }
+
     /// 从小时汇总表获取来源统计
     ///
     /// 使用 `source_counts` 字段（utm_source 参数、ref:{domain} 或 direct）
@@ -785,6 +917,116 @@ impl super::SeaOrmStorage {
             .collect())
     }
 
+    /// 从小时汇总表获取自定义 query 参数的取值分布
+    ///
+    /// 读取 `param_counts` 中指定参数的计数，时间范围限制为 90 天。
+    /// 汇总行内被 top-K 截断的值计入 `(other)`。
+    pub async fn get_link_param_values_from_rollup(
+        &self,
+        code: &str,
+        param: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ParamValueRow>> {
+        const MAX_QUERY_DAYS: i64 = 90;
+        if (end - start).num_days() > MAX_QUERY_DAYS {
+            return Err(anyhow::anyhow!(
+                "Time range exceeds {} days limit, please use a smaller range",
+                MAX_QUERY_DAYS
+            ));
+        }
+
+        // 限制查询记录数，防止内存溢出（90天 * 24小时 = 2160）
+        const MAX_HOURLY_RECORDS: u64 = 2160;
+
+        let records: Vec<Option<String>> = click_stats_hourly::Entity::find()
+            .select_only()
+            .column(click_stats_hourly::Column::ParamCounts)
+            .filter(click_stats_hourly::Column::ShortCode.eq(code))
+            .filter(click_stats_hourly::Column::HourBucket.gte(start))
+            .filter(click_stats_hourly::Column::HourBucket.lte(end))
+            .filter(click_stats_hourly::Column::ParamCounts.is_not_null())
+            .limit(MAX_HOURLY_RECORDS)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        if records.len() >= MAX_HOURLY_RECORDS as usize {
+            warn!(
+                "get_link_param_values_from_rollup: Query hit limit ({} records), results may be incomplete",
+                MAX_HOURLY_RECORDS
+            );
+        }
+
+        // 每行每个参数至多 top-K + 1 个值，聚合结果同样限制 key 数量
+        const MAX_AGGREGATED_KEYS: usize = 1000;
+        let mut aggregated: HashMap<String, i64> = HashMap::new();
+        for record in records {
+            let Some(values) = parse_param_counts(&record).remove(param) else {
+                continue;
+            };
+            for (value, count) in values {
+                if aggregated.len() >= MAX_AGGREGATED_KEYS && !aggregated.contains_key(&value) {
+                    continue;
+                }
+                *aggregated.entry(value).or_insert(0) += i64::try_from(count).unwrap_or(i64::MAX);
+            }
+        }
+
+        Ok(top_param_values(aggregated, limit))
+    }
+
+    /// 从明细表获取带过滤条件的自定义参数取值分布
+    ///
+    /// 只统计 `filter` 参数取指定值的点击，按 `param` 的取值分组。
+    /// 过滤条件以序列化后的 `"名":"值"` 片段匹配 `click_logs.query_params`，
+    /// 最多扫描最近的 [`MAX_PARAM_FILTER_ROWS`] 条点击；返回值的第二项表示是否达到扫描上限。
+    pub async fn get_link_param_values_filtered(
+        &self,
+        code: &str,
+        param: &str,
+        (filter_param, filter_value): (&str, &str),
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<(Vec<ParamValueRow>, bool)> {
+        let pair = format!(
+            "{}:{}",
+            serde_json::to_string(filter_param)?,
+            serde_json::to_string(filter_value)?
+        );
+        let pattern = format!("%{}%", escape_like(&pair));
+
+        let records: Vec<Option<String>> = click_log::Entity::find()
+            .select_only()
+            .column(click_log::Column::QueryParams)
+            .filter(click_log::Column::ShortCode.eq(code))
+            .filter(click_log::Column::ClickedAt.gte(start))
+            .filter(click_log::Column::ClickedAt.lte(end))
+            .filter(click_log::Column::QueryParams.like(LikeExpr::new(pattern).escape('\\')))
+            .order_by_desc(click_log::Column::ClickedAt)
+            .limit(MAX_PARAM_FILTER_ROWS)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        let truncated = records.len() as u64 >= MAX_PARAM_FILTER_ROWS;
+
+        let mut aggregated: HashMap<String, i64> = HashMap::new();
+        for record in records {
+            let mut params = parse_params(&record);
+            // LIKE 只是预筛选，以解析后的值为准
+            if params.get(filter_param).map(String::as_str) != Some(filter_value) {
+                continue;
+            }
+            if let Some(value) = params.remove(param) {
+                *aggregated.entry(value).or_insert(0) += 1;
+            }
+        }
+
+        Ok((top_param_values(aggregated, limit), truncated))
+    }
+
     /// 从天汇总表获取热门链接
     ///
     /// 使用 SQL GROUP BY + SUM 聚合，性能优于内存聚合。
@@ -812,6 +1054,71 @@ impl super::SeaOrmStorage {
         Ok(results)
     }
 
+    /// 从小时汇总表获取热门链接
+    pub async fn get_top_links_from_hourly(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<TopLinkRow>> {
+        let count_expr = click_stats_hourly::Column::ClickCount.sum();
+
+        let results = click_stats_hourly::Entity::find()
+            .select_only()
+            .column(click_stats_hourly::Column::ShortCode)
+            .column_as(count_expr.clone(), "count")
+            .filter(click_stats_hourly::Column::HourBucket.gte(start))
+            .filter(click_stats_hourly::Column::HourBucket.lte(end))
+            .group_by(click_stats_hourly::Column::ShortCode)
+            .order_by_desc(count_expr)
+            .limit(limit as u64)
+            .into_model::<TopLinkRow>()
+            .all(&self.db)
+            .await?;
+
+        Ok(results)
+    }
+
+    /// 读取指定链接在指定整点桶上的点击数（没有记录的桶不返回）
+    pub async fn get_hourly_counts(
+        &self,
+        codes: &[String],
+        buckets: &[DateTime<Utc>],
+    ) -> anyhow::Result<Vec<HourlyCountRow>> {
+        if codes.is_empty() || buckets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        click_stats_hourly::Entity::find()
+            .select_only()
+            .column(click_stats_hourly::Column::ShortCode)
+            .column(click_stats_hourly::Column::HourBucket)
+            .column(click_stats_hourly::Column::ClickCount)
+            .filter(click_stats_hourly::Column::ShortCode.is_in(codes.iter().cloned()))
+            .filter(click_stats_hourly::Column::HourBucket.is_in(buckets.iter().copied()))
+            .into_model::<HourlyCountRow>()
+            .all(&self.db)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// 读取时间范围内的 redirect 耗时样本（最新的 `limit` 条）
+    pub async fn get_redirect_timings(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<redirect_timing::Model>> {
+        redirect_timing::Entity::find()
+            .filter(redirect_timing::Column::RecordedAt.gte(start))
+            .filter(redirect_timing::Column::RecordedAt.lte(end))
+            .order_by_desc(redirect_timing::Column::RecordedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(Into::into)
+    }
+
     // ============ 导出与分页 ============
 
     /// 导出点击日志
@@ -840,27 +1147,85 @@ impl super::SeaOrmStorage {
         end: DateTime<Utc>,
         page_size: u64,
     ) -> Pin<Box<dyn Stream<Item = anyhow::Result<Vec<click_log::Model>>> + Send + 'static>> {
-        let db = self.db.clone();
+        stream_by_id_cursor(
+            self.db.clone(),
+            click_log::Entity::find()
+                .filter(click_log::Column::ClickedAt.gte(start))
+                .filter(click_log::Column::ClickedAt.lte(end)),
+            click_log::Column::Id,
+            |m| m.id,
+            page_size,
+        )
+    }
+
+    /// 流式导出小时汇总（游标分页）
+    pub fn stream_hourly_stats_cursor(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        page_size: u64,
+    ) -> Pin<Box<dyn Stream<Item = anyhow::Result<Vec<click_stats_hourly::Model>>> + Send + 'static>>
+    {
+        stream_by_id_cursor(
+            self.db.clone(),
+            click_stats_hourly::Entity::find()
+                .filter(click_stats_hourly::Column::HourBucket.gte(start))
+                .filter(click_stats_hourly::Column::HourBucket.lte(end)),
+            click_stats_hourly::Column::Id,
+            |m| m.id,
+            page_size,
+        )
+    }
 
-        use futures_util::stream;
+    /// 流式导出天汇总（游标分页，日期闭区间）
+    pub fn stream_daily_stats_cursor(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        page_size: u64,
+    ) -> Pin<Box<dyn Stream<Item = anyhow::Result<Vec<click_stats_daily::Model>>> + Send + 'static>>
+    {
+        stream_by_id_cursor(
+            self.db.clone(),
+            click_stats_daily::Entity::find()
+                .filter(click_stats_daily::Column::DayBucket.gte(start))
+                .filter(click_stats_daily::Column::DayBucket.lte(end)),
+            click_stats_daily::Column::Id,
+            |m| m.id,
+            page_size,
+        )
+    }
+}
 
-        Box::pin(stream::unfold(
-            (None::<i64>, db, start, end, page_size, false),
-            |(cursor, db, start, end, page_size, done)| async move {
+/// 按自增 ID 游标分页读取 `base` 的结果，每页最多 `page_size` 行
+fn stream_by_id_cursor<E>(
+    db: DatabaseConnection,
+    base: Select<E>,
+    id_column: E::Column,
+    id_of: fn(&E::Model) -> i64,
+    page_size: u64,
+) -> Pin<Box<dyn Stream<Item = anyhow::Result<Vec<E::Model>>> + Send + 'static>>
+where
+    E: EntityTrait,
+{
+    use futures_util::stream;
+
+    Box::pin(stream::unfold(
+        (None::<i64>, false),
+        move |(cursor, done)| {
+            let db = db.clone();
+            let mut query = base.clone();
+            async move {
                 if done {
                     return None;
                 }
 
-                let mut query = click_log::Entity::find()
-                    .filter(click_log::Column::ClickedAt.gte(start))
-                    .filter(click_log::Column::ClickedAt.lte(end));
-
                 if let Some(last_id) = cursor {
-                    query = query.filter(click_log::Column::Id.gt(last_id));
+                    query = query.filter(id_column.gt(last_id));
                 }
 
                 let models = query
-                    .order_by_asc(click_log::Column::Id)
+                    .order_by_asc(id_column)
                     .limit(page_size)
                     .all(&db)
                     .await;
@@ -868,19 +1233,16 @@ impl super::SeaOrmStorage {
                 match models {
                     Ok(models) if models.is_empty() => None,
                     Ok(models) => {
-                        let next_cursor = models.last().map(|m| m.id);
+                        let next_cursor = models.last().map(id_of);
                         let is_last = (models.len() as u64) < page_size;
-                        Some((
-                            Ok(models),
-                            (next_cursor, db, start, end, page_size, is_last),
-                        ))
+                        Some((Ok(models), (next_cursor, is_last)))
                     }
                     Err(e) => Some((
                         Err(anyhow::anyhow!("Cursor pagination query failed: {}", e)),
-                        (cursor, db, start, end, page_size, true),
+                        (cursor, true),
                     )),
                 }
-            },
-        ))
-    }
+            }
+        },
+    ))
 }
@@ -0,0 +1,244 @@
+//! API token and quota counters for SeaOrmStorage
+//!
+//! - 链接数：`short_links.owner_token` 实时 COUNT，删除链接自然回退
+//! - 每日创建数：`api_token_daily_usage` 按 (token_id, day) 累加，删除不回退
+
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder,
+    sea_query::{Expr, OnConflict},
+};
+use tracing::info;
+
+use super::SeaOrmStorage;
+use crate::errors::{Result, ShortlinkerError};
+use crate::storage::{AnalyticsLevel, ApiToken};
+
+use migration::entities::{api_token, api_token_daily_usage, short_link};
+
+fn model_to_api_token(model: api_token::Model) -> ApiToken {
+    ApiToken {
+        id: model.id,
+        name: model.name,
+        token_hash: model.token_hash,
+        max_links: model.max_links.map(|v| v.max(0) as u64),
+        max_daily_creates: model.max_daily_creates.map(|v| v.max(0) as u64),
+        max_analytics_level: model
+            .max_analytics_level
+            .as_deref()
+            .map(AnalyticsLevel::from_db),
+        alert_sent_on: model.alert_sent_on,
+        created_at: model.created_at,
+    }
+}
+
+fn quota_to_db(value: Option<u64>) -> Option<i64> {
+    value.map(|v| i64::try_from(v).unwrap_or(i64::MAX))
+}
+
+fn level_to_db(value: Option<AnalyticsLevel>) -> Option<String> {
+    value.map(|level| level.as_str().to_string())
+}
+
+fn db_error(action: &str) -> impl FnOnce(sea_orm::DbErr) -> ShortlinkerError + '_ {
+    move |e| ShortlinkerError::database_operation(format!("Failed to {}: {}", action, e))
+}
+
+impl SeaOrmStorage {
+    pub async fn insert_api_token(&self, token: &ApiToken) -> Result<()> {
+        let model = api_token::ActiveModel {
+            id: Set(token.id.clone()),
+            name: Set(token.name.clone()),
+            token_hash: Set(token.token_hash.clone()),
+            max_links: Set(quota_to_db(token.max_links)),
+            max_daily_creates: Set(quota_to_db(token.max_daily_creates)),
+            max_analytics_level: Set(level_to_db(token.max_analytics_level)),
+            alert_sent_on: Set(None),
+            created_at: Set(token.created_at),
+        };
+        api_token::Entity::insert(model)
+            .exec(&self.db)
+            .await
+            .map_err(db_error("insert API token"))?;
+        info!("API token created: {} ({})", token.id, token.name);
+        Ok(())
+    }
+
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiToken>> {
+        let models = api_token::Entity::find()
+            .order_by_asc(api_token::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(db_error("list API tokens"))?;
+        Ok(models.into_iter().map(model_to_api_token).collect())
+    }
+
+    pub async fn get_api_token(&self, id: &str) -> Result<Option<ApiToken>> {
+        let model = api_token::Entity::find_by_id(id.to_string())
+            .one(&self.db)
+            .await
+            .map_err(db_error("load API token"))?;
+        Ok(model.map(model_to_api_token))
+    }
+
+    /// 更新配额，返回 token 是否存在
+    pub async fn update_api_token_quotas(
+        &self,
+        id: &str,
+        max_links: Option<u64>,
+        max_daily_creates: Option<u64>,
+        max_analytics_level: Option<AnalyticsLevel>,
+    ) -> Result<bool> {
+        let result = api_token::Entity::update_many()
+            .col_expr(
+                api_token::Column::MaxLinks,
+                Expr::value(quota_to_db(max_links)),
+            )
+            .col_expr(
+                api_token::Column::MaxDailyCreates,
+                Expr::value(quota_to_db(max_daily_creates)),
+            )
+            .col_expr(
+                api_token::Column::MaxAnalyticsLevel,
+                Expr::value(level_to_db(max_analytics_level)),
+            )
+            // 配额变化后允许重新提醒
+            .col_expr(api_token::Column::AlertSentOn, Expr::value(None::<String>))
+            .filter(api_token::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(db_error("update API token quotas"))?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 删除 token 及其计数；其名下链接保留并转为无归属
+    pub async fn delete_api_token(&self, id: &str) -> Result<bool> {
+        let id_owned = id.to_string();
+        let deleted = aster_forge_db::transaction::with_transaction_retry(
+            &self.db,
+            &self.retry_config,
+            |txn| {
+                let id = id_owned.clone();
+                Box::pin(async move {
+                    short_link::Entity::update_many()
+                        .col_expr(short_link::Column::OwnerToken, Expr::value(None::<String>))
+                        .filter(short_link::Column::OwnerToken.eq(id.clone()))
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    api_token_daily_usage::Entity::delete_many()
+                        .filter(api_token_daily_usage::Column::TokenId.eq(id.clone()))
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    let result = api_token::Entity::delete_by_id(id)
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    Ok(result.rows_affected > 0)
+                })
+            },
+            aster_forge_db::DbError::is_retryable,
+        )
+        .await
+        .map_err(ShortlinkerError::from)?;
+
+        if deleted {
+            info!("API token deleted: {}", id);
+        }
+        Ok(deleted)
+    }
+
+    /// 实时统计 token 名下的链接数（不含已归档链接）
+    pub async fn count_links_by_owner(&self, id: &str) -> Result<u64> {
+        short_link::Entity::find()
+            .filter(short_link::Column::OwnerToken.eq(id))
+            .count(&self.db)
+            .await
+            .map_err(db_error("count links by owner"))
+    }
+
+    /// 将尚无归属的链接计入 token，返回新计入的条数
+    pub async fn assign_link_owner(&self, id: &str, codes: &[String]) -> Result<u64> {
+        let mut assigned = 0;
+        // 分批更新，避免 SQL IN 子句过长
+        for chunk in codes.chunks(500) {
+            let result = short_link::Entity::update_many()
+                .col_expr(short_link::Column::OwnerToken, Expr::value(id.to_string()))
+                .filter(
+                    Condition::all()
+                        .add(short_link::Column::ShortCode.is_in(chunk.to_vec()))
+                        .add(short_link::Column::OwnerToken.is_null()),
+                )
+                .exec(&self.db)
+                .await
+                .map_err(db_error("assign link owner"))?;
+            assigned += result.rows_affected;
+        }
+        Ok(assigned)
+    }
+
+    /// 读取 token 在某天（YYYY-MM-DD）的创建数
+    pub async fn daily_created_count(&self, id: &str, day: &str) -> Result<u64> {
+        let model = api_token_daily_usage::Entity::find()
+            .filter(api_token_daily_usage::Column::TokenId.eq(id))
+            .filter(api_token_daily_usage::Column::Day.eq(day))
+            .one(&self.db)
+            .await
+            .map_err(db_error("load daily usage"))?;
+        Ok(model.map_or(0, |m| m.created_count.max(0) as u64))
+    }
+
+    /// 原子累加每日创建数
+    pub async fn add_daily_created(&self, id: &str, day: &str, count: u64) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        // SQLite/PostgreSQL: excluded.created_count（PostgreSQL 需用表名限定原值）
+        // MySQL: VALUES(created_count)
+        let increment = match self.db.get_database_backend() {
+            DatabaseBackend::MySql => "created_count + VALUES(created_count)",
+            _ => "api_token_daily_usage.created_count + excluded.created_count",
+        };
+        let on_conflict = OnConflict::columns([
+            api_token_daily_usage::Column::TokenId,
+            api_token_daily_usage::Column::Day,
+        ])
+        .value(
+            api_token_daily_usage::Column::CreatedCount,
+            Expr::cust(increment),
+        )
+        .to_owned();
+
+        let model = api_token_daily_usage::ActiveModel {
+            id: NotSet,
+            token_id: Set(id.to_string()),
+            day: Set(day.to_string()),
+            created_count: Set(i64::try_from(count).unwrap_or(i64::MAX)),
+        };
+        api_token_daily_usage::Entity::insert(model)
+            .on_conflict(on_conflict)
+            .exec(&self.db)
+            .await
+            .map_err(db_error("update daily usage"))?;
+        Ok(())
+    }
+
+    /// 标记当天已发送用量提醒；返回 false 表示当天已有其他请求发送过
+    pub async fn mark_api_token_alert_sent(&self, id: &str, day: &str) -> Result<bool> {
+        let result = api_token::Entity::update_many()
+            .col_expr(api_token::Column::AlertSentOn, Expr::value(day.to_string()))
+            .filter(api_token::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(api_token::Column::AlertSentOn.is_null())
+                    .add(api_token::Column::AlertSentOn.ne(day)),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(db_error("mark quota alert"))?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,275 @@
+//! Archive operations for SeaOrmStorage
+//!
+//! 归档表 `short_link_archive` 的读写。归档即在同一事务内把主表行复制到归档表
+//! 并删除主表行；恢复则反向操作。点击日志按 short_code 关联，归档前后不变。
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::OnConflict,
+};
+use tracing::info;
+
+use super::SeaOrmStorage;
+use super::converters::{
+    archive_model_to_archived_link, archive_model_to_short_link, short_link_to_archive_model,
+};
+use crate::errors::{Result, ShortlinkerError};
+use crate::storage::{ArchivedLink, ShortLink};
+
+use migration::entities::{short_link, short_link_archive};
+
+impl SeaOrmStorage {
+    /// 在单个事务内归档一批链接
+    ///
+    /// 返回 (已归档的 codes, 主表中不存在的 codes)。调用方负责分批，
+    /// 每批大小应控制在 SQL IN 子句可接受的范围内。
+    pub async fn archive_batch(&self, codes: &[String]) -> Result<(Vec<String>, Vec<String>)> {
+        if codes.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let requested = codes.to_vec();
+        let (archived, not_found) = aster_forge_db::transaction::with_transaction_retry(
+            &self.db,
+            &self.retry_config,
+            |txn| {
+                let requested = requested.clone();
+                Box::pin(async move {
+                    let models = short_link::Entity::find()
+                        .filter(short_link::Column::ShortCode.is_in(requested.iter().cloned()))
+                        .all(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+
+                    let archived: Vec<String> =
+                        models.iter().map(|m| m.short_code.clone()).collect();
+                    let archived_set: HashSet<&String> = archived.iter().collect();
+                    let not_found = requested
+                        .into_iter()
+                        .filter(|code| !archived_set.contains(code))
+                        .collect();
+
+                    if !models.is_empty() {
+                        let archived_at = Utc::now();
+                        let archive_models = models
+                            .into_iter()
+                            .map(|model| short_link_to_archive_model(model, archived_at))
+                            .collect::<Vec<_>>();
+
+                        // 同一短码再次归档（恢复后又归档）时覆盖旧的归档记录
+                        short_link_archive::Entity::insert_many(archive_models)
+                            .on_conflict(
+                                OnConflict::column(short_link_archive::Column::ShortCode)
+                                    .update_columns([
+                                        short_link_archive::Column::TargetUrl,
+                                        short_link_archive::Column::CreatedAt,
+                                        short_link_archive::Column::ExpiresAt,
+                                        short_link_archive::Column::Password,
+                                        short_link_archive::Column::ClickCount,
+                                        short_link_archive::Column::CreatedVia,
+                                        short_link_archive::Column::AnalyticsLevel,
+                                        short_link_archive::Column::Extras,
+                                        short_link_archive::Column::ArchivedAt,
+                                    ])
+                                    .to_owned(),
+                            )
+                            .exec(txn)
+                            .await
+                            .map_err(aster_forge_db::DbError::from)?;
+
+                        short_link::Entity::delete_many()
+                            .filter(short_link::Column::ShortCode.is_in(archived.iter().cloned()))
+                            .exec(txn)
+                            .await
+                            .map_err(aster_forge_db::DbError::from)?;
+                    }
+
+                    Ok((archived, not_found))
+                })
+            },
+            aster_forge_db::DbError::is_retryable,
+        )
+        .await
+        .map_err(ShortlinkerError::from)?;
+
+        self.invalidate_count_cache();
+        info!("Archived {} links", archived.len());
+
+        Ok((archived, not_found))
+    }
+
+    /// 从归档表恢复单个链接
+    ///
+    /// 归档表中不存在时返回 `Ok(None)`。调用方需先确认主表中没有同名短码。
+    pub async fn unarchive(&self, code: &str) -> Result<Option<ShortLink>> {
+        let code_owned = code.to_string();
+
+        let restored = aster_forge_db::transaction::with_transaction_retry(
+            &self.db,
+            &self.retry_config,
+            |txn| {
+                let code = code_owned.clone();
+                Box::pin(async move {
+                    let Some(model) = short_link_archive::Entity::find_by_id(code.clone())
+                        .one(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?
+                    else {
+                        return Ok(None);
+                    };
+
+                    let link = archive_model_to_archived_link(model.clone()).link;
+                    short_link::Entity::insert(archive_model_to_short_link(model))
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    short_link_archive::Entity::delete_by_id(code)
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+
+                    Ok(Some(link))
+                })
+            },
+            aster_forge_db::DbError::is_retryable,
+        )
+        .await
+        .map_err(ShortlinkerError::from)?;
+
+        if restored.is_some() {
+            self.invalidate_count_cache();
+            info!("Short link unarchived: {}", code);
+        }
+        Ok(restored)
+    }
+
+    /// 查询单个归档链接
+    pub async fn get_archived(&self, code: &str) -> Result<Option<ArchivedLink>> {
+        let db = &self.db;
+        let code_owned = code.to_string();
+
+        let result = aster_forge_db::retry::with_sea_orm_retry(
+            &format!("get_archived({code})"),
+            self.retry_config,
+            || async {
+                short_link_archive::Entity::find_by_id(&code_owned)
+                    .one(db)
+                    .await
+            },
+        )
+        .await
+        .map_err(|e| {
+            ShortlinkerError::database_operation(format!("Failed to query archived link: {}", e))
+        })?;
+
+        Ok(result.map(archive_model_to_archived_link))
+    }
+
+    /// 短码是否处于归档状态（redirect 区分 410 / 404 使用）
+    pub async fn is_archived(&self, code: &str) -> Result<bool> {
+        let db = &self.db;
+        let code_owned = code.to_string();
+
+        let count =
+            aster_forge_db::retry::with_sea_orm_retry("is_archived", self.retry_config, || async {
+                short_link_archive::Entity::find()
+                    .filter(short_link_archive::Column::ShortCode.eq(code_owned.clone()))
+                    .count(db)
+                    .await
+            })
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "Failed to query archived link: {}",
+                    e
+                ))
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// 分页查询归档链接（按归档时间倒序）
+    pub async fn load_archived_paginated(
+        &self,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+    ) -> Result<(Vec<ArchivedLink>, u64)> {
+        let mut condition = Condition::all();
+        if let Some(ref search) = search {
+            condition = condition.add(
+                Condition::any()
+                    .add(short_link_archive::Column::ShortCode.contains(search))
+                    .add(short_link_archive::Column::TargetUrl.contains(search)),
+            );
+        }
+
+        let db = &self.db;
+        let total = aster_forge_db::retry::with_sea_orm_retry(
+            "load_archived_paginated(count)",
+            self.retry_config,
+            || async {
+                short_link_archive::Entity::find()
+                    .filter(condition.clone())
+                    .count(db)
+                    .await
+            },
+        )
+        .await
+        .map_err(|e| {
+            ShortlinkerError::database_operation(format!(
+                "Archived pagination COUNT query failed: {}",
+                e
+            ))
+        })?;
+
+        let page_offset = page.saturating_sub(1);
+        let models = aster_forge_db::retry::with_sea_orm_retry(
+            "load_archived_paginated(data)",
+            self.retry_config,
+            || async {
+                short_link_archive::Entity::find()
+                    .filter(condition.clone())
+                    .order_by_desc(short_link_archive::Column::ArchivedAt)
+                    .paginate(db, page_size)
+                    .fetch_page(page_offset)
+                    .await
+            },
+        )
+        .await
+        .map_err(|e| {
+            ShortlinkerError::database_operation(format!(
+                "Archived pagination data query failed: {}",
+                e
+            ))
+        })?;
+
+        let links = models
+            .into_iter()
+            .map(archive_model_to_archived_link)
+            .collect();
+        Ok((links, total))
+    }
+
+    /// 加载全部归档短码（用于缓存层区分 410 / 404）
+    pub async fn load_archived_codes(&self) -> Result<Vec<String>> {
+        let codes = short_link_archive::Entity::find()
+            .select_only()
+            .column(short_link_archive::Column::ShortCode)
+            .into_tuple::<String>()
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "Failed to load archived short codes: {}",
+                    e
+                ))
+            })?;
+
+        info!("Loaded {} archived short codes", codes.len());
+        Ok(codes)
+    }
+}
@@ -15,12 +15,14 @@ use sea_orm::{ActiveValue::Set, ConnectionTrait, EntityTrait, ExprTrait, Iden};
 use tracing::{debug, warn};
 
 use super::SeaOrmStorage;
+use crate::analytics::query_params::params_to_json;
 use crate::analytics::{
-    ClickDetail, ClickSink, DetailedClickSink, HourlyRollupWriter, truncate_to_hour,
+    ClickDetail, ClickSink, DetailedClickSink, HourlyRollupWriter, RedirectTiming,
+    RedirectTimingSink, truncate_to_hour,
 };
 use crate::utils::is_valid_short_code;
 
-use migration::entities::{click_log, short_link};
+use migration::entities::{click_log, redirect_timing, short_link};
 
 #[async_trait]
 impl ClickSink for SeaOrmStorage {
@@ -29,8 +31,31 @@ impl ClickSink for SeaOrmStorage {
             return Ok(());
         }
 
+        self.update_click_counts(&updates).await?;
+
+        // 同步更新小时汇总表
+        if let Err(e) = self.update_hourly_rollup(&updates, Utc::now()).await {
+            warn!("Failed to update hourly rollup (non-blocking): {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// `count_only` 级别的链接：只累加 click_count，不写小时汇总
+    async fn flush_counts_only(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        self.update_click_counts(&updates).await
+    }
+}
+
+impl SeaOrmStorage {
+    /// 批量累加 short_links.click_count（单事务，带重试）
+    async fn update_click_counts(&self, updates: &[(String, usize)]) -> anyhow::Result<()> {
         // 安全校验：确保所有 short_code 格式合法，防止 SQL 注入
-        for (code, _) in &updates {
+        for (code, _) in updates {
             if !is_valid_short_code(code) {
                 return Err(anyhow::anyhow!(
                     "Invalid short_code format detected: '{}' - refusing to execute SQL",
@@ -110,11 +135,6 @@ impl ClickSink for SeaOrmStorage {
             total_count
         );
 
-        // 同步更新小时汇总表
-        if let Err(e) = self.update_hourly_rollup(&updates, Utc::now()).await {
-            warn!("Failed to update hourly rollup (non-blocking): {}", e);
-        }
-
         Ok(())
     }
 }
@@ -144,6 +164,7 @@ impl DetailedClickSink for SeaOrmStorage {
                 country: Set(detail.country.clone()),
                 city: Set(detail.city.clone()),
                 source: Set(detail.source.clone()),
+                query_params: Set(params_to_json(&detail.query_params)),
                 ..Default::default()
             })
             .collect();
@@ -183,6 +204,42 @@ impl DetailedClickSink for SeaOrmStorage {
     }
 }
 
+#[async_trait]
+impl RedirectTimingSink for SeaOrmStorage {
+    async fn log_timings_batch(&self, timings: Vec<RedirectTiming>) -> anyhow::Result<()> {
+        // 分批插入，避免超出 SQL 变量限制
+        const BATCH_SIZE: usize = 500;
+        let to_i32 = |us: u32| i32::try_from(us).unwrap_or(i32::MAX);
+
+        for chunk in timings.chunks(BATCH_SIZE) {
+            let models: Vec<redirect_timing::ActiveModel> = chunk
+                .iter()
+                .map(|timing| redirect_timing::ActiveModel {
+                    short_code: Set(timing.code.clone()),
+                    recorded_at: Set(timing.recorded_at),
+                    status: Set(i16::try_from(timing.status).unwrap_or(i16::MAX)),
+                    total_us: Set(to_i32(timing.total_us)),
+                    cache_us: Set(to_i32(timing.cache_us)),
+                    bloom_us: Set(timing.bloom_us.map(to_i32)),
+                    db_us: Set(timing.db_us.map(to_i32)),
+                    geo_us: Set(timing.geo_us.map(to_i32)),
+                    enqueue_us: Set(timing.enqueue_us.map(to_i32)),
+                    ..Default::default()
+                })
+                .collect();
+            redirect_timing::Entity::insert_many(models)
+                .exec(&self.db)
+                .await?;
+        }
+
+        debug!(
+            "Redirect timing samples written ({} records)",
+            timings.len()
+        );
+        Ok(())
+    }
+}
+
 impl SeaOrmStorage {
     /// 创建 HourlyRollupWriter 实例
     fn hourly_writer(&self) -> HourlyRollupWriter<'_, sea_orm::DatabaseConnection> {
@@ -1,11 +1,60 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
 use sea_orm::DatabaseConnection;
 use tracing::info;
 
 use crate::errors::{Result, ShortlinkerError};
+use crate::metrics::MetricsRecorder;
 use migration::{Migrator, MigratorTrait};
 
+/// 建立数据库连接（不运行迁移）
+///
+/// `SeaOrmStorage::new` 在此基础上运行迁移；`migrate` 命令需要在迁移前后查看状态，
+/// 直接使用本函数。
+pub async fn connect(
+    database_url: &str,
+    backend_name: &str,
+    metrics: Arc<dyn MetricsRecorder>,
+) -> Result<DatabaseConnection> {
+    let config = crate::config::get_config();
+
+    // 新配置使用标准 SQLite URL；这里保留旧版允许的裸文件路径和
+    // `:memory:`，在进入 Forge/SeaORM 连接层前转换为等价连接串。
+    let database_url = if backend_name == "sqlite" {
+        if database_url == ":memory:" {
+            Cow::Borrowed("sqlite::memory:")
+        } else if database_url.starts_with("sqlite:") {
+            Cow::Borrowed(database_url)
+        } else {
+            Cow::Owned(format!("sqlite://{database_url}?mode=rwc"))
+        }
+    } else {
+        Cow::Borrowed(database_url)
+    };
+
+    let mut forge_config = aster_forge_db::DatabaseConfig::new(database_url.as_ref());
+    forge_config.pool_size = config.database.pool_size;
+    forge_config.retry_count = config.database.retry_count;
+    let mut db = aster_forge_db::connect(&forge_config)
+        .await
+        .map_err(|error| {
+            ShortlinkerError::database_connection(format!(
+                "Failed to connect to {} database: {error}",
+                backend_name.to_uppercase()
+            ))
+        })?;
+    // 语句日志与 Forge 数据库指标共用 SeaORM 唯一的 metric callback
+    super::statement_log::install(&mut db, metrics.forge_recorder());
+    Ok(db)
+}
+
 /// 运行数据库迁移
+///
+/// 数据库被更新版本迁移过时直接报错并提示回滚命令。
 pub async fn run_migrations(db: &DatabaseConnection) -> Result<()> {
+    super::migrate::ensure_schema_compatible(db).await?;
+
     Migrator::up(db, None)
         .await
         .map_err(|e| ShortlinkerError::database_operation(format!("Migration failed: {}", e)))?;
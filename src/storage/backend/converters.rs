@@ -1,5 +1,7 @@
-use crate::storage::ShortLink;
-use migration::entities::short_link;
+use chrono::{DateTime, Utc};
+
+use crate::storage::{AnalyticsLevel, ArchivedLink, CreatedVia, ShortLink};
+use migration::entities::{short_link, short_link_archive};
 
 /// 将 Sea-ORM Model 转换为 ShortLink
 pub fn model_to_shortlink(model: short_link::Model) -> ShortLink {
@@ -14,6 +16,9 @@ pub fn model_to_shortlink(model: short_link::Model) -> ShortLink {
             "click_count",
         )
         .unwrap_or(usize::MAX),
+        created_via: CreatedVia::from_db(&model.created_via),
+        analytics_level: AnalyticsLevel::from_db(&model.analytics_level),
+        extras: model.extras,
     }
 }
 
@@ -35,13 +40,84 @@ pub fn shortlink_to_active_model(link: &ShortLink, is_new: bool) -> short_link::
         } else {
             NotSet
         },
+        created_via: if is_new {
+            Set(link.created_via.as_str().to_string())
+        } else {
+            NotSet
+        },
+        analytics_level: Set(link.analytics_level.as_str().to_string()),
+        extras: Set(link.extras.clone()),
+        // 配额归属由 ApiTokenService 单独登记，普通写入不改动
+        owner_token: NotSet,
+    }
+}
+
+/// 将归档表 Model 转换为 ArchivedLink
+pub fn archive_model_to_archived_link(model: short_link_archive::Model) -> ArchivedLink {
+    ArchivedLink {
+        link: ShortLink {
+            code: model.short_code,
+            target: model.target_url,
+            created_at: model.created_at,
+            expires_at: model.expires_at,
+            password: model.password,
+            click: aster_forge_utils::numbers::i64_to_usize(
+                std::cmp::max(model.click_count, 0),
+                "click_count",
+            )
+            .unwrap_or(usize::MAX),
+            created_via: CreatedVia::from_db(&model.created_via),
+            analytics_level: AnalyticsLevel::from_db(&model.analytics_level),
+            extras: model.extras,
+        },
+        archived_at: model.archived_at,
+    }
+}
+
+/// 将主表 Model 原样转换为归档表 ActiveModel
+pub fn short_link_to_archive_model(
+    model: short_link::Model,
+    archived_at: DateTime<Utc>,
+) -> short_link_archive::ActiveModel {
+    use sea_orm::ActiveValue::Set;
+
+    short_link_archive::ActiveModel {
+        short_code: Set(model.short_code),
+        target_url: Set(model.target_url),
+        created_at: Set(model.created_at),
+        expires_at: Set(model.expires_at),
+        password: Set(model.password),
+        click_count: Set(model.click_count),
+        created_via: Set(model.created_via),
+        analytics_level: Set(model.analytics_level),
+        extras: Set(model.extras),
+        archived_at: Set(archived_at),
+    }
+}
+
+/// 将归档表 Model 还原为主表 ActiveModel
+pub fn archive_model_to_short_link(model: short_link_archive::Model) -> short_link::ActiveModel {
+    use sea_orm::ActiveValue::{NotSet, Set};
+
+    short_link::ActiveModel {
+        short_code: Set(model.short_code),
+        target_url: Set(model.target_url),
+        created_at: Set(model.created_at),
+        expires_at: Set(model.expires_at),
+        password: Set(model.password),
+        click_count: Set(model.click_count),
+        created_via: Set(model.created_via),
+        analytics_level: Set(model.analytics_level),
+        extras: Set(model.extras),
+        // 归档不保留配额归属，恢复后的链接不计入任何 token
+        owner_token: NotSet,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Utc};
+    use chrono::Duration;
     use sea_orm::ActiveValue;
 
     fn create_test_model() -> short_link::Model {
@@ -52,6 +128,10 @@ mod tests {
             expires_at: Some(Utc::now() + Duration::days(7)),
             password: Some("hashed_password".to_string()),
             click_count: 42,
+            created_via: "import".to_string(),
+            analytics_level: "count_only".to_string(),
+            extras: Some(r#"{"ticket":"JIRA-123"}"#.to_string()),
+            owner_token: None,
         }
     }
 
@@ -63,6 +143,9 @@ mod tests {
             expires_at: Some(Utc::now() + Duration::hours(24)),
             password: Some("secret".to_string()),
             click: 100,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Aggregate,
+            extras: Some(r#"{"team":"growth"}"#.to_string()),
         }
     }
 
@@ -78,6 +161,9 @@ mod tests {
         assert_eq!(link.code, expected_code);
         assert_eq!(link.target, expected_target);
         assert_eq!(link.click, expected_click);
+        assert_eq!(link.created_via, CreatedVia::Import);
+        assert_eq!(link.analytics_level, AnalyticsLevel::CountOnly);
+        assert_eq!(link.extras.as_deref(), Some(r#"{"ticket":"JIRA-123"}"#));
     }
 
     #[test]
@@ -89,6 +175,10 @@ mod tests {
             expires_at: None,
             password: None,
             click_count: 0,
+            created_via: "unknown".to_string(),
+            analytics_level: "inherit".to_string(),
+            extras: None,
+            owner_token: None,
         };
 
         let link = model_to_shortlink(model);
@@ -107,6 +197,10 @@ mod tests {
             expires_at: None,
             password: None,
             click_count: -10, // 负数应该被转换为 0
+            created_via: "unknown".to_string(),
+            analytics_level: "inherit".to_string(),
+            extras: None,
+            owner_token: None,
         };
 
         let link = model_to_shortlink(model);
@@ -125,6 +219,10 @@ mod tests {
         assert!(matches!(active_model.expires_at, ActiveValue::Set(_)));
         assert!(matches!(active_model.password, ActiveValue::Set(_)));
         assert!(matches!(active_model.click_count, ActiveValue::Set(_)));
+        assert!(matches!(&active_model.created_via, ActiveValue::Set(via) if via == "api"));
+        assert!(
+            matches!(&active_model.analytics_level, ActiveValue::Set(level) if level == "aggregate")
+        );
 
         // 验证值
         if let ActiveValue::Set(code) = active_model.short_code {
@@ -143,13 +241,17 @@ mod tests {
         let link = create_test_shortlink();
         let active_model = shortlink_to_active_model(&link, false);
 
-        // 更新时，created_at 和 click_count 应该是 NotSet
+        // 更新时，created_at、click_count 和 created_via 应该是 NotSet，
+        // analytics_level 与 extras 可修改，始终写入
         assert!(matches!(active_model.short_code, ActiveValue::Set(_)));
         assert!(matches!(active_model.target_url, ActiveValue::Set(_)));
         assert!(matches!(active_model.created_at, ActiveValue::NotSet));
         assert!(matches!(active_model.expires_at, ActiveValue::Set(_)));
         assert!(matches!(active_model.password, ActiveValue::Set(_)));
         assert!(matches!(active_model.click_count, ActiveValue::NotSet));
+        assert!(matches!(active_model.created_via, ActiveValue::NotSet));
+        assert!(matches!(active_model.analytics_level, ActiveValue::Set(_)));
+        assert!(matches!(active_model.extras, ActiveValue::Set(Some(_))));
     }
 
     #[test]
@@ -161,6 +263,9 @@ mod tests {
             expires_at: None,
             password: None,
             click: 0,
+            created_via: CreatedVia::Unknown,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         };
 
         let active_model = shortlink_to_active_model(&link, true);
@@ -193,4 +298,39 @@ mod tests {
             assert_eq!(target, expected_target);
         }
     }
+
+    #[test]
+    fn test_archive_roundtrip_preserves_fields() {
+        let original = create_test_model();
+        let archived_at = Utc::now();
+
+        let archive = short_link_to_archive_model(original.clone(), archived_at);
+        let archive_model = short_link_archive::Model {
+            short_code: archive.short_code.unwrap(),
+            target_url: archive.target_url.unwrap(),
+            created_at: archive.created_at.unwrap(),
+            expires_at: archive.expires_at.unwrap(),
+            password: archive.password.unwrap(),
+            click_count: archive.click_count.unwrap(),
+            created_via: archive.created_via.unwrap(),
+            analytics_level: archive.analytics_level.unwrap(),
+            extras: archive.extras.unwrap(),
+            archived_at: archive.archived_at.unwrap(),
+        };
+
+        let archived = archive_model_to_archived_link(archive_model.clone());
+        assert_eq!(archived.link.code, original.short_code);
+        assert_eq!(archived.link.click, 42);
+        assert_eq!(archived.link.created_via, CreatedVia::Import);
+        assert_eq!(archived.link.analytics_level, AnalyticsLevel::CountOnly);
+        assert_eq!(archived.link.extras, original.extras);
+        assert_eq!(archived.archived_at, archived_at);
+
+        let restored = archive_model_to_short_link(archive_model);
+        assert_eq!(restored.short_code.unwrap(), original.short_code);
+        assert_eq!(restored.click_count.unwrap(), original.click_count);
+        assert_eq!(restored.created_at.unwrap(), original.created_at);
+        assert_eq!(restored.analytics_level.unwrap(), original.analytics_level);
+        assert_eq!(restored.extras.unwrap(), original.extras);
+    }
 }
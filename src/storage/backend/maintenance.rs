@@ -0,0 +1,214 @@
+//! 数据库维护（`shortlinker db maintain` 与周期维护任务）
+//!
+//! 按后端以原生语句回收空间、更新统计信息，并统计执行前后的空间占用：
+//! - SQLite：`PRAGMA optimize`；完整模式为 `VACUUM`（重写整个文件，需要独占访问）
+//! - PostgreSQL：`VACUUM ANALYZE`；完整模式为 `VACUUM FULL ANALYZE`（逐表重写并持有排他锁）
+//! - MySQL / MariaDB：`ANALYZE TABLE`；完整模式为 `OPTIMIZE TABLE`（InnoDB 会重建表）
+
+use std::time::{Duration, Instant};
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, Statement};
+use tracing::info;
+
+use crate::errors::{Result, ShortlinkerError};
+
+/// 一次维护的结果
+#[derive(Debug, Clone)]
+pub struct MaintenanceReport {
+    pub backend: DatabaseBackend,
+    pub full: bool,
+    /// 按执行顺序排列的语句
+    pub statements: Vec<String>,
+    /// 执行前的空间占用（字节，统计失败时为 `None`）
+    pub size_before: Option<u64>,
+    /// 执行后的空间占用（字节，统计失败时为 `None`）
+    pub size_after: Option<u64>,
+    pub elapsed: Duration,
+}
+
+/// 执行维护；`full` 选择各后端的完整模式
+pub async fn maintain(db: &DatabaseConnection, full: bool) -> Result<MaintenanceReport> {
+    let backend = db.get_database_backend();
+    let start = Instant::now();
+    let size_before = database_size(db).await;
+
+    let statements = maintenance_statements(db, full).await?;
+    for sql in &statements {
+        run_statement(db, sql).await?;
+    }
+
+    let report = MaintenanceReport {
+        backend,
+        full,
+        statements,
+        size_before,
+        size_after: database_size(db).await,
+        elapsed: start.elapsed(),
+    };
+    info!(
+        "Database maintenance finished ({} statements, {:?} -> {:?} bytes) in {}ms",
+        report.statements.len(),
+        report.size_before,
+        report.size_after,
+        report.elapsed.as_millis()
+    );
+    Ok(report)
+}
+
+/// 当前后端的维护语句
+async fn maintenance_statements(db: &DatabaseConnection, full: bool) -> Result<Vec<String>> {
+    let statements = match db.get_database_backend() {
+        DatabaseBackend::Sqlite if full => vec!["VACUUM".to_string()],
+        DatabaseBackend::Sqlite => vec!["PRAGMA optimize".to_string()],
+        DatabaseBackend::Postgres => {
+            ensure_postgres_owner(db).await?;
+            let sql = if full {
+                "VACUUM (FULL, ANALYZE)"
+            } else {
+                "VACUUM (ANALYZE)"
+            };
+            vec![sql.to_string()]
+        }
+        DatabaseBackend::MySql => {
+            let tables = mysql_tables(db).await?;
+            if tables.is_empty() {
+                return Ok(Vec::new());
+            }
+            let tables: Vec<String> = tables
+                .iter()
+                .map(|table| format!("`{}`", table.replace('`', "``")))
+                .collect();
+            let command = if full { "OPTIMIZE" } else { "ANALYZE" };
+            vec![format!("{} TABLE {}", command, tables.join(", "))]
+        }
+        _ => {
+            return Err(ShortlinkerError::database_operation(
+                "Database maintenance is not supported for this backend",
+            ));
+        }
+    };
+    Ok(statements)
+}
+
+/// 执行单条维护语句
+///
+/// MySQL 的 `ANALYZE` / `OPTIMIZE TABLE` 以结果集报告逐表错误，需要逐行检查。
+async fn run_statement(db: &DatabaseConnection, sql: &str) -> Result<()> {
+    let backend = db.get_database_backend();
+    if backend != DatabaseBackend::MySql {
+        db.execute_raw(Statement::from_string(backend, sql))
+            .await
+            .map_err(|e| maintenance_error(sql, e))?;
+        return Ok(());
+    }
+
+    let rows = db
+        .query_all_raw(Statement::from_string(backend, sql))
+        .await
+        .map_err(|e| maintenance_error(sql, e))?;
+    let errors: Vec<String> = rows
+        .iter()
+        .filter(|row| {
+            row.try_get::<String>("", "Msg_type")
+                .is_ok_and(|kind| kind.eq_ignore_ascii_case("error"))
+        })
+        .map(|row| {
+            let table = row.try_get::<String>("", "Table").unwrap_or_default();
+            let text = row.try_get::<String>("", "Msg_text").unwrap_or_default();
+            format!("{}: {}", table, text)
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ShortlinkerError::database_operation(format!(
+            "{} reported errors: {}",
+            sql,
+            errors.join("; ")
+        )))
+    }
+}
+
+/// 维护语句失败时的报错；权限不足单独说明
+fn maintenance_error(sql: &str, error: DbErr) -> ShortlinkerError {
+    let message = error.to_string();
+    let lowered = message.to_lowercase();
+    let hint = if lowered.contains("denied")
+        || lowered.contains("permission")
+        || lowered.contains("must be owner")
+        || lowered.contains("readonly")
+        || lowered.contains("read-only")
+    {
+        " (insufficient privileges: run maintenance as the database owner or a user allowed to run it)"
+    } else if lowered.contains("locked") || lowered.contains("busy") {
+        " (database is in use: stop the server or retry when it is idle)"
+    } else {
+        ""
+    };
+    ShortlinkerError::database_operation(format!("{} failed: {}{}", sql, message, hint))
+}
+
+/// PostgreSQL 的 VACUUM 会静默跳过无权处理的表，先检查表的所有权
+async fn ensure_postgres_owner(db: &DatabaseConnection) -> Result<()> {
+    let sql = "SELECT c.relname FROM pg_class c \
+               JOIN pg_namespace n ON n.oid = c.relnamespace \
+               WHERE n.nspname = current_schema() AND c.relkind = 'r' \
+               AND NOT pg_has_role(c.relowner, 'USAGE') \
+               ORDER BY c.relname";
+    let rows = db
+        .query_all_raw(Statement::from_string(DatabaseBackend::Postgres, sql))
+        .await
+        .map_err(|e| maintenance_error("permission check", e))?;
+    let tables: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .collect();
+    if tables.is_empty() {
+        return Ok(());
+    }
+    Err(ShortlinkerError::database_operation(format!(
+        "Insufficient privileges: the current role does not own {} and VACUUM would skip them; run maintenance as the table owner",
+        tables.join(", ")
+    )))
+}
+
+/// 当前 MySQL 数据库中的表
+async fn mysql_tables(db: &DatabaseConnection) -> Result<Vec<String>> {
+    let sql = "SELECT table_name FROM information_schema.tables \
+               WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE' \
+               ORDER BY table_name";
+    let rows = db
+        .query_all_raw(Statement::from_string(DatabaseBackend::MySql, sql))
+        .await
+        .map_err(|e| maintenance_error("table listing", e))?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .collect())
+}
+
+/// 数据库当前的空间占用（字节）
+///
+/// SQLite 为页数 × 页大小（不含 WAL 文件），PostgreSQL 为整个库的大小，
+/// MySQL 为当前库所有表的数据、索引与碎片空间之和。
+pub async fn database_size(db: &DatabaseConnection) -> Option<u64> {
+    let backend = db.get_database_backend();
+    let sql = match backend {
+        DatabaseBackend::Sqlite => {
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()"
+        }
+        DatabaseBackend::Postgres => "SELECT pg_database_size(current_database())::BIGINT",
+        DatabaseBackend::MySql => {
+            "SELECT CAST(COALESCE(SUM(data_length + index_length + data_free), 0) AS SIGNED) \
+             FROM information_schema.tables WHERE table_schema = DATABASE()"
+        }
+        _ => return None,
+    };
+    let row = db
+        .query_one_raw(Statement::from_string(backend, sql))
+        .await
+        .ok()??;
+    row.try_get_by_index::<i64>(0)
+        .ok()
+        .and_then(|size| u64::try_from(size).ok())
+}
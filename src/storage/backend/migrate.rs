@@ -0,0 +1,290 @@
+//! 迁移状态与回滚（`shortlinker migrate status / down`）
+//!
+//! 状态直接读取 `seaql_migrations`，因此能识别数据库中已应用、但当前二进制不认识的迁移
+//! （数据库被更新的版本迁移过）。这种情况下旧二进制无法启动，需要先用新版本回滚。
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Alias, Asterisk, Expr, Func, Query};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use tracing::info;
+
+use crate::errors::{Result, ShortlinkerError};
+use migration::rollback::{Dropped, RollbackImpact, applied_migrations, rollback_impact};
+use migration::{Migrator, MigratorTrait};
+
+/// 单个迁移的状态
+#[derive(Debug, Clone)]
+pub struct MigrationState {
+    pub name: String,
+    /// `None` 表示尚未应用
+    pub applied_at: Option<DateTime<Utc>>,
+    /// 当前二进制是否包含该迁移
+    pub known: bool,
+}
+
+/// 数据库结构与当前二进制的兼容性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    UpToDate,
+    /// 有未应用的迁移，服务启动时会自动执行
+    Pending(usize),
+    /// 数据库包含当前二进制不认识的迁移
+    Newer(Vec<String>),
+}
+
+/// 读取全部迁移状态：先按当前二进制的迁移顺序，再追加不认识的迁移
+pub async fn migration_status(db: &DatabaseConnection) -> Result<Vec<MigrationState>> {
+    let mut applied = applied_migrations(db).await.map_err(|e| {
+        ShortlinkerError::database_operation(format!("Failed to read migration table: {}", e))
+    })?;
+
+    let mut states: Vec<MigrationState> = Migrator::migrations()
+        .iter()
+        .map(|migration| {
+            let name = migration.name().to_string();
+            let applied_at = applied
+                .iter()
+                .position(|row| row.version == name)
+                .map(|index| applied.remove(index))
+                .and_then(|row| DateTime::from_timestamp(row.applied_at, 0));
+            MigrationState {
+                name,
+                applied_at,
+                known: true,
+            }
+        })
+        .collect();
+
+    states.extend(applied.into_iter().map(|row| MigrationState {
+        name: row.version,
+        applied_at: DateTime::from_timestamp(row.applied_at, 0),
+        known: false,
+    }));
+    Ok(states)
+}
+
+/// 根据迁移状态判断兼容性
+pub fn schema_compatibility(states: &[MigrationState]) -> SchemaCompatibility {
+    let unknown: Vec<String> = states
+        .iter()
+        .filter(|state| !state.known)
+        .map(|state| state.name.clone())
+        .collect();
+    if !unknown.is_empty() {
+        return SchemaCompatibility::Newer(unknown);
+    }
+
+    match states
+        .iter()
+        .filter(|state| state.applied_at.is_none())
+        .count()
+    {
+        0 => SchemaCompatibility::UpToDate,
+        pending => SchemaCompatibility::Pending(pending),
+    }
+}
+
+/// 启动前检查：数据库被更新的版本迁移过时给出回滚指引，而不是迁移库的原始报错
+pub async fn ensure_schema_compatible(db: &DatabaseConnection) -> Result<()> {
+    let states = migration_status(db).await?;
+    if let SchemaCompatibility::Newer(unknown) = schema_compatibility(&states) {
+        let latest_known = states
+            .iter()
+            .filter(|state| state.known && state.applied_at.is_some())
+            .map(|state| state.name.as_str())
+            .next_back()
+            .unwrap_or("<VERSION>");
+        return Err(ShortlinkerError::database_operation(format!(
+            "Database schema is newer than this binary (unknown migrations: {}). \
+             Roll back with the newer binary first: shortlinker migrate down --to {}",
+            unknown.join(", "),
+            latest_known
+        )));
+    }
+    Ok(())
+}
+
+/// 回滚会删除的对象及其中的数据量
+#[derive(Debug, Clone)]
+pub struct DroppedData {
+    pub object: Dropped,
+    /// 表的行数 / 列的非空行数（对象不存在或统计失败时为 `None`）
+    pub rows: Option<u64>,
+}
+
+/// 回滚计划中的一步
+#[derive(Debug, Clone)]
+pub struct RollbackStep {
+    pub name: String,
+    pub impact: RollbackImpact,
+    pub dropped: Vec<DroppedData>,
+}
+
+/// 回滚计划：`steps` 按执行顺序排列（最新的迁移在前）
+#[derive(Debug, Clone)]
+pub struct RollbackPlan {
+    pub target: String,
+    pub steps: Vec<RollbackStep>,
+}
+
+impl RollbackPlan {
+    /// 计划中不可逆的迁移
+    pub fn irreversible(&self) -> Vec<&RollbackStep> {
+        self.steps
+            .iter()
+            .filter(|step| !step.impact.reversible)
+            .collect()
+    }
+}
+
+/// 计划回滚到 `to`（保留 `to` 本身，回滚其后所有已应用的迁移）
+///
+/// `to` 可以是完整迁移名，也可以是能唯一匹配的前缀（如 `m20261016_000006`）。
+pub async fn plan_rollback(db: &DatabaseConnection, to: &str) -> Result<RollbackPlan> {
+    let states = migration_status(db).await?;
+    if let SchemaCompatibility::Newer(unknown) = schema_compatibility(&states) {
+        return Err(ShortlinkerError::validation(format!(
+            "Database contains migrations unknown to this binary ({}); run the rollback with the binary that applied them",
+            unknown.join(", ")
+        )));
+    }
+
+    let matches: Vec<usize> = states
+        .iter()
+        .enumerate()
+        .filter(|(_, state)| state.name == to || state.name.starts_with(to))
+        .map(|(index, _)| index)
+        .collect();
+    let target_index = match matches.as_slice() {
+        [index] => *index,
+        [] => {
+            return Err(ShortlinkerError::validation(format!(
+                "Unknown migration '{}'",
+                to
+            )));
+        }
+        _ => {
+            let names: Vec<&str> = matches.iter().map(|&i| states[i].name.as_str()).collect();
+            return Err(ShortlinkerError::validation(format!(
+                "Migration '{}' is ambiguous: {}",
+                to,
+                names.join(", ")
+            )));
+        }
+    };
+    let target = &states[target_index];
+    if target.applied_at.is_none() {
+        return Err(ShortlinkerError::validation(format!(
+            "Migration '{}' is not applied",
+            target.name
+        )));
+    }
+
+    let mut steps = Vec::new();
+    for state in states[target_index + 1..]
+        .iter()
+        .rev()
+        .filter(|state| state.applied_at.is_some())
+    {
+        let impact = rollback_impact(&state.name).unwrap_or(RollbackImpact::UNREGISTERED);
+        let mut dropped = Vec::with_capacity(impact.drops.len());
+        for object in impact.drops {
+            dropped.push(DroppedData {
+                object: *object,
+                rows: count_rows(db, object).await,
+            });
+        }
+        steps.push(RollbackStep {
+            name: state.name.clone(),
+            impact,
+            dropped,
+        });
+    }
+
+    Ok(RollbackPlan {
+        target: target.name.clone(),
+        steps,
+    })
+}
+
+/// 执行回滚计划；包含不可逆迁移时拒绝执行
+pub async fn rollback(db: &DatabaseConnection, plan: &RollbackPlan) -> Result<()> {
+    let irreversible = plan.irreversible();
+    if !irreversible.is_empty() {
+        let names: Vec<&str> = irreversible.iter().map(|step| step.name.as_str()).collect();
+        return Err(ShortlinkerError::validation(format!(
+            "Refusing to roll back irreversible migrations: {}; restore a backup instead",
+            names.join(", ")
+        )));
+    }
+    if plan.steps.is_empty() {
+        return Ok(());
+    }
+
+    let steps = u32::try_from(plan.steps.len()).unwrap_or(u32::MAX);
+    Migrator::down(db, Some(steps))
+        .await
+        .map_err(|e| ShortlinkerError::database_operation(format!("Rollback failed: {}", e)))?;
+
+    info!(
+        "Rolled back {} migrations, schema is now at {}",
+        plan.steps.len(),
+        plan.target
+    );
+    Ok(())
+}
+
+/// SQLite 备份（`VACUUM INTO`，得到一致的单文件副本）
+pub async fn backup_sqlite(db: &DatabaseConnection, path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(ShortlinkerError::validation(format!(
+            "Backup file '{}' already exists",
+            path.display()
+        )));
+    }
+    let path = path.to_string_lossy().replace('\'', "''");
+    db.execute_unprepared(&format!("VACUUM INTO '{}'", path))
+        .await
+        .map_err(|e| ShortlinkerError::database_operation(format!("Backup failed: {}", e)))?;
+    Ok(())
+}
+
+/// 默认备份路径：数据库文件旁的 `<文件名>.<时间戳>.bak`（内存库返回 `None`）
+pub fn default_sqlite_backup_path(database_url: &str) -> Option<PathBuf> {
+    let path = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))
+        .unwrap_or(database_url);
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path.contains(":memory:") {
+        return None;
+    }
+    Some(PathBuf::from(format!(
+        "{}.{}.bak",
+        path,
+        Utc::now().format("%Y%m%d%H%M%S")
+    )))
+}
+
+/// 统计将被删除的数据量：表的行数 / 列的非空行数
+async fn count_rows(db: &DatabaseConnection, object: &Dropped) -> Option<u64> {
+    let mut query = Query::select();
+    query.expr(Func::count(Expr::col(Asterisk)));
+    match *object {
+        Dropped::Table(table) => {
+            query.from(Alias::new(table));
+        }
+        Dropped::Column(table, column) => {
+            query
+                .from(Alias::new(table))
+                .and_where(Expr::col(Alias::new(column)).is_not_null());
+        }
+    }
+
+    let row = db.query_one(&query).await.ok()??;
+    row.try_get_by_index::<i64>(0)
+        .ok()
+        .and_then(|count| u64::try_from(count).ok())
+}
@@ -4,16 +4,27 @@
 //! supporting SQLite, MySQL/MariaDB, and PostgreSQL.
 
 mod analytics;
+mod api_tokens;
+mod archive;
 mod click_sink;
 mod connection;
 pub(crate) mod converters;
+pub mod maintenance;
+pub mod migrate;
 mod mutations;
 mod operations;
+mod outbox;
 mod query;
+mod retired_codes;
+pub mod sql_redact;
+pub mod statement_log;
 
-pub use analytics::{GeoRow, GroupBy, ReferrerRow, TopLinkRow, TrendRow, UaStatsRow};
+pub use analytics::{
+    GeoRow, GroupBy, HourlyCountRow, MAX_PARAM_FILTER_ROWS, ParamValueRow, PeriodTrendRow,
+    ReferrerRow, TopLinkRow, TrendRow, UaStatsRow,
+};
+pub use query::{CreatedViaCountRow, CreationTrendRow};
 
-use std::borrow::Cow;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -24,11 +35,11 @@ use tracing::warn;
 
 use crate::analytics::ClickSink;
 use crate::errors::{Result, ShortlinkerError};
-use crate::storage::models::StorageConfig;
+use crate::storage::models::{CreatedVia, StorageConfig};
 
 use crate::metrics::MetricsRecorder;
 
-pub use connection::run_migrations;
+pub use connection::{connect, run_migrations};
 pub use converters::{model_to_shortlink, shortlink_to_active_model};
 pub use operations::upsert;
 
@@ -73,6 +84,11 @@ pub struct LinkFilter {
     pub only_expired: bool,
     /// 只返回未过期的链接
     pub only_active: bool,
+    /// 只返回指定渠道创建的链接
+    pub created_via: Option<CreatedVia>,
+    /// extras 顶层 key 精确匹配（均需满足，只匹配字符串值）；key 需先经
+    /// [`is_valid_extras_key`](crate::services::link_validation::is_valid_extras_key) 校验
+    pub extras: Vec<(String, String)>,
 }
 
 /// SeaORM-based storage backend
@@ -106,31 +122,7 @@ impl SeaOrmStorage {
             max_delay_ms: config.database.retry_max_delay_ms,
         };
 
-        // 新配置使用标准 SQLite URL；这里保留旧版允许的裸文件路径和
-        // `:memory:`，在进入 Forge/SeaORM 连接层前转换为等价连接串。
-        let database_url = if backend_name == "sqlite" {
-            if database_url == ":memory:" {
-                Cow::Borrowed("sqlite::memory:")
-            } else if database_url.starts_with("sqlite:") {
-                Cow::Borrowed(database_url)
-            } else {
-                Cow::Owned(format!("sqlite://{database_url}?mode=rwc"))
-            }
-        } else {
-            Cow::Borrowed(database_url)
-        };
-
-        let mut forge_config = aster_forge_db::DatabaseConfig::new(database_url.as_ref());
-        forge_config.pool_size = config.database.pool_size;
-        forge_config.retry_count = config.database.retry_count;
-        let db = aster_forge_db::connect_with_metrics(&forge_config, metrics.forge_recorder())
-            .await
-            .map_err(|error| {
-                ShortlinkerError::database_connection(format!(
-                    "Failed to connect to {} database: {error}",
-                    backend_name.to_uppercase()
-                ))
-            })?;
+        let db = connect(database_url, backend_name, metrics).await?;
 
         let storage = SeaOrmStorage {
             db,
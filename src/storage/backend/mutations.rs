@@ -142,6 +142,9 @@ impl SeaOrmStorage {
                                     short_link::Column::Password,
                                     short_link::Column::CreatedAt,
                                     short_link::Column::ClickCount,
+                                    short_link::Column::CreatedVia,
+                                    short_link::Column::AnalyticsLevel,
+                                    short_link::Column::Extras,
                                 ])
                                 .to_owned(),
                         )
@@ -1,4 +1,4 @@
-use sea_orm::{DatabaseConnection, DbErr, EntityTrait, sea_query::OnConflict};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait, sea_query::OnConflict};
 use tracing::info;
 
 use crate::storage::ShortLink;
@@ -8,8 +8,9 @@ use super::converters::shortlink_to_active_model;
 
 /// 使用 ON CONFLICT 的原子 upsert
 ///
-/// 返回原始 `DbErr` 以便调用方的 retry 机制能识别连接错误类型
-pub async fn upsert(db: &DatabaseConnection, link: &ShortLink) -> Result<(), DbErr> {
+/// 返回原始 `DbErr` 以便调用方的 retry 机制能识别连接错误类型；
+/// 可在连接或事务上执行
+pub async fn upsert<C: ConnectionTrait>(db: &C, link: &ShortLink) -> Result<(), DbErr> {
     let active_model = shortlink_to_active_model(link, true);
 
     short_link::Entity::insert(active_model)
@@ -20,6 +21,8 @@ pub async fn upsert(db: &DatabaseConnection, link: &ShortLink) -> Result<(), DbE
                     short_link::Column::ExpiresAt,
                     short_link::Column::Password,
                     short_link::Column::ClickCount,
+                    short_link::Column::AnalyticsLevel,
+                    short_link::Column::Extras,
                 ])
                 .to_owned(),
         )
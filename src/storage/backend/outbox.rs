@@ -0,0 +1,195 @@
+//! Side effect outbox for SeaOrmStorage
+//!
+//! 链接写入与待执行的收尾副作用在同一事务内提交（表 `pending_side_effects`），
+//! 保证"写库成功但缓存未失效"不会因进程崩溃而永久残留。执行与重放策略见
+//! [`SideEffectRunner`](crate::services::SideEffectRunner)。
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::Expr,
+};
+use tracing::info;
+
+use super::SeaOrmStorage;
+use super::operations::upsert;
+use crate::errors::{Result, ShortlinkerError};
+use crate::storage::{PendingSideEffect, ShortLink, SideEffect};
+
+use migration::entities::{pending_side_effect, short_link};
+
+/// 在事务内写入 outbox 条目，返回与 `effects` 顺序一致的 id
+async fn insert_effects<C: ConnectionTrait>(
+    txn: &C,
+    effects: &[SideEffect],
+) -> std::result::Result<Vec<i64>, sea_orm::DbErr> {
+    let now = Utc::now();
+    let mut ids = Vec::with_capacity(effects.len());
+    for effect in effects {
+        let (effect_type, payload) = effect.encode();
+        let model = pending_side_effect::ActiveModel {
+            id: NotSet,
+            effect_type: Set(effect_type.to_string()),
+            payload: Set(payload),
+            created_at: Set(now),
+            attempts: Set(0),
+            last_error: Set(None),
+        };
+        let result = pending_side_effect::Entity::insert(model).exec(txn).await?;
+        ids.push(result.last_insert_id);
+    }
+    Ok(ids)
+}
+
+impl SeaOrmStorage {
+    /// 写入链接并在同一事务内登记副作用
+    pub async fn set_with_effects(
+        &self,
+        link: ShortLink,
+        effects: &[SideEffect],
+    ) -> Result<Vec<i64>> {
+        let effects = effects.to_vec();
+        let ids = aster_forge_db::transaction::with_transaction_retry(
+            &self.db,
+            &self.retry_config,
+            |txn| {
+                let link = link.clone();
+                let effects = effects.clone();
+                Box::pin(async move {
+                    upsert(txn, &link)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    insert_effects(txn, &effects)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)
+                })
+            },
+            aster_forge_db::DbError::is_retryable,
+        )
+        .await
+        .map_err(ShortlinkerError::from)?;
+
+        self.invalidate_count_cache();
+        Ok(ids)
+    }
+
+    /// 删除链接并在同一事务内登记副作用；链接不存在时不登记任何条目
+    pub async fn remove_with_effects(
+        &self,
+        code: &str,
+        effects: &[SideEffect],
+    ) -> Result<Vec<i64>> {
+        let code_owned = code.to_string();
+        let effects = effects.to_vec();
+        let ids = aster_forge_db::transaction::with_transaction_retry(
+            &self.db,
+            &self.retry_config,
+            |txn| {
+                let code = code_owned.clone();
+                let effects = effects.clone();
+                Box::pin(async move {
+                    let result = short_link::Entity::delete_by_id(code)
+                        .exec(txn)
+                        .await
+                        .map_err(aster_forge_db::DbError::from)?;
+                    if result.rows_affected == 0 {
+                        return Ok(None);
+                    }
+                    insert_effects(txn, &effects)
+                        .await
+                        .map(Some)
+                        .map_err(aster_forge_db::DbError::from)
+                })
+            },
+            aster_forge_db::DbError::is_retryable,
+        )
+        .await
+        .map_err(ShortlinkerError::from)?;
+
+        let Some(ids) = ids else {
+            return Err(ShortlinkerError::not_found(format!(
+                "Short link not found: {}",
+                code
+            )));
+        };
+
+        self.invalidate_count_cache();
+        info!("Short link deleted: {}", code);
+        Ok(ids)
+    }
+
+    /// 删除已执行成功的条目
+    pub async fn delete_side_effects(&self, ids: &[i64]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        pending_side_effect::Entity::delete_many()
+            .filter(pending_side_effect::Column::Id.is_in(ids.iter().copied()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "Failed to delete pending side effects: {}",
+                    e
+                ))
+            })?;
+        Ok(())
+    }
+
+    /// 按 id 顺序加载创建时间早于 `created_before`、失败次数低于 `max_attempts` 的条目
+    pub async fn load_pending_side_effects(
+        &self,
+        created_before: DateTime<Utc>,
+        max_attempts: i32,
+        limit: u64,
+    ) -> Result<Vec<PendingSideEffect>> {
+        let models = pending_side_effect::Entity::find()
+            .filter(pending_side_effect::Column::CreatedAt.lt(created_before))
+            .filter(pending_side_effect::Column::Attempts.lt(max_attempts))
+            .order_by_asc(pending_side_effect::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "Failed to load pending side effects: {}",
+                    e
+                ))
+            })?;
+
+        Ok(models
+            .into_iter()
+            .map(|m| PendingSideEffect {
+                id: m.id,
+                effect_type: m.effect_type,
+                payload: m.payload,
+                created_at: m.created_at,
+                attempts: m.attempts,
+            })
+            .collect())
+    }
+
+    /// 记录一次执行失败（attempts + 1），条目保留等待下次重放
+    pub async fn record_side_effect_failure(&self, id: i64, error: &str) -> Result<()> {
+        pending_side_effect::Entity::update_many()
+            .col_expr(
+                pending_side_effect::Column::Attempts,
+                Expr::col(pending_side_effect::Column::Attempts).add(1),
+            )
+            .col_expr(
+                pending_side_effect::Column::LastError,
+                Expr::value(error.to_string()),
+            )
+            .filter(pending_side_effect::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "Failed to record side effect failure: {}",
+                    e
+                ))
+            })?;
+        Ok(())
+    }
+}
@@ -17,13 +17,18 @@ use super::{LinkFilter, SeaOrmStorage};
 use crate::errors::{Result, ShortlinkerError};
 use crate::storage::ShortLink;
 use crate::storage::models::LinkStats;
+use crate::utils::shard::CodeShard;
 
 use migration::entities::short_link;
 
 use super::converters::model_to_shortlink;
 
 /// 根据 LinkFilter 构建 SeaORM 查询条件
-fn build_filter_condition(filter: &LinkFilter, now: chrono::DateTime<Utc>) -> Condition {
+fn build_filter_condition(
+    filter: &LinkFilter,
+    backend: &str,
+    now: chrono::DateTime<Utc>,
+) -> Condition {
     let mut condition = Condition::all();
 
     // search: 模糊匹配 code 或 target
@@ -52,6 +57,11 @@ fn build_filter_condition(filter: &LinkFilter, now: chrono::DateTime<Utc>) -> Co
         condition = condition.add(short_link::Column::ExpiresAt.lt(now));
     }
 
+    // created_via: 按创建渠道过滤
+    if let Some(via) = filter.created_via {
+        condition = condition.add(short_link::Column::CreatedVia.eq(via.as_str()));
+    }
+
     // only_active: 只返回未过期的（expires_at 为 null 或 > now）
     if filter.only_active {
         condition = condition.add(
@@ -61,9 +71,52 @@ fn build_filter_condition(filter: &LinkFilter, now: chrono::DateTime<Utc>) -> Co
         );
     }
 
+    // extras: 顶层 key 精确匹配
+    for (key, value) in &filter.extras {
+        condition = condition.add(extras_match_expr(backend, key, value));
+    }
+
     condition
 }
 
+/// extras 顶层 key 等于给定字符串的表达式
+///
+/// extras 以文本存储且没有索引，各数据库都需逐行解析 JSON（全表扫描），
+/// 数据量大时应与其他过滤条件组合使用。只匹配字符串值：`{"n": 1}` 不匹配 `n=1`。
+/// key 已限制为 `[A-Za-z0-9_-]`，可直接拼入 JSON 路径。
+fn extras_match_expr(backend: &str, key: &str, value: &str) -> Expr {
+    let path = format!("$.\"{}\"", key);
+    match backend {
+        // json_extract 对字符串返回 TEXT，对数字返回 INTEGER / REAL，不会与绑定的文本相等
+        "sqlite" => {
+            Expr::cust_with_values("json_extract(extras, ?) = ?", [path, value.to_string()])
+        }
+        "mysql" => Expr::cust_with_values(
+            "(JSON_TYPE(JSON_EXTRACT(extras, ?)) = 'STRING' AND JSON_UNQUOTE(JSON_EXTRACT(extras, ?)) = ?)",
+            [path.clone(), path, value.to_string()],
+        ),
+        _ => Expr::cust_with_values(
+            "(extras::jsonb -> ?) = to_jsonb(?::text)",
+            [key.to_string(), value.to_string()],
+        ),
+    }
+}
+
+/// 按创建渠道分组计数的结果行
+#[derive(Debug, FromQueryResult)]
+pub struct CreatedViaCountRow {
+    pub created_via: String,
+    pub count: i64,
+}
+
+/// 按天、按创建渠道分组计数的结果行
+#[derive(Debug, FromQueryResult)]
+pub struct CreationTrendRow {
+    pub label: String,
+    pub created_via: String,
+    pub count: i64,
+}
+
 /// 用于统计查询的结果结构体（DSL 聚合查询）
 #[derive(Debug, FromQueryResult)]
 struct StatsResult {
@@ -94,6 +147,16 @@ impl SeaOrmStorage {
     }
 
     pub async fn load_all(&self) -> Result<HashMap<String, ShortLink>> {
+        self.load_all_in_shard(None).await
+    }
+
+    /// 加载所有短链接，指定分片时只保留归该分片的短码
+    ///
+    /// 分片哈希（FNV-1a）无法在各数据库方言中统一表达，过滤在应用层进行。
+    pub async fn load_all_in_shard(
+        &self,
+        shard: Option<CodeShard>,
+    ) -> Result<HashMap<String, ShortLink>> {
         let models = short_link::Entity::find()
             .all(&self.db)
             .await
@@ -104,15 +167,15 @@ impl SeaOrmStorage {
                 ))
             })?;
 
-        let count = models.len();
         let links: HashMap<String, ShortLink> = models
             .into_iter()
+            .filter(|model| shard.is_none_or(|shard| shard.owns(&model.short_code)))
             .map(|model| {
                 let link = model_to_shortlink(model);
                 (link.code.clone(), link)
             })
             .collect();
-        info!("Loaded {} short links", count);
+        info!("Loaded {} short links", links.len());
         Ok(links)
     }
 
@@ -201,16 +264,18 @@ impl SeaOrmStorage {
 
         // 生成缓存 key（基于过滤条件）
         let cache_key = format!(
-            "count:s={:?}:a={:?}:b={:?}:e={}:v={}",
+            "count:s={:?}:a={:?}:b={:?}:e={}:v={}:c={:?}:x={:?}",
             filter.search,
             filter.created_after.map(|d| d.timestamp()),
             filter.created_before.map(|d| d.timestamp()),
             filter.only_expired,
-            filter.only_active
+            filter.only_active,
+            filter.created_via,
+            filter.extras
         );
 
         // 构建查询条件
-        let condition = build_filter_condition(&filter, now);
+        let condition = build_filter_condition(&filter, &self.backend_name, now);
 
         // 尝试从缓存获取总数
         let total = if let Some(cached) = self.count_cache.get(&cache_key) {
@@ -365,7 +430,7 @@ impl SeaOrmStorage {
     ) -> Pin<Box<dyn Stream<Item = Result<Vec<ShortLink>>> + Send + 'static>> {
         let db = self.db.clone();
         let now = Utc::now();
-        let condition = build_filter_condition(&filter, now);
+        let condition = build_filter_condition(&filter, &self.backend_name, now);
 
         use futures_util::stream;
 
@@ -458,4 +523,55 @@ impl SeaOrmStorage {
             None => Ok(LinkStats::default()),
         }
     }
+
+    /// 按 created_at 取 UTC 日期（YYYY-MM-DD）的表达式
+    fn created_day_expr(&self) -> Expr {
+        match self.backend_name.as_str() {
+            "sqlite" => Expr::cust("strftime('%Y-%m-%d', created_at)"),
+            "mysql" => Expr::cust("DATE_FORMAT(created_at, '%Y-%m-%d')"),
+            _ => Expr::cust("TO_CHAR(created_at, 'YYYY-MM-DD')"),
+        }
+    }
+
+    /// 按创建渠道统计链接数
+    pub async fn count_by_created_via(&self) -> Result<Vec<CreatedViaCountRow>> {
+        short_link::Entity::find()
+            .select_only()
+            .column(short_link::Column::CreatedVia)
+            .column_as(short_link::Column::ShortCode.count(), "count")
+            .group_by(short_link::Column::CreatedVia)
+            .into_model::<CreatedViaCountRow>()
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!(
+                    "created_via stats query failed: {}",
+                    e
+                ))
+            })
+    }
+
+    /// 统计 `since` 之后按天、按创建渠道分组的创建数
+    pub async fn get_creation_trend(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<CreationTrendRow>> {
+        let date_expr = self.created_day_expr();
+
+        short_link::Entity::find()
+            .select_only()
+            .column_as(date_expr.clone(), "label")
+            .column(short_link::Column::CreatedVia)
+            .column_as(short_link::Column::ShortCode.count(), "count")
+            .filter(short_link::Column::CreatedAt.gte(since))
+            .group_by(date_expr.clone())
+            .group_by(short_link::Column::CreatedVia)
+            .order_by_asc(date_expr)
+            .into_model::<CreationTrendRow>()
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                ShortlinkerError::database_operation(format!("Creation trend query failed: {}", e))
+            })
+    }
 }
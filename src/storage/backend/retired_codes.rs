@@ -0,0 +1,83 @@
+//! Retired short codes for SeaOrmStorage
+//!
+//! 开启 `links.code_reuse_cooldown_days` 后删除的短码记录在 `retired_codes` 表，
+//! 创建路径据此拒绝冷却期内的复用；过期记录由 DataRetentionTask 清理。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, sea_query::OnConflict};
+
+use super::SeaOrmStorage;
+use crate::errors::{Result, ShortlinkerError};
+
+use migration::entities::retired_code;
+
+fn db_error(action: &str) -> impl FnOnce(sea_orm::DbErr) -> ShortlinkerError + '_ {
+    move |e| ShortlinkerError::database_operation(format!("Failed to {}: {}", action, e))
+}
+
+impl SeaOrmStorage {
+    /// 记录被删除的短码；重复删除时以最近一次的时间为准
+    pub async fn retire_codes(&self, codes: &[String], retired_at: DateTime<Utc>) -> Result<()> {
+        if codes.is_empty() {
+            return Ok(());
+        }
+        let models = codes.iter().map(|code| retired_code::ActiveModel {
+            short_code: Set(code.clone()),
+            retired_at: Set(retired_at),
+        });
+        retired_code::Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::column(retired_code::Column::ShortCode)
+                    .update_column(retired_code::Column::RetiredAt)
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(db_error("record retired codes"))?;
+        Ok(())
+    }
+
+    /// 查询短码的删除时间（未记录的短码不出现在结果中）
+    pub async fn get_retired_codes(
+        &self,
+        codes: &[&str],
+    ) -> Result<HashMap<String, DateTime<Utc>>> {
+        if codes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let models = retired_code::Entity::find()
+            .filter(retired_code::Column::ShortCode.is_in(codes.iter().copied()))
+            .all(&self.db)
+            .await
+            .map_err(db_error("load retired codes"))?;
+        Ok(models
+            .into_iter()
+            .map(|m| (m.short_code, m.retired_at))
+            .collect())
+    }
+
+    /// 清除指定短码的记录（冷却期内被强制复用）
+    pub async fn release_retired_codes(&self, codes: &[String]) -> Result<()> {
+        if codes.is_empty() {
+            return Ok(());
+        }
+        retired_code::Entity::delete_many()
+            .filter(retired_code::Column::ShortCode.is_in(codes.iter().cloned()))
+            .exec(&self.db)
+            .await
+            .map_err(db_error("release retired codes"))?;
+        Ok(())
+    }
+
+    /// 删除 `retired_before` 之前的记录，返回删除条数
+    pub async fn purge_retired_codes(&self, retired_before: DateTime<Utc>) -> Result<u64> {
+        let result = retired_code::Entity::delete_many()
+            .filter(retired_code::Column::RetiredAt.lt(retired_before))
+            .exec(&self.db)
+            .await
+            .map_err(db_error("purge retired codes"))?;
+        Ok(result.rows_affected)
+    }
+}
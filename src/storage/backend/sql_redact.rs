@@ -0,0 +1,458 @@
+//! SQL 语句日志的参数脱敏
+//!
+//! 只处理字符串：调用方先把绑定参数渲染成 SQL 字面量，这里按占位符推断每个参数
+//! 对应的列名。默认掩码（fail closed）：只有列名在 [`LOGGABLE_COLUMNS`] 白名单中的值
+//! 才原样输出（超长截断），推断不出列名或列不在白名单中的值一律掩码，新增列不会
+//! 因为忘记登记而泄露。不依赖 SeaORM 类型，规则可以单独测试。
+
+use std::fmt::Write;
+
+/// 值可以原样输出的列名（不区分大小写），其余列的值一律掩码
+///
+/// 只收录标识符、时间、计数与枚举类字段。`target_url`（可能带 `user:pass@` 或
+/// `access_token=` 等查询参数）、`password`、`token_hash`、`ip_address`、
+/// 运行时配置的 `value` / `old_value` / `new_value`、`referrer`、`query_params`、
+/// `payload`、`extras` 等可能含敏感信息的列刻意不在其中。`limit` / `offset` 是
+/// LIMIT / OFFSET 子句参数的伪列名。
+pub const LOGGABLE_COLUMNS: &[&str] = &[
+    // short_links / short_link_archive / retired_codes
+    "short_code",
+    "created_at",
+    "expires_at",
+    "click_count",
+    "created_via",
+    "analytics_level",
+    "archived_at",
+    "retired_at",
+    // 点击统计与汇总
+    "id",
+    "clicked_at",
+    "country",
+    "source",
+    "day_bucket",
+    "hour_bucket",
+    "week_start",
+    "month_start",
+    "total_clicks",
+    "unique_links",
+    "range_start",
+    "range_end",
+    "removed_rows",
+    "clicks_before",
+    "clicks_after",
+    // redirect_timings
+    "recorded_at",
+    "status",
+    "total_us",
+    "cache_us",
+    "bloom_us",
+    "db_us",
+    "geo_us",
+    "enqueue_us",
+    // api_tokens / api_token_daily_usage
+    "token_id",
+    "name",
+    "max_links",
+    "max_daily_creates",
+    "max_analytics_level",
+    "day",
+    "created_count",
+    // config_history / pending_side_effects / user_agents
+    "config_key",
+    "changed_at",
+    "effect_type",
+    "attempts",
+    "hash",
+    "first_seen",
+    "last_seen",
+    "is_bot",
+    // LIMIT / OFFSET 子句
+    "limit",
+    "offset",
+];
+
+/// 单个参数值保留的最大字符数
+pub const MAX_PARAM_CHARS: usize = 64;
+
+/// 最多输出的参数个数（批量写入可能绑定上千个参数）
+pub const MAX_PARAMS: usize = 32;
+
+/// SQL 模板保留的最大字符数
+pub const MAX_SQL_CHARS: usize = 2048;
+
+const MASK: &str = "'***'";
+
+/// 不当作列名的关键字；其余裸单词（后面不跟 `(` 的）视为标识符
+const KEYWORDS: &[&str] = &[
+    "ALL",
+    "AND",
+    "AS",
+    "ASC",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CONFLICT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "ELSE",
+    "END",
+    "EXISTS",
+    "FALSE",
+    "FROM",
+    "GROUP",
+    "HAVING",
+    "IGNORE",
+    "ILIKE",
+    "IN",
+    "INNER",
+    "INSERT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NOT",
+    "NOTHING",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "RETURNING",
+    "SELECT",
+    "SET",
+    "THEN",
+    "TRUE",
+    "UNION",
+    "UPDATE",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+];
+
+/// 列名是否属于 [`LOGGABLE_COLUMNS`]
+pub fn is_loggable_column(column: &str) -> bool {
+    LOGGABLE_COLUMNS
+        .iter()
+        .any(|loggable| loggable.eq_ignore_ascii_case(column))
+}
+
+/// 截断到 `max` 个字符，并注明原长度
+pub fn truncate(value: &str, max: usize) -> String {
+    match value.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…({} chars)", &value[..idx], value.chars().count()),
+        None => value.to_string(),
+    }
+}
+
+/// 脱敏单个已渲染的参数值：白名单列超长截断，其余（含列名未知）掩码
+pub fn redact_value(column: Option<&str>, rendered: &str) -> String {
+    if !column.is_some_and(is_loggable_column) {
+        return MASK.to_string();
+    }
+    truncate(rendered, MAX_PARAM_CHARS)
+}
+
+/// 按参数下标返回每个绑定参数对应的列名
+///
+/// `?`（SQLite / MySQL）按出现顺序编号，`$N`（PostgreSQL）按 N 编号。列名取占位符
+/// 前最近的标识符（`"password" = ?`、`"short_code" IN (?, ?)`）；INSERT 的 VALUES
+/// 元组按列清单中的位置对应，LIMIT / OFFSET 的参数记为 `limit` / `offset`。推断不出时为 `None`。
+pub fn placeholder_columns(sql: &str) -> Vec<Option<String>> {
+    let mut scan = Scan::default();
+    let mut chars = sql.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\'' => {
+                // 字符串字面量，`''` 为转义的单引号
+                while let Some((_, c)) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek().is_some_and(|&(_, next)| next == '\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' | '`' => {
+                let mut end = sql.len();
+                for (idx, next) in chars.by_ref() {
+                    if next == c {
+                        end = idx;
+                        break;
+                    }
+                }
+                scan.identifier(&sql[start + 1..end]);
+            }
+            '?' => {
+                let index = scan.next_positional;
+                scan.next_positional += 1;
+                scan.placeholder(index);
+            }
+            '$' => {
+                let mut number = 0usize;
+                let mut digits = false;
+                while let Some(&(_, d)) = chars.peek() {
+                    let Some(digit) = d.to_digit(10) else { break };
+                    number = number.saturating_mul(10).saturating_add(digit as usize);
+                    digits = true;
+                    chars.next();
+                }
+                if digits && number > 0 {
+                    scan.placeholder(number - 1);
+                }
+            }
+            '(' => scan.open(),
+            ')' => scan.close(),
+            ',' => scan.comma(),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = sql.len();
+                while let Some(&(idx, next)) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '_') {
+                        end = idx;
+                        break;
+                    }
+                    chars.next();
+                }
+                let word = &sql[start..end];
+                let is_call = sql[end..].trim_start().starts_with('(');
+                scan.word(word, is_call);
+            }
+            c if c.is_ascii_digit() => {
+                // 数字字面量
+                while chars
+                    .peek()
+                    .is_some_and(|&(_, next)| next.is_ascii_alphanumeric() || next == '.')
+                {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    scan.columns
+}
+
+/// 把全部参数格式化为 `[short_code='abc', password='***', 10]`
+pub fn format_params(sql: &str, rendered: &[String]) -> String {
+    let columns = placeholder_columns(sql);
+    let mut out = String::from("[");
+    for (i, value) in rendered.iter().take(MAX_PARAMS).enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        let column = columns.get(i).and_then(Option::as_deref);
+        if let Some(column) = column {
+            out.push_str(column);
+            out.push('=');
+        }
+        out.push_str(&redact_value(column, value));
+    }
+    if rendered.len() > MAX_PARAMS {
+        let _ = write!(out, ", …({} more)", rendered.len() - MAX_PARAMS);
+    }
+    out.push(']');
+    out
+}
+
+/// [`placeholder_columns`] 的扫描状态
+#[derive(Default)]
+struct Scan {
+    columns: Vec<Option<String>>,
+    next_positional: usize,
+    depth: usize,
+    last_identifier: Option<String>,
+    /// 见到 INSERT，等待列清单
+    awaiting_insert_columns: bool,
+    in_insert_columns: bool,
+    insert_columns: Vec<String>,
+    /// 处于 INSERT ... VALUES 的元组中
+    in_values: bool,
+    tuple_position: usize,
+}
+
+impl Scan {
+    fn identifier(&mut self, name: &str) {
+        if self.in_insert_columns {
+            self.insert_columns.push(name.to_string());
+        } else {
+            self.last_identifier = Some(name.to_string());
+        }
+    }
+
+    fn word(&mut self, word: &str, is_call: bool) {
+        let upper = word.to_ascii_uppercase();
+        match upper.as_str() {
+            "INSERT" => {
+                self.awaiting_insert_columns = true;
+                self.insert_columns.clear();
+            }
+            "VALUES" => {
+                self.awaiting_insert_columns = false;
+                self.in_values = true;
+                self.tuple_position = 0;
+            }
+            "LIMIT" | "OFFSET" => self.last_identifier = Some(upper.to_ascii_lowercase()),
+            _ if self.in_values && self.depth == 0 => self.in_values = false,
+            _ => {}
+        }
+        if !is_call && !KEYWORDS.contains(&upper.as_str()) {
+            self.identifier(word);
+        }
+    }
+
+    fn open(&mut self) {
+        self.depth += 1;
+        if self.depth == 1 {
+            if self.awaiting_insert_columns {
+                self.in_insert_columns = true;
+            } else if self.in_values {
+                self.tuple_position = 0;
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        if self.depth == 1 && self.in_insert_columns {
+            self.in_insert_columns = false;
+            self.awaiting_insert_columns = false;
+        }
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn comma(&mut self) {
+        if self.in_values && self.depth == 1 {
+            self.tuple_position += 1;
+        }
+    }
+
+    fn placeholder(&mut self, index: usize) {
+        let column = if self.in_values && self.depth >= 1 {
+            self.insert_columns.get(self.tuple_position).cloned()
+        } else {
+            self.last_identifier.clone()
+        };
+        if self.columns.len() <= index {
+            self.columns.resize(index + 1, None);
+        }
+        if self.columns[index].is_none() {
+            self.columns[index] = column;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_columns(sql: &str, expected: &[Option<&str>]) {
+        let expected: Vec<Option<String>> =
+            expected.iter().map(|c| c.map(str::to_string)).collect();
+        assert_eq!(placeholder_columns(sql), expected);
+    }
+
+    #[test]
+    fn test_where_comparisons_and_in_lists() {
+        assert_columns(
+            r#"SELECT "short_code" FROM "short_links" WHERE "short_code" IN (?, ?) AND "password" = ? LIMIT ?"#,
+            &[
+                Some("short_code"),
+                Some("short_code"),
+                Some("password"),
+                Some("limit"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_insert_values_follow_column_list() {
+        let sql = r#"INSERT INTO "short_links" ("short_code", "target_url", "password") VALUES (?, ?, ?), (?, ?, ?) ON CONFLICT ("short_code") DO UPDATE SET "target_url" = "excluded"."target_url""#;
+        assert_columns(
+            sql,
+            &[
+                Some("short_code"),
+                Some("target_url"),
+                Some("password"),
+                Some("short_code"),
+                Some("target_url"),
+                Some("password"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_postgres_numbered_placeholders() {
+        assert_columns(
+            r#"UPDATE "api_tokens" SET "token_hash" = $2 WHERE "id" = $1"#,
+            &[Some("id"), Some("token_hash")],
+        );
+    }
+
+    #[test]
+    fn test_literals_and_function_calls_are_skipped() {
+        assert_columns(
+            r#"SELECT * FROM t WHERE note = 'what? "x"' AND LOWER(name) LIKE ? AND n > 10"#,
+            &[Some("name")],
+        );
+        assert_columns("SELECT 1", &[]);
+    }
+
+    #[test]
+    fn test_redact_masks_everything_outside_the_allowlist() {
+        assert_eq!(redact_value(Some("PASSWORD"), "'$argon2id$...'"), "'***'");
+        assert_eq!(redact_value(Some("SHORT_CODE"), "'abc'"), "'abc'");
+        // 列名推断失败或未登记的新列同样掩码
+        assert_eq!(redact_value(None, "'secret'"), "'***'");
+        assert_eq!(redact_value(Some("new_secret_column"), "'s3cr3t'"), "'***'");
+
+        let long = format!("'{}'", "x".repeat(100));
+        let redacted = redact_value(Some("short_code"), &long);
+        assert!(redacted.starts_with(&format!("'{}", "x".repeat(MAX_PARAM_CHARS - 1))));
+        assert!(redacted.ends_with("…(102 chars)"));
+    }
+
+    #[test]
+    fn test_target_url_with_credentials_is_masked() {
+        let sql = r#"UPDATE "short_links" SET "target_url" = ? WHERE "short_code" = ?"#;
+        let rendered = vec![
+            "'https://u:p@host/?token=x'".to_string(),
+            "'gh'".to_string(),
+        ];
+        assert_eq!(
+            format_params(sql, &rendered),
+            "[target_url='***', short_code='gh']"
+        );
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        assert_eq!(truncate("短链接服务", 2), "短链…(5 chars)");
+        assert_eq!(truncate("abc", 3), "abc");
+    }
+
+    #[test]
+    fn test_format_params() {
+        let sql =
+            r#"UPDATE "short_links" SET "password" = ?, "click_count" = ? WHERE "short_code" = ?"#;
+        let rendered = vec!["'secret'".to_string(), "3".to_string(), "'gh'".to_string()];
+        assert_eq!(
+            format_params(sql, &rendered),
+            "[password='***', click_count=3, short_code='gh']"
+        );
+
+        let rendered = vec!["'unknown'".to_string(), "10".to_string()];
+        assert_eq!(
+            format_params("SELECT ? LIMIT ?", &rendered),
+            "['***', limit=10]"
+        );
+
+        let many: Vec<String> = (0..MAX_PARAMS + 5).map(|i| i.to_string()).collect();
+        assert!(format_params("SELECT ?", &many).ends_with(", …(5 more)]"));
+    }
+}
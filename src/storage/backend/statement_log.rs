@@ -0,0 +1,211 @@
+//! SQL 语句日志
+//!
+//! 排查只在生产出现的查询问题时临时打开：`database.log_statements` 为 `slow_only`
+//! 或 `all` 时，在 trace 级别（target `shortlinker::sql`）输出执行过的 SQL 模板、
+//! 脱敏后的绑定参数（规则见 [`sql_redact`](super::sql_redact)）、耗时与调用方
+//! 操作名。开关热更新；默认 `off` 时每条语句只多一次配置版本比较。
+//!
+//! 挂在 SeaORM 的 metric callback 上。SeaORM 每个连接只保留一个回调，这里接管后
+//! 继续按 Forge 的口径记录 `db_queries_*` 指标。
+
+use std::borrow::Cow;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use aster_forge_metrics::{
+    DbMetricBackend, DbMetricsRecorder, DbQueryKind, DbQueryMetric,
+    SharedMetricsRecorder as SharedForgeMetricsRecorder,
+};
+use sea_orm::sea_query::{
+    MysqlQueryBuilder, PostgresQueryBuilder, QueryBuilder, SqliteQueryBuilder, Value,
+};
+use sea_orm::{DatabaseConnection, DbBackend, metric};
+use tracing::{Level, trace};
+
+use super::sql_redact::{MAX_SQL_CHARS, format_params, truncate};
+use crate::config::keys;
+use crate::config::runtime_config::try_get_runtime_config;
+
+/// 语句日志的 tracing target
+pub const LOG_TARGET: &str = "shortlinker::sql";
+
+/// 未设置 `database.slow_statement_ms` 时的慢语句阈值
+const DEFAULT_SLOW_STATEMENT_MS: u64 = 200;
+
+tokio::task_local! {
+    static OPERATION: Cow<'static, str>;
+}
+
+/// `database.log_statements` 的取值
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatementLogMode {
+    #[default]
+    Off,
+    /// 只记录耗时超过 `database.slow_statement_ms` 的语句
+    SlowOnly,
+    All,
+}
+
+impl StatementLogMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "slow_only" => Some(Self::SlowOnly),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::SlowOnly => "slow_only",
+            Self::All => "all",
+        }
+    }
+}
+
+struct ActiveSettings {
+    version: u64,
+    mode: StatementLogMode,
+    slow_threshold: Duration,
+}
+
+static ACTIVE_SETTINGS: LazyLock<ArcSwap<ActiveSettings>> = LazyLock::new(|| {
+    ArcSwap::from_pointee(ActiveSettings {
+        version: u64::MAX,
+        mode: StatementLogMode::Off,
+        slow_threshold: Duration::from_millis(DEFAULT_SLOW_STATEMENT_MS),
+    })
+});
+
+/// 获取当前设置（运行时配置版本变化时重新读取）
+///
+/// 在 metric callback 中同步调用，只读运行时配置的内存缓存，不会再触发查询。
+fn settings() -> Arc<ActiveSettings> {
+    let Some(rt) = try_get_runtime_config() else {
+        return ACTIVE_SETTINGS.load_full();
+    };
+
+    let version = rt.version();
+    let current = ACTIVE_SETTINGS.load_full();
+    if current.version == version {
+        return current;
+    }
+
+    let settings = Arc::new(ActiveSettings {
+        version,
+        mode: StatementLogMode::parse(&rt.get_or(keys::DATABASE_LOG_STATEMENTS, "off"))
+            .unwrap_or_default(),
+        slow_threshold: Duration::from_millis(
+            rt.get_u64_or(keys::DATABASE_SLOW_STATEMENT_MS, DEFAULT_SLOW_STATEMENT_MS),
+        ),
+    });
+    ACTIVE_SETTINGS.store(settings.clone());
+    settings
+}
+
+/// 在 `operation` 的名义下运行 `fut`，期间执行的语句日志带上该操作名
+///
+/// 由 Admin API 请求、IPC 命令分发与后台任务监控在入口处设置；未设置时记为 `-`。
+pub async fn with_operation<F: Future>(
+    operation: impl Into<Cow<'static, str>>,
+    fut: F,
+) -> F::Output {
+    OPERATION.scope(operation.into(), fut).await
+}
+
+/// 在连接上安装语句日志与 Forge 数据库指标的回调
+pub fn install(db: &mut DatabaseConnection, forge: SharedForgeMetricsRecorder) {
+    let backend = db.get_database_backend();
+    let metric_backend = match backend {
+        DbBackend::MySql => DbMetricBackend::MySql,
+        DbBackend::Postgres => DbMetricBackend::Postgres,
+        _ => DbMetricBackend::Sqlite,
+    };
+    let record_metrics = DbMetricsRecorder::enabled(forge.as_ref());
+
+    db.set_metric_callback(move |info: &metric::Info<'_>| {
+        if record_metrics {
+            DbMetricsRecorder::record_db_query(
+                forge.as_ref(),
+                &DbQueryMetric::new(
+                    metric_backend,
+                    query_kind(&info.statement.sql),
+                    info.failed,
+                    info.elapsed,
+                ),
+            );
+        }
+        log_statement(info, backend);
+    });
+}
+
+fn log_statement(info: &metric::Info<'_>, backend: DbBackend) {
+    let settings = settings();
+    let slow = info.elapsed >= settings.slow_threshold;
+    match settings.mode {
+        StatementLogMode::Off => return,
+        StatementLogMode::SlowOnly if !slow => return,
+        _ => {}
+    }
+    if !tracing::enabled!(target: LOG_TARGET, Level::TRACE) {
+        return;
+    }
+
+    let statement = info.statement;
+    let rendered: Vec<String> = statement
+        .values
+        .as_ref()
+        .map(|values| values.0.iter().map(|v| render_value(backend, v)).collect())
+        .unwrap_or_default();
+    let params = format_params(&statement.sql, &rendered);
+    let sql = truncate(&statement.sql, MAX_SQL_CHARS);
+
+    let emit = |operation: &str| {
+        trace!(
+            target: LOG_TARGET,
+            operation,
+            elapsed = ?info.elapsed,
+            slow,
+            failed = info.failed,
+            params = %params,
+            "{}",
+            sql
+        );
+    };
+    if OPERATION
+        .try_with(|operation| emit(operation.as_ref()))
+        .is_err()
+    {
+        emit("-");
+    }
+}
+
+fn render_value(backend: DbBackend, value: &Value) -> String {
+    match backend {
+        DbBackend::MySql => MysqlQueryBuilder.value_to_string(value),
+        DbBackend::Postgres => PostgresQueryBuilder.value_to_string(value),
+        _ => SqliteQueryBuilder.value_to_string(value),
+    }
+}
+
+fn query_kind(sql: &str) -> DbQueryKind {
+    let verb = sql
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or_default();
+    if verb.eq_ignore_ascii_case("select") || verb.eq_ignore_ascii_case("with") {
+        DbQueryKind::Select
+    } else if verb.eq_ignore_ascii_case("insert") {
+        DbQueryKind::Insert
+    } else if verb.eq_ignore_ascii_case("update") {
+        DbQueryKind::Update
+    } else if verb.eq_ignore_ascii_case("delete") {
+        DbQueryKind::Delete
+    } else {
+        DbQueryKind::Other
+    }
+}
@@ -88,15 +88,27 @@ impl ConfigStore {
 
     /// 设置配置值
     pub async fn set(&self, key: &str, value: &str) -> Result<ConfigUpdateResult> {
+        self.set_by(key, value, None).await
+    }
+
+    /// 设置配置值，并在变更历史中记录操作方（`changed_by`）
+    pub async fn set_by(
+        &self,
+        key: &str,
+        value: &str,
+        changed_by: Option<&str>,
+    ) -> Result<ConfigUpdateResult> {
         let retry_config = aster_forge_db::retry::RetryConfig::deadlock();
         let key = key.to_string();
         let value = value.to_string();
+        let changed_by = changed_by.map(str::to_string);
         aster_forge_db::transaction::with_transaction_retry(
             &self.db,
             &retry_config,
             |txn| {
                 let key = key.clone();
                 let value = value.clone();
+                let changed_by = changed_by.clone();
                 Box::pin(async move {
                     SYSTEM_CONFIG_BINDING.lock_by_key(txn, &key).await?;
                     let old_record = SYSTEM_CONFIG_BINDING
@@ -144,7 +156,7 @@ impl ConfigStore {
                         old_value: Set(history_old_value),
                         new_value: Set(history_new_value),
                         changed_at: Set(updated.updated_at),
-                        changed_by: Set(None),
+                        changed_by: Set(changed_by),
                     }
                     .insert(txn)
                     .await
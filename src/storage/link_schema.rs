@@ -0,0 +1,170 @@
+//! 链接的对外序列化 schema
+//!
+//! 导出文件（CSV）、Admin API 响应与 IPC 响应共用同一套字段名、类型与时间格式，
+//! 对接脚本只需按这里的定义解析一次：
+//!
+//! | 字段 | 类型 | 缺省语义 |
+//! |------|------|----------|
+//! | `code` | string | 必填 |
+//! | `target` | string | 必填 |
+//! | `created_at` | RFC 3339 时间 | 必填 |
+//! | `expires_at` | RFC 3339 时间 | 缺省 / 空为永不过期 |
+//! | `password` | string | 缺省 / 空为无密码 |
+//! | `click_count` | 非负整数 | 缺省为 0 |
+//! | `created_via` | `api` / `cli` / `import` / ... | 缺省为 `unknown` |
+//! | `analytics_level` | `inherit` / `none` / `count_only` / `aggregate` / `full` | 缺省为 `inherit` |
+//! | `extras` | JSON 对象（CSV 中为紧凑 JSON 文本） | 缺省 / `null` / 空为无扩展字段 |
+//!
+//! 时间统一为 UTC，以 `Z` 结尾，秒以下的精度按实际值输出（如 `2026-01-01T00:00:00Z`、
+//! `2026-01-01T00:00:00.123Z`）。
+//!
+//! 文件型导出在头部携带 [`LINK_SCHEMA_VERSION`]；schema 发生不兼容变化时递增。
+//! 旧字段名 `click`（IPC 曾使用）在反序列化时仍被接受，保留一个版本后移除。
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::models::{AnalyticsLevel, CreatedVia, ShortLink};
+
+/// 当前链接序列化 schema 的版本
+pub const LINK_SCHEMA_VERSION: u32 = 1;
+
+/// 链接的规范序列化形式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub code: String,
+    pub target: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 旧字段名 `click` 仅用于读取旧版本 IPC 数据，下一个 schema 版本移除
+    #[serde(default, alias = "click")]
+    pub click_count: usize,
+    #[serde(default)]
+    pub created_via: CreatedVia,
+    #[serde(default)]
+    pub analytics_level: AnalyticsLevel,
+    #[serde(default)]
+    pub extras: Option<Value>,
+}
+
+/// 按 schema 格式化时间（UTC、`Z` 结尾、按需输出秒以下精度）
+pub fn format_timestamp(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// 将存储的 extras 文本展开为 JSON 对象（写入前已校验，解析失败按未设置处理）
+pub fn extras_to_value(extras: Option<&str>) -> Option<Value> {
+    extras.and_then(|text| serde_json::from_str(text).ok())
+}
+
+impl From<ShortLink> for LinkRecord {
+    fn from(link: ShortLink) -> Self {
+        Self {
+            code: link.code,
+            target: link.target,
+            created_at: link.created_at,
+            expires_at: link.expires_at,
+            password: link.password,
+            click_count: link.click,
+            created_via: link.created_via,
+            analytics_level: link.analytics_level,
+            extras: extras_to_value(link.extras.as_deref()),
+        }
+    }
+}
+
+impl From<&ShortLink> for LinkRecord {
+    fn from(link: &ShortLink) -> Self {
+        link.clone().into()
+    }
+}
+
+impl From<LinkRecord> for ShortLink {
+    fn from(record: LinkRecord) -> Self {
+        Self {
+            code: record.code,
+            target: record.target,
+            created_at: record.created_at,
+            expires_at: record.expires_at,
+            password: record.password,
+            click: record.click_count,
+            created_via: record.created_via,
+            analytics_level: record.analytics_level,
+            extras: record.extras.map(|value| value.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample() -> ShortLink {
+        ShortLink {
+            code: "docs".to_string(),
+            target: "https://example.com/docs".to_string(),
+            created_at: Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap(),
+            expires_at: Some(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()),
+            password: None,
+            click: 42,
+            created_via: CreatedVia::Cli,
+            analytics_level: AnalyticsLevel::CountOnly,
+            extras: Some(r#"{"ticket":"JIRA-123"}"#.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let json = serde_json::to_value(LinkRecord::from(sample())).unwrap();
+        assert_eq!(json["created_at"], "2026-01-02T03:04:05Z");
+        assert_eq!(json["expires_at"], "2027-01-01T00:00:00Z");
+        assert_eq!(json["click_count"], 42);
+        assert_eq!(json["created_via"], "cli");
+        assert_eq!(json["analytics_level"], "count_only");
+        assert_eq!(json["extras"]["ticket"], "JIRA-123");
+        assert!(json.get("click").is_none());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let record = LinkRecord::from(sample());
+        let json = serde_json::to_string(&record).unwrap();
+        let back: LinkRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, record);
+
+        let link = ShortLink::from(back);
+        assert_eq!(link.click, 42);
+        assert_eq!(link.analytics_level, AnalyticsLevel::CountOnly);
+        assert_eq!(link.extras.as_deref(), Some(r#"{"ticket":"JIRA-123"}"#));
+    }
+
+    #[test]
+    fn test_reads_previous_format() {
+        // 旧版 IPC 直接序列化 ShortLink：字段名为 `click`，时间带 `+00:00` 偏移
+        let old = r#"{
+            "code": "old",
+            "target": "https://example.com",
+            "created_at": "2025-06-01T08:00:00+00:00",
+            "expires_at": null,
+            "password": null,
+            "click": 7
+        }"#;
+        let record: LinkRecord = serde_json::from_str(old).unwrap();
+        assert_eq!(record.click_count, 7);
+        assert_eq!(format_timestamp(&record.created_at), "2025-06-01T08:00:00Z");
+        assert_eq!(record.created_via, CreatedVia::Unknown);
+        assert_eq!(record.analytics_level, AnalyticsLevel::Inherit);
+        assert_eq!(record.extras, None);
+    }
+
+    #[test]
+    fn test_format_timestamp_keeps_subseconds() {
+        let dt = Utc.timestamp_millis_opt(1_767_225_600_123).unwrap();
+        assert_eq!(format_timestamp(&dt), "2026-01-01T00:00:00.123Z");
+    }
+}
@@ -5,11 +5,16 @@ use crate::metrics::MetricsRecorder;
 
 pub mod backend;
 pub mod config_store;
+pub mod link_schema;
 pub mod models;
 
 pub use backend::{LinkFilter, SeaOrmStorage};
 pub use config_store::{ConfigHistoryEntry, ConfigItem, ConfigStore, ConfigUpdateResult};
-pub use models::{LinkStats, ShortLink};
+pub use link_schema::{LINK_SCHEMA_VERSION, LinkRecord, extras_to_value, format_timestamp};
+pub use models::{
+    AnalyticsLevel, ApiToken, ArchivedLink, CreatedVia, CreationStats, CreationTrendPoint,
+    LinkStats, PendingSideEffect, ShortLink, SideEffect,
+};
 
 pub struct StorageFactory;
 
@@ -1,5 +1,176 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
+/// 链接的创建渠道
+///
+/// 创建时写入，之后覆盖/更新都保持不变；迁移前的存量数据为 `Unknown`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatedVia {
+    /// Admin HTTP API（面板与自动化调用）
+    Api,
+    /// 命令行（经 IPC 转发或直连数据库）
+    Cli,
+    /// 终端 UI
+    Tui,
+    /// CSV / JSON 批量导入
+    Import,
+    /// 未声明渠道的 IPC 调用方
+    Ipc,
+    /// 启动时自动写入
+    Bootstrap,
+    #[default]
+    Unknown,
+}
+
+impl CreatedVia {
+    pub const ALL: [CreatedVia; 7] = [
+        CreatedVia::Api,
+        CreatedVia::Cli,
+        CreatedVia::Tui,
+        CreatedVia::Import,
+        CreatedVia::Ipc,
+        CreatedVia::Bootstrap,
+        CreatedVia::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CreatedVia::Api => "api",
+            CreatedVia::Cli => "cli",
+            CreatedVia::Tui => "tui",
+            CreatedVia::Import => "import",
+            CreatedVia::Ipc => "ipc",
+            CreatedVia::Bootstrap => "bootstrap",
+            CreatedVia::Unknown => "unknown",
+        }
+    }
+
+    /// 从数据库值解析，无法识别的值视为 `Unknown`
+    pub fn from_db(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for CreatedVia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CreatedVia {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|via| via.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid created_via '{}'. Expected one of: {}",
+                    s,
+                    Self::ALL.map(|via| via.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// 单个链接的点击统计级别
+///
+/// 级别随链接缓存一起携带到跳转热路径，不需要额外查库。`Inherit` 表示跟随全局
+/// 配置：开启 `analytics.enable_detailed_logging` 时等同 `Full`，否则等同 `Aggregate`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsLevel {
+    /// 跟随全局配置
+    #[default]
+    Inherit,
+    /// 完全不统计
+    None,
+    /// 只累加 `click_count`，不写小时汇总和点击日志
+    CountOnly,
+    /// 累加 `click_count` 并写小时汇总，不写 `click_logs` 明细
+    Aggregate,
+    /// 完整统计（明细仍受全局详细日志开关和采样率约束）
+    Full,
+}
+
+impl AnalyticsLevel {
+    pub const ALL: [AnalyticsLevel; 5] = [
+        AnalyticsLevel::Inherit,
+        AnalyticsLevel::None,
+        AnalyticsLevel::CountOnly,
+        AnalyticsLevel::Aggregate,
+        AnalyticsLevel::Full,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalyticsLevel::Inherit => "inherit",
+            AnalyticsLevel::None => "none",
+            AnalyticsLevel::CountOnly => "count_only",
+            AnalyticsLevel::Aggregate => "aggregate",
+            AnalyticsLevel::Full => "full",
+        }
+    }
+
+    /// 从数据库值解析，无法识别的值视为 `Inherit`
+    pub fn from_db(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+
+    /// 是否可能记录点击明细（`Inherit` / `Full`）
+    pub fn allows_details(&self) -> bool {
+        matches!(self, AnalyticsLevel::Inherit | AnalyticsLevel::Full)
+    }
+
+    /// 按上限收紧级别，`cap` 为 `None` 或 `Inherit` 时不限
+    ///
+    /// `Inherit` 可能等同 `Full`，因此上限低于 `Full` 时同样收紧为上限。
+    pub fn capped(self, cap: Option<AnalyticsLevel>) -> Self {
+        match cap {
+            Some(cap) if cap != AnalyticsLevel::Inherit && self.rank() > cap.rank() => cap,
+            _ => self,
+        }
+    }
+
+    /// 统计粒度排序，`Inherit` 按可能的最高级别 `Full` 计
+    fn rank(self) -> u8 {
+        match self {
+            AnalyticsLevel::None => 0,
+            AnalyticsLevel::CountOnly => 1,
+            AnalyticsLevel::Aggregate => 2,
+            AnalyticsLevel::Full | AnalyticsLevel::Inherit => 3,
+        }
+    }
+}
+
+impl fmt::Display for AnalyticsLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AnalyticsLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid analytics_level '{}'. Expected one of: {}",
+                    s,
+                    Self::ALL.map(|level| level.as_str()).join(", ")
+                )
+            })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShortLink {
     pub code: String,
@@ -10,6 +181,16 @@ pub struct ShortLink {
 
     #[serde(default)]
     pub click: usize,
+
+    #[serde(default)]
+    pub created_via: CreatedVia,
+
+    #[serde(default)]
+    pub analytics_level: AnalyticsLevel,
+
+    /// 自定义扩展字段：紧凑格式的 JSON 对象文本，`None` 表示未设置
+    #[serde(default)]
+    pub extras: Option<String>,
 }
 
 impl ShortLink {
@@ -35,6 +216,28 @@ impl ShortLink {
             None => Some(default_ttl), // 无过期时间，用默认 TTL
         }
     }
+
+    /// 估算该链接在内存缓存中占用的字节数
+    ///
+    /// 结构体本身大小加上各字符串的堆上容量，用于 L1 缓存的加权淘汰与单条大小阈值判断。
+    pub fn estimated_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.code.capacity()
+            + self.target.capacity()
+            + self.password.as_ref().map_or(0, String::capacity)
+            + self.extras.as_ref().map_or(0, String::capacity)
+    }
+}
+
+/// 已归档的链接
+///
+/// 归档后链接从主表移入 `short_link_archive`，跳转返回 410 Gone，
+/// 点击日志与汇总数据保留，可按原短码查询。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedLink {
+    #[serde(flatten)]
+    pub link: ShortLink,
+    pub archived_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -51,6 +254,102 @@ pub struct LinkStats {
     pub active_links: usize,
 }
 
+/// 按创建渠道的统计切片
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreationStats {
+    /// 各渠道的链接总数
+    pub by_created_via: BTreeMap<String, usize>,
+    /// 最近 N 天按天、按渠道的创建数（UTC 日期，无数据的日期补 0）
+    pub daily: Vec<CreationTrendPoint>,
+}
+
+/// 单日按渠道的创建数
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreationTrendPoint {
+    /// 日期（YYYY-MM-DD）
+    pub date: String,
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// 写事务提交后需要执行的收尾副作用（outbox 条目）
+///
+/// 与数据写入在同一事务内落库，提交后立即执行并删除；进程在两者之间崩溃时
+/// 由后台任务重放。因此每种副作用都必须可安全重放：执行多次与执行一次效果相同。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SideEffect {
+    /// 按数据库当前状态刷新该短码的缓存：存在则写入，不存在则移除
+    CacheRefresh { code: String },
+}
+
+/// `CacheRefresh` 的 payload
+#[derive(Serialize, Deserialize)]
+struct CodePayload {
+    code: String,
+}
+
+impl SideEffect {
+    pub const CACHE_REFRESH: &'static str = "cache_refresh";
+
+    pub fn cache_refresh(code: impl Into<String>) -> Self {
+        SideEffect::CacheRefresh { code: code.into() }
+    }
+
+    /// 编码为 (effect_type, JSON payload)
+    pub fn encode(&self) -> (&'static str, String) {
+        match self {
+            SideEffect::CacheRefresh { code } => (
+                Self::CACHE_REFRESH,
+                serde_json::to_string(&CodePayload { code: code.clone() })
+                    .expect("string payload is always serializable"),
+            ),
+        }
+    }
+
+    /// 从表中的 (effect_type, payload) 解码；未知类型（如新版本写入）返回错误
+    pub fn decode(effect_type: &str, payload: &str) -> Result<Self, String> {
+        match effect_type {
+            Self::CACHE_REFRESH => serde_json::from_str::<CodePayload>(payload)
+                .map(|p| SideEffect::CacheRefresh { code: p.code })
+                .map_err(|e| format!("invalid {} payload: {}", effect_type, e)),
+            other => Err(format!("unknown side effect type '{}'", other)),
+        }
+    }
+}
+
+/// `pending_side_effects` 中尚未执行成功的条目
+#[derive(Debug, Clone)]
+pub struct PendingSideEffect {
+    pub id: i64,
+    pub effect_type: String,
+    pub payload: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// 重放失败次数
+    pub attempts: i32,
+}
+
+impl PendingSideEffect {
+    pub fn decode(&self) -> Result<SideEffect, String> {
+        SideEffect::decode(&self.effect_type, &self.payload)
+    }
+}
+
+/// 团队 API Token（明文只在创建时返回一次，库中仅存 argon2 哈希）
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub token_hash: String,
+    /// 最大链接数，`None` 为不限
+    pub max_links: Option<u64>,
+    /// 每日（UTC）最大创建数，`None` 为不限
+    pub max_daily_creates: Option<u64>,
+    /// 名下链接的统计级别上限，`None` 为不限
+    pub max_analytics_level: Option<AnalyticsLevel>,
+    /// 最近一次发送用量提醒的日期（YYYY-MM-DD）
+    pub alert_sent_on: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,6 +363,9 @@ mod tests {
             expires_at,
             password: None,
             click: 0,
+            created_via: CreatedVia::Unknown,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         }
     }
 
@@ -131,6 +433,81 @@ mod tests {
         assert_eq!(ttl, Some(3600));
     }
 
+    #[test]
+    fn test_estimated_size_grows_with_target_length() {
+        let short = create_test_link(None);
+        let mut long = create_test_link(None);
+        long.target = format!("https://sso.example.com/?state={}", "A".repeat(4096));
+        long.password = Some("secret".to_string());
+
+        assert!(short.estimated_size() >= std::mem::size_of::<ShortLink>() + short.target.len());
+        assert!(long.estimated_size() >= short.estimated_size() + 4096);
+    }
+
+    #[test]
+    fn test_created_via_round_trip() {
+        for via in CreatedVia::ALL {
+            assert_eq!(via.as_str().parse::<CreatedVia>(), Ok(via));
+            assert_eq!(
+                serde_json::to_string(&via).unwrap(),
+                format!("\"{}\"", via.as_str())
+            );
+        }
+        assert_eq!("IMPORT".parse::<CreatedVia>(), Ok(CreatedVia::Import));
+        assert!("panel".parse::<CreatedVia>().is_err());
+        assert_eq!(CreatedVia::from_db("legacy"), CreatedVia::Unknown);
+    }
+
+    #[test]
+    fn test_created_via_defaults_when_missing() {
+        let json = r#"{"code":"a","target":"https://example.com","created_at":"2026-01-01T00:00:00Z","expires_at":null,"password":null}"#;
+        let link: ShortLink = serde_json::from_str(json).unwrap();
+        assert_eq!(link.created_via, CreatedVia::Unknown);
+        assert_eq!(link.analytics_level, AnalyticsLevel::Inherit);
+    }
+
+    #[test]
+    fn test_analytics_level_round_trip() {
+        for level in AnalyticsLevel::ALL {
+            assert_eq!(level.as_str().parse::<AnalyticsLevel>(), Ok(level));
+            assert_eq!(
+                serde_json::to_string(&level).unwrap(),
+                format!("\"{}\"", level.as_str())
+            );
+        }
+        assert_eq!(
+            "Count_Only".parse::<AnalyticsLevel>(),
+            Ok(AnalyticsLevel::CountOnly)
+        );
+        assert!("detailed".parse::<AnalyticsLevel>().is_err());
+        assert_eq!(AnalyticsLevel::from_db("legacy"), AnalyticsLevel::Inherit);
+        assert!(AnalyticsLevel::Full.allows_details());
+        assert!(!AnalyticsLevel::Aggregate.allows_details());
+    }
+
+    #[test]
+    fn test_analytics_level_capped() {
+        let cap = Some(AnalyticsLevel::Aggregate);
+        assert_eq!(
+            AnalyticsLevel::Inherit.capped(cap),
+            AnalyticsLevel::Aggregate
+        );
+        assert_eq!(AnalyticsLevel::Full.capped(cap), AnalyticsLevel::Aggregate);
+        assert_eq!(
+            AnalyticsLevel::CountOnly.capped(cap),
+            AnalyticsLevel::CountOnly
+        );
+        assert_eq!(
+            AnalyticsLevel::Inherit.capped(Some(AnalyticsLevel::Full)),
+            AnalyticsLevel::Inherit
+        );
+        assert_eq!(
+            AnalyticsLevel::Full.capped(Some(AnalyticsLevel::Inherit)),
+            AnalyticsLevel::Full
+        );
+        assert_eq!(AnalyticsLevel::Full.capped(None), AnalyticsLevel::Full);
+    }
+
     #[test]
     fn test_link_stats_default() {
         let stats = LinkStats::default();
@@ -138,4 +515,14 @@ mod tests {
         assert_eq!(stats.total_clicks, 0);
         assert_eq!(stats.active_links, 0);
     }
+
+    #[test]
+    fn test_side_effect_encode_decode_roundtrip() {
+        let effect = SideEffect::cache_refresh("abc");
+        let (effect_type, payload) = effect.encode();
+        assert_eq!(effect_type, "cache_refresh");
+        assert_eq!(SideEffect::decode(effect_type, &payload), Ok(effect));
+        assert!(SideEffect::decode("audit_log", "{}").is_err());
+        assert!(SideEffect::decode("cache_refresh", "not json").is_err());
+    }
 }
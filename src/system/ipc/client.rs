@@ -7,10 +7,10 @@ use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::time::timeout;
 
-use super::platform::{IpcPlatform, PlatformIpc};
+use super::platform::{IpcPlatform, PROBE_TIMEOUT, PlatformIpc, ServerProbe};
 use super::protocol::{decode, encode};
 use super::types::{ConfigImportItem, ImportLinkData, IpcCommand, IpcError, IpcResponse};
-use crate::storage::ShortLink;
+use crate::storage::{AnalyticsLevel, CreatedVia, ShortLink};
 use crate::system::reload::ReloadTarget;
 
 /// Check if the server is running
@@ -20,6 +20,12 @@ pub fn is_server_running() -> bool {
     PlatformIpc::is_server_running()
 }
 
+/// Probe the server with the short [`PROBE_TIMEOUT`], distinguishing a stale socket
+/// from a missing or hung server
+pub fn probe_server() -> ServerProbe {
+    PlatformIpc::probe_server(PROBE_TIMEOUT)
+}
+
 /// Send an IPC command and wait for response
 ///
 /// Uses timeout from configuration based on command type.
@@ -27,9 +33,11 @@ pub async fn send_command(cmd: IpcCommand) -> Result<IpcResponse, IpcError> {
     let config = crate::config::get_config();
     let timeout_duration = match &cmd {
         IpcCommand::Reload { .. } => config.ipc.reload_timeout_duration(),
-        IpcCommand::ImportLinks { .. } | IpcCommand::ExportLinks => {
-            config.ipc.bulk_timeout_duration()
-        }
+        IpcCommand::ImportLinks { .. }
+        | IpcCommand::ExportLinks
+        | IpcCommand::ArchiveLinks { .. }
+        | IpcCommand::SampleLinks { .. }
+        | IpcCommand::GenerateLinks { .. } => config.ipc.bulk_timeout_duration(),
         _ => config.ipc.default_timeout(),
     };
     send_command_with_timeout(cmd, timeout_duration).await
@@ -117,12 +125,17 @@ pub async fn shutdown() -> Result<IpcResponse, IpcError> {
 // ============ Link Management Client Functions ============
 
 /// Add a new link via IPC
+#[allow(clippy::too_many_arguments)]
 pub async fn add_link(
     code: Option<String>,
     target: String,
     force: bool,
     expires_at: Option<String>,
     password: Option<String>,
+    created_via: Option<CreatedVia>,
+    analytics_level: Option<AnalyticsLevel>,
+    extras: Option<String>,
+    override_cooldown: bool,
 ) -> Result<IpcResponse, IpcError> {
     send_command(IpcCommand::AddLink {
         code,
@@ -130,6 +143,10 @@ pub async fn add_link(
         force,
         expires_at,
         password,
+        created_via,
+        analytics_level,
+        extras,
+        override_cooldown,
     })
     .await
 }
@@ -144,18 +161,92 @@ pub async fn batch_delete_links(codes: Vec<String>) -> Result<IpcResponse, IpcEr
     send_command(IpcCommand::BatchDeleteLinks { codes }).await
 }
 
+/// Batch extend link expiration times via IPC
+#[allow(clippy::too_many_arguments)]
+pub async fn batch_extend_links(
+    codes: Vec<String>,
+    search: Option<String>,
+    extend_by: Option<String>,
+    new_expires_at: Option<String>,
+    include_permanent: bool,
+    revive_from_now: bool,
+    dry_run: bool,
+) -> Result<IpcResponse, IpcError> {
+    send_command(IpcCommand::BatchExtendLinks {
+        codes,
+        search,
+        extend_by,
+        new_expires_at,
+        include_permanent,
+        revive_from_now,
+        dry_run,
+    })
+    .await
+}
+
+/// Archive links via IPC
+pub async fn archive_links(
+    codes: Vec<String>,
+    search: Option<String>,
+) -> Result<IpcResponse, IpcError> {
+    send_command(IpcCommand::ArchiveLinks { codes, search }).await
+}
+
+/// Restore an archived link via IPC
+pub async fn unarchive_link(code: String) -> Result<IpcResponse, IpcError> {
+    send_command(IpcCommand::UnarchiveLink { code }).await
+}
+
+/// Sample links uniformly at random via IPC
+pub async fn sample_links(
+    n: usize,
+    seed: Option<u64>,
+    search: Option<String>,
+) -> Result<IpcResponse, IpcError> {
+    send_command(IpcCommand::SampleLinks { n, seed, search }).await
+}
+
+/// Generate links from a template via IPC
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_links(
+    url_template: String,
+    code_template: Option<String>,
+    vars: Vec<crate::services::TemplateVar>,
+    force: bool,
+    expires_at: Option<String>,
+    password: Option<String>,
+    created_via: Option<CreatedVia>,
+    dry_run: bool,
+) -> Result<IpcResponse, IpcError> {
+    send_command(IpcCommand::GenerateLinks {
+        url_template,
+        code_template,
+        vars,
+        force,
+        expires_at,
+        password,
+        created_via,
+        dry_run,
+    })
+    .await
+}
+
 /// Update a link via IPC
 pub async fn update_link(
     code: String,
     target: String,
     expires_at: Option<String>,
     password: Option<String>,
+    analytics_level: Option<AnalyticsLevel>,
+    extras: Option<String>,
 ) -> Result<IpcResponse, IpcError> {
     send_command(IpcCommand::UpdateLink {
         code,
         target,
         expires_at,
         password,
+        analytics_level,
+        extras,
     })
     .await
 }
@@ -306,7 +397,7 @@ pub async fn export_links() -> Result<Vec<ShortLink>, IpcError> {
                 .map_err(|e| IpcError::ProtocolError(e.to_string()))?
             {
                 Some(IpcResponse::ExportChunk { links }) => {
-                    all_links.extend(links);
+                    all_links.extend(links.into_iter().map(ShortLink::from));
                     continue; // Try decoding more from buffer
                 }
                 Some(IpcResponse::ExportDone { .. }) => {
@@ -8,13 +8,16 @@ use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
-use super::types::{ConfigItemData, ImportErrorData, ImportLinkData, IpcCommand, IpcResponse};
+use super::types::{
+    BackgroundTaskStatus, ConfigItemData, ImportErrorData, ImportLinkData, IpcCommand, IpcResponse,
+};
 use crate::errors::ShortlinkerError;
 use crate::services::{
-    ConfigService, CreateLinkRequest, ImportLinkItemRaw, ImportMode, LinkService,
-    UpdateLinkRequest, validate_import_rows,
+    BatchExtendRequest, ConfigService, CreateLinkRequest, ExtendAction, GenerateLinksOptions,
+    ImportLinkItemRaw, ImportMode, LinkSelection, LinkService, LinkTemplate, UpdateLinkRequest,
+    validate_import_rows,
 };
-use crate::storage::{LinkFilter, ShortLink};
+use crate::storage::{AnalyticsLevel, CreatedVia, LinkFilter, ShortLink};
 use crate::system::reload::get_reload_coordinator;
 
 /// Server start time for uptime calculation
@@ -55,6 +58,22 @@ fn get_uptime_secs() -> u64 {
 }
 
 /// Convert ShortlinkerError to IpcResponse::Error
+/// 后台任务健康快照（后台任务未启动时为空）
+fn background_task_status() -> Vec<BackgroundTaskStatus> {
+    crate::runtime::supervisor::registry()
+        .map(|registry| registry.snapshot())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|task| BackgroundTaskStatus {
+            name: task.name.to_string(),
+            state: task.state.as_str().to_string(),
+            restarts: task.restarts,
+            heartbeat_age_secs: task.heartbeat_age.map(|age| age.as_secs()),
+            last_panic: task.last_panic,
+        })
+        .collect()
+}
+
 fn error_response(err: ShortlinkerError) -> IpcResponse {
     IpcResponse::Error {
         code: err.code().to_string(),
@@ -129,6 +148,7 @@ pub async fn handle_command(cmd: IpcCommand) -> IpcResponse {
         }
 
         IpcCommand::GetStatus => {
+            let (ipc_in_flight, ipc_commands) = super::stats::snapshot();
             let coordinator = get_reload_coordinator();
             let status = coordinator.map(|c| c.status());
 
@@ -156,6 +176,9 @@ pub async fn handle_command(cmd: IpcCommand) -> IpcResponse {
                     .and_then(|s| s.last_config_reload.as_ref())
                     .map(|r| r.finished_at.to_rfc3339()),
                 links_count,
+                ipc_in_flight,
+                ipc_commands,
+                background_tasks: background_task_status(),
             }
         }
 
@@ -173,18 +196,125 @@ pub async fn handle_command(cmd: IpcCommand) -> IpcResponse {
             force,
             expires_at,
             password,
-        } => handle_add_link(code, target, force, expires_at, password).await,
+            created_via,
+            analytics_level,
+            extras,
+            override_cooldown,
+        } => {
+            handle_add_link(CreateLinkRequest {
+                code,
+                target,
+                force,
+                expires_at,
+                password,
+                created_via: created_via.unwrap_or(CreatedVia::Ipc),
+                analytics_level: analytics_level.unwrap_or_default(),
+                extras,
+                override_cooldown,
+            })
+            .await
+        }
 
         IpcCommand::RemoveLink { code } => handle_remove_link(code).await,
 
         IpcCommand::BatchDeleteLinks { codes } => handle_batch_delete_links(codes).await,
 
+        IpcCommand::BatchExtendLinks {
+            codes,
+            search,
+            extend_by,
+            new_expires_at,
+            include_permanent,
+            revive_from_now,
+            dry_run,
+        } => {
+            let selection = if codes.is_empty() {
+                LinkSelection::Filter(LinkFilter {
+                    search,
+                    ..Default::default()
+                })
+            } else {
+                LinkSelection::Codes(codes)
+            };
+            let action =
+                match ExtendAction::from_inputs(extend_by.as_deref(), new_expires_at.as_deref()) {
+                    Ok(action) => action,
+                    Err(e) => return error_response(e),
+                };
+            handle_batch_extend_links(
+                selection,
+                BatchExtendRequest {
+                    action,
+                    include_permanent,
+                    revive_from_now,
+                    dry_run,
+                },
+            )
+            .await
+        }
+
+        IpcCommand::ArchiveLinks { codes, search } => {
+            let selection = if codes.is_empty() {
+                LinkSelection::Filter(LinkFilter {
+                    search,
+                    ..Default::default()
+                })
+            } else {
+                LinkSelection::Codes(codes)
+            };
+            handle_archive_links(selection).await
+        }
+
+        IpcCommand::UnarchiveLink { code } => handle_unarchive_link(code).await,
+
+        IpcCommand::SampleLinks { n, seed, search } => {
+            handle_sample_links(
+                LinkFilter {
+                    search,
+                    ..Default::default()
+                },
+                n,
+                seed,
+            )
+            .await
+        }
+
+        IpcCommand::GenerateLinks {
+            url_template,
+            code_template,
+            vars,
+            force,
+            expires_at,
+            password,
+            created_via,
+            dry_run,
+        } => {
+            handle_generate_links(
+                LinkTemplate {
+                    url: url_template,
+                    code: code_template,
+                    vars,
+                },
+                GenerateLinksOptions {
+                    force,
+                    expires_at,
+                    password,
+                    created_via: created_via.unwrap_or(CreatedVia::Ipc),
+                    analytics_level: AnalyticsLevel::Inherit,
+                    dry_run,
+                },
+            )
+            .await
+        }
+
         IpcCommand::UpdateLink {
             code,
             target,
             expires_at,
             password,
-        } => handle_update_link(code, target, expires_at, password).await,
+            analytics_level,
+            extras,
+        } => handle_update_link(code, target, expires_at, password, analytics_level, extras).await,
 
         IpcCommand::GetLink { code } => handle_get_link(code).await,
 
@@ -242,30 +372,17 @@ pub async fn handle_command(cmd: IpcCommand) -> IpcResponse {
 
 // ============ Link Management Handlers ============
 
-async fn handle_add_link(
-    code: Option<String>,
-    target: String,
-    force: bool,
-    expires_at: Option<String>,
-    password: Option<String>,
-) -> IpcResponse {
+async fn handle_add_link(req: CreateLinkRequest) -> IpcResponse {
     let service = match get_link_service() {
         Ok(s) => s,
         Err(e) => return e,
     };
 
-    let req = CreateLinkRequest {
-        code,
-        target,
-        force,
-        expires_at,
-        password,
-    };
-
     match service.create_link(req).await {
         Ok(result) => IpcResponse::LinkCreated {
-            link: result.link,
+            link: result.link.into(),
             generated_code: result.generated_code,
+            warnings: result.warnings,
         },
         Err(e) => error_response(e),
     }
@@ -307,11 +424,120 @@ async fn handle_batch_delete_links(codes: Vec<String>) -> IpcResponse {
     }
 }
 
+async fn handle_batch_extend_links(
+    selection: LinkSelection,
+    req: BatchExtendRequest,
+) -> IpcResponse {
+    let service = match get_link_service() {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    let to_error_data = |f: crate::services::BatchFailedItem| ImportErrorData {
+        code: f.code,
+        message: f.reason,
+        error_code: None,
+    };
+
+    match service.batch_extend_links(selection, req).await {
+        Ok(result) => IpcResponse::BatchExtendResult {
+            updated: result.updated,
+            skipped: result.skipped.into_iter().map(to_error_data).collect(),
+            failed: result.failed.into_iter().map(to_error_data).collect(),
+            dry_run: result.dry_run,
+        },
+        Err(e) => error_response(e),
+    }
+}
+
+async fn handle_archive_links(selection: LinkSelection) -> IpcResponse {
+    let service = match get_link_service() {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    match service.archive_links(selection).await {
+        Ok(result) => IpcResponse::ArchiveResult {
+            archived: result.archived,
+            not_found: result.not_found,
+            failed: result
+                .failed
+                .into_iter()
+                .map(|f| ImportErrorData {
+                    code: f.code,
+                    message: f.reason,
+                    error_code: None,
+                })
+                .collect(),
+        },
+        Err(e) => error_response(e),
+    }
+}
+
+async fn handle_unarchive_link(code: String) -> IpcResponse {
+    let service = match get_link_service() {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    match service.unarchive_link(&code).await {
+        Ok(link) => IpcResponse::LinkUnarchived { link: link.into() },
+        Err(e) => error_response(e),
+    }
+}
+
+async fn handle_sample_links(filter: LinkFilter, n: usize, seed: Option<u64>) -> IpcResponse {
+    let service = match get_link_service() {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    match service.sample_links(filter, n, seed).await {
+        Ok(sample) => IpcResponse::SampleResult {
+            links: sample.links.into_iter().map(Into::into).collect(),
+            seed: sample.seed,
+            population: sample.population,
+        },
+        Err(e) => error_response(e),
+    }
+}
+
+async fn handle_generate_links(
+    template: LinkTemplate,
+    options: GenerateLinksOptions,
+) -> IpcResponse {
+    let service = match get_link_service() {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    match service.generate_links(template, options).await {
+        Ok(result) => IpcResponse::GenerateResult {
+            planned: result.planned,
+            existing: result.existing,
+            created: result.created,
+            failed: result
+                .failed
+                .into_iter()
+                .map(|f| ImportErrorData {
+                    code: f.code,
+                    message: f.reason,
+                    error_code: None,
+                })
+                .collect(),
+            dry_run: result.dry_run,
+        },
+        Err(e) => error_response(e),
+    }
+}
+
 async fn handle_update_link(
     code: String,
     target: String,
     expires_at: Option<String>,
     password: Option<String>,
+    analytics_level: Option<AnalyticsLevel>,
+    extras: Option<String>,
 ) -> IpcResponse {
     let service = match get_link_service() {
         Ok(s) => s,
@@ -322,10 +548,15 @@ async fn handle_update_link(
         target,
         expires_at,
         password,
+        analytics_level,
+        extras,
     };
 
-    match service.update_link(&code, req).await {
-        Ok(link) => IpcResponse::LinkUpdated { link },
+    match service.update_link_checked(&code, req).await {
+        Ok(result) => IpcResponse::LinkUpdated {
+            link: result.link.into(),
+            warnings: result.warnings,
+        },
         Err(e) => error_response(e),
     }
 }
@@ -337,7 +568,9 @@ async fn handle_get_link(code: String) -> IpcResponse {
     };
 
     match service.get_link(&code).await {
-        Ok(link) => IpcResponse::LinkFound { link },
+        Ok(link) => IpcResponse::LinkFound {
+            link: link.map(Into::into),
+        },
         Err(e) => error_response(e),
     }
 }
@@ -354,11 +587,13 @@ async fn handle_list_links(page: u64, page_size: u64, search: Option<String>) ->
         created_before: None,
         only_expired: false,
         only_active: false,
+        created_via: None,
+        extras: Vec::new(),
     };
 
     match service.list_links(filter, page, page_size).await {
         Ok((links, total)) => IpcResponse::LinkList {
-            links,
+            links: links.into_iter().map(Into::into).collect(),
             total: total as usize,
             page,
             page_size,
@@ -377,7 +612,8 @@ async fn handle_import_links(links: Vec<ImportLinkData>, overwrite: bool) -> Ipc
     let raw_items: Vec<ImportLinkItemRaw> =
         links.into_iter().map(ImportLinkItemRaw::from).collect();
 
-    let (valid_items, row_errors) = validate_import_rows(raw_items);
+    // 过期时间已过去等警告由 CLI 在本地解析 CSV 时展示
+    let (valid_items, row_errors, _warnings) = validate_import_rows(raw_items);
 
     let pre_errors: Vec<ImportErrorData> = row_errors
         .into_iter()
@@ -471,7 +707,7 @@ pub fn import_links_with_progress(
         let raw_items: Vec<ImportLinkItemRaw> =
             links.into_iter().map(ImportLinkItemRaw::from).collect();
 
-        let (valid_items, row_errors) = validate_import_rows(raw_items);
+        let (valid_items, row_errors, _warnings) = validate_import_rows(raw_items);
 
         let pre_errors: Vec<ImportErrorData> = row_errors
             .into_iter()
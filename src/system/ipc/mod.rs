@@ -11,6 +11,7 @@
 //! - **server.rs**: IPC server that runs alongside the HTTP server
 //! - **client.rs**: IPC client for CLI commands
 //! - **handler.rs**: Command handler that processes IPC commands
+//! - **stats.rs**: Per-command metrics, statistics and slow-command logging
 //!
 //! # Usage
 //!
@@ -44,15 +45,17 @@ pub mod handler;
 pub mod platform;
 pub mod protocol;
 pub mod server;
+pub mod stats;
 pub mod types;
 
 pub use client::{
-    add_link, batch_delete_links, config_get, config_import, config_list, config_reset, config_set,
-    export_links, get_link, get_link_stats, import_links, import_links_streaming,
-    is_server_running, list_links, ping, reload, remove_link, send_command, update_link,
+    add_link, archive_links, batch_delete_links, batch_extend_links, config_get, config_import,
+    config_list, config_reset, config_set, export_links, generate_links, get_link, get_link_stats,
+    import_links, import_links_streaming, is_server_running, list_links, ping, probe_server,
+    reload, remove_link, sample_links, send_command, unarchive_link, update_link,
 };
-pub use platform::PlatformIpc;
+pub use platform::{PlatformIpc, ServerProbe};
 pub use types::{
-    ConfigImportItem, ConfigItemData, ImportErrorData, ImportLinkData, ImportPhase, IpcCommand,
-    IpcError, IpcResponse,
+    BackgroundTaskStatus, ConfigImportItem, ConfigItemData, ImportErrorData, ImportLinkData,
+    ImportPhase, IpcCommand, IpcCommandStats, IpcError, IpcResponse,
 };
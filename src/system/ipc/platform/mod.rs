@@ -5,11 +5,30 @@
 //! - Windows: Named Pipe
 
 use std::io;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 #[cfg(unix)]
 use std::path::Path;
 
+/// Timeout used by [`IpcPlatform::is_server_running`] for the Ping probe
+///
+/// 只用来判断服务是否在线，不需要等待繁忙的服务；卡死的服务在这段时间后即判定为无响应。
+pub const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Result of probing the IPC endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerProbe {
+    /// The server answered a Ping within the timeout
+    Running,
+    /// No socket file (Unix) or pipe (Windows)
+    NotRunning,
+    /// The socket file exists but nothing is listening (left behind by a crashed server)
+    Stale,
+    /// The endpoint accepted the connection but did not answer the Ping in time
+    Unresponsive,
+}
+
 /// Platform-specific IPC operations trait
 ///
 /// This trait defines the interface for platform-specific IPC implementations.
@@ -24,11 +43,16 @@ pub trait IpcPlatform: Send + Sync + 'static {
     /// Get the socket/pipe path for this platform (from config)
     fn socket_path() -> String;
 
+    /// Probe the socket/pipe synchronously, waiting at most `timeout` for a Ping reply
+    fn probe_server(timeout: Duration) -> ServerProbe;
+
     /// Check if the server is running by testing socket connectivity
     ///
     /// This performs a quick synchronous check to determine if a server
     /// is listening on the socket.
-    fn is_server_running() -> bool;
+    fn is_server_running() -> bool {
+        Self::probe_server(PROBE_TIMEOUT) == ServerProbe::Running
+    }
 
     /// Create a listener (server side)
     ///
@@ -73,13 +97,13 @@ pub type PlatformIpc = WindowsIpc;
 
 /// Get the socket path for the current platform (from config)
 pub fn socket_path() -> String {
-    crate::config::get_config().ipc.effective_socket_path()
+    crate::config::get_config().ipc_socket_path()
 }
 
 /// Check if the socket file exists (Unix only)
 #[cfg(unix)]
 pub fn socket_exists() -> bool {
-    let path_str = crate::config::get_config().ipc.effective_socket_path();
+    let path_str = crate::config::get_config().ipc_socket_path();
     Path::new(&path_str).exists()
 }
 
@@ -4,9 +4,10 @@
 
 use std::io;
 use std::path::Path;
+use std::time::Duration;
 use tokio::net::{UnixListener, UnixStream};
 
-use super::IpcPlatform;
+use super::{IpcPlatform, ServerProbe};
 use crate::config::get_config;
 
 /// Unix IPC implementation using Unix Domain Sockets
@@ -17,45 +18,11 @@ impl IpcPlatform for UnixIpc {
     type Listener = UnixListener;
 
     fn socket_path() -> String {
-        get_config().ipc.effective_socket_path()
+        get_config().ipc_socket_path()
     }
 
-    fn is_server_running() -> bool {
-        use std::io::{Read, Write};
-        use std::time::Duration;
-
-        use crate::system::ipc::protocol::encode;
-        use crate::system::ipc::types::IpcCommand;
-
-        let path_str = Self::socket_path();
-        let path = Path::new(&path_str);
-        if !path.exists() {
-            return false;
-        }
-
-        let mut stream = match std::os::unix::net::UnixStream::connect(path) {
-            Ok(s) => s,
-            Err(_) => return false,
-        };
-
-        // 设置短超时，避免僵死进程阻塞检测
-        let timeout = Duration::from_secs(1);
-        let _ = stream.set_read_timeout(Some(timeout));
-        let _ = stream.set_write_timeout(Some(timeout));
-
-        // 发送 Ping 命令，验证服务器能实际响应
-        let ping_data = match encode(&IpcCommand::Ping) {
-            Ok(data) => data,
-            Err(_) => return false,
-        };
-
-        if stream.write_all(&ping_data).is_err() {
-            return false;
-        }
-
-        // 读取响应（至少需要 4 字节长度头 + JSON 数据）
-        let mut buf = [0u8; 512];
-        matches!(stream.read(&mut buf), Ok(n) if n > 4)
+    fn probe_server(timeout: Duration) -> ServerProbe {
+        probe_socket(Path::new(&Self::socket_path()), timeout)
     }
 
     async fn bind() -> io::Result<Self::Listener> {
@@ -95,3 +62,94 @@ impl IpcPlatform for UnixIpc {
         let _ = std::fs::remove_file(Self::socket_path());
     }
 }
+
+/// 探测 `path` 上的 IPC 服务：连接后发送 Ping，在 `timeout` 内收到响应才算在线
+pub(crate) fn probe_socket(path: &Path, timeout: Duration) -> ServerProbe {
+    use std::io::{Read, Write};
+
+    use crate::system::ipc::protocol::encode;
+    use crate::system::ipc::types::IpcCommand;
+
+    if !path.exists() {
+        return ServerProbe::NotRunning;
+    }
+
+    let mut stream = match std::os::unix::net::UnixStream::connect(path) {
+        Ok(s) => s,
+        // socket 文件还在但没有进程监听：服务崩溃或被 kill -9 后的残留
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => return ServerProbe::Stale,
+        Err(_) => return ServerProbe::Unresponsive,
+    };
+
+    // 设置短超时，避免僵死进程阻塞检测
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    // 发送 Ping 命令，验证服务器能实际响应
+    let Ok(ping_data) = encode(&IpcCommand::Ping) else {
+        return ServerProbe::Unresponsive;
+    };
+    if stream.write_all(&ping_data).is_err() {
+        return ServerProbe::Unresponsive;
+    }
+
+    // 读取响应（至少需要 4 字节长度头 + JSON 数据）
+    let mut buf = [0u8; 512];
+    match stream.read(&mut buf) {
+        Ok(n) if n > 4 => ServerProbe::Running,
+        _ => ServerProbe::Unresponsive,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener as StdUnixListener;
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn test_probe_missing_socket() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("missing.sock");
+        assert_eq!(probe_socket(&path, TIMEOUT), ServerProbe::NotRunning);
+    }
+
+    #[test]
+    fn test_probe_stale_socket_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("stale.sock");
+        // 绑定后直接关闭：socket 文件保留，连接被拒绝
+        drop(StdUnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        assert_eq!(probe_socket(&path, TIMEOUT), ServerProbe::Stale);
+    }
+
+    #[test]
+    fn test_probe_listener_that_never_answers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("hung.sock");
+        // 监听但从不 accept / 响应，模拟卡死的服务
+        let _listener = StdUnixListener::bind(&path).unwrap();
+        let started = std::time::Instant::now();
+        assert_eq!(probe_socket(&path, TIMEOUT), ServerProbe::Unresponsive);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_probe_responding_server() {
+        use std::io::{Read, Write};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("live.sock");
+        let listener = StdUnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 64];
+            let _ = stream.read(&mut buf).unwrap();
+            stream.write_all(b"\0\0\0\x04pong").unwrap();
+        });
+        assert_eq!(probe_socket(&path, TIMEOUT), ServerProbe::Running);
+        server.join().unwrap();
+    }
+}
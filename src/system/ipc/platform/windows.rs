@@ -7,12 +7,13 @@ use std::ffi::c_void;
 use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::net::windows::named_pipe::{
     ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
 };
 
-use super::IpcPlatform;
+use super::{IpcPlatform, ServerProbe};
 use crate::config::get_config;
 
 /// RAII guard for security descriptor allocated via SDDL.
@@ -142,21 +143,22 @@ impl IpcPlatform for WindowsIpc {
     type Listener = PipeListener;
 
     fn socket_path() -> String {
-        get_config().ipc.effective_socket_path()
+        get_config().ipc_socket_path()
     }
 
-    fn is_server_running() -> bool {
+    fn probe_server(_timeout: Duration) -> ServerProbe {
         // ERROR_PIPE_BUSY (231): All pipe instances are busy
         // ERROR_FILE_NOT_FOUND (2): Pipe does not exist
         const ERROR_PIPE_BUSY: i32 = 231;
 
+        // 命名管道随进程销毁，不会残留；打开即视为在线
         let pipe_name = Self::socket_path();
         match ClientOptions::new().open(&pipe_name) {
-            Ok(_) => true,
-            Err(e) => {
-                // PIPE_BUSY means server is running (just busy)
-                e.raw_os_error() == Some(ERROR_PIPE_BUSY)
-            }
+            Ok(_) => ServerProbe::Running,
+            // PIPE_BUSY means server is running (just busy)
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => ServerProbe::Running,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => ServerProbe::NotRunning,
+            Err(_) => ServerProbe::Unresponsive,
         }
     }
 
@@ -12,12 +12,36 @@ use tracing::{debug, error, info, warn};
 use super::handler::handle_command;
 use super::platform::{IpcPlatform, PlatformIpc};
 use super::protocol::{decode, encode};
+use super::stats::CommandTimer;
 use super::types::{IpcCommand, IpcResponse};
+use crate::storage::backend::statement_log::with_operation;
 
 pub async fn run_ipc_server(shutdown_token: CancellationToken) {
+    run_ipc_server_with_drain(
+        shutdown_token.clone(),
+        shutdown_token,
+        CancellationToken::new(),
+    )
+    .await;
+    PlatformIpc::cleanup();
+    info!("IPC socket cleaned up");
+}
+
+/// Run the IPC server with a two-step shutdown
+///
+/// After `stop_accepting` is cancelled no new connections are accepted and
+/// in-flight commands run to completion; connections still open when `abort`
+/// is cancelled are aborted. `listening` is cancelled once the socket is bound.
+/// The socket file is left for the caller to remove.
+pub async fn run_ipc_server_with_drain(
+    stop_accepting: CancellationToken,
+    abort: CancellationToken,
+    listening: CancellationToken,
+) {
     let mut listener = match PlatformIpc::bind().await {
         Ok(listener) => {
             info!("IPC server listening on {}", PlatformIpc::socket_path());
+            listening.cancel();
             listener
         }
         Err(error) => {
@@ -29,20 +53,44 @@ pub async fn run_ipc_server(shutdown_token: CancellationToken) {
 
     loop {
         tokio::select! {
-            _ = shutdown_token.cancelled() => break,
+            _ = stop_accepting.cancelled() => break,
             result = PlatformIpc::accept(&mut listener) => match result {
                 Ok(stream) => {
                     connections.spawn(handle_connection(stream));
                 }
                 Err(error) => warn!(%error, "Failed to accept IPC connection"),
             },
+            // 回收已结束的连接，保证关闭时的计数只含在途连接
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
         }
     }
+    drop(listener);
 
-    connections.abort_all();
-    while connections.join_next().await.is_some() {}
-    PlatformIpc::cleanup();
-    info!("IPC server stopped, socket cleaned up");
+    if !connections.is_empty() && !abort.is_cancelled() {
+        info!(
+            "IPC server stopped accepting, waiting for {} in-flight connections",
+            connections.len()
+        );
+    }
+    loop {
+        tokio::select! {
+            biased;
+            _ = abort.cancelled() => {
+                if !connections.is_empty() {
+                    warn!("Aborting {} unfinished IPC connections", connections.len());
+                }
+                connections.abort_all();
+                while connections.join_next().await.is_some() {}
+                break;
+            }
+            result = connections.join_next() => {
+                if result.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    info!("IPC server stopped");
 }
 
 /// Send a single IpcResponse over the stream
@@ -100,7 +148,9 @@ where
                     continue;
                 }
                 total += count;
-                let chunk = IpcResponse::ExportChunk { links };
+                let chunk = IpcResponse::ExportChunk {
+                    links: links.into_iter().map(Into::into).collect(),
+                };
                 send_response(stream, &chunk).await?;
                 debug!(
                     "IPC export: sent chunk of {} links (total: {})",
@@ -192,35 +242,39 @@ where
         loop {
             match decode::<IpcCommand>(&mut buf) {
                 Ok(Some(cmd)) => {
-                    debug!("Received IPC command: {:?}", cmd);
+                    debug!("Received IPC command: {} [{}]", cmd.name(), cmd.summary());
 
-                    match cmd {
-                        IpcCommand::ExportLinks => {
-                            // Streaming export: send multiple responses
-                            if handle_streaming_export(&mut stream).await.is_err() {
-                                return;
+                    // 分发层统一计时：指标、状态统计与慢命令日志
+                    let timer = CommandTimer::start(&cmd);
+                    let operation = format!("ipc:{}", cmd.name());
+                    let outcome = with_operation(operation, async {
+                        match cmd {
+                            IpcCommand::ExportLinks => {
+                                // Streaming export: send multiple responses
+                                handle_streaming_export(&mut stream).await.map(|()| true)
                             }
-                        }
-                        IpcCommand::ImportLinks {
-                            links,
-                            overwrite,
-                            stream_progress: true,
-                        } => {
-                            // Streaming import: send progress + final result
-                            if handle_streaming_import(&mut stream, links, overwrite)
-                                .await
-                                .is_err()
-                            {
-                                return;
+                            IpcCommand::ImportLinks {
+                                links,
+                                overwrite,
+                                stream_progress: true,
+                            } => {
+                                // Streaming import: send progress + final result
+                                handle_streaming_import(&mut stream, links, overwrite)
+                                    .await
+                                    .map(|()| true)
                             }
-                        }
-                        other_cmd => {
-                            // Single response commands
-                            let response = handle_command(other_cmd).await;
-                            if send_response(&mut stream, &response).await.is_err() {
-                                return;
+                            other_cmd => {
+                                // Single response commands
+                                let response = handle_command(other_cmd).await;
+                                let ok = !response.is_error();
+                                send_response(&mut stream, &response).await.map(|()| ok)
                             }
                         }
+                    })
+                    .await;
+                    timer.finish(outcome.unwrap_or(false));
+                    if outcome.is_err() {
+                        return;
                     }
                 }
                 Ok(None) => {
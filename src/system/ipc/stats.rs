@@ -0,0 +1,184 @@
+//! IPC command observability
+//!
+//! The server dispatch layer times every command once (including streaming
+//! import/export), so individual commands need no instrumentation:
+//! - Prometheus: per-command count / error count / duration, in-flight gauge
+//! - `GetStatus`: per-command statistics since start
+//! - Commands slower than `ipc.slow_command_ms` are logged at warn level with a
+//!   redacted argument summary ([`IpcCommand::summary`])
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use super::types::{IpcCommand, IpcCommandStats};
+use crate::metrics::MetricsRecorder;
+
+/// Metrics recorder for IPC commands
+static METRICS: OnceLock<Arc<dyn MetricsRecorder>> = OnceLock::new();
+
+/// Process-wide IPC command statistics
+static STATS: LazyLock<IpcStats> = LazyLock::new(IpcStats::new);
+
+/// Initialize the metrics recorder for IPC commands
+///
+/// Should be called once during server startup; without it only the
+/// in-process statistics and slow-command logs are kept.
+pub fn init_metrics(metrics: Arc<dyn MetricsRecorder>) {
+    let _ = METRICS.set(metrics);
+}
+
+/// Statistics snapshot for `GetStatus`: (in-flight count, per-command stats)
+pub fn snapshot() -> (usize, Vec<IpcCommandStats>) {
+    (STATS.in_flight(), STATS.snapshot())
+}
+
+#[derive(Debug, Default)]
+struct CommandCounters {
+    count: u64,
+    errors: u64,
+    slow: u64,
+    total: Duration,
+    max: Duration,
+    last: Duration,
+    last_at: Option<DateTime<Utc>>,
+}
+
+/// Aggregated per-command statistics
+struct IpcStats {
+    in_flight: AtomicUsize,
+    commands: Mutex<HashMap<&'static str, CommandCounters>>,
+}
+
+impl IpcStats {
+    fn new() -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            commands: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, command: &'static str, elapsed: Duration, ok: bool, slow: bool) {
+        let mut commands = self.commands.lock().expect("IPC stats lock poisoned");
+        let counters = commands.entry(command).or_default();
+        counters.count += 1;
+        counters.errors += u64::from(!ok);
+        counters.slow += u64::from(slow);
+        counters.total += elapsed;
+        counters.max = counters.max.max(elapsed);
+        counters.last = elapsed;
+        counters.last_at = Some(Utc::now());
+    }
+
+    fn snapshot(&self) -> Vec<IpcCommandStats> {
+        let commands = self.commands.lock().expect("IPC stats lock poisoned");
+        let mut stats: Vec<IpcCommandStats> = commands
+            .iter()
+            .map(|(command, c)| IpcCommandStats {
+                command: command.to_string(),
+                count: c.count,
+                errors: c.errors,
+                slow: c.slow,
+                avg_ms: millis(c.total / u32::try_from(c.count.max(1)).unwrap_or(u32::MAX)),
+                max_ms: millis(c.max),
+                last_ms: millis(c.last),
+                last_at: c.last_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            })
+            .collect();
+        stats.sort_by(|a, b| b.count.cmp(&a.count).then(a.command.cmp(&b.command)));
+        stats
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Times one IPC command from dispatch to response
+///
+/// Counts as in flight until dropped, so connections aborted mid-command do not
+/// leave the gauge inflated.
+pub struct CommandTimer {
+    name: &'static str,
+    summary: String,
+    start: Instant,
+}
+
+impl CommandTimer {
+    pub fn start(cmd: &IpcCommand) -> Self {
+        let in_flight = STATS.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(metrics) = METRICS.get() {
+            metrics.set_ipc_commands_in_flight(in_flight as f64);
+        }
+        Self {
+            name: cmd.name(),
+            summary: cmd.summary(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Record the outcome; `ok = false` for error responses and failed sends
+    pub fn finish(self, ok: bool) {
+        let elapsed = self.start.elapsed();
+        let threshold_ms = crate::config::get_config().ipc.slow_command_ms;
+        let slow = threshold_ms > 0 && elapsed >= Duration::from_millis(threshold_ms);
+        if slow {
+            warn!(
+                "Slow IPC command {} [{}] took {}ms (ok: {})",
+                self.name,
+                self.summary,
+                elapsed.as_millis(),
+                ok
+            );
+        }
+
+        STATS.record(self.name, elapsed, ok, slow);
+        if let Some(metrics) = METRICS.get() {
+            metrics.inc_ipc_command(self.name, if ok { "ok" } else { "error" });
+            metrics.observe_ipc_command_duration(self.name, elapsed.as_secs_f64());
+        }
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        let in_flight = STATS.in_flight.fetch_sub(1, Ordering::Relaxed) - 1;
+        if let Some(metrics) = METRICS.get() {
+            metrics.set_ipc_commands_in_flight(in_flight as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_aggregate_per_command() {
+        let stats = IpcStats::new();
+        stats.record("GetLink", Duration::from_millis(10), true, false);
+        stats.record("GetLink", Duration::from_millis(30), false, false);
+        stats.record("ImportLinks", Duration::from_millis(1500), true, true);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 2);
+
+        let get = &snapshot[0];
+        assert_eq!(get.command, "GetLink");
+        assert_eq!((get.count, get.errors, get.slow), (2, 1, 0));
+        assert_eq!((get.avg_ms, get.max_ms, get.last_ms), (20, 30, 30));
+        assert!(!get.last_at.is_empty());
+
+        let import = &snapshot[1];
+        assert_eq!(import.command, "ImportLinks");
+        assert_eq!((import.count, import.slow, import.max_ms), (1, 1, 1500));
+    }
+}
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io;
 
-use crate::storage::ShortLink;
+use crate::storage::{AnalyticsLevel, CreatedVia, LinkRecord, format_timestamp};
 use crate::system::reload::ReloadTarget;
 
 /// Import link data structure
@@ -21,6 +21,12 @@ pub struct ImportLinkData {
     pub expires_at: Option<String>,
     pub password: Option<String>,
     pub click_count: usize,
+    /// 旧版客户端不发送该字段，按 `inherit` 处理
+    #[serde(default)]
+    pub analytics_level: Option<String>,
+    /// 自定义扩展字段（JSON 对象文本），旧版客户端不发送
+    #[serde(default)]
+    pub extras: Option<String>,
 }
 
 impl From<&crate::services::ImportLinkItemRich> for ImportLinkData {
@@ -28,10 +34,12 @@ impl From<&crate::services::ImportLinkItemRich> for ImportLinkData {
         Self {
             code: l.code.clone(),
             target: l.target.clone(),
-            created_at: l.created_at.to_rfc3339(),
-            expires_at: l.expires_at.map(|dt| dt.to_rfc3339()),
+            created_at: format_timestamp(&l.created_at),
+            expires_at: l.expires_at.as_ref().map(format_timestamp),
             password: l.password.clone(),
             click_count: l.click_count,
+            analytics_level: Some(l.analytics_level.as_str().to_string()),
+            extras: l.extras.clone(),
         }
     }
 }
@@ -62,6 +70,36 @@ pub struct ConfigItemData {
     pub updated_at: String,
 }
 
+/// Per-command IPC statistics since server start (reported by `GetStatus`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcCommandStats {
+    pub command: String,
+    pub count: u64,
+    pub errors: u64,
+    /// Commands that exceeded `ipc.slow_command_ms`
+    pub slow: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+    /// Duration of the most recent call
+    pub last_ms: u64,
+    /// Finish time of the most recent call (RFC3339)
+    pub last_at: String,
+}
+
+/// Background task health (reported by `GetStatus`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackgroundTaskStatus {
+    pub name: String,
+    /// `running` / `stalled` / `restarting` / `dead` / `stopped`
+    pub state: String,
+    /// Restarts after panic since server start
+    pub restarts: u32,
+    /// Seconds since the last heartbeat (None = task does not report heartbeats)
+    pub heartbeat_age_secs: Option<u64>,
+    /// Most recent panic message
+    pub last_panic: Option<String>,
+}
+
 /// Import progress phase
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ImportPhase {
@@ -112,6 +150,18 @@ pub enum IpcCommand {
         force: bool,
         expires_at: Option<String>,
         password: Option<String>,
+        /// Creation channel reported by the caller (absent = `ipc`)
+        #[serde(default)]
+        created_via: Option<CreatedVia>,
+        /// Per-link analytics level (absent = `inherit`)
+        #[serde(default)]
+        analytics_level: Option<AnalyticsLevel>,
+        /// Custom metadata as a JSON object (absent = none)
+        #[serde(default)]
+        extras: Option<String>,
+        /// Reuse a code still in its deletion cooldown
+        #[serde(default)]
+        override_cooldown: bool,
     },
 
     /// Remove a short link
@@ -120,12 +170,63 @@ pub enum IpcCommand {
     /// Batch delete short links
     BatchDeleteLinks { codes: Vec<String> },
 
+    /// Batch extend link expiration times
+    BatchExtendLinks {
+        /// Explicit codes (takes precedence over `search` when non-empty)
+        codes: Vec<String>,
+        search: Option<String>,
+        extend_by: Option<String>,
+        new_expires_at: Option<String>,
+        include_permanent: bool,
+        revive_from_now: bool,
+        dry_run: bool,
+    },
+
+    /// Archive short links (moved to the archive table, redirects return 410)
+    ArchiveLinks {
+        /// Explicit codes (takes precedence over `search` when non-empty)
+        codes: Vec<String>,
+        search: Option<String>,
+    },
+
+    /// Restore an archived short link
+    UnarchiveLink { code: String },
+
+    /// Uniform random sample of links (reservoir sampling over a cursor scan)
+    SampleLinks {
+        n: usize,
+        /// Random seed; the server picks one when absent
+        seed: Option<u64>,
+        search: Option<String>,
+    },
+
+    /// Expand a link template and create every combination
+    GenerateLinks {
+        url_template: String,
+        code_template: Option<String>,
+        vars: Vec<crate::services::TemplateVar>,
+        force: bool,
+        expires_at: Option<String>,
+        password: Option<String>,
+        /// Creation channel reported by the caller (absent = `ipc`)
+        #[serde(default)]
+        created_via: Option<CreatedVia>,
+        /// Only expand and report existing codes, do not write
+        dry_run: bool,
+    },
+
     /// Update an existing short link
     UpdateLink {
         code: String,
         target: String,
         expires_at: Option<String>,
         password: Option<String>,
+        /// New analytics level (absent = keep existing)
+        #[serde(default)]
+        analytics_level: Option<AnalyticsLevel>,
+        /// New custom metadata (absent = keep existing, `{}` = clear)
+        #[serde(default)]
+        extras: Option<String>,
     },
 
     /// Get a single short link
@@ -181,6 +282,11 @@ impl IpcCommand {
             IpcCommand::AddLink { .. } => "AddLink",
             IpcCommand::RemoveLink { .. } => "RemoveLink",
             IpcCommand::BatchDeleteLinks { .. } => "BatchDeleteLinks",
+            IpcCommand::BatchExtendLinks { .. } => "BatchExtendLinks",
+            IpcCommand::ArchiveLinks { .. } => "ArchiveLinks",
+            IpcCommand::UnarchiveLink { .. } => "UnarchiveLink",
+            IpcCommand::SampleLinks { .. } => "SampleLinks",
+            IpcCommand::GenerateLinks { .. } => "GenerateLinks",
             IpcCommand::UpdateLink { .. } => "UpdateLink",
             IpcCommand::GetLink { .. } => "GetLink",
             IpcCommand::ListLinks { .. } => "ListLinks",
@@ -194,6 +300,87 @@ impl IpcCommand {
             IpcCommand::ConfigImport { .. } => "ConfigImport",
         }
     }
+
+    /// 参数摘要，用于慢命令日志
+    ///
+    /// 只包含短码、数量、配置键等非敏感信息：不输出密码、配置值，目标 URL 只保留主机名。
+    pub fn summary(&self) -> String {
+        match self {
+            IpcCommand::Ping
+            | IpcCommand::GetStatus
+            | IpcCommand::Shutdown
+            | IpcCommand::ExportLinks
+            | IpcCommand::GetLinkStats => String::new(),
+            IpcCommand::Reload { target } => format!("target={:?}", target),
+            IpcCommand::AddLink {
+                code,
+                target,
+                force,
+                ..
+            } => format!(
+                "code={} target_host={} force={}",
+                code.as_deref().unwrap_or("<auto>"),
+                target_host(target),
+                force
+            ),
+            IpcCommand::UpdateLink { code, target, .. } => {
+                format!("code={} target_host={}", code, target_host(target))
+            }
+            IpcCommand::RemoveLink { code }
+            | IpcCommand::UnarchiveLink { code }
+            | IpcCommand::GetLink { code } => format!("code={}", code),
+            IpcCommand::BatchDeleteLinks { codes } => format!("codes={}", codes.len()),
+            IpcCommand::BatchExtendLinks {
+                codes,
+                search,
+                dry_run,
+                ..
+            } => format!(
+                "codes={} search={} dry_run={}",
+                codes.len(),
+                search.is_some(),
+                dry_run
+            ),
+            IpcCommand::ArchiveLinks { codes, search } => {
+                format!("codes={} search={}", codes.len(), search.is_some())
+            }
+            IpcCommand::SampleLinks { n, search, .. } => {
+                format!("n={} search={}", n, search.is_some())
+            }
+            IpcCommand::GenerateLinks { vars, dry_run, .. } => {
+                format!("vars={} dry_run={}", vars.len(), dry_run)
+            }
+            IpcCommand::ListLinks {
+                page,
+                page_size,
+                search,
+            } => format!(
+                "page={} page_size={} search={}",
+                page,
+                page_size,
+                search.is_some()
+            ),
+            IpcCommand::ImportLinks {
+                links, overwrite, ..
+            } => format!("links={} overwrite={}", links.len(), overwrite),
+            IpcCommand::ConfigList { category } => {
+                format!("category={}", category.as_deref().unwrap_or("<all>"))
+            }
+            IpcCommand::ConfigGet { key }
+            | IpcCommand::ConfigSet { key, .. }
+            | IpcCommand::ConfigReset { key } => format!("key={}", key),
+            IpcCommand::ConfigImport { configs } => format!("configs={}", configs.len()),
+        }
+    }
+}
+
+/// 目标 URL 的主机部分（去掉协议、用户信息、路径与查询参数）
+fn target_host(target: &str) -> &str {
+    let rest = target.split_once("://").map_or(target, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
 }
 
 /// IPC responses sent from server to client
@@ -233,6 +420,15 @@ pub enum IpcResponse {
         last_config_reload: Option<String>,
         /// Number of links in cache
         links_count: usize,
+        /// IPC commands being handled (including this status query)
+        #[serde(default)]
+        ipc_in_flight: usize,
+        /// Per-command IPC statistics since start, most frequent first
+        #[serde(default)]
+        ipc_commands: Vec<IpcCommandStats>,
+        /// Supervised background tasks
+        #[serde(default)]
+        background_tasks: Vec<BackgroundTaskStatus>,
     },
 
     /// Shutdown acknowledgment
@@ -249,9 +445,12 @@ pub enum IpcResponse {
     // ============ Link Management Responses ============
     /// Link created successfully
     LinkCreated {
-        link: ShortLink,
+        link: LinkRecord,
         /// Generated code if none was provided
         generated_code: bool,
+        /// Non-fatal notices, e.g. credentials detected in the target URL
+        #[serde(default)]
+        warnings: Vec<String>,
     },
 
     /// Link deleted successfully
@@ -264,15 +463,57 @@ pub enum IpcResponse {
         errors: Vec<ImportErrorData>,
     },
 
+    /// Batch extend result
+    BatchExtendResult {
+        updated: Vec<crate::services::BatchExtendItem>,
+        skipped: Vec<ImportErrorData>,
+        failed: Vec<ImportErrorData>,
+        dry_run: bool,
+    },
+
+    /// Archive result
+    ArchiveResult {
+        archived: Vec<String>,
+        not_found: Vec<String>,
+        failed: Vec<ImportErrorData>,
+    },
+
+    /// Link restored from the archive
+    LinkUnarchived { link: LinkRecord },
+
+    /// Random sample result
+    SampleResult {
+        links: Vec<LinkRecord>,
+        /// Seed actually used (pass it back to reproduce the sample)
+        seed: u64,
+        /// Number of links matching the filter
+        population: u64,
+    },
+
+    /// Template generation result
+    GenerateResult {
+        planned: Vec<crate::services::TemplateLink>,
+        /// Codes that already exist (dry run only)
+        existing: Vec<String>,
+        created: Vec<crate::services::TemplateLink>,
+        failed: Vec<ImportErrorData>,
+        dry_run: bool,
+    },
+
     /// Link updated successfully
-    LinkUpdated { link: ShortLink },
+    LinkUpdated {
+        link: LinkRecord,
+        /// Non-fatal notices, e.g. credentials detected in the target URL
+        #[serde(default)]
+        warnings: Vec<String>,
+    },
 
     /// Get link result
-    LinkFound { link: Option<ShortLink> },
+    LinkFound { link: Option<LinkRecord> },
 
     /// List links result
     LinkList {
-        links: Vec<ShortLink>,
+        links: Vec<LinkRecord>,
         total: usize,
         page: u64,
         page_size: u64,
@@ -297,10 +538,10 @@ pub enum IpcResponse {
     },
 
     /// Export result
-    ExportResult { links: Vec<ShortLink> },
+    ExportResult { links: Vec<LinkRecord> },
 
     /// Export chunk (streaming export)
-    ExportChunk { links: Vec<ShortLink> },
+    ExportChunk { links: Vec<LinkRecord> },
 
     /// Export done marker (streaming export)
     ExportDone { total: usize },
@@ -347,6 +588,16 @@ pub enum IpcResponse {
     },
 }
 
+impl IpcResponse {
+    /// Whether this response reports a failed command
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            IpcResponse::Error { .. } | IpcResponse::ReloadResult { success: false, .. }
+        )
+    }
+}
+
 /// IPC connection errors
 #[derive(Debug)]
 pub enum IpcError {
@@ -444,6 +695,49 @@ mod tests {
         assert!(err.source().is_some());
     }
 
+    #[test]
+    fn test_command_summary_redacts_sensitive_fields() {
+        let add = IpcCommand::AddLink {
+            code: Some("promo".into()),
+            target: "https://user:pw@shop.example.com/cart?token=secret".into(),
+            force: false,
+            expires_at: None,
+            password: Some("hunter2".into()),
+            created_via: None,
+            analytics_level: None,
+            extras: None,
+            override_cooldown: false,
+        };
+        let summary = add.summary();
+        assert_eq!(
+            summary,
+            "code=promo target_host=shop.example.com force=false"
+        );
+
+        let set = IpcCommand::ConfigSet {
+            key: "api.admin_token".into(),
+            value: "super-secret".into(),
+        };
+        assert_eq!(set.summary(), "key=api.admin_token");
+
+        let import = IpcCommand::ImportLinks {
+            links: Vec::new(),
+            overwrite: true,
+            stream_progress: false,
+        };
+        assert_eq!(import.summary(), "links=0 overwrite=true");
+    }
+
+    #[test]
+    fn test_response_is_error() {
+        let error = IpcResponse::Error {
+            code: "E".into(),
+            message: "failed".into(),
+        };
+        assert!(error.is_error());
+        assert!(!IpcResponse::ShuttingDown.is_error());
+    }
+
     #[test]
     fn test_import_error_data_with_error_code() {
         let data = ImportErrorData {
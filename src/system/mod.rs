@@ -4,7 +4,9 @@
 //! - Platform abstraction (signals, locks)
 //! - Hot reload functionality
 //! - IPC (Inter-Process Communication) for CLI-server communication
+//! - Runtime directory for files written while running (lock file, socket, crash log)
 
 pub mod ipc;
 pub mod platform;
 pub mod reload;
+pub mod runtime_dir;
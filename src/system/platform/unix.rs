@@ -9,6 +9,10 @@ use std::fs;
 use tracing::{debug, error, info};
 
 use super::PlatformOps;
+use crate::system::runtime_dir::runtime_path;
+
+/// PID 文件名（位于 `system.runtime_dir` 下）
+const PID_FILE_NAME: &str = "shortlinker.pid";
 
 /// Unix platform operations implementation
 pub struct UnixPlatform;
@@ -17,10 +21,10 @@ impl PlatformOps for UnixPlatform {
     fn init_lockfile() -> std::io::Result<()> {
         use nix::sys::signal;
         use nix::unistd::Pid;
-        use std::path::Path;
         use std::process;
 
-        let pid_file = "shortlinker.pid";
+        let pid_file = runtime_path(PID_FILE_NAME);
+        let pid_file = pid_file.as_path();
 
         // First, check if server is running via IPC (more reliable)
         if PlatformIpc::is_server_running() {
@@ -36,7 +40,7 @@ impl PlatformOps for UnixPlatform {
         PlatformIpc::cleanup();
 
         // Check if PID file already exists (fallback check)
-        if Path::new(pid_file).exists() {
+        if pid_file.exists() {
             match fs::read_to_string(pid_file) {
                 Ok(old_pid_str) => {
                     if let Ok(old_pid) = old_pid_str.trim().parse::<u32>() {
@@ -90,13 +94,13 @@ impl PlatformOps for UnixPlatform {
     }
 
     fn cleanup_lockfile() {
-        let pid_file = "shortlinker.pid";
-        if let Err(e) = fs::remove_file(pid_file)
+        let pid_file = runtime_path(PID_FILE_NAME);
+        if let Err(e) = fs::remove_file(&pid_file)
             && e.kind() != std::io::ErrorKind::NotFound
         {
             error!("Failed to delete PID file: {}", e);
-        } else if std::path::Path::new(pid_file).exists() {
-            info!("PID file cleaned: {}", pid_file);
+        } else if pid_file.exists() {
+            info!("PID file cleaned: {}", pid_file.display());
         }
 
         // Also clean up IPC socket
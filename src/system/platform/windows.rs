@@ -12,6 +12,10 @@ use std::fs;
 use tracing::{error, info, warn};
 
 use super::PlatformOps;
+use crate::system::runtime_dir::runtime_path;
+
+/// 锁文件名（位于 `system.runtime_dir` 下）
+const LOCK_FILE_NAME: &str = ".shortlinker.lock";
 
 /// Windows platform operations implementation
 pub struct WindowsPlatform;
@@ -19,9 +23,9 @@ pub struct WindowsPlatform;
 impl PlatformOps for WindowsPlatform {
     fn init_lockfile() -> std::io::Result<()> {
         use std::io::{self, Write};
-        use std::path::Path;
 
-        let lock_file = ".shortlinker.lock";
+        let lock_file = runtime_path(LOCK_FILE_NAME);
+        let lock_file = lock_file.as_path();
 
         // First, check if server is running via IPC (more reliable)
         if PlatformIpc::is_server_running() {
@@ -34,7 +38,7 @@ impl PlatformOps for WindowsPlatform {
         }
 
         // Check if lock file already exists (fallback check)
-        if Path::new(lock_file).exists() {
+        if lock_file.exists() {
             // On Windows, we can't reliably check if the process is still running
             // So we just warn and remove the lock file since IPC check passed
             warn!("Lock file exists but IPC not responding, assuming stale");
@@ -59,13 +63,13 @@ impl PlatformOps for WindowsPlatform {
     }
 
     fn cleanup_lockfile() {
-        let lock_file = ".shortlinker.lock";
-        if let Err(e) = fs::remove_file(lock_file)
+        let lock_file = runtime_path(LOCK_FILE_NAME);
+        if let Err(e) = fs::remove_file(&lock_file)
             && e.kind() != std::io::ErrorKind::NotFound
         {
             error!("Failed to delete lock file: {}", e);
-        } else if std::path::Path::new(lock_file).exists() {
-            info!("Lock file cleaned: {}", lock_file);
+        } else if lock_file.exists() {
+            info!("Lock file cleaned: {}", lock_file.display());
         }
 
         // Also clean up IPC (no-op on Windows for named pipes)
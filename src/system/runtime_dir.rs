@@ -0,0 +1,72 @@
+//! 运行时可写目录
+//!
+//! 进程运行期间需要写入的文件（PID / 锁文件、IPC socket、崩溃日志）
+//! 统一放在 `system.runtime_dir` 下，使配置文件和程序本身可以只读挂载。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// runtime_dir 下的文件路径
+pub fn runtime_path(name: &str) -> PathBuf {
+    crate::config::get_config().system.runtime_path(name)
+}
+
+/// 启动自检：确保 runtime_dir 存在且可写
+///
+/// 目录不存在时尝试创建；随后写入并删除一个探测文件。
+/// 失败时返回带挂载建议的错误信息。
+pub fn ensure_writable() -> Result<PathBuf, String> {
+    let dir = PathBuf::from(&crate::config::get_config().system.runtime_dir);
+    check_writable(&dir).map_err(|e| {
+        format!(
+            "Runtime directory '{}' is not writable: {}. \
+             It holds the PID/lock file, IPC socket and crash log. \
+             On a read-only root filesystem, mount a writable volume there \
+             (e.g. a Kubernetes emptyDir at /run/shortlinker) and set \
+             `system.runtime_dir` (env SL__SYSTEM__RUNTIME_DIR) to it.",
+            dir.display(),
+            e
+        )
+    })?;
+    Ok(dir)
+}
+
+fn check_writable(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".shortlinker-write-probe-{}", std::process::id()));
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_writable_creates_missing_dir() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("nested/runtime");
+        check_writable(&dir).unwrap();
+        assert!(dir.is_dir());
+        // 探测文件不残留
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_writable_rejects_read_only_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::set_permissions(temp.path(), fs::Permissions::from_mode(0o555)).unwrap();
+        let result = check_writable(temp.path());
+        // root 不受权限位限制，此时无法构造只读目录
+        let bypassed = fs::File::create(temp.path().join("bypass")).is_ok();
+        fs::set_permissions(temp.path(), fs::Permissions::from_mode(0o755)).unwrap();
+
+        if !bypassed {
+            assert!(result.is_err());
+        }
+    }
+}
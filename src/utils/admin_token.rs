@@ -0,0 +1,230 @@
+//! 管理员 Token 轮换工具模块
+//!
+//! `token rotate` 会把旧 token 的哈希保存到 `api.admin_token_previous`，并在
+//! `api.admin_token_previous_expires_at` 之前继续接受旧值，形成"当前 + 上一个"
+//! 的双凭证窗口，方便调用方平滑迁移。
+//!
+//! 登录签发的每个 JWT 都带有所用管理员 Token 的指纹（`cred` claim），校验时按
+//! [`credential_status`] 判定：当前 Token 签发的正常放行，上一个 Token 签发的只在
+//! 宽限期内放行，其余（含 `--revoke-now` 吊销、再次轮换、未携带指纹的 JWT）一律拒绝。
+
+use chrono::{DateTime, Utc};
+use xxhash_rust::xxh64::xxh64;
+
+use super::password::PasswordError;
+use crate::config::{get_runtime_config, keys};
+
+/// 处于宽限期内的上一个管理员 Token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviousAdminToken {
+    pub hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PreviousAdminToken {
+    /// 从原始配置值解析，哈希为空、时间无效或已过宽限期时返回 None
+    pub fn from_values(hash: &str, expires_at: &str, now: DateTime<Utc>) -> Option<Self> {
+        if hash.is_empty() {
+            return None;
+        }
+        let expires_at = DateTime::parse_from_rfc3339(expires_at)
+            .ok()?
+            .with_timezone(&Utc);
+        (expires_at > now).then(|| Self {
+            hash: hash.to_string(),
+            expires_at,
+        })
+    }
+
+    /// 从运行时配置读取当前仍有效的上一个 Token
+    pub fn load() -> Option<Self> {
+        let rt = get_runtime_config();
+        Self::from_values(
+            &rt.get_or(keys::API_ADMIN_TOKEN_PREVIOUS, ""),
+            &rt.get_or(keys::API_ADMIN_TOKEN_PREVIOUS_EXPIRES_AT, ""),
+            Utc::now(),
+        )
+    }
+}
+
+/// 管理员 Token 校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminTokenMatch {
+    /// 匹配当前 Token
+    Current,
+    /// 匹配宽限期内的上一个 Token，附带其失效时间
+    Previous(DateTime<Utc>),
+    /// 均不匹配
+    Invalid,
+}
+
+/// 依次校验当前 Token 与宽限期内的上一个 Token
+pub fn verify_admin_token(
+    password: &str,
+    current_hash: &str,
+    previous: Option<&PreviousAdminToken>,
+) -> Result<AdminTokenMatch, PasswordError> {
+    let verify = |hash: &str| {
+        aster_forge_crypto::verify_password(password, hash)
+            .map_err(|e| PasswordError::HashError(e.to_string()))
+    };
+
+    if verify(current_hash)? {
+        return Ok(AdminTokenMatch::Current);
+    }
+    match previous {
+        Some(previous) if verify(&previous.hash)? => {
+            Ok(AdminTokenMatch::Previous(previous.expires_at))
+        }
+        _ => Ok(AdminTokenMatch::Invalid),
+    }
+}
+
+/// JWT 中记录的凭据指纹：签发时所用管理员 Token 哈希的 xxh64
+///
+/// 只用来区分 Token 代次，JWT 本身有签名，不需要抗碰撞。
+pub fn credential_fingerprint(hash: &str) -> String {
+    format!("{:016x}", xxh64(hash.as_bytes(), 0))
+}
+
+/// JWT 所属管理员凭据的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialStatus {
+    /// 由当前 Token 签发
+    Current,
+    /// 由宽限期内的上一个 Token 签发，附带其失效时间
+    Deprecated(DateTime<Utc>),
+    /// 凭据已被轮换、吊销或过了宽限期，JWT 不再有效
+    Revoked,
+}
+
+/// 按 JWT 中的凭据指纹判断其是否仍然有效（读取运行时配置）
+pub fn credential_status(fingerprint: Option<&str>) -> CredentialStatus {
+    let rt = get_runtime_config();
+    credential_status_with(
+        fingerprint,
+        &rt.get_or(keys::API_ADMIN_TOKEN, ""),
+        PreviousAdminToken::load().as_ref(),
+    )
+}
+
+/// [`credential_status`] 的纯函数版本
+pub fn credential_status_with(
+    fingerprint: Option<&str>,
+    current_hash: &str,
+    previous: Option<&PreviousAdminToken>,
+) -> CredentialStatus {
+    let Some(fingerprint) = fingerprint else {
+        return CredentialStatus::Revoked;
+    };
+    if !current_hash.is_empty() && fingerprint == credential_fingerprint(current_hash) {
+        return CredentialStatus::Current;
+    }
+    match previous {
+        Some(previous) if fingerprint == credential_fingerprint(&previous.hash) => {
+            CredentialStatus::Deprecated(previous.expires_at)
+        }
+        _ => CredentialStatus::Revoked,
+    }
+}
+
+/// `X-Token-Deprecation` 响应头的值
+pub fn deprecation_header_value(expires_at: i64) -> String {
+    DateTime::<Utc>::from_timestamp(expires_at, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn previous(password: &str, expires_at: DateTime<Utc>) -> PreviousAdminToken {
+        PreviousAdminToken {
+            hash: aster_forge_crypto::hash_password(password).expect("hash should succeed"),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_from_values_requires_hash_and_future_expiry() {
+        let now = Utc::now();
+        let future = (now + Duration::hours(1)).to_rfc3339();
+        let past = (now - Duration::hours(1)).to_rfc3339();
+
+        assert!(PreviousAdminToken::from_values("$argon2id$x", &future, now).is_some());
+        assert!(PreviousAdminToken::from_values("", &future, now).is_none());
+        assert!(PreviousAdminToken::from_values("$argon2id$x", &past, now).is_none());
+        assert!(PreviousAdminToken::from_values("$argon2id$x", "", now).is_none());
+    }
+
+    #[test]
+    fn test_verify_accepts_current_and_previous_tokens() {
+        let current = aster_forge_crypto::hash_password("new-token-value").unwrap();
+        let expires_at = Utc::now() + Duration::hours(72);
+        let previous = previous("old-token-value", expires_at);
+
+        assert_eq!(
+            verify_admin_token("new-token-value", &current, Some(&previous)).unwrap(),
+            AdminTokenMatch::Current
+        );
+        assert_eq!(
+            verify_admin_token("old-token-value", &current, Some(&previous)).unwrap(),
+            AdminTokenMatch::Previous(expires_at)
+        );
+        assert_eq!(
+            verify_admin_token("old-token-value", &current, None).unwrap(),
+            AdminTokenMatch::Invalid
+        );
+        assert_eq!(
+            verify_admin_token("other", &current, Some(&previous)).unwrap(),
+            AdminTokenMatch::Invalid
+        );
+    }
+
+    #[test]
+    fn test_credential_status_follows_rotation() {
+        let expires_at = Utc::now() + Duration::hours(72);
+        let old = PreviousAdminToken {
+            hash: "$argon2id$old".to_string(),
+            expires_at,
+        };
+        let old_fp = credential_fingerprint(&old.hash);
+        let new_fp = credential_fingerprint("$argon2id$new");
+
+        // 轮换前：旧 Token 即当前 Token
+        assert_eq!(
+            credential_status_with(Some(&old_fp), &old.hash, None),
+            CredentialStatus::Current
+        );
+        // 宽限期内：新旧都有效，旧的标记为弃用
+        assert_eq!(
+            credential_status_with(Some(&new_fp), "$argon2id$new", Some(&old)),
+            CredentialStatus::Current
+        );
+        assert_eq!(
+            credential_status_with(Some(&old_fp), "$argon2id$new", Some(&old)),
+            CredentialStatus::Deprecated(expires_at)
+        );
+        // --revoke-now 或宽限期结束：旧指纹失效
+        assert_eq!(
+            credential_status_with(Some(&old_fp), "$argon2id$new", None),
+            CredentialStatus::Revoked
+        );
+        // 未携带指纹的 JWT 一律拒绝
+        assert_eq!(
+            credential_status_with(None, "$argon2id$new", Some(&old)),
+            CredentialStatus::Revoked
+        );
+        assert_eq!(
+            credential_status_with(Some(""), "", None),
+            CredentialStatus::Revoked
+        );
+    }
+
+    #[test]
+    fn test_deprecation_header_value_is_rfc3339() {
+        assert_eq!(deprecation_header_value(0), "1970-01-01T00:00:00Z");
+    }
+}
@@ -0,0 +1,356 @@
+//! 短链对外 base URL 的解析
+//!
+//! 所有需要生成完整短链 URL 的地方都应调用 [`resolve_base_url`]，优先级：
+//!
+//! 1. 显式配置 `features.public_base_url`
+//! 2. 请求来自 `api.trusted_proxies` 时，按 `Forwarded`（RFC 7239，取第一跳）→
+//!    `X-Forwarded-Proto` / `X-Forwarded-Host` / `X-Forwarded-Port` 推断
+//! 3. `Host` 头（HTTP/2 为 `:authority`）加连接本身的协议
+//!
+//! 非可信来源的转发头一律忽略，避免客户端伪造出指向其他域名的链接。推断结果中
+//! 与协议默认端口相同的端口（http 80 / https 443）会被省略，末尾不带 `/`。
+
+use actix_web::HttpRequest;
+use actix_web::http::header::{FORWARDED, HOST, HeaderMap};
+
+use crate::config::{keys, try_get_runtime_config};
+use crate::utils::cidr::Cidr;
+use crate::utils::ip::{peer_ip, trusted_proxies};
+
+/// base URL 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseUrlSource {
+    /// `features.public_base_url`
+    Config,
+    /// 可信代理的转发头
+    Forwarded,
+    /// `Host` 头
+    Host,
+}
+
+impl BaseUrlSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Forwarded => "forwarded",
+            Self::Host => "host",
+        }
+    }
+}
+
+/// 解析结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedBaseUrl {
+    /// 如 `https://s.example.com`，不带末尾 `/`
+    pub url: String,
+    pub source: BaseUrlSource,
+}
+
+/// 显式配置的 `features.public_base_url`，未配置时返回 `None`
+pub fn configured_base_url() -> Option<String> {
+    let rt = try_get_runtime_config()?;
+    let url = rt.get_or(keys::FEATURES_PUBLIC_BASE_URL, "");
+    let url = url.trim().trim_end_matches('/');
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+/// 当前请求对应的短链 base URL（显式配置 > 推断）
+pub fn resolve_base_url(req: &HttpRequest) -> String {
+    resolve(req).url
+}
+
+/// 同 [`resolve_base_url`]，同时返回来源
+pub fn resolve(req: &HttpRequest) -> ResolvedBaseUrl {
+    if let Some(url) = configured_base_url() {
+        return ResolvedBaseUrl {
+            url,
+            source: BaseUrlSource::Config,
+        };
+    }
+
+    let connection_scheme = if req.app_config().secure() {
+        "https"
+    } else {
+        "http"
+    };
+    let authority = req.uri().authority().map(|a| a.as_str());
+    let fallback_host = authority.unwrap_or_else(|| req.app_config().host());
+    infer_base_url(
+        req.headers(),
+        is_trusted_peer(req),
+        connection_scheme,
+        fallback_host,
+    )
+}
+
+/// 从请求头推断 base URL
+///
+/// - `from_trusted_proxy`：直连对端是否属于可信代理，为 `false` 时忽略所有转发头
+/// - `connection_scheme`：连接本身的协议（`http` / `https`）
+/// - `fallback_host`：没有 `Host` 头时使用的主机（HTTP/2 的 `:authority` 或服务端配置）
+///
+/// 转发头只提供协议或主机其中之一时，另一部分取自连接本身。非法的头值视为缺失。
+pub fn infer_base_url(
+    headers: &HeaderMap,
+    from_trusted_proxy: bool,
+    connection_scheme: &str,
+    fallback_host: &str,
+) -> ResolvedBaseUrl {
+    let mut forwarded = Forwarded::default();
+    if from_trusted_proxy {
+        forwarded = Forwarded::from_rfc7239(headers);
+        if forwarded.is_empty() {
+            forwarded = Forwarded::from_x_forwarded(headers);
+        }
+    }
+
+    let source = if forwarded.is_empty() {
+        BaseUrlSource::Host
+    } else {
+        BaseUrlSource::Forwarded
+    };
+    let scheme = forwarded
+        .proto
+        .unwrap_or_else(|| connection_scheme.to_ascii_lowercase());
+    let host = forwarded
+        .host
+        .or_else(|| header_str(headers, HOST.as_str()).and_then(parse_host))
+        .or_else(|| parse_host(fallback_host))
+        .unwrap_or_else(|| "localhost".to_string());
+    let host = match forwarded.port {
+        Some(port) if !has_port(&host) => format!("{}:{}", host, port),
+        _ => host,
+    };
+
+    ResolvedBaseUrl {
+        url: format!("{}://{}", scheme, strip_default_port(&host, &scheme)),
+        source,
+    }
+}
+
+/// 从转发头得到的协议 / 主机 / 端口
+#[derive(Debug, Default)]
+struct Forwarded {
+    proto: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+impl Forwarded {
+    fn is_empty(&self) -> bool {
+        self.proto.is_none() && self.host.is_none() && self.port.is_none()
+    }
+
+    /// `Forwarded: for=1.2.3.4;proto=https;host=s.example.com, for=...`，只取第一跳
+    fn from_rfc7239(headers: &HeaderMap) -> Self {
+        let mut forwarded = Self::default();
+        let Some(first) =
+            header_str(headers, FORWARDED.as_str()).and_then(|value| value.split(',').next())
+        else {
+            return forwarded;
+        };
+        for pair in first.split(';') {
+            let Some((name, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+            match name.trim().to_ascii_lowercase().as_str() {
+                "proto" => forwarded.proto = parse_scheme(value),
+                "host" => forwarded.host = parse_host(value),
+                _ => {}
+            }
+        }
+        forwarded
+    }
+
+    /// `X-Forwarded-Proto` / `X-Forwarded-Host` / `X-Forwarded-Port`，多值时取第一个
+    fn from_x_forwarded(headers: &HeaderMap) -> Self {
+        let first = |name: &str| {
+            header_str(headers, name)
+                .and_then(|value| value.split(',').next())
+                .map(str::trim)
+        };
+        Self {
+            proto: first("x-forwarded-proto").and_then(parse_scheme),
+            host: first("x-forwarded-host").and_then(parse_host),
+            port: first("x-forwarded-port").and_then(|port| port.parse().ok()),
+        }
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_scheme(value: &str) -> Option<String> {
+    let scheme = value.trim().to_ascii_lowercase();
+    matches!(scheme.as_str(), "http" | "https").then_some(scheme)
+}
+
+/// 校验主机（可带端口），只接受主机名 / IPv4 / `[IPv6]` 允许的字符，统一小写
+fn parse_host(value: &str) -> Option<String> {
+    let host = value.trim();
+    let valid = !host.is_empty()
+        && host.len() <= 255
+        && host.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b':' | b'[' | b']')
+        });
+    valid.then(|| host.to_ascii_lowercase())
+}
+
+fn has_port(host: &str) -> bool {
+    match host.rsplit_once(':') {
+        // `[::1]` 的冒号在方括号内
+        Some((_, port)) => !port.contains(']'),
+        None => false,
+    }
+}
+
+/// 去掉与协议默认端口相同的端口
+fn strip_default_port<'a>(host: &'a str, scheme: &str) -> &'a str {
+    let default_port = match scheme {
+        "https" => "443",
+        _ => "80",
+    };
+    match host.rsplit_once(':') {
+        Some((name, port)) if port == default_port && !name.is_empty() => name,
+        _ => host,
+    }
+}
+
+/// 直连对端是否在 `api.trusted_proxies` 中（Unix Socket 模式下本机视为可信）
+fn is_trusted_peer(req: &HttpRequest) -> bool {
+    let Some(peer) = peer_ip(req.peer_addr()) else {
+        return false;
+    };
+    trusted_proxies()
+        .iter()
+        .filter_map(|raw| Cidr::parse(raw))
+        .any(|cidr| cidr.contains(peer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        map
+    }
+
+    fn infer(pairs: &[(&'static str, &'static str)], trusted: bool) -> ResolvedBaseUrl {
+        infer_base_url(&headers(pairs), trusted, "http", "127.0.0.1:8080")
+    }
+
+    #[test]
+    fn test_host_header_and_default_ports() {
+        let resolved = infer(&[("host", "S.Example.com:80")], false);
+        assert_eq!(resolved.url, "http://s.example.com");
+        assert_eq!(resolved.source, BaseUrlSource::Host);
+
+        assert_eq!(
+            infer(&[("host", "s.example.com:8443")], false).url,
+            "http://s.example.com:8443"
+        );
+        // 没有 Host 头时回退到连接信息
+        assert_eq!(infer(&[], false).url, "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_forwarded_headers_ignored_from_untrusted_peer() {
+        let pairs = [
+            ("host", "internal:3000"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "evil.example"),
+            ("forwarded", "proto=https;host=evil.example"),
+        ];
+        let resolved = infer(&pairs, false);
+        assert_eq!(resolved.url, "http://internal:3000");
+        assert_eq!(resolved.source, BaseUrlSource::Host);
+    }
+
+    #[test]
+    fn test_x_forwarded_headers() {
+        let resolved = infer(
+            &[
+                ("host", "internal:3000"),
+                ("x-forwarded-proto", "https, http"),
+                ("x-forwarded-host", "s.example.com:443"),
+            ],
+            true,
+        );
+        assert_eq!(resolved.url, "https://s.example.com");
+        assert_eq!(resolved.source, BaseUrlSource::Forwarded);
+
+        // 只有协议：主机取自 Host 头，端口按转发后的协议判断
+        assert_eq!(
+            infer(
+                &[
+                    ("host", "s.example.com:443"),
+                    ("x-forwarded-proto", "https")
+                ],
+                true
+            )
+            .url,
+            "https://s.example.com"
+        );
+        // X-Forwarded-Port 仅在主机未带端口时生效
+        assert_eq!(
+            infer(
+                &[
+                    ("x-forwarded-proto", "https"),
+                    ("x-forwarded-host", "s.example.com"),
+                    ("x-forwarded-port", "8443"),
+                ],
+                true
+            )
+            .url,
+            "https://s.example.com:8443"
+        );
+    }
+
+    #[test]
+    fn test_rfc7239_forwarded_takes_precedence() {
+        let resolved = infer(
+            &[
+                (
+                    "forwarded",
+                    "for=203.0.113.1;proto=https;host=\"s.example.com\", for=10.0.0.1;proto=http",
+                ),
+                ("x-forwarded-host", "other.example"),
+            ],
+            true,
+        );
+        assert_eq!(resolved.url, "https://s.example.com");
+        assert_eq!(resolved.source, BaseUrlSource::Forwarded);
+    }
+
+    #[test]
+    fn test_invalid_values_are_ignored() {
+        assert_eq!(
+            infer(
+                &[
+                    ("host", "s.example.com"),
+                    ("x-forwarded-proto", "javascript"),
+                    ("x-forwarded-host", "a.example/evil"),
+                ],
+                true
+            )
+            .url,
+            "http://s.example.com"
+        );
+        assert_eq!(infer(&[("host", "[::1]:80")], false).url, "http://[::1]");
+        assert_eq!(infer(&[("host", "[::1]")], false).url, "http://[::1]");
+    }
+}
@@ -0,0 +1,277 @@
+//! 通用熔断器
+//!
+//! 连续失败达到阈值后断开一段时间，期间调用方直接跳过下游；到期后进入半开状态，
+//! 只放行一个探测请求：成功则闭合，失败则重新断开。不关心下游是什么，
+//! GeoIP 外部 API、webhook 等需要"下游挂了就别再堆请求"的地方都可以复用。
+//!
+//! 时间由调用方传入（`Instant`），状态迁移可以在测试里精确驱动。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// 正常放行
+    Closed,
+    /// 断开中，直接跳过
+    Open,
+    /// 断开到期，等待探测结果
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+
+    /// 指标用的数值：0 = closed，1 = open，2 = half_open
+    pub fn as_gauge(&self) -> f64 {
+        match self {
+            Self::Closed => 0.0,
+            Self::Open => 1.0,
+            Self::HalfOpen => 2.0,
+        }
+    }
+}
+
+/// 熔断器状态快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// 断开状态的剩余时长（仅 Open 时有值）
+    pub open_remaining: Option<Duration>,
+    /// 累计断开次数
+    pub trips: u64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    probe_in_flight: bool,
+    trips: u64,
+}
+
+/// 连续失败计数熔断器
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// 连续失败阈值，0 = 永不断开
+    failure_threshold: u32,
+    open_duration: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_duration,
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                open_until: None,
+                probe_in_flight: false,
+                trips: 0,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 是否放行一次调用
+    ///
+    /// 放行后调用方必须以 [`record_success`](Self::record_success)、
+    /// [`record_failure`](Self::record_failure) 或 [`abandon`](Self::abandon)
+    /// 之一结束，否则半开状态的探测名额不会归还。
+    pub fn try_acquire(&self, now: Instant) -> bool {
+        let mut inner = self.lock();
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                if inner.open_until.is_some_and(|until| now < until) {
+                    return false;
+                }
+                inner.state = CircuitState::HalfOpen;
+                inner.open_until = None;
+                inner.probe_in_flight = true;
+                true
+            }
+            CircuitState::HalfOpen => {
+                if inner.probe_in_flight {
+                    return false;
+                }
+                inner.probe_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// 记录一次成功，返回变化后的状态（状态未变时为 `None`）
+    pub fn record_success(&self) -> Option<CircuitState> {
+        let mut inner = self.lock();
+        inner.consecutive_failures = 0;
+        inner.probe_in_flight = false;
+        if inner.state == CircuitState::Closed {
+            return None;
+        }
+        inner.state = CircuitState::Closed;
+        inner.open_until = None;
+        Some(CircuitState::Closed)
+    }
+
+    /// 记录一次失败，返回变化后的状态（状态未变时为 `None`）
+    pub fn record_failure(&self, now: Instant) -> Option<CircuitState> {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        inner.probe_in_flight = false;
+        let trip = match inner.state {
+            CircuitState::Closed => {
+                self.failure_threshold > 0 && inner.consecutive_failures >= self.failure_threshold
+            }
+            CircuitState::HalfOpen => true,
+            // 断开前放行的慢请求晚到的失败，不延长断开时间
+            CircuitState::Open => false,
+        };
+        if !trip {
+            return None;
+        }
+        inner.state = CircuitState::Open;
+        inner.open_until = Some(now + self.open_duration);
+        inner.trips += 1;
+        Some(CircuitState::Open)
+    }
+
+    /// 放行后没有真正调用下游（被其他限制拦下或调用被取消），归还探测名额
+    pub fn abandon(&self) {
+        self.lock().probe_in_flight = false;
+    }
+
+    pub fn snapshot(&self, now: Instant) -> CircuitSnapshot {
+        let inner = self.lock();
+        CircuitSnapshot {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            open_remaining: match inner.state {
+                CircuitState::Open => inner
+                    .open_until
+                    .map(|until| until.saturating_duration_since(now)),
+                _ => None,
+            },
+            trips: inner.trips,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, OPEN);
+        let now = Instant::now();
+
+        assert!(breaker.try_acquire(now));
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.record_failure(now), None);
+        // 成功会清零连续失败计数
+        assert_eq!(breaker.record_success(), None);
+        assert_eq!(breaker.snapshot(now).consecutive_failures, 0);
+
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.record_failure(now), Some(CircuitState::Open));
+
+        let snapshot = breaker.snapshot(now);
+        assert_eq!(snapshot.state, CircuitState::Open);
+        assert_eq!(snapshot.open_remaining, Some(OPEN));
+        assert_eq!(snapshot.trips, 1);
+        assert!(!breaker.try_acquire(now + Duration::from_secs(59)));
+    }
+
+    #[test]
+    fn test_half_open_allows_single_probe() {
+        let breaker = CircuitBreaker::new(1, OPEN);
+        let now = Instant::now();
+        breaker.record_failure(now);
+
+        let later = now + OPEN;
+        assert!(breaker.try_acquire(later));
+        assert_eq!(breaker.snapshot(later).state, CircuitState::HalfOpen);
+        // 探测未返回前其他调用继续跳过
+        assert!(!breaker.try_acquire(later));
+
+        assert_eq!(breaker.record_success(), Some(CircuitState::Closed));
+        assert!(breaker.try_acquire(later));
+        assert!(breaker.try_acquire(later));
+    }
+
+    #[test]
+    fn test_failed_probe_reopens() {
+        let breaker = CircuitBreaker::new(2, OPEN);
+        let now = Instant::now();
+        breaker.record_failure(now);
+        breaker.record_failure(now);
+
+        let later = now + OPEN;
+        assert!(breaker.try_acquire(later));
+        // 半开状态一次失败就重新断开，不需要再攒够阈值
+        assert_eq!(breaker.record_failure(later), Some(CircuitState::Open));
+        let snapshot = breaker.snapshot(later);
+        assert_eq!(snapshot.state, CircuitState::Open);
+        assert_eq!(snapshot.open_remaining, Some(OPEN));
+        assert_eq!(snapshot.trips, 2);
+        assert!(!breaker.try_acquire(later));
+        assert!(breaker.try_acquire(later + OPEN));
+    }
+
+    #[test]
+    fn test_abandon_returns_probe() {
+        let breaker = CircuitBreaker::new(1, OPEN);
+        let now = Instant::now();
+        breaker.record_failure(now);
+
+        let later = now + OPEN;
+        assert!(breaker.try_acquire(later));
+        breaker.abandon();
+        assert_eq!(breaker.snapshot(later).state, CircuitState::HalfOpen);
+        assert!(breaker.try_acquire(later));
+    }
+
+    #[test]
+    fn test_late_failure_while_open_does_not_extend() {
+        let breaker = CircuitBreaker::new(1, OPEN);
+        let now = Instant::now();
+        breaker.record_failure(now);
+
+        let later = now + Duration::from_secs(30);
+        assert_eq!(breaker.record_failure(later), None);
+        assert_eq!(
+            breaker.snapshot(later).open_remaining,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(breaker.snapshot(later).trips, 1);
+    }
+
+    #[test]
+    fn test_zero_threshold_never_opens() {
+        let breaker = CircuitBreaker::new(0, OPEN);
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(breaker.try_acquire(now));
+            assert_eq!(breaker.record_failure(now), None);
+        }
+        assert_eq!(breaker.snapshot(now).state, CircuitState::Closed);
+    }
+}
@@ -0,0 +1,338 @@
+//! CSV 方言检测
+//!
+//! 导入的 CSV 常来自 Excel 等外部工具：欧洲区域设置导出分号分隔 + Latin-1 编码，
+//! "UTF-8 CSV" 格式会带 BOM，表头大小写和空格也不统一。这里在解析前统一处理：
+//!
+//! - 编码：剥离 UTF-8 BOM；非法 UTF-8 按 Latin-1（Windows-1252）转码
+//! - 分隔符：显式指定，或按首行统计在 `,` / `;` / Tab 中嗅探
+//! - 表头：去空格、转小写，空格和 `-` 视为 `_`
+//! - 元数据：表头前以 `#` 开头的行（导出写入的 `# schema_version=1`）被剥离并解析，
+//!   版本高于当前 [`LINK_SCHEMA_VERSION`] 的文件直接拒绝
+//!
+//! CLI 导入与 Admin API 导入共用，检测结果写入导入报告。
+
+use csv::{Reader, ReaderBuilder, StringRecord};
+
+use crate::errors::ShortlinkerError;
+use crate::storage::LINK_SCHEMA_VERSION;
+
+/// 参与嗅探的分隔符，平票时靠前者优先
+const SNIFF_CANDIDATES: [u8; 3] = [b',', b';', b'\t'];
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Windows-1252 在 0x80..=0x9F 的字符；未定义的字节按 ISO-8859-1 原样映射
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// 检测到的文本编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvEncoding {
+    Utf8,
+    Utf8Bom,
+    /// ISO-8859-1，按其超集 Windows-1252 解码（Excel 西欧区域设置实际使用的编码）
+    Latin1,
+}
+
+impl CsvEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsvEncoding::Utf8 => "utf-8",
+            CsvEncoding::Utf8Bom => "utf-8 bom",
+            CsvEncoding::Latin1 => "latin-1",
+        }
+    }
+}
+
+/// CSV 方言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: u8,
+    /// 分隔符是否由嗅探得出（否则为显式指定）
+    pub delimiter_sniffed: bool,
+    pub encoding: CsvEncoding,
+}
+
+impl CsvDialect {
+    /// 导入报告中的说明，如 `detected: ; delimiter, latin-1 encoding`
+    pub fn describe(&self) -> String {
+        let delimiter = match self.delimiter {
+            b'\t' => "tab".to_string(),
+            other => (other as char).to_string(),
+        };
+        let source = if self.delimiter_sniffed {
+            ""
+        } else {
+            " (explicit)"
+        };
+        format!(
+            "detected: {} delimiter{}, {} encoding",
+            delimiter,
+            source,
+            self.encoding.as_str()
+        )
+    }
+}
+
+/// 解析用户指定的分隔符：`,` / `;` / `tab`（也接受 `\t`、`comma`、`semicolon`）
+pub fn parse_delimiter(input: &str) -> Result<u8, String> {
+    if input == "\t" {
+        return Ok(b'\t');
+    }
+    match input.trim().to_lowercase().as_str() {
+        "," | "comma" => Ok(b','),
+        ";" | "semicolon" => Ok(b';'),
+        "tab" | "\\t" => Ok(b'\t'),
+        other => Err(format!(
+            "Unsupported CSV delimiter '{}'. Use ',', ';' or 'tab'",
+            other
+        )),
+    }
+}
+
+/// 解码后的 CSV 文本及其方言
+#[derive(Debug, Clone)]
+pub struct DecodedCsv {
+    /// 剥离元数据行后的 CSV 文本
+    pub text: String,
+    pub dialect: CsvDialect,
+    /// 元数据行声明的 schema 版本；没有元数据行的旧文件为 `None`
+    pub schema_version: Option<u32>,
+    /// 表头所在的行号（1-based），用于计算数据行在原文件中的行号
+    pub header_line: usize,
+}
+
+impl DecodedCsv {
+    /// 解码原始字节；`delimiter` 为 `None` 时按首行嗅探
+    pub fn decode(bytes: &[u8], delimiter: Option<u8>) -> Result<Self, ShortlinkerError> {
+        if bytes.starts_with(b"\xFF\xFE") || bytes.starts_with(b"\xFE\xFF") {
+            return Err(ShortlinkerError::csv_parse_failed(
+                "UTF-16 encoded CSV is not supported, please save the file as UTF-8",
+            ));
+        }
+
+        let (text, encoding) = decode_text(bytes);
+        let (metadata_lines, schema_version, body) = split_metadata(&text)?;
+        if let Some(version) = schema_version
+            && version > LINK_SCHEMA_VERSION
+        {
+            return Err(ShortlinkerError::csv_parse_failed(format!(
+                "CSV schema_version {} is newer than the supported version {}, please upgrade shortlinker",
+                version, LINK_SCHEMA_VERSION
+            )));
+        }
+
+        let text = body.to_string();
+        let dialect = CsvDialect {
+            delimiter: delimiter.unwrap_or_else(|| sniff_delimiter(&text)),
+            delimiter_sniffed: delimiter.is_none(),
+            encoding,
+        };
+        Ok(Self {
+            text,
+            dialect,
+            schema_version,
+            header_line: metadata_lines + 1,
+        })
+    }
+
+    /// 第 `index` 个数据行（0-based）在原文件中的行号（1-based）
+    pub fn row_number(&self, index: usize) -> usize {
+        self.header_line + index + 1
+    }
+
+    /// 按检测到的方言构建 reader，表头已规范化
+    pub fn reader(&self) -> Reader<&[u8]> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .delimiter(self.dialect.delimiter)
+            .from_reader(self.text.as_bytes());
+
+        // 表头读取失败时保持原样，错误由后续逐行解析报告
+        if let Ok(headers) = reader.headers() {
+            let normalized: StringRecord = headers.iter().map(normalize_header).collect();
+            reader.set_headers(normalized);
+        }
+        reader
+    }
+}
+
+/// 剥离表头前的 `#` 元数据行，返回 (元数据行数, schema 版本, 剩余文本)
+///
+/// 元数据为空白分隔的 `key=value`，目前只识别 `schema_version`，其余键忽略。
+fn split_metadata(text: &str) -> Result<(usize, Option<u32>, &str), ShortlinkerError> {
+    let mut rest = text;
+    let mut lines = 0;
+    let mut schema_version = None;
+
+    while let Some(comment) = rest.strip_prefix('#') {
+        let (line, next) = comment.split_once('\n').unwrap_or((comment, ""));
+        for (key, value) in line.split_whitespace().filter_map(|kv| kv.split_once('=')) {
+            if key == "schema_version" {
+                let version = value.parse::<u32>().map_err(|_| {
+                    ShortlinkerError::csv_parse_failed(format!(
+                        "Invalid schema_version '{}' in CSV metadata",
+                        value
+                    ))
+                })?;
+                schema_version = Some(version);
+            }
+        }
+        rest = next;
+        lines += 1;
+    }
+    Ok((lines, schema_version, rest))
+}
+
+fn decode_text(bytes: &[u8]) -> (String, CsvEncoding) {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return match std::str::from_utf8(rest) {
+            Ok(text) => (text.to_string(), CsvEncoding::Utf8Bom),
+            // BOM 之后不是合法 UTF-8：按 Latin-1 处理剩余内容
+            Err(_) => (decode_latin1(rest), CsvEncoding::Latin1),
+        };
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), CsvEncoding::Utf8),
+        Err(_) => (decode_latin1(bytes), CsvEncoding::Latin1),
+    }
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+/// 统计首个非空行中引号外各候选分隔符的出现次数，取最多者；都没有时为逗号
+fn sniff_delimiter(text: &str) -> u8 {
+    let first_line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+
+    let mut counts = [0usize; SNIFF_CANDIDATES.len()];
+    let mut in_quotes = false;
+    for b in first_line.bytes() {
+        if b == b'"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && let Some(i) = SNIFF_CANDIDATES.iter().position(|&c| c == b) {
+            counts[i] += 1;
+        }
+    }
+
+    let best = counts
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, &n)| if n > counts[best] { i } else { best });
+    SNIFF_CANDIDATES[best]
+}
+
+/// 宽松表头：`" Click Count "` → `click_count`
+pub(crate) fn normalize_header(header: &str) -> String {
+    header
+        .trim_matches(|c: char| c.is_whitespace() || c == '\u{FEFF}' || c == '\u{200B}')
+        .to_lowercase()
+        .replace([' ', '-'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_delimiter() {
+        assert_eq!(sniff_delimiter("code,target,created_at"), b',');
+        assert_eq!(sniff_delimiter("code;target;created_at"), b';');
+        assert_eq!(sniff_delimiter("code\ttarget\tcreated_at"), b'\t');
+        // 引号内的分隔符不计数
+        assert_eq!(sniff_delimiter("\"a,b,c,d\";target;created_at"), b';');
+        // 跳过前导空行；无候选时默认逗号
+        assert_eq!(sniff_delimiter("\n\ncode;target"), b';');
+        assert_eq!(sniff_delimiter("code"), b',');
+    }
+
+    #[test]
+    fn test_decode_encodings() {
+        let (text, enc) = decode_text("code,ü".as_bytes());
+        assert_eq!((text.as_str(), enc), ("code,ü", CsvEncoding::Utf8));
+
+        let (text, enc) = decode_text(b"\xEF\xBB\xBFcode");
+        assert_eq!((text.as_str(), enc), ("code", CsvEncoding::Utf8Bom));
+
+        let (text, enc) = decode_text(b"M\xFCnchen \x80");
+        assert_eq!((text.as_str(), enc), ("München €", CsvEncoding::Latin1));
+    }
+
+    #[test]
+    fn test_metadata_lines() {
+        let decoded =
+            DecodedCsv::decode(b"# schema_version=1\r\ncode;target\nabc;https://x", None).unwrap();
+        assert_eq!(decoded.schema_version, Some(1));
+        assert_eq!(decoded.header_line, 2);
+        assert_eq!(decoded.row_number(0), 3);
+        assert_eq!(decoded.dialect.delimiter, b';');
+        assert!(decoded.text.starts_with("code;target"));
+
+        // 没有元数据行的旧文件
+        let decoded = DecodedCsv::decode(b"code,target", None).unwrap();
+        assert_eq!(decoded.schema_version, None);
+        assert_eq!(decoded.row_number(0), 2);
+
+        let newer = format!("# schema_version={}\ncode,target", LINK_SCHEMA_VERSION + 1);
+        assert!(DecodedCsv::decode(newer.as_bytes(), None).is_err());
+        assert!(DecodedCsv::decode(b"# schema_version=x\ncode", None).is_err());
+    }
+
+    #[test]
+    fn test_utf16_rejected() {
+        assert!(DecodedCsv::decode(b"\xFF\xFEc\0o\0", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter(";"), Ok(b';'));
+        assert_eq!(parse_delimiter("TAB"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("\t"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("comma"), Ok(b','));
+        assert!(parse_delimiter("|").is_err());
+    }
+
+    #[test]
+    fn test_normalize_header() {
+        assert_eq!(normalize_header(" Click Count "), "click_count");
+        assert_eq!(normalize_header("\u{FEFF}Code"), "code");
+        assert_eq!(normalize_header("Expires-At"), "expires_at");
+    }
+
+    #[test]
+    fn test_describe() {
+        let dialect = CsvDialect {
+            delimiter: b';',
+            delimiter_sniffed: true,
+            encoding: CsvEncoding::Latin1,
+        };
+        assert_eq!(
+            dialect.describe(),
+            "detected: ; delimiter, latin-1 encoding"
+        );
+
+        let dialect = CsvDialect {
+            delimiter: b'\t',
+            delimiter_sniffed: false,
+            encoding: CsvEncoding::Utf8,
+        };
+        assert_eq!(
+            dialect.describe(),
+            "detected: tab delimiter (explicit), utf-8 encoding"
+        );
+    }
+}
@@ -3,17 +3,25 @@
 //! 提供统一的 CSV 读写功能，供 CLI 和 Web Admin 使用
 
 use chrono::Utc;
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::WriterBuilder;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
 use crate::errors::ShortlinkerError;
-use crate::services::{ImportLinkItemRaw, validate_import_row};
-use crate::storage::ShortLink;
+use crate::services::{ImportLinkItemRaw, ImportRowWarning, validate_import_row_checked};
+use crate::storage::{CreatedVia, LINK_SCHEMA_VERSION, ShortLink, format_timestamp};
+use crate::utils::csv_dialect::{CsvDialect, DecodedCsv};
+use crate::utils::csv_transform::ImportTransform;
+
+/// dry-run 预览的行数
+pub const PREVIEW_ROWS: usize = 10;
 
 /// CSV 行数据结构（用于序列化/反序列化）
+///
+/// 列名与时间格式遵循 [`link_schema`](crate::storage::link_schema)；
+/// `created_via`、`analytics_level` 与 `extras` 列为后续新增，旧文件缺省即可。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CsvLinkRow {
     pub code: String,
@@ -25,6 +33,19 @@ pub struct CsvLinkRow {
     pub password: Option<String>,
     #[serde(default)]
     pub click_count: usize,
+    /// 仅导出；导入的链接渠道固定记为 `import`
+    #[serde(default)]
+    pub created_via: Option<String>,
+    #[serde(default)]
+    pub analytics_level: Option<String>,
+    /// 紧凑 JSON 对象文本，空为无扩展字段
+    #[serde(default)]
+    pub extras: Option<String>,
+}
+
+/// 导出文件首行的元数据，导入时由 [`DecodedCsv`] 解析
+pub fn schema_metadata_line() -> String {
+    format!("# schema_version={}\n", LINK_SCHEMA_VERSION)
 }
 
 /// 点击日志 CSV 导出行（仅用于序列化）
@@ -44,10 +65,13 @@ impl From<&ShortLink> for CsvLinkRow {
         Self {
             code: link.code.clone(),
             target: link.target.clone(),
-            created_at: link.created_at.to_rfc3339(),
-            expires_at: link.expires_at.map(|dt| dt.to_rfc3339()),
+            created_at: format_timestamp(&link.created_at),
+            expires_at: link.expires_at.as_ref().map(format_timestamp),
             password: link.password.clone(),
             click_count: link.click,
+            created_via: Some(link.created_via.as_str().to_string()),
+            analytics_level: Some(link.analytics_level.as_str().to_string()),
+            extras: link.extras.clone(),
         }
     }
 }
@@ -55,6 +79,14 @@ impl From<&ShortLink> for CsvLinkRow {
 impl CsvLinkRow {
     /// 转换为 ShortLink，委托给共享验证逻辑
     pub fn into_short_link(self) -> Result<ShortLink, ShortlinkerError> {
+        self.into_short_link_checked().map(|(link, _)| link)
+    }
+
+    /// 同 [`into_short_link`](Self::into_short_link)，同时返回该行的警告（如过期时间已过去、
+    /// 目标 URL 含凭据）
+    pub fn into_short_link_checked(
+        self,
+    ) -> Result<(ShortLink, Vec<ImportRowWarning>), ShortlinkerError> {
         let raw = ImportLinkItemRaw {
             code: self.code,
             target: self.target,
@@ -62,17 +94,24 @@ impl CsvLinkRow {
             expires_at: self.expires_at,
             password: self.password,
             click_count: self.click_count,
+            analytics_level: self.analytics_level,
+            extras: self.extras,
             row_num: None,
         };
-        let rich = validate_import_row(raw).map_err(|e| e.error)?;
-        Ok(ShortLink {
+        let row = validate_import_row_checked(raw).map_err(|e| e.error)?;
+        let rich = row.item;
+        let link = ShortLink {
             code: rich.code,
             target: rich.target,
             created_at: rich.created_at,
             expires_at: rich.expires_at,
             password: rich.password,
             click: rich.click_count,
-        })
+            created_via: CreatedVia::Import,
+            analytics_level: rich.analytics_level,
+            extras: rich.extras,
+        };
+        Ok((link, row.warnings))
     }
 }
 
@@ -83,7 +122,14 @@ pub fn export_to_csv<P: AsRef<Path>>(
 ) -> Result<(), ShortlinkerError> {
     let file = File::create(path.as_ref())
         .map_err(|e| ShortlinkerError::file_operation(format!("Failed to create file: {}", e)))?;
-    let writer = BufWriter::new(file);
+    write_csv(links, BufWriter::new(file))
+}
+
+/// 以导出格式将链接写入任意 writer（如 stdout），首行为 schema 元数据
+pub fn write_csv<W: Write>(links: &[&ShortLink], mut writer: W) -> Result<(), ShortlinkerError> {
+    writer
+        .write_all(schema_metadata_line().as_bytes())
+        .map_err(|e| ShortlinkerError::file_operation(format!("Failed to write CSV: {}", e)))?;
     let mut csv_writer = WriterBuilder::new().from_writer(writer);
 
     for link in links {
@@ -100,37 +146,69 @@ pub fn export_to_csv<P: AsRef<Path>>(
     Ok(())
 }
 
+/// CSV 文件导入结果
+#[derive(Debug, Clone)]
+pub struct CsvImport {
+    pub links: Vec<ShortLink>,
+    pub dialect: CsvDialect,
+    /// 照常导入但需要提示的行，如 `Row 3: expires_at ... is in the past`
+    pub warnings: Vec<String>,
+    /// 被跳过的行，如 `Row 4: Empty code`
+    pub errors: Vec<String>,
+    /// 转换后的前 [`PREVIEW_ROWS`] 行（原文件行号, 行），供 dry-run 预览
+    pub preview: Vec<(usize, CsvLinkRow)>,
+}
+
 /// 从 CSV 文件导入链接
-pub fn import_from_csv<P: AsRef<Path>>(path: P) -> Result<Vec<ShortLink>, ShortlinkerError> {
-    let file = File::open(path.as_ref())
+///
+/// `delimiter` 为 `None` 时自动嗅探，编码与表头格式见 [`csv_dialect`](super::csv_dialect)；
+/// `transform` 为可选的字段级转换规则，见 [`csv_transform`](super::csv_transform)。
+pub fn import_from_csv<P: AsRef<Path>>(
+    path: P,
+    delimiter: Option<u8>,
+    transform: Option<&ImportTransform>,
+) -> Result<CsvImport, ShortlinkerError> {
+    let bytes = std::fs::read(path.as_ref())
         .map_err(|e| ShortlinkerError::file_operation(format!("Failed to open file: {}", e)))?;
-    let reader = BufReader::new(file);
-    let mut csv_reader = ReaderBuilder::new()
-        .has_headers(true)
-        .flexible(true)
-        .trim(csv::Trim::All)
-        .from_reader(reader);
+    import_from_csv_bytes(&bytes, delimiter, transform)
+}
+
+/// 从内存中的 CSV 内容导入链接（如从对象存储下载的文件）
+pub fn import_from_csv_bytes(
+    bytes: &[u8],
+    delimiter: Option<u8>,
+    transform: Option<&ImportTransform>,
+) -> Result<CsvImport, ShortlinkerError> {
+    let decoded = DecodedCsv::decode(bytes, delimiter)?;
 
     let mut links = Vec::new();
     let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut preview = Vec::new();
 
-    for (row_idx, result) in csv_reader.deserialize::<CsvLinkRow>().enumerate() {
-        let row_num = row_idx + 2; // CSV 行号（1-based，跳过 header）
-
+    for (row_num, result) in read_link_rows(&decoded, transform) {
         match result {
             Ok(row) => {
+                if preview.len() < PREVIEW_ROWS {
+                    preview.push((row_num, row.clone()));
+                }
                 if row.code.is_empty() {
                     errors.push(format!("Row {}: Empty code", row_num));
                     continue;
                 }
-                match row.into_short_link() {
-                    Ok(link) => links.push(link),
+                match row.into_short_link_checked() {
+                    Ok((link, row_warnings)) => {
+                        links.push(link);
+                        warnings.extend(
+                            row_warnings
+                                .into_iter()
+                                .map(|w| format!("Row {}: {}", row_num, w.message)),
+                        );
+                    }
                     Err(e) => errors.push(format!("Row {}: {}", row_num, e)),
                 }
             }
-            Err(e) => {
-                errors.push(format!("Row {}: CSV parse error: {}", row_num, e));
-            }
+            Err(e) => errors.push(format!("Row {}: {}", row_num, e)),
         }
     }
 
@@ -146,7 +224,57 @@ pub fn import_from_csv<P: AsRef<Path>>(path: P) -> Result<Vec<ShortLink>, Shortl
         tracing::warn!("CSV import warnings:\n{}", errors.join("\n"));
     }
 
-    Ok(links)
+    Ok(CsvImport {
+        links,
+        dialect: decoded.dialect,
+        warnings,
+        errors,
+        preview,
+    })
+}
+
+/// 逐行读取导入 CSV，返回 (原文件行号, 行)
+///
+/// 给出 `transform` 时先按规则映射列名、转换取值，再反序列化为 [`CsvLinkRow`]；
+/// 解析或转换失败的行返回错误说明，由调用方决定如何报告。CLI 与 Admin API 导入共用。
+pub fn read_link_rows(
+    decoded: &DecodedCsv,
+    transform: Option<&ImportTransform>,
+) -> Vec<(usize, Result<CsvLinkRow, String>)> {
+    let mut reader = decoded.reader();
+    let parse_error = |e: csv::Error| format!("CSV parse error: {}", e);
+
+    let Some(transform) = transform else {
+        return reader
+            .deserialize::<CsvLinkRow>()
+            .enumerate()
+            .map(|(i, row)| (decoded.row_number(i), row.map_err(parse_error)))
+            .collect();
+    };
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => return vec![(decoded.header_line, Err(parse_error(e)))],
+    };
+    let rows = transform.prepare(&headers);
+    reader
+        .records()
+        .enumerate()
+        .map(|(i, record)| {
+            let row = record
+                .map_err(parse_error)
+                .and_then(|record| {
+                    rows.apply(&record)
+                        .map_err(|e| format!("Transform error: {}", e))
+                })
+                .and_then(|record| {
+                    record
+                        .deserialize::<CsvLinkRow>(Some(rows.headers()))
+                        .map_err(parse_error)
+                });
+            (decoded.row_number(i), row)
+        })
+        .collect()
 }
 
 /// 生成默认导出文件名（带时间戳）
@@ -160,6 +288,7 @@ pub fn generate_export_filename() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::AnalyticsLevel;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -172,6 +301,9 @@ mod tests {
             expires_at: None,
             password: None,
             click: 42,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         };
 
         let row = CsvLinkRow::from(&link);
@@ -189,6 +321,9 @@ mod tests {
             expires_at: None,
             password: None,
             click: 10,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Aggregate,
+            extras: Some(r#"{"owner":"growth","tags":["a","b"]}"#.to_string()),
         };
 
         let temp_file = NamedTempFile::new().unwrap();
@@ -198,11 +333,108 @@ mod tests {
         export_to_csv(&[&link], path).unwrap();
 
         // Import
-        let imported = import_from_csv(path).unwrap();
+        let imported = import_from_csv(path, None, None).unwrap().links;
         assert_eq!(imported.len(), 1);
         assert_eq!(imported[0].code, "roundtrip");
         assert_eq!(imported[0].target, "https://example.com");
         assert_eq!(imported[0].click, 10);
+        assert_eq!(imported[0].analytics_level, AnalyticsLevel::Aggregate);
+        assert_eq!(imported[0].extras, link.extras);
+        assert_eq!(imported[0].created_via, CreatedVia::Import);
+    }
+
+    #[test]
+    fn test_export_header() {
+        let link = ShortLink {
+            code: "hdr".to_string(),
+            target: "https://example.com".to_string(),
+            created_at: "2026-01-02T03:04:05.500Z".parse().unwrap(),
+            expires_at: None,
+            password: None,
+            click: 3,
+            created_via: CreatedVia::Cli,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        };
+
+        let mut out = Vec::new();
+        write_csv(&[&link], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("# schema_version={}", LINK_SCHEMA_VERSION)
+        );
+        assert_eq!(
+            lines[1],
+            "code,target,created_at,expires_at,password,click_count,created_via,analytics_level,extras"
+        );
+        assert_eq!(
+            lines[2],
+            "hdr,https://example.com,2026-01-02T03:04:05.500Z,,,3,cli,inherit,"
+        );
+    }
+
+    #[test]
+    fn test_import_previous_format() {
+        // schema_version 之前的导出：无元数据行、无 created_via / analytics_level 列、+00:00 偏移
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "code,target,created_at,expires_at,password,click_count"
+        )
+        .unwrap();
+        writeln!(
+            temp_file,
+            "old,https://example.com,2025-01-01T00:00:00+00:00,2030-01-01T00:00:00+00:00,,5"
+        )
+        .unwrap();
+
+        let imported = import_from_csv(temp_file.path(), None, None).unwrap().links;
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].click, 5);
+        assert_eq!(
+            format_timestamp(&imported[0].expires_at.unwrap()),
+            "2030-01-01T00:00:00Z"
+        );
+        assert_eq!(imported[0].analytics_level, AnalyticsLevel::Inherit);
+    }
+
+    #[test]
+    fn test_import_past_expiry_warns() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "code,target,created_at,expires_at").unwrap();
+        writeln!(
+            temp_file,
+            "old,https://example.com,2019-01-01T00:00:00Z,2020-01-01T00:00:00Z"
+        )
+        .unwrap();
+        writeln!(
+            temp_file,
+            "open,https://example.com,2019-01-01T00:00:00Z,never"
+        )
+        .unwrap();
+
+        let imported = import_from_csv(temp_file.path(), None, None).unwrap();
+        assert_eq!(imported.links.len(), 2);
+        assert_eq!(imported.links[1].expires_at, None);
+        assert_eq!(imported.warnings.len(), 1);
+        assert!(
+            imported.warnings[0].starts_with("Row 2: "),
+            "{:?}",
+            imported.warnings
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_newer_schema() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "# schema_version={}", LINK_SCHEMA_VERSION + 1).unwrap();
+        writeln!(temp_file, "code,target,created_at").unwrap();
+        writeln!(temp_file, "new,https://example.com,2025-01-01T00:00:00Z").unwrap();
+
+        let err = import_from_csv(temp_file.path(), None, None).unwrap_err();
+        assert!(err.to_string().contains("schema_version"), "{}", err);
     }
 
     #[test]
@@ -219,8 +451,8 @@ mod tests {
         )
         .unwrap();
 
-        let result = import_from_csv(temp_file.path());
-        assert!(result.is_err() || result.unwrap().is_empty());
+        let result = import_from_csv(temp_file.path(), None, None);
+        assert!(result.is_err() || result.unwrap().links.is_empty());
     }
 
     #[test]
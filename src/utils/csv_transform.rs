@@ -0,0 +1,507 @@
+//! CSV 导入的字段级转换规则
+//!
+//! 其他系统导出的 CSV 列名与取值格式和我们不同（url 列叫 `destination`、
+//! 过期时间是 Unix 时间戳、code 带完整域名前缀），转换配置在进入现有校验管线前
+//! 逐行清洗，省去一次性脚本。只提供几种受限的内置转换器，不执行任意脚本：
+//!
+//! ```toml
+//! # 列名映射：源列名 → 导入字段（源列名按表头同样的规则宽松匹配）
+//! [columns]
+//! destination = "target"
+//! expiry = "expires_at"
+//!
+//! # 按顺序执行，field 为映射后的导入字段名
+//! [[rules]]
+//! field = "code"
+//! op = "strip_prefix"
+//! prefix = "https://sho.rt/"
+//!
+//! [[rules]]
+//! field = "expires_at"
+//! op = "timestamp"
+//! format = "unix"
+//! ```
+//!
+//! CLI `import --transform` 读取文件，Admin API 导入的 multipart `transform` 字段内联。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use csv::StringRecord;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::errors::ShortlinkerError;
+use crate::storage::format_timestamp;
+use crate::utils::csv_dialect::normalize_header;
+
+/// 可作为映射目标与规则字段的导入列（与 [`CsvLinkRow`](super::csv_handler::CsvLinkRow) 一致）
+pub const IMPORT_FIELDS: [&str; 8] = [
+    "code",
+    "target",
+    "created_at",
+    "expires_at",
+    "password",
+    "click_count",
+    "analytics_level",
+    "extras",
+];
+
+/// 转换配置文件结构
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformFile {
+    #[serde(default)]
+    columns: BTreeMap<String, String>,
+    #[serde(default)]
+    rules: Vec<RuleFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    field: String,
+    #[serde(flatten)]
+    op: OpFile,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum OpFile {
+    StripPrefix { prefix: String },
+    Timestamp { format: String },
+    Default { value: String },
+    Lowercase,
+    Uppercase,
+    Replace { pattern: String, with: String },
+}
+
+/// 时间戳格式声明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Unix 秒
+    Unix,
+    /// Unix 毫秒
+    UnixMs,
+    /// chrono strftime 格式；不含时区的时间按 UTC 处理
+    Pattern(String),
+}
+
+impl TimestampFormat {
+    fn parse(format: &str) -> Self {
+        match format {
+            "unix" => Self::Unix,
+            "unix_ms" => Self::UnixMs,
+            other => Self::Pattern(other.to_string()),
+        }
+    }
+
+    /// 转为导出格式（RFC 3339 UTC）
+    fn convert(&self, value: &str) -> Result<String, String> {
+        let parsed = match self {
+            Self::Unix => value
+                .parse::<i64>()
+                .ok()
+                .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0)),
+            Self::UnixMs => value
+                .parse::<i64>()
+                .ok()
+                .and_then(DateTime::<Utc>::from_timestamp_millis),
+            Self::Pattern(pattern) => DateTime::parse_from_str(value, pattern)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NaiveDateTime::parse_from_str(value, pattern)
+                        .ok()
+                        .map(|dt| dt.and_utc())
+                })
+                .or_else(|| {
+                    NaiveDate::parse_from_str(value, pattern)
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|dt| dt.and_utc())
+                }),
+        };
+        parsed
+            .map(|dt| format_timestamp(&dt))
+            .ok_or_else(|| format!("'{}' does not match timestamp format {}", value, self))
+    }
+}
+
+impl std::fmt::Display for TimestampFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unix => f.write_str("unix"),
+            Self::UnixMs => f.write_str("unix_ms"),
+            Self::Pattern(pattern) => write!(f, "'{}'", pattern),
+        }
+    }
+}
+
+/// 内置转换器
+///
+/// 除 [`Default`](Self::Default) 外，空值原样保留（空值的语义由后续校验决定，
+/// 如 `expires_at` 为空表示永不过期）。
+#[derive(Debug, Clone)]
+pub enum Transformer {
+    /// 去掉前缀，不匹配时原样保留
+    StripPrefix(String),
+    /// 按声明的格式解析时间，输出 RFC 3339
+    Timestamp(TimestampFormat),
+    /// 值为空或列不存在时填充
+    Default(String),
+    Lowercase,
+    Uppercase,
+    /// 正则替换全部匹配，`with` 中可用 `$1` 引用分组
+    Replace {
+        pattern: Regex,
+        with: String,
+    },
+}
+
+impl Transformer {
+    fn from_file(op: OpFile) -> Result<Self, String> {
+        Ok(match op {
+            OpFile::StripPrefix { prefix } => Self::StripPrefix(prefix),
+            OpFile::Timestamp { format } => Self::Timestamp(TimestampFormat::parse(&format)),
+            OpFile::Default { value } => Self::Default(value),
+            OpFile::Lowercase => Self::Lowercase,
+            OpFile::Uppercase => Self::Uppercase,
+            OpFile::Replace { pattern, with } => Self::Replace {
+                pattern: Regex::new(&pattern)
+                    .map_err(|e| format!("invalid regex '{}': {}", pattern, e))?,
+                with,
+            },
+        })
+    }
+
+    /// 对单个值应用转换
+    pub fn apply(&self, value: &str) -> Result<String, String> {
+        if value.is_empty() {
+            return Ok(match self {
+                Self::Default(default) => default.clone(),
+                _ => String::new(),
+            });
+        }
+        Ok(match self {
+            Self::StripPrefix(prefix) => value
+                .strip_prefix(prefix.as_str())
+                .unwrap_or(value)
+                .to_string(),
+            Self::Timestamp(format) => format.convert(value)?,
+            Self::Default(_) => value.to_string(),
+            Self::Lowercase => value.to_lowercase(),
+            Self::Uppercase => value.to_uppercase(),
+            Self::Replace { pattern, with } => {
+                pattern.replace_all(value, with.as_str()).into_owned()
+            }
+        })
+    }
+}
+
+/// 作用于某个导入字段的转换规则
+#[derive(Debug, Clone)]
+pub struct FieldRule {
+    pub field: String,
+    pub transformer: Transformer,
+}
+
+/// 解析并校验过的转换配置
+#[derive(Debug, Clone, Default)]
+pub struct ImportTransform {
+    /// 规范化后的源列名 → 导入字段
+    columns: BTreeMap<String, String>,
+    rules: Vec<FieldRule>,
+}
+
+impl ImportTransform {
+    /// 解析 TOML 转换配置；映射目标与规则字段必须是 [`IMPORT_FIELDS`] 之一
+    pub fn from_toml(text: &str) -> Result<Self, ShortlinkerError> {
+        let invalid = |msg: String| {
+            ShortlinkerError::validation(format!("Invalid import transform: {}", msg))
+        };
+        let file: TransformFile = toml::from_str(text).map_err(|e| invalid(e.to_string()))?;
+
+        let mut columns = BTreeMap::new();
+        for (source, field) in file.columns {
+            check_field(&field).map_err(&invalid)?;
+            columns.insert(normalize_header(&source), field);
+        }
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                check_field(&rule.field)
+                    .and_then(|_| Transformer::from_file(rule.op))
+                    .map(|transformer| FieldRule {
+                        field: rule.field,
+                        transformer,
+                    })
+                    .map_err(|msg| invalid(format!("rules[{}]: {}", i, msg)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { columns, rules })
+    }
+
+    /// 读取转换配置文件
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, ShortlinkerError> {
+        let text = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            ShortlinkerError::file_operation(format!(
+                "Failed to read transform file '{}': {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        Self::from_toml(&text)
+    }
+
+    pub fn rules(&self) -> &[FieldRule] {
+        &self.rules
+    }
+
+    /// 按表头准备逐行转换
+    ///
+    /// 映射后的表头保留未映射的列；规则涉及但文件中没有的字段追加为新列（值为空，
+    /// 交给 `default` 等规则填充）。
+    pub fn prepare(&self, headers: &StringRecord) -> RowTransformer<'_> {
+        let mut output: Vec<String> = headers
+            .iter()
+            .map(|h| {
+                self.columns
+                    .get(h)
+                    .cloned()
+                    .unwrap_or_else(|| h.to_string())
+            })
+            .collect();
+        let source_len = output.len();
+        for rule in &self.rules {
+            if !output.contains(&rule.field) {
+                output.push(rule.field.clone());
+            }
+        }
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let index = output.iter().position(|h| *h == rule.field).unwrap_or(0);
+                (index, rule)
+            })
+            .collect();
+        RowTransformer {
+            headers: output.iter().collect(),
+            source_len,
+            rules,
+        }
+    }
+}
+
+fn check_field(field: &str) -> Result<(), String> {
+    if IMPORT_FIELDS.contains(&field) {
+        Ok(())
+    } else {
+        Err(format!(
+            "unknown field '{}', expected one of: {}",
+            field,
+            IMPORT_FIELDS.join(", ")
+        ))
+    }
+}
+
+/// 绑定到具体表头的逐行转换器
+pub struct RowTransformer<'a> {
+    headers: StringRecord,
+    source_len: usize,
+    /// (列下标, 规则)
+    rules: Vec<(usize, &'a FieldRule)>,
+}
+
+impl RowTransformer<'_> {
+    /// 映射后的表头，用于反序列化转换后的行
+    pub fn headers(&self) -> &StringRecord {
+        &self.headers
+    }
+
+    /// 转换一行；失败时返回 `field: 原因`
+    pub fn apply(&self, record: &StringRecord) -> Result<StringRecord, String> {
+        let mut values: Vec<String> = (0..self.headers.len())
+            .map(|i| {
+                if i < self.source_len {
+                    record.get(i).unwrap_or("").to_string()
+                } else {
+                    String::new()
+                }
+            })
+            .collect();
+        for (index, rule) in &self.rules {
+            values[*index] = rule
+                .transformer
+                .apply(&values[*index])
+                .map_err(|msg| format!("{}: {}", rule.field, msg))?;
+        }
+        Ok(values.iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(transformer: Transformer, value: &str) -> Result<String, String> {
+        transformer.apply(value)
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let t = || Transformer::StripPrefix("https://sho.rt/".into());
+        assert_eq!(apply(t(), "https://sho.rt/abc").unwrap(), "abc");
+        assert_eq!(apply(t(), "abc").unwrap(), "abc");
+        assert_eq!(apply(t(), "").unwrap(), "");
+    }
+
+    #[test]
+    fn test_timestamp_unix() {
+        let t = || Transformer::Timestamp(TimestampFormat::Unix);
+        assert_eq!(apply(t(), "1735689600").unwrap(), "2025-01-01T00:00:00Z");
+        assert!(apply(t(), "tomorrow").is_err());
+        // 空值不转换，仍表示永不过期
+        assert_eq!(apply(t(), "").unwrap(), "");
+
+        let ms = Transformer::Timestamp(TimestampFormat::UnixMs);
+        assert_eq!(
+            apply(ms, "1735689600500").unwrap(),
+            "2025-01-01T00:00:00.500Z"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_pattern() {
+        let t = |f: &str| Transformer::Timestamp(TimestampFormat::parse(f));
+        assert_eq!(
+            apply(t("%d/%m/%Y %H:%M"), "31/12/2025 08:30").unwrap(),
+            "2025-12-31T08:30:00Z"
+        );
+        assert_eq!(
+            apply(t("%d.%m.%Y"), "01.02.2026").unwrap(),
+            "2026-02-01T00:00:00Z"
+        );
+        assert_eq!(
+            apply(t("%Y-%m-%d %H:%M:%S %z"), "2025-06-01 10:00:00 +0800").unwrap(),
+            "2025-06-01T02:00:00Z"
+        );
+        let err = apply(t("%d/%m/%Y"), "2025-12-31").unwrap_err();
+        assert!(err.contains("'%d/%m/%Y'"), "{}", err);
+    }
+
+    #[test]
+    fn test_default_fills_empty_only() {
+        let t = || Transformer::Default("2024-01-01T00:00:00Z".into());
+        assert_eq!(apply(t(), "").unwrap(), "2024-01-01T00:00:00Z");
+        assert_eq!(
+            apply(t(), "2025-01-01T00:00:00Z").unwrap(),
+            "2025-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(apply(Transformer::Lowercase, "PROMO-A").unwrap(), "promo-a");
+        assert_eq!(apply(Transformer::Uppercase, "promo-a").unwrap(), "PROMO-A");
+    }
+
+    #[test]
+    fn test_replace() {
+        let t = Transformer::Replace {
+            pattern: Regex::new(r"^http://(.+)$").unwrap(),
+            with: "https://$1".into(),
+        };
+        assert_eq!(
+            apply(t.clone(), "http://example.com/a").unwrap(),
+            "https://example.com/a"
+        );
+        assert_eq!(apply(t, "ftp://example.com").unwrap(), "ftp://example.com");
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_fields_and_bad_regex() {
+        let err = ImportTransform::from_toml("[columns]\nurl = \"destination\"\n").unwrap_err();
+        assert!(err.message().contains("unknown field 'destination'"));
+
+        let err = ImportTransform::from_toml(
+            "[[rules]]\nfield = \"target\"\nop = \"replace\"\npattern = \"(\"\nwith = \"\"\n",
+        )
+        .unwrap_err();
+        assert!(err.message().contains("rules[0]"), "{}", err.message());
+
+        let err =
+            ImportTransform::from_toml("[[rules]]\nfield = \"code\"\nop = \"eval\"\n").unwrap_err();
+        assert!(err.message().contains("Invalid import transform"));
+    }
+
+    #[test]
+    fn test_row_transform_maps_columns_and_applies_rules_in_order() {
+        let transform = ImportTransform::from_toml(
+            r#"
+            [columns]
+            "Short URL" = "code"
+            destination = "target"
+            expiry = "expires_at"
+
+            [[rules]]
+            field = "code"
+            op = "strip_prefix"
+            prefix = "https://sho.rt/"
+
+            [[rules]]
+            field = "code"
+            op = "lowercase"
+
+            [[rules]]
+            field = "expires_at"
+            op = "timestamp"
+            format = "unix"
+
+            [[rules]]
+            field = "created_at"
+            op = "default"
+            value = "2024-01-01T00:00:00Z"
+            "#,
+        )
+        .unwrap();
+
+        let headers = StringRecord::from(vec!["short_url", "destination", "expiry", "note"]);
+        let rows = transform.prepare(&headers);
+        assert_eq!(
+            rows.headers(),
+            &StringRecord::from(vec!["code", "target", "expires_at", "note", "created_at"])
+        );
+
+        let out = rows
+            .apply(&StringRecord::from(vec![
+                "https://sho.rt/PROMO",
+                "https://example.com",
+                "1735689600",
+                "x",
+            ]))
+            .unwrap();
+        assert_eq!(
+            out,
+            StringRecord::from(vec![
+                "promo",
+                "https://example.com",
+                "2025-01-01T00:00:00Z",
+                "x",
+                "2024-01-01T00:00:00Z",
+            ])
+        );
+
+        let err = rows
+            .apply(&StringRecord::from(vec![
+                "a",
+                "https://example.com",
+                "soon",
+                "",
+            ]))
+            .unwrap_err();
+        assert!(err.starts_with("expires_at:"), "{}", err);
+    }
+}
@@ -0,0 +1,47 @@
+//! 客户端 IP 解析
+//!
+//! redirect、请求拦截中间件、登录限流与 base URL 推断共用同一套可信代理规则：
+//! 按 `api.trusted_proxies` 处理转发头；Unix Socket 监听时对端视为本机并信任本机网段。
+
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::http::header::HeaderMap;
+
+use crate::config::{keys, try_get_runtime_config};
+
+/// 可信代理网段（`api.trusted_proxies`，Unix Socket 监听时追加本机网段）
+pub fn trusted_proxies() -> Vec<String> {
+    let mut trusted = try_get_runtime_config()
+        .map(|rt| rt.get_json_or(keys::API_TRUSTED_PROXIES, Vec::<String>::new()))
+        .unwrap_or_default();
+    #[cfg(unix)]
+    if crate::config::get_config().server.unix_socket.is_some() {
+        trusted.extend(["127.0.0.0/8".to_string(), "::1/128".to_string()]);
+    }
+    trusted
+}
+
+/// 直连对端 IP；Unix Socket 连接没有对端地址，视为本机
+pub fn peer_ip(peer_addr: Option<SocketAddr>) -> Option<IpAddr> {
+    let peer = peer_addr.map(|address| address.ip());
+    #[cfg(unix)]
+    let peer = peer.or_else(|| {
+        crate::config::get_config()
+            .server
+            .unix_socket
+            .as_ref()
+            .map(|_| IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
+    });
+    peer
+}
+
+/// 解析客户端 IP：对端是可信代理时取转发头中的真实地址，否则为对端地址
+pub fn client_ip(peer_addr: Option<SocketAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+    peer_ip(peer_addr).map(|peer| {
+        aster_forge_actix_middleware::client_ip::real_ip_from_headers(
+            headers,
+            peer,
+            &trusted_proxies(),
+        )
+    })
+}
@@ -0,0 +1,67 @@
+//! 热路径日志采样
+//!
+//! # 热路径日志约定
+//!
+//! redirect 及其同步调用链（Firewall、缓存查询、点击计数）每个请求都会执行，
+//! 这里的日志调用按以下约定书写，新增或修改前先对照：
+//!
+//! 1. 用字段语法（`debug!(code, "cache miss")`），不要写 `format!` 风格的消息拼接；
+//!    `to_string()`、序列化等昂贵表达式只放在宏参数里——宏参数只在级别开启时求值，
+//!    提前算好再传给宏的值在 info 级别下同样要付出代价。
+//! 2. 每个请求都必然经过的分支不放日志，需要的观测走 `MetricsRecorder` 指标。
+//! 3. 可能被外部流量放大的日志（404、丢弃事件、插件失败）用 [`LogSampler`] 采样；
+//!    debug / trace 级别先用 `tracing::enabled!` 判断，关闭时连采样计数器也不碰。
+//! 4. 必须在宏外准备数据时，同样先用 `tracing::enabled!` 判断
+//!    （例如 `storage::backend::statement_log`）。
+//!
+//! 改动后用 `cargo bench --bench hot_path_logging` 对比 info 级别下的开销。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 按固定间隔放行日志：第 1 次、第 `every + 1` 次……输出，其余跳过
+///
+/// 计数器只做一次 `Relaxed` 自增，可放在 `static` 中跨线程共享。
+#[derive(Debug)]
+pub struct LogSampler {
+    every: u64,
+    seen: AtomicU64,
+}
+
+impl LogSampler {
+    /// `every` 为 0 时按 1 处理（每次都放行）
+    pub const fn new(every: u64) -> Self {
+        Self {
+            every: if every == 0 { 1 } else { every },
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// 记录一次事件；返回 `Some(suppressed)` 时应输出日志，`suppressed` 为上次
+    /// 输出以来跳过的事件数，建议作为日志字段一并输出
+    #[inline]
+    pub fn sample(&self) -> Option<u64> {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        if seen % self.every != 0 {
+            return None;
+        }
+        Some(if seen == 0 { 0 } else { self.every - 1 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_event_and_every_nth_pass() {
+        let sampler = LogSampler::new(3);
+        let passed: Vec<Option<u64>> = (0..7).map(|_| sampler.sample()).collect();
+        assert_eq!(passed, [Some(0), None, None, Some(2), None, None, Some(2)]);
+    }
+
+    #[test]
+    fn test_zero_interval_passes_everything() {
+        let sampler = LogSampler::new(0);
+        assert!((0..5).all(|_| sampler.sample() == Some(0)));
+    }
+}
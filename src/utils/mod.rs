@@ -1,8 +1,20 @@
+pub mod admin_token;
+pub mod base_url;
+pub mod cidr;
+pub mod circuit_breaker;
+pub mod csv_dialect;
 pub mod csv_handler;
+pub mod csv_transform;
+pub mod ip;
+pub mod log_sample;
 pub mod password;
+pub mod redirect_body;
+pub mod s3;
+pub mod sampling;
+pub mod shard;
 pub mod time_parser;
 
-pub use time_parser::TimeParser;
+pub use time_parser::{DateOnlyBoundary, ExpiryInputRules, InputTimeZone, TimeParser};
 
 /// 短码最大长度
 pub const MAX_SHORT_CODE_LEN: usize = 128;
@@ -26,12 +38,18 @@ pub fn is_valid_short_code(code: &str) -> bool {
 /// 必须从 RuntimeConfig 读取，因为配置可能在数据库中被修改。
 /// RuntimeConfig 未初始化时使用默认值（仅启动早期）。
 pub fn get_reserved_prefixes() -> Vec<String> {
+    use crate::api::constants::BADGE_PREFIX;
     use crate::config::{keys, try_get_runtime_config};
 
     let rt = match try_get_runtime_config() {
         Some(rt) => rt,
         None => {
-            return vec!["admin".into(), "health".into(), "panel".into()];
+            return vec![
+                "admin".into(),
+                "health".into(),
+                "panel".into(),
+                BADGE_PREFIX.trim_start_matches('/').into(),
+            ];
         }
     };
 
@@ -39,6 +57,7 @@ pub fn get_reserved_prefixes() -> Vec<String> {
         rt.get_or(keys::ROUTES_ADMIN_PREFIX, "/admin"),
         rt.get_or(keys::ROUTES_HEALTH_PREFIX, "/health"),
         rt.get_or(keys::ROUTES_FRONTEND_PREFIX, "/panel"),
+        BADGE_PREFIX.to_string(),
     ]
     .into_iter()
     .map(|p| p.trim_start_matches('/').to_string())
@@ -0,0 +1,93 @@
+//! 重定向响应的 HTML 降级 body
+//!
+//! 部分企业代理 / 安全网关会吞掉 3xx 的 `Location` 头，浏览器只能看到空白页。
+//! 响应 body 带一个 meta-refresh 和可点击链接作为兜底；正常客户端直接跟随
+//! `Location`，不会渲染 body。
+//!
+//! 热路径上每次重定向都会调用，这里只做一次预分配的字符串拼接，不引入模板引擎。
+//! 模板本身固定约 110 字节，body 总长为该开销加两倍的转义后 URL。
+
+const PREFIX: &str =
+    r#"<!DOCTYPE html><meta charset="utf-8"><meta http-equiv="refresh" content="0;url="#;
+const MIDDLE: &str = r#""><a href=""#;
+const SUFFIX: &str = r#"">Redirecting...</a>"#;
+
+/// 模板的固定开销（不含 URL）
+pub const FALLBACK_BODY_OVERHEAD: usize = PREFIX.len() + MIDDLE.len() + SUFFIX.len();
+
+/// 生成降级 body，`target` 做 HTML 属性转义
+pub fn fallback_body(target: &str) -> String {
+    let escaped_len = escaped_len(target);
+    let mut body = String::with_capacity(FALLBACK_BODY_OVERHEAD + 2 * escaped_len);
+    body.push_str(PREFIX);
+    push_escaped(&mut body, target);
+    body.push_str(MIDDLE);
+    push_escaped(&mut body, target);
+    body.push_str(SUFFIX);
+    body
+}
+
+#[inline]
+fn escape(b: u8) -> Option<&'static str> {
+    match b {
+        b'&' => Some("&amp;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        b'"' => Some("&quot;"),
+        b'\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+fn escaped_len(s: &str) -> usize {
+    s.bytes().map(|b| escape(b).map_or(1, str::len)).sum()
+}
+
+/// 按连续未转义片段整体追加，避免逐字符 push
+fn push_escaped(out: &mut String, s: &str) {
+    let mut start = 0;
+    for (i, b) in s.bytes().enumerate() {
+        if let Some(entity) = escape(b) {
+            out.push_str(&s[start..i]);
+            out.push_str(entity);
+            start = i + 1;
+        }
+    }
+    out.push_str(&s[start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_body() {
+        let body = fallback_body("https://example.com/a");
+        assert_eq!(
+            body,
+            r#"<!DOCTYPE html><meta charset="utf-8"><meta http-equiv="refresh" content="0;url=https://example.com/a"><a href="https://example.com/a">Redirecting...</a>"#
+        );
+        assert!(FALLBACK_BODY_OVERHEAD < 300);
+    }
+
+    #[test]
+    fn test_escaping() {
+        let body = fallback_body(r#"https://example.com/?a=1&b="><script>'x'</script>"#);
+        assert!(!body.contains("<script>"));
+        assert!(body.contains(
+            "https://example.com/?a=1&amp;b=&quot;&gt;&lt;script&gt;&#39;x&#39;&lt;/script&gt;"
+        ));
+    }
+
+    #[test]
+    fn test_preallocated_size() {
+        for target in [
+            "https://example.com",
+            "https://example.com/?q=<&>\"'",
+            "https://例子.com/路径",
+        ] {
+            let body = fallback_body(target);
+            assert_eq!(body.len(), FALLBACK_BODY_OVERHEAD + 2 * escaped_len(target));
+        }
+    }
+}
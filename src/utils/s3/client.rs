@@ -0,0 +1,428 @@
+//! 基于 rust-s3 的上传与下载
+//!
+//! 上传在独立线程上运行自己的 tokio runtime：[`S3Writer`] 是同步 `Write`，
+//! 可以直接交给 CSV / Parquet writer，写满一个分片就通过有界通道交给上传线程，
+//! 通道满时阻塞写入方，内存占用约为 [`PART_SIZE`] × (`QUEUED_PARTS` + 2)。
+//! CLI 使用单线程 runtime，阻塞写入方不会卡住上传。
+//!
+//! 数据不足一个分片时用单次 PUT，否则走分片上传。完成后 HEAD 对象，
+//! 比对大小与 ETag（本地计算的 MD5），不一致即报错。
+
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::thread::JoinHandle;
+
+use ::s3::bucket::Bucket;
+use ::s3::creds::Credentials;
+use ::s3::error::S3Error;
+use ::s3::serde_types::Part;
+use ::s3::{Region, creds::error::CredentialsError};
+use md5::{Digest, Md5};
+use tracing::{debug, warn};
+
+use super::{S3Location, S3Settings, UploadReport, expected_etag};
+use crate::config::keys;
+use crate::errors::ShortlinkerError;
+
+/// 分片大小（S3 要求除最后一片外不小于 5 MiB）
+pub const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// 排队等待上传的分片数
+const QUEUED_PARTS: usize = 2;
+
+enum Message {
+    Part(Vec<u8>),
+    Commit,
+    Abort,
+}
+
+/// 流式写入对象存储的 writer
+///
+/// drop 时把剩余数据交给上传线程；之后调用 [`S3Upload::finish`] 提交。
+pub struct S3Writer {
+    buffer: Vec<u8>,
+    sender: SyncSender<Message>,
+}
+
+impl S3Writer {
+    fn send_buffer(&mut self) -> io::Result<()> {
+        let part = std::mem::replace(&mut self.buffer, Vec::with_capacity(PART_SIZE));
+        self.sender
+            .send(Message::Part(part))
+            .map_err(|_| io::Error::other("S3 upload failed, see the upload error for details"))
+    }
+}
+
+impl Write for S3Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(PART_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..n]);
+        if self.buffer.len() == PART_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // 分片必须凑满 PART_SIZE，flush 不提前发送
+        Ok(())
+    }
+}
+
+impl Drop for S3Writer {
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            let _ = self.send_buffer();
+        }
+    }
+}
+
+/// 上传任务的句柄
+pub struct S3Upload {
+    sender: SyncSender<Message>,
+    worker: JoinHandle<Result<UploadReport, ShortlinkerError>>,
+}
+
+impl S3Upload {
+    /// 提交上传并等待校验完成（需先 drop 对应的 [`S3Writer`]）
+    pub async fn finish(self) -> Result<UploadReport, ShortlinkerError> {
+        let _ = self.sender.send(Message::Commit);
+        join(self.worker).await
+    }
+
+    /// 放弃上传并清理已上传的分片
+    ///
+    /// 上传本身失败时返回该错误，便于调用方报告真正的原因。
+    pub async fn abort(self) -> Option<ShortlinkerError> {
+        let _ = self.sender.send(Message::Abort);
+        join(self.worker).await.err().filter(|e| !is_aborted(e))
+    }
+}
+
+async fn join(
+    worker: JoinHandle<Result<UploadReport, ShortlinkerError>>,
+) -> Result<UploadReport, ShortlinkerError> {
+    tokio::task::spawn_blocking(move || worker.join())
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_else(|| {
+            Err(ShortlinkerError::file_operation(
+                "S3 upload thread panicked",
+            ))
+        })
+}
+
+const ABORTED: &str = "S3 upload aborted";
+
+fn is_aborted(error: &ShortlinkerError) -> bool {
+    error.message() == ABORTED
+}
+
+/// 开始上传，返回写入端与句柄
+pub fn start_upload(
+    location: &S3Location,
+    settings: &S3Settings,
+    content_type: &str,
+) -> Result<(S3Writer, S3Upload), ShortlinkerError> {
+    let bucket = open_bucket(&location.bucket, settings)?;
+    let (sender, receiver) = sync_channel(QUEUED_PARTS);
+    let task = UploadTask {
+        bucket,
+        location: location.clone(),
+        endpoint: settings.endpoint_display(),
+        content_type: content_type.to_string(),
+    };
+    let worker = std::thread::Builder::new()
+        .name("s3-upload".to_string())
+        .spawn(move || task.run(receiver))
+        .map_err(|e| {
+            ShortlinkerError::file_operation(format!("Failed to start S3 upload thread: {}", e))
+        })?;
+
+    Ok((
+        S3Writer {
+            buffer: Vec::with_capacity(PART_SIZE),
+            sender: sender.clone(),
+        },
+        S3Upload { sender, worker },
+    ))
+}
+
+/// 下载整个对象
+pub async fn download(
+    location: &S3Location,
+    settings: &S3Settings,
+) -> Result<Vec<u8>, ShortlinkerError> {
+    let bucket = open_bucket(&location.bucket, settings)?;
+    let response = bucket
+        .get_object(&location.key)
+        .await
+        .map_err(|e| describe_error(e, location, &settings.endpoint_display()))?;
+    Ok(response.bytes().to_vec())
+}
+
+fn open_bucket(name: &str, settings: &S3Settings) -> Result<Box<Bucket>, ShortlinkerError> {
+    let region = match &settings.endpoint {
+        Some(endpoint) => Region::Custom {
+            region: settings.region.clone(),
+            endpoint: endpoint.clone(),
+        },
+        None => settings.region.parse().map_err(|e| {
+            ShortlinkerError::validation(format!(
+                "Invalid S3 region '{}' ({}): check {} or AWS_REGION",
+                settings.region,
+                e,
+                keys::STORAGE_S3_REGION
+            ))
+        })?,
+    };
+
+    let credentials = match (&settings.access_key, &settings.secret_key) {
+        (Some(access_key), Some(secret_key)) => {
+            Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+        }
+        (Some(_), None) | (None, Some(_)) => {
+            return Err(ShortlinkerError::validation(format!(
+                "{} and {} must be set together",
+                keys::STORAGE_S3_ACCESS_KEY,
+                keys::STORAGE_S3_SECRET_KEY
+            )));
+        }
+        (None, None) => Credentials::default(),
+    }
+    .map_err(credentials_error)?;
+
+    let bucket = Bucket::new(name, region, credentials).map_err(|e| {
+        ShortlinkerError::validation(format!("Invalid S3 bucket '{}': {}", name, e))
+    })?;
+    // 自建服务（MinIO 等）通常不支持虚拟主机风格的域名
+    Ok(match settings.endpoint {
+        Some(_) => bucket.with_path_style(),
+        None => bucket,
+    })
+}
+
+fn credentials_error(error: CredentialsError) -> ShortlinkerError {
+    ShortlinkerError::validation(format!(
+        "No usable S3 credentials ({}): set {} / {} or AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY",
+        error,
+        keys::STORAGE_S3_ACCESS_KEY,
+        keys::STORAGE_S3_SECRET_KEY
+    ))
+}
+
+/// 把 S3 错误转换为带处理建议的报错
+fn describe_error(error: S3Error, location: &S3Location, endpoint: &str) -> ShortlinkerError {
+    let message = match error {
+        S3Error::HttpFailWithBody(403, _) => format!(
+            "Access to {} denied: check that the S3 credentials are correct and allow reading and writing this bucket",
+            location
+        ),
+        S3Error::HttpFailWithBody(404, body) if body.contains("NoSuchBucket") => format!(
+            "Bucket '{}' does not exist at {}",
+            location.bucket, endpoint
+        ),
+        S3Error::HttpFailWithBody(404, _) => format!("{} not found", location),
+        S3Error::HttpFailWithBody(301 | 307, _) => format!(
+            "Bucket '{}' is in a different region: set {} to the bucket's region",
+            location.bucket,
+            keys::STORAGE_S3_REGION
+        ),
+        S3Error::HttpFailWithBody(400, body) if body.contains("AuthorizationHeaderMalformed") => {
+            format!(
+                "Region mismatch for bucket '{}': set {} to the bucket's region",
+                location.bucket,
+                keys::STORAGE_S3_REGION
+            )
+        }
+        S3Error::HttpFailWithBody(status, body) => {
+            format!("S3 request for {} failed ({}): {}", location, status, body)
+        }
+        S3Error::Credentials(e) => return credentials_error(e),
+        S3Error::Reqwest(e) => format!(
+            "Cannot reach S3 at {} ({}): check {} and the network",
+            endpoint,
+            e,
+            keys::STORAGE_S3_ENDPOINT
+        ),
+        other => format!("S3 request for {} failed: {}", location, other),
+    };
+    ShortlinkerError::file_operation(message)
+}
+
+/// 已开始的分片上传
+struct Multipart {
+    upload_id: String,
+    parts: Vec<Part>,
+}
+
+struct UploadTask {
+    bucket: Box<Bucket>,
+    location: S3Location,
+    endpoint: String,
+    content_type: String,
+}
+
+impl UploadTask {
+    fn run(self, receiver: Receiver<Message>) -> Result<UploadReport, ShortlinkerError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                ShortlinkerError::file_operation(format!("Failed to start S3 runtime: {}", e))
+            })?;
+
+        let mut multipart = None;
+        let result = runtime.block_on(self.upload(receiver, &mut multipart));
+        if result.is_err()
+            && let Some(multipart) = multipart
+            && let Err(e) = runtime.block_on(
+                self.bucket
+                    .abort_upload(&self.location.key, &multipart.upload_id),
+            )
+        {
+            warn!(
+                "Failed to abort multipart upload {} for {}: {}",
+                multipart.upload_id, self.location, e
+            );
+        }
+        result
+    }
+
+    async fn upload(
+        &self,
+        receiver: Receiver<Message>,
+        multipart: &mut Option<Multipart>,
+    ) -> Result<UploadReport, ShortlinkerError> {
+        let key = &self.location.key;
+        let mut digests = Vec::new();
+        let mut bytes = 0u64;
+        // 始终压住最新的一片：结束时才知道它是不是最后一片（或唯一一片）
+        let mut pending: Option<Vec<u8>> = None;
+
+        loop {
+            // 独立线程上的 runtime 只跑这一个任务，阻塞接收不影响其他任务
+            match receiver.recv() {
+                Ok(Message::Part(part)) => {
+                    bytes += part.len() as u64;
+                    digests.push(Md5::digest(&part).into());
+                    if let Some(previous) = pending.replace(part) {
+                        self.upload_part(previous, multipart).await?;
+                    }
+                }
+                Ok(Message::Commit) => break,
+                Ok(Message::Abort) | Err(_) => {
+                    return Err(ShortlinkerError::file_operation(ABORTED));
+                }
+            }
+        }
+
+        let part_count = digests.len();
+        let is_multipart = multipart.is_some();
+        if is_multipart {
+            if let Some(last) = pending {
+                self.upload_part(last, multipart).await?;
+            }
+            let Some(upload) = multipart.as_mut() else {
+                unreachable!("multipart upload started above");
+            };
+            let parts = std::mem::take(&mut upload.parts);
+            self.bucket
+                .complete_multipart_upload(key, &upload.upload_id, parts)
+                .await
+                .map_err(|e| self.error(e))?;
+            // 已提交，之后的失败不再需要中止分片上传
+            *multipart = None;
+        } else {
+            let content = pending.unwrap_or_default();
+            self.bucket
+                .put_object_with_content_type(key, &content, &self.content_type)
+                .await
+                .map_err(|e| self.error(e))?;
+        }
+
+        let expected = expected_etag(&digests, is_multipart, |data| Md5::digest(data).into());
+        let etag = self.verify(bytes, &expected).await?;
+        debug!(
+            "Uploaded {} ({} bytes, {} parts, ETag {})",
+            self.location, bytes, part_count, etag
+        );
+        Ok(UploadReport {
+            location: self.location.clone(),
+            bytes,
+            parts: part_count.max(1),
+            etag,
+        })
+    }
+
+    async fn upload_part(
+        &self,
+        part: Vec<u8>,
+        multipart: &mut Option<Multipart>,
+    ) -> Result<(), ShortlinkerError> {
+        let key = &self.location.key;
+        if multipart.is_none() {
+            let response = self
+                .bucket
+                .initiate_multipart_upload(key, &self.content_type)
+                .await
+                .map_err(|e| self.error(e))?;
+            *multipart = Some(Multipart {
+                upload_id: response.upload_id,
+                parts: Vec::new(),
+            });
+        }
+        let Some(upload) = multipart.as_mut() else {
+            unreachable!("multipart upload initiated above");
+        };
+        let part_number = upload.parts.len() as u32 + 1;
+        let part = self
+            .bucket
+            .put_multipart_chunk(
+                part,
+                key,
+                part_number,
+                &upload.upload_id,
+                &self.content_type,
+            )
+            .await
+            .map_err(|e| self.error(e))?;
+        upload.parts.push(part);
+        Ok(())
+    }
+
+    /// HEAD 对象，比对大小与 ETag
+    async fn verify(&self, bytes: u64, expected_etag: &str) -> Result<String, ShortlinkerError> {
+        let (head, _) = self
+            .bucket
+            .head_object(&self.location.key)
+            .await
+            .map_err(|e| self.error(e))?;
+
+        let size = head.content_length.unwrap_or_default();
+        if size != bytes as i64 {
+            return Err(ShortlinkerError::file_operation(format!(
+                "Checksum verification failed for {}: uploaded {} bytes but the object has {}",
+                self.location, bytes, size
+            )));
+        }
+
+        let etag = head
+            .e_tag
+            .unwrap_or_default()
+            .trim_matches('"')
+            .to_ascii_lowercase();
+        if etag != expected_etag {
+            return Err(ShortlinkerError::file_operation(format!(
+                "Checksum verification failed for {}: ETag {} does not match the local MD5 {}",
+                self.location, etag, expected_etag
+            )));
+        }
+        Ok(etag)
+    }
+
+    fn error(&self, error: S3Error) -> ShortlinkerError {
+        describe_error(error, &self.location, &self.endpoint)
+    }
+}
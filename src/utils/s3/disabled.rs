@@ -0,0 +1,58 @@
+//! 未启用 `s3` feature 时的占位实现：解析路径照常，上传与下载直接报错
+
+use std::convert::Infallible;
+use std::io::{self, Write};
+
+use super::{S3Location, S3Settings, UploadReport};
+use crate::errors::ShortlinkerError;
+
+fn unavailable() -> ShortlinkerError {
+    ShortlinkerError::file_operation(
+        "S3 support is not available in this build (enable the `s3` feature)",
+    )
+}
+
+/// 流式写入对象存储的 writer（本构建中无法创建）
+pub struct S3Writer {
+    never: Infallible,
+}
+
+impl Write for S3Writer {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        match self.never {}
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.never {}
+    }
+}
+
+/// 上传任务的句柄（本构建中无法创建）
+pub struct S3Upload {
+    never: Infallible,
+}
+
+impl S3Upload {
+    pub async fn finish(self) -> Result<UploadReport, ShortlinkerError> {
+        match self.never {}
+    }
+
+    pub async fn abort(self) -> Option<ShortlinkerError> {
+        match self.never {}
+    }
+}
+
+pub fn start_upload(
+    _location: &S3Location,
+    _settings: &S3Settings,
+    _content_type: &str,
+) -> Result<(S3Writer, S3Upload), ShortlinkerError> {
+    Err(unavailable())
+}
+
+pub async fn download(
+    _location: &S3Location,
+    _settings: &S3Settings,
+) -> Result<Vec<u8>, ShortlinkerError> {
+    Err(unavailable())
+}
@@ -0,0 +1,299 @@
+//! S3 兼容对象存储：导出、备份的上传目标与导入的下载来源
+//!
+//! CLI 中的文件路径以 `s3:` 开头时改走对象存储：
+//! - `s3://bucket/path/file.csv`：显式指定存储桶与对象 key
+//! - `s3:path/file.csv`：使用 `storage.s3_bucket`，key 前加上 `storage.s3_prefix`
+//!
+//! key 为空或以 `/` 结尾时视为目录，由调用方补上默认文件名。
+//!
+//! 上传与下载需启用 `s3` feature；连接参数来自运行时配置 `storage.s3_*`，
+//! 留空的项回退到 AWS 环境变量链（`AWS_ENDPOINT_URL`、`AWS_REGION`、
+//! `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`、profile、实例元数据）。
+
+#[cfg(feature = "s3")]
+mod client;
+#[cfg(not(feature = "s3"))]
+mod disabled;
+
+#[cfg(feature = "s3")]
+pub use client::{S3Upload, S3Writer, download, start_upload};
+#[cfg(not(feature = "s3"))]
+pub use disabled::{S3Upload, S3Writer, download, start_upload};
+
+use std::fmt;
+
+use crate::config::{RuntimeConfig, keys};
+use crate::errors::ShortlinkerError;
+
+/// 未配置区域且环境变量也未设置时使用的默认区域
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/// 路径是否指向对象存储
+pub fn is_s3_path(path: &str) -> bool {
+    path.starts_with("s3:")
+}
+
+/// 对象存储中的位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl S3Location {
+    /// 解析 `s3://bucket/key` 或 `s3:key`（后者使用设置中的默认存储桶与前缀）
+    pub fn parse(path: &str, settings: &S3Settings) -> Result<Self, ShortlinkerError> {
+        let rest = path.strip_prefix("s3:").ok_or_else(|| {
+            ShortlinkerError::validation(format!("'{}' is not an s3: path", path))
+        })?;
+
+        if let Some(rest) = rest.strip_prefix("//") {
+            let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+            if bucket.is_empty() {
+                return Err(ShortlinkerError::validation(format!(
+                    "Missing bucket name in '{}' (expected s3://bucket/path)",
+                    path
+                )));
+            }
+            return Ok(Self {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+            });
+        }
+
+        if settings.bucket.is_empty() {
+            return Err(ShortlinkerError::validation(format!(
+                "'{}' uses the default bucket, but {} is not set (or use s3://bucket/path)",
+                path,
+                keys::STORAGE_S3_BUCKET
+            )));
+        }
+        let key = rest.trim_start_matches('/');
+        let key = if settings.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", settings.prefix, key)
+        };
+        Ok(Self {
+            bucket: settings.bucket.clone(),
+            key,
+        })
+    }
+
+    /// key 为空或以 `/` 结尾时表示目录
+    pub fn is_dir(&self) -> bool {
+        self.key.is_empty() || self.key.ends_with('/')
+    }
+
+    /// 目录位置补上文件名，文件位置保持不变
+    pub fn or_file_name(mut self, file_name: &str) -> Self {
+        if self.is_dir() {
+            self.key.push_str(file_name);
+        }
+        self
+    }
+}
+
+impl fmt::Display for S3Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+/// 对象存储连接参数
+#[derive(Debug, Clone, Default)]
+pub struct S3Settings {
+    /// 自定义服务地址（MinIO、R2 等）；`None` 表示 AWS S3
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: String,
+    pub prefix: String,
+    /// 静态凭证；`None` 时走 AWS 凭证链
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+}
+
+impl S3Settings {
+    /// 从运行时配置读取，留空的项回退到 AWS 环境变量
+    pub fn from_runtime_config(rt: &RuntimeConfig) -> Self {
+        Self::resolve(|key| rt.get_or(key, ""), |name| std::env::var(name).ok())
+    }
+
+    fn resolve(config: impl Fn(&str) -> String, env: impl Fn(&str) -> Option<String>) -> Self {
+        let non_empty = |value: String| {
+            let value = value.trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+        let from_env = |names: &[&str]| names.iter().find_map(|name| env(name).and_then(non_empty));
+
+        Self {
+            endpoint: non_empty(config(keys::STORAGE_S3_ENDPOINT))
+                .or_else(|| from_env(&["AWS_ENDPOINT_URL_S3", "AWS_ENDPOINT_URL"]))
+                .map(|endpoint| endpoint.trim_end_matches('/').to_string()),
+            region: non_empty(config(keys::STORAGE_S3_REGION))
+                .or_else(|| from_env(&["AWS_REGION", "AWS_DEFAULT_REGION"]))
+                .unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            bucket: config(keys::STORAGE_S3_BUCKET).trim().to_string(),
+            prefix: config(keys::STORAGE_S3_PREFIX)
+                .trim()
+                .trim_matches('/')
+                .to_string(),
+            access_key: non_empty(config(keys::STORAGE_S3_ACCESS_KEY)),
+            secret_key: non_empty(config(keys::STORAGE_S3_SECRET_KEY)),
+        }
+    }
+
+    /// 用于报错的服务地址描述
+    pub fn endpoint_display(&self) -> String {
+        match &self.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => format!("AWS S3 ({})", self.region),
+        }
+    }
+}
+
+/// 对象的预期 ETag
+///
+/// 单次 PUT 的 ETag 是内容的 MD5；分片上传为各分片 MD5 拼接后再取 MD5，
+/// 并追加 `-<分片数>`。
+pub fn expected_etag(
+    part_digests: &[[u8; 16]],
+    multipart: bool,
+    md5: impl Fn(&[u8]) -> [u8; 16],
+) -> String {
+    if !multipart && let [digest] = part_digests {
+        return hex(digest);
+    }
+    let concatenated: Vec<u8> = part_digests.iter().flatten().copied().collect();
+    format!("{}-{}", hex(&md5(&concatenated)), part_digests.len())
+}
+
+/// S3 返回的 ETag 为小写十六进制
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 上传完成后的校验结果
+#[derive(Debug, Clone)]
+pub struct UploadReport {
+    pub location: S3Location,
+    pub bytes: u64,
+    pub parts: usize,
+    pub etag: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(bucket: &str, prefix: &str) -> S3Settings {
+        S3Settings {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_explicit_bucket() {
+        let location =
+            S3Location::parse("s3://backups/daily/links.csv", &settings("", "")).unwrap();
+        assert_eq!(location.bucket, "backups");
+        assert_eq!(location.key, "daily/links.csv");
+        assert!(!location.is_dir());
+        assert_eq!(location.to_string(), "s3://backups/daily/links.csv");
+
+        // 显式存储桶不叠加前缀
+        let location = S3Location::parse("s3://other/a.csv", &settings("backups", "sl")).unwrap();
+        assert_eq!(location.key, "a.csv");
+    }
+
+    #[test]
+    fn test_parse_default_bucket_with_prefix() {
+        let location =
+            S3Location::parse("s3:exports/links.csv", &settings("backups", "sl")).unwrap();
+        assert_eq!(location.bucket, "backups");
+        assert_eq!(location.key, "sl/exports/links.csv");
+
+        let location = S3Location::parse("s3:links.csv", &settings("backups", "")).unwrap();
+        assert_eq!(location.key, "links.csv");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(S3Location::parse("s3:links.csv", &settings("", "")).is_err());
+        assert!(S3Location::parse("s3:///links.csv", &settings("backups", "")).is_err());
+        assert!(S3Location::parse("links.csv", &settings("backups", "")).is_err());
+    }
+
+    #[test]
+    fn test_directory_targets() {
+        for path in ["s3://backups", "s3://backups/", "s3://backups/exports/"] {
+            let location = S3Location::parse(path, &settings("", "")).unwrap();
+            assert!(location.is_dir(), "{}", path);
+        }
+        let location = S3Location::parse("s3://backups/exports/", &settings("", ""))
+            .unwrap()
+            .or_file_name("links.csv");
+        assert_eq!(location.key, "exports/links.csv");
+
+        let location = S3Location::parse("s3://backups/a.csv", &settings("", ""))
+            .unwrap()
+            .or_file_name("links.csv");
+        assert_eq!(location.key, "a.csv");
+    }
+
+    #[test]
+    fn test_settings_fall_back_to_env() {
+        let env = |name: &str| match name {
+            "AWS_ENDPOINT_URL" => Some("http://minio:9000/".to_string()),
+            "AWS_DEFAULT_REGION" => Some("eu-west-1".to_string()),
+            _ => None,
+        };
+        let resolved = S3Settings::resolve(|_| String::new(), env);
+        assert_eq!(resolved.endpoint.as_deref(), Some("http://minio:9000"));
+        assert_eq!(resolved.region, "eu-west-1");
+        assert_eq!(resolved.access_key, None);
+
+        let resolved = S3Settings::resolve(
+            |key| match key {
+                keys::STORAGE_S3_REGION => "ap-east-1".to_string(),
+                keys::STORAGE_S3_PREFIX => "/sl/backups/".to_string(),
+                keys::STORAGE_S3_ACCESS_KEY => "AKID".to_string(),
+                _ => String::new(),
+            },
+            env,
+        );
+        assert_eq!(resolved.region, "ap-east-1");
+        assert_eq!(resolved.prefix, "sl/backups");
+        assert_eq!(resolved.access_key.as_deref(), Some("AKID"));
+
+        let resolved = S3Settings::resolve(|_| String::new(), |_| None);
+        assert_eq!(resolved.region, DEFAULT_REGION);
+        assert_eq!(resolved.endpoint_display(), "AWS S3 (us-east-1)");
+    }
+
+    #[test]
+    fn test_expected_etag() {
+        // 测试不依赖 md-5：用固定摘要验证拼接与格式
+        let fake_md5 = |data: &[u8]| {
+            let mut digest = [0u8; 16];
+            digest[0] = data.len() as u8;
+            digest
+        };
+        let part = [0xabu8; 16];
+        assert_eq!(
+            expected_etag(&[part], false, fake_md5),
+            "abababababababababababababababab"
+        );
+        assert_eq!(
+            expected_etag(&[part, part], true, fake_md5),
+            "20000000000000000000000000000000-2"
+        );
+        // 只有一个分片的分片上传仍带 -1 后缀
+        assert_eq!(
+            expected_etag(&[part], true, fake_md5),
+            "10000000000000000000000000000000-1"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! 均匀随机抽样工具
+//!
+//! 提供可复现的蓄水池抽样（Algorithm R）：单次遍历、O(n) 内存，
+//! 不需要预先知道总体大小，适合对分批流式扫描的结果抽样，
+//! 避免在数据库侧使用 `ORDER BY RANDOM()` 全表排序。
+//!
+//! 随机源为内置的 SplitMix64，同一 seed 在任意版本/平台下产生相同序列，
+//! 保证抽检结果可按 seed 复现。
+
+/// 可复现的 64 位伪随机数生成器（SplitMix64）
+///
+/// 仅用于抽样等非安全场景，不可用于生成 token。
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 返回 `[0, bound)` 内的均匀整数（乘法映射，偏差 < bound / 2^64）
+    pub fn below(&mut self, bound: u64) -> u64 {
+        debug_assert!(bound > 0);
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// 蓄水池抽样器
+///
+/// 依次 [`offer`](Self::offer) 总体中的每个元素，结束后
+/// [`into_sample`](Self::into_sample) 得到至多 `capacity` 个元素的均匀样本：
+/// 总体中每个元素被选中的概率均为 `min(1, capacity / seen)`。
+#[derive(Debug, Clone)]
+pub struct ReservoirSampler<T> {
+    capacity: usize,
+    seen: u64,
+    reservoir: Vec<T>,
+    rng: SeededRng,
+}
+
+impl<T> ReservoirSampler<T> {
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        Self {
+            capacity,
+            seen: 0,
+            reservoir: Vec::with_capacity(capacity),
+            rng: SeededRng::new(seed),
+        }
+    }
+
+    /// 提供一个总体元素
+    pub fn offer(&mut self, item: T) {
+        self.seen += 1;
+        if self.reservoir.len() < self.capacity {
+            self.reservoir.push(item);
+            return;
+        }
+        let slot = self.rng.below(self.seen) as usize;
+        if slot < self.capacity {
+            self.reservoir[slot] = item;
+        }
+    }
+
+    /// 已提供的总体元素数
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// 取出样本（顺序无意义）
+    pub fn into_sample(self) -> Vec<T> {
+        self.reservoir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_population_smaller_than_capacity_keeps_all() {
+        let mut sampler = ReservoirSampler::new(10, 1);
+        for i in 0..5 {
+            sampler.offer(i);
+        }
+        assert_eq!(sampler.seen(), 5);
+        assert_eq!(sampler.into_sample(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut sampler = ReservoirSampler::new(0, 1);
+        for i in 0..100 {
+            sampler.offer(i);
+        }
+        assert_eq!(sampler.seen(), 100);
+        assert!(sampler.into_sample().is_empty());
+    }
+
+    #[test]
+    fn test_same_seed_same_sample() {
+        let run = |seed| {
+            let mut sampler = ReservoirSampler::new(20, seed);
+            for i in 0..10_000 {
+                sampler.offer(i);
+            }
+            sampler.into_sample()
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn test_sample_has_no_duplicates() {
+        let mut sampler = ReservoirSampler::new(100, 7);
+        for i in 0..5_000 {
+            sampler.offer(i);
+        }
+        let mut sample = sampler.into_sample();
+        assert_eq!(sample.len(), 100);
+        sample.sort_unstable();
+        sample.dedup();
+        assert_eq!(sample.len(), 100);
+    }
+
+    #[test]
+    fn test_below_stays_in_range() {
+        let mut rng = SeededRng::new(0);
+        for bound in [1u64, 2, 3, 10, 1_000, u64::MAX] {
+            for _ in 0..1_000 {
+                assert!(rng.below(bound) < bound);
+            }
+        }
+    }
+
+    /// 均匀性检验：总体 50 个元素、每次抽 5 个、重复 20000 次，
+    /// 每个元素的期望命中次数为 2000。自由度 49 时卡方统计量的
+    /// 99.9% 分位约为 85.4，这里放宽到 100 避免偶发失败（seed 固定，结果确定）。
+    #[test]
+    fn test_uniformity_chi_square() {
+        const POPULATION: usize = 50;
+        const CAPACITY: usize = 5;
+        const TRIALS: u64 = 20_000;
+
+        let mut counts = [0u64; POPULATION];
+        for trial in 0..TRIALS {
+            let mut sampler = ReservoirSampler::new(CAPACITY, trial.wrapping_mul(0x2545_F491));
+            for i in 0..POPULATION {
+                sampler.offer(i);
+            }
+            for i in sampler.into_sample() {
+                counts[i] += 1;
+            }
+        }
+
+        let expected = (TRIALS as f64) * (CAPACITY as f64) / (POPULATION as f64);
+        let chi_square: f64 = counts
+            .iter()
+            .map(|&observed| {
+                let diff = observed as f64 - expected;
+                diff * diff / expected
+            })
+            .sum();
+        assert!(
+            chi_square < 100.0,
+            "chi-square {chi_square:.2} too large, counts = {counts:?}"
+        );
+    }
+}
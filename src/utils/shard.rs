@@ -0,0 +1,126 @@
+//! 短码分片
+//!
+//! 多个只读副本分摊 redirect 流量时，每个实例可以只为自己负责的短码子集构建
+//! Bloom Filter 与进程内缓存：`shard_of(code) == cache.shard_index` 的短码归本
+//! 实例所有。哈希为 FNV-1a 64（对短码的 UTF-8 字节）再经 MurmurHash3 的 fmix64
+//! 混合低位，在任意版本/平台下结果相同，上游负载均衡可按同一公式路由：
+//!
+//! ```text
+//! h = 0xcbf29ce484222325
+//! for byte in code: h = (h ^ byte) * 0x100000001b3        (mod 2^64)
+//! h ^= h >> 33; h *= 0xff51afd7ed558ccd; h ^= h >> 33;
+//! h *= 0xc4ceb9fe1a85ec53; h ^= h >> 33                   (mod 2^64)
+//! shard = h % shard_total
+//! ```
+
+use tracing::warn;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 短码的稳定哈希（FNV-1a 64 + fmix64）
+pub fn code_hash(code: &str) -> u64 {
+    let hash = code.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    // FNV 的低位分布较差（取模 2 时只取决于各字节奇偶），取模前先混合
+    fmix64(hash)
+}
+
+fn fmix64(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^ (h >> 33)
+}
+
+/// 本实例负责的分片
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeShard {
+    index: u64,
+    total: u64,
+}
+
+impl CodeShard {
+    /// 构造分片；`total <= 1` 视为未分片，`index >= total` 无效，均返回 `None`
+    pub fn new(index: u64, total: u64) -> Option<Self> {
+        (total > 1 && index < total).then_some(Self { index, total })
+    }
+
+    /// 从 `cache.shard_index` / `cache.shard_total` 读取分片配置
+    ///
+    /// 未配置（`shard_total` 为 0 或 1）或运行时配置未初始化时返回 `None`；
+    /// 配置无效时记录警告并按未分片处理。
+    pub fn from_runtime_config() -> Option<Self> {
+        use crate::config::{keys, try_get_runtime_config};
+
+        let rt = try_get_runtime_config()?;
+        let total = rt.get_u64_or(keys::CACHE_SHARD_TOTAL, 0);
+        let index = rt.get_u64_or(keys::CACHE_SHARD_INDEX, 0);
+        if total <= 1 {
+            return None;
+        }
+        let shard = Self::new(index, total);
+        if shard.is_none() {
+            warn!(
+                "cache.shard_index ({}) must be less than cache.shard_total ({}); sharding disabled",
+                index, total
+            );
+        }
+        shard
+    }
+
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// 短码是否归本分片
+    #[inline]
+    pub fn owns(&self, code: &str) -> bool {
+        code_hash(code) % self.total == self.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_hash_is_stable() {
+        // 上游路由依赖该公式，参考值不得变化
+        assert_eq!(code_hash(""), 0xefd0_1f60_ba99_2926);
+        assert_eq!(code_hash("a"), 0x82a2_a958_a9be_ce5b);
+        assert_eq!(code_hash("foobar"), 0x2c22_1949_22d1_672b);
+    }
+
+    #[test]
+    fn test_shards_partition_codes() {
+        let shards: Vec<CodeShard> = (0..4).map(|i| CodeShard::new(i, 4).unwrap()).collect();
+        let mut sizes = [0usize; 4];
+        for i in 0..10_000 {
+            let code = format!("code{}", i);
+            let owners: Vec<usize> = (0..4).filter(|&s| shards[s].owns(&code)).collect();
+            assert_eq!(owners.len(), 1, "{} must belong to exactly one shard", code);
+            sizes[owners[0]] += 1;
+        }
+        // 分布大致均匀
+        assert!(
+            sizes.iter().all(|&n| (2_000..3_000).contains(&n)),
+            "{:?}",
+            sizes
+        );
+    }
+
+    #[test]
+    fn test_invalid_shards_are_rejected() {
+        assert_eq!(CodeShard::new(0, 0), None);
+        assert_eq!(CodeShard::new(0, 1), None);
+        assert_eq!(CodeShard::new(4, 4), None);
+        assert!(CodeShard::new(3, 4).is_some());
+    }
+}
@@ -1,25 +1,167 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{
+    DateTime, Duration, FixedOffset, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    Offset, TimeZone, Utc,
+};
+
+/// 不带时区的时间输入（`2024-12-31`、`2024-12-31 18:00`）按哪个时区解释
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputTimeZone {
+    /// 服务器本地时区，随夏令时变化
+    #[default]
+    Local,
+    /// 固定偏移，`UTC` 即 `+00:00`
+    Fixed(FixedOffset),
+}
+
+impl InputTimeZone {
+    /// 解析 `local`、`UTC` 或 `±HH:MM`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("local") {
+            return Some(Self::Local);
+        }
+        if value.eq_ignore_ascii_case("utc") || value == "Z" {
+            return Some(Self::Fixed(FixedOffset::east_opt(0)?));
+        }
+        let (sign, rest) = match value.as_bytes().first()? {
+            b'+' => (1, &value[1..]),
+            b'-' => (-1, &value[1..]),
+            _ => return None,
+        };
+        let (hours, minutes) = rest.split_once(':')?;
+        if hours.len() != 2 || minutes.len() != 2 {
+            return None;
+        }
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if hours > 14 || minutes > 59 {
+            return None;
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).map(Self::Fixed)
+    }
+
+    /// 配置中保存的规范写法：`local`、`UTC` 或 `+08:00`
+    pub fn to_config_value(self) -> String {
+        match self {
+            Self::Local => "local".to_string(),
+            Self::Fixed(offset) if offset.local_minus_utc() == 0 => "UTC".to_string(),
+            Self::Fixed(offset) => offset.to_string(),
+        }
+    }
+
+    /// 按该时区展示时间，如 `2024-12-31 08:00:00 +08:00`
+    pub fn format(self, dt: DateTime<Utc>) -> String {
+        const FORMAT: &str = "%Y-%m-%d %H:%M:%S %:z";
+        match self {
+            Self::Local => dt.with_timezone(&Local).format(FORMAT).to_string(),
+            Self::Fixed(offset) => dt.with_timezone(&offset).format(FORMAT).to_string(),
+        }
+    }
+}
+
+/// 只有日期的输入（`2024-12-31`）落在当天的哪一刻
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateOnlyBoundary {
+    /// 当天 23:59:59，即"到这一天为止都有效"
+    #[default]
+    EndOfDay,
+    /// 当天 00:00:00
+    StartOfDay,
+}
+
+impl DateOnlyBoundary {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "end_of_day" => Some(Self::EndOfDay),
+            "start_of_day" => Some(Self::StartOfDay),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::EndOfDay => "end_of_day",
+            Self::StartOfDay => "start_of_day",
+        }
+    }
+}
+
+/// 绝对时间输入的解释规则，来自 `links.expiry_timezone` 与 `links.date_only_expiry`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExpiryInputRules {
+    pub timezone: InputTimeZone,
+    pub date_only: DateOnlyBoundary,
+}
+
+/// 不带时区的日期时间格式；`%.f` 的小数秒可省略
+const NAIVE_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
 
 #[derive(Debug, Clone)]
 pub struct TimeParser;
 
 impl TimeParser {
     /// 解析时间字符串，支持多种格式：
-    /// - RFC3339 格式：2023-10-01T12:00:00Z
+    /// - RFC3339 格式：2023-10-01T12:00:00Z（带偏移，原样使用）
+    /// - 不带时区的日期时间：2023-10-01 12:00、2023-10-01T12:00:00（按配置时区解释）
+    /// - 只有日期：2023-10-01（按配置时区的当天结束或开始）
     /// - 相对时间：1d, 2w, 3M (大写M表示月), 1y, 1h30m, 2d12h
     /// - 组合格式：1d2h30m
     ///
     /// 注意：m 表示分钟，M 表示月份
     pub fn parse_expire_time(input: &str) -> Result<DateTime<Utc>, String> {
+        Self::parse_expire_time_with(input, &ExpiryInputRules::default())
+    }
+
+    /// 按给定规则解析过期时间：先尝试绝对时间，再尝试相对时间
+    pub fn parse_expire_time_with(
+        input: &str,
+        rules: &ExpiryInputRules,
+    ) -> Result<DateTime<Utc>, String> {
+        let input = input.trim();
+        match Self::parse_absolute_time(input, rules) {
+            Ok(dt) => Ok(dt),
+            // 形如日期但不合法（如 2023-02-30），报告日期错误而不是"不支持的单位"
+            Err(e) if looks_like_date(input) => Err(e),
+            Err(_) => Self::parse_relative_time(input),
+        }
+    }
+
+    /// 只解析绝对时间（RFC3339、不带时区的日期时间、只有日期）
+    pub fn parse_absolute_time(
+        input: &str,
+        rules: &ExpiryInputRules,
+    ) -> Result<DateTime<Utc>, String> {
         let input = input.trim();
 
-        // 尝试解析 RFC3339 格式
         if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
             return Ok(dt.with_timezone(&Utc));
         }
 
-        // 尝试解析相对时间格式
-        Self::parse_relative_time(input)
+        let naive = NAIVE_DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok());
+        let (naive, prefer_latest) = match naive {
+            Some(naive) => (naive, false),
+            None => {
+                let date = NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid date or time '{}': {}", input, e))?;
+                match rules.date_only {
+                    DateOnlyBoundary::EndOfDay => (date.and_time(end_of_day()), true),
+                    DateOnlyBoundary::StartOfDay => (date.and_time(NaiveTime::MIN), false),
+                }
+            }
+        };
+
+        let resolved = match rules.timezone {
+            InputTimeZone::Local => resolve_local(&Local, naive, prefer_latest),
+            InputTimeZone::Fixed(offset) => resolve_local(&offset, naive, prefer_latest),
+        };
+        resolved.ok_or_else(|| format!("Time '{}' is out of valid range", input))
     }
 
     /// 解析过期时间，带有详细的格式帮助信息
@@ -30,13 +172,18 @@ impl TimeParser {
             format!(
                 "Invalid expiration time format: {}. Supported formats:\n  \
                 - RFC3339: 2023-10-01T12:00:00Z\n  \
+                - Local date/time: 2023-10-01 12:00, 2023-10-01\n  \
                 - Relative time: 1d, 2w, 1y, 1d2h30m",
                 e
             )
         })
     }
 
-    fn parse_relative_time(input: &str) -> Result<DateTime<Utc>, String> {
+    /// 解析相对时长（如 `7d`、`1d12h`），不叠加当前时间
+    ///
+    /// 适用于"顺延 N 天"这类需要偏移量而非绝对时间的场景
+    pub fn parse_duration(input: &str) -> Result<Duration, String> {
+        let input = input.trim();
         let mut total_duration = Duration::zero();
         let mut remaining = input;
 
@@ -116,6 +263,12 @@ impl TimeParser {
             return Err("Duration cannot be zero".to_string());
         }
 
+        Ok(total_duration)
+    }
+
+    fn parse_relative_time(input: &str) -> Result<DateTime<Utc>, String> {
+        let total_duration = Self::parse_duration(input)?;
+
         let now = Utc::now();
         match now.checked_add_signed(total_duration) {
             Some(future_time) => Ok(future_time),
@@ -155,6 +308,43 @@ impl TimeParser {
     }
 }
 
+/// 只有日期时使用的"当天结束"时刻
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).expect("valid time")
+}
+
+/// 以 `YYYY-` 开头的输入视为日期，不再按相对时间解析
+fn looks_like_date(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    bytes.len() >= 5 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-'
+}
+
+/// 把时区 `tz` 下的本地时间换算为 UTC
+///
+/// - 夏令时回拨造成的重复时段：默认取较早的时刻，`prefer_latest` 时取较晚的
+///   （"当天结束"应覆盖整段重复时间）
+/// - 夏令时跳过的时段：按跳变前的偏移换算，相当于顺延跳过的时长
+fn resolve_local<Tz: TimeZone>(
+    tz: &Tz,
+    naive: NaiveDateTime,
+    prefer_latest: bool,
+) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, latest) => {
+            let dt = if prefer_latest { latest } else { earliest };
+            Some(dt.with_timezone(&Utc))
+        }
+        LocalResult::None => {
+            let before = naive.checked_sub_signed(Duration::hours(3))?;
+            let offset = tz.offset_from_local_datetime(&before).earliest()?.fix();
+            let utc =
+                naive.checked_sub_signed(Duration::seconds(i64::from(offset.local_minus_utc())))?;
+            Some(Utc.from_utc_datetime(&utc))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +370,17 @@ mod tests {
         assert!((actual_seconds - expected_seconds).abs() < 5); // 允许5秒误差
     }
 
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(TimeParser::parse_duration("7d").unwrap(), Duration::days(7));
+        assert_eq!(
+            TimeParser::parse_duration("1d12h").unwrap(),
+            Duration::hours(36)
+        );
+        assert!(TimeParser::parse_duration("0d").is_err());
+        assert!(TimeParser::parse_duration("2023-10-01T12:00:00Z").is_err());
+    }
+
     #[test]
     fn test_parse_rfc3339() {
         let result = TimeParser::parse_expire_time("2023-10-01T12:00:00Z");
@@ -297,4 +498,239 @@ mod tests {
         let result = TimeParser::parse_expire_time("520w"); // 约 10 年
         assert!(result.is_ok(), "520w should be a valid value");
     }
+
+    /// 测试用时区：标准时间 +01:00，2025-03-30 02:00 跳到 03:00，2025-10-26 03:00 回拨到 02:00
+    #[derive(Debug, Clone, Copy)]
+    struct TestDst;
+
+    impl TestDst {
+        fn standard() -> FixedOffset {
+            FixedOffset::east_opt(3600).unwrap()
+        }
+
+        fn summer() -> FixedOffset {
+            FixedOffset::east_opt(7200).unwrap()
+        }
+    }
+
+    impl TimeZone for TestDst {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            TestDst
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let candidates: Vec<FixedOffset> = [Self::summer(), Self::standard()]
+                .into_iter()
+                .filter(|offset| {
+                    let utc = *local - Duration::seconds(i64::from(offset.local_minus_utc()));
+                    self.offset_from_utc_datetime(&utc) == *offset
+                })
+                .collect();
+            match candidates[..] {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(offset),
+                [earliest, latest, ..] => LocalResult::Ambiguous(earliest, latest),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let starts = utc_at("2025-03-30T01:00:00Z").naive_utc();
+            let ends = utc_at("2025-10-26T01:00:00Z").naive_utc();
+            if (starts..ends).contains(utc) {
+                Self::summer()
+            } else {
+                Self::standard()
+            }
+        }
+    }
+
+    fn utc_at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn naive(input: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn rules(timezone: &str, date_only: DateOnlyBoundary) -> ExpiryInputRules {
+        ExpiryInputRules {
+            timezone: InputTimeZone::parse(timezone).unwrap(),
+            date_only,
+        }
+    }
+
+    #[test]
+    fn test_absolute_time_matrix() {
+        use DateOnlyBoundary::{EndOfDay, StartOfDay};
+
+        // (输入, 时区, 只有日期时的边界, 期望的 UTC 时刻)
+        let cases = [
+            // 只有日期：配置时区的当天结束 / 开始
+            ("2024-12-31", "UTC", EndOfDay, "2024-12-31T23:59:59Z"),
+            ("2024-12-31", "UTC", StartOfDay, "2024-12-31T00:00:00Z"),
+            ("2024-12-31", "+08:00", EndOfDay, "2024-12-31T15:59:59Z"),
+            ("2024-12-31", "+08:00", StartOfDay, "2024-12-30T16:00:00Z"),
+            ("2024-12-31", "-05:00", EndOfDay, "2025-01-01T04:59:59Z"),
+            ("2024-12-31", "+05:45", EndOfDay, "2024-12-31T18:14:59Z"),
+            ("2024-12-31", "+14:00", StartOfDay, "2024-12-30T10:00:00Z"),
+            ("2024-12-31", "-12:00", EndOfDay, "2025-01-01T11:59:59Z"),
+            ("2024-02-29", "UTC", EndOfDay, "2024-02-29T23:59:59Z"),
+            // 不带时区的日期时间：按配置时区解释，与边界设置无关
+            (
+                "2024-12-31 18:00",
+                "+08:00",
+                EndOfDay,
+                "2024-12-31T10:00:00Z",
+            ),
+            (
+                "2024-12-31T18:00:30",
+                "+08:00",
+                StartOfDay,
+                "2024-12-31T10:00:30Z",
+            ),
+            (
+                "2024-12-31 00:00:00",
+                "-05:00",
+                EndOfDay,
+                "2024-12-31T05:00:00Z",
+            ),
+            (
+                "2024-12-31T23:59:59.5",
+                "UTC",
+                EndOfDay,
+                "2024-12-31T23:59:59.5Z",
+            ),
+            // 带偏移：原样使用，忽略配置时区
+            (
+                "2024-12-31T00:00:00Z",
+                "+08:00",
+                EndOfDay,
+                "2024-12-31T00:00:00Z",
+            ),
+            (
+                "2024-12-31T18:00:00+09:00",
+                "-05:00",
+                EndOfDay,
+                "2024-12-31T09:00:00Z",
+            ),
+        ];
+
+        for (input, timezone, date_only, expected) in cases {
+            let parsed = TimeParser::parse_absolute_time(input, &rules(timezone, date_only))
+                .unwrap_or_else(|e| panic!("{} ({}): {}", input, timezone, e));
+            assert_eq!(parsed, utc_at(expected), "{} ({})", input, timezone);
+        }
+    }
+
+    #[test]
+    fn test_absolute_time_rejects_invalid_dates() {
+        let rules = ExpiryInputRules::default();
+        for input in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-12-31 24:00",
+            "2024-12-31T25:00:00",
+        ] {
+            assert!(
+                TimeParser::parse_absolute_time(input, &rules).is_err(),
+                "{}",
+                input
+            );
+            // 形如日期的非法值不回退到相对时间
+            let err = TimeParser::parse_expire_time_with(input, &rules).unwrap_err();
+            assert!(err.contains("Invalid date or time"), "{}: {}", input, err);
+        }
+        assert!(TimeParser::parse_absolute_time("1d", &rules).is_err());
+    }
+
+    #[test]
+    fn test_expire_time_with_rules_keeps_relative() {
+        let result =
+            TimeParser::parse_expire_time_with("2d", &rules("+08:00", DateOnlyBoundary::EndOfDay))
+                .unwrap();
+        let hours = (result - Utc::now()).num_hours();
+        assert!((47..=48).contains(&hours), "{}", hours);
+    }
+
+    #[test]
+    fn test_resolve_across_dst() {
+        // 普通时段
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-01-15 12:00:00"), false),
+            Some(utc_at("2025-01-15T11:00:00Z"))
+        );
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-07-15 12:00:00"), false),
+            Some(utc_at("2025-07-15T10:00:00Z"))
+        );
+        // 跳过的 02:30 顺延一小时，即 03:30 夏令时
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-03-30 02:30:00"), false),
+            Some(utc_at("2025-03-30T01:30:00Z"))
+        );
+        // 重复的 02:30：默认取较早的一次，prefer_latest 取较晚的一次
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-10-26 02:30:00"), false),
+            Some(utc_at("2025-10-26T00:30:00Z"))
+        );
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-10-26 02:30:00"), true),
+            Some(utc_at("2025-10-26T01:30:00Z"))
+        );
+        // 切换当天的日期边界
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-03-30 23:59:59"), true),
+            Some(utc_at("2025-03-30T21:59:59Z"))
+        );
+        assert_eq!(
+            resolve_local(&TestDst, naive("2025-10-26 00:00:00"), false),
+            Some(utc_at("2025-10-25T22:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_input_timezone_parse() {
+        assert_eq!(InputTimeZone::parse("local"), Some(InputTimeZone::Local));
+        assert_eq!(InputTimeZone::parse("LOCAL"), Some(InputTimeZone::Local));
+        for (input, canonical) in [
+            ("UTC", "UTC"),
+            ("utc", "UTC"),
+            ("+00:00", "UTC"),
+            ("+08:00", "+08:00"),
+            ("-05:30", "-05:30"),
+            ("+14:00", "+14:00"),
+        ] {
+            let parsed = InputTimeZone::parse(input).unwrap_or_else(|| panic!("{}", input));
+            assert_eq!(parsed.to_config_value(), canonical, "{}", input);
+        }
+        assert_eq!(
+            InputTimeZone::parse("+08:00")
+                .unwrap()
+                .format(utc_at("2024-12-31T00:00:00Z")),
+            "2024-12-31 08:00:00 +08:00"
+        );
+        for input in [
+            "",
+            "Asia/Shanghai",
+            "8",
+            "+8:00",
+            "+0800",
+            "+15:00",
+            "+08:60",
+        ] {
+            assert_eq!(InputTimeZone::parse(input), None, "{}", input);
+        }
+    }
 }
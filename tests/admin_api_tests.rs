@@ -12,10 +12,14 @@ use actix_web::{App, web};
 use async_trait::async_trait;
 use serde_json::json;
 
+use shortlinker::api::middleware::{PanelVersionGuard, RequestContext};
+use shortlinker::api::services::admin::meta::{admin_not_found, extractor_error};
 use shortlinker::api::services::admin::routes::links_routes;
+use shortlinker::api::services::admin::routes::meta_routes;
 use shortlinker::api::services::admin::routes::stats_routes;
 use shortlinker::api::services::admin::{
-    ApiResponse, LinkResponse, PaginatedResponse, PostNewLink,
+    ApiResponse, BaseUrlInfo, ErrorCatalogEntry, ErrorCode, LinkResponse, PaginatedResponse,
+    PostNewLink, StatsResponse, VersionInfo,
 };
 use shortlinker::config::init_config;
 use shortlinker::metrics::NoopMetrics;
@@ -261,6 +265,51 @@ async fn test_update_link_success() {
     assert_eq!(link.target, "https://example.com/new");
 }
 
+#[tokio::test]
+async fn test_update_link_expiry_null_clears_and_omitted_keeps() {
+    init_admin_test_env().await;
+    let app = admin_app!();
+
+    let req = TestRequest::post()
+        .uri("/v1/links")
+        .set_json(json!({
+            "code": "api-upd-exp",
+            "target": "https://example.com/exp",
+            "expires_at": "2099-01-01T00:00:00Z",
+            "force": true,
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: ApiResponse<PostNewLink> = test::read_body_json(resp).await;
+    // 响应回显的是存储后的时间，而不是原始输入
+    assert_eq!(
+        body.data.unwrap().expires_at.flatten().as_deref(),
+        Some("2099-01-01T00:00:00Z")
+    );
+
+    // 省略 expires_at：保持不变
+    let req = TestRequest::put()
+        .uri("/v1/links/api-upd-exp")
+        .set_json(json!({ "target": "https://example.com/exp2" }))
+        .to_request();
+    let body: ApiResponse<PostNewLink> =
+        test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(
+        body.data.unwrap().expires_at.flatten().as_deref(),
+        Some("2099-01-01T00:00:00Z")
+    );
+
+    // 显式 null：清除过期时间
+    let req = TestRequest::put()
+        .uri("/v1/links/api-upd-exp")
+        .set_json(json!({ "target": "https://example.com/exp2", "expires_at": null }))
+        .to_request();
+    let body: ApiResponse<PostNewLink> =
+        test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body.data.unwrap().expires_at.flatten(), None);
+}
+
 #[tokio::test]
 async fn test_delete_link_success() {
     init_admin_test_env().await;
@@ -328,10 +377,69 @@ async fn test_get_stats() {
     init_admin_test_env().await;
     let app = admin_app!();
 
+    let req = TestRequest::post()
+        .uri("/v1/links")
+        .set_json(json!({
+            "code": "api-stats-via",
+            "target": "https://example.com/stats-via",
+            "force": true,
+        }))
+        .to_request();
+    test::call_service(&app, req).await;
+
     let req = TestRequest::get().uri("/v1/stats").to_request();
     let resp = test::call_service(&app, req).await;
 
     assert_eq!(resp.status(), StatusCode::OK);
+    let body: ApiResponse<StatsResponse> = test::read_body_json(resp).await;
+    let stats = body.data.unwrap();
+    assert!(stats.created_via.get("api").copied().unwrap_or(0) >= 1);
+    assert_eq!(stats.created_via_trend.len(), 30);
+    let today = stats.created_via_trend.last().unwrap();
+    assert_eq!(
+        today.date,
+        chrono::Utc::now().format("%Y-%m-%d").to_string()
+    );
+    assert!(today.counts.get("api").copied().unwrap_or(0) >= 1);
+}
+
+#[tokio::test]
+async fn test_get_all_links_filter_by_created_via() {
+    init_admin_test_env().await;
+    let app = admin_app!();
+
+    let req = TestRequest::post()
+        .uri("/v1/links")
+        .set_json(json!({
+            "code": "api-via-filter",
+            "target": "https://example.com/via",
+            "force": true,
+        }))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = TestRequest::get()
+        .uri("/v1/links?created_via=api&search=api-via-filter")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: PaginatedResponse<Vec<LinkResponse>> = test::read_body_json(resp).await;
+    let links = body.data.unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].created_via, "api");
+
+    let req = TestRequest::get()
+        .uri("/v1/links?created_via=import&search=api-via-filter")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body: PaginatedResponse<Vec<LinkResponse>> = test::read_body_json(resp).await;
+    assert_eq!(body.pagination.total, 0);
+
+    let req = TestRequest::get()
+        .uri("/v1/links?created_via=panel")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 }
 
 // =============================================================================
@@ -399,3 +507,177 @@ async fn test_batch_delete_links() {
     }
     assert_eq!(status, StatusCode::OK, "Batch delete failed after retries");
 }
+
+// =============================================================================
+// Error Envelope Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_error_envelope_carries_request_id() {
+    init_admin_test_env().await;
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(get_service())).service(
+            web::scope("/v1")
+                .wrap(RequestContext)
+                .service(links_routes()),
+        ),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/v1/links/nonexistent-envelope-link")
+        .insert_header(("X-Request-Id", "sdk-req-1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "sdk-req-1");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["request_id"], "sdk-req-1");
+    assert!(body["error"]["message"].is_string());
+    // 旧版顶层字段默认保留，且与新结构一致
+    assert_eq!(body["code"], body["error"]["code"]);
+    assert_eq!(body["message"], body["error"]["message"]);
+}
+
+#[tokio::test]
+async fn test_error_envelope_on_extractor_failure() {
+    init_admin_test_env().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(get_service()))
+            .app_data(web::QueryConfig::default().error_handler(extractor_error))
+            .service(web::scope("/v1").service(links_routes()))
+            .default_service(web::to(admin_not_found)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/v1/links?page=not-a-number")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], ErrorCode::BadRequest as i32);
+    assert!(body["error"]["request_id"].is_string());
+
+    let req = TestRequest::get().uri("/v1/no-such-route").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], ErrorCode::NotFound as i32);
+}
+
+#[tokio::test]
+async fn test_error_catalog_endpoint() {
+    let app = test::init_service(App::new().service(meta_routes())).await;
+
+    let req = TestRequest::get().uri("/meta/errors").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: ApiResponse<Vec<ErrorCatalogEntry>> = test::read_body_json(resp).await;
+    let catalog = body.data.unwrap();
+    assert_eq!(catalog.len(), ErrorCode::ALL.len() - 1);
+
+    let conflict = catalog
+        .iter()
+        .find(|entry| entry.name == "LinkAlreadyExists")
+        .unwrap();
+    assert_eq!(conflict.code, 3001);
+    assert_eq!(conflict.http_status, 409);
+    assert!(!conflict.retryable);
+
+    let unavailable = catalog
+        .iter()
+        .find(|entry| entry.name == "ServiceUnavailable")
+        .unwrap();
+    assert_eq!(unavailable.http_status, 503);
+    assert!(unavailable.retryable);
+}
+
+// =============================================================================
+// Panel Version Handshake Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_version_endpoint() {
+    let app = test::init_service(App::new().service(meta_routes())).await;
+
+    let req = TestRequest::get().uri("/meta/version").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: ApiResponse<VersionInfo> = test::read_body_json(resp).await;
+    let info = body.data.unwrap();
+    assert_eq!(
+        info.api_version,
+        shortlinker::api::version::ADMIN_API_VERSION
+    );
+    assert_eq!(info.server_version, env!("CARGO_PKG_VERSION"));
+    assert!(shortlinker::api::version::is_panel_compatible(
+        &info.min_panel_version
+    ));
+}
+
+#[tokio::test]
+async fn test_base_url_endpoint_ignores_untrusted_forwarded_headers() {
+    let app = test::init_service(App::new().service(meta_routes())).await;
+
+    let req = TestRequest::get()
+        .uri("/meta/base-url")
+        .insert_header(("Host", "s.example.com:80"))
+        .insert_header(("X-Forwarded-Proto", "https"))
+        .insert_header(("X-Forwarded-Host", "evil.example"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: ApiResponse<BaseUrlInfo> = test::read_body_json(resp).await;
+    let info = body.data.unwrap();
+    assert_eq!(info.base_url, "http://s.example.com");
+    assert_eq!(info.source, "host");
+}
+
+#[tokio::test]
+async fn test_incompatible_panel_version_rejected() {
+    init_admin_test_env().await;
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(get_service())).service(
+            web::scope("/admin")
+                .wrap(PanelVersionGuard)
+                .service(meta_routes())
+                .service(web::scope("/v1").service(stats_routes())),
+        ),
+    )
+    .await;
+
+    // 旧面板：409 + PanelVersionIncompatible
+    let req = TestRequest::get()
+        .uri("/admin/v1/stats")
+        .insert_header(("X-Panel-Version", "0.0.1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body["error"]["code"],
+        ErrorCode::PanelVersionIncompatible as i32
+    );
+
+    // 兼容面板与未携带版本头的调用方不受影响
+    let req = TestRequest::get()
+        .uri("/admin/v1/stats")
+        .insert_header(("X-Panel-Version", env!("CARGO_PKG_VERSION")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    let req = TestRequest::get().uri("/admin/v1/stats").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // 元数据端点始终可访问，旧面板据此得知需要刷新
+    let req = TestRequest::get()
+        .uri("/admin/meta/version")
+        .insert_header(("X-Panel-Version", "0.0.1"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
@@ -1,7 +1,8 @@
 //! Analytics 模块测试
 //!
 //! 覆盖 ClickAggregation、ClickDetail、ClickManager、
-//! aggregate_click_details、RollupManager 和 DataRetentionTask。
+//! aggregate_click_details、RollupManager、DataRetentionTask、AnomalyDetectionTask、
+//! redirect 耗时采样、数据导出、数据订正、周 / 月汇总和链接统计级别。
 
 use std::sync::{Arc, Once};
 
@@ -11,9 +12,10 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use tempfile::TempDir;
 use tokio::time::Duration as TokioDuration;
 
+use shortlinker::analytics::anomaly::AnomalyKind;
 use shortlinker::analytics::{
-    ClickAggregation, ClickDetail, ClickManager, ClickSink, DataRetentionTask, DetailedClickSink,
-    RollupManager, aggregate_click_details,
+    AnomalyDetectionTask, ClickAggregation, ClickDetail, ClickManager, ClickSink,
+    DataRetentionTask, DetailedClickSink, RollupManager, aggregate_click_details,
 };
 use shortlinker::config::init_config;
 use shortlinker::config::runtime_config::init_runtime_config;
@@ -487,3 +489,905 @@ mod retention_tests {
         assert_eq!(report.daily_stats_deleted, 0);
     }
 }
+
+// =============================================================================
+// Redirect 耗时采样测试
+// =============================================================================
+
+mod redirect_timing_tests {
+    use super::*;
+    use shortlinker::analytics::{RedirectTiming, RedirectTimingSink};
+    use shortlinker::services::{AnalyticsService, GroupBy, TimingGroupBy};
+
+    fn timing(minutes_ago: i64, status: u16, total_us: u32, db_us: Option<u32>) -> RedirectTiming {
+        RedirectTiming {
+            code: "abc".to_string(),
+            recorded_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            status,
+            total_us,
+            cache_us: total_us / 2,
+            bloom_us: Some(2),
+            db_us,
+            geo_us: None,
+            enqueue_us: Some(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timing_percentiles_by_phase_and_status() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let mut samples: Vec<RedirectTiming> =
+            (1..=100).map(|i| timing(5, 307, i * 10, None)).collect();
+        samples.push(timing(5, 404, 5_000, Some(4_000)));
+        storage.log_timings_batch(samples).await.unwrap();
+
+        let service = AnalyticsService::new(storage);
+        let end = Utc::now();
+        let start = end - chrono::Duration::hours(1);
+
+        let trends = service
+            .get_redirect_timings(start, end, 50.0, TimingGroupBy::Phase, GroupBy::Day)
+            .await
+            .unwrap();
+        assert_eq!(trends.sample_count, 101);
+        assert!(!trends.truncated);
+        assert!(!trends.labels.is_empty());
+        let names: Vec<&str> = trends.series.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["total", "cache", "bloom", "db", "geo", "enqueue"]);
+        let series = |name: &str| trends.series.iter().find(|s| s.name == name).unwrap();
+        let last = trends.labels.len() - 1;
+        assert_eq!(series("total").values[last], Some(510));
+        assert_eq!(series("db").samples[last], 1);
+        assert_eq!(series("geo").values[last], None);
+
+        let by_status = service
+            .get_redirect_timings(start, end, 99.0, TimingGroupBy::Status, GroupBy::Day)
+            .await
+            .unwrap();
+        let names: Vec<&str> = by_status.series.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["307", "404"]);
+
+        assert!(
+            service
+                .get_redirect_timings(start, end, 0.0, TimingGroupBy::Phase, GroupBy::Day)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retention_removes_old_timings() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        storage
+            .log_timings_batch(vec![
+                timing(8 * 24 * 60, 307, 100, None),
+                timing(5, 307, 100, None),
+            ])
+            .await
+            .unwrap();
+
+        let rollup = Arc::new(RollupManager::new(storage.clone()));
+        let report = DataRetentionTask::new(storage.clone(), rollup)
+            .run_cleanup()
+            .await
+            .unwrap();
+        assert_eq!(report.timings_deleted, 1);
+
+        let remaining = migration::entities::RedirectTimingEntity::find()
+            .all(storage.get_db())
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+}
+
+// =============================================================================
+// AnomalyDetectionTask 测试
+// =============================================================================
+
+mod anomaly_detection_tests {
+    use super::*;
+    use chrono::{DateTime, Duration};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_detects_spike_and_drop_from_hourly_rollups() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let manager = RollupManager::new(storage.clone());
+
+        // 评估 11:00 这一小时；默认小时汇总保留 7 天，基线取前 6 天的 11:00
+        let now = at("2030-01-08T12:05:00Z");
+        let hour = at("2030-01-08T11:00:00Z");
+        for days in 1..=6 {
+            let bucket = hour - Duration::days(days);
+            let baseline = vec![
+                ("steady".to_string(), 100usize),
+                ("spiky".to_string(), 20usize),
+                ("normal".to_string(), 100usize),
+            ];
+            manager
+                .increment_hourly_counts(&baseline, bucket)
+                .await
+                .unwrap();
+        }
+        // steady 归零（无记录），spiky 被刷量，normal 正常
+        let current = vec![
+            ("spiky".to_string(), 500usize),
+            ("normal".to_string(), 98usize),
+        ];
+        manager
+            .increment_hourly_counts(&current, hour)
+            .await
+            .unwrap();
+
+        let mut task = AnomalyDetectionTask::new(storage, NoopMetrics::arc());
+        let mut alerts = task.run_once(now).await.unwrap();
+        alerts.sort_by(|a, b| a.code.cmp(&b.code));
+
+        assert_eq!(alerts.len(), 2, "alerts: {:?}", alerts);
+        assert_eq!(alerts[0].code, "spiky");
+        assert_eq!(alerts[0].kind, AnomalyKind::Spike);
+        assert_eq!(alerts[0].clicks, 500);
+        assert_eq!(alerts[1].code, "steady");
+        assert_eq!(alerts[1].kind, AnomalyKind::Drop);
+        assert_eq!(alerts[1].clicks, 0);
+        assert_eq!(alerts[1].hour, hour);
+
+        // 冷却期内重复评估不再告警
+        let again = task.run_once(now + Duration::minutes(1)).await.unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_data_no_alerts() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let mut task = AnomalyDetectionTask::new(storage, NoopMetrics::arc());
+        let alerts = task.run_once(Utc::now()).await.unwrap();
+        assert!(alerts.is_empty());
+    }
+}
+
+// =============================================================================
+// 导出测试
+// =============================================================================
+
+mod export_tests {
+    use super::*;
+    use chrono::{DateTime, Duration, NaiveDate};
+    use migration::entities::{click_log, click_stats_daily, click_stats_hourly};
+    use sea_orm::PaginatorTrait;
+    use shortlinker::analytics::export::{
+        CountsEncoding, ExportFormat, ExportOptions, ExportTable, export_analytics,
+    };
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    /// 三天内 30 次点击，外加一条范围外的点击
+    async fn seed(storage: &Arc<SeaOrmStorage>) {
+        let start = at("2030-01-01T00:30:00Z");
+        let mut details = Vec::new();
+        for i in 0..30i64 {
+            let mut detail = ClickDetail::new(["docs", "blog", "shop"][i as usize % 3].to_string());
+            detail.timestamp = start + Duration::hours(i * 2);
+            detail.referrer = Some(format!("https://ref{}.example.com", i % 4));
+            detail.country = Some(["CN", "US"][i as usize % 2].to_string());
+            detail.source = Some("direct".to_string());
+            details.push(detail);
+        }
+        let mut outside = ClickDetail::new("docs".to_string());
+        outside.timestamp = at("2030-02-01T00:00:00Z");
+        details.push(outside);
+        storage.log_clicks_batch(details).await.unwrap();
+
+        let rollup = RollupManager::new(storage.clone());
+        for day in 1..=3 {
+            rollup
+                .rollup_hourly_to_daily(NaiveDate::from_ymd_opt(2030, 1, day).unwrap())
+                .await
+                .unwrap();
+        }
+    }
+
+    fn options(table: ExportTable, format: ExportFormat, counts: CountsEncoding) -> ExportOptions {
+        ExportOptions {
+            table,
+            start: at("2030-01-01T00:00:00Z"),
+            end: at("2030-01-03T23:59:59Z"),
+            format,
+            counts,
+        }
+    }
+
+    async fn expected_rows(storage: &SeaOrmStorage, table: ExportTable) -> u64 {
+        let opts = options(table, ExportFormat::Csv, CountsEncoding::Json);
+        let db = storage.get_db();
+        match table {
+            ExportTable::ClickLog => click_log::Entity::find()
+                .filter(click_log::Column::ClickedAt.between(opts.start, opts.end))
+                .count(db)
+                .await
+                .unwrap(),
+            ExportTable::Daily => click_stats_daily::Entity::find()
+                .filter(
+                    click_stats_daily::Column::DayBucket
+                        .between(opts.start.date_naive(), opts.end.date_naive()),
+                )
+                .count(db)
+                .await
+                .unwrap(),
+            ExportTable::Hourly => click_stats_hourly::Entity::find()
+                .filter(click_stats_hourly::Column::HourBucket.between(opts.start, opts.end))
+                .count(db)
+                .await
+                .unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_matches_sql_count() {
+        let (storage, td) = create_temp_storage().await;
+        seed(&storage).await;
+
+        for table in [
+            ExportTable::ClickLog,
+            ExportTable::Daily,
+            ExportTable::Hourly,
+        ] {
+            let path = td.path().join(format!("{}.csv", table.table_name()));
+            let file = std::fs::File::create(&path).unwrap();
+            let summary = export_analytics(
+                &storage,
+                &options(table, ExportFormat::Csv, CountsEncoding::Json),
+                file,
+            )
+            .await
+            .unwrap();
+
+            let expected = expected_rows(&storage, table).await;
+            assert!(expected > 0, "{} 应有数据", table.table_name());
+            assert_eq!(summary.rows, expected, "{}", table.table_name());
+
+            let mut reader = csv::Reader::from_path(&path).unwrap();
+            assert_eq!(reader.records().count() as u64, expected);
+        }
+        assert_eq!(
+            expected_rows(&storage, ExportTable::ClickLog).await,
+            30,
+            "范围外的点击不应导出"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_counts_rejected_for_csv() {
+        let (storage, td) = create_temp_storage().await;
+        let file = std::fs::File::create(td.path().join("daily.csv")).unwrap();
+        let result = export_analytics(
+            &storage,
+            &options(
+                ExportTable::Daily,
+                ExportFormat::Csv,
+                CountsEncoding::Nested,
+            ),
+            file,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_parquet_export_round_trip() {
+        use arrow_schema::{DataType, TimeUnit};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let (storage, td) = create_temp_storage().await;
+        seed(&storage).await;
+
+        let read_back = |path: &std::path::Path| {
+            let builder =
+                ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path).unwrap())
+                    .unwrap();
+            let schema = builder.schema().clone();
+            let rows: usize = builder
+                .build()
+                .unwrap()
+                .map(|batch| batch.unwrap().num_rows())
+                .sum();
+            (schema, rows as u64)
+        };
+
+        let path = td.path().join("clicks.parquet");
+        export_analytics(
+            &storage,
+            &options(
+                ExportTable::ClickLog,
+                ExportFormat::Parquet,
+                CountsEncoding::Json,
+            ),
+            std::fs::File::create(&path).unwrap(),
+        )
+        .await
+        .unwrap();
+        let (schema, rows) = read_back(&path);
+        assert_eq!(rows, expected_rows(&storage, ExportTable::ClickLog).await);
+        assert_eq!(
+            schema.field_with_name("clicked_at").unwrap().data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert!(matches!(
+            schema.field_with_name("short_code").unwrap().data_type(),
+            DataType::Dictionary(_, _)
+        ));
+
+        let path = td.path().join("daily.parquet");
+        export_analytics(
+            &storage,
+            &options(
+                ExportTable::Daily,
+                ExportFormat::Parquet,
+                CountsEncoding::Nested,
+            ),
+            std::fs::File::create(&path).unwrap(),
+        )
+        .await
+        .unwrap();
+        let (schema, rows) = read_back(&path);
+        assert_eq!(rows, expected_rows(&storage, ExportTable::Daily).await);
+        assert_eq!(
+            schema.field_with_name("day_bucket").unwrap().data_type(),
+            &DataType::Date32
+        );
+        let DataType::List(item) = schema.field_with_name("top_countries").unwrap().data_type()
+        else {
+            panic!("top_countries 应为 list<struct>");
+        };
+        assert!(matches!(item.data_type(), DataType::Struct(fields) if fields.len() == 2));
+    }
+}
+
+// =============================================================================
+// 数据订正测试
+// =============================================================================
+
+mod amend_tests {
+    use super::*;
+    use chrono::{Duration, NaiveTime};
+    use migration::entities::{analytics_amendment, click_log, click_stats_daily};
+    use sea_orm::PaginatorTrait;
+    use shortlinker::analytics::amend::{AmendRequest, ClickAmender};
+    use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+    #[tokio::test]
+    async fn test_amend_removes_matching_clicks_and_records_audit() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+
+        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        let base = yesterday
+            .and_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+            .and_utc();
+        storage
+            .set(ShortLink {
+                code: "spam".to_string(),
+                target: "https://example.com".to_string(),
+                created_at: Utc::now(),
+                expires_at: None,
+                password: None,
+                click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+            })
+            .await
+            .unwrap();
+
+        // 3 次来自 203.0.113.0/24 的刷量 + 2 次正常点击
+        let mut details = Vec::new();
+        for (i, ip) in [
+            "203.0.113.1",
+            "203.0.113.2",
+            "198.51.100.7",
+            "203.0.113.9",
+            "192.0.2.1",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut detail = ClickDetail::new("spam".to_string());
+            detail.timestamp = base + Duration::minutes(i as i64 * 20);
+            detail.ip_address = Some(ip.to_string());
+            details.push(detail);
+        }
+        storage.log_clicks_batch(details).await.unwrap();
+        storage
+            .flush_clicks(vec![("spam".to_string(), 5)])
+            .await
+            .unwrap();
+        RollupManager::new(storage.clone())
+            .rollup_hourly_to_daily(yesterday)
+            .await
+            .unwrap();
+
+        let request = AmendRequest {
+            code: "spam".to_string(),
+            start: yesterday.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            end: yesterday.and_hms_opt(23, 59, 59).unwrap().and_utc(),
+            ip_cidrs: vec!["203.0.113.0/24".to_string()],
+            operator: Some("tester".to_string()),
+        };
+        let amender = ClickAmender::new(storage.clone());
+        let db = storage.get_db();
+
+        // dry-run 只报告，不写入
+        let report = amender.run(&request, true).await.unwrap();
+        assert_eq!(report.removed_rows, 3);
+        assert_eq!(report.detail_rows.before, 5);
+        assert_eq!(report.detail_rows.after, 2);
+        assert_eq!(report.hourly_clicks.before, 5);
+        assert_eq!(report.hourly_clicks.after, 2);
+        assert_eq!(click_log::Entity::find().count(db).await.unwrap(), 5);
+        assert_eq!(
+            analytics_amendment::Entity::find().count(db).await.unwrap(),
+            0
+        );
+
+        let report = amender.run(&request, false).await.unwrap();
+        assert_eq!(report.removed_rows, 3);
+        assert_eq!(report.link_clicks.before, 5);
+        assert_eq!(report.link_clicks.after, 2);
+        assert_eq!(report.hourly_clicks.after, 2);
+        assert_eq!(report.daily_clicks.after, 2);
+        assert_eq!(report.rebuilt_days, vec![yesterday]);
+        assert!(report.failed_days.is_empty());
+
+        let remaining: Vec<String> = click_log::Entity::find()
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|row| row.ip_address)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|ip| !ip.starts_with("203.0.113.")));
+
+        let daily = click_stats_daily::Entity::find()
+            .filter(click_stats_daily::Column::ShortCode.eq("spam"))
+            .filter(click_stats_daily::Column::DayBucket.eq(yesterday))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(daily.click_count, 2);
+        assert_eq!(storage.get("spam").await.unwrap().unwrap().click, 2);
+
+        let audits = analytics_amendment::Entity::find().all(db).await.unwrap();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].short_code, "spam");
+        assert_eq!(audits[0].removed_rows, 3);
+        assert_eq!(audits[0].clicks_before, 5);
+        assert_eq!(audits[0].clicks_after, 2);
+        assert_eq!(audits[0].operator.as_deref(), Some("tester"));
+
+        // 重复执行时没有可删除的行
+        let report = amender.run(&request, false).await.unwrap();
+        assert_eq!(report.removed_rows, 0);
+    }
+}
+
+// =============================================================================
+// 周 / 月汇总测试
+// =============================================================================
+
+mod period_rollup_tests {
+    use super::*;
+    use chrono::{DateTime, Duration, NaiveDate};
+    use migration::entities::{click_stats_daily, click_stats_monthly, click_stats_weekly};
+    use sea_orm::{ActiveModelTrait, ActiveValue::Set, QueryOrder};
+    use shortlinker::analytics::period::{month_start_of, week_start_of};
+    use shortlinker::analytics::{PeriodGranularity, WeekStart};
+    use shortlinker::services::{AnalyticsService, GroupBy};
+
+    /// 两周前的周日（保证在天汇总保留期内且已结束）
+    fn recent_sunday() -> NaiveDate {
+        week_start_of(
+            Utc::now().date_naive() - Duration::days(14),
+            WeekStart::Sunday,
+        )
+    }
+
+    async fn seed_daily(storage: &SeaOrmStorage, code: &str, day: NaiveDate, clicks: i64) {
+        click_stats_daily::ActiveModel {
+            short_code: Set(code.to_string()),
+            day_bucket: Set(day),
+            click_count: Set(clicks),
+            top_referrers: Set(Some(format!(r#"[["direct",{}]]"#, clicks))),
+            ..Default::default()
+        }
+        .insert(storage.get_db())
+        .await
+        .unwrap();
+    }
+
+    async fn weekly_rows(storage: &SeaOrmStorage) -> Vec<(NaiveDate, i64)> {
+        click_stats_weekly::Entity::find()
+            .order_by_asc(click_stats_weekly::Column::WeekStart)
+            .all(storage.get_db())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.week_start, row.click_count))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_week_start_boundaries_and_rebuild() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let manager = RollupManager::new(storage.clone());
+
+        let sunday = recent_sunday();
+        let saturday = sunday - Duration::days(1);
+        let monday = sunday + Duration::days(1);
+        seed_daily(&storage, "wk", saturday, 2).await;
+        seed_daily(&storage, "wk", sunday, 3).await;
+        seed_daily(&storage, "wk", monday, 5).await;
+
+        // 周一起始：周六、周日属于上一周
+        for day in [saturday, sunday, monday] {
+            manager
+                .refresh_periods(day, WeekStart::Monday)
+                .await
+                .unwrap();
+        }
+        let previous_monday = sunday - Duration::days(6);
+        assert_eq!(
+            weekly_rows(&storage).await,
+            vec![(previous_monday, 5), (monday, 5)]
+        );
+
+        // 重复刷新结果不变
+        manager
+            .refresh_periods(monday, WeekStart::Monday)
+            .await
+            .unwrap();
+        assert_eq!(
+            weekly_rows(&storage).await,
+            vec![(previous_monday, 5), (monday, 5)]
+        );
+
+        // 趋势查询按周起始日期作为标签
+        let svc = AnalyticsService::new(storage.clone());
+        let start = saturday.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let end = monday.and_hms_opt(23, 59, 59).unwrap().and_utc();
+        let trend = svc.get_trends_v2(start, end, GroupBy::Week).await.unwrap();
+        assert_eq!(
+            trend.labels,
+            vec![previous_monday.to_string(), monday.to_string()]
+        );
+        assert_eq!(trend.values, vec![5, 5]);
+        let link_trend = svc
+            .get_link_trends_v2("wk", start, end, GroupBy::Week)
+            .await
+            .unwrap();
+        assert_eq!(link_trend.values, vec![5, 5]);
+
+        // 改为周日起始后重算：按周一对齐的旧行被替换
+        let report = manager
+            .rebuild_periods(
+                PeriodGranularity::Week,
+                Some(saturday),
+                monday,
+                WeekStart::Sunday,
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.deleted, 2);
+        assert_eq!(report.periods, vec![sunday - Duration::days(7), sunday]);
+        assert!(report.skipped_from.is_none());
+        assert_eq!(
+            weekly_rows(&storage).await,
+            vec![(sunday - Duration::days(7), 2), (sunday, 8)]
+        );
+
+        // 配置仍为周一起始时，按周日对齐的行被忽略
+        let trend = svc.get_trends_v2(start, end, GroupBy::Week).await.unwrap();
+        assert!(trend.labels.is_empty());
+
+        // 月汇总与周起始日无关
+        let months = click_stats_monthly::Entity::find()
+            .all(storage.get_db())
+            .await
+            .unwrap();
+        let mut expected: Vec<(NaiveDate, i64)> = Vec::new();
+        for (day, clicks) in [(saturday, 2), (sunday, 3), (monday, 5)] {
+            match expected.iter_mut().find(|(m, _)| *m == month_start_of(day)) {
+                Some((_, total)) => *total += clicks,
+                None => expected.push((month_start_of(day), clicks)),
+            }
+        }
+        let mut actual: Vec<(NaiveDate, i64)> = months
+            .into_iter()
+            .map(|row| (row.month_start, row.click_count))
+            .collect();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_offset_timestamps_roll_up_by_utc_day() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let manager = RollupManager::new(storage.clone());
+
+        // 本地（UTC-8）周日 23:30 = UTC 周一 07:30
+        let sunday = recent_sunday();
+        let local = format!("{}T23:30:00-08:00", sunday);
+        let timestamp = DateTime::parse_from_rfc3339(&local)
+            .unwrap()
+            .with_timezone(&Utc);
+        let monday = timestamp.date_naive();
+        assert_eq!(monday, sunday + Duration::days(1));
+
+        manager
+            .increment_hourly_counts(&[("tz".to_string(), 4)], timestamp)
+            .await
+            .unwrap();
+        manager.rollup_hourly_to_daily(monday).await.unwrap();
+
+        manager
+            .refresh_periods(monday, WeekStart::Monday)
+            .await
+            .unwrap();
+        assert_eq!(weekly_rows(&storage).await, vec![(monday, 4)]);
+
+        manager
+            .rebuild_periods(
+                PeriodGranularity::Week,
+                Some(monday),
+                monday,
+                WeekStart::Sunday,
+            )
+            .await
+            .unwrap();
+        assert_eq!(weekly_rows(&storage).await, vec![(sunday, 4)]);
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_skips_periods_before_daily_retention() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        let manager = RollupManager::new(storage);
+
+        let today = Utc::now().date_naive();
+        let report = manager
+            .rebuild_periods(
+                PeriodGranularity::Month,
+                Some(today - Duration::days(800)),
+                today,
+                WeekStart::Monday,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            report.skipped_from,
+            Some(month_start_of(today - Duration::days(800)))
+        );
+        // 第一个重算的月份完整落在保留期内
+        let first = report.periods[0];
+        assert!(first >= today - Duration::days(365));
+        assert_eq!(first, month_start_of(first));
+    }
+}
+
+// =============================================================================
+// 链接统计级别测试
+// =============================================================================
+
+mod analytics_level_tests {
+    use super::*;
+    use migration::entities::{click_log, click_stats_hourly};
+    use sea_orm::PaginatorTrait;
+    use shortlinker::analytics::RawClickEvent;
+    use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+    const LEVELS: [(&str, AnalyticsLevel); 4] = [
+        ("lvl-none", AnalyticsLevel::None),
+        ("lvl-count", AnalyticsLevel::CountOnly),
+        ("lvl-aggregate", AnalyticsLevel::Aggregate),
+        ("lvl-full", AnalyticsLevel::Full),
+    ];
+
+    #[tokio::test]
+    async fn test_levels_control_what_is_written() {
+        init_test_runtime_config().await;
+        let (storage, _td) = create_temp_storage().await;
+        for (code, level) in LEVELS {
+            storage
+                .set(ShortLink {
+                    code: code.to_string(),
+                    target: "https://example.com".to_string(),
+                    created_at: Utc::now(),
+                    expires_at: None,
+                    password: None,
+                    click: 0,
+                    created_via: CreatedVia::Api,
+                    analytics_level: level,
+                    extras: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let (manager, rx) = ClickManager::with_detailed_logging(
+            storage.clone(),
+            storage.clone(),
+            TokioDuration::from_secs(60),
+            100,
+            NoopMetrics::arc(),
+        );
+
+        for (code, level) in LEVELS {
+            let sent = manager.send_raw_event(RawClickEvent {
+                code: code.to_string(),
+                query: None,
+                referrer: Some("https://referrer.example".to_string()),
+                user_agent: None,
+                ip: Some("192.0.2.1".to_string()),
+                analytics_level: level,
+            });
+            // 只有需要明细的级别进入 channel
+            assert_eq!(sent, level == AnalyticsLevel::Full, "level {}", level);
+        }
+
+        // 模拟事件处理器写入明细
+        let details: Vec<ClickDetail> = rx
+            .try_iter()
+            .map(|event| ClickDetail::new(event.code))
+            .collect();
+        assert_eq!(details.len(), 1);
+        storage.log_clicks_batch(details).await.unwrap();
+        manager.flush().await;
+
+        let db = storage.get_db();
+        for (code, level) in LEVELS {
+            let clicks = storage.get(code).await.unwrap().unwrap().click;
+            let hourly = click_stats_hourly::Entity::find()
+                .filter(click_stats_hourly::Column::ShortCode.eq(code))
+                .count(db)
+                .await
+                .unwrap();
+            let logs = click_log::Entity::find()
+                .filter(click_log::Column::ShortCode.eq(code))
+                .count(db)
+                .await
+                .unwrap();
+
+            let expected = match level {
+                AnalyticsLevel::None => (0, 0, 0),
+                AnalyticsLevel::CountOnly => (1, 0, 0),
+                AnalyticsLevel::Aggregate => (1, 1, 0),
+                _ => (1, 1, 1),
+            };
+            assert_eq!((clicks, hourly, logs), expected, "level {}", level);
+        }
+    }
+}
+
+// =============================================================================
+// 自定义 query 参数测试
+// =============================================================================
+
+mod query_param_tests {
+    use super::*;
+    use chrono::{DateTime, Duration};
+    use migration::entities::{click_log, click_stats_hourly};
+    use shortlinker::analytics::query_params::{extract_captured_params, parse_param_counts};
+    use shortlinker::services::AnalyticsService;
+
+    fn click(code: &str, at: DateTime<Utc>, query: &str) -> ClickDetail {
+        let mut detail = ClickDetail::new(code.to_string());
+        detail.timestamp = at;
+        detail.query_params =
+            extract_captured_params(query, &["ch".to_string(), "utm_*".to_string()]);
+        detail
+    }
+
+    #[tokio::test]
+    async fn test_params_recorded_and_grouped() {
+        let (storage, _td) = create_temp_storage().await;
+        let hour = Utc::now() - Duration::hours(1);
+
+        // 两批写入同一小时：第二批走已有汇总行的合并路径
+        storage
+            .log_clicks_batch(vec![
+                click("promo", hour, "ch=wechat&utm_source=news&token=secret"),
+                click("promo", hour, "ch=wechat&utm_source=mail"),
+            ])
+            .await
+            .unwrap();
+        storage
+            .log_clicks_batch(vec![
+                click("promo", hour, "ch=weibo&utm_source=news"),
+                click("promo", hour, "utm_source=news"),
+                click("promo", hour, ""),
+            ])
+            .await
+            .unwrap();
+
+        let db = storage.get_db();
+        let logs = click_log::Entity::find()
+            .filter(click_log::Column::ShortCode.eq("promo"))
+            .all(db)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 5);
+        assert!(logs.iter().all(|log| {
+            log.query_params
+                .as_deref()
+                .is_none_or(|json| !json.contains("token"))
+        }));
+        assert_eq!(
+            logs.iter().filter(|log| log.query_params.is_none()).count(),
+            1
+        );
+
+        let row = click_stats_hourly::Entity::find()
+            .filter(click_stats_hourly::Column::ShortCode.eq("promo"))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let counts = parse_param_counts(&row.param_counts);
+        assert_eq!(counts["ch"]["wechat"], 2);
+        assert_eq!(counts["ch"]["weibo"], 1);
+        assert_eq!(counts["utm_source"]["news"], 3);
+
+        let svc = AnalyticsService::new(storage.clone());
+        let (start, end) = (hour - Duration::days(1), hour + Duration::days(1));
+
+        // 分组：读取小时汇总
+        let stats = svc
+            .get_link_param_stats("promo", "ch", None, start, end, 20)
+            .await
+            .unwrap();
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.values[0].name, "wechat");
+        assert_eq!(stats.values[0].count, 2);
+        assert!(!stats.truncated);
+
+        // 过滤 + 分组：扫描明细
+        let stats = svc
+            .get_link_param_stats("promo", "ch", Some(("utm_source", "news")), start, end, 20)
+            .await
+            .unwrap();
+        let values: Vec<(&str, u64)> = stats
+            .values
+            .iter()
+            .map(|v| (v.name.as_str(), v.count))
+            .collect();
+        assert_eq!(values, [("wechat", 1), ("weibo", 1)]);
+        assert_eq!(stats.filter, Some(("utm_source".into(), "news".into())));
+
+        // LIKE 通配符按字面匹配
+        let stats = svc
+            .get_link_param_stats("promo", "ch", Some(("utm_source", "n%")), start, end, 20)
+            .await
+            .unwrap();
+        assert_eq!(stats.total, 0);
+
+        assert!(
+            svc.get_link_param_stats("promo", "", None, start, end, 20)
+                .await
+                .is_err()
+        );
+    }
+}
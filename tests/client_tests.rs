@@ -10,6 +10,7 @@ use shortlinker::config::runtime_config::init_runtime_config;
 use shortlinker::metrics::NoopMetrics;
 use shortlinker::services::ImportLinkItemRich;
 use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia};
 use std::sync::{Arc, Once};
 use tempfile::TempDir;
 
@@ -88,6 +89,9 @@ async fn test_get_link_found() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -97,6 +101,7 @@ async fn test_get_link_found() {
     let link = result.unwrap();
     assert_eq!(link.code, "get-test");
     assert_eq!(link.target, "https://example.com");
+    assert_eq!(link.created_via, CreatedVia::Cli);
 }
 
 #[tokio::test]
@@ -116,6 +121,9 @@ async fn test_get_link_returns_correct_target() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -150,6 +158,9 @@ async fn test_get_stats_with_links() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -160,6 +171,9 @@ async fn test_get_stats_with_links() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -179,6 +193,9 @@ async fn test_get_stats_total_clicks_starts_at_zero() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -291,6 +308,9 @@ async fn test_create_get_delete_lifecycle() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -323,6 +343,9 @@ async fn test_get_link_after_update() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -333,6 +356,8 @@ async fn test_get_link_after_update() {
             "https://example.com/new".into(),
             None,
             None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -353,6 +378,8 @@ fn make_import_item(code: &str, target: &str) -> ImportLinkItemRich {
         expires_at: None,
         password: None,
         click_count: 0,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
         row_num: None,
     }
 }
@@ -380,6 +407,9 @@ async fn test_list_links_pagination() {
                 false,
                 None,
                 None,
+                None,
+                None,
+                false,
             )
             .await
             .unwrap();
@@ -400,6 +430,9 @@ async fn test_list_links_with_search() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -410,6 +443,9 @@ async fn test_list_links_with_search() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -433,6 +469,9 @@ async fn test_list_links_page_beyond_total() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -457,6 +496,9 @@ async fn test_batch_delete_all_exist() {
                 false,
                 None,
                 None,
+                None,
+                None,
+                false,
             )
             .await
             .unwrap();
@@ -481,6 +523,9 @@ async fn test_batch_delete_some_not_found() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -535,6 +580,9 @@ async fn test_import_links_skip_mode() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -560,6 +608,9 @@ async fn test_import_links_overwrite_mode() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -570,6 +621,7 @@ async fn test_import_links_overwrite_mode() {
 
     let link = client.get_link("ow".into()).await.unwrap().unwrap();
     assert_eq!(link.target, "https://example.com/replaced");
+    assert_eq!(link.created_via, CreatedVia::Import);
 }
 
 #[tokio::test]
@@ -617,6 +669,9 @@ async fn test_export_links_round_trip() {
                 false,
                 None,
                 None,
+                None,
+                None,
+                false,
             )
             .await
             .unwrap();
@@ -640,7 +695,16 @@ async fn test_export_links_round_trip() {
 async fn test_create_link_auto_generate_code() {
     let (client, _td) = create_test_link_client().await;
     let result = client
-        .create_link(None, "https://example.com/auto".into(), false, None, None)
+        .create_link(
+            None,
+            "https://example.com/auto".into(),
+            false,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .await
         .unwrap();
     assert!(result.generated_code);
@@ -657,6 +721,9 @@ async fn test_create_link_duplicate_without_force() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -668,6 +735,9 @@ async fn test_create_link_duplicate_without_force() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await;
     assert!(
@@ -686,6 +756,9 @@ async fn test_create_link_duplicate_with_force() {
             false,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -697,6 +770,9 @@ async fn test_create_link_duplicate_with_force() {
             true,
             None,
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -713,6 +789,9 @@ async fn test_create_link_with_expiration() {
             false,
             Some("2099-12-31T23:59:59Z".into()),
             None,
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -729,6 +808,9 @@ async fn test_create_link_with_password() {
             false,
             None,
             Some("secret123".into()),
+            None,
+            None,
+            false,
         )
         .await
         .unwrap();
@@ -811,6 +893,8 @@ async fn test_service_error_propagates_through_client() {
             "https://example.com".into(),
             None,
             None,
+            None,
+            None,
         )
         .await;
     assert!(result.is_err());
@@ -0,0 +1,261 @@
+//! 删除后短码冷却期测试
+//!
+//! 运行时配置是进程级单例，本文件所有测试共用 `links.code_reuse_cooldown_days = 30`，
+//! 各测试使用互不相同的短码。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Once};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+use shortlinker::config::init_config;
+use shortlinker::config::keys;
+use shortlinker::config::runtime_config::{get_runtime_config, init_runtime_config};
+use shortlinker::errors::ShortlinkerError;
+use shortlinker::metrics::NoopMetrics;
+use shortlinker::services::{
+    CreateLinkRequest, LinkCache, LinkCacheHealth, LinkCacheLookup, LinkService,
+};
+use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+static INIT: Once = Once::new();
+static TEST_DIR: std::sync::OnceLock<TempDir> = std::sync::OnceLock::new();
+static RT_INIT: tokio::sync::OnceCell<()> = tokio::sync::OnceCell::const_new();
+static STORAGE: std::sync::OnceLock<Arc<SeaOrmStorage>> = std::sync::OnceLock::new();
+
+async fn init_test_env() {
+    INIT.call_once(init_config);
+
+    RT_INIT
+        .get_or_init(|| async {
+            let temp_dir = TempDir::new().expect("Failed to create temp dir");
+            let db_path = temp_dir.path().join("cooldown_test.db");
+            let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+            let db = aster_forge_db::connect(&aster_forge_db::DatabaseConfig::new(&db_url))
+                .await
+                .expect("Failed to connect to SQLite");
+            run_migrations(&db.clone())
+                .await
+                .expect("Failed to run migrations");
+
+            init_runtime_config(db)
+                .await
+                .expect("Failed to init runtime config");
+            get_runtime_config()
+                .set(keys::LINKS_CODE_REUSE_COOLDOWN_DAYS, "30")
+                .await
+                .expect("Failed to enable cooldown");
+
+            let storage = Arc::new(
+                SeaOrmStorage::new(&db_url, "sqlite", NoopMetrics::arc())
+                    .await
+                    .expect("Failed to create storage"),
+            );
+            let _ = STORAGE.set(storage);
+            let _ = TEST_DIR.set(temp_dir);
+        })
+        .await;
+}
+
+async fn create_service() -> (LinkService, Arc<SeaOrmStorage>) {
+    init_test_env().await;
+    let storage = STORAGE.get().expect("Storage not initialized").clone();
+    let service = LinkService::new(storage.clone(), Arc::new(MockCache::default()));
+    (service, storage)
+}
+
+/// Minimal in-memory cache
+#[derive(Default)]
+struct MockCache {
+    data: RwLock<HashMap<String, ShortLink>>,
+    not_found: RwLock<HashSet<String>>,
+}
+
+#[async_trait]
+impl LinkCache for MockCache {
+    async fn get(&self, key: &str) -> LinkCacheLookup {
+        if self.not_found.read().await.contains(key) {
+            return LinkCacheLookup::NotFound;
+        }
+        match self.data.read().await.get(key) {
+            Some(link) => LinkCacheLookup::Found(link.clone()),
+            None => LinkCacheLookup::Miss,
+        }
+    }
+
+    async fn insert(&self, key: &str, value: ShortLink, _ttl_secs: Option<u64>) {
+        self.not_found.write().await.remove(key);
+        self.data.write().await.insert(key.to_string(), value);
+    }
+
+    async fn remove(&self, key: &str) {
+        self.data.write().await.remove(key);
+    }
+
+    async fn invalidate_all(&self) {
+        self.data.write().await.clear();
+        self.not_found.write().await.clear();
+    }
+
+    async fn rebuild_all(&self) -> shortlinker::errors::Result<()> {
+        self.invalidate_all().await;
+        Ok(())
+    }
+
+    async fn mark_not_found(&self, key: &str) {
+        self.not_found.write().await.insert(key.to_string());
+    }
+
+    async fn bloom_check(&self, _key: &str) -> bool {
+        true
+    }
+
+    async fn health_check(&self) -> LinkCacheHealth {
+        LinkCacheHealth {
+            status: "healthy".to_string(),
+            cache_type: "mock".to_string(),
+            bloom_filter_enabled: false,
+            negative_cache_enabled: true,
+            error: None,
+        }
+    }
+}
+
+fn create_request(code: &str, target: &str) -> CreateLinkRequest {
+    CreateLinkRequest {
+        code: Some(code.to_string()),
+        target: target.to_string(),
+        force: false,
+        expires_at: None,
+        password: None,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
+        override_cooldown: false,
+    }
+}
+
+#[tokio::test]
+async fn test_deleted_code_is_cooling_down() {
+    let (service, storage) = create_service().await;
+
+    service
+        .create_link(create_request("cool-a", "https://old.example.com"))
+        .await
+        .unwrap();
+    service.delete_link("cool-a").await.unwrap();
+
+    let retired = storage.get_retired_codes(&["cool-a"]).await.unwrap();
+    assert!(retired.contains_key("cool-a"));
+
+    let err = service
+        .create_link(create_request("cool-a", "https://new.example.com"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ShortlinkerError::LinkCodeCoolingDown(_)));
+    assert!(err.to_string().contains("until"), "{}", err);
+    assert!(storage.get("cool-a").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_code_is_reusable_after_cooldown() {
+    let (service, storage) = create_service().await;
+
+    storage
+        .retire_codes(&["cool-b".to_string()], Utc::now() - Duration::days(31))
+        .await
+        .unwrap();
+
+    service
+        .create_link(create_request("cool-b", "https://new.example.com"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_override_releases_code() {
+    let (service, storage) = create_service().await;
+
+    service
+        .create_link(create_request("cool-c", "https://old.example.com"))
+        .await
+        .unwrap();
+    service.delete_link("cool-c").await.unwrap();
+
+    let result = service
+        .create_link(CreateLinkRequest {
+            override_cooldown: true,
+            ..create_request("cool-c", "https://new.example.com")
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.link.target, "https://new.example.com");
+
+    let retired = storage.get_retired_codes(&["cool-c"]).await.unwrap();
+    assert!(retired.is_empty());
+}
+
+#[tokio::test]
+async fn test_batch_create_reports_cooling_code() {
+    let (service, _storage) = create_service().await;
+
+    service
+        .batch_delete_links(vec!["cool-d".to_string()])
+        .await
+        .unwrap();
+    service
+        .create_link(create_request("cool-e", "https://old.example.com"))
+        .await
+        .unwrap();
+    service
+        .batch_delete_links(vec!["cool-e".to_string()])
+        .await
+        .unwrap();
+
+    let result = service
+        .batch_create_links(vec![
+            create_request("cool-d", "https://d.example.com"),
+            create_request("cool-e", "https://e.example.com"),
+        ])
+        .await
+        .unwrap();
+
+    // cool-d 从未存在，删除失败不会进入冷却期
+    assert_eq!(result.success.len(), 1);
+    assert_eq!(result.success[0].code, "cool-d");
+    assert_eq!(result.failed.len(), 1);
+    assert_eq!(result.failed[0].code, "cool-e");
+    assert!(result.failed[0].reason.contains("until"));
+}
+
+#[tokio::test]
+async fn test_purge_retired_codes() {
+    let (_service, storage) = create_service().await;
+
+    storage
+        .retire_codes(&["cool-f".to_string()], Utc::now() - Duration::days(40))
+        .await
+        .unwrap();
+    storage
+        .retire_codes(&["cool-g".to_string()], Utc::now())
+        .await
+        .unwrap();
+
+    let purged = storage
+        .purge_retired_codes(Utc::now() - Duration::days(30))
+        .await
+        .unwrap();
+    assert!(purged >= 1);
+
+    let retired = storage
+        .get_retired_codes(&["cool-f", "cool-g"])
+        .await
+        .unwrap();
+    assert!(!retired.contains_key("cool-f"));
+    assert!(retired.contains_key("cool-g"));
+}
@@ -0,0 +1,216 @@
+//! 目标 URL 凭据策略测试
+//!
+//! 运行时配置是进程级单例，`links.credential_policy` 在唯一的测试里按顺序切换，
+//! 避免与其他测试并发修改。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Once};
+
+use async_trait::async_trait;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+use shortlinker::config::init_config;
+use shortlinker::config::keys;
+use shortlinker::config::runtime_config::{get_runtime_config, init_runtime_config};
+use shortlinker::errors::ShortlinkerError;
+use shortlinker::metrics::NoopMetrics;
+use shortlinker::services::{
+    CreateLinkRequest, ImportLinkItemRaw, LinkCache, LinkCacheHealth, LinkCacheLookup, LinkService,
+    UpdateLinkRequest, validate_import_rows,
+};
+use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+static INIT: Once = Once::new();
+
+async fn create_service() -> (LinkService, TempDir) {
+    INIT.call_once(init_config);
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let db_path = temp_dir.path().join("credential_policy_test.db");
+    let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+    let db = aster_forge_db::connect(&aster_forge_db::DatabaseConfig::new(&db_url))
+        .await
+        .expect("Failed to connect to SQLite");
+    run_migrations(&db.clone())
+        .await
+        .expect("Failed to run migrations");
+    init_runtime_config(db)
+        .await
+        .expect("Failed to init runtime config");
+
+    let storage = Arc::new(
+        SeaOrmStorage::new(&db_url, "sqlite", NoopMetrics::arc())
+            .await
+            .expect("Failed to create storage"),
+    );
+    let service = LinkService::new(storage, Arc::new(MockCache::default()));
+    (service, temp_dir)
+}
+
+/// Minimal in-memory cache
+#[derive(Default)]
+struct MockCache {
+    data: RwLock<HashMap<String, ShortLink>>,
+    not_found: RwLock<HashSet<String>>,
+}
+
+#[async_trait]
+impl LinkCache for MockCache {
+    async fn get(&self, key: &str) -> LinkCacheLookup {
+        if self.not_found.read().await.contains(key) {
+            return LinkCacheLookup::NotFound;
+        }
+        match self.data.read().await.get(key) {
+            Some(link) => LinkCacheLookup::Found(link.clone()),
+            None => LinkCacheLookup::Miss,
+        }
+    }
+
+    async fn insert(&self, key: &str, value: ShortLink, _ttl_secs: Option<u64>) {
+        self.not_found.write().await.remove(key);
+        self.data.write().await.insert(key.to_string(), value);
+    }
+
+    async fn remove(&self, key: &str) {
+        self.data.write().await.remove(key);
+    }
+
+    async fn invalidate_all(&self) {
+        self.data.write().await.clear();
+        self.not_found.write().await.clear();
+    }
+
+    async fn rebuild_all(&self) -> shortlinker::errors::Result<()> {
+        self.invalidate_all().await;
+        Ok(())
+    }
+
+    async fn mark_not_found(&self, key: &str) {
+        self.not_found.write().await.insert(key.to_string());
+    }
+
+    async fn bloom_check(&self, _key: &str) -> bool {
+        true
+    }
+
+    async fn health_check(&self) -> LinkCacheHealth {
+        LinkCacheHealth {
+            status: "healthy".to_string(),
+            cache_type: "mock".to_string(),
+            bloom_filter_enabled: false,
+            negative_cache_enabled: true,
+            error: None,
+        }
+    }
+}
+
+fn create_request(code: &str, target: &str) -> CreateLinkRequest {
+    CreateLinkRequest {
+        code: Some(code.to_string()),
+        target: target.to_string(),
+        force: false,
+        expires_at: None,
+        password: None,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
+        override_cooldown: false,
+    }
+}
+
+fn update_request(target: &str) -> UpdateLinkRequest {
+    UpdateLinkRequest {
+        target: target.to_string(),
+        expires_at: None,
+        password: None,
+        analytics_level: None,
+        extras: None,
+    }
+}
+
+fn import_row(code: &str, target: &str) -> ImportLinkItemRaw {
+    ImportLinkItemRaw {
+        code: code.to_string(),
+        target: target.to_string(),
+        created_at: "2025-01-01T00:00:00Z".to_string(),
+        expires_at: None,
+        password: None,
+        click_count: 0,
+        analytics_level: None,
+        extras: None,
+        row_num: Some(2),
+    }
+}
+
+async fn set_policy(policy: &str) {
+    get_runtime_config()
+        .set(keys::LINKS_CREDENTIAL_POLICY, policy)
+        .await
+        .expect("Failed to set credential policy");
+}
+
+#[tokio::test]
+async fn test_credential_policies_on_create_update_import() {
+    let (service, _td) = create_service().await;
+    let leaky = "https://user:pw@example.com/a?x=1&access_token=abc";
+
+    // warn（默认）：原样保存并返回 warning
+    let created = service
+        .create_link(create_request("cred-warn", leaky))
+        .await
+        .unwrap();
+    assert_eq!(created.link.target, leaky);
+    assert_eq!(created.warnings.len(), 1);
+    assert!(created.warnings[0].contains("'access_token'"));
+    assert!(!created.warnings[0].contains("abc"));
+
+    // strip：移除凭据后保存，仍返回 warning
+    set_policy("strip").await;
+    let created = service
+        .create_link(create_request("cred-strip", leaky))
+        .await
+        .unwrap();
+    assert_eq!(created.link.target, "https://example.com/a?x=1");
+    assert!(created.warnings[0].starts_with("removed credentials"));
+
+    let updated = service
+        .update_link_checked("cred-warn", update_request(leaky))
+        .await
+        .unwrap();
+    assert_eq!(updated.link.target, "https://example.com/a?x=1");
+    assert_eq!(updated.warnings.len(), 1);
+
+    let (valid, errors, warnings) = validate_import_rows(vec![import_row("cred-imp", leaky)]);
+    assert!(errors.is_empty());
+    assert_eq!(valid[0].target, "https://example.com/a?x=1");
+    assert_eq!(warnings[0].row_num, Some(2));
+
+    // reject：创建、更新、导入都报错
+    set_policy("reject").await;
+    let err = service
+        .create_link(create_request("cred-reject", leaky))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ShortlinkerError::LinkInvalidUrl(_)));
+    assert!(
+        service
+            .update_link("cred-strip", update_request(leaky))
+            .await
+            .is_err()
+    );
+    let (valid, errors, _) = validate_import_rows(vec![import_row("cred-imp2", leaky)]);
+    assert!(valid.is_empty());
+    assert_eq!(errors[0].row_num, Some(2));
+
+    // 不含凭据的目标不受影响
+    let created = service
+        .create_link(create_request("cred-clean", "https://example.com/a?x=1"))
+        .await
+        .unwrap();
+    assert!(created.warnings.is_empty());
+
+    set_policy("warn").await;
+}
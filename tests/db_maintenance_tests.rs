@@ -0,0 +1,73 @@
+//! 数据库维护测试
+//!
+//! 覆盖 SQLite 的轻量维护与 VACUUM 回收空间。
+
+use std::sync::Once;
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, DatabaseBackend};
+use tempfile::TempDir;
+
+use shortlinker::config::init_config;
+use shortlinker::metrics::NoopMetrics;
+use shortlinker::storage::backend::SeaOrmStorage;
+use shortlinker::storage::backend::maintenance::{database_size, maintain};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+static INIT: Once = Once::new();
+
+async fn sqlite_storage() -> (SeaOrmStorage, TempDir) {
+    INIT.call_once(init_config);
+    let td = TempDir::new().unwrap();
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        td.path().join("maintain.db").display()
+    );
+    let storage = SeaOrmStorage::new(&url, "sqlite", NoopMetrics::arc())
+        .await
+        .unwrap();
+    (storage, td)
+}
+
+#[tokio::test]
+async fn test_routine_maintenance() {
+    let (storage, _td) = sqlite_storage().await;
+
+    let report = maintain(storage.get_db(), false).await.unwrap();
+    assert_eq!(report.backend, DatabaseBackend::Sqlite);
+    assert_eq!(report.statements, ["PRAGMA optimize"]);
+    assert!(report.size_before.is_some_and(|size| size > 0));
+    assert!(report.size_after.is_some());
+}
+
+#[tokio::test]
+async fn test_full_maintenance_reclaims_space() {
+    let (storage, _td) = sqlite_storage().await;
+    let long_target = format!("https://example.com/{}", "x".repeat(2000));
+    for i in 0..500 {
+        storage
+            .set(ShortLink {
+                code: format!("bulk{}", i),
+                target: long_target.clone(),
+                created_at: Utc::now(),
+                expires_at: None,
+                password: None,
+                click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+            })
+            .await
+            .unwrap();
+    }
+    let db = storage.get_db();
+    db.execute_unprepared("DELETE FROM short_links")
+        .await
+        .unwrap();
+    let before = database_size(db).await.unwrap();
+
+    let report = maintain(db, true).await.unwrap();
+    assert_eq!(report.statements, ["VACUUM"]);
+    assert_eq!(report.size_before, Some(before));
+    assert!(report.size_after.unwrap() < before);
+}
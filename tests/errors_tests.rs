@@ -450,3 +450,51 @@ mod integration_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod localization_tests {
+    use super::*;
+    use shortlinker::api::services::admin::error_code::ErrorCode;
+    use shortlinker::i18n::{self, Lang};
+
+    fn panel_errors(lang: Lang) -> serde_json::Value {
+        let path = format!(
+            "{}/admin-panel/src/i18n/locales/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            lang.code()
+        );
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        json["errors"].clone()
+    }
+
+    #[test]
+    fn test_error_texts_match_admin_panel() {
+        // CLI 与面板共用 errors.* 文案，改动任一侧都需要同步另一侧
+        for lang in Lang::ALL {
+            let panel = panel_errors(*lang);
+            for code in ErrorCode::ALL.iter().filter(|c| **c != ErrorCode::Success) {
+                let key = code.i18n_key().unwrap();
+                let name = key.strip_prefix("errors.").unwrap();
+                assert_eq!(
+                    i18n::lookup(*lang, key),
+                    panel[name].as_str(),
+                    "{} ({})",
+                    key,
+                    lang.code()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_localized() {
+        let error = ShortlinkerError::link_already_exists("docs");
+        assert_eq!(error.format_localized(Lang::En), error.format_simple());
+        assert_eq!(error.format_localized(Lang::Zh), "短代码已存在: docs");
+
+        // 基础设施错误没有共享文案，保持英文
+        let error = ShortlinkerError::database_connection("refused");
+        assert_eq!(error.format_localized(Lang::Zh), error.format_simple());
+    }
+}
@@ -0,0 +1,47 @@
+;; redirect 过滤插件测试夹具：按短码首字母返回不同结果
+;;
+;;   d → deny 451          o → override 到 https://override.example.com/
+;;   b → deny 200（非法）  x → 非 JSON 输出
+;;   l → 死循环（超时）    m → 申请 64 MiB 内存（超限）
+;;   其他 → allow
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+
+  (data (i32.const 0) "{\"action\":\"allow\"}")
+  (data (i32.const 64) "{\"action\":\"deny\",\"status\":451}")
+  (data (i32.const 128) "{\"action\":\"override\",\"target\":\"https://override.example.com/\"}")
+  (data (i32.const 256) "{\"action\":\"deny\",\"status\":200}")
+  (data (i32.const 320) "not json")
+
+  ;; bump 分配器，每次 filter 调用时复位
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+
+  (func $out (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len))))
+
+  (func (export "filter") (param $ptr i32) (param $len i32) (result i64)
+    (local $c i32)
+    (global.set $heap (i32.const 1024))
+    ;; 输入以 {"code":" 开头，偏移 9 为短码首字母
+    (local.set $c (i32.load8_u offset=9 (local.get $ptr)))
+    (if (i32.eq (local.get $c) (i32.const 100))
+      (then (return (call $out (i32.const 64) (i32.const 30)))))
+    (if (i32.eq (local.get $c) (i32.const 111))
+      (then (return (call $out (i32.const 128) (i32.const 62)))))
+    (if (i32.eq (local.get $c) (i32.const 98))
+      (then (return (call $out (i32.const 256) (i32.const 30)))))
+    (if (i32.eq (local.get $c) (i32.const 120))
+      (then (return (call $out (i32.const 320) (i32.const 8)))))
+    (if (i32.eq (local.get $c) (i32.const 108))
+      (then (loop $spin (br $spin))))
+    (if (i32.eq (local.get $c) (i32.const 109))
+      (then (drop (memory.grow (i32.const 1024)))))
+    (call $out (i32.const 0) (i32.const 18)))
+)
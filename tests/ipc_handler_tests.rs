@@ -12,8 +12,8 @@ use shortlinker::config::runtime_config::init_runtime_config;
 use shortlinker::metrics::NoopMetrics;
 use shortlinker::services::{ConfigService, LinkService};
 use shortlinker::services::{LinkCache, LinkCacheLookup};
-use shortlinker::storage::ShortLink;
 use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{CreatedVia, ShortLink};
 use shortlinker::system::ipc::handler::{
     export_links_stream, handle_command, init_config_service, init_link_service, init_start_time,
 };
@@ -191,6 +191,10 @@ async fn test_add_link_command() {
         force: false,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -198,10 +202,37 @@ async fn test_add_link_command() {
         IpcResponse::LinkCreated {
             link,
             generated_code,
+            ..
         } => {
             assert_eq!(link.code, "ipc-test1");
             assert_eq!(link.target, "https://example.com");
             assert!(!generated_code);
+            assert_eq!(link.created_via, CreatedVia::Ipc);
+        }
+        other => panic!("Expected LinkCreated, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_add_link_command_reports_caller_channel() {
+    setup_ipc_handler().await;
+
+    let resp = handle_command(IpcCommand::AddLink {
+        code: Some("ipc-via-cli".to_string()),
+        target: "https://example.com".to_string(),
+        force: false,
+        expires_at: None,
+        password: None,
+        created_via: Some(CreatedVia::Cli),
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
+    })
+    .await;
+
+    match resp {
+        IpcResponse::LinkCreated { link, .. } => {
+            assert_eq!(link.created_via, CreatedVia::Cli);
         }
         other => panic!("Expected LinkCreated, got {:?}", other),
     }
@@ -217,6 +248,10 @@ async fn test_add_link_auto_generate_code() {
         force: false,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -224,6 +259,7 @@ async fn test_add_link_auto_generate_code() {
         IpcResponse::LinkCreated {
             link,
             generated_code,
+            ..
         } => {
             assert!(!link.code.is_empty());
             assert_eq!(link.target, "https://example.com/auto");
@@ -244,6 +280,10 @@ async fn test_get_link_command() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -291,6 +331,10 @@ async fn test_update_link_command() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -300,11 +344,13 @@ async fn test_update_link_command() {
         target: "https://example.com/new".to_string(),
         expires_at: None,
         password: None,
+        analytics_level: None,
+        extras: None,
     })
     .await;
 
     match resp {
-        IpcResponse::LinkUpdated { link } => {
+        IpcResponse::LinkUpdated { link, .. } => {
             assert_eq!(link.code, "ipc-upd1");
             assert_eq!(link.target, "https://example.com/new");
         }
@@ -323,6 +369,10 @@ async fn test_remove_link_command() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -369,6 +419,10 @@ async fn test_list_links_command() {
             force: true,
             expires_at: None,
             password: None,
+            created_via: None,
+            analytics_level: None,
+            extras: None,
+            override_cooldown: false,
         })
         .await;
     }
@@ -407,6 +461,10 @@ async fn test_list_links_with_search() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
@@ -438,6 +496,8 @@ async fn test_import_links_command() {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: None,
+            extras: None,
         },
         ImportLinkData {
             code: "ipc-imp2".to_string(),
@@ -446,6 +506,8 @@ async fn test_import_links_command() {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: None,
+            extras: None,
         },
     ];
 
@@ -478,6 +540,10 @@ async fn test_export_links_command() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await;
 
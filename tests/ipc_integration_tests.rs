@@ -186,6 +186,10 @@ async fn test_e2e_add_and_get_link() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await
     .expect("AddLink failed");
@@ -194,6 +198,7 @@ async fn test_e2e_add_and_get_link() {
         IpcResponse::LinkCreated {
             link,
             generated_code,
+            ..
         } => {
             assert_eq!(link.code, "e2e-link1");
             assert_eq!(link.target, "https://example.com/e2e");
@@ -229,6 +234,10 @@ async fn test_e2e_update_link() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await
     .expect("AddLink failed");
@@ -238,12 +247,14 @@ async fn test_e2e_update_link() {
         target: "https://example.com/new".to_string(),
         expires_at: None,
         password: None,
+        analytics_level: None,
+        extras: None,
     })
     .await
     .expect("UpdateLink failed");
 
     match resp {
-        IpcResponse::LinkUpdated { link } => {
+        IpcResponse::LinkUpdated { link, .. } => {
             assert_eq!(link.code, "e2e-upd1");
             assert_eq!(link.target, "https://example.com/new");
         }
@@ -261,6 +272,10 @@ async fn test_e2e_remove_link() {
         force: true,
         expires_at: None,
         password: None,
+        created_via: None,
+        analytics_level: None,
+        extras: None,
+        override_cooldown: false,
     })
     .await
     .expect("AddLink failed");
@@ -300,6 +315,10 @@ async fn test_e2e_list_links() {
             force: true,
             expires_at: None,
             password: None,
+            created_via: None,
+            analytics_level: None,
+            extras: None,
+            override_cooldown: false,
         })
         .await
         .expect("AddLink failed");
@@ -341,6 +360,8 @@ async fn test_e2e_import_export() {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: None,
+            extras: None,
         },
         ImportLinkData {
             code: "e2e-imp2".to_string(),
@@ -349,6 +370,8 @@ async fn test_e2e_import_export() {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: None,
+            extras: None,
         },
     ];
 
@@ -407,6 +430,10 @@ async fn test_e2e_concurrent_commands() {
                     force: true,
                     expires_at: None,
                     password: None,
+                    created_via: None,
+                    analytics_level: None,
+                    extras: None,
+                    override_cooldown: false,
                 })
                 .await
             })
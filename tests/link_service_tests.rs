@@ -14,7 +14,7 @@ use shortlinker::services::{
 };
 use shortlinker::services::{LinkCache, LinkCacheLookup};
 use shortlinker::storage::backend::SeaOrmStorage;
-use shortlinker::storage::{LinkFilter, ShortLink};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, LinkFilter, ShortLink};
 use std::sync::Once;
 use tempfile::TempDir;
 use tokio::sync::RwLock;
@@ -34,9 +34,16 @@ fn init_test_config() {
 }
 
 /// Mock cache implementation for testing
+///
+/// Bloom membership is tracked separately and survives `invalidate_all`,
+/// matching the production cache.
 struct MockCache {
     data: RwLock<HashMap<String, ShortLink>>,
     not_found: RwLock<std::collections::HashSet<String>>,
+    bloom: RwLock<std::collections::HashSet<String>>,
+    bulk_writers: std::sync::atomic::AtomicUsize,
+    invalidations: std::sync::atomic::AtomicUsize,
+    invalidated_codes: RwLock<Vec<String>>,
 }
 
 impl MockCache {
@@ -44,6 +51,10 @@ impl MockCache {
         Self {
             data: RwLock::new(HashMap::new()),
             not_found: RwLock::new(std::collections::HashSet::new()),
+            bloom: RwLock::new(std::collections::HashSet::new()),
+            bulk_writers: std::sync::atomic::AtomicUsize::new(0),
+            invalidations: std::sync::atomic::AtomicUsize::new(0),
+            invalidated_codes: RwLock::new(Vec::new()),
         }
     }
 }
@@ -62,6 +73,7 @@ impl LinkCache for MockCache {
 
     async fn insert(&self, key: &str, value: ShortLink, _ttl_secs: Option<u64>) {
         self.not_found.write().await.remove(key);
+        self.bloom.write().await.insert(key.to_string());
         self.data.write().await.insert(key.to_string(), value);
     }
 
@@ -70,6 +82,8 @@ impl LinkCache for MockCache {
     }
 
     async fn invalidate_all(&self) {
+        self.invalidations
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         self.data.write().await.clear();
         self.not_found.write().await.clear();
     }
@@ -85,7 +99,7 @@ impl LinkCache for MockCache {
     }
 
     async fn bloom_check(&self, key: &str) -> bool {
-        self.data.read().await.contains_key(key)
+        self.bloom.read().await.contains(key)
     }
 
     async fn health_check(&self) -> shortlinker::services::LinkCacheHealth {
@@ -97,10 +111,49 @@ impl LinkCache for MockCache {
             error: None,
         }
     }
+
+    async fn invalidate_codes(&self, codes: &[String]) {
+        let mut data = self.data.write().await;
+        let mut not_found = self.not_found.write().await;
+        for code in codes {
+            data.remove(code);
+            not_found.remove(code);
+        }
+        self.invalidated_codes
+            .write()
+            .await
+            .extend(codes.iter().cloned());
+    }
+
+    async fn insert_codes(&self, codes: &[String]) {
+        // 批量插入期间必须处于 bulk write 标记内
+        assert!(self.bulk_write_in_progress());
+        self.bloom.write().await.extend(codes.iter().cloned());
+    }
+
+    fn begin_bulk_write(&self) {
+        self.bulk_writers
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn end_bulk_write(&self) {
+        self.bulk_writers
+            .fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn bulk_write_in_progress(&self) -> bool {
+        self.bulk_writers.load(std::sync::atomic::Ordering::SeqCst) > 0
+    }
 }
 
 /// Create a test service with temporary storage
 async fn create_test_service() -> (LinkService, TempDir) {
+    let (service, _cache, temp_dir) = create_test_service_with_cache().await;
+    (service, temp_dir)
+}
+
+/// Create a test service and keep a handle to its mock cache
+async fn create_test_service_with_cache() -> (LinkService, Arc<MockCache>, TempDir) {
     init_test_config();
 
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -113,10 +166,10 @@ async fn create_test_service() -> (LinkService, TempDir) {
             .expect("Failed to create storage"),
     );
 
-    let cache: Arc<dyn LinkCache> = Arc::new(MockCache::new());
-    let service = LinkService::new(storage, cache);
+    let cache = Arc::new(MockCache::new());
+    let service = LinkService::new(storage, cache.clone());
 
-    (service, temp_dir)
+    (service, cache, temp_dir)
 }
 
 /// Helper to create a basic CreateLinkRequest
@@ -127,6 +180,10 @@ fn create_request(code: Option<&str>, target: &str) -> CreateLinkRequest {
         force: false,
         expires_at: None,
         password: None,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
+        override_cooldown: false,
     }
 }
 
@@ -201,6 +258,10 @@ mod create_link_tests {
             force: true,
             expires_at: None,
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req2).await;
 
@@ -247,6 +308,10 @@ mod create_link_tests {
             force: false,
             expires_at: Some("1d".to_string()), // 1 day
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req).await;
 
@@ -270,6 +335,10 @@ mod create_link_tests {
             force: false,
             expires_at: Some("invalid-time".to_string()),
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req).await;
 
@@ -290,6 +359,10 @@ mod create_link_tests {
             force: false,
             expires_at: None,
             password: Some("secret123".to_string()),
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req).await;
 
@@ -315,6 +388,10 @@ mod create_link_tests {
             force: true,
             expires_at: None,
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req2).await.unwrap();
 
@@ -344,6 +421,8 @@ mod update_link_tests {
             target: "https://new.com".to_string(),
             expires_at: None,
             password: None,
+            analytics_level: None,
+            extras: None,
         };
         let result = service.update_link("update_me", update_req).await;
 
@@ -360,6 +439,8 @@ mod update_link_tests {
             target: "https://new.com".to_string(),
             expires_at: None,
             password: None,
+            analytics_level: None,
+            extras: None,
         };
         let result = service.update_link("nonexistent", update_req).await;
 
@@ -380,6 +461,8 @@ mod update_link_tests {
             target: "not-a-url".to_string(),
             expires_at: None,
             password: None,
+            analytics_level: None,
+            extras: None,
         };
         let result = service.update_link("update_invalid", update_req).await;
 
@@ -404,6 +487,8 @@ mod update_link_tests {
             target: "https://example.com".to_string(),
             expires_at: Some("2h".to_string()),
             password: None,
+            analytics_level: None,
+            extras: None,
         };
         let result = service.update_link("add_expiry", update_req).await;
 
@@ -412,6 +497,50 @@ mod update_link_tests {
         assert!(link.expires_at.is_some());
     }
 
+    #[tokio::test]
+    async fn test_update_link_never_and_now() {
+        let (service, _temp) = create_test_service().await;
+
+        let mut req = create_request(Some("never_now"), "https://example.com");
+        req.expires_at = Some("1d".to_string());
+        service.create_link(req).await.unwrap();
+
+        let update = |expires_at: Option<&str>| UpdateLinkRequest {
+            target: "https://example.com".to_string(),
+            expires_at: expires_at.map(str::to_string),
+            password: None,
+            analytics_level: None,
+            extras: None,
+        };
+
+        // "now" = 立即过期
+        let link = service
+            .update_link("never_now", update(Some("now")))
+            .await
+            .unwrap();
+        assert!(link.is_expired());
+
+        // 缺省 = 保持不变
+        let link = service
+            .update_link("never_now", update(None))
+            .await
+            .unwrap();
+        assert!(link.expires_at.is_some());
+
+        // "never" = 永不过期
+        let link = service
+            .update_link("never_now", update(Some("never")))
+            .await
+            .unwrap();
+        assert!(link.expires_at.is_none());
+
+        // "0" 语义不明，报错
+        assert!(matches!(
+            service.update_link("never_now", update(Some("0"))).await,
+            Err(ShortlinkerError::LinkInvalidExpireTime(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_update_link_remove_password() {
         let (service, _temp) = create_test_service().await;
@@ -423,6 +552,10 @@ mod update_link_tests {
             force: false,
             expires_at: None,
             password: Some("secret".to_string()),
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let created = service.create_link(req).await.unwrap();
         assert!(created.link.password.is_some());
@@ -432,6 +565,8 @@ mod update_link_tests {
             target: "https://example.com".to_string(),
             expires_at: None,
             password: Some("".to_string()), // Empty string = remove
+            analytics_level: None,
+            extras: None,
         };
         let result = service.update_link("remove_pwd", update_req).await;
 
@@ -457,6 +592,8 @@ mod update_link_tests {
             target: "https://new.com".to_string(),
             expires_at: None,
             password: None,
+            analytics_level: None,
+            extras: None,
         };
         let updated = service
             .update_link("preserve_time", update_req)
@@ -466,6 +603,51 @@ mod update_link_tests {
         // created_at should be preserved
         assert_eq!(updated.created_at, original_created_at);
     }
+
+    #[tokio::test]
+    async fn test_update_link_extras_keep_and_clear() {
+        let (service, _temp) = create_test_service().await;
+
+        let mut req = create_request(Some("with_extras"), "https://example.com");
+        req.extras = Some(r#"{ "ticket": "JIRA-123" }"#.to_string());
+        let created = service.create_link(req).await.unwrap();
+        assert_eq!(
+            created.link.extras.as_deref(),
+            Some(r#"{"ticket":"JIRA-123"}"#)
+        );
+
+        let update = |extras: Option<&str>| UpdateLinkRequest {
+            target: "https://example.com/new".to_string(),
+            expires_at: None,
+            password: None,
+            analytics_level: None,
+            extras: extras.map(str::to_string),
+        };
+
+        // 省略 = 保持不变
+        let updated = service
+            .update_link("with_extras", update(None))
+            .await
+            .unwrap();
+        assert_eq!(updated.extras.as_deref(), Some(r#"{"ticket":"JIRA-123"}"#));
+
+        // 非 object 拒绝，原值不变
+        assert!(
+            service
+                .update_link("with_extras", update(Some("[1]")))
+                .await
+                .is_err()
+        );
+
+        // `{}` = 清空
+        let updated = service
+            .update_link("with_extras", update(Some("{}")))
+            .await
+            .unwrap();
+        assert_eq!(updated.extras, None);
+        let stored = service.get_link("with_extras").await.unwrap().unwrap();
+        assert_eq!(stored.extras, None);
+    }
 }
 
 // =============================================================================
@@ -644,6 +826,8 @@ mod import_links_batch_tests {
             expires_at: None,
             password: None,
             click_count: 0,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
             row_num: None,
         }
     }
@@ -682,15 +866,20 @@ mod import_links_batch_tests {
         let req = create_request(Some("ow_test"), "https://old.com");
         service.create_link(req).await.unwrap();
 
-        let items = vec![make_rich_item("ow_test", "https://new.com")];
+        let items = vec![
+            make_rich_item("ow_test", "https://new.com"),
+            make_rich_item("ow_fresh", "https://fresh.com"),
+        ];
 
         let result = service
             .import_links_batch(items, ImportMode::Overwrite)
             .await
             .unwrap();
 
-        assert_eq!(result.success_count, 1);
+        assert_eq!(result.success_count, 2);
         assert_eq!(result.skipped_count, 0);
+        // 配额只计新建的短码
+        assert_eq!(result.created_codes, vec!["ow_fresh".to_string()]);
 
         let link = service.get_link("ow_test").await.unwrap().unwrap();
         assert_eq!(link.target, "https://new.com");
@@ -756,6 +945,8 @@ mod import_links_batch_tests {
             expires_at: None,
             password: Some("hashed_pw".to_string()),
             click_count: 42,
+            analytics_level: AnalyticsLevel::CountOnly,
+            extras: None,
             row_num: None,
         }];
 
@@ -769,6 +960,109 @@ mod import_links_batch_tests {
         assert_eq!(link.created_at, created);
         assert_eq!(link.click, 42);
         assert_eq!(link.password, Some("hashed_pw".to_string()));
+        assert_eq!(link.analytics_level, AnalyticsLevel::CountOnly);
+    }
+
+    #[tokio::test]
+    async fn test_batch_import_registers_and_invalidates_only_written_codes() {
+        let (service, cache, _temp) = create_test_service_with_cache().await;
+
+        let items = vec![
+            make_rich_item("bulk_a", "https://a.com"),
+            make_rich_item("bulk_b", "https://b.com"),
+            make_rich_item("bulk_c", "https://c.com"),
+        ];
+        let result = service
+            .import_links_batch_chunked(items, ImportMode::Skip, 2, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.success_count, 3);
+        for code in ["bulk_a", "bulk_b", "bulk_c"] {
+            assert!(cache.bloom_check(code).await);
+        }
+        let mut invalidated = cache.invalidated_codes.read().await.clone();
+        invalidated.sort();
+        assert_eq!(invalidated, vec!["bulk_a", "bulk_b", "bulk_c"]);
+        assert_eq!(
+            cache
+                .invalidations
+                .load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+        assert!(!cache.bulk_write_in_progress());
+    }
+
+    #[tokio::test]
+    async fn test_batch_import_overwrite_drops_stale_cache_entry_only() {
+        let (service, cache, _temp) = create_test_service_with_cache().await;
+        service
+            .import_links_batch(
+                vec![make_rich_item("cached_ow", "https://old.com")],
+                ImportMode::Skip,
+            )
+            .await
+            .unwrap();
+        let old = service.get_link("cached_ow").await.unwrap().unwrap();
+        cache.insert("cached_ow", old, None).await;
+        let other = make_rich_item("cached_other", "https://other.com");
+        cache
+            .insert(
+                "cached_other",
+                ShortLink {
+                    code: other.code,
+                    target: other.target,
+                    created_at: other.created_at,
+                    expires_at: None,
+                    password: None,
+                    click: 0,
+                    created_via: CreatedVia::Import,
+                    analytics_level: AnalyticsLevel::Inherit,
+                    extras: None,
+                },
+                None,
+            )
+            .await;
+
+        service
+            .import_links_batch(
+                vec![make_rich_item("cached_ow", "https://new.com")],
+                ImportMode::Overwrite,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            cache.get("cached_ow").await,
+            LinkCacheLookup::Miss
+        ));
+        assert!(matches!(
+            cache.get("cached_other").await,
+            LinkCacheLookup::Found(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_reimport_skip_mode_sees_imported_codes() {
+        let (service, _temp) = create_test_service().await;
+
+        let items = || {
+            vec![
+                make_rich_item("again_a", "https://a.com"),
+                make_rich_item("again_b", "https://b.com"),
+            ]
+        };
+        service
+            .import_links_batch(items(), ImportMode::Skip)
+            .await
+            .unwrap();
+        let result = service
+            .import_links_batch(items(), ImportMode::Skip)
+            .await
+            .unwrap();
+
+        assert_eq!(result.success_count, 0);
+        assert_eq!(result.skipped_count, 2);
     }
 
     #[tokio::test]
@@ -804,6 +1098,10 @@ mod edge_cases {
             force: false,
             expires_at: None,
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
         let result = service.create_link(req).await.unwrap();
 
@@ -859,6 +1157,10 @@ mod edge_cases {
             force: false,
             expires_at: None,
             password: Some(hashed.to_string()),
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         };
 
         let result = service.create_link(req).await.unwrap();
@@ -886,6 +1188,10 @@ mod edge_cases {
                 force: false,
                 expires_at: Some(time_str.to_string()),
                 password: None,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+                override_cooldown: false,
             };
 
             let result = service.create_link(req).await.unwrap();
@@ -942,6 +1248,36 @@ mod batch_operations_tests {
         assert_eq!(result.failed[0].code, "invalid_batch");
     }
 
+    #[tokio::test]
+    async fn test_batch_create_links_invalid_expiry_reason() {
+        let (service, _temp) = create_test_service().await;
+
+        let mut req = create_request(Some("bad_expiry"), "https://valid.com");
+        req.expires_at = Some("someday".to_string());
+
+        let result = service.batch_create_links(vec![req]).await.unwrap();
+        assert!(result.success.is_empty());
+        assert_eq!(result.failed[0].code, "bad_expiry");
+        assert!(result.failed[0].reason.starts_with("Invalid expires_at:"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_links_keeps_unchecked_code_format() {
+        // 批量创建历史上不校验短码字符集（ValidationProfile::BATCH_CREATE），单条创建会拒绝
+        let (service, _temp) = create_test_service().await;
+
+        let result = service
+            .batch_create_links(vec![create_request(Some("has space"), "https://valid.com")])
+            .await
+            .unwrap();
+        assert_eq!(result.success.len(), 1);
+
+        let single = service
+            .create_link(create_request(Some("has space2"), "https://valid.com"))
+            .await;
+        assert!(matches!(single, Err(ShortlinkerError::LinkInvalidCode(_))));
+    }
+
     #[tokio::test]
     async fn test_batch_create_links_auto_generate_code() {
         let (service, _temp) = create_test_service().await;
@@ -990,6 +1326,10 @@ mod batch_operations_tests {
             force: true,
             expires_at: None,
             password: None,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            override_cooldown: false,
         }];
 
         let result = service.batch_create_links(requests).await.unwrap();
@@ -1011,6 +1351,10 @@ mod batch_operations_tests {
                 force: false,
                 expires_at: Some("1h".to_string()),
                 password: None,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+                override_cooldown: false,
             },
             CreateLinkRequest {
                 code: Some("batch_exp2".to_string()),
@@ -1018,6 +1362,10 @@ mod batch_operations_tests {
                 force: false,
                 expires_at: Some("invalid-time".to_string()),
                 password: None,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+                override_cooldown: false,
             },
         ];
 
@@ -1044,6 +1392,8 @@ mod batch_operations_tests {
                     target: "https://new1.com".to_string(),
                     expires_at: None,
                     password: None,
+                    analytics_level: None,
+                    extras: None,
                 },
             ),
             (
@@ -1052,6 +1402,8 @@ mod batch_operations_tests {
                     target: "https://new2.com".to_string(),
                     expires_at: None,
                     password: None,
+                    analytics_level: None,
+                    extras: None,
                 },
             ),
         ];
@@ -1075,6 +1427,8 @@ mod batch_operations_tests {
                 target: "https://new.com".to_string(),
                 expires_at: None,
                 password: None,
+                analytics_level: None,
+                extras: None,
             },
         )];
 
@@ -1097,6 +1451,8 @@ mod batch_operations_tests {
                 target: "not-a-url".to_string(),
                 expires_at: None,
                 password: None,
+                analytics_level: None,
+                extras: None,
             },
         )];
 
@@ -1120,6 +1476,8 @@ mod batch_operations_tests {
                     target: "https://new.com".to_string(),
                     expires_at: None,
                     password: None,
+                    analytics_level: None,
+                    extras: None,
                 },
             ),
             (
@@ -1128,6 +1486,8 @@ mod batch_operations_tests {
                     target: "https://new.com".to_string(),
                     expires_at: None,
                     password: None,
+                    analytics_level: None,
+                    extras: None,
                 },
             ),
         ];
@@ -1151,6 +1511,8 @@ mod batch_operations_tests {
                 target: "https://old.com".to_string(),
                 expires_at: None,
                 password: Some("newpassword".to_string()),
+                analytics_level: None,
+                extras: None,
             },
         )];
 
@@ -1219,3 +1581,1169 @@ mod batch_operations_tests {
         assert!(result.not_found.is_empty());
     }
 }
+
+// =============================================================================
+// Batch Extend Tests
+// =============================================================================
+
+#[cfg(test)]
+mod batch_extend_tests {
+    use super::*;
+    use chrono::Duration;
+    use shortlinker::services::{
+        BatchExtendRequest, ExtendAction, LinkSelection, compute_extended_expiry,
+    };
+
+    fn extend_by(days: i64) -> BatchExtendRequest {
+        BatchExtendRequest {
+            action: ExtendAction::By(Duration::days(days)),
+            include_permanent: false,
+            revive_from_now: false,
+            dry_run: false,
+        }
+    }
+
+    async fn create_with_expiry(service: &LinkService, code: &str, expires_at: Option<&str>) {
+        let mut req = create_request(Some(code), "https://example.com");
+        req.expires_at = expires_at.map(|s| s.to_string());
+        service.create_link(req).await.unwrap();
+    }
+
+    #[test]
+    fn test_compute_extended_expiry_rules() {
+        let now = Utc::now();
+        let future = now + Duration::days(3);
+        let past = now - Duration::days(3);
+
+        let req = extend_by(7);
+        assert_eq!(
+            compute_extended_expiry(Some(future), now, &req).unwrap(),
+            future + Duration::days(7)
+        );
+        // 已过期：默认从原 expires_at 起算
+        assert_eq!(
+            compute_extended_expiry(Some(past), now, &req).unwrap(),
+            past + Duration::days(7)
+        );
+        // 永不过期：默认跳过
+        assert!(compute_extended_expiry(None, now, &req).is_err());
+
+        let req = BatchExtendRequest {
+            include_permanent: true,
+            revive_from_now: true,
+            ..extend_by(7)
+        };
+        assert_eq!(
+            compute_extended_expiry(Some(past), now, &req).unwrap(),
+            now + Duration::days(7)
+        );
+        assert_eq!(
+            compute_extended_expiry(None, now, &req).unwrap(),
+            now + Duration::days(7)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_extend_by_codes() {
+        let (service, _temp) = create_test_service().await;
+        create_with_expiry(&service, "ext1", Some("2099-01-01T00:00:00Z")).await;
+        create_with_expiry(&service, "ext_forever", None).await;
+
+        let codes = vec![
+            "ext1".to_string(),
+            "ext_forever".to_string(),
+            "ext_missing".to_string(),
+        ];
+        let result = service
+            .batch_extend_links(LinkSelection::Codes(codes), extend_by(7))
+            .await
+            .unwrap();
+
+        assert_eq!(result.updated.len(), 1);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].code, "ext_forever");
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].code, "ext_missing");
+
+        let link = service.get_link("ext1").await.unwrap().unwrap();
+        assert_eq!(
+            link.expires_at.unwrap().to_rfc3339(),
+            "2099-01-08T00:00:00+00:00"
+        );
+        // 永不过期的链接不受影响
+        let link = service.get_link("ext_forever").await.unwrap().unwrap();
+        assert!(link.expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_extend_dry_run_does_not_write() {
+        let (service, _temp) = create_test_service().await;
+        create_with_expiry(&service, "ext_dry", Some("2099-01-01T00:00:00Z")).await;
+
+        let req = BatchExtendRequest {
+            dry_run: true,
+            ..extend_by(7)
+        };
+        let result = service
+            .batch_extend_links(LinkSelection::Codes(vec!["ext_dry".to_string()]), req)
+            .await
+            .unwrap();
+
+        assert!(result.dry_run);
+        assert_eq!(result.updated.len(), 1);
+        let link = service.get_link("ext_dry").await.unwrap().unwrap();
+        assert_eq!(
+            link.expires_at.unwrap().to_rfc3339(),
+            "2099-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_extend_revives_expired_by_filter() {
+        let (service, _temp) = create_test_service().await;
+        create_with_expiry(&service, "summer_a", Some("2020-01-01T00:00:00Z")).await;
+        create_with_expiry(&service, "summer_b", Some("2099-01-01T00:00:00Z")).await;
+        create_with_expiry(&service, "winter_a", Some("2020-01-01T00:00:00Z")).await;
+
+        let filter = LinkFilter {
+            search: Some("summer".to_string()),
+            ..Default::default()
+        };
+        let req = BatchExtendRequest {
+            revive_from_now: true,
+            ..extend_by(7)
+        };
+        let result = service
+            .batch_extend_links(LinkSelection::Filter(filter), req)
+            .await
+            .unwrap();
+
+        assert_eq!(result.updated.len(), 2);
+        let revived = service.get_link("summer_a").await.unwrap().unwrap();
+        assert!(!revived.is_expired());
+        let untouched = service.get_link("winter_a").await.unwrap().unwrap();
+        assert!(untouched.is_expired());
+    }
+}
+
+// =============================================================================
+// Created Via Tests
+// =============================================================================
+
+#[cfg(test)]
+mod created_via_tests {
+    use super::*;
+    use shortlinker::services::{BatchExtendRequest, ExtendAction, LinkSelection};
+
+    fn request_via(code: &str, created_via: CreatedVia) -> CreateLinkRequest {
+        CreateLinkRequest {
+            created_via,
+            ..create_request(Some(code), "https://example.com/via")
+        }
+    }
+
+    fn import_item(code: &str) -> ImportLinkItemRich {
+        ImportLinkItemRich {
+            code: code.to_string(),
+            target: "https://example.com/imported".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click_count: 0,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            row_num: None,
+        }
+    }
+
+    async fn via_of(service: &LinkService, code: &str) -> CreatedVia {
+        service
+            .get_link(code)
+            .await
+            .unwrap()
+            .expect("link should exist")
+            .created_via
+    }
+
+    #[tokio::test]
+    async fn test_create_link_records_each_channel() {
+        let (service, _temp) = create_test_service().await;
+
+        for via in [
+            CreatedVia::Api,
+            CreatedVia::Cli,
+            CreatedVia::Tui,
+            CreatedVia::Ipc,
+            CreatedVia::Bootstrap,
+        ] {
+            let code = format!("via_{}", via);
+            service.create_link(request_via(&code, via)).await.unwrap();
+            assert_eq!(via_of(&service, &code).await, via);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_records_channel() {
+        let (service, _temp) = create_test_service().await;
+
+        let result = service
+            .batch_create_links(vec![
+                request_via("batch_api", CreatedVia::Api),
+                request_via("batch_cli", CreatedVia::Cli),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(result.success.len(), 2);
+        assert_eq!(via_of(&service, "batch_api").await, CreatedVia::Api);
+        assert_eq!(via_of(&service, "batch_cli").await, CreatedVia::Cli);
+    }
+
+    #[tokio::test]
+    async fn test_import_records_import_channel() {
+        let (service, _temp) = create_test_service().await;
+
+        service
+            .import_links_batch(vec![import_item("imported")], ImportMode::Skip)
+            .await
+            .unwrap();
+
+        assert_eq!(via_of(&service, "imported").await, CreatedVia::Import);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_and_update_keep_original_channel() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(request_via("keep", CreatedVia::Api))
+            .await
+            .unwrap();
+
+        let overwrite = CreateLinkRequest {
+            force: true,
+            ..request_via("keep", CreatedVia::Cli)
+        };
+        service.create_link(overwrite).await.unwrap();
+        assert_eq!(via_of(&service, "keep").await, CreatedVia::Api);
+
+        let update = UpdateLinkRequest {
+            target: "https://example.com/updated".to_string(),
+            expires_at: None,
+            password: None,
+            analytics_level: None,
+            extras: None,
+        };
+        service.update_link("keep", update).await.unwrap();
+        assert_eq!(via_of(&service, "keep").await, CreatedVia::Api);
+
+        let extend = BatchExtendRequest {
+            action: ExtendAction::By(chrono::Duration::days(1)),
+            include_permanent: true,
+            revive_from_now: false,
+            dry_run: false,
+        };
+        service
+            .batch_extend_links(LinkSelection::Codes(vec!["keep".to_string()]), extend)
+            .await
+            .unwrap();
+        assert_eq!(via_of(&service, "keep").await, CreatedVia::Api);
+    }
+
+    #[tokio::test]
+    async fn test_list_links_filters_by_created_via() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(request_via("from_api", CreatedVia::Api))
+            .await
+            .unwrap();
+        service
+            .import_links_batch(
+                vec![import_item("from_import_a"), import_item("from_import_b")],
+                ImportMode::Skip,
+            )
+            .await
+            .unwrap();
+
+        let filter = LinkFilter {
+            created_via: Some(CreatedVia::Import),
+            ..Default::default()
+        };
+        let (links, total) = service.list_links(filter, 1, 10).await.unwrap();
+
+        assert_eq!(total, 2);
+        assert!(links.iter().all(|l| l.created_via == CreatedVia::Import));
+    }
+
+    #[tokio::test]
+    async fn test_creation_stats_by_channel_and_day() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(request_via("stats_api", CreatedVia::Api))
+            .await
+            .unwrap();
+        service
+            .create_link(request_via("stats_cli", CreatedVia::Cli))
+            .await
+            .unwrap();
+        let mut old = import_item("stats_old");
+        old.created_at = Utc::now() - chrono::Duration::days(90);
+        service
+            .import_links_batch(vec![old, import_item("stats_new")], ImportMode::Skip)
+            .await
+            .unwrap();
+
+        let stats = service.get_creation_stats(30).await.unwrap();
+
+        assert_eq!(stats.by_created_via.get("api"), Some(&1));
+        assert_eq!(stats.by_created_via.get("cli"), Some(&1));
+        assert_eq!(stats.by_created_via.get("import"), Some(&2));
+
+        assert_eq!(stats.daily.len(), 30);
+        let today = stats.daily.last().unwrap();
+        assert_eq!(today.date, Utc::now().format("%Y-%m-%d").to_string());
+        assert_eq!(today.counts.get("api"), Some(&1));
+        assert_eq!(today.counts.get("cli"), Some(&1));
+        // 90 天前导入的链接不计入趋势
+        assert_eq!(today.counts.get("import"), Some(&1));
+        let trend_total: usize = stats.daily.iter().flat_map(|d| d.counts.values()).sum();
+        assert_eq!(trend_total, 3);
+    }
+}
+
+// =============================================================================
+// Archive Tests
+// =============================================================================
+
+#[cfg(test)]
+mod archive_tests {
+    use super::*;
+    use shortlinker::services::LinkSelection;
+
+    fn codes(list: &[&str]) -> LinkSelection {
+        LinkSelection::Codes(list.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[tokio::test]
+    async fn test_archive_by_codes_moves_links() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(create_request(Some("arch1"), "https://example.com/1"))
+            .await
+            .unwrap();
+        service
+            .create_link(create_request(Some("arch2"), "https://example.com/2"))
+            .await
+            .unwrap();
+
+        let result = service
+            .archive_links(codes(&["arch1", "arch2", "arch1", "arch_missing"]))
+            .await
+            .unwrap();
+
+        assert_eq!(result.archived.len(), 2);
+        assert_eq!(result.not_found, vec!["arch_missing".to_string()]);
+        assert!(result.failed.is_empty());
+        assert!(service.get_link("arch1").await.unwrap().is_none());
+        assert!(service.get_link("arch2").await.unwrap().is_none());
+
+        let (archived, total) = service.list_archived(None, 1, 10).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(archived.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_archive_by_filter() {
+        let (service, _temp) = create_test_service().await;
+        for code in ["promo_a", "promo_b", "keep_a"] {
+            service
+                .create_link(create_request(Some(code), "https://example.com"))
+                .await
+                .unwrap();
+        }
+
+        let filter = LinkFilter {
+            search: Some("promo".to_string()),
+            ..Default::default()
+        };
+        let result = service
+            .archive_links(LinkSelection::Filter(filter))
+            .await
+            .unwrap();
+
+        assert_eq!(result.archived.len(), 2);
+        assert!(service.get_link("keep_a").await.unwrap().is_some());
+        assert!(service.get_link("promo_a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unarchive_restores_link_and_clicks() {
+        let (service, cache, _temp) = create_test_service_with_cache().await;
+        let item = ImportLinkItemRich {
+            code: "restore_me".to_string(),
+            target: "https://example.com/restore".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click_count: 42,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+            row_num: None,
+        };
+        service
+            .import_links_batch(vec![item], ImportMode::Skip)
+            .await
+            .unwrap();
+        service.archive_links(codes(&["restore_me"])).await.unwrap();
+
+        let link = service.unarchive_link("restore_me").await.unwrap();
+
+        assert_eq!(link.target, "https://example.com/restore");
+        assert_eq!(link.click, 42);
+        assert!(service.get_link("restore_me").await.unwrap().is_some());
+        assert!(matches!(
+            cache.get("restore_me").await,
+            LinkCacheLookup::Found(_)
+        ));
+        let (_, total) = service.list_archived(None, 1, 10).await.unwrap();
+        assert_eq!(total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unarchive_conflicts_with_reused_code() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(create_request(Some("reused"), "https://example.com/old"))
+            .await
+            .unwrap();
+        service.archive_links(codes(&["reused"])).await.unwrap();
+        service
+            .create_link(create_request(Some("reused"), "https://example.com/new"))
+            .await
+            .unwrap();
+
+        let err = service.unarchive_link("reused").await.unwrap_err();
+
+        assert!(matches!(err, ShortlinkerError::LinkAlreadyExists(_)));
+        let link = service.get_link("reused").await.unwrap().unwrap();
+        assert_eq!(link.target, "https://example.com/new");
+    }
+
+    #[tokio::test]
+    async fn test_unarchive_missing_code() {
+        let (service, _temp) = create_test_service().await;
+
+        let err = service.unarchive_link("never_archived").await.unwrap_err();
+
+        assert!(matches!(err, ShortlinkerError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_list_archived_paginates_and_searches() {
+        let (service, _temp) = create_test_service().await;
+        for i in 0..5 {
+            let code = format!("page_{}", i);
+            service
+                .create_link(create_request(Some(&code), "https://example.com"))
+                .await
+                .unwrap();
+        }
+        service
+            .create_link(create_request(Some("other"), "https://example.com"))
+            .await
+            .unwrap();
+        service
+            .archive_links(codes(&[
+                "page_0", "page_1", "page_2", "page_3", "page_4", "other",
+            ]))
+            .await
+            .unwrap();
+
+        let (first, total) = service.list_archived(None, 1, 4).await.unwrap();
+        assert_eq!(total, 6);
+        assert_eq!(first.len(), 4);
+        let (second, _) = service.list_archived(None, 2, 4).await.unwrap();
+        assert_eq!(second.len(), 2);
+
+        let (found, total) = service
+            .list_archived(Some("page".to_string()), 1, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 5);
+        assert!(found.iter().all(|a| a.link.code.starts_with("page_")));
+    }
+}
+
+// =============================================================================
+// Random Sampling Tests
+// =============================================================================
+
+mod sample_tests {
+    use super::*;
+
+    async fn seed_links(service: &LinkService, prefix: &str, count: usize) {
+        for i in 0..count {
+            service
+                .create_link(create_request(
+                    Some(&format!("{}{:02}", prefix, i)),
+                    "https://example.com",
+                ))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sample_returns_n_distinct_links() {
+        let (service, _temp) = create_test_service().await;
+        seed_links(&service, "smp", 30).await;
+
+        let sample = service
+            .sample_links(LinkFilter::default(), 10, Some(1))
+            .await
+            .unwrap();
+
+        assert_eq!(sample.population, 30);
+        assert_eq!(sample.seed, 1);
+        assert_eq!(sample.links.len(), 10);
+        let mut codes: Vec<_> = sample.links.iter().map(|l| l.code.clone()).collect();
+        assert!(codes.is_sorted());
+        codes.dedup();
+        assert_eq!(codes.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_sample_same_seed_is_reproducible() {
+        let (service, _temp) = create_test_service().await;
+        seed_links(&service, "rep", 40).await;
+
+        let first = service
+            .sample_links(LinkFilter::default(), 5, None)
+            .await
+            .unwrap();
+        let again = service
+            .sample_links(LinkFilter::default(), 5, Some(first.seed))
+            .await
+            .unwrap();
+
+        let codes = |s: &shortlinker::services::LinkSample| {
+            s.links.iter().map(|l| l.code.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(codes(&first), codes(&again));
+    }
+
+    #[tokio::test]
+    async fn test_sample_respects_filter_and_small_population() {
+        let (service, _temp) = create_test_service().await;
+        seed_links(&service, "promo", 3).await;
+        seed_links(&service, "other", 10).await;
+
+        let filter = LinkFilter {
+            search: Some("promo".to_string()),
+            ..Default::default()
+        };
+        let sample = service.sample_links(filter, 100, Some(7)).await.unwrap();
+
+        assert_eq!(sample.population, 3);
+        assert_eq!(sample.links.len(), 3);
+        assert!(sample.links.iter().all(|l| l.code.starts_with("promo")));
+    }
+
+    #[tokio::test]
+    async fn test_sample_rejects_invalid_size() {
+        let (service, _temp) = create_test_service().await;
+
+        assert!(
+            service
+                .sample_links(LinkFilter::default(), 0, None)
+                .await
+                .is_err()
+        );
+        assert!(
+            service
+                .sample_links(
+                    LinkFilter::default(),
+                    shortlinker::services::MAX_SAMPLE_SIZE + 1,
+                    None
+                )
+                .await
+                .is_err()
+        );
+    }
+}
+
+// =============================================================================
+// Template Generate Tests
+// =============================================================================
+
+mod generate_links_tests {
+    use super::*;
+    use shortlinker::services::{GenerateLinksOptions, LinkTemplate, TemplateVar};
+
+    fn template(code: Option<&str>) -> LinkTemplate {
+        LinkTemplate {
+            url: "https://example.com/landing?utm_source={source}&utm_content={content}"
+                .to_string(),
+            code: code.map(str::to_string),
+            vars: vec![
+                TemplateVar::parse("source=tv,radio,web").unwrap(),
+                TemplateVar::parse("content=a,b").unwrap(),
+            ],
+        }
+    }
+
+    fn options(force: bool, dry_run: bool) -> GenerateLinksOptions {
+        GenerateLinksOptions {
+            force,
+            expires_at: None,
+            password: None,
+            created_via: CreatedVia::Cli,
+            analytics_level: AnalyticsLevel::Inherit,
+            dry_run,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_dry_run_reports_existing_without_writing() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(create_request(Some("q4-web-b"), "https://old.example.com"))
+            .await
+            .unwrap();
+
+        let result = service
+            .generate_links(
+                template(Some("q4-{source}-{content}")),
+                options(false, true),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.dry_run);
+        assert_eq!(result.planned.len(), 6);
+        assert_eq!(result.existing, vec!["q4-web-b".to_string()]);
+        assert!(result.created.is_empty());
+        assert!(service.get_link("q4-tv-a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_creates_all_combinations_with_batch_conflict_policy() {
+        let (service, _temp) = create_test_service().await;
+        service
+            .create_link(create_request(Some("q4-web-b"), "https://old.example.com"))
+            .await
+            .unwrap();
+
+        let result = service
+            .generate_links(
+                template(Some("q4-{source}-{content}")),
+                options(false, false),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.created.len(), 5);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].code, "q4-web-b");
+
+        let link = service.get_link("q4-radio-a").await.unwrap().unwrap();
+        assert_eq!(
+            link.target,
+            "https://example.com/landing?utm_source=radio&utm_content=a"
+        );
+        assert_eq!(link.created_via, CreatedVia::Cli);
+
+        let result = service
+            .generate_links(
+                template(Some("q4-{source}-{content}")),
+                options(true, false),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.created.len(), 6);
+        let link = service.get_link("q4-web-b").await.unwrap().unwrap();
+        assert_eq!(
+            link.target,
+            "https://example.com/landing?utm_source=web&utm_content=b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_random_codes_and_rejects_invalid_template() {
+        let (service, _temp) = create_test_service().await;
+
+        let result = service
+            .generate_links(template(None), options(false, false))
+            .await
+            .unwrap();
+        assert_eq!(result.created.len(), 6);
+        assert!(result.created.iter().all(|l| l.code.is_some()));
+
+        let err = service
+            .generate_links(template(Some("q4-{source}")), options(false, false))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ShortlinkerError::Validation(_)));
+    }
+}
+
+// =============================================================================
+// Side Effect Outbox Tests
+// =============================================================================
+
+mod side_effect_tests {
+    use super::*;
+    use shortlinker::services::SideEffectRunner;
+    use shortlinker::storage::SideEffect;
+    use std::time::Duration;
+
+    struct Fixture {
+        storage: Arc<SeaOrmStorage>,
+        cache: Arc<MockCache>,
+        service: LinkService,
+        _temp: TempDir,
+    }
+
+    async fn fixture() -> Fixture {
+        init_test_config();
+        let temp = TempDir::new().unwrap();
+        let db_url = format!(
+            "sqlite://{}?mode=rwc",
+            temp.path().join("outbox.db").display()
+        );
+        let storage = Arc::new(
+            SeaOrmStorage::new(&db_url, "sqlite", NoopMetrics::arc())
+                .await
+                .unwrap(),
+        );
+        let cache = Arc::new(MockCache::new());
+        let service = LinkService::new(storage.clone(), cache.clone());
+        Fixture {
+            storage,
+            cache,
+            service,
+            _temp: temp,
+        }
+    }
+
+    fn link(code: &str, target: &str) -> ShortLink {
+        ShortLink {
+            code: code.to_string(),
+            target: target.to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        }
+    }
+
+    async fn pending_count(storage: &SeaOrmStorage) -> usize {
+        storage
+            .load_pending_side_effects(Utc::now() + chrono::Duration::seconds(1), i32::MAX, 1000)
+            .await
+            .unwrap()
+            .len()
+    }
+
+    async fn cached_target(cache: &MockCache, code: &str) -> Option<String> {
+        match cache.get(code).await {
+            LinkCacheLookup::Found(link) => Some(link.target),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_normal_writes_leave_no_pending_entries() {
+        let f = fixture().await;
+        f.service
+            .create_link(create_request(Some("ob1"), "https://example.com/1"))
+            .await
+            .unwrap();
+        f.service
+            .update_link(
+                "ob1",
+                UpdateLinkRequest {
+                    target: "https://example.com/2".to_string(),
+                    expires_at: None,
+                    password: None,
+                    analytics_level: None,
+                    extras: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            cached_target(&f.cache, "ob1").await.as_deref(),
+            Some("https://example.com/2")
+        );
+
+        f.service.delete_link("ob1").await.unwrap();
+        assert_eq!(cached_target(&f.cache, "ob1").await, None);
+        assert_eq!(pending_count(&f.storage).await, 0);
+    }
+
+    /// 崩溃注入：事务已提交、收尾未执行，"重启"后由重放补偿
+    #[tokio::test]
+    async fn test_crash_after_commit_is_compensated_on_restart() {
+        let f = fixture().await;
+        f.service
+            .create_link(create_request(Some("crash"), "https://example.com/old"))
+            .await
+            .unwrap();
+
+        // 写入新目标后"崩溃"：缓存仍是旧值
+        f.storage
+            .set_with_effects(
+                link("crash", "https://example.com/new"),
+                &[SideEffect::cache_refresh("crash")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            cached_target(&f.cache, "crash").await.as_deref(),
+            Some("https://example.com/old")
+        );
+        assert_eq!(pending_count(&f.storage).await, 1);
+
+        // 重启：新的执行器（共享同一外部缓存）重放残留条目
+        let runner = SideEffectRunner::new(f.storage.clone(), f.cache.clone());
+        let report = runner.replay_pending(Duration::ZERO).await.unwrap();
+        assert_eq!(report.replayed, 1);
+        assert_eq!(report.failed, 0);
+        assert_eq!(
+            cached_target(&f.cache, "crash").await.as_deref(),
+            Some("https://example.com/new")
+        );
+        assert_eq!(pending_count(&f.storage).await, 0);
+
+        // 再次重放没有可执行的条目
+        let report = runner.replay_pending(Duration::ZERO).await.unwrap();
+        assert_eq!(report.replayed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_crash_after_delete_commit_evicts_stale_cache() {
+        let f = fixture().await;
+        f.service
+            .create_link(create_request(Some("gone"), "https://example.com"))
+            .await
+            .unwrap();
+
+        f.storage
+            .remove_with_effects("gone", &[SideEffect::cache_refresh("gone")])
+            .await
+            .unwrap();
+        assert!(cached_target(&f.cache, "gone").await.is_some());
+
+        let runner = SideEffectRunner::new(f.storage.clone(), f.cache.clone());
+        runner.replay_pending(Duration::ZERO).await.unwrap();
+        assert_eq!(cached_target(&f.cache, "gone").await, None);
+    }
+
+    /// 同一短码的多条残留（或已执行但未删除的条目）重复执行无害，缓存收敛到最终状态
+    #[tokio::test]
+    async fn test_duplicate_replay_is_harmless() {
+        let f = fixture().await;
+        for target in ["https://example.com/a", "https://example.com/b"] {
+            f.storage
+                .set_with_effects(link("dup", target), &[SideEffect::cache_refresh("dup")])
+                .await
+                .unwrap();
+        }
+        // 模拟一次已执行的即时收尾（条目未删除）
+        let runner = SideEffectRunner::new(f.storage.clone(), f.cache.clone());
+        runner
+            .execute(&SideEffect::cache_refresh("dup"))
+            .await
+            .unwrap();
+
+        let report = runner.replay_pending(Duration::ZERO).await.unwrap();
+        assert_eq!(report.replayed, 2);
+        assert_eq!(
+            cached_target(&f.cache, "dup").await.as_deref(),
+            Some("https://example.com/b")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grace_window_skips_fresh_entries() {
+        let f = fixture().await;
+        f.storage
+            .set_with_effects(
+                link("fresh", "https://example.com"),
+                &[SideEffect::cache_refresh("fresh")],
+            )
+            .await
+            .unwrap();
+
+        let runner = SideEffectRunner::new(f.storage.clone(), f.cache.clone());
+        let report = runner
+            .replay_pending(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(report.replayed, 0);
+        assert_eq!(pending_count(&f.storage).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_missing_link_records_nothing() {
+        let f = fixture().await;
+        assert!(
+            f.storage
+                .remove_with_effects("nope", &[SideEffect::cache_refresh("nope")])
+                .await
+                .is_err()
+        );
+        assert_eq!(pending_count(&f.storage).await, 0);
+    }
+}
+
+mod api_token_quota_tests {
+    use super::*;
+    use shortlinker::services::{ApiTokenQuotas, ApiTokenService};
+
+    struct Fixture {
+        tokens: ApiTokenService,
+        service: LinkService,
+        _temp: TempDir,
+    }
+
+    async fn fixture() -> Fixture {
+        init_test_config();
+        let temp = TempDir::new().unwrap();
+        let db_url = format!(
+            "sqlite://{}?mode=rwc",
+            temp.path().join("tokens.db").display()
+        );
+        let storage = Arc::new(
+            SeaOrmStorage::new(&db_url, "sqlite", NoopMetrics::arc())
+                .await
+                .unwrap(),
+        );
+        let cache = Arc::new(MockCache::new());
+        Fixture {
+            tokens: ApiTokenService::new(storage.clone()),
+            service: LinkService::new(storage, cache),
+            _temp: temp,
+        }
+    }
+
+    fn quotas(max_links: Option<u64>, max_daily_creates: Option<u64>) -> ApiTokenQuotas {
+        ApiTokenQuotas {
+            max_links,
+            max_daily_creates,
+            max_analytics_level: None,
+        }
+    }
+
+    /// 创建链接并按 handler 的方式登记
+    async fn create_as(f: &Fixture, token_id: &str, code: &str) {
+        f.service
+            .create_link(create_request(Some(code), "https://example.com"))
+            .await
+            .unwrap();
+        f.tokens.record_created(token_id, &[code.to_string()]).await;
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate() {
+        let f = fixture().await;
+        let created = f.tokens.create("growth", quotas(None, None)).await.unwrap();
+        assert!(created.plaintext.starts_with("slk_"));
+        assert_ne!(created.token.token_hash, created.plaintext);
+
+        assert_eq!(
+            f.tokens.authenticate(&created.plaintext).await,
+            Some(created.token.id.clone())
+        );
+        // 缓存命中
+        assert_eq!(
+            f.tokens.authenticate(&created.plaintext).await,
+            Some(created.token.id.clone())
+        );
+
+        let forged = format!("slk_{}_{}", created.token.id, "0".repeat(48));
+        assert_eq!(f.tokens.authenticate(&forged).await, None);
+        assert_eq!(f.tokens.authenticate("slk_missing_secret").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_invalid_name() {
+        let f = fixture().await;
+        let err = f.tokens.create("  ", quotas(None, None)).await.unwrap_err();
+        assert!(matches!(err, ShortlinkerError::Validation(_)));
+        assert!(
+            f.tokens
+                .create(&"x".repeat(65), quotas(None, None))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_links_quota() {
+        let f = fixture().await;
+        let id = f
+            .tokens
+            .create("team", quotas(Some(2), None))
+            .await
+            .unwrap()
+            .token
+            .id;
+
+        create_as(&f, &id, "q1").await;
+        create_as(&f, &id, "q2").await;
+
+        let usage = f.tokens.usage(&id).await.unwrap();
+        assert_eq!(usage.links, 2);
+        assert_eq!(usage.created_today, 2);
+
+        let err = f.tokens.check_quota(&id, 1).await.unwrap_err();
+        assert!(matches!(err, ShortlinkerError::QuotaExceeded(_)));
+
+        // 删除链接释放链接数额度
+        f.service.delete_link("q1").await.unwrap();
+        assert_eq!(f.tokens.usage(&id).await.unwrap().links, 1);
+        assert!(f.tokens.check_quota(&id, 1).await.is_ok());
+        assert!(f.tokens.check_quota(&id, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_daily_quota_not_released_by_delete() {
+        let f = fixture().await;
+        let id = f
+            .tokens
+            .create("team", quotas(None, Some(2)))
+            .await
+            .unwrap()
+            .token
+            .id;
+
+        create_as(&f, &id, "d1").await;
+        create_as(&f, &id, "d2").await;
+        f.service.delete_link("d1").await.unwrap();
+
+        let usage = f.tokens.usage(&id).await.unwrap();
+        assert_eq!(usage.links, 1);
+        assert_eq!(usage.created_today, 2);
+        let err = f.tokens.check_quota(&id, 1).await.unwrap_err();
+        assert!(matches!(err, ShortlinkerError::QuotaExceeded(_)));
+    }
+
+    #[tokio::test]
+    async fn test_batch_check_counts_requested_items() {
+        let f = fixture().await;
+        let id = f
+            .tokens
+            .create("team", quotas(Some(10), None))
+            .await
+            .unwrap()
+            .token
+            .id;
+
+        assert!(f.tokens.check_quota(&id, 10).await.is_ok());
+        assert!(f.tokens.check_quota(&id, 11).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_existing_owner_is_kept() {
+        let f = fixture().await;
+        let a = f
+            .tokens
+            .create("a", quotas(None, None))
+            .await
+            .unwrap()
+            .token
+            .id;
+        let b = f
+            .tokens
+            .create("b", quotas(None, None))
+            .await
+            .unwrap()
+            .token
+            .id;
+
+        create_as(&f, &a, "shared").await;
+        // b 覆盖写入同一短码：链接仍归 a，但计入 b 当天的创建数
+        f.tokens.record_created(&b, &["shared".to_string()]).await;
+
+        assert_eq!(f.tokens.usage(&a).await.unwrap().links, 1);
+        let usage_b = f.tokens.usage(&b).await.unwrap();
+        assert_eq!(usage_b.links, 0);
+        assert_eq!(usage_b.created_today, 1);
+    }
+
+    #[tokio::test]
+    async fn test_analytics_level_cap() {
+        let f = fixture().await;
+        let created = f
+            .tokens
+            .create(
+                "team",
+                ApiTokenQuotas {
+                    max_analytics_level: Some(AnalyticsLevel::Aggregate),
+                    ..quotas(None, None)
+                },
+            )
+            .await
+            .unwrap();
+        let id = created.token.id.clone();
+        assert_eq!(
+            f.tokens.analytics_cap(&id).await.unwrap(),
+            Some(AnalyticsLevel::Aggregate)
+        );
+
+        // inherit 上限视为不限
+        let updated = f
+            .tokens
+            .update_quotas(
+                &id,
+                ApiTokenQuotas {
+                    max_analytics_level: Some(AnalyticsLevel::Inherit),
+                    ..quotas(None, None)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.max_analytics_level, None);
+        assert_eq!(f.tokens.analytics_cap(&id).await.unwrap(), None);
+        assert!(f.tokens.analytics_cap("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_token() {
+        let f = fixture().await;
+        let created = f
+            .tokens
+            .create("team", quotas(Some(1), None))
+            .await
+            .unwrap();
+        let id = created.token.id.clone();
+        create_as(&f, &id, "kept").await;
+        assert!(f.tokens.check_quota(&id, 1).await.is_err());
+
+        let updated = f
+            .tokens
+            .update_quotas(&id, quotas(Some(5), Some(100)))
+            .await
+            .unwrap();
+        assert_eq!(updated.max_links, Some(5));
+        assert_eq!(updated.max_daily_creates, Some(100));
+        assert!(f.tokens.check_quota(&id, 1).await.is_ok());
+
+        f.tokens.delete(&id).await.unwrap();
+        assert!(f.tokens.list().await.unwrap().is_empty());
+        assert_eq!(f.tokens.authenticate(&created.plaintext).await, None);
+        assert!(matches!(
+            f.tokens.usage(&id).await.unwrap_err(),
+            ShortlinkerError::NotFound(_)
+        ));
+        // 名下链接保留
+        assert!(f.service.get_link("kept").await.unwrap().is_some());
+        assert!(matches!(
+            f.tokens.delete(&id).await.unwrap_err(),
+            ShortlinkerError::NotFound(_)
+        ));
+    }
+}
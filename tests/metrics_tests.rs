@@ -109,15 +109,22 @@ fn redirect_auth_and_bloom_metrics_remain_product_owned() {
     let metrics = metrics();
     metrics.inc_redirect("307");
     metrics.inc_redirect("404");
+    metrics.inc_redirect_delayed("tarpit");
     metrics.inc_auth_failure("bearer");
     metrics.inc_bloom_false_positive();
+    metrics.inc_bloom_lookup("stale");
+    metrics.set_bloom_false_positive_rate(0.25);
 
     let output = aster_forge_metrics::prometheus::export_metrics()
         .expect("Forge metrics export should succeed");
     assert!(output.contains("shortlinker_redirects_total{status=\"307\"}"));
     assert!(output.contains("shortlinker_redirects_total{status=\"404\"}"));
+    assert!(output.contains("shortlinker_redirects_delayed_total{reason=\"tarpit\"}"));
     assert!(output.contains("shortlinker_auth_failures_total{method=\"bearer\"}"));
     assert!(output.contains("shortlinker_bloom_filter_false_positives_total"));
+    assert!(output.contains("shortlinker_bloom_filter_lookups_total{outcome=\"stale\"}"));
+    assert!(output.contains("shortlinker_bloom_filter_lookups_total{outcome=\"rejected\"} 0"));
+    assert!(output.contains("shortlinker_bloom_filter_false_positive_rate 0.25"));
 }
 
 #[test]
@@ -1,6 +1,6 @@
 //! Middleware tests
 //!
-//! Tests for AdminAuth and CsrfGuard middleware.
+//! Tests for AdminAuth, CsrfGuard and Firewall middleware (including 404 auto-ban).
 //! Replaces the old middleware_tests.rs.disabled.
 
 use actix_web::http::{Method, StatusCode};
@@ -8,7 +8,7 @@ use actix_web::test::{self, TestRequest};
 use actix_web::{App, HttpResponse, web};
 use std::sync::Arc;
 
-use shortlinker::api::middleware::{AdminAuth, CsrfGuard};
+use shortlinker::api::middleware::{AdminAuth, CsrfGuard, Firewall};
 use shortlinker::config::init_config;
 use shortlinker::config::runtime_config::init_runtime_config;
 use shortlinker::metrics::NoopMetrics;
@@ -57,10 +57,13 @@ async fn init_test_runtime_config() {
 }
 
 /// Generate a valid JWT access token for testing.
+///
+/// Tagged with the fingerprint of `test-secret-token`, the admin token the tests set.
 fn generate_test_token() -> String {
     use shortlinker::api::jwt::get_jwt_service;
+    use shortlinker::utils::admin_token::credential_fingerprint;
     get_jwt_service()
-        .generate_access_token()
+        .generate_access_token(&credential_fingerprint("test-secret-token"))
         .expect("Failed to generate test token")
 }
 
@@ -296,6 +299,42 @@ async fn test_admin_auth_invalid_bearer_token() {
     assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+async fn test_admin_auth_rejects_jwt_from_rotated_token() {
+    use shortlinker::api::jwt::get_jwt_service;
+    use shortlinker::utils::admin_token::credential_fingerprint;
+
+    init_test_runtime_config().await;
+
+    let rt = shortlinker::config::get_runtime_config();
+    let _ = rt.set("api.admin_token", "test-secret-token").await;
+
+    // 签名有效，但签发时的管理员 Token 已被替换且不在宽限期内
+    let token = get_jwt_service()
+        .generate_access_token(&credential_fingerprint("rotated-away-token"))
+        .expect("Failed to generate test token");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(
+                NoopMetrics::arc() as Arc<dyn shortlinker::metrics::MetricsRecorder>
+            ))
+            .service(
+                web::scope("/admin")
+                    .wrap(AdminAuth)
+                    .route("/v1/test", web::get().to(ok_handler)),
+            ),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/admin/v1/test")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 // =============================================================================
 // CSRF Tests
 // =============================================================================
@@ -417,3 +456,149 @@ async fn test_csrf_accepts_matching_tokens() {
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), StatusCode::OK);
 }
+
+// =============================================================================
+// Firewall Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_firewall_rules_hot_reload_and_actions() {
+    init_test_runtime_config().await;
+
+    let rt = shortlinker::config::get_runtime_config();
+
+    // 非法规则在写入时被拒绝
+    assert!(
+        rt.set(
+            "firewall.rules",
+            r#"[{"name":"bad","user_agent_regex":"(","action":"block"}]"#
+        )
+        .await
+        .is_err()
+    );
+
+    let app = test::init_service(
+        App::new().service(
+            web::scope("/fw")
+                .wrap(Firewall)
+                .route("/{code}", web::get().to(ok_handler)),
+        ),
+    )
+    .await;
+
+    // 未配置规则时放行
+    let req = TestRequest::get()
+        .uri("/fw/abc")
+        .insert_header(("User-Agent", "BadBot/1.0"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // 规则热更新后立即生效，无需重建 app
+    rt.set(
+        "firewall.rules",
+        r#"[
+            {"name":"observe-curl","user_agent_contains":"curl","action":"log_only"},
+            {"name":"block-badbot","user_agent_contains":"badbot","path_prefix":"/fw/","action":"block"}
+        ]"#,
+    )
+    .await
+    .expect("valid rules accepted");
+
+    let req = TestRequest::get()
+        .uri("/fw/abc")
+        .insert_header(("User-Agent", "BadBot/1.0"))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // log_only 只记录不拦截
+    let req = TestRequest::get()
+        .uri("/fw/abc")
+        .insert_header(("User-Agent", "curl/8.5.0"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = TestRequest::get()
+        .uri("/fw/abc")
+        .insert_header(("User-Agent", "Mozilla/5.0"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    rt.set("firewall.rules", "[]").await.expect("reset rules");
+}
+
+#[tokio::test]
+async fn test_firewall_rejects_auto_banned_ip() {
+    use shortlinker::services::auto_ban::{auto_ban_settings, auto_banner};
+    use std::time::Instant;
+
+    init_test_runtime_config().await;
+
+    let rt = shortlinker::config::get_runtime_config();
+    rt.set("security.auto_ban_404_threshold", "3")
+        .await
+        .expect("set threshold");
+
+    let app = test::init_service(
+        App::new().service(
+            web::scope("/ban")
+                .wrap(Firewall)
+                .route("/{code}", web::get().to(ok_handler)),
+        ),
+    )
+    .await;
+    let request_from = |peer: &str| {
+        TestRequest::get()
+            .uri("/ban/abc")
+            .peer_addr(peer.parse().unwrap())
+            .to_request()
+    };
+
+    let attacker: std::net::IpAddr = "203.0.113.50".parse().unwrap();
+    let settings = auto_ban_settings();
+    assert!(settings.enabled());
+    for _ in 0..2 {
+        assert!(
+            auto_banner()
+                .record_not_found(attacker, &settings, Instant::now())
+                .is_none()
+        );
+    }
+    let req = request_from("203.0.113.50:40000");
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    assert!(
+        auto_banner()
+            .record_not_found(attacker, &settings, Instant::now())
+            .is_some()
+    );
+    // 封禁后所有请求直接 403，其他 IP 不受影响
+    let req = request_from("203.0.113.50:40001");
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let req = request_from("203.0.113.51:40000");
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // 加入白名单后立即放行
+    rt.set("security.auto_ban_whitelist", r#"["203.0.113.0/24"]"#)
+        .await
+        .expect("set whitelist");
+    let req = request_from("203.0.113.50:40002");
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    rt.set("security.auto_ban_whitelist", "[]")
+        .await
+        .expect("reset whitelist");
+
+    // 手动解除
+    assert!(auto_banner().unban(attacker).is_some());
+    let req = request_from("203.0.113.50:40003");
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    rt.set("security.auto_ban_404_threshold", "0")
+        .await
+        .expect("reset threshold");
+}
@@ -0,0 +1,146 @@
+//! 迁移回滚测试
+//!
+//! 覆盖回滚说明登记、回滚计划的数据影响统计、不可逆迁移拒绝回滚、回滚后重新迁移，
+//! 以及数据库被更新版本迁移过时的兼容性检查。
+
+use std::sync::Once;
+
+use chrono::Utc;
+use migration::rollback::{Dropped, rollback_impact};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use tempfile::TempDir;
+
+use shortlinker::config::init_config;
+use shortlinker::metrics::NoopMetrics;
+use shortlinker::storage::backend::migrate::{
+    SchemaCompatibility, backup_sqlite, migration_status, plan_rollback, rollback,
+    schema_compatibility,
+};
+use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+static INIT: Once = Once::new();
+
+async fn migrated_storage() -> (SeaOrmStorage, TempDir) {
+    INIT.call_once(init_config);
+    let td = TempDir::new().unwrap();
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        td.path().join("migrate.db").display()
+    );
+    let storage = SeaOrmStorage::new(&url, "sqlite", NoopMetrics::arc())
+        .await
+        .unwrap();
+    (storage, td)
+}
+
+async fn compatibility(db: &DatabaseConnection) -> SchemaCompatibility {
+    schema_compatibility(&migration_status(db).await.unwrap())
+}
+
+#[test]
+fn test_every_migration_has_rollback_notes() {
+    for migration in Migrator::migrations() {
+        assert!(
+            rollback_impact(migration.name()).is_some(),
+            "{} has no rollback notes",
+            migration.name()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_rollback_and_reapply() {
+    let (storage, td) = migrated_storage().await;
+    storage
+        .set(ShortLink {
+            code: "keep".to_string(),
+            target: "https://example.com".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::CountOnly,
+            extras: None,
+        })
+        .await
+        .unwrap();
+    let db = storage.get_db();
+    assert_eq!(compatibility(db).await, SchemaCompatibility::UpToDate);
+
+    let plan = plan_rollback(db, "m20261016_000006").await.unwrap();
+    assert_eq!(plan.target, "m20261016_000006_analytics_amendments");
+    let names: Vec<&str> = plan.steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "m20261016_000012_api_token_analytics_cap",
+            "m20261016_000011_short_link_extras",
+            "m20261016_000010_captured_query_params",
+            "m20261016_000009_retired_codes",
+            "m20261016_000008_short_link_analytics_level",
+            "m20261016_000007_period_rollups"
+        ]
+    );
+    assert!(plan.irreversible().is_empty());
+    let level = plan.steps[4]
+        .dropped
+        .iter()
+        .find(|d| d.object == Dropped::Column("short_links", "analytics_level"))
+        .unwrap();
+    assert_eq!(level.rows, Some(1));
+
+    let backup = td.path().join("backup.db");
+    backup_sqlite(db, &backup).await.unwrap();
+    assert!(backup.exists());
+    assert!(backup_sqlite(db, &backup).await.is_err());
+
+    rollback(db, &plan).await.unwrap();
+    assert_eq!(compatibility(db).await, SchemaCompatibility::Pending(6));
+
+    // 回滚后的库可以重新迁移，数据保留（级别回到默认值）
+    run_migrations(db).await.unwrap();
+    assert_eq!(compatibility(db).await, SchemaCompatibility::UpToDate);
+    let link = storage.get("keep").await.unwrap().unwrap();
+    assert_eq!(link.analytics_level, AnalyticsLevel::Inherit);
+}
+
+#[tokio::test]
+async fn test_irreversible_migration_is_refused() {
+    let (storage, _td) = migrated_storage().await;
+    let db = storage.get_db();
+
+    let plan = plan_rollback(db, "m20260208_000001").await.unwrap();
+    let irreversible: Vec<&str> = plan
+        .irreversible()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(irreversible, ["m20260208_000002_drop_user_agent"]);
+    assert!(rollback(db, &plan).await.is_err());
+    assert_eq!(compatibility(db).await, SchemaCompatibility::UpToDate);
+
+    assert!(plan_rollback(db, "m2026").await.is_err());
+    assert!(plan_rollback(db, "m20990101").await.is_err());
+}
+
+#[tokio::test]
+async fn test_newer_schema_is_detected() {
+    let (storage, _td) = migrated_storage().await;
+    let db = storage.get_db();
+    db.execute_unprepared(
+        "INSERT INTO seaql_migrations (version, applied_at) VALUES ('m20990101_000001_future', 0)",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        compatibility(db).await,
+        SchemaCompatibility::Newer(vec!["m20990101_000001_future".to_string()])
+    );
+    let err = run_migrations(db).await.unwrap_err().to_string();
+    assert!(err.contains("migrate down --to"), "{}", err);
+    assert!(plan_rollback(db, "m20261016_000006").await.is_err());
+}
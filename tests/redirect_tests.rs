@@ -12,13 +12,14 @@ use actix_web::{App, web};
 use async_trait::async_trait;
 use chrono::Utc;
 
+use shortlinker::api::services::badge_routes;
 use shortlinker::api::services::redirect::redirect_routes;
-use shortlinker::config::init_config;
 use shortlinker::config::runtime_config::init_runtime_config;
+use shortlinker::config::{MissBatchConfig, init_config};
 use shortlinker::metrics::{MetricsRecorder, NoopMetrics};
-use shortlinker::services::{LinkCache, LinkCacheLookup};
-use shortlinker::storage::ShortLink;
+use shortlinker::services::{LinkCache, LinkCacheLookup, MissBatcher};
 use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
 
 use std::sync::Once;
 use tempfile::TempDir;
@@ -177,6 +178,9 @@ async fn test_redirect_existing_link_from_cache() {
                 expires_at: None,
                 password: None,
                 click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
             },
             Some(3600),
         )
@@ -206,6 +210,9 @@ async fn test_redirect_existing_link_from_db() {
             expires_at: None,
             password: None,
             click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         })
         .await
         .expect("Failed to insert link");
@@ -279,6 +286,9 @@ async fn test_redirect_expired_link() {
             expires_at: Some(Utc::now() - chrono::Duration::days(1)),
             password: None,
             click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
         })
         .await
         .expect("Failed to insert link");
@@ -292,6 +302,39 @@ async fn test_redirect_expired_link() {
     assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_redirect_archived_link_returns_gone() {
+    init_test_env().await;
+
+    let storage = get_storage();
+    storage
+        .set(ShortLink {
+            code: "archived1".to_string(),
+            target: "https://example.com/archived".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 0,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        })
+        .await
+        .expect("Failed to insert link");
+    storage
+        .archive_batch(&["archived1".to_string()])
+        .await
+        .expect("Failed to archive link");
+
+    let cache = Arc::new(MockCache::new());
+    let app = redirect_app!(cache);
+
+    let req = TestRequest::get().uri("/archived1").to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::GONE);
+}
+
 #[tokio::test]
 async fn test_redirect_head_request() {
     init_test_env().await;
@@ -307,6 +350,9 @@ async fn test_redirect_head_request() {
                 expires_at: None,
                 password: None,
                 click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
             },
             Some(3600),
         )
@@ -322,3 +368,549 @@ async fn test_redirect_head_request() {
 
     assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
 }
+
+#[tokio::test]
+async fn test_redirect_fallback_body() {
+    init_test_env().await;
+
+    let cache = Arc::new(MockCache::new());
+    cache
+        .insert(
+            "fallback",
+            ShortLink {
+                code: "fallback".to_string(),
+                target: "https://example.com/?a=1&b=\"<x>".to_string(),
+                created_at: Utc::now(),
+                expires_at: None,
+                password: None,
+                click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+            },
+            Some(3600),
+        )
+        .await;
+    let app = redirect_app!(cache);
+    let rt = shortlinker::config::get_runtime_config();
+
+    let resp = test::call_service(&app, TestRequest::get().uri("/fallback").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains(r#"content="0;url=https://example.com/?a=1&amp;b=&quot;&lt;x&gt;""#));
+    assert!(body.contains(r#"<a href="https://example.com/?a=1&amp;b=&quot;&lt;x&gt;">"#));
+    assert!(!body.contains("<x>"));
+
+    rt.set("redirect.include_fallback_body", "false")
+        .await
+        .unwrap();
+    let resp = test::call_service(&app, TestRequest::get().uri("/fallback").to_request()).await;
+    rt.set("redirect.include_fallback_body", "true")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert!(resp.headers().get("Content-Type").is_none());
+    assert!(test::read_body(resp).await.is_empty());
+}
+
+// =============================================================================
+// Privacy Signal Tests
+// =============================================================================
+
+/// 按短码累计刷盘点击数的 sink
+#[derive(Default)]
+struct RecordingSink {
+    clicks: std::sync::Mutex<HashMap<String, usize>>,
+}
+
+#[async_trait]
+impl shortlinker::analytics::ClickSink for RecordingSink {
+    async fn flush_clicks(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+        let mut clicks = self.clicks.lock().unwrap();
+        for (code, count) in updates {
+            *clicks.entry(code).or_insert(0) += count;
+        }
+        Ok(())
+    }
+}
+
+impl RecordingSink {
+    fn count(&self, code: &str) -> usize {
+        self.clicks.lock().unwrap().get(code).copied().unwrap_or(0)
+    }
+}
+
+/// 两种模式共用全局运行时配置与点击管理器，放在同一个测试中顺序执行
+#[tokio::test]
+async fn test_redirect_respects_privacy_signals() {
+    use shortlinker::analytics::{ClickManager, global::set_global_click_manager};
+
+    init_test_env().await;
+
+    let sink = Arc::new(RecordingSink::default());
+    let manager = Arc::new(ClickManager::new(
+        sink.clone(),
+        std::time::Duration::from_secs(3600),
+        usize::MAX,
+        NoopMetrics::arc(),
+    ));
+    set_global_click_manager(manager.clone());
+
+    let cache = Arc::new(MockCache::new());
+    for code in ["dnt_off", "dnt_details", "dnt_strict"] {
+        cache
+            .insert(
+                code,
+                ShortLink {
+                    code: code.to_string(),
+                    target: "https://example.com/private".to_string(),
+                    created_at: Utc::now(),
+                    expires_at: None,
+                    password: None,
+                    click: 0,
+                    created_via: CreatedVia::Api,
+                    analytics_level: AnalyticsLevel::Inherit,
+                    extras: None,
+                },
+                Some(3600),
+            )
+            .await;
+    }
+    let app = redirect_app!(cache);
+    let rt = shortlinker::config::get_runtime_config();
+
+    // 未开启时忽略 DNT
+    let req = TestRequest::get()
+        .uri("/dnt_off")
+        .insert_header(("DNT", "1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert!(resp.headers().get("Tk").is_none());
+
+    rt.set("analytics.respect_dnt", "true").await.unwrap();
+
+    // details：点击数照常计入，回应 Tk: N
+    rt.set("analytics.dnt_mode", "details").await.unwrap();
+    let before = shortlinker::analytics::privacy_click_stats();
+    let req = TestRequest::get()
+        .uri("/dnt_details")
+        .insert_header(("DNT", "1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(resp.headers().get("Tk").unwrap(), "N");
+
+    // strict：完全不统计，同样回应 Tk: N（GPC 与 DNT 等价）
+    rt.set("analytics.dnt_mode", "strict").await.unwrap();
+    let req = TestRequest::get()
+        .uri("/dnt_strict")
+        .insert_header(("Sec-GPC", "1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(resp.headers().get("Tk").unwrap(), "N");
+
+    // 未携带信号的请求不受影响
+    let req = TestRequest::get().uri("/dnt_strict").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.headers().get("Tk").is_none());
+
+    let after = shortlinker::analytics::privacy_click_stats();
+    assert_eq!(after.opted_out - before.opted_out, 2);
+
+    rt.set("analytics.respect_dnt", "false").await.unwrap();
+    rt.set("analytics.dnt_mode", "details").await.unwrap();
+
+    manager.flush().await;
+    assert_eq!(sink.count("dnt_off"), 1);
+    assert_eq!(sink.count("dnt_details"), 1);
+    // 只有未携带信号的那一次被计入
+    assert_eq!(sink.count("dnt_strict"), 1);
+}
+
+/// 对同一路径连续请求 `n` 次，返回耗时中位数；`$uri` 为每次请求生成 URI 的闭包
+macro_rules! median_latency {
+    ($app:expr, $n:expr, $uri:expr, $status:expr) => {{
+        let mut samples = Vec::with_capacity($n);
+        for i in 0..$n {
+            let req = TestRequest::get().uri(&($uri)(i)).to_request();
+            let started = std::time::Instant::now();
+            let resp = test::call_service(&$app, req).await;
+            samples.push(started.elapsed());
+            assert_eq!(resp.status(), $status);
+        }
+        samples.sort();
+        samples[samples.len() / 2]
+    }};
+}
+
+/// 404 的各条路径（负缓存、查库未命中、非法短码）时延统一补齐到目标区间，307 不受影响；
+/// 同一 IP 连续 404 后叠加 tarpit。共用全局运行时配置，放在同一个测试中顺序执行
+#[tokio::test]
+async fn test_constant_time_404_equalizes_not_found_latency() {
+    use std::time::Duration;
+
+    init_test_env().await;
+
+    let cache = Arc::new(MockCache::new());
+    cache.mark_not_found("ct_negative").await;
+    cache
+        .insert(
+            "ct_exists",
+            ShortLink {
+                code: "ct_exists".to_string(),
+                target: "https://example.com/ct".to_string(),
+                created_at: Utc::now(),
+                expires_at: None,
+                password: None,
+                click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+            },
+            Some(3600),
+        )
+        .await;
+    let app = redirect_app!(cache);
+    let rt = shortlinker::config::get_runtime_config();
+
+    let negative = |_: usize| "/ct_negative".to_string();
+    let db_miss = |i: usize| format!("/ct_missing_{}", i);
+    let invalid = |_: usize| "/%3Cct%3E".to_string();
+    let found = |_: usize| "/ct_exists".to_string();
+
+    rt.set("redirect.not_found_delay_ms", "30").await.unwrap();
+    rt.set("redirect.constant_time_404", "true").await.unwrap();
+
+    let medians = [
+        median_latency!(app, 9, negative, StatusCode::NOT_FOUND),
+        median_latency!(app, 9, db_miss, StatusCode::NOT_FOUND),
+        median_latency!(app, 9, invalid, StatusCode::NOT_FOUND),
+    ];
+    let redirect = median_latency!(app, 9, found, StatusCode::TEMPORARY_REDIRECT);
+
+    // 目标 30ms ± 20%：各路径都不低于下限，彼此差异在抖动范围内
+    let fastest = *medians.iter().min().unwrap();
+    let slowest = *medians.iter().max().unwrap();
+    assert!(fastest >= Duration::from_millis(24), "{:?}", medians);
+    assert!(
+        slowest.as_secs_f64() / fastest.as_secs_f64() < 1.5,
+        "{:?}",
+        medians
+    );
+    assert!(
+        redirect < Duration::from_millis(24),
+        "307 不应被延迟: {:?}",
+        redirect
+    );
+
+    // 同一 IP 超过 20 次 404 后追加 tarpit
+    let peer: std::net::SocketAddr = "198.51.100.9:40000".parse().unwrap();
+    let mut last = Duration::ZERO;
+    for i in 0..21 {
+        let req = TestRequest::get()
+            .uri(&format!("/ct_scan_{}", i))
+            .peer_addr(peer)
+            .to_request();
+        let started = std::time::Instant::now();
+        let resp = test::call_service(&app, req).await;
+        last = started.elapsed();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+    assert!(last >= Duration::from_millis(100), "{:?}", last);
+
+    rt.set("redirect.constant_time_404", "false").await.unwrap();
+    rt.set("redirect.not_found_delay_ms", "5").await.unwrap();
+}
+
+// =============================================================================
+// Miss Micro-batching Tests
+// =============================================================================
+
+/// 按 mode 统计回源查询次数
+#[derive(Default)]
+struct MissLoadCounter {
+    loads: std::sync::Mutex<HashMap<String, usize>>,
+}
+
+impl MissLoadCounter {
+    fn count(&self, mode: &str) -> usize {
+        self.loads.lock().unwrap().get(mode).copied().unwrap_or(0)
+    }
+}
+
+impl MetricsRecorder for MissLoadCounter {
+    fn inc_cache_miss_load(&self, mode: &str) {
+        *self
+            .loads
+            .lock()
+            .unwrap()
+            .entry(mode.to_string())
+            .or_default() += 1;
+    }
+}
+
+async fn insert_batch_links(prefix: &str, count: usize) -> Vec<String> {
+    let storage = get_storage();
+    let mut codes = Vec::with_capacity(count);
+    for i in 0..count {
+        let code = format!("{}{}", prefix, i);
+        storage
+            .set(ShortLink {
+                code: code.clone(),
+                target: format!("https://example.com/{}", code),
+                created_at: Utc::now(),
+                expires_at: None,
+                password: None,
+                click: 0,
+                created_via: CreatedVia::Api,
+                analytics_level: AnalyticsLevel::Inherit,
+                extras: None,
+            })
+            .await
+            .expect("Failed to insert link");
+        codes.push(code);
+    }
+    codes
+}
+
+fn miss_batcher(
+    enabled: bool,
+    window_ms: u64,
+    max_batch_size: usize,
+) -> (Arc<MissBatcher>, Arc<MissLoadCounter>) {
+    let counter = Arc::new(MissLoadCounter::default());
+    let config = MissBatchConfig {
+        enabled,
+        window_ms,
+        max_batch_size,
+    };
+    let batcher = Arc::new(MissBatcher::new(get_storage(), &config, counter.clone()));
+    (batcher, counter)
+}
+
+#[tokio::test]
+async fn test_miss_batcher_merges_concurrent_lookups() {
+    init_test_env().await;
+    let codes = insert_batch_links("mb_merge_", 30).await;
+    let (batcher, counter) = miss_batcher(true, 20, 100);
+
+    // 存在的、不存在的与重复的短码混在同一窗口内
+    let mut requested: Vec<String> = codes.clone();
+    requested.extend((0..5).map(|i| format!("mb_merge_missing_{}", i)));
+    requested.extend(codes.iter().take(5).cloned());
+
+    let results =
+        futures_util::future::join_all(requested.iter().map(|code| batcher.load(code))).await;
+
+    for (code, result) in requested.iter().zip(results) {
+        let link = result.expect("lookup failed");
+        if code.contains("missing") {
+            assert!(link.is_none(), "{} should not exist", code);
+        } else {
+            assert_eq!(link.expect("link should exist").code, *code);
+        }
+    }
+    // 首个请求无并发直接单查，其余合并为一次 IN 查询
+    assert_eq!(counter.count("direct"), 1);
+    assert_eq!(counter.count("batch"), 1);
+}
+
+#[tokio::test]
+async fn test_miss_batcher_flushes_full_batches_early() {
+    init_test_env().await;
+    let codes = insert_batch_links("mb_full_", 26).await;
+    let (batcher, counter) = miss_batcher(true, 200, 10);
+
+    let started = std::time::Instant::now();
+    let results = futures_util::future::join_all(codes.iter().map(|code| batcher.load(code))).await;
+    assert!(results.iter().all(|r| matches!(r, Ok(Some(_)))));
+
+    // 1 次直查 + 2 个凑满提前执行的批 + 1 个窗口结束的批
+    assert_eq!(counter.count("direct"), 1);
+    assert_eq!(counter.count("batch"), 3);
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn test_miss_batcher_disabled_queries_each_code() {
+    init_test_env().await;
+    let codes = insert_batch_links("mb_off_", 8).await;
+    let (batcher, counter) = miss_batcher(false, 20, 100);
+
+    let results = futures_util::future::join_all(codes.iter().map(|code| batcher.load(code))).await;
+    assert!(results.iter().all(|r| matches!(r, Ok(Some(_)))));
+    assert_eq!(counter.count("direct"), codes.len());
+    assert_eq!(counter.count("batch"), 0);
+}
+
+#[tokio::test]
+async fn test_miss_batcher_skips_cancelled_waiters() {
+    init_test_env().await;
+    let codes = insert_batch_links("mb_cancel_", 3).await;
+    let (batcher, counter) = miss_batcher(true, 50, 100);
+
+    let leader = batcher.load(&codes[0]);
+    let cancelled = async {
+        // 等待者在窗口结束前被取消（如客户端断开）
+        let result =
+            tokio::time::timeout(std::time::Duration::from_millis(5), batcher.load(&codes[1]))
+                .await;
+        assert!(result.is_err());
+    };
+    let kept = batcher.load(&codes[2]);
+
+    let (leader, (), kept) = tokio::join!(leader, cancelled, kept);
+    assert_eq!(leader.unwrap().unwrap().code, codes[0]);
+    assert_eq!(kept.unwrap().unwrap().code, codes[2]);
+    // 被取消的短码在执行前剔除，剩余 1 个短码走单查
+    assert_eq!(counter.count("single"), 1);
+    assert_eq!(counter.count("batch"), 0);
+}
+
+#[tokio::test]
+async fn test_redirect_uses_miss_batcher() {
+    init_test_env().await;
+    let codes = insert_batch_links("mb_redirect_", 1).await;
+    let (batcher, counter) = miss_batcher(true, 5, 50);
+    let cache: Arc<dyn LinkCache> = Arc::new(MockCache::new());
+    let metrics: Arc<dyn MetricsRecorder> = NoopMetrics::arc();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(cache))
+            .app_data(web::Data::new(get_storage()))
+            .app_data(web::Data::new(batcher))
+            .app_data(web::Data::new(metrics))
+            .service(redirect_routes()),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri(&format!("/{}", codes[0]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(counter.count("direct"), 1);
+}
+
+// =============================================================================
+// Badge Tests
+// =============================================================================
+
+macro_rules! badge_app {
+    () => {{ badge_app!(Arc::new(MockCache::new())) }};
+    ($cache:expr) => {{
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new($cache as Arc<dyn LinkCache>))
+                .app_data(web::Data::new(get_storage()))
+                .service(badge_routes())
+                .service(redirect_routes()),
+        )
+        .await
+    }};
+}
+
+async fn badge_body(resp: actix_web::dev::ServiceResponse) -> String {
+    String::from_utf8(test::read_body(resp).await.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_badge_shows_formatted_click_count() {
+    init_test_env().await;
+    get_storage()
+        .set(ShortLink {
+            code: "dl/release".to_string(),
+            target: "https://example.com/release.tar.gz".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 1234,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        })
+        .await
+        .expect("Failed to insert link");
+    let app = badge_app!();
+
+    let req = TestRequest::get()
+        .uri("/badge/dl/release.svg?label=downloads&color=green&style=flat-square")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "image/svg+xml; charset=utf-8"
+    );
+    assert_eq!(
+        resp.headers().get("Cache-Control").unwrap(),
+        "public, max-age=60"
+    );
+    let body = badge_body(resp).await;
+    assert!(body.contains("downloads: 1.2k"));
+    assert!(body.contains("#97ca00"));
+    assert!(!body.contains("linearGradient"));
+
+    // 徽章请求不计入点击
+    let link = get_storage().get("dl/release").await.unwrap().unwrap();
+    assert_eq!(link.click, 1234);
+}
+
+#[tokio::test]
+async fn test_badge_unknown_code_returns_grey_not_found() {
+    init_test_env().await;
+    let app = badge_app!();
+
+    for uri in [
+        "/badge/no-such-badge.svg?color=red",
+        "/badge/%3Cscript%3E.svg?label=%3Cx%3E",
+    ] {
+        let resp = test::call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = badge_body(resp).await;
+        assert!(body.contains(": not found"));
+        assert!(body.contains("#9f9f9f"));
+        assert!(!body.contains("#e05d44"));
+        assert!(!body.contains("<x>"));
+    }
+}
+
+#[tokio::test]
+async fn test_badge_skips_storage_for_cached_not_found() {
+    init_test_env().await;
+    get_storage()
+        .set(ShortLink {
+            code: "badge-negative".to_string(),
+            target: "https://example.com".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            password: None,
+            click: 42,
+            created_via: CreatedVia::Api,
+            analytics_level: AnalyticsLevel::Inherit,
+            extras: None,
+        })
+        .await
+        .expect("Failed to insert link");
+
+    // 缓存判定不存在时直接返回，不再查库
+    let cache = Arc::new(MockCache::new());
+    cache.mark_not_found("badge-negative").await;
+    let app = badge_app!(cache);
+
+    let req = TestRequest::get()
+        .uri("/badge/badge-negative.svg")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = badge_body(resp).await;
+    assert!(body.contains(": not found"));
+}
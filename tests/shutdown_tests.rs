@@ -0,0 +1,192 @@
+//! 有序关闭编排测试
+//!
+//! 用慢速 ClickSink 模拟关闭时的慢 flush，验证阶段顺序、阶段超时与总超时。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use shortlinker::analytics::{ClickManager, ClickSink};
+use shortlinker::metrics::NoopMetrics;
+use shortlinker::runtime::{ShutdownPhase, ShutdownPlan};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// 每次 flush 前等待 `delay` 的 sink
+struct SlowSink {
+    delay: Duration,
+    log: Log,
+}
+
+#[async_trait]
+impl ClickSink for SlowSink {
+    async fn flush_clicks(&self, updates: Vec<(String, usize)>) -> anyhow::Result<()> {
+        tokio::time::sleep(self.delay).await;
+        let total: usize = updates.iter().map(|(_, count)| count).sum();
+        self.log.lock().unwrap().push(format!("flushed {}", total));
+        Ok(())
+    }
+}
+
+fn slow_manager(delay: Duration, log: &Log) -> Arc<ClickManager> {
+    let sink = Arc::new(SlowSink {
+        delay,
+        log: log.clone(),
+    });
+    let manager = ClickManager::new(sink, Duration::from_secs(60), 10_000, NoopMetrics::arc());
+    manager.increment("docs");
+    manager.increment("docs");
+    manager.increment("blog");
+    Arc::new(manager)
+}
+
+fn record(log: &Log, entry: &'static str) -> impl Future<Output = ()> + Send + 'static {
+    let log = log.clone();
+    async move { log.lock().unwrap().push(entry.to_string()) }
+}
+
+/// 按服务端的阶段登记一组步骤，flush 使用给定的 ClickManager
+fn server_like_plan(total: Duration, manager: Arc<ClickManager>, log: &Log) -> ShutdownPlan {
+    let mut plan = ShutdownPlan::new(total);
+    // 故意乱序登记，执行顺序只取决于阶段
+    plan.step(ShutdownPhase::Cleanup, "ipc_socket", record(log, "cleanup"))
+        .step(
+            ShutdownPhase::CloseDatabase,
+            "database",
+            record(log, "database closed"),
+        )
+        .step(ShutdownPhase::FlushAnalytics, "click_manager", async move {
+            manager.flush().await
+        })
+        .step(
+            ShutdownPhase::StopBackgroundTasks,
+            "bloom_rebuild",
+            record(log, "tasks stopped"),
+        )
+        .step(
+            ShutdownPhase::DrainInFlight,
+            "ipc_server",
+            record(log, "ipc drained"),
+        )
+        .step(ShutdownPhase::StopAccepting, "http", record(log, "paused"));
+    plan
+}
+
+#[tokio::test]
+async fn test_phases_run_in_order() {
+    let log: Log = Arc::default();
+    let manager = slow_manager(Duration::from_millis(50), &log);
+
+    let report = server_like_plan(Duration::from_secs(5), manager, &log)
+        .run()
+        .await;
+
+    assert!(report.is_clean(), "{:?}", report);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "paused",
+            "ipc drained",
+            "flushed 3",
+            "tasks stopped",
+            "database closed",
+            "cleanup",
+        ]
+    );
+    let phases: Vec<ShutdownPhase> = report.phases.iter().map(|p| p.phase).collect();
+    assert_eq!(phases, ShutdownPhase::ALL);
+}
+
+#[tokio::test]
+async fn test_slow_flush_finishes_before_database_close() {
+    let log: Log = Arc::default();
+    let manager = slow_manager(Duration::from_millis(300), &log);
+
+    let report = server_like_plan(Duration::from_secs(5), manager, &log)
+        .run()
+        .await;
+
+    assert!(report.is_clean(), "{:?}", report);
+    let log = log.lock().unwrap();
+    let flushed = log.iter().position(|e| e == "flushed 3").unwrap();
+    let closed = log.iter().position(|e| e == "database closed").unwrap();
+    assert!(flushed < closed, "{:?}", log);
+    let flush = report.phase(ShutdownPhase::FlushAnalytics).unwrap();
+    assert!(flush.elapsed >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_slow_flush_hits_phase_timeout() {
+    let log: Log = Arc::default();
+    let manager = slow_manager(Duration::from_secs(30), &log);
+
+    let mut plan = server_like_plan(Duration::from_secs(5), manager, &log);
+    plan.set_phase_timeout(ShutdownPhase::FlushAnalytics, Duration::from_millis(100));
+    let started = Instant::now();
+    let report = plan.run().await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(!report.is_clean());
+    let flush = report.phase(ShutdownPhase::FlushAnalytics).unwrap();
+    assert_eq!(flush.timed_out, vec!["click_manager"]);
+    assert!(flush.completed.is_empty());
+    assert_eq!(
+        report.unfinished(),
+        vec![(ShutdownPhase::FlushAnalytics, "click_manager")]
+    );
+
+    // 超时后仍按顺序执行后续阶段
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "paused",
+            "ipc drained",
+            "tasks stopped",
+            "database closed",
+            "cleanup",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_total_timeout_skips_remaining_phases() {
+    let log: Log = Arc::default();
+    let manager = slow_manager(Duration::from_secs(30), &log);
+
+    let started = Instant::now();
+    let report = server_like_plan(Duration::from_millis(200), manager, &log)
+        .run()
+        .await;
+
+    assert!(started.elapsed() < Duration::from_secs(2));
+    let flush = report.phase(ShutdownPhase::FlushAnalytics).unwrap();
+    assert_eq!(flush.timed_out, vec!["click_manager"]);
+    for phase in [
+        ShutdownPhase::StopBackgroundTasks,
+        ShutdownPhase::CloseDatabase,
+        ShutdownPhase::Cleanup,
+    ] {
+        let skipped = report.phase(phase).unwrap();
+        assert_eq!(skipped.skipped.len(), 1, "{:?}", skipped);
+        assert!(skipped.completed.is_empty());
+    }
+    assert_eq!(*log.lock().unwrap(), vec!["paused", "ipc drained"]);
+    assert_eq!(report.unfinished().len(), 4);
+}
+
+#[tokio::test]
+async fn test_components_in_one_phase_run_concurrently() {
+    let mut plan = ShutdownPlan::new(Duration::from_secs(5));
+    for name in ["http", "ipc_server", "extra"] {
+        plan.step(ShutdownPhase::DrainInFlight, name, async {
+            tokio::time::sleep(Duration::from_millis(200)).await
+        });
+    }
+    let started = Instant::now();
+    let report = plan.run().await;
+
+    assert!(report.is_clean());
+    assert!(started.elapsed() < Duration::from_millis(550));
+    assert_eq!(report.phases[0].completed.len(), 3);
+}
@@ -4,10 +4,10 @@
 
 use chrono::{Duration, Utc};
 use shortlinker::config::init_config;
-use shortlinker::storage::ShortLink;
 use shortlinker::storage::backend::{
     LinkFilter, SeaOrmStorage, infer_backend_from_url, normalize_backend_name, run_migrations,
 };
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
 use std::sync::Once;
 use tempfile::TempDir;
 
@@ -31,6 +31,9 @@ fn create_test_link(code: &str, target: &str) -> ShortLink {
         expires_at: None,
         password: None,
         click: 0,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
     }
 }
 
@@ -43,6 +46,9 @@ fn create_test_link_with_expiry(code: &str, expires_in: Duration) -> ShortLink {
         expires_at: Some(Utc::now() + expires_in),
         password: None,
         click: 0,
+        created_via: CreatedVia::Api,
+        analytics_level: AnalyticsLevel::Inherit,
+        extras: None,
     }
 }
 
@@ -482,6 +488,28 @@ mod query_tests {
         assert_eq!(all.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_load_all_in_shard_partitions_links() {
+        use shortlinker::utils::shard::CodeShard;
+
+        let (storage, _temp) = create_temp_storage().await;
+
+        for i in 0..20 {
+            let link = create_test_link(&format!("shard_{}", i), "https://example.com");
+            storage.set(link).await.unwrap();
+        }
+
+        let mut loaded = 0;
+        for index in 0..3 {
+            let shard = CodeShard::new(index, 3).unwrap();
+            let links = storage.load_all_in_shard(Some(shard)).await.unwrap();
+            assert!(links.keys().all(|code| shard.owns(code)));
+            loaded += links.len();
+        }
+        assert_eq!(loaded, 20);
+        assert_eq!(storage.load_all_in_shard(None).await.unwrap().len(), 20);
+    }
+
     #[tokio::test]
     async fn test_load_all_codes() {
         let (storage, _temp) = create_temp_storage().await;
@@ -637,6 +665,55 @@ mod query_tests {
         assert!(codes.contains(&"expired_2"));
         assert!(!codes.contains(&"active"));
     }
+
+    #[tokio::test]
+    async fn test_load_paginated_filtered_extras() {
+        let (storage, _temp) = create_temp_storage().await;
+
+        for (code, extras) in [
+            ("jira_1", Some(r#"{"team":"growth","ticket":"JIRA-123"}"#)),
+            ("jira_2", Some(r#"{"team":"core","ticket":"JIRA-123"}"#)),
+            ("other", Some(r#"{"ticket":"JIRA-456"}"#)),
+            ("numeric", Some(r#"{"ticket":123}"#)),
+            ("plain", None),
+        ] {
+            let mut link = create_test_link(code, "https://example.com");
+            link.extras = extras.map(str::to_string);
+            storage.set(link).await.unwrap();
+        }
+
+        let filter = |pairs: &[(&str, &str)]| LinkFilter {
+            extras: pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        let (links, total) = storage
+            .load_paginated_filtered(1, 10, filter(&[("ticket", "JIRA-123")]))
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        let mut codes: Vec<&str> = links.iter().map(|l| l.code.as_str()).collect();
+        codes.sort();
+        assert_eq!(codes, ["jira_1", "jira_2"]);
+
+        // 多个条件同时满足
+        let (links, total) = storage
+            .load_paginated_filtered(1, 10, filter(&[("ticket", "JIRA-123"), ("team", "core")]))
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(links[0].code, "jira_2");
+
+        // 只匹配字符串值
+        let (_, total) = storage
+            .load_paginated_filtered(1, 10, filter(&[("ticket", "123")]))
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+    }
 }
 
 // =============================================================================
@@ -22,3 +22,112 @@ fn generate_random_code_uniqueness() {
     }
     assert!(codes.len() > 90);
 }
+
+// ============== CSV 方言导入（fixtures）==============
+
+mod csv_dialect_fixtures {
+    use shortlinker::utils::csv_dialect::CsvEncoding;
+    use shortlinker::utils::csv_handler::{CsvImport, import_from_csv};
+
+    fn import(name: &str, delimiter: Option<u8>) -> CsvImport {
+        let path = format!("{}/tests/fixtures/csv/{}", env!("CARGO_MANIFEST_DIR"), name);
+        import_from_csv(path, delimiter, None).unwrap()
+    }
+
+    #[test]
+    fn comma_utf8() {
+        let imported = import("comma_utf8.csv", None);
+        assert_eq!(imported.dialect.delimiter, b',');
+        assert_eq!(imported.dialect.encoding, CsvEncoding::Utf8);
+        assert_eq!(imported.links.len(), 2);
+        assert_eq!(imported.links[0].click, 3);
+    }
+
+    #[test]
+    fn semicolon_latin1() {
+        let imported = import("semicolon_latin1.csv", None);
+        assert_eq!(
+            imported.dialect.describe(),
+            "detected: ; delimiter, latin-1 encoding"
+        );
+        assert_eq!(imported.links.len(), 2);
+        assert_eq!(imported.links[0].code, "eu1");
+        assert_eq!(imported.links[0].target, "https://example.de/München");
+        assert_eq!(imported.links[0].click, 12);
+        // 引号内的逗号和分号不拆分
+        assert_eq!(imported.links[1].target, "https://example.de/?q=a,b;c");
+    }
+
+    #[test]
+    fn utf8_bom_is_stripped_from_first_header() {
+        let imported = import("utf8_bom.csv", None);
+        assert_eq!(imported.dialect.encoding, CsvEncoding::Utf8Bom);
+        assert_eq!(imported.links.len(), 1);
+        assert_eq!(imported.links[0].code, "bom1");
+        assert_eq!(imported.links[0].target, "https://example.jp/東京");
+    }
+
+    #[test]
+    fn tab_separated() {
+        let imported = import("tab.csv", None);
+        assert_eq!(imported.dialect.delimiter, b'\t');
+        assert_eq!(imported.links[0].target, "https://example.com/t?a=1,2");
+        assert_eq!(imported.links[0].click, 5);
+    }
+
+    #[test]
+    fn messy_headers_match_loosely() {
+        let imported = import("messy_headers.csv", None);
+        assert_eq!(imported.links.len(), 1);
+        assert_eq!(imported.links[0].code, "messy1");
+        assert_eq!(imported.links[0].click, 7);
+    }
+
+    #[test]
+    fn explicit_delimiter_overrides_sniffing() {
+        let imported = import("semicolon_latin1.csv", Some(b';'));
+        assert!(!imported.dialect.delimiter_sniffed);
+        assert_eq!(imported.links.len(), 2);
+
+        // 指定错误的分隔符时整行解析为单列，全部失败
+        let path = format!(
+            "{}/tests/fixtures/csv/semicolon_latin1.csv",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        assert!(import_from_csv(path, Some(b','), None).is_err());
+    }
+
+    #[test]
+    fn transform_maps_foreign_export() {
+        use shortlinker::utils::csv_transform::ImportTransform;
+
+        let dir = format!("{}/tests/fixtures/csv", env!("CARGO_MANIFEST_DIR"));
+        let transform =
+            ImportTransform::from_file(format!("{}/foreign_transform.toml", dir)).unwrap();
+        let imported = import_from_csv(
+            format!("{}/foreign_export.csv", dir),
+            None,
+            Some(&transform),
+        )
+        .unwrap();
+
+        assert_eq!(imported.links.len(), 2);
+        let first = &imported.links[0];
+        assert_eq!(first.code, "promo-a");
+        assert_eq!(first.target, "https://example.com/a");
+        assert_eq!(first.click, 12);
+        assert_eq!(
+            first.expires_at.unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00+00:00"
+        );
+        assert_eq!(first.created_at.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+        // 空值不经时间戳转换，仍为永不过期
+        assert_eq!(imported.links[1].expires_at, None);
+
+        // 转换失败的行单独报告，不影响其他行
+        assert_eq!(imported.errors.len(), 1);
+        assert!(imported.errors[0].starts_with("Row 4: Transform error: expires_at:"));
+        assert_eq!(imported.preview.len(), 2);
+        assert_eq!(imported.preview[0].0, 2);
+    }
+}
@@ -0,0 +1,337 @@
+//! WASM redirect 过滤插件测试
+//!
+//! 夹具 `fixtures/wasm/redirect_filter.wat` 按短码首字母返回不同结果，覆盖放行 / 拒绝 /
+//! 改写、超时、超内存与非法输出，以及经 redirect 路由的端到端行为。
+
+#![cfg(feature = "wasm-plugins")]
+
+use std::sync::{Arc, Once};
+use std::time::{Duration, Instant};
+
+use actix_web::http::StatusCode;
+use actix_web::test::{self, TestRequest};
+use actix_web::{App, web};
+use async_trait::async_trait;
+use chrono::Utc;
+use tempfile::TempDir;
+
+use shortlinker::api::services::redirect::redirect_routes;
+use shortlinker::config::init_config;
+use shortlinker::config::runtime_config::init_runtime_config;
+use shortlinker::metrics::{MetricsRecorder, NoopMetrics};
+use shortlinker::services::redirect_filter::{
+    FAIL_CLOSED_STATUS, FailMode, FilterDecision, FilterInput, FilterSettings, RedirectFilter,
+    set_redirect_filter,
+};
+use shortlinker::services::{LinkCache, LinkCacheHealth, LinkCacheLookup};
+use shortlinker::storage::backend::{SeaOrmStorage, run_migrations};
+use shortlinker::storage::{AnalyticsLevel, CreatedVia, ShortLink};
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/wasm/redirect_filter.wat"
+);
+
+fn settings(fail_mode: FailMode) -> FilterSettings {
+    FilterSettings {
+        fail_mode,
+        timeout: Duration::from_millis(20),
+        max_memory_bytes: 2 * 1024 * 1024,
+        pool_size: 2,
+        forward_headers: vec!["user-agent".to_string(), "accept-language".to_string()],
+    }
+}
+
+fn fixture(fail_mode: FailMode) -> RedirectFilter {
+    RedirectFilter::from_file(FIXTURE, settings(fail_mode)).expect("fixture should load")
+}
+
+fn input(code: &str) -> FilterInput<'_> {
+    FilterInput {
+        code,
+        target: "https://example.com/",
+        headers: Default::default(),
+        client_ip: None,
+    }
+}
+
+// =============================================================================
+// 插件调用
+// =============================================================================
+
+#[test]
+fn test_allow_deny_override() {
+    let filter = fixture(FailMode::Open);
+
+    assert_eq!(filter.call(&input("abc")).unwrap(), FilterDecision::Allow);
+    assert_eq!(
+        filter.call(&input("deny")).unwrap(),
+        FilterDecision::Deny { status: 451 }
+    );
+    assert_eq!(
+        filter.call(&input("other")).unwrap(),
+        FilterDecision::Override {
+            target: "https://override.example.com/".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_timeout_discards_instance() {
+    let filter = fixture(FailMode::Open);
+
+    let started = Instant::now();
+    assert!(filter.call(&input("loop")).is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    // 出错的实例被丢弃，后续调用使用新实例
+    assert_eq!(filter.call(&input("abc")).unwrap(), FilterDecision::Allow);
+}
+
+#[test]
+fn test_memory_limit() {
+    let filter = fixture(FailMode::Open);
+    assert!(filter.call(&input("memory")).is_err());
+    assert_eq!(filter.call(&input("abc")).unwrap(), FilterDecision::Allow);
+}
+
+#[test]
+fn test_invalid_output_is_an_error() {
+    let filter = fixture(FailMode::Open);
+    // 非 JSON
+    assert!(filter.call(&input("xyz")).is_err());
+    // deny 的状态码不是 4xx / 5xx
+    assert!(filter.call(&input("bad")).is_err());
+}
+
+#[test]
+fn test_fail_decision_follows_fail_mode() {
+    assert_eq!(
+        fixture(FailMode::Open).fail_decision(),
+        FilterDecision::Allow
+    );
+    assert_eq!(
+        fixture(FailMode::Closed).fail_decision(),
+        FilterDecision::Deny {
+            status: FAIL_CLOSED_STATUS
+        }
+    );
+}
+
+#[test]
+fn test_invalid_modules_rejected_at_load() {
+    // 缺少导出
+    assert!(RedirectFilter::from_bytes(b"(module)", settings(FailMode::Open)).is_err());
+    // 不提供任何宿主导入
+    let with_import = br#"(module
+        (import "env" "log" (func))
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) (i32.const 0))
+        (func (export "filter") (param i32 i32) (result i64) (i64.const 0)))"#;
+    assert!(RedirectFilter::from_bytes(with_import, settings(FailMode::Open)).is_err());
+    // 初始内存超过上限
+    let oversized = br#"(module
+        (memory (export "memory") 64)
+        (func (export "alloc") (param i32) (result i32) (i32.const 0))
+        (func (export "filter") (param i32 i32) (result i64) (i64.const 0)))"#;
+    assert!(RedirectFilter::from_bytes(oversized, settings(FailMode::Open)).is_err());
+}
+
+#[test]
+fn test_collect_headers_only_forwards_configured_names() {
+    let filter = fixture(FailMode::Open);
+    let req = TestRequest::get()
+        .insert_header(("User-Agent", "curl/8"))
+        .insert_header(("Authorization", "Bearer secret"))
+        .to_http_request();
+
+    let headers = filter.collect_headers(req.headers());
+    assert_eq!(headers.len(), 1);
+    assert_eq!(
+        headers.get("user-agent").map(String::as_str),
+        Some("curl/8")
+    );
+}
+
+// =============================================================================
+// 端到端：经 redirect 路由
+// =============================================================================
+
+static INIT: Once = Once::new();
+static RT_INIT: tokio::sync::OnceCell<(Arc<SeaOrmStorage>, TempDir)> =
+    tokio::sync::OnceCell::const_new();
+
+/// 总是 miss 的缓存，让请求回源到 DB
+struct NoCache;
+
+#[async_trait]
+impl LinkCache for NoCache {
+    async fn get(&self, _key: &str) -> LinkCacheLookup {
+        LinkCacheLookup::Miss
+    }
+
+    async fn insert(&self, _key: &str, _value: ShortLink, _ttl_secs: Option<u64>) {}
+
+    async fn remove(&self, _key: &str) {}
+
+    async fn invalidate_all(&self) {}
+
+    async fn rebuild_all(&self) -> shortlinker::errors::Result<()> {
+        Ok(())
+    }
+
+    async fn mark_not_found(&self, _key: &str) {}
+
+    async fn bloom_check(&self, _key: &str) -> bool {
+        true
+    }
+
+    async fn health_check(&self) -> LinkCacheHealth {
+        LinkCacheHealth {
+            status: "healthy".to_string(),
+            cache_type: "none".to_string(),
+            bloom_filter_enabled: false,
+            negative_cache_enabled: false,
+            error: None,
+        }
+    }
+}
+
+async fn storage() -> Arc<SeaOrmStorage> {
+    INIT.call_once(init_config);
+    let (storage, _) = RT_INIT
+        .get_or_init(|| async {
+            let td = TempDir::new().unwrap();
+            let url = format!("sqlite://{}?mode=rwc", td.path().join("wasm.db").display());
+            let db = aster_forge_db::connect(&aster_forge_db::DatabaseConfig::new(&url))
+                .await
+                .unwrap();
+            run_migrations(&db).await.unwrap();
+            init_runtime_config(db).await.unwrap();
+            let storage = Arc::new(
+                SeaOrmStorage::new(&url, "sqlite", NoopMetrics::arc())
+                    .await
+                    .unwrap(),
+            );
+            for code in ["allowed", "denied", "overridden", "looping"] {
+                storage
+                    .set(ShortLink {
+                        code: code.to_string(),
+                        target: format!("https://example.com/{}", code),
+                        created_at: Utc::now(),
+                        expires_at: None,
+                        password: None,
+                        click: 0,
+                        created_via: CreatedVia::Api,
+                        analytics_level: AnalyticsLevel::Inherit,
+                        extras: None,
+                    })
+                    .await
+                    .unwrap();
+            }
+            set_redirect_filter(fixture(FailMode::Closed));
+            (storage, td)
+        })
+        .await;
+    storage.clone()
+}
+
+#[tokio::test]
+async fn test_redirect_applies_filter_decisions() {
+    let storage = storage().await;
+    let metrics: Arc<dyn MetricsRecorder> = NoopMetrics::arc();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Arc::new(NoCache) as Arc<dyn LinkCache>))
+            .app_data(web::Data::new(storage))
+            .app_data(web::Data::new(metrics))
+            .service(redirect_routes()),
+    )
+    .await;
+
+    let get = |path: &str| TestRequest::get().uri(path).to_request();
+
+    let resp = test::call_service(&app, get("/allowed")).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        resp.headers().get("Location").unwrap(),
+        "https://example.com/allowed"
+    );
+
+    let resp = test::call_service(&app, get("/denied")).await;
+    assert_eq!(resp.status().as_u16(), 451);
+    assert!(resp.headers().get("Location").is_none());
+
+    let resp = test::call_service(&app, get("/overridden")).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        resp.headers().get("Location").unwrap(),
+        "https://override.example.com/"
+    );
+
+    // 超时按 fail-close 返回 503
+    let resp = test::call_service(&app, get("/looping")).await;
+    assert_eq!(resp.status().as_u16(), FAIL_CLOSED_STATUS);
+
+    // 不存在的短码不会调用插件
+    let resp = test::call_service(&app, get("/missing")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+// =============================================================================
+// Rust 示例插件（需要 wasm32-unknown-unknown target）
+// =============================================================================
+
+#[test]
+#[ignore = "builds examples/wasm-redirect-filter for wasm32-unknown-unknown"]
+fn test_rust_example_plugin() {
+    let manifest = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/wasm-redirect-filter/Cargo.toml"
+    );
+    let target_dir = concat!(env!("CARGO_TARGET_TMPDIR"), "/wasm-redirect-filter");
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+        .args(["--manifest-path", manifest, "--target-dir", target_dir])
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "building the example plugin failed");
+
+    let wasm = format!(
+        "{}/wasm32-unknown-unknown/release/shortlinker_redirect_filter_example.wasm",
+        target_dir
+    );
+    let filter = RedirectFilter::from_file(&wasm, settings(FailMode::Closed)).unwrap();
+
+    let mut request = input("promo");
+    request
+        .headers
+        .insert("user-agent".to_string(), "BadBot/2.0".to_string());
+    assert_eq!(
+        filter.call(&request).unwrap(),
+        FilterDecision::Deny { status: 403 }
+    );
+
+    let mut request = input("promo");
+    request
+        .headers
+        .insert("accept-language".to_string(), "zh-CN".to_string());
+    assert_eq!(
+        filter.call(&request).unwrap(),
+        FilterDecision::Override {
+            target: "https://example.com/?lang=zh".to_string()
+        }
+    );
+
+    let mut request = input("internal-wiki");
+    request.client_ip = Some("203.0.113.9".to_string());
+    assert_eq!(
+        filter.call(&request).unwrap(),
+        FilterDecision::Deny { status: 404 }
+    );
+
+    // 多次调用复用池中实例，dealloc 后内存不会持续增长
+    for _ in 0..1_000 {
+        assert_eq!(filter.call(&input("promo")).unwrap(), FilterDecision::Allow);
+    }
+}